@@ -0,0 +1,52 @@
+//! Subscriber example from a plain `fn main()`: `agora::blocking` drives the connection on its shared runtime.
+//! Subscribes to any `String` path, e.g. one published by a `PyStringPublisher`.
+
+use agora::ConnectionHandle;
+use agora::blocking::BlockingSubscriber;
+use agora::constants::METASERVER_PORT;
+use clap::Parser;
+use local_ip_address::local_ip;
+use std::net::IpAddr;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// Path of a `String` publisher
+    path: String,
+
+    /// Port for the metaserver
+    #[arg(short, long, default_value_t = METASERVER_PORT)]
+    port: u16,
+
+    #[arg(long, help = "Metaserver host IP address (defaults to local IP)")]
+    host: Option<String>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    let address: IpAddr = match cli.host {
+        Some(host) => host
+            .parse()
+            .map_err(|_| format!("Invalid IP address: {}", host))?,
+        None => local_ip().map_err(|e| format!("Failed to get local IP: {}", e))?,
+    };
+    let metaserver_connection = ConnectionHandle::new(address, cli.port);
+
+    let mut subscriber = BlockingSubscriber::<String>::new(cli.path.clone(), metaserver_connection)
+        .map_err(|e| format!("Failed to create subscriber: {}", e))?;
+    println!("Current value of '{}': {}", cli.path, subscriber.get()?);
+
+    println!("Listening for new messages (Ctrl+C to exit):");
+    loop {
+        match subscriber.recv_timeout(Duration::from_secs(5)) {
+            Ok(Some(message)) => println!("New message: {}", message),
+            Ok(None) => println!("(no message in 5s)"),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                return Ok(());
+            }
+        }
+    }
+}
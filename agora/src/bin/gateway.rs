@@ -11,6 +11,7 @@ struct Args {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    agora::init_tracing();
     let args = Args::parse();
 
     let _gateway = Gateway::new(args.port)
@@ -35,7 +35,7 @@ async fn main() -> anyhow::Result<()> {
 
     let metaserver_connection = ConnectionHandle::new(address, args.port);
 
-    let client = match AgoraClient::new(metaserver_connection.clone()).await {
+    let client = match AgoraClient::new(metaserver_connection).await {
         Ok(client) => {
             println!(
                 "Successfully connected to metaserver at {}:{}...",
@@ -63,6 +63,7 @@ async fn main() -> anyhow::Result<()> {
         indoc! {"
             Available commands:
               remove <path>      - Remove publisher at path
+              remove_subtree <prefix> - Remove every publisher at or below prefix
               info <path>        - Get publisher info at path
               monitor <path>     - Monitor string outputs from publisher at path (Ctrl+D to exit)
               print              - Print current path tree
@@ -106,6 +107,15 @@ async fn main() -> anyhow::Result<()> {
                 remove_publisher(&client, path).await;
                 print_path_tree(&client).await;
             }
+            "remove_subtree" => {
+                if parts.len() < 2 {
+                    println!("Usage: remove_subtree <prefix>");
+                    continue;
+                }
+                let prefix = parts[1];
+                remove_subtree(&client, prefix).await;
+                print_path_tree(&client).await;
+            }
             "info" => {
                 if parts.len() < 2 {
                     println!("Usage: info <path>");
@@ -121,7 +131,7 @@ async fn main() -> anyhow::Result<()> {
                     continue;
                 }
                 let path = parts[1];
-                monitor_path(path, metaserver_connection.clone()).await;
+                monitor_path(path, metaserver_connection).await;
             }
             _ => {
                 println!(
@@ -141,6 +151,7 @@ fn show_help() {
         indoc! {"
             Available commands:
               remove <path>      - Remove publisher at path
+              remove_subtree <prefix> - Remove every publisher at or below prefix
               info <path>        - Get publisher info at path
               monitor <path>     - Monitor string outputs from publisher at path (Ctrl+D to exit)
               print              - Print current path tree
@@ -177,10 +188,33 @@ async fn remove_publisher(client: &AgoraClient, path: &str) {
     }
 }
 
+async fn remove_subtree(client: &AgoraClient, prefix: &str) {
+    match client.remove_subtree(prefix).await {
+        Ok(removed) => {
+            println!(
+                "✅ Removed {} publishers under '{}': {:?}",
+                removed.len(),
+                prefix,
+                removed
+            );
+        }
+        Err(e) => {
+            println!("❌ Failed to remove subtree: {}", e);
+        }
+    }
+}
+
 async fn get_publisher_info(client: &AgoraClient, path: &str) {
     match client.get_publisher_info(path).await {
         Ok(publisher) => {
             println!("Publisher info at '{}': {:?}", path, publisher);
+            println!(
+                "Last confirmed {}ms ago",
+                publisher.confirmed_ago().num_milliseconds()
+            );
+            if let Ok(count) = client.get_subscriber_count(path).await {
+                println!("Streaming subscribers: {}", count);
+            }
         }
         Err(e) => {
             println!("❌ Failed to get publisher info: {}", e);
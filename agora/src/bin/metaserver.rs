@@ -1,8 +1,12 @@
-use agora::constants::METASERVER_PORT;
-use agora::metaserver::AgoraMetaServer;
+use agora::constants::{
+    METASERVER_MAX_CONCURRENT_CHANNELS, METASERVER_PORT, UNCONFIRMED_PUBLISHER_GRACE_MS,
+};
+use agora::AuthToken;
+use agora::metaserver::{AgoraMetaServer, PathCharset};
 use clap::Parser;
 use local_ip_address::local_ip;
 use std::net::IpAddr;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -12,15 +16,57 @@ struct Args {
 
     #[arg(long, help = "Metaserver host IP address (defaults to local IP)")]
     host: Option<String>,
+
+    #[arg(
+        long,
+        env = "AGORA_METASERVER_TOKEN",
+        help = "Shared secret required to register/confirm/remove publishers (open if unset)"
+    )]
+    token: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = METASERVER_MAX_CONCURRENT_CHANNELS,
+        help = "Most client connections served at once; further ones wait until a slot frees"
+    )]
+    max_channels: usize,
+
+    #[arg(
+        long,
+        help = "Only accept path segments made of [A-Za-z0-9._@-], safe as filenames on any filesystem"
+    )]
+    portable_paths: bool,
+
+    #[arg(
+        long,
+        default_value_t = UNCONFIRMED_PUBLISHER_GRACE_MS,
+        help = "Evict publishers that registered but did not confirm within this many milliseconds"
+    )]
+    unconfirmed_grace_ms: u64,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    agora::init_tracing();
     let args = Args::parse();
     let address: IpAddr = if let Some(host) = args.host {
         host.parse()?
     } else {
         local_ip()?
     };
-    AgoraMetaServer::run_server(address, args.port).await
+    let token = args.token.as_deref().map(AuthToken::new).transpose()?;
+    let path_charset = if args.portable_paths {
+        PathCharset::Portable
+    } else {
+        PathCharset::Permissive
+    };
+    AgoraMetaServer::run_server(
+        address,
+        args.port,
+        token,
+        Some(args.max_channels),
+        Some(path_charset),
+        Some(Duration::from_millis(args.unconfirmed_grace_ms)),
+    )
+    .await
 }
@@ -42,7 +42,7 @@ async fn main() -> anyhow::Result<()> {
 
     // Create client with String type
     let client: RawStreamClient<String> =
-        match RawStreamClient::new(gateway, &args.directory, None, None) {
+        match RawStreamClient::new(gateway, &args.directory, None, None, None) {
             Ok(client) => {
                 println!("✅ Connected successfully!");
                 client
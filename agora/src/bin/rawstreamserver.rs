@@ -36,7 +36,7 @@ async fn main() -> anyhow::Result<()> {
     );
 
     // Create and start the server
-    let server: RawStreamServer<String> = RawStreamServer::new(&uds_path, None)
+    let server: RawStreamServer<String> = RawStreamServer::new(&uds_path, None, None, None, None)
         .await
         .map_err(|e| anyhow::anyhow!(e))?;
 
@@ -7,7 +7,6 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use std::io::{self, Write};
 use std::net::IpAddr;
-use tokio;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -181,7 +180,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Perform swapon
         match relay
-            .swapon(src_path.clone(), src_metaserver_connection)
+            .swapon(src_path.clone(), src_metaserver_connection, None)
             .await
         {
             Ok(()) => {
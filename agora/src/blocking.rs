@@ -0,0 +1,151 @@
+//! Synchronous facade over `Publisher` and `Subscriber` for consumers without a tokio runtime (scripts, plain `fn main()`).
+//!
+//! Every blocking handle in the process shares one current-thread runtime, driven by a background thread
+//! (`agora-blocking`) so that publisher servers and subscriber connections keep running between calls.
+//! Calls block the caller on that runtime; no runtime is created per call or per handle.
+//!
+//! Must not be called from within a tokio runtime (including `#[tokio::main]`): blocking there would stall
+//! the caller's executor. Such calls return an error instead; use the async API there.
+
+use crate::agora_error;
+use crate::core::Agorable;
+use crate::core::publisher::Publisher;
+use crate::core::subscriber::Subscriber;
+use crate::utils::{ConnectionHandle, OrError};
+use futures_util::stream::{Stream, StreamExt};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+/// Handle of the shared runtime, started on first use
+fn shared_runtime() -> OrError<&'static Handle> {
+    static RUNTIME: OnceLock<Handle> = OnceLock::new();
+    if let Some(handle) = RUNTIME.get() {
+        return Ok(handle);
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| {
+            anyhow::anyhow!(agora_error!(
+                "blocking",
+                "shared_runtime",
+                &format!("failed to build tokio runtime: {}", e)
+            ))
+        })?;
+    Ok(RUNTIME.get_or_init(|| {
+        let handle = runtime.handle().clone();
+        // The driver thread lives for the rest of the process; a current-thread runtime only
+        // drives IO and timers while some thread is inside `Runtime::block_on`
+        std::thread::Builder::new()
+            .name("agora-blocking".to_string())
+            .spawn(move || runtime.block_on(std::future::pending::<()>()))
+            .expect("failed to spawn agora-blocking runtime thread");
+        handle
+    }))
+}
+
+/// Runs `future` to completion on the shared runtime, blocking the caller.
+/// Error: Called from within a tokio runtime
+fn block_on<F: Future>(component: &str, method: &str, future: F) -> OrError<F::Output> {
+    if Handle::try_current().is_ok() {
+        return Err(anyhow::anyhow!(agora_error!(
+            component,
+            method,
+            "called from within a tokio runtime; use the async API instead"
+        )));
+    }
+    Ok(shared_runtime()?.block_on(future))
+}
+
+/// Synchronous `Publisher<T>`: same registration sequence, `publish` blocks until the value is broadcast.
+pub struct BlockingPublisher<T: Agorable> {
+    inner: Publisher<T>,
+}
+
+impl<T: Agorable> BlockingPublisher<T> {
+    /// Blocking `Publisher::new`.
+    /// Error: Called within a tokio runtime, or `Publisher::new` fails
+    pub fn new(
+        name: String,
+        path: String,
+        initial_value: T,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        let inner = block_on(
+            "blocking::BlockingPublisher",
+            "new",
+            Publisher::new(
+                name,
+                path,
+                initial_value,
+                metaserver_connection,
+                local_gateway_port,
+            ),
+        )??;
+        Ok(Self { inner })
+    }
+
+    pub fn publish(&mut self, value: T) -> OrError<()> {
+        block_on(
+            "blocking::BlockingPublisher",
+            "publish",
+            self.inner.publish(value),
+        )?
+    }
+}
+
+/// Synchronous `Subscriber<T>`. Subscribes to updates on creation, so `recv` sees every value
+/// published after `new` returns (buffered while the caller is not receiving).
+pub struct BlockingSubscriber<T: Agorable> {
+    inner: Subscriber<T>,
+    stream: Pin<Box<dyn Stream<Item = OrError<T>> + Send>>,
+}
+
+impl<T: Agorable> BlockingSubscriber<T> {
+    /// Blocking `Subscriber::new` followed by `get_stream`.
+    /// Error: Called within a tokio runtime, or the publisher cannot be reached
+    pub fn new(path: String, metaserver_connection: ConnectionHandle) -> OrError<Self> {
+        block_on("blocking::BlockingSubscriber", "new", async move {
+            let mut inner = Subscriber::new(path, metaserver_connection).await?;
+            let (_current_value, stream) = inner.get_stream().await?;
+            Ok(Self { inner, stream })
+        })?
+    }
+
+    /// Current value of the publisher (one-time query)
+    pub fn get(&mut self) -> OrError<T> {
+        block_on("blocking::BlockingSubscriber", "get", self.inner.get())?
+    }
+
+    /// Blocks until the next update.
+    /// Error: Stream error, or the stream ended
+    pub fn recv(&mut self) -> OrError<T> {
+        match block_on("blocking::BlockingSubscriber", "recv", self.stream.next())? {
+            Some(result) => result,
+            None => Err(stream_ended("recv")),
+        }
+    }
+
+    /// Like `recv`, but returns `Ok(None)` if no update arrives within `timeout`
+    pub fn recv_timeout(&mut self, timeout: Duration) -> OrError<Option<T>> {
+        let received = block_on(
+            "blocking::BlockingSubscriber",
+            "recv_timeout",
+            // Built inside the runtime: `timeout` registers its timer on creation
+            async { tokio::time::timeout(timeout, self.stream.next()).await },
+        )?;
+        match received {
+            Err(_elapsed) => Ok(None),
+            Ok(Some(result)) => result.map(Some),
+            Ok(None) => Err(stream_ended("recv_timeout")),
+        }
+    }
+}
+
+fn stream_ended(method: &str) -> anyhow::Error {
+    anyhow::anyhow!(agora_error!("blocking::BlockingSubscriber", method, "stream ended"))
+}
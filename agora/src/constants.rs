@@ -1,4 +1,4 @@
-/// Central port configuration for all Agora services
+//! Central port configuration for all Agora services
 
 /// Default port for the Agora Metaserver
 pub const METASERVER_PORT: u16 = 8080;
@@ -8,3 +8,53 @@ pub const GATEWAY_PORT: u16 = 8081;
 
 /// Metaserver pings publisher once this interval. Non-responding publishers are removed
 pub const CHECK_PUBLISHER_LIVELINESS_EVERY_MS: u64 = 10000;
+
+/// Registered publishers still unconfirmed after this long are evicted by the metaserver's prune pass (e.g. `Publisher::new` crashed between register and confirm)
+pub const UNCONFIRMED_PUBLISHER_GRACE_MS: u64 = 30000;
+
+/// Default cap on metaserver connections served at once; further connections wait in the accept queue until one closes.
+/// Each open channel costs a TCP socket plus its JSON framing buffers (which grow to the largest frame seen)
+/// and up to 100 buffered responses: tens of KiB when idle, so the default stays well under 100MiB.
+pub const METASERVER_MAX_CONCURRENT_CHANNELS: usize = 1024;
+
+/// Default time `Relay::swapon` waits for a not-yet-registered source before giving up
+pub const SWAPON_RETRY_TIMEOUT_MS: u64 = 10000;
+
+/// Weight of the newest sample in `RelayStats::latency`, an exponential moving average of forwarding latency
+pub const RELAY_LATENCY_EWMA_WEIGHT: f64 = 0.1;
+
+/// Default interval at which `Relay::swapon` polls the metaserver for the source path
+pub const SWAPON_POLL_INTERVAL_MS: u64 = 100;
+
+/// Default retries of `Relay::new` after registering or confirming its destination fails
+pub const RELAY_NEW_RETRIES: u32 = 3;
+
+/// Default delay before `Relay::new` retries; doubles on each further retry
+pub const RELAY_NEW_RETRY_BACKOFF_MS: u64 = 100;
+
+/// How long a subscriber waits for the publisher's codec handshake on the binary rawstream
+pub const CODEC_HANDSHAKE_TIMEOUT_MS: u64 = 5000;
+
+/// Largest WebSocket frame (and message) the rawstream server, client and gateway accept by default, in bytes
+pub const RAWSTREAM_MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+/// Default interval at which `RawStreamServer` sends each client a heartbeat ping
+pub const RAWSTREAM_HEARTBEAT_INTERVAL_MS: u64 = 5000;
+
+/// Default time `RawStreamServer` waits for a client's heartbeat ack (or for a send to complete) before dropping the client
+pub const RAWSTREAM_HEARTBEAT_TIMEOUT_MS: u64 = 15000;
+
+/// Frames the gateway buffers per multiplexed (`/mux`) connection before its subscriptions wait for the client to read
+pub const GATEWAY_MUX_QUEUE_SIZE: usize = 4096;
+
+/// Deepest nesting `TreeNode::from_repr` accepts (root is depth 0); deeper reprs are rejected instead of overflowing the stack
+pub const TREE_REPR_MAX_DEPTH: usize = 64;
+
+/// Most nodes `TreeNode::from_repr` will build from a single repr
+pub const TREE_REPR_MAX_NODES: usize = 100_000;
+
+/// Longest shared secret accepted by `AuthToken::new`, in bytes
+pub const AUTH_TOKEN_MAX_LEN: usize = 32;
+
+/// Fill level of the fullest subscriber send queue at which `Publisher::is_congested` reports congestion
+pub const PUBLISHER_CONGESTION_THRESHOLD: f64 = 0.75;
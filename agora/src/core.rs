@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
-pub trait Agorable: Display + Serialize + for<'de> Deserialize<'de> + Send + 'static {}
+pub trait Agorable: Display + Serialize + for<'de> Deserialize<'de> + Send + 'static {
+    /// Version of this type's serialized layout. Bump it whenever a field is added, removed or retyped:
+    /// publishers announce it in the binary rawstream handshake (and every frame header) and `Subscriber<T>` refuses
+    /// a publisher announcing a different version (`SchemaVersionMismatch`) instead of misdecoding its payloads.
+    const SCHEMA_VERSION: u32 = 1;
+}
 
 impl Agorable for String {}
 impl Agorable for i64 {}
@@ -23,7 +28,9 @@ impl<T: Display> Display for AgorableOption<T> {
     }
 }
 
-impl<T: Agorable> Agorable for AgorableOption<T> {}
+impl<T: Agorable> Agorable for AgorableOption<T> {
+    const SCHEMA_VERSION: u32 = T::SCHEMA_VERSION;
+}
 
 // Convenience conversions
 impl<T> From<Option<T>> for AgorableOption<T> {
@@ -38,5 +45,101 @@ impl<T> From<AgorableOption<T>> for Option<T> {
     }
 }
 
+/// Declares a sum type whose variants travel with an explicit, stable string tag, so one publisher path can carry
+/// heterogeneous events in order (e.g. trades, quotes and books of one symbol). Each variant wraps one payload and
+/// names its wire tag; the enum is encoded as a `(tag, payload)` pair, which every `WireCodec` supports.
+/// Tags, not declaration order, identify variants: reordering variants keeps the wire format, renaming a tag changes it.
+/// Decoding a tag this build does not know (e.g. a variant added by a newer publisher) fails with an error naming
+/// the tag instead of misreading the payload (postcard drops the message, but still rejects the frame). Implement `Display` and `Agorable` for the enum as for any other type.
+///
+/// ```ignore
+/// agora::agorable_enum! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub enum MarketEvent {
+///         Trade(TradeUpdate) = "trade",
+///         Quote(BboUpdate) = "quote",
+///         Book(OrderbookSnapshot) = "book",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! agorable_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident($payload:ty) = $tag:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant($payload)),+
+        }
+
+        impl $name {
+            /// Wire tags of every variant, in declaration order
+            pub const TAGS: &'static [&'static str] = &[$($tag),+];
+
+            /// Wire tag of this variant
+            pub fn tag(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => $tag),+
+                }
+            }
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use ::serde::ser::SerializeTuple;
+                let mut tuple = serializer.serialize_tuple(2)?;
+                match self {
+                    $(Self::$variant(payload) => {
+                        tuple.serialize_element($tag)?;
+                        tuple.serialize_element(payload)?;
+                    })+
+                }
+                tuple.end()
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct TaggedVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for TaggedVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        write!(f, "a (tag, payload) pair tagged one of {:?}", $name::TAGS)
+                    }
+
+                    fn visit_seq<A: ::serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<$name, A::Error> {
+                        let tag: String = seq
+                            .next_element()?
+                            .ok_or_else(|| ::serde::de::Error::invalid_length(0, &self))?;
+                        match tag.as_str() {
+                            $($tag => seq
+                                .next_element()?
+                                .map($name::$variant)
+                                .ok_or_else(|| ::serde::de::Error::invalid_length(1, &self)),)+
+                            unknown => Err(::serde::de::Error::custom(format!(
+                                "unknown {} tag '{}', expected one of {:?}",
+                                stringify!($name),
+                                unknown,
+                                $name::TAGS
+                            ))),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_tuple(2, TaggedVisitor)
+            }
+        }
+    };
+}
+
+pub mod broadcast;
+pub mod codec;
+pub mod group;
+pub mod join;
 pub mod publisher;
 pub mod subscriber;
@@ -0,0 +1,86 @@
+//! In-process fan-out of one `Subscriber<T>`: `BroadcastHandle<T>` runs a single receive loop and hands out
+//! any number of `tokio::sync::broadcast` receivers, so N local consumers share one rawstream connection.
+//!
+//! Lagging: the channel holds the last `capacity` values. A receiver that falls further behind skips the
+//! oldest values and gets `RecvError::Lagged(skipped)` once before resuming; other receivers are unaffected
+//! and the publisher is never slowed down. Size `capacity` for the slowest consumer's worst burst.
+
+use super::Agorable;
+use super::subscriber::Subscriber;
+use crate::agora_error;
+use crate::utils::OrError;
+use anyhow::bail;
+use futures_util::StreamExt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// One subscription's values broadcast to local receivers. Dropping the handle stops the receive loop
+/// and closes the subscription; outstanding receivers then drain what they hold and see `RecvError::Closed`.
+pub struct BroadcastHandle<T: Agorable + Clone> {
+    sender: broadcast::Sender<T>,
+    latest: Arc<Mutex<T>>,
+    task_handle: JoinHandle<()>,
+}
+
+impl<T: Agorable + Clone> Subscriber<T> {
+    /// Consumes the subscriber into a `BroadcastHandle` buffering up to `capacity` values per receiver.
+    /// Stream errors (e.g. undecodable payloads) are logged and skipped; receivers only see values.
+    /// Error: `capacity` is 0, or the initial `get_stream` fails → propagates to caller.
+    pub async fn into_broadcast(mut self, capacity: usize) -> OrError<BroadcastHandle<T>> {
+        if capacity == 0 {
+            bail!(agora_error!(
+                "core::Subscriber",
+                "into_broadcast",
+                "capacity must be at least 1"
+            ));
+        }
+        let (current, mut stream) = self.get_stream().await?;
+        let (sender, _) = broadcast::channel(capacity);
+        let latest = Arc::new(Mutex::new(current));
+
+        let sender_ = sender.clone();
+        let latest_ = latest.clone();
+        let task_handle = tokio::spawn(async move {
+            let _subscriber = self; // Keep the rawstream connection alive
+            while let Some(item) = stream.next().await {
+                match item {
+                    Ok(value) => {
+                        *latest_.lock().unwrap() = value.clone();
+                        // Err only means no receiver is subscribed right now
+                        let _ = sender_.send(value);
+                    }
+                    Err(e) => tracing::warn!(error = %e, "BroadcastHandle: stream error"),
+                }
+            }
+        });
+
+        Ok(BroadcastHandle {
+            sender,
+            latest,
+            task_handle,
+        })
+    }
+}
+
+impl<T: Agorable + Clone> BroadcastHandle<T> {
+    /// New receiver seeing every value broadcast from now on. Use `latest` for the value before that.
+    pub fn subscribe(&self) -> broadcast::Receiver<T> {
+        self.sender.subscribe()
+    }
+
+    /// Most recent value received (the publisher's current value until the first update)
+    pub fn latest(&self) -> T {
+        self.latest.lock().unwrap().clone()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl<T: Agorable + Clone> Drop for BroadcastHandle<T> {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
@@ -0,0 +1,175 @@
+//! Wire codecs for `Agorable` payloads on the binary endpoint and ping server.
+//! Each `Publisher<T>` picks one codec; its binary rawstream announces it to every connecting client
+//! (see `WireCodec::handshake`) so `Subscriber<T>` decodes with the same codec or refuses to connect.
+//! Typed publishers append their `Agorable::SCHEMA_VERSION` to the frame (see `WireCodec::handshake_with_schema`).
+//! Both are repeated in the `FrameHeader` of every binary frame (see `WireCodec::frame_header`).
+
+use crate::agora_error;
+use crate::rawstream::FrameHeader;
+use crate::utils::OrError;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Prefix of the text frame a binary rawstream sends first on every connection.
+pub const CODEC_HANDSHAKE_PREFIX: &str = "agora-codec:";
+
+/// Separates the codec name from the payload schema version in a handshake, e.g. `agora-codec:bincode;schema=2`.
+pub const SCHEMA_HANDSHAKE_SEPARATOR: &str = ";schema=";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum WireCodec {
+    /// Compact varint encoding. The original wire format and the default.
+    #[default]
+    Postcard,
+    /// Human-readable; for debugging payloads with standard tools. Largest and slowest.
+    Json,
+    /// Compact and fast on large nested payloads (e.g. orderbook snapshots with many levels).
+    Bincode,
+}
+
+impl WireCodec {
+    pub const ALL: [WireCodec; 3] = [WireCodec::Postcard, WireCodec::Json, WireCodec::Bincode];
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> OrError<Vec<u8>> {
+        let encoded = match self {
+            Self::Postcard => postcard::to_allocvec(value).map_err(anyhow::Error::from),
+            Self::Json => serde_json::to_vec(value).map_err(anyhow::Error::from),
+            Self::Bincode => bincode::serde::encode_to_vec(value, bincode::config::standard())
+                .map_err(anyhow::Error::from),
+        };
+        encoded.context(agora_error!(
+            "core::WireCodec",
+            "encode",
+            &format!("failed to encode value as {}", self)
+        ))
+    }
+
+    pub fn decode<T: for<'de> Deserialize<'de>>(&self, bytes: &[u8]) -> OrError<T> {
+        let decoded = match self {
+            Self::Postcard => postcard::from_bytes(bytes).map_err(anyhow::Error::from),
+            Self::Json => serde_json::from_slice(bytes).map_err(anyhow::Error::from),
+            Self::Bincode => {
+                bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+                    .map(|(value, _)| value)
+                    .map_err(anyhow::Error::from)
+            }
+        };
+        decoded.context(agora_error!(
+            "core::WireCodec",
+            "decode",
+            &format!("failed to decode {} payload", self)
+        ))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Postcard => "postcard",
+            Self::Json => "json",
+            Self::Bincode => "bincode",
+        }
+    }
+
+    /// Codec id carried in each frame's `FrameHeader`. Stable across releases: never renumber.
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::Postcard => 0,
+            Self::Json => 1,
+            Self::Bincode => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> OrError<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|codec| codec.id() == id)
+            .ok_or_else(|| {
+                anyhow::anyhow!(agora_error!(
+                    "core::WireCodec",
+                    "from_id",
+                    &format!("unknown codec id {}", id)
+                ))
+            })
+    }
+
+    /// Header of binary frames encoded with this codec at `schema_version`
+    pub fn frame_header(&self, schema_version: u32) -> FrameHeader {
+        FrameHeader::new(self.id(), schema_version)
+    }
+
+    pub fn from_name(name: &str) -> OrError<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|codec| codec.name() == name.trim().to_ascii_lowercase())
+            .ok_or_else(|| {
+                anyhow::anyhow!(agora_error!(
+                    "core::WireCodec",
+                    "from_name",
+                    &format!("unknown codec '{}'", name)
+                ))
+            })
+    }
+
+    /// Text frame announcing this codec, e.g. `agora-codec:bincode`
+    pub fn handshake(&self) -> String {
+        format!("{}{}", CODEC_HANDSHAKE_PREFIX, self.name())
+    }
+
+    /// Text frame announcing this codec and the payload schema version, e.g. `agora-codec:bincode;schema=2`
+    pub fn handshake_with_schema(&self, schema_version: u32) -> String {
+        format!("{}{}{}", self.handshake(), SCHEMA_HANDSHAKE_SEPARATOR, schema_version)
+    }
+
+    /// Codec of a handshake frame, ignoring any schema version.
+    pub fn from_handshake(frame: &str) -> OrError<Self> {
+        Self::parse_handshake(frame).map(|(codec, _)| codec)
+    }
+
+    /// Codec and schema version of a handshake frame.
+    /// The version is `None` for frames without one (publishers predating schema versions).
+    pub fn parse_handshake(frame: &str) -> OrError<(Self, Option<u32>)> {
+        let announced = frame.strip_prefix(CODEC_HANDSHAKE_PREFIX).ok_or_else(|| {
+            anyhow::anyhow!(agora_error!(
+                "core::WireCodec",
+                "parse_handshake",
+                &format!("'{}' is not a codec handshake", frame)
+            ))
+        })?;
+        match announced.split_once(SCHEMA_HANDSHAKE_SEPARATOR) {
+            None => Ok((Self::from_name(announced)?, None)),
+            Some((name, version)) => {
+                let version = version.parse::<u32>().map_err(|_| {
+                    anyhow::anyhow!(agora_error!(
+                        "core::WireCodec",
+                        "parse_handshake",
+                        &format!("invalid schema version in handshake '{}'", frame)
+                    ))
+                })?;
+                Ok((Self::from_name(name)?, Some(version)))
+            }
+        }
+    }
+
+    /// Picks `announced` if the subscriber supports it; otherwise fails naming both sides.
+    pub fn negotiate(announced: WireCodec, supported: &[WireCodec]) -> OrError<WireCodec> {
+        if supported.contains(&announced) {
+            return Ok(announced);
+        }
+        let supported: Vec<&str> = supported.iter().map(|codec| codec.name()).collect();
+        Err(anyhow::anyhow!(agora_error!(
+            "core::WireCodec",
+            "negotiate",
+            &format!(
+                "publisher uses codec {} but subscriber only supports [{}]",
+                announced,
+                supported.join(", ")
+            )
+        )))
+    }
+}
+
+impl fmt::Display for WireCodec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
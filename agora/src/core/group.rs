@@ -0,0 +1,154 @@
+//! All-or-nothing batch registration: `PublisherGroup<T>` registers many publishers and, if any one fails,
+//! deregisters the ones it already registered so a failed init never leaves a partial topology in the path tree.
+
+use super::Agorable;
+use super::publisher::Publisher;
+use crate::agora_error;
+use crate::metaserver::AgoraClient;
+use crate::utils::{ConnectionHandle, OrError};
+use anyhow::Context;
+use std::sync::Arc;
+
+/// One publisher of a `PublisherGroup`: same arguments as `Publisher::new`.
+pub struct PublisherSpec<T: Agorable> {
+    pub name: String,
+    pub path: String,
+    pub initial_value: T,
+}
+
+/// Publishers registered and torn down together.
+/// Dropping the group drops every publisher and deregisters their paths (on the current tokio runtime);
+/// `shutdown` does the same but waits for the metaserver to acknowledge.
+pub struct PublisherGroup<T: Agorable> {
+    publishers: Vec<Publisher<T>>,
+    paths: Vec<String>,
+    // Shared with the deregistration task spawned on drop (`AgoraClient::clone` reconnects and blocks)
+    metaclient: Arc<AgoraClient>,
+}
+
+impl<T: Agorable> PublisherGroup<T> {
+    /// Registers every spec in order. On the first failure, deregisters all publishers already
+    /// registered by this call and returns that failure.
+    /// Error: Any `Publisher::new` fails → propagates after rollback.
+    pub async fn register_all(
+        specs: Vec<PublisherSpec<T>>,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        let metaclient = AgoraClient::new(metaserver_connection)
+            .await
+            .context(agora_error!(
+                "core::PublisherGroup",
+                "register_all",
+                "failed to create AgoraClient"
+            ))?;
+
+        let mut group = Self {
+            publishers: Vec::with_capacity(specs.len()),
+            paths: Vec::with_capacity(specs.len()),
+            metaclient: Arc::new(metaclient),
+        };
+        let total = specs.len();
+        for spec in specs {
+            let created = Publisher::new(
+                spec.name,
+                spec.path.clone(),
+                spec.initial_value,
+                metaserver_connection,
+                local_gateway_port,
+            )
+            .await;
+            match created {
+                Ok(publisher) => {
+                    group.publishers.push(publisher);
+                    group.paths.push(spec.path);
+                }
+                Err(e) => {
+                    // `Publisher::new` already rolled back its own partial registration
+                    let registered = group.paths.len();
+                    if let Err(rollback) = group.shutdown().await {
+                        tracing::error!(error = %rollback, "PublisherGroup: incomplete rollback");
+                    }
+                    return Err(e).context(agora_error!(
+                        "core::PublisherGroup",
+                        "register_all",
+                        &format!(
+                            "failed to register {} ({} of {}); rolled back {} registered publishers",
+                            spec.path,
+                            registered + 1,
+                            total,
+                            registered
+                        )
+                    ));
+                }
+            }
+        }
+        Ok(group)
+    }
+
+    pub fn len(&self) -> usize {
+        self.publishers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.publishers.is_empty()
+    }
+
+    /// Paths in registration order; `get_mut(i)` is the publisher at `paths()[i]`
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Publisher<T>> {
+        self.publishers.get_mut(index)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Publisher<T>> {
+        self.publishers.iter_mut()
+    }
+
+    /// Drops every publisher and deregisters their paths, waiting for the metaserver.
+    /// Error: Some deregistration failed (e.g. already pruned) → reported after attempting all of them.
+    pub async fn shutdown(mut self) -> OrError<()> {
+        self.publishers.clear();
+        let paths = std::mem::take(&mut self.paths);
+        deregister(&self.metaclient, paths).await
+    }
+}
+
+async fn deregister(metaclient: &AgoraClient, paths: Vec<String>) -> OrError<()> {
+    let mut failures = Vec::new();
+    for path in paths {
+        if let Err(e) = metaclient.remove_publisher(&path).await {
+            failures.push(format!("{}: {}", path, e));
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(agora_error!(
+            "core::PublisherGroup",
+            "deregister",
+            &format!("failed to deregister {}", failures.join("; "))
+        )))
+    }
+}
+
+impl<T: Agorable> Drop for PublisherGroup<T> {
+    fn drop(&mut self) {
+        self.publishers.clear();
+        let paths = std::mem::take(&mut self.paths);
+        if paths.is_empty() {
+            return;
+        }
+        // Without a runtime the paths are left for the metaserver's liveness prune
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let metaclient = self.metaclient.clone();
+            runtime.spawn(async move {
+                if let Err(e) = deregister(&metaclient, paths).await {
+                    tracing::error!(error = %e, "PublisherGroup: deregistration failed");
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! As-of join of two typed streams: `JoinSubscriber<A, B>` yields the latest value of each input whenever either updates.
+//! Inputs may be of different `Agorable` types (e.g. a bbo and a last-trade stream of one symbol).
+
+use super::Agorable;
+use super::subscriber::Subscriber;
+use crate::ConnectionHandle;
+use crate::utils::OrError;
+use futures_util::StreamExt;
+use futures_util::stream::Stream;
+use std::pin::Pin;
+
+/// Input whose update produced a `Joined` record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinSide {
+    Left,
+    Right,
+}
+
+/// What `JoinSubscriber` emits before both inputs have a value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinColdStart {
+    /// Emit nothing until both inputs have streamed an update; every record then has both sides
+    #[default]
+    WaitForAll,
+    /// Emit on every update from the start, with `None` for an input that has not updated yet
+    EmitPartial,
+    /// Start both sides from the publishers' current values (as `get` returns them), so every update emits a full record.
+    /// Current values may be placeholders (e.g. `AgorableOption(None)`) if the publisher has not published yet.
+    SeedWithCurrent,
+}
+
+/// One as-of join record: the latest value of each input when `trigger` updated
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joined<A, B> {
+    pub left: Option<A>,
+    pub right: Option<B>,
+    pub trigger: JoinSide,
+}
+
+impl<A, B> Joined<A, B> {
+    /// Both sides, if both have a value (always, except under `JoinColdStart::EmitPartial`)
+    pub fn complete(self) -> Option<(A, B)> {
+        Some((self.left?, self.right?))
+    }
+}
+
+enum Update<A, B> {
+    Left(OrError<A>),
+    Right(OrError<B>),
+}
+
+/// Subscribes to two paths and joins their streams as-of: each update of either input emits a `Joined` record carrying
+/// it together with the other input's last value. Updates are joined in arrival order; there is no timestamp alignment.
+/// Cold start (inputs without a value yet) is set by `JoinColdStart`.
+pub struct JoinSubscriber<A: Agorable + Clone, B: Agorable + Clone> {
+    left: Subscriber<A>,
+    right: Subscriber<B>,
+    cold_start: JoinColdStart,
+}
+
+impl<A: Agorable + Clone, B: Agorable + Clone> JoinSubscriber<A, B> {
+    /// Subscribes to `left_path` and `right_path`. `cold_start` defaults to `JoinColdStart::WaitForAll`.
+    /// Error: Either subscription fails → propagates to caller.
+    pub async fn new(
+        left_path: String,
+        right_path: String,
+        metaserver_connection: ConnectionHandle,
+        cold_start: Option<JoinColdStart>,
+    ) -> OrError<Self> {
+        let left = Subscriber::<A>::new(left_path, metaserver_connection).await?;
+        let right = Subscriber::<B>::new(right_path, metaserver_connection).await?;
+        Ok(Self::from_subscribers(left, right, cold_start))
+    }
+
+    /// Joins already configured subscribers, e.g. multiplexed or sampled ones.
+    pub fn from_subscribers(
+        left: Subscriber<A>,
+        right: Subscriber<B>,
+        cold_start: Option<JoinColdStart>,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            cold_start: cold_start.unwrap_or_default(),
+        }
+    }
+
+    pub fn cold_start(&self) -> JoinColdStart {
+        self.cold_start
+    }
+
+    /// Stream of joined records. Input stream errors are passed through as `Err` items and leave the join state unchanged.
+    /// Ends when both input streams have ended.
+    /// Error: Initial ping of either publisher fails → propagates to caller.
+    pub async fn get_stream(
+        &mut self,
+    ) -> OrError<Pin<Box<dyn Stream<Item = OrError<Joined<A, B>>> + Send>>> {
+        let (left_current, left_stream) = self.left.get_stream().await?;
+        let (right_current, right_stream) = self.right.get_stream().await?;
+        let (mut left, mut right) = match self.cold_start {
+            JoinColdStart::SeedWithCurrent => (Some(left_current), Some(right_current)),
+            JoinColdStart::WaitForAll | JoinColdStart::EmitPartial => (None, None),
+        };
+        let emit_partial = self.cold_start == JoinColdStart::EmitPartial;
+
+        let mut updates = futures_util::stream::select(
+            left_stream.map(Update::<A, B>::Left),
+            right_stream.map(Update::<A, B>::Right),
+        );
+        Ok(Box::pin(async_stream::stream! {
+            while let Some(update) = updates.next().await {
+                let trigger = match update {
+                    Update::Left(Ok(value)) => {
+                        left = Some(value);
+                        JoinSide::Left
+                    }
+                    Update::Right(Ok(value)) => {
+                        right = Some(value);
+                        JoinSide::Right
+                    }
+                    Update::Left(Err(e)) | Update::Right(Err(e)) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+                if emit_partial || (left.is_some() && right.is_some()) {
+                    yield Ok(Joined {
+                        left: left.clone(),
+                        right: right.clone(),
+                        trigger,
+                    });
+                }
+            }
+        }))
+    }
+}
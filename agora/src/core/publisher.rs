@@ -2,7 +2,9 @@
 //! and provides health check server. Handles registration sequence: register → create sockets → confirm.
 
 use super::Agorable;
+use super::codec::WireCodec;
 use crate::agora_error;
+use crate::constants::PUBLISHER_CONGESTION_THRESHOLD;
 use crate::metaserver::AgoraClient;
 use crate::ping::PingServer;
 use crate::rawstream::RawStreamServer;
@@ -14,23 +16,41 @@ use std::marker::PhantomData;
 /// User provides service `name`, `path`, `initial_value`, metaserver and gateway connections.
 /// 1. **Assumes that Gateway is running** on the publisher host's specified port
 /// 2. **Assumes connection to Metaserver** at `metaserver_connection`.
+///
 /// The publisher completes a handshake with metaserver by:
 /// 1. Initiate a metaclient and register path with metaserver.
 /// 2. Initiate a `PingServer` which responds with last values. Metaserver holds a `PingClient` and confirms the publisher upon successful pinging at `/tmp/agora/{path}/ping.sock`.
 /// 3. Initiate `RawStreamServer`s for bytes and strings at `/tmp/agora/{path}/bytes/rawstream.sock` and `/tmp/agora/{path}/string/rawstream.sock`. These are relayed by the Gateway.
+///
+/// Binary payloads are encoded with the publisher's `WireCodec`, announced to subscribers in the bytes rawstream handshake
+/// together with `T::SCHEMA_VERSION`, and repeated in the `FrameHeader` of every binary frame.
 pub struct Publisher<T: Agorable> {
     rawstream_byteserver: RawStreamServer<Vec<u8>>,
     rawstream_omniserver: RawStreamServer<String>,
     pingserver: PingServer,
+    codec: WireCodec,
+    dedup: Option<Dedup>,
+    snapshot_provider: Option<SnapshotProvider<T>>,
     _phantom: PhantomData<T>,
 }
 
+/// `Publisher::with_snapshot_provider`'s source of the full current state
+type SnapshotProvider<T> = Box<dyn Fn() -> Option<T> + Send + Sync>;
+
+/// State of `Publisher::with_dedup`: the last published binary payload and how many repeats were skipped
+#[derive(Default)]
+struct Dedup {
+    last_payload: Option<Vec<u8>>,
+    skipped: u64,
+}
+
 impl<T: Agorable> Publisher<T> {
     /// Creates publisher with registration sequence: register → create sockets → confirm.
     /// Creates three UDS WebSocket servers:
     /// - `/tmp/agora/{path}/bytes/rawstream.sock` (binary messages for `Subscriber<T>`)
     /// - `/tmp/agora/{path}/string/rawstream.sock` (string messages for `OmniSubscriber`)
     /// - `/tmp/agora/{path}/ping.sock` (health checks and current value queries)
+    ///
     /// Error: Any step fails → propagates to user code.
     /// Called by: User code, `Relay::new`
     pub async fn new(
@@ -39,6 +59,27 @@ impl<T: Agorable> Publisher<T> {
         initial_value: T,
         metaserver_connection: ConnectionHandle,
         local_gateway_port: u16,
+    ) -> OrError<Self> {
+        Self::with_codec(
+            name,
+            path,
+            initial_value,
+            metaserver_connection,
+            local_gateway_port,
+            WireCodec::default(),
+        )
+        .await
+    }
+
+    /// Like `new`, but encodes binary payloads with `codec` (e.g. `WireCodec::Json` to inspect payloads,
+    /// `WireCodec::Bincode` for large high-frequency values). Subscribers pick the codec up from the handshake.
+    pub async fn with_codec(
+        name: String,
+        path: String,
+        initial_value: T,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+        codec: WireCodec,
     ) -> OrError<Self> {
         // Step 1: Connect to metaserver
         let metaclient = AgoraClient::new(metaserver_connection)
@@ -51,42 +92,74 @@ impl<T: Agorable> Publisher<T> {
             .await?;
         let _local_gateway_connection = *publisher_info.connection();
 
-        let normalized_path = strip_and_verify(&path)?;
+        // Steps 3-5 run against a registered path. Roll the registration back if any of them fails,
+        // so a half-initialized publisher does not linger in the path tree until the next prune.
+        let started = Self::start_endpoints(&metaclient, &path, initial_value, codec).await;
+        if started.is_err() {
+            let _ = metaclient.remove_publisher(&path).await;
+        }
+        started
+    }
+
+    /// Steps 3-5 of `new`: create the ping and rawstream endpoints, then confirm with the metaserver.
+    async fn start_endpoints(
+        metaclient: &AgoraClient,
+        path: &str,
+        initial_value: T,
+        codec: WireCodec,
+    ) -> OrError<Self> {
+        let normalized_path = strip_and_verify(path)?;
 
         // Step 3: Create UDS socket paths for dual endpoints (bytes + strings)
         let bytes_socket_path_str = format!("/tmp/agora/{}/bytes/rawstream.sock", normalized_path);
         let string_socket_path_str =
             format!("/tmp/agora/{}/string/rawstream.sock", normalized_path);
 
-        let (vec_payload, str_payload) = Self::value_to_payloads(&initial_value)?;
+        let (vec_payload, str_payload) = Self::value_to_payloads(codec, &initial_value)?;
 
         // Step 4a: Create ping server at base path
-        let pingserver =
+        let mut pingserver =
             PingServer::new(&normalized_path, vec_payload.clone(), str_payload.clone())
                 .await
                 .context(agora_error!("core::Publisher", "new", "failed to create ping server"))?;
 
-        // Step 4b: Create binary rawstream server (for Subscriber\<T>)
-        let rawstream_byteserver = RawStreamServer::new(&bytes_socket_path_str, None)
-            .await
-            .context(agora_error!(
-                "core::Publisher",
-                "new",
-                "failed to create byte rawstream server"
-            ))?;
+        // Step 4b: Create binary rawstream server (for Subscriber\<T>), announcing codec and schema version on connect
+        // and again in the header of every frame
+        let rawstream_byteserver = RawStreamServer::with_handshake(
+            &bytes_socket_path_str,
+            None,
+            None,
+            Some(codec.handshake_with_schema(T::SCHEMA_VERSION)),
+            Some(codec.frame_header(T::SCHEMA_VERSION)),
+            None,
+            None,
+        )
+        .await
+        .context(agora_error!(
+            "core::Publisher",
+            "new",
+            "failed to create byte rawstream server"
+        ))?;
 
         // Step 4c: Create string rawstream server (for OmniSubscriber)
-        let rawstream_omniserver = RawStreamServer::new(&string_socket_path_str, None)
-            .await
-            .context(agora_error!(
-                "core::Publisher",
-                "new",
-                "failed to create string rawstream server"
-            ))?;
+        let rawstream_omniserver =
+            RawStreamServer::new(&string_socket_path_str, None, None, None, None)
+                .await
+                .context(agora_error!(
+                    "core::Publisher",
+                    "new",
+                    "failed to create string rawstream server"
+                ))?;
+
+        // Ping responses report how many rawstream clients (typed + omni) are connected
+        pingserver.track_subscribers(vec![
+            rawstream_byteserver.subscriber_counter(),
+            rawstream_omniserver.subscriber_counter(),
+        ]);
 
         // Step 5: Confirm publisher (metaserver pings to verify sockets are live)
         metaclient
-            .confirm_publisher(&path)
+            .confirm_publisher(path)
             .await
             .context(agora_error!("core::Publisher", "new", "failed to confirm publisher"))?;
 
@@ -94,12 +167,70 @@ impl<T: Agorable> Publisher<T> {
             rawstream_byteserver,
             rawstream_omniserver,
             pingserver,
+            codec,
+            dedup: None,
+            snapshot_provider: None,
             _phantom: PhantomData,
         })
     }
 
-    fn value_to_payloads(value: &T) -> OrError<(Vec<u8>, String)> {
-        let vec_payload = postcard::to_allocvec(value).context(agora_error!(
+    /// Skips `publish` calls whose value encodes to the same bytes as the previously published one,
+    /// e.g. feeds re-sending unchanged snapshots. Payloads are compared in full, so a changed value is never
+    /// suppressed; the first publish after enabling always goes through. `initial_value` does not count as published.
+    pub fn with_dedup(mut self) -> Self {
+        self.dedup = Some(Dedup::default());
+        self
+    }
+
+    /// Greets each new streaming subscriber with `snapshot_provider()`, the full current state, instead of joining it
+    /// mid-stream: e.g. a publisher of orderbook diffs serializing the book it maintains. A subscriber that connects is
+    /// held back until the next `publish`, then sent the snapshot and every value published after it, so it sees no gap
+    /// and no overlap. The snapshot is taken right after that `publish`, so the state it reads must already include the
+    /// published value: apply each update, then publish it. While the provider returns `None` (no state yet), subscribers
+    /// stay held back.
+    /// Subscribers already connected are unaffected; `get()` still returns the last published value.
+    pub fn with_snapshot_provider(
+        mut self,
+        snapshot_provider: impl Fn() -> Option<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.rawstream_byteserver.hold_new_clients();
+        self.rawstream_omniserver.hold_new_clients();
+        self.snapshot_provider = Some(Box::new(snapshot_provider));
+        self
+    }
+
+    /// Publishes skipped by `with_dedup` as repeats; always 0 without it
+    pub fn deduplicated_count(&self) -> u64 {
+        self.dedup.as_ref().map_or(0, |dedup| dedup.skipped)
+    }
+
+    pub fn codec(&self) -> WireCodec {
+        self.codec
+    }
+
+    /// Streaming subscribers currently connected: `Subscriber<T>`s on the binary endpoint plus `OmniSubscriber`s.
+    /// Same number the metaserver reports via `AgoraClient::get_subscriber_count`.
+    pub fn subscriber_count(&self) -> usize {
+        self.rawstream_byteserver.subscriber_count() + self.rawstream_omniserver.subscriber_count()
+    }
+
+    /// Backlog of the slowest streaming subscriber: fill level of its send queue, from 0.0 to 1.0.
+    /// See `is_congested`.
+    pub fn congestion_ratio(&self) -> f64 {
+        self.rawstream_byteserver
+            .congestion_ratio()
+            .max(self.rawstream_omniserver.congestion_ratio())
+    }
+
+    /// Whether some subscriber's send queue is at least `PUBLISHER_CONGESTION_THRESHOLD` full, i.e. it reads
+    /// slower than this publisher writes and further values will start displacing queued ones. Producers can
+    /// use it to shed load, e.g. coalescing intermediate updates until it clears; publishing still works either way.
+    pub fn is_congested(&self) -> bool {
+        self.congestion_ratio() >= PUBLISHER_CONGESTION_THRESHOLD
+    }
+
+    fn value_to_payloads(codec: WireCodec, value: &T) -> OrError<(Vec<u8>, String)> {
+        let vec_payload = codec.encode(value).context(agora_error!(
             "core::Publisher",
             "value_to_payloads",
             "failed to serialize value to bytes"
@@ -110,18 +241,59 @@ impl<T: Agorable> Publisher<T> {
 
     /// Publishes value to all endpoints (ping + binary stream + string stream).
     /// Updates ping server's current value, then broadcasts to all connected `Subscriber<T>` and `OmniSubscriber` instances.
+    /// With `with_dedup`, a value identical to the previously published one is dropped here and `Ok(())` returned.
     pub async fn publish(&mut self, value: T) -> OrError<()> {
-        let (vec_payload, str_payload) = Self::value_to_payloads(&value)?;
+        let (vec_payload, str_payload) = Self::value_to_payloads(self.codec, &value)?;
+
+        if let Some(dedup) = self.dedup.as_mut()
+            && dedup.last_payload.as_ref() == Some(&vec_payload)
+        {
+            dedup.skipped += 1;
+            return Ok(());
+        }
+
+        // Reject oversized payloads before any endpoint sees them, so a failed publish changes nothing
+        self.rawstream_byteserver
+            .check_frame_size(vec_payload.len())?;
+        self.rawstream_omniserver
+            .check_frame_size(str_payload.len())?;
 
         // Update ping server (for health checks and get() calls)
         self.pingserver.update_payload(&vec_payload, &str_payload);
 
+        // Remember the payload before it is handed off, so the next publish can be compared against it
+        if let Some(dedup) = self.dedup.as_mut() {
+            dedup.last_payload = Some(vec_payload.clone());
+        }
+
         // Broadcast to binary subscribers (Subscriber\<T>)
         self.rawstream_byteserver.publish(vec_payload)?;
 
         // Broadcast to string subscribers (OmniSubscriber)
         self.rawstream_omniserver.publish(str_payload)?;
 
-        Ok(())
+        self.admit_pending_subscribers()
+    }
+
+    /// Sends subscribers held back by `with_snapshot_provider` the current snapshot, queued behind every value
+    /// published so far, and lets them in.
+    fn admit_pending_subscribers(&self) -> OrError<()> {
+        let Some(snapshot_provider) = &self.snapshot_provider else {
+            return Ok(());
+        };
+        if !self.rawstream_byteserver.has_pending() && !self.rawstream_omniserver.has_pending() {
+            return Ok(());
+        }
+        let Some(snapshot) = snapshot_provider() else {
+            return Ok(());
+        };
+        let (vec_snapshot, str_snapshot) = Self::value_to_payloads(self.codec, &snapshot)?;
+        // Both sizes are checked before either server admits anyone, so a failure leaves every subscriber held back
+        self.rawstream_byteserver
+            .check_frame_size(vec_snapshot.len())?;
+        self.rawstream_omniserver
+            .check_frame_size(str_snapshot.len())?;
+        self.rawstream_byteserver.admit_pending(vec_snapshot)?;
+        self.rawstream_omniserver.admit_pending(str_snapshot)
     }
 }
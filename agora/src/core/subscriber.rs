@@ -2,17 +2,280 @@
 //! Both query metaserver for publisher location, connect to gateway-proxied WebSocket streams, and provide current value + stream access.
 
 use super::Agorable;
+use super::codec::WireCodec;
 use crate::ConnectionHandle;
 use crate::agora_error;
+use crate::constants::CODEC_HANDSHAKE_TIMEOUT_MS;
 use crate::metaserver::AgoraClient;
 use crate::ping::PingClient;
-use crate::rawstream::RawStreamClient;
+use crate::rawstream::{FrameHeader, MuxConnection, RawStreamClient};
 use crate::utils::{OrError, strip_and_verify};
 use anyhow::Context;
 use futures_util::StreamExt;
 use futures_util::stream::Stream;
+use std::collections::VecDeque;
+use std::fmt;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+/// Payload at `path` could not be decoded as the requested type.
+/// Returned (wrapped in `anyhow::Error`, downcastable) by `OmniSubscriber::into_typed` and `OmniSubscriber::recv_as`.
+#[derive(Debug)]
+pub struct TypeMismatchError {
+    pub path: String,
+    pub expected_type: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for TypeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "payload at {} is not a {}: {}",
+            self.path, self.expected_type, self.reason
+        )
+    }
+}
+
+impl std::error::Error for TypeMismatchError {}
+
+/// Publisher at `path` announced a different `Agorable::SCHEMA_VERSION` than the subscriber's type.
+/// Returned (wrapped in `anyhow::Error`, downcastable) by `Subscriber::new`, `OmniSubscriber::into_typed` and
+/// `OmniSubscriber::recv_as`; callers can adapt by subscribing with the type matching `publisher_version`.
+#[derive(Debug)]
+pub struct SchemaVersionMismatch {
+    pub path: String,
+    pub publisher_version: u32,
+    pub subscriber_version: u32,
+}
+
+impl fmt::Display for SchemaVersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "publisher at {} sends schema version {} but subscriber expects version {}",
+            self.path, self.publisher_version, self.subscriber_version
+        )
+    }
+}
+
+impl std::error::Error for SchemaVersionMismatch {}
+
+/// Refuses a publisher whose announced schema version differs from `T::SCHEMA_VERSION`.
+/// Publishers that announce no version (predating schema versions) are accepted.
+fn check_schema_version<T: Agorable>(
+    announced: Option<u32>,
+    path: &str,
+) -> Result<(), SchemaVersionMismatch> {
+    match announced {
+        Some(version) if version != T::SCHEMA_VERSION => Err(SchemaVersionMismatch {
+            path: path.to_string(),
+            publisher_version: version,
+            subscriber_version: T::SCHEMA_VERSION,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Decodes a binary-endpoint payload with the wire codec negotiated with the publisher.
+fn decode_payload<T: Agorable>(
+    codec: WireCodec,
+    bytes: &[u8],
+    path: &str,
+) -> Result<T, TypeMismatchError> {
+    codec.decode::<T>(bytes).map_err(|e| TypeMismatchError {
+        path: path.to_string(),
+        expected_type: std::any::type_name::<T>(),
+        reason: format!("{:#}", e),
+    })
+}
+
+/// Connects to the binary endpoint at `{path}/bytes` and negotiates the codec from the publisher's handshake.
+/// With `multiplexed`, streams over the gateway's shared `MuxConnection` instead of a connection of its own.
+/// Also returns the schema version the publisher announced, if any.
+/// Error: No handshake, or the publisher's codec is not in `supported` → propagates to caller.
+async fn connect_binary(
+    host_gateway_connection: ConnectionHandle,
+    path: &str,
+    supported: &[WireCodec],
+    multiplexed: bool,
+) -> OrError<(RawStreamClient<Vec<u8>>, WireCodec, Option<u32>)> {
+    let bytes_path_str = format!("{}/bytes", path);
+    let rawstreamclient: RawStreamClient<Vec<u8>> = if multiplexed {
+        let mux = MuxConnection::shared(host_gateway_connection);
+        RawStreamClient::multiplexed(mux, &bytes_path_str, None)
+    } else {
+        RawStreamClient::new(host_gateway_connection, &bytes_path_str, None, None, None).context(
+            agora_error!(
+                "core::Subscriber",
+                "connect_binary",
+                "failed to create byte rawstream client"
+            ),
+        )?
+    };
+    let handshake = rawstreamclient
+        .handshake(Duration::from_millis(CODEC_HANDSHAKE_TIMEOUT_MS))
+        .await
+        .context(agora_error!(
+            "core::Subscriber",
+            "connect_binary",
+            &format!("publisher at {} did not announce its codec", path)
+        ))?;
+    let (announced, schema_version) = WireCodec::parse_handshake(&handshake)?;
+    let codec = WireCodec::negotiate(announced, supported)?;
+    Ok((rawstreamclient, codec, schema_version))
+}
+
+/// Receives the raw payload and decode error of each stream frame that is not a valid `T`
+/// (the whole frame, header included, if its header was rejected with a `ProtocolMismatch`).
+/// Set with `Subscriber::on_decode_error`; called on the stream's task, so it should return quickly.
+pub type DecodeErrorHandler = Arc<dyn Fn(&[u8], &anyhow::Error) + Send + Sync>;
+
+/// Undecodable stream frame delivered by `Subscriber::with_dead_letter`
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub bytes: Vec<u8>,
+    pub error: String,
+}
+
+/// Checks each stream frame's header, then decodes its payload, counting failures. With a handler, failed frames go
+/// to it and are skipped (`None`) instead of surfacing as stream errors, so one bad frame leaves the subscription usable.
+#[derive(Clone)]
+struct FrameDecoder {
+    codec: WireCodec,
+    header: FrameHeader,
+    decode_errors: Arc<AtomicU64>,
+    on_decode_error: Option<DecodeErrorHandler>,
+}
+
+impl FrameDecoder {
+    fn decode<T: Agorable>(&self, frame: Vec<u8>) -> Option<OrError<T>> {
+        let (bytes, decoded) = match self.header.strip(&frame) {
+            Ok(payload) => (
+                payload,
+                self.codec.decode::<T>(payload).context(agora_error!(
+                    "core::Subscriber",
+                    "get_stream",
+                    "failed to deserialize stream value"
+                )),
+            ),
+            Err(mismatch) => (
+                frame.as_slice(),
+                Err(anyhow::Error::new(mismatch).context(agora_error!(
+                    "core::Subscriber",
+                    "get_stream",
+                    "rejected frame from an incompatible publisher"
+                ))),
+            ),
+        };
+        match decoded {
+            Ok(value) => Some(Ok(value)),
+            Err(error) => {
+                self.decode_errors.fetch_add(1, Ordering::Relaxed);
+                match &self.on_decode_error {
+                    Some(handler) => {
+                        handler(bytes, &error);
+                        None
+                    }
+                    None => Some(Err(error)),
+                }
+            }
+        }
+    }
+}
+
+/// Subscriber-side sampling applied to `Subscriber::get_stream`. Needs no publisher cooperation.
+/// Only sampled messages are decoded; the freshest message in each window is the one yielded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    /// Yield the last of every `n` updates.
+    EveryN(u32),
+    /// Yield the freshest update received in each window; quiet windows yield nothing.
+    Interval(Duration),
+}
+
+/// Decodes and yields only the sampled messages of `raw_stream`.
+/// Messages the broadcast buffer dropped (`Lagged`) were intermediates of the current window anyway:
+/// they count toward `EveryN` windows and are not reported as stream errors.
+fn sampled_stream<T: Agorable>(
+    mut raw_stream: BroadcastStream<Vec<u8>>,
+    decoder: FrameDecoder,
+    sampling: Sampling,
+) -> Pin<Box<dyn Stream<Item = OrError<T>> + Send>> {
+    let decode = move |bytes: Vec<u8>| decoder.decode::<T>(bytes);
+    match sampling {
+        Sampling::EveryN(n) => Box::pin(async_stream::stream! {
+            let n = u64::from(n.max(1));
+            let mut seen: u64 = 0;
+            while let Some(result) = raw_stream.next().await {
+                match result {
+                    Ok(bytes) => {
+                        seen += 1;
+                        if seen >= n {
+                            seen = 0;
+                            if let Some(item) = decode(bytes) {
+                                yield item;
+                            }
+                        }
+                    }
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => seen += skipped,
+                }
+            }
+        }),
+        Sampling::Interval(period) => Box::pin(async_stream::stream! {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await; // First tick completes immediately
+            let mut latest: Option<Vec<u8>> = None;
+            loop {
+                // `yield` can't appear inside `select!`, so the select only reports which side fired
+                let window_closed = tokio::select! {
+                    result = raw_stream.next() => match result {
+                        Some(Ok(bytes)) => {
+                            latest = Some(bytes);
+                            false
+                        }
+                        Some(Err(BroadcastStreamRecvError::Lagged(_))) => false,
+                        None => break,
+                    },
+                    _ = ticker.tick() => true,
+                };
+                if window_closed
+                    && let Some(bytes) = latest.take()
+                    && let Some(item) = decode(bytes)
+                {
+                    yield item;
+                }
+            }
+            if let Some(bytes) = latest.take()
+                && let Some(item) = decode(bytes)
+            {
+                yield item;
+            }
+        }),
+    }
+}
+
+/// Newest raw frames kept by the `with_latest` / `with_history` loop, oldest first
+struct RecentFrames {
+    frames: VecDeque<Vec<u8>>,
+    capacity: usize,
+}
+
+impl RecentFrames {
+    fn push(&mut self, bytes: Vec<u8>) {
+        while self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(bytes);
+    }
+}
 
 /// Typed subscriber that queries metaserver for publisher location and connects to binary endpoint.
 /// Requires: Publisher exists and is confirmed in metaserver.
@@ -20,6 +283,12 @@ use std::pin::Pin;
 pub struct Subscriber<T: Agorable> {
     rawstreamclient: RawStreamClient<Vec<u8>>,
     pingclient: PingClient,
+    codec: WireCodec,
+    schema_version: Option<u32>,
+    sampling: Option<Sampling>,
+    decode_errors: Arc<AtomicU64>,
+    on_decode_error: Option<DecodeErrorHandler>,
+    recent: Option<Arc<Mutex<RecentFrames>>>, // Kept by the `with_latest` / `with_history` loop
     _phantom: PhantomData<T>,
 }
 
@@ -27,11 +296,42 @@ impl<T: Agorable> Subscriber<T> {
     /// Creates typed subscriber by querying metaserver for publisher location.
     /// Network flow: metaserver query → get gateway address → connect to binary endpoint.
     /// Connects to `ws://gateway/rawstream/{path}/bytes` (via gateway proxy to UDS).
-    /// Error: Publisher not found or connection fails → propagates to user code.
+    /// Accepts any `WireCodec` the publisher announces.
+    /// Error: `SchemaVersionMismatch` if the publisher announces a schema version other than `T::SCHEMA_VERSION`;
+    /// publisher not found or connection fails → propagates to user code.
     /// Called by: User code, `Relay::swapon`
     pub async fn new(
         path: String,
         metaserver_connection: ConnectionHandle,
+    ) -> OrError<Subscriber<T>> {
+        Self::with_codecs(path, metaserver_connection, &WireCodec::ALL).await
+    }
+
+    /// Like `new`, but only accepts publishers whose announced codec is in `supported`.
+    /// Error: Publisher uses an unsupported codec → fails here, before any value is decoded.
+    pub async fn with_codecs(
+        path: String,
+        metaserver_connection: ConnectionHandle,
+        supported: &[WireCodec],
+    ) -> OrError<Subscriber<T>> {
+        Self::connect(path, metaserver_connection, supported, false).await
+    }
+
+    /// Like `new`, but streams over one connection per gateway shared by every multiplexed subscriber in this process
+    /// (`MuxConnection::shared`), instead of opening a WebSocket per subscriber. Meant for subscribing to many paths
+    /// behind the same gateway. `get` and the initial value of `get_stream` still use a ping connection per subscriber.
+    pub async fn multiplexed(
+        path: String,
+        metaserver_connection: ConnectionHandle,
+    ) -> OrError<Subscriber<T>> {
+        Self::connect(path, metaserver_connection, &WireCodec::ALL, true).await
+    }
+
+    async fn connect(
+        path: String,
+        metaserver_connection: ConnectionHandle,
+        supported: &[WireCodec],
+        multiplexed: bool,
     ) -> OrError<Subscriber<T>> {
         // Step 1: Connect to metaserver
         let metaclient = AgoraClient::new(metaserver_connection)
@@ -43,36 +343,168 @@ impl<T: Agorable> Subscriber<T> {
         // Step 2: Query metaserver for publisher location (pings publisher to verify alive)
         let publisher_info = metaclient.get_publisher_info(&normalized_path).await?;
         let host_gateway_connection = *publisher_info.connection();
+        // A publisher moved by `move_subtree` still serves from its original path
+        let source_path = publisher_info.path();
 
-        // Step 3: Connect to binary endpoint (path/bytes for Subscriber\<T>)
-        let bytes_path_str = format!("{}/bytes", normalized_path);
-
-        let rawstreamclient: RawStreamClient<Vec<u8>> =
-            RawStreamClient::new(host_gateway_connection, &bytes_path_str, None, None).context(
-                agora_error!(
-                    "core::Subscriber",
-                    "new",
-                    "failed to create byte rawstream client"
-                )
-            )?;
+        // Step 3: Connect to binary endpoint (path/bytes for Subscriber\<T>) and negotiate its codec
+        let (rawstreamclient, codec, schema_version) =
+            connect_binary(host_gateway_connection, source_path, supported, multiplexed).await?;
+        check_schema_version::<T>(schema_version, &normalized_path)?;
 
         // Step 4: Create ping client for synchronous queries
-        let pingclient = PingClient::new(&normalized_path, host_gateway_connection)
+        let pingclient = PingClient::new(source_path, host_gateway_connection)
             .await
             .context(agora_error!("core::Subscriber", "new", "failed to create ping client"))?;
 
         Ok(Self {
             rawstreamclient,
             pingclient,
+            codec,
+            schema_version,
+            sampling: None,
+            decode_errors: Arc::new(AtomicU64::new(0)),
+            on_decode_error: None,
+            recent: None,
             _phantom: PhantomData,
         })
     }
 
+    /// Makes `get_stream` yield only every `every_n`-th update (e.g. ~1/sec per symbol for dashboards).
+    pub fn with_sampling(mut self, every_n: u32) -> Self {
+        self.sampling = Some(Sampling::EveryN(every_n));
+        self
+    }
+
+    /// Makes `get_stream` yield at most one update per `interval`: the freshest received in that window.
+    pub fn with_sample_interval(mut self, interval: Duration) -> Self {
+        self.sampling = Some(Sampling::Interval(interval));
+        self
+    }
+
+    pub fn sampling(&self) -> Option<Sampling> {
+        self.sampling
+    }
+
+    /// Routes stream frames that fail to decode as `T` (e.g. publisher type or version skew, or a `ProtocolMismatch`)
+    /// to `handler` and skips them, instead of yielding them as stream errors. Applies to streams from later `get_stream` calls.
+    pub fn on_decode_error(
+        mut self,
+        handler: impl Fn(&[u8], &anyhow::Error) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_decode_error = Some(Arc::new(handler));
+        self
+    }
+
+    /// Like `on_decode_error`, but delivers undecodable frames to the returned dead-letter channel.
+    /// Frames are dropped once the receiver is gone.
+    pub fn with_dead_letter(self) -> (Self, mpsc::UnboundedReceiver<DeadLetter>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let subscriber = self.on_decode_error(move |bytes, error| {
+            let _ = sender.send(DeadLetter {
+                bytes: bytes.to_vec(),
+                error: format!("{:#}", error),
+            });
+        });
+        (subscriber, receiver)
+    }
+
+    /// Starts a background loop keeping the newest update for `latest`, the pull-model complement to `get_stream`.
+    /// The loop has its own receiver: streams from `get_stream` still see every update, so both can be used together.
+    /// Sampling does not apply to it. It ends when the subscriber is dropped.
+    /// Same as `with_history(1)`; keeps a longer history if `with_history` was already called.
+    pub fn with_latest(self) -> Self {
+        match &self.recent {
+            Some(_) => self,
+            None => self.with_history(1),
+        }
+    }
+
+    /// Like `with_latest`, but keeps the `capacity` (at least 1) most recent updates for `history`, e.g. so a newly
+    /// attached dashboard can render the last 100 trades at once. Memory is bounded by `capacity` encoded frames.
+    /// Calling it again resizes the history, dropping the oldest updates if it shrinks.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        if let Some(recent) = &self.recent {
+            {
+                let mut recent = recent.lock().unwrap();
+                recent.capacity = capacity;
+                while recent.frames.len() > capacity {
+                    recent.frames.pop_front();
+                }
+            }
+            return self;
+        }
+        let recent = Arc::new(Mutex::new(RecentFrames {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }));
+        let slot = recent.clone();
+        let mut raw_stream = self.rawstreamclient.subscribe();
+        tokio::spawn(async move {
+            // Frames the broadcast buffer dropped (`Lagged`) are missing from the history
+            while let Some(result) = raw_stream.next().await {
+                if let Ok(bytes) = result {
+                    slot.lock().unwrap().push(bytes);
+                }
+            }
+        });
+        self.recent = Some(recent);
+        self
+    }
+
+    /// Most recent update received since `with_latest`, without waiting or consuming from any stream.
+    /// May run ahead of what a `get_stream` consumer has processed; use `get` for the publisher's current value.
+    /// `None` without `with_latest`/`with_history`, before the first update, or if the newest frame does not decode as
+    /// `T` (or carries a mismatched `FrameHeader`); otherwise the last element of `history`.
+    pub fn latest(&self) -> Option<T> {
+        let frame = self.recent.as_ref()?.lock().unwrap().frames.back()?.clone();
+        self.decode_frame(&frame)
+    }
+
+    /// Updates received since `with_history`, oldest first: at most its `capacity` newest ones.
+    /// Frames that do not decode as `T` are left out. Empty without `with_history`/`with_latest`.
+    pub fn history(&self) -> Vec<T> {
+        let Some(recent) = &self.recent else {
+            return Vec::new();
+        };
+        let frames: Vec<Vec<u8>> = recent.lock().unwrap().frames.iter().cloned().collect();
+        frames
+            .iter()
+            .filter_map(|frame| self.decode_frame(frame))
+            .collect()
+    }
+
+    /// Header every stream frame must carry: the negotiated codec and `T::SCHEMA_VERSION`
+    pub fn frame_header(&self) -> FrameHeader {
+        self.codec.frame_header(T::SCHEMA_VERSION)
+    }
+
+    /// Payload of a stream frame decoded as `T`; `None` if its header or payload does not match
+    fn decode_frame(&self, frame: &[u8]) -> Option<T> {
+        let payload = self.frame_header().strip(frame).ok()?;
+        self.codec.decode(payload).ok()
+    }
+
+    /// Stream frames that failed to decode so far, whether or not a handler is set
+    pub fn decode_error_count(&self) -> u64 {
+        self.decode_errors.load(Ordering::Relaxed)
+    }
+
+    /// Codec negotiated with the publisher
+    pub fn codec(&self) -> WireCodec {
+        self.codec
+    }
+
+    /// Schema version the publisher announced; `None` if it predates schema versions
+    pub fn publisher_schema_version(&self) -> Option<u32> {
+        self.schema_version
+    }
+
     /// Fetches current value via ping (one-time query, no streaming).
     /// Error: Ping fails or deserialization fails → propagates to caller.
     pub async fn get(&mut self) -> OrError<T> {
         let (current_bytes, _, _td) = self.pingclient.ping().await?;
-        let current_value: T = postcard::from_bytes(&current_bytes).context(agora_error!(
+        let current_value: T = self.codec.decode(&current_bytes).context(agora_error!(
             "core::Subscriber",
             "get",
             "failed to deserialize current value"
@@ -80,33 +512,42 @@ impl<T: Agorable> Subscriber<T> {
         Ok(current_value)
     }
 
-    /// Returns current value + stream of future updates.
+    /// Returns current value + stream of future updates, sampled if `with_sampling`/`with_sample_interval` was set.
     /// Stream auto-reconnects on disconnect (handled by `RawStreamClient`).
-    /// Error: Initial ping fails → propagates to caller. Stream errors appear in stream items.
+    /// Error: Initial ping fails → propagates to caller. Stream errors appear in stream items; a frame whose header
+    /// differs from `frame_header` (e.g. after reconnecting to an incompatible publisher) is a `ProtocolMismatch`.
     pub async fn get_stream(
         &mut self,
     ) -> OrError<(T, Pin<Box<dyn Stream<Item = OrError<T>> + Send>>)> {
         // Get initial value via ping
         let (current_bytes, _, _td) = self.pingclient.ping().await?;
-        let current_value: T = postcard::from_bytes(&current_bytes).context(agora_error!(
+        let current_value: T = self.codec.decode(&current_bytes).context(agora_error!(
             "core::Subscriber",
             "get_stream",
             "failed to deserialize current value. If Omnisubscriber is succeeding, then double-check if published data type aligns with subscriber type"
         ))?;
 
         // Create stream that deserializes binary messages to T
+        let decoder = FrameDecoder {
+            codec: self.codec,
+            header: self.frame_header(),
+            decode_errors: self.decode_errors.clone(),
+            on_decode_error: self.on_decode_error.clone(),
+        };
         let raw_stream = self.rawstreamclient.subscribe();
-        let typed_stream = raw_stream.map(|result| match result {
-            Ok(bytes) => postcard::from_bytes::<T>(&bytes).context(agora_error!(
-                "core::Subscriber",
-                "get_stream",
-                "failed to deserialize stream value"
-            )),
-            Err(e) => Err(e).context(agora_error!(
-                "core::Subscriber",
-                "get_stream",
-                "stream error"
-            )),
+        if let Some(sampling) = self.sampling {
+            return Ok((current_value, sampled_stream(raw_stream, decoder, sampling)));
+        }
+        let typed_stream = raw_stream.filter_map(move |result| {
+            let item = match result {
+                Ok(bytes) => decoder.decode::<T>(bytes),
+                Err(e) => Some(Err(e).context(agora_error!(
+                    "core::Subscriber",
+                    "get_stream",
+                    "stream error"
+                ))),
+            };
+            futures_util::future::ready(item)
         });
 
         let boxed_stream: Pin<Box<dyn Stream<Item = OrError<T>> + Send>> = Box::pin(typed_stream);
@@ -121,6 +562,10 @@ pub struct OmniSubscriber {
     _metaclient: AgoraClient,
     rawstreamclient: RawStreamClient<String>,
     pingclient: PingClient,
+    path: String,
+    host_gateway_connection: ConnectionHandle,
+    codec: Option<WireCodec>, // Binary endpoint codec, negotiated on first `recv_as`
+    schema_version: Option<u32>, // Schema version announced alongside `codec`
 }
 
 impl OmniSubscriber {
@@ -144,12 +589,14 @@ impl OmniSubscriber {
 
         let publisher_info = metaclient.get_publisher_info(&normalized_path).await?;
         let host_gateway_connection = *publisher_info.connection();
+        // A publisher moved by `move_subtree` still serves from its original path
+        let source_path = publisher_info.path().to_string();
 
         // Connect to string endpoint (path/string for OmniSubscriber)
-        let string_path_str = format!("{}/string", normalized_path);
+        let string_path_str = format!("{}/string", source_path);
 
         let rawstreamclient: RawStreamClient<String> =
-            RawStreamClient::new(host_gateway_connection, &string_path_str, None, None).context(
+            RawStreamClient::new(host_gateway_connection, &string_path_str, None, None, None).context(
                 agora_error!(
                     "core::OmniSubscriber",
                     "new",
@@ -157,7 +604,7 @@ impl OmniSubscriber {
                 )
             )?;
 
-        let pingclient = PingClient::new(&normalized_path, host_gateway_connection)
+        let pingclient = PingClient::new(&source_path, host_gateway_connection)
             .await
             .context(agora_error!(
                 "core::OmniSubscriber",
@@ -169,9 +616,66 @@ impl OmniSubscriber {
             _metaclient: metaclient,
             rawstreamclient,
             pingclient,
+            path: source_path,
+            host_gateway_connection,
+            codec: None,
+            schema_version: None,
+        })
+    }
+
+    /// Converts into a typed `Subscriber<T>` on the same publisher, reusing the ping connection.
+    /// Fails fast: the publisher's current value is decoded as `T` before the binary stream is opened.
+    /// Error: `TypeMismatchError` if the payload is not a `T`, `SchemaVersionMismatch` if the publisher announces
+    /// another schema version; ping or connection failure → propagates to caller.
+    pub async fn into_typed<T: Agorable>(mut self) -> OrError<Subscriber<T>> {
+        let (rawstreamclient, codec, schema_version) = connect_binary(
+            self.host_gateway_connection,
+            &self.path,
+            &WireCodec::ALL,
+            false,
+        )
+        .await?;
+        self.codec = Some(codec);
+        self.schema_version = schema_version;
+        self.recv_as::<T>().await?;
+
+        Ok(Subscriber {
+            rawstreamclient,
+            pingclient: self.pingclient,
+            codec,
+            schema_version,
+            sampling: None,
+            decode_errors: Arc::new(AtomicU64::new(0)),
+            on_decode_error: None,
+            recent: None,
+            _phantom: PhantomData,
         })
     }
 
+    /// Fetches the current value decoded as `T` (same codec as `Subscriber<T>::get`).
+    /// The first call connects to the binary endpoint once to learn the publisher's codec.
+    /// Error: `TypeMismatchError` if the payload is not a `T`, `SchemaVersionMismatch` if the publisher announces
+    /// another schema version; ping failure → propagates to caller.
+    pub async fn recv_as<T: Agorable>(&mut self) -> OrError<T> {
+        let codec = match self.codec {
+            Some(codec) => codec,
+            None => {
+                let (_, codec, schema_version) = connect_binary(
+                    self.host_gateway_connection,
+                    &self.path,
+                    &WireCodec::ALL,
+                    false,
+                )
+                .await?;
+                self.schema_version = schema_version;
+                *self.codec.insert(codec)
+            }
+        };
+        check_schema_version::<T>(self.schema_version, &self.path)?;
+        let (current_bytes, _, _td) = self.pingclient.ping().await?;
+        Ok(decode_payload::<T>(codec, &current_bytes, &self.path)?)
+    }
+
     /// Fetches current value as string via ping.
     pub async fn get(&mut self) -> OrError<String> {
         let (_current_bytes, current_string, _td) = self.pingclient.ping().await?;
@@ -1,19 +1,29 @@
 //! TCP-to-UDS WebSocket gateway enabling cross-node publisher access.
 //! Routes external connections to local Unix domain sockets: `/rawstream/{path}` → `/tmp/agora/{path}/rawstream.sock`, `/ping/{path}` → `/tmp/agora/{path}/ping.sock`.
+//! `/mux` serves many rawstream subscriptions over one connection (see `rawstream::MuxConnection`).
 
 use crate::ConnectionHandle;
+use crate::constants::{GATEWAY_MUX_QUEUE_SIZE, RAWSTREAM_MAX_FRAME_BYTES};
+use crate::rawstream::mux::{MuxCommand, encode_frame};
+use crate::rawstream::websocket_config;
 use crate::utils::OrError;
 use anyhow::{bail, Context};
 use futures_util::{SinkExt, StreamExt};
 use local_ip_address::local_ip;
-use tokio::net::{TcpListener, UnixStream};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream, UnixStream};
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
-use tokio_tungstenite::{accept_hdr_async, client_async};
+use tokio_tungstenite::{WebSocketStream, accept_hdr_async_with_config, client_async_with_config};
+use tracing::{info, warn};
 
 /// TCP-to-UDS WebSocket proxy enabling cross-node publisher access.
 /// Listens on TCP, routes requests to local UDS sockets based on URL path.
-/// Routing: `/rawstream/{path}` → `/tmp/agora/{path}/rawstream.sock`, `/ping/{path}` → `/tmp/agora/{path}/ping.sock`
+/// Routing: `/rawstream/{path}` → `/tmp/agora/{path}/rawstream.sock`, `/ping/{path}` → `/tmp/agora/{path}/ping.sock`,
+/// `/mux` → one rawstream socket per multiplexed subscription
 pub struct Gateway {
     connection: ConnectionHandle,
     task_handle: JoinHandle<()>,
@@ -26,6 +36,12 @@ impl Gateway {
     /// Error: Bind fails → propagates to caller. Connection errors logged per-connection.
     /// Called by: User code (main gateway process)
     pub async fn new(port: u16) -> OrError<Self> {
+        Self::with_max_frame_bytes(port, RAWSTREAM_MAX_FRAME_BYTES).await
+    }
+
+    /// Like `new`, but proxies frames up to `max_frame_bytes` (default `RAWSTREAM_MAX_FRAME_BYTES`) on both hops.
+    /// Should be at least the largest `max_frame_bytes` of any publisher behind it, or its frames are dropped here.
+    pub async fn with_max_frame_bytes(port: u16, max_frame_bytes: usize) -> OrError<Self> {
         let ip = local_ip().context("Agora Gateway error: cannot get own ip")?;
         let connection = ConnectionHandle::new(ip, port);
 
@@ -34,7 +50,7 @@ impl Gateway {
             .await
             .context(format!("Failed to bind gateway to {}", addr))?;
 
-        eprintln!("Agora Gateway listening on {}", addr);
+        info!(%addr, max_frame_bytes, "gateway listening");
 
         // Accept loop: spawn per-connection handler tasks
         let task_handle = tokio::spawn(async move {
@@ -42,13 +58,13 @@ impl Gateway {
                 match listener.accept().await {
                     Ok((tcp_stream, peer_addr)) => {
                         tokio::spawn(async move {
-                            if let Err(e) = handle_connection(tcp_stream).await {
-                                eprintln!("Gateway connection from {} error: {}", peer_addr, e);
+                            if let Err(e) = handle_connection(tcp_stream, max_frame_bytes).await {
+                                warn!(%peer_addr, error = %e, "gateway connection error");
                             }
                         });
                     }
                     Err(e) => {
-                        eprintln!("Gateway accept error: {}", e);
+                        warn!(error = %e, "gateway accept error");
                     }
                 }
             }
@@ -67,12 +83,16 @@ impl Gateway {
 
 // Handles single gateway connection: TCP WebSocket ↔ UDS WebSocket bidirectional proxy.
 // URL routing determines UDS target, then forwards all messages in both directions.
-async fn handle_connection(tcp_stream: tokio::net::TcpStream) -> OrError<()> {
+#[allow(clippy::result_large_err)]
+async fn handle_connection(
+    tcp_stream: tokio::net::TcpStream,
+    max_frame_bytes: usize,
+) -> OrError<()> {
     let mut agora_path = String::new();
     let mut service_type = String::new();
 
     // Accept WebSocket, extract path from URL during handshake
-    let ws_stream = accept_hdr_async(tcp_stream, |req: &Request, response: Response| {
+    let route = |req: &Request, response: Response| {
         let path = req.uri().path();
 
         // URL routing: map external path to UDS socket path
@@ -86,18 +106,27 @@ async fn handle_connection(tcp_stream: tokio::net::TcpStream) -> OrError<()> {
             agora_path = stripped.to_string();
             service_type = "ping".to_string();
             Ok(response)
+        } else if path == "/mux" {
+            // /mux → rawstream sockets named by the client's subscribe commands
+            service_type = "mux".to_string();
+            Ok(response)
         } else {
             // Invalid URL - reject with 400
-            eprintln!("Gateway: invalid path format: {}", path);
+            warn!(path, "gateway rejected invalid path");
             Err(tokio_tungstenite::tungstenite::http::Response::builder()
                 .status(400)
                 .body(Some(format!("Invalid path: {}", path)))
                 .unwrap())
         }
-    })
-    .await
-    .context("WebSocket upgrade failed")?;
+    };
+    let config = Some(websocket_config(max_frame_bytes));
+    let ws_stream = accept_hdr_async_with_config(tcp_stream, route, config)
+        .await
+        .context("WebSocket upgrade failed")?;
 
+    if service_type == "mux" {
+        return handle_mux(ws_stream, max_frame_bytes).await;
+    }
     if agora_path.is_empty() || service_type.is_empty() {
         bail!("Failed to extract path from request");
     }
@@ -109,16 +138,20 @@ async fn handle_connection(tcp_stream: tokio::net::TcpStream) -> OrError<()> {
         .context(format!("Failed to connect to UDS {}", uds_path))?;
 
     // Upgrade UDS to WebSocket
-    let (uds_ws_stream, _) = client_async("ws://localhost/", unix_stream)
-        .await
-        .context("Failed to upgrade UDS to WebSocket")?;
+    let (uds_ws_stream, _) = client_async_with_config(
+        "ws://localhost/",
+        unix_stream,
+        Some(websocket_config(max_frame_bytes)),
+    )
+    .await
+    .context("Failed to upgrade UDS to WebSocket")?;
 
     // Split both WebSocket streams for bidirectional forwarding
     let (mut ext_write, mut ext_read) = ws_stream.split();
     let (mut int_write, mut int_read) = uds_ws_stream.split();
 
     // Task 1: External → Internal forwarding
-    let ext_to_int = tokio::spawn(async move {
+    let mut ext_to_int = tokio::spawn(async move {
         while let Some(msg) = ext_read.next().await {
             match msg {
                 Ok(msg) => {
@@ -132,7 +165,7 @@ async fn handle_connection(tcp_stream: tokio::net::TcpStream) -> OrError<()> {
     });
 
     // Task 2: Internal → External forwarding
-    let int_to_ext = tokio::spawn(async move {
+    let mut int_to_ext = tokio::spawn(async move {
         while let Some(msg) = int_read.next().await {
             match msg {
                 Ok(msg) => {
@@ -148,15 +181,108 @@ async fn handle_connection(tcp_stream: tokio::net::TcpStream) -> OrError<()> {
         }
     });
 
-    // Wait for either direction to close - then terminate both
+    // Wait for either direction to close - then terminate both, so the UDS side sees the disconnect immediately
     tokio::select! {
-        _ = ext_to_int => {},
-        _ = int_to_ext => {},
+        _ = &mut ext_to_int => {},
+        _ = &mut int_to_ext => {},
+    }
+    ext_to_int.abort();
+    int_to_ext.abort();
+
+    Ok(())
+}
+
+// Handles a multiplexed rawstream connection: each `sub {id} {path}` command gets its own UDS connection,
+// forwarded with frames tagged by `id` until `unsub {id}` or the client disconnects.
+async fn handle_mux(ws_stream: WebSocketStream<TcpStream>, max_frame_bytes: usize) -> OrError<()> {
+    let (mut ext_write, mut ext_read) = ws_stream.split();
+    // Bounded: a slow client backs up into each publisher's per-client queue, which applies its overflow policy
+    let (out_tx, mut out_rx) = mpsc::channel::<Message>(GATEWAY_MUX_QUEUE_SIZE);
+    let writer = tokio::spawn(async move {
+        while let Some(msg) = out_rx.recv().await {
+            if ext_write.send(msg).await.is_err() {
+                break; // External disconnected
+            }
+        }
+    });
+
+    let mut subscriptions: HashMap<u32, JoinHandle<()>> = HashMap::new();
+    while let Some(Ok(msg)) = ext_read.next().await {
+        match msg {
+            Message::Text(text) => match MuxCommand::parse(text.as_str()) {
+                Some(MuxCommand::Subscribe { id, path }) => {
+                    // A reconnecting client may resubscribe an id it already queued
+                    let out = out_tx.clone();
+                    subscriptions.entry(id).or_insert_with(|| {
+                        tokio::spawn(forward_mux_subscription(id, path, out, max_frame_bytes))
+                    });
+                }
+                Some(MuxCommand::Unsubscribe { id }) => {
+                    if let Some(task) = subscriptions.remove(&id) {
+                        task.abort();
+                    }
+                }
+                None => warn!(
+                    command = text.as_str(),
+                    "gateway ignored invalid mux command"
+                ),
+            },
+            Message::Close(_) => break,
+            _ => {}
+        }
     }
 
+    // Dropping each UDS connection lets its publisher see the disconnect immediately
+    for task in subscriptions.into_values() {
+        task.abort();
+    }
+    writer.abort();
     Ok(())
 }
 
+// Streams one mux subscription: `/tmp/agora/{path}/rawstream.sock` → frames tagged with `id` on `out`.
+// Reconnects to the UDS every 100ms while subscribed (e.g. until the publisher is back), like `RawStreamClient`.
+async fn forward_mux_subscription(
+    id: u32,
+    path: String,
+    out: mpsc::Sender<Message>,
+    max_frame_bytes: usize,
+) {
+    let uds_path = format!("/tmp/agora/{}/rawstream.sock", path);
+    loop {
+        if let Ok(unix_stream) = UnixStream::connect(&uds_path).await
+            && let Ok((mut uds_ws_stream, _)) = client_async_with_config(
+                "ws://localhost/",
+                unix_stream,
+                Some(websocket_config(max_frame_bytes)),
+            )
+            .await
+        {
+            while let Some(Ok(msg)) = uds_ws_stream.next().await {
+                let tagged = match msg {
+                    Message::Binary(data) => Message::Binary(encode_frame(id, &data).into()),
+                    Message::Text(handshake) => {
+                        Message::Text(format!("{} {}", id, handshake.as_str()).into())
+                    }
+                    // Heartbeat: acked here for the mux client. If the client stops reading, `out` fills
+                    // and this loop stops acking, so the publisher still drops the subscription.
+                    Message::Ping(payload) => {
+                        if uds_ws_stream.send(Message::Binary(payload)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if out.send(tagged).await.is_err() {
+                    return; // Client connection gone
+                }
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
 impl Drop for Gateway {
     fn drop(&mut self) {
         self.task_handle.abort();
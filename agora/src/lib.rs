@@ -1,22 +1,36 @@
 // Re-export error macros at crate root
 #[macro_use]
 pub mod utils;
-pub use utils::ConnectionHandle;
+pub use utils::{AuthToken, ConnectionHandle};
 
 pub mod constants;
 mod core;
+mod logging;
+pub use logging::init_tracing;
 pub mod metaserver;
 pub mod ping;
 mod pywrappers;
 pub mod rawstream;
+pub use rawstream::ProtocolMismatch;
 mod relay;
-pub use relay::Relay;
+pub use relay::{PauseBehavior, Relay, RelayRetry, RelayStats, SwaponRetry};
 
 pub mod gateway;
 
+pub mod blocking;
+
+pub mod testkit;
+
 // Re-export core types at the top level for easy access
 pub use core::publisher::Publisher;
-pub use core::subscriber::{OmniSubscriber, Subscriber};
+pub use core::subscriber::{
+    DeadLetter, DecodeErrorHandler, OmniSubscriber, Sampling, SchemaVersionMismatch, Subscriber,
+    TypeMismatchError,
+};
+pub use core::broadcast::BroadcastHandle;
+pub use core::codec::WireCodec;
+pub use core::group::{PublisherGroup, PublisherSpec};
+pub use core::join::{JoinColdStart, JoinSide, JoinSubscriber, Joined};
 pub use core::{Agorable, AgorableOption};
 
 use pyo3::prelude::*;
@@ -24,6 +38,9 @@ use pyo3::prelude::*;
 /// A Python module implemented in Rust.
 #[pymodule]
 fn agora(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Print library events (reconnects, registration, ...) as the old stderr lines did
+    init_tracing();
+
     // Connection handle
     m.add_class::<pywrappers::connection_handle::PyConnectionHandle>()?;
 
@@ -43,6 +60,7 @@ fn agora(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<pywrappers::publishers::PyBoolPublisher>()?;
     m.add_class::<pywrappers::publishers::PyF64Publisher>()?;
     m.add_class::<pywrappers::publishers::PyF32Publisher>()?;
+    m.add_class::<pywrappers::publishers::PyDictPublisher>()?;
 
     // Typed relays
     m.add_class::<pywrappers::relays::PyStringRelay>()?;
@@ -58,5 +76,12 @@ fn agora(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<pywrappers::subscribers::PyF64Iterator>()?;
     m.add_class::<pywrappers::subscribers::PyF32Iterator>()?;
 
+    // Typed async iterators (`async for`)
+    m.add_class::<pywrappers::subscribers::PyStringAsyncIterator>()?;
+    m.add_class::<pywrappers::subscribers::PyI64AsyncIterator>()?;
+    m.add_class::<pywrappers::subscribers::PyBoolAsyncIterator>()?;
+    m.add_class::<pywrappers::subscribers::PyF64AsyncIterator>()?;
+    m.add_class::<pywrappers::subscribers::PyF32AsyncIterator>()?;
+
     Ok(())
 }
@@ -0,0 +1,25 @@
+//! `tracing` setup for agora and argus processes.
+//! Library code emits leveled events with structured fields (path, symbol, error, ...); binaries call `init_tracing` once to print them.
+
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable selecting the output format: `json` for one JSON object per event, anything else for plain text
+const LOG_FORMAT_ENV: &str = "AGORA_LOG_FORMAT";
+
+/// Installs the global `tracing` subscriber, writing to stderr.
+/// Levels come from `RUST_LOG` (e.g. `RUST_LOG=agora=debug,argus=warn`), defaulting to `info`.
+/// Output is plain text unless `AGORA_LOG_FORMAT=json`.
+/// No-op if a global subscriber is already installed, so it is safe to call from every entry point.
+pub fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let json =
+        std::env::var(LOG_FORMAT_ENV).is_ok_and(|format| format.eq_ignore_ascii_case("json"));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    let _ = if json {
+        builder.json().try_init()
+    } else {
+        builder.try_init()
+    };
+}
@@ -11,7 +11,7 @@ mod client;
 pub use client::AgoraClient;
 
 mod protocol;
-pub use protocol::AgoraMeta;
+pub use protocol::{AgoraMeta, MetaResult, MetaServerError};
 
 mod state;
-pub use state::ServerState;
+pub use state::{PathCharset, ServerState, ServerStatus};
@@ -1,8 +1,9 @@
 //! TARPC client for metaserver RPC operations (register, confirm, query publishers).
 //! `AgoraClient` wraps `AgoraMetaClient`, provides high-level API for `Publisher`, `Subscriber`, `Relay` to interact with metaserver.
 
-use super::protocol::AgoraMetaClient;
+use super::protocol::{AgoraMetaClient, MetaServerError};
 use super::publisher_info::PublisherInfo;
+use super::state::ServerStatus;
 use crate::ConnectionHandle;
 use crate::agora_error;
 use crate::utils::OrError;
@@ -13,6 +14,7 @@ use tarpc::{client, context, tokio_serde::formats::Json};
 /// TARPC client for metaserver RPC communication (service discovery and publisher lifecycle).
 /// Maintains persistent TCP connection to metaserver, provides high-level API over `AgoraMetaClient`.
 /// Used by: `Publisher`, `Subscriber`, `OmniSubscriber`, `Relay` for registration/query operations.
+/// Presents `metaserver_connection`'s `AuthToken` (if any) on register/confirm/remove.
 pub struct AgoraClient {
     metaserver_connection: ConnectionHandle,
     client: AgoraMetaClient,
@@ -49,7 +51,13 @@ impl AgoraClient {
     ) -> OrError<PublisherInfo> {
         let host_connection = ConnectionHandle::new_local(gateway_port)?;
         let rpc_result = self.client
-            .register_publisher(context::current(), name, path, host_connection)
+            .register_publisher(
+                context::current(),
+                name,
+                path,
+                host_connection,
+                self.metaserver_connection.token(),
+            )
             .await
             .context(agora_error!(
                 "metaserver::AgoraClient",
@@ -59,31 +67,77 @@ impl AgoraClient {
                     self.metaserver_connection
                 )
             ))?;
-        rpc_result.map_err(|e| anyhow::anyhow!(e))
+        rpc_result.map_err(meta_error)
     }
 
     pub async fn confirm_publisher(&self, path: &str) -> OrError<()> {
         let rpc_result = self.client
-            .confirm_publisher(context::current(), path.to_string())
+            .confirm_publisher(
+                context::current(),
+                path.to_string(),
+                self.metaserver_connection.token(),
+            )
             .await
             .context(agora_error!(
                 "metaserver::AgoraClient",
                 "confirm_publisher",
                 "RPC call failed"
             ))?;
-        rpc_result.map_err(|e| anyhow::anyhow!(e))
+        rpc_result.map_err(meta_error)
     }
 
     pub async fn remove_publisher(&self, path: &str) -> OrError<PublisherInfo> {
         let rpc_result = self.client
-            .remove_publisher(context::current(), path.to_string())
+            .remove_publisher(
+                context::current(),
+                path.to_string(),
+                self.metaserver_connection.token(),
+            )
             .await
             .context(agora_error!(
                 "metaserver::AgoraClient",
                 "remove_publisher",
                 "RPC call failed"
             ))?;
-        rpc_result.map_err(|e| anyhow::anyhow!(e))
+        rpc_result.map_err(meta_error)
+    }
+
+    /// Removes every publisher at or below `prefix` in one RPC (e.g. a retired versioned worker set).
+    /// Returns: Removed paths, sorted.
+    pub async fn remove_subtree(&self, prefix: &str) -> OrError<Vec<String>> {
+        let rpc_result = self.client
+            .remove_subtree(
+                context::current(),
+                prefix.to_string(),
+                self.metaserver_connection.token(),
+            )
+            .await
+            .context(agora_error!(
+                "metaserver::AgoraClient",
+                "remove_subtree",
+                "RPC call failed"
+            ))?;
+        rpc_result.map_err(meta_error)
+    }
+
+    /// Moves `from` and everything below it to `to` (e.g. renaming a publisher's leaf).
+    /// Moved publishers keep their connections; subscribers of `to` reach them at their original sockets.
+    /// Returns: New paths of the moved publishers, sorted.
+    pub async fn move_subtree(&self, from: &str, to: &str) -> OrError<Vec<String>> {
+        let rpc_result = self.client
+            .move_subtree(
+                context::current(),
+                from.to_string(),
+                to.to_string(),
+                self.metaserver_connection.token(),
+            )
+            .await
+            .context(agora_error!(
+                "metaserver::AgoraClient",
+                "move_subtree",
+                "RPC call failed"
+            ))?;
+        rpc_result.map_err(meta_error)
     }
 
     pub async fn get_path_tree(&self) -> OrError<TreeNodeRef> {
@@ -111,6 +165,73 @@ impl AgoraClient {
             ))?;
         rpc_result.map_err(|e| anyhow::anyhow!(e))
     }
+
+    /// Streaming subscribers (`Subscriber<T>` and `OmniSubscriber`) currently connected to the publisher at `path`.
+    /// Error: Publisher absent, unconfirmed or unreachable → propagates to caller.
+    pub async fn get_subscriber_count(&self, path: &str) -> OrError<usize> {
+        let rpc_result = self.client
+            .subscriber_count(context::current(), path.to_string())
+            .await
+            .context(agora_error!(
+                "metaserver::AgoraClient",
+                "get_subscriber_count",
+                "RPC call failed"
+            ))?;
+        rpc_result.map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Registry paths of publishers whose gateway is on `host`'s IP (and port, if `match_port`), sorted.
+    /// Used to drain a host: list its publishers, then migrate each elsewhere.
+    pub async fn get_publishers_by_host(
+        &self,
+        host: ConnectionHandle,
+        match_port: bool,
+    ) -> OrError<Vec<String>> {
+        self.client
+            .publishers_by_host(context::current(), host, match_port)
+            .await
+            .context(agora_error!(
+                "metaserver::AgoraClient",
+                "get_publishers_by_host",
+                "RPC call failed"
+            ))
+    }
+
+    /// `(registry path, info)` of publishers whose registered name contains `name_substring`, sorted by path.
+    /// Finds a symbol's publishers without knowing their (versioned) paths; complements path-based lookups.
+    pub async fn find_publishers(
+        &self,
+        name_substring: &str,
+    ) -> OrError<Vec<(String, PublisherInfo)>> {
+        self.client
+            .find_publishers(context::current(), name_substring.to_string())
+            .await
+            .context(agora_error!(
+                "metaserver::AgoraClient",
+                "find_publishers",
+                "RPC call failed"
+            ))
+    }
+
+    /// Registry counts and prune statistics. Cheap liveness probe: the metaserver pings nothing.
+    pub async fn get_server_status(&self) -> OrError<ServerStatus> {
+        self.client
+            .server_status(context::current())
+            .await
+            .context(agora_error!(
+                "metaserver::AgoraClient",
+                "get_server_status",
+                "RPC call failed"
+            ))
+    }
+}
+
+/// `Unauthorized` stays a typed (downcastable) error; other failures keep the server's message as before.
+fn meta_error(e: MetaServerError) -> anyhow::Error {
+    match e {
+        MetaServerError::Failed(message) => anyhow::anyhow!(message),
+        unauthorized => anyhow::Error::new(unauthorized),
+    }
 }
 
 impl Clone for AgoraClient {
@@ -1,9 +1,38 @@
 //! TARPC service trait defining metaserver RPC protocol.
-//! `AgoraMeta` specifies async methods for publisher lifecycle: register, confirm, remove, query, and path tree inspection.
+//! `AgoraMeta` specifies async methods for publisher lifecycle: register, confirm, remove, query, path tree inspection, and server status.
+//! Mutating methods carry the caller's `AuthToken`; read-only methods stay open.
 
 use super::publisher_info::PublisherInfo;
+use super::state::ServerStatus;
+use crate::utils::{AuthToken, RpcError};
 use crate::ConnectionHandle;
-use crate::utils::RpcError;
+use std::fmt;
+
+/// Error returned by the token-gated metaserver RPCs.
+/// `AgoraClient` surfaces `Unauthorized` as a downcastable `anyhow::Error`; `Failed` keeps the server's message.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum MetaServerError {
+    /// The server requires a token and the caller presented none, or a different one
+    Unauthorized { method: String },
+    Failed(String),
+}
+
+impl fmt::Display for MetaServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetaServerError::Unauthorized { method } => write!(
+                f,
+                "unauthorized: {} requires the metaserver's auth token",
+                method
+            ),
+            MetaServerError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for MetaServerError {}
+
+pub type MetaResult<T> = Result<T, MetaServerError>;
 
 #[tarpc::service]
 pub trait AgoraMeta {
@@ -12,13 +41,30 @@ pub trait AgoraMeta {
         name: String,
         path: String,
         host_connection: ConnectionHandle,
-    ) -> RpcError<PublisherInfo>;
+        token: Option<AuthToken>,
+    ) -> MetaResult<PublisherInfo>;
     /// Confirms a registered publisher by pinging it.
-    async fn confirm_publisher(path: String) -> RpcError<()>;
+    async fn confirm_publisher(path: String, token: Option<AuthToken>) -> MetaResult<()>;
     /// Removes a publisher from the specified path.
-    async fn remove_publisher(path: String) -> RpcError<PublisherInfo>;
+    async fn remove_publisher(path: String, token: Option<AuthToken>) -> MetaResult<PublisherInfo>;
+    /// Removes every publisher at or below `prefix`, returning the removed paths.
+    async fn remove_subtree(prefix: String, token: Option<AuthToken>) -> MetaResult<Vec<String>>;
+    /// Moves the node at `from` and its subtree to `to`, returning the moved publishers' new paths.
+    async fn move_subtree(
+        from: String,
+        to: String,
+        token: Option<AuthToken>,
+    ) -> MetaResult<Vec<String>>;
     /// Returns the path tree as a string representation.
     async fn path_tree() -> String;
     /// Retrieves publisher information for the specified path.
     async fn publisher_info(path: String) -> RpcError<PublisherInfo>;
+    /// Returns how many streaming subscribers are connected to the publisher at `path`.
+    async fn subscriber_count(path: String) -> RpcError<usize>;
+    /// Returns the registry paths of publishers served from `host`'s IP (and port, if `match_port`).
+    async fn publishers_by_host(host: ConnectionHandle, match_port: bool) -> Vec<String>;
+    /// Returns `(path, info)` of publishers whose registered name contains `name_substring`.
+    async fn find_publishers(name_substring: String) -> Vec<(String, PublisherInfo)>;
+    /// Returns registry counts, prune statistics and uptime.
+    async fn server_status() -> ServerStatus;
 }
@@ -2,31 +2,62 @@
 //! `PublisherInfo` is returned by metaserver queries and transmitted via TARPC for service discovery.
 
 use crate::ConnectionHandle;
+use chrono::{DateTime, TimeDelta, Utc};
 
 /// Publisher metadata bundling name, gateway address, and registry path.
 /// Returned by `AgoraMetaServer::register_publisher` and `get_publisher_info`, transmitted to subscribers for connection setup.
+/// `last_confirmed` is when the metaserver last heard back from the publisher's ping endpoint (registration time until then).
+/// `registered_at` is when it was registered; a publisher still unconfirmed long after it is evicted (see `ServerState::with_unconfirmed_grace`).
 #[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PublisherInfo {
     name: String,
     host_connection: ConnectionHandle,
     agora_path: String,
+    last_confirmed: DateTime<Utc>,
+    /// Absent from snapshots written before it existed; those publishers count as registered on restore
+    #[serde(default = "Utc::now")]
+    registered_at: DateTime<Utc>,
 }
 
 impl PublisherInfo {
     pub fn new(name: &str, host_connection: ConnectionHandle, agora_path: &str) -> Self {
+        let now = Utc::now();
         Self {
             name: String::from(name),
             host_connection,
             agora_path: String::from(agora_path),
+            last_confirmed: now,
+            registered_at: now,
         }
     }
 
+    /// Time of the metaserver's last successful ping (confirmation, pruning pass or `get_publisher_info`)
+    pub fn last_confirmed(&self) -> DateTime<Utc> {
+        self.last_confirmed
+    }
+
+    /// How long ago the publisher was last confirmed healthy, e.g. to tell "200ms ago" from "45s ago"
+    pub fn confirmed_ago(&self) -> TimeDelta {
+        Utc::now().signed_duration_since(self.last_confirmed)
+    }
+
+    /// Time the publisher was registered with the metaserver
+    pub fn registered_at(&self) -> DateTime<Utc> {
+        self.registered_at
+    }
+
+    /// Called by: `ServerState` after every successful ping
+    pub(crate) fn mark_confirmed(&mut self, at: DateTime<Utc>) {
+        self.last_confirmed = at;
+    }
+
     /// Get the socket address for tcp-websocket connections
     pub fn connection(&self) -> &ConnectionHandle {
         &self.host_connection
     }
 
     /// Get the socket address for heartbeat connections
+    /// Path the publisher serves its sockets from. Differs from its registry path after `move_subtree`.
     pub fn path(&self) -> &str {
         &self.agora_path
     }
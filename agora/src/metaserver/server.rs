@@ -2,14 +2,19 @@
 //! `AgoraMetaServer` implements `AgoraMeta` RPC trait, manages `ServerState` via `RwLock`, runs background task to prune stale publishers every 500ms.
 
 use super::ServerState;
-use super::protocol::AgoraMeta;
+use super::protocol::{AgoraMeta, MetaResult, MetaServerError};
 use super::publisher_info::PublisherInfo;
+use super::state::{PathCharset, ServerStatus};
 use crate::ConnectionHandle;
-use crate::constants::CHECK_PUBLISHER_LIVELINESS_EVERY_MS;
-use crate::utils::RpcError;
+use crate::constants::{
+    CHECK_PUBLISHER_LIVELINESS_EVERY_MS, METASERVER_MAX_CONCURRENT_CHANNELS,
+    UNCONFIRMED_PUBLISHER_GRACE_MS,
+};
+use crate::utils::{AuthToken, RpcError};
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
 use std::net::IpAddr;
 use tarpc::context;
@@ -25,10 +30,12 @@ use tokio::time::{Duration, interval};
 /// TARPC-based metaserver managing publisher registry with shared state architecture.
 /// Architecture: Single `ServerState` protected by `RwLock`, multiple concurrent TARPC connections, background pruning task.
 /// RPC protocol: `AgoraMeta` trait defines service discovery methods (register, confirm, query publishers).
+/// Auth: With a server token, register/confirm/remove require the caller to present the same token; queries stay open.
 #[derive(Clone)]
 pub struct AgoraMetaServer {
     state: Arc<RwLock<ServerState>>,
     bg_handle: Arc<Mutex<JoinHandle<()>>>,
+    auth_token: Option<AuthToken>,
 }
 
 impl AgoraMeta for AgoraMetaServer {
@@ -38,19 +45,67 @@ impl AgoraMeta for AgoraMetaServer {
         name: String,
         path: String,
         host_connection: ConnectionHandle,
-    ) -> RpcError<PublisherInfo> {
+        token: Option<AuthToken>,
+    ) -> MetaResult<PublisherInfo> {
+        self.authorize("register_publisher", token)?;
+        let mut state = self.state.write().await;
+        state
+            .register_publisher(name, path, host_connection)
+            .map_err(|e| MetaServerError::Failed(e.to_string()))
+    }
+
+    async fn confirm_publisher(
+        self,
+        _: context::Context,
+        path: String,
+        token: Option<AuthToken>,
+    ) -> MetaResult<()> {
+        self.authorize("confirm_publisher", token)?;
         let mut state = self.state.write().await;
-        state.register_publisher(name, path, host_connection).map_err(|e| e.to_string())
+        state
+            .confirm_publisher(&path)
+            .await
+            .map_err(|e| MetaServerError::Failed(e.to_string()))
     }
 
-    async fn confirm_publisher(self, _: context::Context, path: String) -> RpcError<()> {
+    async fn remove_publisher(
+        self,
+        _: context::Context,
+        path: String,
+        token: Option<AuthToken>,
+    ) -> MetaResult<PublisherInfo> {
+        self.authorize("remove_publisher", token)?;
         let mut state = self.state.write().await;
-        state.confirm_publisher(&path).await.map_err(|e| e.to_string())
+        state
+            .remove_publisher(&path)
+            .map_err(|e| MetaServerError::Failed(e.to_string()))
     }
 
-    async fn remove_publisher(self, _: context::Context, path: String) -> RpcError<PublisherInfo> {
+    async fn remove_subtree(
+        self,
+        _: context::Context,
+        prefix: String,
+        token: Option<AuthToken>,
+    ) -> MetaResult<Vec<String>> {
+        self.authorize("remove_subtree", token)?;
         let mut state = self.state.write().await;
-        state.remove_publisher(&path).map_err(|e| e.to_string())
+        state
+            .remove_subtree(&prefix)
+            .map_err(|e| MetaServerError::Failed(e.to_string()))
+    }
+
+    async fn move_subtree(
+        self,
+        _: context::Context,
+        from: String,
+        to: String,
+        token: Option<AuthToken>,
+    ) -> MetaResult<Vec<String>> {
+        self.authorize("move_subtree", token)?;
+        let mut state = self.state.write().await;
+        state
+            .move_subtree(&from, &to)
+            .map_err(|e| MetaServerError::Failed(e.to_string()))
     }
 
     async fn path_tree(self, _: context::Context) -> String {
@@ -62,13 +117,60 @@ impl AgoraMeta for AgoraMetaServer {
         let mut state = self.state.write().await;
         state.get_publisher_info(&path).await.map_err(|e| e.to_string())
     }
+
+    async fn subscriber_count(self, _: context::Context, path: String) -> RpcError<usize> {
+        let mut state = self.state.write().await;
+        state.subscriber_count(&path).await.map_err(|e| e.to_string())
+    }
+
+    async fn publishers_by_host(
+        self,
+        _: context::Context,
+        host: ConnectionHandle,
+        match_port: bool,
+    ) -> Vec<String> {
+        let state = self.state.read().await;
+        state.publishers_by_host(&host, match_port)
+    }
+
+    async fn find_publishers(
+        self,
+        _: context::Context,
+        name_substring: String,
+    ) -> Vec<(String, PublisherInfo)> {
+        let state = self.state.read().await;
+        state.find_publishers(&name_substring)
+    }
+
+    async fn server_status(self, _: context::Context) -> ServerStatus {
+        let state = self.state.read().await;
+        state.server_status()
+    }
 }
 
 impl AgoraMetaServer {
-    fn new(shared_state: Arc<RwLock<ServerState>>, bg_handle: Arc<Mutex<JoinHandle<()>>>) -> Self {
+    fn new(
+        shared_state: Arc<RwLock<ServerState>>,
+        bg_handle: Arc<Mutex<JoinHandle<()>>>,
+        auth_token: Option<AuthToken>,
+    ) -> Self {
         Self {
             state: shared_state,
             bg_handle,
+            auth_token,
+        }
+    }
+
+    /// No server token: everything is open. Otherwise the presented token must match.
+    fn authorize(&self, method: &str, token: Option<AuthToken>) -> MetaResult<()> {
+        match &self.auth_token {
+            Some(expected) if !expected.matches(token.as_ref()) => {
+                warn!(method, "rejected unauthorized call");
+                Err(MetaServerError::Unauthorized {
+                    method: method.to_string(),
+                })
+            }
+            _ => Ok(()),
         }
     }
 
@@ -76,15 +178,44 @@ impl AgoraMetaServer {
     /// Architecture: One `ServerState` (`RwLock`ed), many client connections, one pruning task.
     /// Network: Listens on TCP for TARPC connections, serves `AgoraMeta` RPC methods.
     /// Background: Prunes stale publishers every 500ms by pinging them.
-    pub async fn run_server(address: IpAddr, port: u16) -> anyhow::Result<()> {
+    /// Auth: `auth_token` gates register/confirm/remove; `None` leaves the server open to anyone who can reach the port.
+    /// Concurrency: At most `max_channels` connections (default `METASERVER_MAX_CONCURRENT_CHANNELS`) are served at once;
+    /// later ones are accepted as earlier ones close. See the constant for the memory cost per channel.
+    /// Paths: `path_charset` (default `PathCharset::Permissive`) bounds the characters allowed in path segments.
+    /// Zombies: publishers that register but never confirm are evicted after `unconfirmed_grace`
+    /// (default `UNCONFIRMED_PUBLISHER_GRACE_MS`), see `ServerState::with_unconfirmed_grace`.
+    pub async fn run_server(
+        address: IpAddr,
+        port: u16,
+        auth_token: Option<AuthToken>,
+        max_channels: Option<usize>,
+        path_charset: Option<PathCharset>,
+        unconfirmed_grace: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        let max_channels = max_channels
+            .unwrap_or(METASERVER_MAX_CONCURRENT_CHANNELS)
+            .max(1);
         let server_addr = (address, port);
 
         // Single shared state accessed by all TARPC connections
-        let shared_state = Arc::new(RwLock::new(ServerState::new()));
+        let shared_state = Arc::new(RwLock::new(
+            ServerState::new()
+                .with_path_charset(path_charset.unwrap_or_default())
+                .with_unconfirmed_grace(
+                    unconfirmed_grace
+                        .unwrap_or(Duration::from_millis(UNCONFIRMED_PUBLISHER_GRACE_MS)),
+                ),
+        ));
 
         // TARPC TCP listener with JSON serialization
         let mut listener = tarpc::serde_transport::tcp::listen(&server_addr, Json::default).await?;
-        println!("Metaserver active on {}:{}", address, port);
+        info!(
+            %address,
+            port,
+            auth = if auth_token.is_some() { "token required" } else { "open" },
+            max_channels,
+            "metaserver active"
+        );
         listener.config_mut().max_frame_length(usize::MAX);
 
         // Background pruning task: pings publishers every 500ms, removes dead ones
@@ -98,10 +229,12 @@ impl AgoraMetaServer {
                     state.prune_stale_publishers().await
                 }; // Drop lock before printing
                 if !pruned_paths.is_empty() {
-                    println!("Pruned stale publishers: {:?}", pruned_paths);
+                    info!(paths = ?pruned_paths, "pruned stale publishers");
                 }
             }
         })));
+        // Aborting the task running this future (e.g. `testkit::TestCluster` drop) also stops pruning
+        let _pruning_guard = PruningGuard(Arc::clone(&bg_handle));
 
         // TARPC connection processing pipeline
         listener
@@ -111,13 +244,16 @@ impl AgoraMetaServer {
             .map(|channel| {
                 // Each channel = one client connection
                 // All connections share the same ServerState
-                let server =
-                    AgoraMetaServer::new(Arc::clone(&shared_state), Arc::clone(&bg_handle));
+                let server = AgoraMetaServer::new(
+                    Arc::clone(&shared_state),
+                    Arc::clone(&bg_handle),
+                    auth_token,
+                );
                 channel.execute(server.serve()).for_each(|fut| async {
                     fut.await;
                 })
             })
-            .buffer_unordered(32768 * 1024)
+            .buffer_unordered(max_channels)
             .for_each(|_| async {}) // Run forever
             .await;
 
@@ -125,13 +261,24 @@ impl AgoraMetaServer {
     }
 }
 
+/// Aborts the background pruning task when `run_server`'s future is dropped
+struct PruningGuard(Arc<Mutex<JoinHandle<()>>>);
+
+impl Drop for PruningGuard {
+    fn drop(&mut self) {
+        if let Ok(handle_guard) = self.0.try_lock() {
+            handle_guard.abort();
+        }
+    }
+}
+
 impl Drop for AgoraMetaServer {
     fn drop(&mut self) {
         if Arc::strong_count(&self.bg_handle) == 1
             && let Ok(handle_guard) = self.bg_handle.try_lock()
         {
             handle_guard.abort();
-            println!("Background pruning task stopped");
+            debug!("background pruning task stopped");
         }
     }
 }
@@ -4,10 +4,60 @@
 use super::publisher_info::PublisherInfo;
 use crate::ConnectionHandle;
 use crate::ping::PingClient;
-use crate::utils::{OrError, TreeNode, TreeNodeRef, TreeTrait};
+use crate::utils::{OrError, PathTreeSnapshot, TreeNode, TreeNodeRef, TreeTrait};
 use crate::agora_error;
+use crate::constants::UNCONFIRMED_PUBLISHER_GRACE_MS;
 use anyhow::{bail, Context};
+use chrono::Utc;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{info, instrument, warn};
+
+/// Point-in-time metaserver health summary, cheap enough for liveness probes.
+/// Returned by `AgoraMeta::server_status`; unlike `list_publishers`-style queries it pings nothing.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ServerStatus {
+    pub registered_count: usize,
+    pub confirmed_count: usize,
+    /// Publishers removed by the most recent prune pass
+    pub last_prune_removed: usize,
+    /// Publishers removed by all prune passes since startup
+    pub total_pruned: u64,
+    pub uptime: Duration,
+}
+
+/// Path tree node as encoded by `ServerState::to_snapshot_repr`: the `TreeNode` structure plus,
+/// for publisher leaves, the registered `PublisherInfo`. Leaves without one are (empty) directories.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct SnapshotNode {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    publisher: Option<PublisherInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<SnapshotNode>,
+}
+
+/// Characters accepted in path segments by `ServerState::validate_path_format`.
+/// Segment names become directory names under the scribe's archive root, so `Portable` keeps them filesystem-safe everywhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathCharset {
+    /// Anything except control characters
+    #[default]
+    Permissive,
+    /// Only `[A-Za-z0-9._@-]` within segments
+    Portable,
+}
+
+impl PathCharset {
+    pub fn allows(&self, c: char) -> bool {
+        match self {
+            PathCharset::Permissive => !c.is_control(),
+            PathCharset::Portable => {
+                c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '@' | '-')
+            }
+        }
+    }
+}
 
 /// Shared metaserver state managing publisher registry, path tree hierarchy, and health checks.
 /// Three maps: `publishers` (all registered), `confirmed_publishers` (with active `PingClient`s), `path_tree` (hierarchical structure).
@@ -17,6 +67,11 @@ pub struct ServerState {
     pub path_tree: TreeNodeRef,
     pub publishers: HashMap<String, PublisherInfo>,
     pub confirmed_publishers: HashMap<String, PingClient>,
+    started_at: Instant,
+    last_prune_removed: usize,
+    total_pruned: u64,
+    path_charset: PathCharset,
+    unconfirmed_grace: Duration,
 }
 
 impl Default for ServerState {
@@ -31,6 +86,43 @@ impl ServerState {
             path_tree: TreeNode::new("agora"),
             publishers: HashMap::new(),
             confirmed_publishers: HashMap::new(),
+            started_at: Instant::now(),
+            last_prune_removed: 0,
+            total_pruned: 0,
+            path_charset: PathCharset::default(),
+            unconfirmed_grace: Duration::from_millis(UNCONFIRMED_PUBLISHER_GRACE_MS),
+        }
+    }
+
+    /// Restricts the characters accepted in path segments; `Permissive` unless set.
+    pub fn with_path_charset(mut self, path_charset: PathCharset) -> Self {
+        self.path_charset = path_charset;
+        self
+    }
+
+    pub fn path_charset(&self) -> PathCharset {
+        self.path_charset
+    }
+
+    /// How long a registered publisher may stay unconfirmed before `prune_stale_publishers` evicts it,
+    /// freeing the path a crashed `Publisher::new` reserved; `UNCONFIRMED_PUBLISHER_GRACE_MS` unless set.
+    pub fn with_unconfirmed_grace(mut self, unconfirmed_grace: Duration) -> Self {
+        self.unconfirmed_grace = unconfirmed_grace;
+        self
+    }
+
+    pub fn unconfirmed_grace(&self) -> Duration {
+        self.unconfirmed_grace
+    }
+
+    /// Counters only: no pings, no tree walk, so callers hold the lock briefly.
+    pub fn server_status(&self) -> ServerStatus {
+        ServerStatus {
+            registered_count: self.publishers.len(),
+            confirmed_count: self.confirmed_publishers.len(),
+            last_prune_removed: self.last_prune_removed,
+            total_pruned: self.total_pruned,
+            uptime: self.started_at.elapsed(),
         }
     }
 
@@ -38,10 +130,17 @@ impl ServerState {
         self.path_tree.clone()
     }
 
+    /// Owned, lock-free copy of the path tree for iteration and globbing.
+    /// Prefer over `path_tree()` for read-only walks: the copy is taken once, so callers never hold tree locks.
+    pub fn path_tree_snapshot(&self) -> PathTreeSnapshot {
+        PathTreeSnapshot::of_node(&self.path_tree)
+    }
+
     /// Registers publisher at path after validation.
     /// Validates: path format, not duplicate, parents are directories, path is new leaf.
     /// Error: Validation fails → returns to `AgoraClient` RPC caller → `Publisher::new`.
     /// Called by: `AgoraMetaServer` (TARPC handler) ← `AgoraClient::register_publisher` ← `Publisher::new`
+    #[instrument(level = "debug", skip_all, fields(path = %path))]
     pub fn register_publisher(
         &mut self,
         name: String,
@@ -83,10 +182,11 @@ impl ServerState {
 
         let publisher_info = PublisherInfo::new(&name, host_connection, &path);
         self.publishers.insert(path, publisher_info.clone());
-        println!(
-            "Registered publisher {:?} at path {}",
-            &publisher_info.name(),
-            &publisher_info.path()
+        info!(
+            name = %publisher_info.name(),
+            path = %publisher_info.path(),
+            host = %host_connection,
+            "registered publisher"
         );
         Ok(publisher_info)
     }
@@ -95,6 +195,7 @@ impl ServerState {
     /// Auto-removes publisher from registry if ping fails.
     /// Error: Not registered, already confirmed, or ping fails → returns to `Publisher::new`.
     /// Called by: `AgoraMetaServer` (TARPC) ← `AgoraClient::confirm_publisher` ← `Publisher::new`
+    #[instrument(level = "debug", skip(self))]
     pub async fn confirm_publisher(&mut self, path: &str) -> OrError<()> {
         if !self.publishers.contains_key(path) {
             bail!(agora_error!(
@@ -117,11 +218,12 @@ impl ServerState {
         // Create ping client - if fails, auto-remove registration
         let mut pingclient = PingClient::new(path, *publisher_info.connection())
             .await
-            .inspect_err(|_e| {
+            .inspect_err(|e| {
                 let _ = self.remove_publisher(path);
-                eprintln!(
-                    "Removed registered publisher {} upon unsuccessful confirmation",
-                    path
+                warn!(
+                    path,
+                    error = %e,
+                    "removed registered publisher upon unsuccessful confirmation"
                 );
             })
             .context(agora_error!(
@@ -131,21 +233,26 @@ impl ServerState {
             ))?;
 
         // Test ping - if fails, auto-remove registration
-        let _ = pingclient.ping().await.inspect_err(|_| {
+        let _ = pingclient.ping().await.inspect_err(|e| {
             let _ = self.remove_publisher(path);
-            eprintln!(
-                "Removed registered publisher {} upon unsuccessful confirmation",
-                path
+            warn!(
+                path,
+                error = %e,
+                "removed registered publisher upon unsuccessful confirmation"
             );
         })?;
 
         // Success: store ping client for health checks
-        println!("Publisher {} confirmed.", path);
+        info!(path, "publisher confirmed");
+        if let Some(publisher_info) = self.publishers.get_mut(path) {
+            publisher_info.mark_confirmed(Utc::now());
+        }
         self.confirmed_publishers
             .insert(path.to_string(), pingclient);
         Ok(())
     }
 
+    #[instrument(level = "debug", skip(self))]
     pub fn remove_publisher(&mut self, path: &str) -> OrError<PublisherInfo> {
         // Validate path format strictly
         self.validate_path_format(path)?;
@@ -158,6 +265,7 @@ impl ServerState {
                 self.path_tree.remove_child_and_branch(path)?;
                 // Remove from confirmed_publishers if it exists (it may not if confirmation failed)
                 self.confirmed_publishers.remove(path);
+                info!(path, name = %publisher_info.name(), "removed publisher");
                 Ok(publisher_info)
             }
             None => bail!(agora_error!(
@@ -168,18 +276,227 @@ impl ServerState {
         }
     }
 
+    /// Removes every registered publisher at or below `prefix` (segment-wise: `a/b` covers `a/b/c`, not `a/bc`),
+    /// pruning emptied directories via `remove_child_and_branch`. Publishers still awaiting confirmation are removed too,
+    /// so a torn-down subtree leaves nothing behind. Directory nodes are never targeted directly.
+    /// Returns: Removed paths, sorted. Empty if nothing is registered under `prefix`.
+    /// Error: Empty prefix or root (`/`), or malformed prefix → returns to `AgoraClient::remove_subtree`.
+    #[instrument(level = "debug", skip(self))]
+    pub fn remove_subtree(&mut self, prefix: &str) -> OrError<Vec<String>> {
+        if prefix.trim_matches('/').is_empty() {
+            bail!(agora_error!(
+                "metaserver::ServerState",
+                "remove_subtree",
+                "refusing to remove the root: prefix cannot be empty"
+            ));
+        }
+        self.validate_path_format(prefix)?;
+
+        let subtree_prefix = format!("{}/", prefix);
+        let mut paths: Vec<String> = self
+            .publishers
+            .keys()
+            .filter(|path| *path == prefix || path.starts_with(&subtree_prefix))
+            .cloned()
+            .collect();
+        paths.sort();
+
+        for path in &paths {
+            self.remove_publisher(path).context(agora_error!(
+                "metaserver::ServerState",
+                "remove_subtree",
+                &format!("failed to remove {} under {}", path, prefix)
+            ))?;
+        }
+        Ok(paths)
+    }
+
+    /// Moves the node at `from` and everything below it to `to`, re-keying the publishers it holds.
+    /// Only the registry moves: a moved publisher keeps serving from its original path (`PublisherInfo::path`),
+    /// which subscribers resolving the new path connect to. Directories emptied by the move are pruned.
+    /// Returns: New paths of the moved publishers, sorted.
+    /// Error: Malformed paths, `to` below a publisher, or tree validation (see `TreeNode::move_subtree`)
+    /// → returns to `AgoraClient::move_subtree`.
+    #[instrument(level = "debug", skip(self))]
+    pub fn move_subtree(&mut self, from: &str, to: &str) -> OrError<Vec<String>> {
+        self.validate_path_format(from)?;
+        self.validate_path_format(to)?;
+        // Invariant: the destination's ancestors must stay directories
+        self.validate_parent_paths_are_directories(to)?;
+        self.path_tree.move_subtree(from, to).context(agora_error!(
+            "metaserver::ServerState",
+            "move_subtree",
+            &format!("cannot move {} to {}", from, to)
+        ))?;
+
+        let subtree_prefix = format!("{}/", from);
+        let rekey = |path: &str| -> Option<String> {
+            if path == from {
+                Some(to.to_string())
+            } else {
+                path.strip_prefix(&subtree_prefix).map(|rest| format!("{}/{}", to, rest))
+            }
+        };
+        let moved: Vec<(String, String)> = self
+            .publishers
+            .keys()
+            .filter_map(|path| rekey(path).map(|new_path| (path.clone(), new_path)))
+            .collect();
+        for (old_path, new_path) in &moved {
+            if let Some(publisher_info) = self.publishers.remove(old_path) {
+                self.publishers.insert(new_path.clone(), publisher_info);
+            }
+            if let Some(pingclient) = self.confirmed_publishers.remove(old_path) {
+                self.confirmed_publishers.insert(new_path.clone(), pingclient);
+            }
+        }
+
+        // The source's parent may now be an empty directory: prune it like `remove_publisher` would
+        if let Some((from_parent, _)) = from.rsplit_once('/')
+            && self
+                .path_tree
+                .get_child(from_parent)
+                .is_ok_and(|node| node.is_leaf())
+        {
+            self.path_tree.remove_child_and_branch(from_parent)?;
+        }
+
+        let mut new_paths: Vec<String> = moved.into_iter().map(|(_, new_path)| new_path).collect();
+        new_paths.sort();
+        info!(from, to, publishers = new_paths.len(), "moved subtree");
+        Ok(new_paths)
+    }
+
     pub fn get_path_tree_repr(&self) -> String {
         self.path_tree.to_repr()
     }
 
-    /// Returns publisher info after pinging to verify it's alive.
+    /// Serializes the path tree together with the publisher registry, for persisting metaserver state.
+    /// Unlike `get_path_tree_repr` (structure only, served by the tree RPC), every publisher leaf carries its `PublisherInfo`.
+    /// Confirmations, ping clients and prune counters are runtime state and are not encoded.
+    pub fn to_snapshot_repr(&self) -> OrError<String> {
+        let root = self.snapshot_node(&self.path_tree, "");
+        serde_json::to_string(&root).context(agora_error!(
+            "metaserver::ServerState",
+            "to_snapshot_repr",
+            "failed to serialize snapshot"
+        ))
+    }
+
+    /// Restores state written by `to_snapshot_repr`. Every publisher comes back registered but unconfirmed,
+    /// so the restored server must re-confirm each (see `unconfirmed_paths`) before serving it to subscribers.
+    /// Error: Malformed JSON, invalid path segments, duplicate siblings, or a publisher with children.
+    pub fn from_snapshot_repr(repr: &str) -> OrError<Self> {
+        let root: SnapshotNode = serde_json::from_str(repr).context(agora_error!(
+            "metaserver::ServerState",
+            "from_snapshot_repr",
+            "malformed snapshot"
+        ))?;
+        if root.publisher.is_some() {
+            bail!(agora_error!(
+                "metaserver::ServerState",
+                "from_snapshot_repr",
+                "the root cannot be a publisher"
+            ));
+        }
+        let mut state = Self::new();
+        let tree_root = state.path_tree();
+        for child in &root.children {
+            state.restore_snapshot_node(&tree_root, child, "")?;
+        }
+        Ok(state)
+    }
+
+    /// Registered publishers without a ping client, sorted: the ones a restored server still has to confirm.
+    pub fn unconfirmed_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .publishers
+            .keys()
+            .filter(|path| !self.confirmed_publishers.contains_key(*path))
+            .cloned()
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn snapshot_node(&self, node: &TreeNodeRef, path: &str) -> SnapshotNode {
+        // Children keep their registration order so the restored tree renders exactly like the original
+        let children = node.children();
+        SnapshotNode {
+            name: node.name().to_string(),
+            publisher: self.publishers.get(path).cloned(),
+            children: children
+                .iter()
+                .map(|child| {
+                    let child_path = if path.is_empty() {
+                        child.name().to_string()
+                    } else {
+                        format!("{}/{}", path, child.name())
+                    };
+                    self.snapshot_node(child, &child_path)
+                })
+                .collect(),
+        }
+    }
+
+    fn restore_snapshot_node(
+        &mut self,
+        parent: &TreeNodeRef,
+        node: &SnapshotNode,
+        parent_path: &str,
+    ) -> OrError<()> {
+        if node.name.contains('/') {
+            bail!(agora_error!(
+                "metaserver::ServerState",
+                "from_snapshot_repr",
+                &format!("node name {:?} contains '/'", node.name)
+            ));
+        }
+        let path = if parent_path.is_empty() {
+            node.name.clone()
+        } else {
+            format!("{}/{}", parent_path, node.name)
+        };
+        self.validate_path_format(&path).context(agora_error!(
+            "metaserver::ServerState",
+            "from_snapshot_repr",
+            &format!("invalid node name {:?}", node.name)
+        ))?;
+        if parent.get_child(&node.name).is_ok() {
+            bail!(agora_error!(
+                "metaserver::ServerState",
+                "from_snapshot_repr",
+                &format!("duplicate node at {}", path)
+            ));
+        }
+        // Invariant: Publishers are leaves
+        if node.publisher.is_some() && !node.children.is_empty() {
+            bail!(agora_error!(
+                "metaserver::ServerState",
+                "from_snapshot_repr",
+                &format!("publisher at {} has children", path)
+            ));
+        }
+
+        let tree_node = TreeNode::new(&node.name);
+        parent.add_child(tree_node.clone());
+        if let Some(publisher_info) = &node.publisher {
+            self.publishers.insert(path.clone(), publisher_info.clone());
+        }
+        for child in &node.children {
+            self.restore_snapshot_node(&tree_node, child, &path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns publisher info after pinging to verify it's alive, with `last_confirmed` set to this ping.
     /// Error: Not found, not confirmed, or ping fails → returns to `Subscriber::new`.
     /// Called by: `AgoraMetaServer` (TARPC) ← `AgoraClient::get_publisher_info` ← `Subscriber::new`
     pub async fn get_publisher_info(&mut self, path: &str) -> OrError<PublisherInfo> {
         self.validate_path_format(path)?;
 
         match (
-            self.publishers.get(path),
+            self.publishers.get_mut(path),
             self.confirmed_publishers.get_mut(path),
         ) {
             (Some(publisher), Some(pingclient)) => {
@@ -189,6 +506,7 @@ impl ServerState {
                     "get_publisher_info",
                     &format!("cannot ping {}. Publisher might be stale", path)
                 ))?;
+                publisher.mark_confirmed(Utc::now());
                 Ok(publisher.clone())
             }
             (Some(_), None) => bail!(agora_error!(
@@ -204,6 +522,25 @@ impl ServerState {
         }
     }
 
+    /// Streaming subscribers currently connected to the publisher at `path`, as reported by its ping server.
+    /// Error: Not found, not confirmed, or ping fails → returns to `AgoraClient::get_subscriber_count`.
+    pub async fn subscriber_count(&mut self, path: &str) -> OrError<usize> {
+        self.validate_path_format(path)?;
+        let pingclient = self.confirmed_publishers.get_mut(path).ok_or_else(|| {
+            anyhow::anyhow!(agora_error!(
+                "metaserver::ServerState",
+                "subscriber_count",
+                &format!("no confirmed publisher at {}", path)
+            ))
+        })?;
+        let response = pingclient.ping_response().await.context(agora_error!(
+            "metaserver::ServerState",
+            "subscriber_count",
+            &format!("cannot ping {}. Publisher might be stale", path)
+        ))?;
+        Ok(response.subscriber_count)
+    }
+
     fn validate_path_format(&self, path: &str) -> OrError<()> {
         if path.is_empty() {
             bail!(agora_error!(
@@ -265,6 +602,40 @@ impl ServerState {
                     )
                 ));
             }
+
+            // "." and ".." would resolve outside the publisher's directory once the scribe joins the path
+            if *segment == "." || *segment == ".." {
+                bail!(agora_error!(
+                    "metaserver::ServerState",
+                    "validate_path_format",
+                    &format!(
+                        "path '{}' has relative segment '{}' - not allowed",
+                        path, segment
+                    )
+                ));
+            }
+
+            if let Some(c) = segment.chars().find(|c| c.is_control()) {
+                bail!(agora_error!(
+                    "metaserver::ServerState",
+                    "validate_path_format",
+                    &format!(
+                        "path segment {:?} contains control character {:?} - not allowed",
+                        segment, c
+                    )
+                ));
+            }
+
+            if let Some(c) = segment.chars().find(|c| !self.path_charset.allows(*c)) {
+                bail!(agora_error!(
+                    "metaserver::ServerState",
+                    "validate_path_format",
+                    &format!(
+                        "path segment '{}' contains character {:?} outside the portable charset [A-Za-z0-9._@-]",
+                        segment, c
+                    )
+                ));
+            }
         }
 
         Ok(())
@@ -304,34 +675,100 @@ impl ServerState {
         Ok(())
     }
 
-    /// Pings all confirmed publishers, removes those that fail to respond.
-    /// Called by: Background pruning task in `AgoraMetaServer::run_server` (every 500ms).
+    /// Registry paths of every publisher whose gateway is on `host`'s IP (and port, if `match_port`), sorted.
+    /// For draining a host: enumerate its publishers, then migrate them. Registered-but-unconfirmed publishers are included.
+    pub fn publishers_by_host(&self, host: &ConnectionHandle, match_port: bool) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .publishers
+            .iter()
+            .filter(|(_, info)| {
+                let connection = info.connection();
+                connection.addr() == host.addr()
+                    && (!match_port || connection.port() == host.port())
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Publishers whose registered `name` contains `name_substring` (case-sensitive), as `(registry path, info)` sorted by path.
+    /// Workers register under their symbol, so this finds a symbol's publishers across versioned paths; `""` matches all.
+    pub fn find_publishers(&self, name_substring: &str) -> Vec<(String, PublisherInfo)> {
+        let mut found: Vec<(String, PublisherInfo)> = self
+            .publishers
+            .iter()
+            .filter(|(_, info)| info.name().contains(name_substring))
+            .map(|(path, info)| (path.clone(), info.clone()))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        found
+    }
+
+    /// Pings all confirmed publishers, removes those that fail to respond and refreshes `last_confirmed` of the rest.
+    /// Also evicts publishers registered but still unconfirmed after `unconfirmed_grace`, counted as pruned.
+    /// Grace runs from registration or this server's start, whichever is later, so publishers restored by
+    /// `from_snapshot_repr` get a full grace period to be re-confirmed.
+    /// Called by: Background pruning task in `AgoraMetaServer::run_server` (every `CHECK_PUBLISHER_LIVELINESS_EVERY_MS`).
+    /// Updates `last_prune_removed` and `total_pruned` for `server_status`.
     /// Returns: List of pruned paths for logging.
+    #[instrument(level = "debug", skip(self))]
     pub async fn prune_stale_publishers(&mut self) -> Vec<String> {
-        let mut stale_paths: Vec<String> = Vec::new();
+        let mut stale_paths: Vec<String> = self.expired_unconfirmed_paths();
+        for path in &stale_paths {
+            info!(path = %path, grace = ?self.unconfirmed_grace, "evicting publisher never confirmed");
+        }
 
         // Snapshot paths to avoid borrow issues
         let paths_to_check: Vec<String> = self.confirmed_publishers.keys().cloned().collect();
 
         // Ping each publisher - collect failures
         for path in paths_to_check {
-            if let Some(ping_client) = self.confirmed_publishers.get_mut(&path)
-                && let Err(_) = ping_client.ping().await
-            {
-                stale_paths.push(path);
+            if let Some(ping_client) = self.confirmed_publishers.get_mut(&path) {
+                match ping_client.ping().await {
+                    Ok(_) => {
+                        if let Some(publisher_info) = self.publishers.get_mut(&path) {
+                            publisher_info.mark_confirmed(Utc::now());
+                        }
+                    }
+                    Err(_) => stale_paths.push(path),
+                }
             }
         }
 
         // Remove stale publishers from registry and tree
         for path in &stale_paths {
             if let Err(e) = self.remove_publisher(path) {
-                eprintln!("Failed to remove stale publisher at {}: {}", path, e);
+                warn!(path = %path, error = %e, "failed to remove stale publisher");
             }
         }
 
+        self.last_prune_removed = stale_paths.len();
+        self.total_pruned += stale_paths.len() as u64;
         stale_paths
     }
 
+    /// Unconfirmed publishers whose grace (see `prune_stale_publishers`) has elapsed, sorted
+    fn expired_unconfirmed_paths(&self) -> Vec<String> {
+        if self.started_at.elapsed() <= self.unconfirmed_grace {
+            return Vec::new();
+        }
+        let now = Utc::now();
+        let mut paths: Vec<String> = self
+            .publishers
+            .iter()
+            .filter(|(path, _)| !self.confirmed_publishers.contains_key(*path))
+            .filter(|(_, info)| {
+                now.signed_duration_since(info.registered_at())
+                    .to_std()
+                    .is_ok_and(|age| age > self.unconfirmed_grace)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        paths.sort();
+        paths
+    }
+
     fn ensure_path_exists(&mut self, path: &str) -> OrError<()> {
         // Path is already validated, so we can split safely
         let path_parts: Vec<&str> = path.split('/').collect();
@@ -9,6 +9,9 @@ pub struct PingResponse {
     pub vec_payload: Vec<u8>,
     pub str_payload: String,
     pub timestamp: DateTime<Utc>,
+    /// Rawstream clients connected to the publisher; 0 from publishers predating the field
+    #[serde(default)]
+    pub subscriber_count: usize,
 }
 
 mod client;
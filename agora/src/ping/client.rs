@@ -62,31 +62,33 @@ impl PingClient {
     /// Returns `(binary_payload, string_payload, round_trip_time)`.
     /// Error: Send/receive fails or connection closed → propagates to `Subscriber::get`, metaserver pruning.
     pub async fn ping(&mut self) -> OrError<(Vec<u8>, String, TimeDelta)> {
+        let response = self.ping_response().await?;
+        let time_delta = chrono::Utc::now().signed_duration_since(response.timestamp);
+        Ok((response.vec_payload, response.str_payload, time_delta))
+    }
+
+    /// Like `ping`, but returns the full `PingResponse` (including the publisher's subscriber count).
+    /// Called by: `ping`, `ServerState::subscriber_count`
+    pub async fn ping_response(&mut self) -> OrError<PingResponse> {
         // Send ping request
         self.ws_write
             .send(Message::Text("ping".to_string().into()))
             .await
-            .context(agora_error!("ping::PingClient", "ping", "failed to send ping"))?;
+            .context(agora_error!("ping::PingClient", "ping_response", "failed to send ping"))?;
 
         // Wait for response
         if let Some(msg) = self.ws_read.next().await {
             match msg {
-                Ok(Message::Text(json)) => {
-                    let response: PingResponse = serde_json::from_str(&json).context(agora_error!(
-                        "ping::PingClient",
-                        "ping",
-                        "failed to parse response"
-                    ))?;
-
-                    let time_delta = chrono::Utc::now().signed_duration_since(response.timestamp);
-
-                    Ok((response.vec_payload, response.str_payload, time_delta))
-                }
-                Ok(_) => bail!(agora_error!("ping::PingClient", "ping", "unexpected message type")),
-                Err(e) => Err(e).context(agora_error!("ping::PingClient", "ping", "WebSocket error")),
+                Ok(Message::Text(json)) => serde_json::from_str(&json).context(agora_error!(
+                    "ping::PingClient",
+                    "ping_response",
+                    "failed to parse response"
+                )),
+                Ok(_) => bail!(agora_error!("ping::PingClient", "ping_response", "unexpected message type")),
+                Err(e) => Err(e).context(agora_error!("ping::PingClient", "ping_response", "WebSocket error")),
             }
         } else {
-            bail!(agora_error!("ping::PingClient", "ping", "connection closed"));
+            bail!(agora_error!("ping::PingClient", "ping_response", "connection closed"));
         }
     }
 }
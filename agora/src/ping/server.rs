@@ -7,9 +7,10 @@ use crate::agora_error;
 use anyhow::Context;
 use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 use tokio::net::UnixListener;
-use tokio::task::JoinHandle;
+use tokio::task::{JoinHandle, JoinSet};
 use tokio_tungstenite::{accept_async, tungstenite::Message};
 
 #[derive(Clone)]
@@ -21,8 +22,10 @@ struct Payload {
 /// UDS WebSocket server responding to `"ping"` with JSON payload (current value + timestamp).
 /// Protocol: Receives `"ping"` text → sends JSON `PingResponse` with `vec_payload`, `str_payload`, `timestamp`.
 /// Payload updates: Thread-safe via `RwLock`, shared across all connection handlers.
+/// Responses also carry the summed subscriber count of the rawstream servers registered via `track_subscribers`.
 pub struct PingServer {
     payload: Arc<RwLock<Payload>>,
+    subscriber_counters: Arc<RwLock<Vec<Arc<AtomicUsize>>>>,
     bg_handle: JoinHandle<()>,
     socket_path: String,
 }
@@ -51,25 +54,38 @@ impl PingServer {
             str_payload,
         }));
         let shared_payload = payload.clone();
+        let subscriber_counters: Arc<RwLock<Vec<Arc<AtomicUsize>>>> = Arc::new(RwLock::new(Vec::new()));
+        let shared_counters = subscriber_counters.clone();
 
-        // Background task: accept connections and respond to pings
+        // Background task: accept connections and respond to pings. Connection handlers live in its `JoinSet`,
+        // so aborting it (on drop) also closes every open connection and the publisher stops answering pings.
         let bg_handle = tokio::spawn(async move {
+            let mut connections = JoinSet::new();
             loop {
                 if let Ok((stream, _)) = listener.accept().await {
+                    while connections.try_join_next().is_some() {} // Reap finished handlers
                     let payload = shared_payload.clone();
-                    tokio::spawn(async move {
+                    let counters = shared_counters.clone();
+                    connections.spawn(async move {
                         if let Ok(ws_stream) = accept_async(stream).await {
                             let (mut write, mut read) = ws_stream.split();
                             while let Some(msg) = read.next().await {
                                 // Protocol: receive "ping" → send JSON response
                                 if let Ok(Message::Text(text)) = msg
                                     && text == "ping" {
+                                    let subscriber_count = counters
+                                        .read()
+                                        .unwrap()
+                                        .iter()
+                                        .map(|counter| counter.load(Ordering::Acquire))
+                                        .sum();
                                     let response = {
                                         let p = payload.read().unwrap();
                                         PingResponse {
                                             vec_payload: p.vec_payload.clone(),
                                             str_payload: p.str_payload.clone(),
                                             timestamp: Utc::now(),
+                                            subscriber_count,
                                         }
                                     };
                                     if let Ok(json) = serde_json::to_string(&response) {
@@ -85,6 +101,7 @@ impl PingServer {
 
         Ok(Self {
             payload,
+            subscriber_counters,
             bg_handle,
             socket_path,
         })
@@ -96,6 +113,12 @@ impl PingServer {
         payload.vec_payload = vec_payload.to_vec();
         payload.str_payload = str_payload.to_string();
     }
+
+    /// Reports the sum of `counters` (see `RawStreamServer::subscriber_counter`) in every ping response.
+    /// Called by: `Publisher::new`, once its rawstream servers are up
+    pub fn track_subscribers(&mut self, counters: Vec<Arc<AtomicUsize>>) {
+        *self.subscriber_counters.write().unwrap() = counters;
+    }
 }
 
 impl Drop for PingServer {
@@ -1,5 +1,12 @@
 use crate::utils::{BlockingStreamIterator, OrError};
+use futures_util::stream::{Stream, StreamExt};
+use pyo3::exceptions::{PyRuntimeError, PyStopAsyncIteration};
 use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::task::AbortHandle;
 
 #[macro_export]
 macro_rules! create_py_result_iterator {
@@ -34,4 +41,136 @@ macro_rules! create_py_result_iterator {
     };
 }
 
+/// Subscriber stream shared by the tokio tasks serving successive `__anext__` calls
+pub type SharedStream<T> = Arc<tokio::sync::Mutex<Pin<Box<dyn Stream<Item = OrError<T>> + Send>>>>;
+
+/// `async for` iterator: each `__anext__` returns an asyncio future that a task on the
+/// subscriber's runtime resolves, so waiting never blocks the event loop thread.
+/// Cancelling the future (e.g. loop shutdown) or dropping the iterator aborts the pending receive.
+#[macro_export]
+macro_rules! create_py_async_iterator {
+    ($name:ident, $type:ty) => {
+        #[pyclass]
+        pub struct $name {
+            stream: SharedStream<$type>,
+            rt_handle: Handle,
+            pending: Option<AbortHandle>,
+        }
+
+        #[pymethods]
+        impl $name {
+            fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+                slf
+            }
+
+            fn __anext__(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+                let (future, pending) = next_as_future(py, self.stream.clone(), &self.rt_handle)?;
+                self.pending = Some(pending);
+                Ok(future)
+            }
+        }
+
+        impl $name {
+            pub fn new(
+                stream: Pin<Box<dyn Stream<Item = OrError<$type>> + Send>>,
+                rt_handle: Handle,
+            ) -> Self {
+                Self {
+                    stream: Arc::new(tokio::sync::Mutex::new(stream)),
+                    rt_handle,
+                    pending: None,
+                }
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                if let Some(pending) = &self.pending {
+                    pending.abort();
+                }
+            }
+        }
+    };
+}
+
+/// Resolves an asyncio future from its event loop thread, unless it was cancelled meanwhile
+#[pyclass]
+struct FutureCompleter {
+    future: PyObject,
+    outcome: Option<PyResult<PyObject>>,
+}
+
+#[pymethods]
+impl FutureCompleter {
+    fn __call__(&mut self, py: Python<'_>) -> PyResult<()> {
+        let future = self.future.bind(py);
+        if future.call_method0("done")?.is_truthy()? {
+            return Ok(());
+        }
+        match self.outcome.take() {
+            Some(Ok(value)) => {
+                future.call_method1("set_result", (value,))?;
+            }
+            Some(Err(e)) => {
+                future.call_method1("set_exception", (e.into_value(py),))?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+/// Done-callback aborting the task behind an asyncio future once the future is cancelled
+#[pyclass]
+struct AbortOnCancel(AbortHandle);
+
+#[pymethods]
+impl AbortOnCancel {
+    fn __call__(&self, future: &Bound<'_, PyAny>) -> PyResult<()> {
+        if future.call_method0("cancelled")?.is_truthy()? {
+            self.0.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Returns a future on the running asyncio loop resolving to the next item of `stream`, and the
+/// handle of the task awaiting it on `rt_handle`. End of stream raises `StopAsyncIteration`.
+/// Error: Called without a running event loop
+pub fn next_as_future<T>(
+    py: Python<'_>,
+    stream: SharedStream<T>,
+    rt_handle: &Handle,
+) -> PyResult<(PyObject, AbortHandle)>
+where
+    T: for<'py> IntoPyObject<'py> + Send + 'static,
+{
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let (loop_ref, future_ref) = (event_loop.unbind(), future.clone().unbind());
+
+    let task = rt_handle.spawn(async move {
+        let item = stream.lock().await.next().await;
+        Python::with_gil(|py| {
+            let outcome = match item {
+                Some(Ok(value)) => value.into_py_any(py),
+                Some(Err(e)) => Err(PyRuntimeError::new_err(e.to_string())),
+                None => Err(PyStopAsyncIteration::new_err(())),
+            };
+            let completer = FutureCompleter {
+                future: future_ref,
+                outcome: Some(outcome),
+            };
+            // Fails only once the loop is closed, when nobody awaits the future anymore
+            let _ = loop_ref
+                .bind(py)
+                .call_method1("call_soon_threadsafe", (completer,));
+        });
+    });
+    let pending = task.abort_handle();
+    future.call_method1("add_done_callback", (AbortOnCancel(pending.clone()),))?;
+    Ok((future.unbind(), pending))
+}
+
 create_py_result_iterator!(PyResultIterator, String);
+create_py_async_iterator!(PyResultAsyncIterator, String);
@@ -59,6 +59,6 @@ impl PyConnectionHandle {
 impl PyConnectionHandle {
     /// Convert to the underlying Rust ConnectionHandle
     pub fn to_connection_handle(&self) -> ConnectionHandle {
-        self.inner.clone()
+        self.inner
     }
 }
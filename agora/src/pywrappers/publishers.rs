@@ -1,7 +1,11 @@
 use crate::Publisher;
+use crate::metaserver::AgoraClient;
 use crate::pywrappers::connection_handle::PyConnectionHandle;
-use pyo3::exceptions::PyRuntimeError;
+use crate::utils::{ConnectionHandle, OrError};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyFloat, PyInt, PyString};
+use std::collections::HashMap;
 use tokio::runtime::Runtime;
 
 macro_rules! create_typed_publisher {
@@ -56,3 +60,227 @@ create_typed_publisher!(PyI64Publisher, i64);
 create_typed_publisher!(PyBoolPublisher, bool);
 create_typed_publisher!(PyF64Publisher, f64);
 create_typed_publisher!(PyF32Publisher, f32);
+
+/// Publisher behind one key of a `PyDictPublisher`; the variant fixes the key's type
+enum KeyPublisher {
+    String(Publisher<String>),
+    I64(Publisher<i64>),
+    Bool(Publisher<bool>),
+    F64(Publisher<f64>),
+}
+
+/// Python primitive routed to a `KeyPublisher`. `bool` is checked before `int` (Python bools are ints).
+enum KeyValue {
+    String(String),
+    I64(i64),
+    Bool(bool),
+    F64(f64),
+}
+
+impl KeyValue {
+    fn extract(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if value.is_instance_of::<PyBool>() {
+            Ok(KeyValue::Bool(value.extract()?))
+        } else if value.is_instance_of::<PyInt>() {
+            Ok(KeyValue::I64(value.extract()?))
+        } else if value.is_instance_of::<PyFloat>() {
+            Ok(KeyValue::F64(value.extract()?))
+        } else if value.is_instance_of::<PyString>() {
+            Ok(KeyValue::String(value.extract()?))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "Unsupported value type {}: expected str, int, bool or float",
+                value.get_type().name()?
+            )))
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            KeyValue::String(_) => "str",
+            KeyValue::I64(_) => "int",
+            KeyValue::Bool(_) => "bool",
+            KeyValue::F64(_) => "float",
+        }
+    }
+}
+
+impl KeyPublisher {
+    async fn create(
+        name: String,
+        path: String,
+        initial_value: KeyValue,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        Ok(match initial_value {
+            KeyValue::String(v) => KeyPublisher::String(
+                Publisher::new(name, path, v, metaserver_connection, local_gateway_port).await?,
+            ),
+            KeyValue::I64(v) => KeyPublisher::I64(
+                Publisher::new(name, path, v, metaserver_connection, local_gateway_port).await?,
+            ),
+            KeyValue::Bool(v) => KeyPublisher::Bool(
+                Publisher::new(name, path, v, metaserver_connection, local_gateway_port).await?,
+            ),
+            KeyValue::F64(v) => KeyPublisher::F64(
+                Publisher::new(name, path, v, metaserver_connection, local_gateway_port).await?,
+            ),
+        })
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            KeyPublisher::String(_) => "str",
+            KeyPublisher::I64(_) => "int",
+            KeyPublisher::Bool(_) => "bool",
+            KeyPublisher::F64(_) => "float",
+        }
+    }
+}
+
+/// Publishes heterogeneous primitives under `{prefix}/{key}` without one typed publisher per path.
+/// The first `publish` of a key registers a publisher of the value's type (str, int → i64, bool, float → f64)
+/// with that value as initial value; later values of another type raise `TypeError`.
+/// `close()` (or leaving a `with` block) deregisters every key's path.
+#[pyclass]
+pub struct PyDictPublisher {
+    name: String,
+    prefix: String,
+    metaserver_connection: ConnectionHandle,
+    local_gateway_port: u16,
+    metaclient: AgoraClient,
+    publishers: HashMap<String, KeyPublisher>,
+    closed: bool,
+    rt: Runtime,
+}
+
+#[pymethods]
+impl PyDictPublisher {
+    #[new]
+    fn new(
+        name: String,
+        prefix: String,
+        metaserver_connection: PyConnectionHandle,
+        local_gateway_port: u16,
+    ) -> PyResult<Self> {
+        // Create runtime that will be kept for the lifetime of this object
+        let rt = tokio::runtime::Runtime::new().map_err(|e| {
+            PyRuntimeError::new_err(format!("Failed to create tokio runtime: {}", e))
+        })?;
+
+        let metaserver_connection = metaserver_connection.to_connection_handle();
+        let metaclient = rt
+            .block_on(AgoraClient::new(metaserver_connection))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+        Ok(Self {
+            name,
+            prefix: prefix.trim_end_matches('/').to_string(),
+            metaserver_connection,
+            local_gateway_port,
+            metaclient,
+            publishers: HashMap::new(),
+            closed: false,
+            rt,
+        })
+    }
+
+    /// Publishes `value` at `{prefix}/{key}`, registering the path on first use.
+    /// Raises `TypeError` if `key` was first published with another type.
+    fn publish(&mut self, key: String, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        if self.closed {
+            return Err(PyRuntimeError::new_err("PyDictPublisher is closed"));
+        }
+        let value = KeyValue::extract(value)?;
+
+        let Some(publisher) = self.publishers.get_mut(&key) else {
+            let path = format!("{}/{}", self.prefix, key);
+            let publisher = self
+                .rt
+                .block_on(KeyPublisher::create(
+                    self.name.clone(),
+                    path,
+                    value,
+                    self.metaserver_connection,
+                    self.local_gateway_port,
+                ))
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            self.publishers.insert(key, publisher);
+            return Ok(());
+        };
+
+        let published = match (publisher, value) {
+            (KeyPublisher::String(p), KeyValue::String(v)) => self.rt.block_on(p.publish(v)),
+            (KeyPublisher::I64(p), KeyValue::I64(v)) => self.rt.block_on(p.publish(v)),
+            (KeyPublisher::Bool(p), KeyValue::Bool(v)) => self.rt.block_on(p.publish(v)),
+            (KeyPublisher::F64(p), KeyValue::F64(v)) => self.rt.block_on(p.publish(v)),
+            (publisher, value) => {
+                return Err(PyTypeError::new_err(format!(
+                    "Key '{}' publishes {}, got {}",
+                    key,
+                    publisher.type_name(),
+                    value.type_name()
+                )));
+            }
+        };
+        published.map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Keys published so far, sorted
+    fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.publishers.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Drops every key's publisher and deregisters its path. Idempotent.
+    /// Raises `RuntimeError` listing the paths whose deregistration failed, after attempting all of them.
+    fn close(&mut self) -> PyResult<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+        let mut keys: Vec<String> = self.publishers.drain().map(|(key, _)| key).collect();
+        keys.sort();
+
+        let mut failures = Vec::new();
+        for key in keys {
+            let path = format!("{}/{}", self.prefix, key);
+            if let Err(e) = self.rt.block_on(self.metaclient.remove_publisher(&path)) {
+                failures.push(format!("{}: {}", path, e));
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(PyRuntimeError::new_err(format!(
+                "Failed to deregister {}",
+                failures.join("; ")
+            )))
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+}
+
+impl Drop for PyDictPublisher {
+    fn drop(&mut self) {
+        // Garbage-collected without close(): deregister rather than wait for the metaserver's liveness prune
+        if let Err(e) = self.close() {
+            tracing::warn!(error = %e, "PyDictPublisher: close on drop failed");
+        }
+    }
+}
@@ -49,7 +49,7 @@ macro_rules! create_typed_relay {
                     .rt
                     .block_on(
                         self.inner
-                            .swapon(src_path, src_metaserver_connection.to_connection_handle()),
+                            .swapon(src_path, src_metaserver_connection.to_connection_handle(), None),
                     )
                     .map_err(|e| PyRuntimeError::new_err(e.to_string()))
             }
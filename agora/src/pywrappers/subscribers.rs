@@ -1,16 +1,21 @@
-use super::async_helpers::PyResultIterator;
-use crate::create_py_result_iterator;
+use super::async_helpers::{PyResultAsyncIterator, PyResultIterator, SharedStream, next_as_future};
 use crate::pywrappers::connection_handle::PyConnectionHandle;
 use crate::utils::{BlockingStreamIterator, OrError, stream_to_iter};
 use crate::{OmniSubscriber, Subscriber};
+use crate::{create_py_async_iterator, create_py_result_iterator};
+use futures_util::stream::Stream;
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
-use tokio::runtime::Runtime;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::runtime::{Handle, Runtime};
+use tokio::task::AbortHandle;
 
 macro_rules! create_typed_subscriber {
-    ($name:ident, $iterator_name:ident, $type:ty) => {
-        // Create the corresponding PyResultIterator for this type
+    ($name:ident, $iterator_name:ident, $async_iterator_name:ident, $type:ty) => {
+        // Create the corresponding blocking and async iterators for this type
         create_py_result_iterator!($iterator_name, $type);
+        create_py_async_iterator!($async_iterator_name, $type);
 
         #[pyclass]
         pub struct $name {
@@ -57,16 +62,26 @@ macro_rules! create_typed_subscriber {
 
                 Ok($iterator_name::new(iterator))
             }
+
+            /// `async for value in subscriber`: connects synchronously, then awaits values without blocking the loop
+            fn __aiter__(&mut self) -> PyResult<$async_iterator_name> {
+                let (_current_value, stream) = self
+                    .rt
+                    .block_on(self.inner.get_stream())
+                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+                Ok($async_iterator_name::new(stream, self.rt.handle().clone()))
+            }
         }
     };
 }
 
 // Generate typed subscribers for all Agorable types
-create_typed_subscriber!(PyStringSubscriber, PyStringIterator, String);
-create_typed_subscriber!(PyI64Subscriber, PyI64Iterator, i64);
-create_typed_subscriber!(PyBoolSubscriber, PyBoolIterator, bool);
-create_typed_subscriber!(PyF64Subscriber, PyF64Iterator, f64);
-create_typed_subscriber!(PyF32Subscriber, PyF32Iterator, f32);
+create_typed_subscriber!(PyStringSubscriber, PyStringIterator, PyStringAsyncIterator, String);
+create_typed_subscriber!(PyI64Subscriber, PyI64Iterator, PyI64AsyncIterator, i64);
+create_typed_subscriber!(PyBoolSubscriber, PyBoolIterator, PyBoolAsyncIterator, bool);
+create_typed_subscriber!(PyF64Subscriber, PyF64Iterator, PyF64AsyncIterator, f64);
+create_typed_subscriber!(PyF32Subscriber, PyF32Iterator, PyF32AsyncIterator, f32);
 
 #[pyclass]
 pub struct PyOmniSubscriber {
@@ -113,4 +128,14 @@ impl PyOmniSubscriber {
 
         Ok(PyResultIterator::new(iterator))
     }
+
+    /// `async for value in subscriber`: connects synchronously, then awaits values without blocking the loop
+    fn __aiter__(&mut self) -> PyResult<PyResultAsyncIterator> {
+        let (_current_value, stream) = self
+            .rt
+            .block_on(self.inner.get_stream())
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+        Ok(PyResultAsyncIterator::new(stream, self.rt.handle().clone()))
+    }
 }
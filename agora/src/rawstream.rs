@@ -1,8 +1,24 @@
 //! Streaming WebSocket module for continuous publisher data transmission.
 //! Provides `RawStreamClient` (auto-reconnecting, broadcast fanout) and `RawStreamServer` (UDS, 1-to-N broadcasting) for real-time message streams.
+//! `MuxConnection` carries many `RawStreamClient` subscriptions over one gateway connection.
+//! Typed binary streams put a `FrameHeader` in front of every frame (see `RawStreamServer::with_handshake`).
 
 mod client;
+mod frame;
+pub(crate) mod mux;
 mod server;
 
 pub use client::RawStreamClient;
-pub use server::RawStreamServer;
+pub use frame::{FRAME_HEADER_LEN, FRAME_MAGIC, FrameHeader, ProtocolMismatch};
+pub use mux::MuxConnection;
+pub use server::{Heartbeat, OverflowPolicy, RawStreamServer};
+
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+
+/// WebSocket config capping both single frames and reassembled messages at `max_frame_bytes`.
+/// Shared by `RawStreamServer`, `RawStreamClient` and `Gateway` so every hop enforces the same limit.
+pub(crate) fn websocket_config(max_frame_bytes: usize) -> WebSocketConfig {
+    WebSocketConfig::default()
+        .max_frame_size(Some(max_frame_bytes))
+        .max_message_size(Some(max_frame_bytes))
+}
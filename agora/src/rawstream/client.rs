@@ -1,14 +1,22 @@
 //! Auto-reconnecting WebSocket client for streaming publisher data.
 //! `RawStreamClient<T>` connects via gateway, retries every 100ms on failure, broadcasts messages to multiple subscribers via `tokio::broadcast`.
+//! Acks the server's heartbeat pings (see `Heartbeat`) so it is not dropped as dead.
+//! `RawStreamClient::multiplexed` receives over a shared `MuxConnection` instead of its own WebSocket.
 
+use super::mux::MuxConnection;
+use super::websocket_config;
 use crate::ConnectionHandle;
+use crate::agora_error;
+use crate::constants::RAWSTREAM_MAX_FRAME_BYTES;
 use crate::utils::OrError;
-use chrono::Utc;
-use futures_util::StreamExt;
-use tokio::sync::broadcast;
+use futures_util::{SinkExt, StreamExt};
+use std::sync::Arc;
+use tokio::sync::{broadcast, watch};
 use tokio::task::JoinHandle;
 use tokio_stream::wrappers::BroadcastStream;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::connect_async_with_config;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tracing::warn;
 
 /// WebSocket client that connects to `RawStreamServer` with auto-reconnect.
 /// Retries connection every 100ms on failure, broadcasts messages to multiple subscribers via `tokio::broadcast`.
@@ -18,7 +26,21 @@ where
     <T as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
 {
     receiver: broadcast::Receiver<T>,
-    bg_handle: JoinHandle<()>,
+    handshake: watch::Receiver<Option<String>>,
+    bg_handle: Option<JoinHandle<()>>, // Own connection task; `None` when multiplexed
+    _mux_route: Option<MuxRoute>, // Held only to unsubscribe on drop
+}
+
+/// Subscription on a shared `MuxConnection`; unsubscribes when dropped
+struct MuxRoute {
+    connection: Arc<MuxConnection>,
+    id: u32,
+}
+
+impl Drop for MuxRoute {
+    fn drop(&mut self) {
+        self.connection.unregister(self.id);
+    }
 }
 
 impl<T> RawStreamClient<T>
@@ -27,22 +49,25 @@ where
     <T as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
 {
     fn log_error(message: &str) {
-        let timestamp = Utc::now().format("%H:%M:%S");
-        eprintln!("RawStreamClient error {}: {}", timestamp, message);
+        warn!("RawStreamClient: {}", message);
     }
     /// Creates WebSocket client with auto-reconnect to publisher's gateway.
     /// Network: Connects via gateway proxy: `ws://host:port/rawstream/{path}` → `/tmp/agora/{path}/rawstream.sock`
     /// Errors never propagate after creation - client retries connection every 100ms indefinitely.
+    /// `max_frame_bytes` caps each incoming message (default `RAWSTREAM_MAX_FRAME_BYTES`); it should be at least the server's.
     /// Called by: `Subscriber::new`, `OmniSubscriber::new`
     pub fn new(
         host_gateway: ConnectionHandle,
         socket_path: &str,
         poll_connection_every_ms: Option<u64>,
         buffer_size: Option<usize>,
+        max_frame_bytes: Option<usize>,
     ) -> OrError<Self> {
         let poll_interval = poll_connection_every_ms.unwrap_or(100);
         let buffer_capacity = buffer_size.unwrap_or(4096);
+        let max_frame_bytes = max_frame_bytes.unwrap_or(RAWSTREAM_MAX_FRAME_BYTES);
         let (tx, rx) = broadcast::channel::<T>(buffer_capacity);
+        let (handshake_tx, handshake_rx) = watch::channel::<Option<String>>(None);
 
         // Construct WebSocket URL that gateway will proxy to UDS
         let addr_string = format!(
@@ -55,10 +80,11 @@ where
         let bg_handle = tokio::spawn(async move {
             loop {
                 // Outer loop: Connection retry - runs forever
-                match connect_async(&addr_string).await {
+                let config = Some(websocket_config(max_frame_bytes));
+                match connect_async_with_config(&addr_string, config, false).await {
                     Ok((ws_stream, _)) => {
                         // Connected: enter message processing loop
-                        let (_, mut ws_receiver) = ws_stream.split();
+                        let (mut ws_sender, mut ws_receiver) = ws_stream.split();
                         loop {
                             // Inner loop: Message processing - runs until disconnect
                             match ws_receiver.next().await {
@@ -80,13 +106,32 @@ where
                                         }
                                     }
                                 }
+                                Some(Ok(Message::Text(text))) => {
+                                    // Server handshake (e.g. codec announcement), resent on every reconnect
+                                    handshake_tx.send_replace(Some(text.as_str().to_string()));
+                                }
+                                Some(Ok(Message::Ping(payload))) => {
+                                    // Server heartbeat: ack by echoing the payload as a binary frame
+                                    if ws_sender.send(Message::Binary(payload)).await.is_err() {
+                                        Self::log_error("failed to ack heartbeat");
+                                        break;
+                                    }
+                                }
                                 Some(Ok(msg)) => {
-                                    // Unexpected: non-binary message (e.g., text, ping, pong)
+                                    // Unexpected: non-binary message (e.g., pong)
                                     Self::log_error(&format!(
                                         "received non-binary message from WebSocket: {:?}",
                                         msg
                                     ));
                                 }
+                                Some(Err(WsError::Capacity(capacity_error))) => {
+                                    // Oversized frame: the connection is unusable → break to reconnect
+                                    Self::log_error(&format!(
+                                        "message exceeds max_frame_bytes ({}): {}",
+                                        max_frame_bytes, capacity_error
+                                    ));
+                                    break;
+                                }
                                 Some(Err(ws_error)) => {
                                     // WebSocket protocol error → break to reconnect
                                     Self::log_error(&format!(
@@ -121,10 +166,66 @@ where
         });
         Ok(Self {
             receiver: rx,
-            bg_handle,
+            handshake: handshake_rx,
+            bg_handle: Some(bg_handle),
+            _mux_route: None,
         })
     }
 
+    /// Like `new`, but subscribes to `socket_path` over `mux` instead of opening its own WebSocket.
+    /// Reconnects along with `mux`; the server handshake is delivered the same way.
+    /// Called by: `Subscriber::multiplexed`
+    pub fn multiplexed(
+        mux: Arc<MuxConnection>,
+        socket_path: &str,
+        buffer_size: Option<usize>,
+    ) -> Self {
+        let (tx, rx) = broadcast::channel::<T>(buffer_size.unwrap_or(4096));
+        let (handshake_tx, handshake_rx) = watch::channel::<Option<String>>(None);
+        let deliver = move |data: Vec<u8>| match T::try_from(data) {
+            // No receivers left only means nobody is listening right now
+            Ok(converted) => {
+                let _ = tx.send(converted);
+            }
+            Err(conversion_err) => {
+                Self::log_error(&format!("invalid message data: {}", conversion_err));
+            }
+        };
+        let id = mux.register(socket_path, Box::new(deliver), handshake_tx);
+        Self {
+            receiver: rx,
+            handshake: handshake_rx,
+            bg_handle: None,
+            _mux_route: Some(MuxRoute {
+                connection: mux,
+                id,
+            }),
+        }
+    }
+
+    /// Waits for the server's handshake text frame (see `RawStreamServer::with_handshake`).
+    /// Error: No handshake within `timeout` (server sends none, or is unreachable) → propagates to caller.
+    pub async fn handshake(&self, timeout: std::time::Duration) -> OrError<String> {
+        let mut handshake = self.handshake.clone();
+        let received = tokio::time::timeout(timeout, handshake.wait_for(|h| h.is_some()))
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(agora_error!(
+                    "rawstream::RawStreamClient",
+                    "handshake",
+                    &format!("no handshake received within {:?}", timeout)
+                ))
+            })?
+            .map_err(|_| {
+                anyhow::anyhow!(agora_error!(
+                    "rawstream::RawStreamClient",
+                    "handshake",
+                    "connection task exited"
+                ))
+            })?;
+        Ok(received.clone().unwrap_or_default())
+    }
+
     /// Creates independent stream for consuming messages.
     /// Multiple callers can call `subscribe()` to get separate streams of the same data.
     pub fn subscribe(&self) -> BroadcastStream<T> {
@@ -138,6 +239,8 @@ where
     <T as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
 {
     fn drop(&mut self) {
-        self.bg_handle.abort();
+        if let Some(bg_handle) = &self.bg_handle {
+            bg_handle.abort();
+        }
     }
 }
@@ -0,0 +1,100 @@
+//! Header in front of every binary frame of a typed rawstream: magic, codec id and payload schema version.
+//! The handshake is only checked on connect; the header lets a client also reject frames sent after a reconnect to an
+//! incompatible publisher (or by one predating headers) with a `ProtocolMismatch` instead of a decode error.
+
+use std::fmt;
+
+/// First bytes of every framed payload
+pub const FRAME_MAGIC: [u8; 4] = *b"AGRF";
+
+/// Magic, then the codec id (1 byte) and the schema version (4 bytes, big-endian)
+pub const FRAME_HEADER_LEN: usize = FRAME_MAGIC.len() + 1 + 4;
+
+/// Codec and schema version a frame was encoded with. The codec id is `WireCodec::id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameHeader {
+    pub codec_id: u8,
+    pub schema_version: u32,
+}
+
+impl FrameHeader {
+    pub fn new(codec_id: u8, schema_version: u32) -> Self {
+        Self {
+            codec_id,
+            schema_version,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; FRAME_HEADER_LEN] {
+        let mut bytes = [0u8; FRAME_HEADER_LEN];
+        bytes[..FRAME_MAGIC.len()].copy_from_slice(&FRAME_MAGIC);
+        bytes[FRAME_MAGIC.len()] = self.codec_id;
+        bytes[FRAME_MAGIC.len() + 1..].copy_from_slice(&self.schema_version.to_be_bytes());
+        bytes
+    }
+
+    /// `payload` with this header in front
+    pub fn frame(&self, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+        frame.extend_from_slice(&self.to_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    /// Splits `frame` into its header and payload; `None` if it does not start with one (e.g. an old-format frame)
+    pub fn parse(frame: &[u8]) -> Option<(Self, &[u8])> {
+        if frame.len() < FRAME_HEADER_LEN || frame[..FRAME_MAGIC.len()] != FRAME_MAGIC {
+            return None;
+        }
+        let codec_id = frame[FRAME_MAGIC.len()];
+        let version_bytes: [u8; 4] = frame[FRAME_MAGIC.len() + 1..FRAME_HEADER_LEN]
+            .try_into()
+            .ok()?;
+        Some((
+            Self::new(codec_id, u32::from_be_bytes(version_bytes)),
+            &frame[FRAME_HEADER_LEN..],
+        ))
+    }
+
+    /// Payload of `frame` if it carries exactly this header.
+    /// Error: `ProtocolMismatch` if the header is missing or differs.
+    pub fn strip<'a>(&self, frame: &'a [u8]) -> Result<&'a [u8], ProtocolMismatch> {
+        match Self::parse(frame) {
+            Some((found, payload)) if found == *self => Ok(payload),
+            found => Err(ProtocolMismatch {
+                expected: *self,
+                found: found.map(|(header, _)| header),
+            }),
+        }
+    }
+}
+
+/// Frame whose header does not match what the client negotiated; `found` is `None` if it has no header at all.
+/// Returned (wrapped in `anyhow::Error`, downcastable) for stream frames of `Subscriber::get_stream`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolMismatch {
+    pub expected: FrameHeader,
+    pub found: Option<FrameHeader>,
+}
+
+impl fmt::Display for ProtocolMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found {
+            None => write!(
+                f,
+                "frame has no agora header (expected codec id {}, schema version {}); the publisher likely predates frame headers",
+                self.expected.codec_id, self.expected.schema_version
+            ),
+            Some(found) => write!(
+                f,
+                "frame header has codec id {} and schema version {}, expected codec id {} and schema version {}",
+                found.codec_id,
+                found.schema_version,
+                self.expected.codec_id,
+                self.expected.schema_version
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolMismatch {}
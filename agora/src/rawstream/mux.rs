@@ -0,0 +1,257 @@
+//! Multiplexed rawstream: many logical subscriptions over one gateway WebSocket (`ws://gateway/mux`).
+//! The client sends `sub {id} {path}` / `unsub {id}` text frames; the gateway opens `/tmp/agora/{path}/rawstream.sock`
+//! per subscription and tags every frame it forwards with the subscription id: binary frames are prefixed with the id
+//! as 4 big-endian bytes, handshake text frames are sent as `{id} {handshake}`.
+
+use super::websocket_config;
+use crate::ConnectionHandle;
+use crate::constants::RAWSTREAM_MAX_FRAME_BYTES;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::connect_async_with_config;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tracing::warn;
+
+/// Bytes of subscription id in front of every multiplexed binary frame
+pub(crate) const MUX_HEADER_BYTES: usize = 4;
+
+/// Prefixes `payload` with its subscription id
+pub(crate) fn encode_frame(id: u32, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(MUX_HEADER_BYTES + payload.len());
+    frame.extend_from_slice(&id.to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Splits a multiplexed binary frame into its subscription id and payload; `None` if shorter than the header
+pub(crate) fn decode_frame(frame: &[u8]) -> Option<(u32, &[u8])> {
+    let (header, payload) = frame.split_at_checked(MUX_HEADER_BYTES)?;
+    Some((u32::from_be_bytes(header.try_into().ok()?), payload))
+}
+
+/// Control message from a mux client to the gateway
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MuxCommand {
+    /// Stream the rawstream at `path` (as in `/rawstream/{path}`), tagged with `id`
+    Subscribe {
+        id: u32,
+        path: String,
+    },
+    Unsubscribe {
+        id: u32,
+    },
+}
+
+impl MuxCommand {
+    pub(crate) fn to_text(&self) -> String {
+        match self {
+            MuxCommand::Subscribe { id, path } => format!("sub {} {}", id, path),
+            MuxCommand::Unsubscribe { id } => format!("unsub {}", id),
+        }
+    }
+
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.splitn(3, ' ');
+        match (parts.next()?, parts.next()?.parse().ok()?, parts.next()) {
+            ("sub", id, Some(path)) if !path.is_empty() => Some(MuxCommand::Subscribe {
+                id,
+                path: path.to_string(),
+            }),
+            ("unsub", id, None) => Some(MuxCommand::Unsubscribe { id }),
+            _ => None,
+        }
+    }
+}
+
+/// Receiving end of one logical subscription on a `MuxConnection`
+struct Route {
+    path: String,
+    deliver: Box<dyn Fn(Vec<u8>) + Send + Sync>,
+    handshake: watch::Sender<Option<String>>,
+}
+
+type Routes = Arc<Mutex<HashMap<u32, Route>>>;
+
+/// One WebSocket to a gateway's `/mux` endpoint carrying any number of rawstream subscriptions.
+/// Reconnects every 100ms on failure and resubscribes every open route, like `RawStreamClient`.
+/// Get one per gateway with `shared`; `RawStreamClient::multiplexed` registers a subscription on it.
+pub struct MuxConnection {
+    routes: Routes,
+    commands: mpsc::UnboundedSender<MuxCommand>,
+    next_id: AtomicU32,
+    bg_handle: JoinHandle<()>,
+}
+
+impl MuxConnection {
+    /// Opens a dedicated mux connection to `host_gateway`.
+    /// `max_frame_bytes` caps each payload (default `RAWSTREAM_MAX_FRAME_BYTES`); it should be at least the gateway's.
+    pub fn connect(host_gateway: ConnectionHandle, max_frame_bytes: Option<usize>) -> Arc<Self> {
+        let max_frame_bytes = max_frame_bytes.unwrap_or(RAWSTREAM_MAX_FRAME_BYTES);
+        let routes: Routes = Arc::new(Mutex::new(HashMap::new()));
+        let (commands, command_rx) = mpsc::unbounded_channel();
+        let addr_string = format!("ws://{}/mux", host_gateway);
+        let bg_handle = tokio::spawn(run_connection(
+            addr_string,
+            max_frame_bytes,
+            routes.clone(),
+            command_rx,
+        ));
+        Arc::new(Self {
+            routes,
+            commands,
+            next_id: AtomicU32::new(0),
+            bg_handle,
+        })
+    }
+
+    /// Process-wide mux connection to `host_gateway`, opened on first use and closed once no one holds it.
+    /// Called by: `Subscriber::multiplexed`
+    pub fn shared(host_gateway: ConnectionHandle) -> Arc<Self> {
+        static SHARED: OnceLock<Mutex<HashMap<String, Weak<MuxConnection>>>> = OnceLock::new();
+        let mut shared = SHARED.get_or_init(Default::default).lock().unwrap();
+        shared.retain(|_, connection| connection.strong_count() > 0);
+        let key = host_gateway.to_string();
+        if let Some(connection) = shared.get(&key).and_then(Weak::upgrade) {
+            return connection;
+        }
+        let connection = Self::connect(host_gateway, None);
+        shared.insert(key, Arc::downgrade(&connection));
+        connection
+    }
+
+    /// Logical subscriptions currently open on this connection
+    pub fn subscription_count(&self) -> usize {
+        self.routes.lock().unwrap().len()
+    }
+
+    /// Routes frames of the rawstream at `path` to `deliver` and its handshake to `handshake`; returns the subscription id.
+    pub(crate) fn register(
+        &self,
+        path: &str,
+        deliver: Box<dyn Fn(Vec<u8>) + Send + Sync>,
+        handshake: watch::Sender<Option<String>>,
+    ) -> u32 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let route = Route {
+            path: path.to_string(),
+            deliver,
+            handshake,
+        };
+        self.routes.lock().unwrap().insert(id, route);
+        let _ = self.commands.send(MuxCommand::Subscribe {
+            id,
+            path: path.to_string(),
+        });
+        id
+    }
+
+    pub(crate) fn unregister(&self, id: u32) {
+        if self.routes.lock().unwrap().remove(&id).is_some() {
+            let _ = self.commands.send(MuxCommand::Unsubscribe { id });
+        }
+    }
+}
+
+impl Drop for MuxConnection {
+    fn drop(&mut self) {
+        self.bg_handle.abort();
+    }
+}
+
+/// Connection task: (re)connects, subscribes every open route, then sends commands and dispatches frames until disconnect.
+/// Commands queued while disconnected may repeat the resubscription; the gateway ignores known ids and unknown unsubscribes.
+async fn run_connection(
+    addr_string: String,
+    max_frame_bytes: usize,
+    routes: Routes,
+    mut commands: mpsc::UnboundedReceiver<MuxCommand>,
+) {
+    let poll_interval = tokio::time::Duration::from_millis(100);
+    loop {
+        let config = Some(websocket_config(max_frame_bytes + MUX_HEADER_BYTES));
+        match connect_async_with_config(&addr_string, config, false).await {
+            Ok((ws_stream, _)) => {
+                let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+                let open: Vec<MuxCommand> = routes
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(&id, route)| MuxCommand::Subscribe {
+                        id,
+                        path: route.path.clone(),
+                    })
+                    .collect();
+                let mut connected = true;
+                for command in open {
+                    if ws_sender
+                        .send(Message::Text(command.to_text().into()))
+                        .await
+                        .is_err()
+                    {
+                        connected = false;
+                        break;
+                    }
+                }
+                while connected {
+                    tokio::select! {
+                        command = commands.recv() => match command {
+                            Some(command) => {
+                                connected = ws_sender
+                                    .send(Message::Text(command.to_text().into()))
+                                    .await
+                                    .is_ok();
+                            }
+                            None => return, // `MuxConnection` dropped
+                        },
+                        incoming = ws_receiver.next() => {
+                            connected = dispatch(&routes, incoming, &addr_string);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(url = %addr_string, error = %e, "mux connection failed");
+            }
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Hands an incoming frame to its subscription's route. Returns false once the connection is closed or broken.
+fn dispatch(routes: &Routes, incoming: Option<Result<Message, WsError>>, url: &str) -> bool {
+    match incoming {
+        Some(Ok(Message::Binary(frame))) => match decode_frame(&frame) {
+            Some((id, payload)) => {
+                if let Some(route) = routes.lock().unwrap().get(&id) {
+                    (route.deliver)(payload.to_vec());
+                }
+            }
+            None => warn!(url, "mux frame shorter than its header"),
+        },
+        Some(Ok(Message::Text(text))) => {
+            let routed = text
+                .as_str()
+                .split_once(' ')
+                .and_then(|(id, handshake)| Some((id.parse::<u32>().ok()?, handshake)));
+            match routed {
+                Some((id, handshake)) => {
+                    if let Some(route) = routes.lock().unwrap().get(&id) {
+                        route.handshake.send_replace(Some(handshake.to_string()));
+                    }
+                }
+                None => warn!(url, "malformed mux handshake frame"),
+            }
+        }
+        Some(Ok(_)) => {}
+        Some(Err(e)) => {
+            warn!(url, error = %e, "mux connection error");
+            return false;
+        }
+        None => return false,
+    }
+    true
+}
@@ -1,26 +1,180 @@
 //! UDS WebSocket server for broadcasting messages to N clients.
-//! `RawStreamServer<T>` uses dual-task architecture: ingestion (receives from `publish()`) + connection handler (fans out to clients via `tokio::broadcast`).
+//! `RawStreamServer<T>` uses dual-task architecture: ingestion (receives from `publish()`) + connection handler (fans out to clients via bounded per-client queues).
+//! Each client is pinged on a `Heartbeat` and dropped if it stops acking, so half-open connections do not linger.
 
+use super::frame::{FRAME_HEADER_LEN, FrameHeader};
+use super::websocket_config;
+use crate::constants::{
+    RAWSTREAM_HEARTBEAT_INTERVAL_MS, RAWSTREAM_HEARTBEAT_TIMEOUT_MS, RAWSTREAM_MAX_FRAME_BYTES,
+};
 use crate::utils::{OrError, prepare_socket_path};
 use crate::agora_error;
-use anyhow::Context;
-use futures_util::{SinkExt, StreamExt};
+use anyhow::{Context, bail};
+use futures_util::{Sink, SinkExt, StreamExt};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::net::UnixListener;
-use tokio::sync::broadcast;
+use tokio::sync::Notify;
 use tokio::task::JoinHandle;
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_tungstenite::{accept_async_with_config, tungstenite::Message};
+use tracing::warn;
 
-/// UDS WebSocket server that broadcasts messages to N clients via `tokio::broadcast`.
+/// What a client's send queue does when it is full (i.e. the client reads slower than the publisher writes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Wait for space. A stalled client stalls ingestion for every client on this server.
+    Block,
+    /// Evict the oldest queued message. Keeps the freshest data; default for market data.
+    #[default]
+    DropOldest,
+    /// Discard the incoming message, keeping the queued backlog intact.
+    DropNewest,
+}
+
+/// Application-level liveness check of each client. Every `interval` the server sends a WebSocket ping;
+/// the client acks by echoing its payload as a binary frame (`RawStreamClient` does this, and the gateway does it
+/// for multiplexed subscriptions). A client with no ack for `timeout`, or whose send stalls for `timeout`, is dropped.
+/// Pongs do not count: the gateway answers pings itself, so only the binary echo proves the far end is alive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(RAWSTREAM_HEARTBEAT_INTERVAL_MS),
+            timeout: Duration::from_millis(RAWSTREAM_HEARTBEAT_TIMEOUT_MS),
+        }
+    }
+}
+
+/// Sends `msg`, giving up after `timeout` (a peer that stopped reading). Returns false unless it was sent.
+async fn send_within<S>(sender: &mut S, msg: Message, timeout: Duration) -> bool
+where
+    S: Sink<Message> + Unpin,
+{
+    matches!(
+        tokio::time::timeout(timeout, sender.send(msg)).await,
+        Ok(Ok(()))
+    )
+}
+
+/// Bounded FIFO between the ingestion task and one client's send task.
+struct ClientQueue<T> {
+    buffer: Mutex<VecDeque<T>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    data_ready: Notify,
+    space_ready: Notify,
+    closed: AtomicBool,
+}
+
+impl<T> ClientQueue<T> {
+    fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            buffer: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            policy,
+            data_ready: Notify::new(),
+            space_ready: Notify::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Enqueues `value` according to the overflow policy. Only awaits under `OverflowPolicy::Block`.
+    async fn push(&self, value: T) {
+        let mut value = Some(value);
+        loop {
+            if self.is_closed() {
+                return;
+            }
+            {
+                let mut buffer = self.buffer.lock().unwrap();
+                if buffer.len() < self.capacity {
+                    buffer.extend(value.take());
+                    drop(buffer);
+                    self.data_ready.notify_one();
+                    return;
+                }
+                match self.policy {
+                    OverflowPolicy::DropOldest => {
+                        buffer.pop_front();
+                        buffer.extend(value.take());
+                        return;
+                    }
+                    OverflowPolicy::DropNewest => return,
+                    OverflowPolicy::Block => {}
+                }
+            }
+            self.space_ready.notified().await;
+        }
+    }
+
+    /// Waits for the next queued message.
+    async fn pop(&self) -> T {
+        loop {
+            let next = self.buffer.lock().unwrap().pop_front();
+            if let Some(value) = next {
+                self.space_ready.notify_one();
+                return value;
+            }
+            self.data_ready.notified().await;
+        }
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        // Wake ingestion if it is blocked on this client
+        self.space_ready.notify_one();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Queued messages as a fraction of capacity
+    fn fill_ratio(&self) -> f64 {
+        self.buffer.lock().unwrap().len() as f64 / self.capacity as f64
+    }
+}
+
+/// Work for the ingestion task, in publish order
+enum Ingest<T> {
+    /// A published value, for every admitted client
+    Data(T),
+    /// Held-back clients joining the broadcast, each sent `snapshot` before the next `Data`
+    Admit {
+        clients: Vec<Arc<ClientQueue<T>>>,
+        snapshot: T,
+    },
+}
+
+/// Clients held back by `hold_new_clients`; `None` while new clients join the broadcast immediately
+type PendingClients<T> = Arc<Mutex<Option<Vec<Arc<ClientQueue<T>>>>>>;
+
+/// UDS WebSocket server that broadcasts messages to N clients via bounded per-client queues.
 /// Two tasks: ingestion (receives from `publish()`) and connection handler (fans out to clients).
+/// Tracks how many clients are connected (see `subscriber_count`); clients that stop acking heartbeats are dropped.
 pub struct RawStreamServer<T>
 where
     T: Clone + Send + 'static + Into<Vec<u8>> + TryFrom<Vec<u8>>,
     <T as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
 {
-    sender: tokio::sync::mpsc::UnboundedSender<T>,
+    sender: tokio::sync::mpsc::UnboundedSender<Ingest<T>>,
     ingest_handle: JoinHandle<()>,
     connection_handle: JoinHandle<()>,
+    clients: Arc<Mutex<Vec<Arc<ClientQueue<T>>>>>,
+    /// Clients waiting for `admit_pending`; `None` unless `hold_new_clients` was called
+    pending: PendingClients<T>,
+    subscribers: Arc<AtomicUsize>,
     socket_path: String,
+    max_frame_bytes: usize,
+    frame_header: Option<FrameHeader>,
 }
 
 impl<T> RawStreamServer<T>
@@ -29,11 +183,45 @@ where
     <T as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
 {
     /// Creates UDS WebSocket server with broadcast to multiple clients.
-    /// Architecture: Two async tasks share broadcast channel for 1-to-N fanout.
+    /// Architecture: Two async tasks share the client queue list for 1-to-N fanout.
+    /// `buffer_size` bounds each client's queue (default 4096); `overflow_policy` decides what happens
+    /// when a slow client fills it (default `DropOldest`, so one stalled client never holds up the rest).
+    /// `max_frame_bytes` caps each published payload (default `RAWSTREAM_MAX_FRAME_BYTES`); larger publishes are rejected.
+    /// `heartbeat` sets how often clients are pinged and how long they may go without acking (default `Heartbeat::default()`).
     /// Error: Socket bind fails → propagates to `Publisher::new`.
     /// Called by: `Publisher::new`
-    pub async fn new(socket_path: &str, buffer_size: Option<usize>) -> OrError<Self> {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    pub async fn new(
+        socket_path: &str,
+        buffer_size: Option<usize>,
+        overflow_policy: Option<OverflowPolicy>,
+        max_frame_bytes: Option<usize>,
+        heartbeat: Option<Heartbeat>,
+    ) -> OrError<Self> {
+        Self::with_handshake(
+            socket_path,
+            buffer_size,
+            overflow_policy,
+            None,
+            None,
+            max_frame_bytes,
+            heartbeat,
+        )
+        .await
+    }
+
+    /// Like `new`, but sends `handshake` as a text frame to every client before any data,
+    /// and puts `frame_header` in front of every binary frame (see `FrameHeader::strip` for the client side).
+    /// Used by `Publisher` to announce its wire codec and schema version on the binary endpoint.
+    pub async fn with_handshake(
+        socket_path: &str,
+        buffer_size: Option<usize>,
+        overflow_policy: Option<OverflowPolicy>,
+        handshake: Option<String>,
+        frame_header: Option<FrameHeader>,
+        max_frame_bytes: Option<usize>,
+        heartbeat: Option<Heartbeat>,
+    ) -> OrError<Self> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Ingest<T>>();
 
         // Create parent directory and clean up existing socket
         prepare_socket_path(socket_path)?;
@@ -44,39 +232,111 @@ where
             "new",
             &format!("failed to bind Unix socket at {}", socket_path)
         ))?;
-        // Create broadcast channel to fan out to all connected clients
-        let buffer_capacity = buffer_size.unwrap_or(4096);
-        let (broadcast_tx, _) = broadcast::channel::<T>(buffer_capacity);
+        // Per-client bounded queues to fan out to all connected clients
+        let buffer_capacity = buffer_size.unwrap_or(4096).max(1);
+        let overflow_policy = overflow_policy.unwrap_or_default();
+        let max_frame_bytes = max_frame_bytes.unwrap_or(RAWSTREAM_MAX_FRAME_BYTES);
+        let heartbeat = heartbeat.unwrap_or_default();
+        let clients: Arc<Mutex<Vec<Arc<ClientQueue<T>>>>> = Arc::new(Mutex::new(Vec::new()));
+        let pending: PendingClients<T> = Arc::new(Mutex::new(None));
+        let subscribers = Arc::new(AtomicUsize::new(0));
+        let connection_subscribers = subscribers.clone();
         let mut input_stream = tokio_stream::wrappers::UnboundedReceiverStream::new(rx);
 
-        // Task 1: Ingestion - receives data from publish() → broadcasts to all clients
-        let ingest_tx = broadcast_tx.clone();
+        // Task 1: Ingestion - receives data from publish() → enqueues for all clients
+        let ingest_clients = clients.clone();
         let ingest_handle = tokio::spawn(async move {
-            while let Some(data) = input_stream.next().await {
-                let _ = ingest_tx.send(data); // Ignore error if no clients listening
+            while let Some(ingest) = input_stream.next().await {
+                match ingest {
+                    Ingest::Data(data) => {
+                        let targets: Vec<Arc<ClientQueue<T>>> = {
+                            let mut clients = ingest_clients.lock().unwrap();
+                            clients.retain(|client| !client.is_closed());
+                            clients.clone()
+                        };
+                        for client in targets {
+                            client.push(data.clone()).await;
+                        }
+                    }
+                    Ingest::Admit { clients, snapshot } => {
+                        for client in &clients {
+                            client.push(snapshot.clone()).await;
+                        }
+                        ingest_clients.lock().unwrap().extend(clients);
+                    }
+                }
             }
         });
 
         // Task 2: Connection handling - accepts new clients and spawns per-client tasks
+        let connection_clients = clients.clone();
+        let connection_pending = pending.clone();
+        let connection_socket_path = socket_path.to_string();
         let connection_handle = tokio::spawn(async move {
             loop {
                 if let Ok((unix_stream, _)) = listener.accept().await {
-                    let mut client_rx = broadcast_tx.subscribe();
+                    let client = Arc::new(ClientQueue::new(buffer_capacity, overflow_policy));
+                    match connection_pending.lock().unwrap().as_mut() {
+                        Some(pending) => pending.push(client.clone()),
+                        None => connection_clients.lock().unwrap().push(client.clone()),
+                    }
+                    let handshake = handshake.clone();
+                    let subscribers = connection_subscribers.clone();
+                    let socket_path = connection_socket_path.clone();
                     tokio::spawn(async move {
-                        if let Ok(ws_stream) = accept_async(unix_stream).await {
-                            let (mut ws_sender, _) = ws_stream.split();
-                            // Forward broadcast messages to this specific client
-                            while let Ok(data) = client_rx.recv().await {
-                                if ws_sender
-                                    .send(Message::Binary(data.into().into()))
-                                    .await
-                                    .is_err()
-                                {
-                                    // Client disconnected - this task exits, others unaffected
-                                    break;
+                        let config = Some(websocket_config(max_frame_bytes));
+                        if let Ok(ws_stream) = accept_async_with_config(unix_stream, config).await {
+                            let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+                            if let Some(handshake) = handshake
+                                && ws_sender.send(Message::Text(handshake.into())).await.is_err()
+                            {
+                                client.close();
+                                return;
+                            }
+                            subscribers.fetch_add(1, Ordering::AcqRel);
+                            let mut heartbeat_tick = tokio::time::interval(heartbeat.interval);
+                            heartbeat_tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                            heartbeat_tick.reset(); // First ping one interval after connecting
+                            let mut last_ack = Instant::now();
+                            // Forward queued messages to this specific client. Clients only send heartbeat acks,
+                            // so watching the read half detects a disconnect without waiting for the next publish.
+                            loop {
+                                tokio::select! {
+                                    data = client.pop() => {
+                                        let payload: Vec<u8> = data.into();
+                                        let payload = match &frame_header {
+                                            Some(header) => header.frame(&payload),
+                                            None => payload,
+                                        };
+                                        let msg = Message::Binary(payload.into());
+                                        if !send_within(&mut ws_sender, msg, heartbeat.timeout).await {
+                                            // Client disconnected or stalled - this task exits, others unaffected
+                                            break;
+                                        }
+                                    }
+                                    _ = heartbeat_tick.tick() => {
+                                        if last_ack.elapsed() > heartbeat.timeout {
+                                            warn!(
+                                                socket_path = %socket_path,
+                                                "rawstream client missed heartbeats, dropping it"
+                                            );
+                                            break;
+                                        }
+                                        let ping = Message::Ping(Vec::new().into());
+                                        if !send_within(&mut ws_sender, ping, heartbeat.timeout).await {
+                                            break;
+                                        }
+                                    }
+                                    incoming = ws_receiver.next() => match incoming {
+                                        None | Some(Err(_)) | Some(Ok(Message::Close(_))) => break,
+                                        Some(Ok(Message::Binary(_))) => last_ack = Instant::now(),
+                                        Some(Ok(_)) => {}
+                                    }
                                 }
                             }
+                            subscribers.fetch_sub(1, Ordering::AcqRel);
                         }
+                        client.close();
                     });
                 }
             }
@@ -86,15 +346,116 @@ where
             sender: tx,
             ingest_handle,
             connection_handle,
+            clients,
+            pending,
+            subscribers,
             socket_path: socket_path.to_string(),
+            max_frame_bytes,
+            frame_header,
         })
     }
 
-    pub fn publish(&self, value: T) -> OrError<()> {
+    /// Clients currently connected (past the handshake). Drops as soon as a client closes its connection.
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.load(Ordering::Acquire)
+    }
+
+    /// Shared handle to the live count, for reporting it from elsewhere (e.g. `PingServer::track_subscribers`)
+    pub fn subscriber_counter(&self) -> Arc<AtomicUsize> {
+        self.subscribers.clone()
+    }
+
+    /// Fill level of the fullest client send queue, from 0.0 (no backlog or no clients) to 1.0 (full).
+    /// A full queue under a drop policy stays near 1.0 until its client catches up.
+    pub fn congestion_ratio(&self) -> f64 {
+        self.clients
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|client| !client.is_closed())
+            .map(|client| client.fill_ratio())
+            .fold(0.0, f64::max)
+    }
+
+    /// Largest frame `publish` accepts, in bytes, including the `FrameHeader` if any
+    pub fn max_frame_bytes(&self) -> usize {
+        self.max_frame_bytes
+    }
+
+    /// Header sent in front of every binary frame; `None` unless set by `with_handshake`
+    pub fn frame_header(&self) -> Option<FrameHeader> {
+        self.frame_header
+    }
+
+    /// Rejects a payload of `len` bytes that clients would refuse as an oversized frame.
+    /// Called by: `publish`, `Publisher::publish` (to validate before touching any state)
+    pub(crate) fn check_frame_size(&self, len: usize) -> OrError<()> {
+        let header_len = self.frame_header.map_or(0, |_| FRAME_HEADER_LEN);
+        if len + header_len > self.max_frame_bytes {
+            bail!(agora_error!(
+                "rawstream::RawStreamServer",
+                "publish",
+                &format!(
+                    "payload of {} bytes (plus {} header bytes) exceeds max_frame_bytes ({})",
+                    len, header_len, self.max_frame_bytes
+                )
+            ));
+        }
+        Ok(())
+    }
+
+    /// Queues `value` for every connected client.
+    /// Error: Payload larger than `max_frame_bytes`, or server tasks gone → nothing is sent.
+    pub fn publish(&self, value: T) -> OrError<()>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.check_frame_size(value.as_ref().len())?;
         self.sender
-            .send(value)
+            .send(Ingest::Data(value))
             .map_err(|_| anyhow::anyhow!(agora_error!("rawstream::RawStreamServer", "publish", "channel closed")))
     }
+
+    /// From now on, clients that connect get nothing until `admit_pending` lets them in.
+    /// Called by: `Publisher::with_snapshot_provider`
+    pub(crate) fn hold_new_clients(&self) {
+        self.pending.lock().unwrap().get_or_insert_with(Vec::new);
+    }
+
+    /// Whether some connected client is waiting for `admit_pending`
+    pub(crate) fn has_pending(&self) -> bool {
+        self.pending
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|pending| pending.iter().any(|client| !client.is_closed()))
+    }
+
+    /// Lets the held-back clients in: each is sent `snapshot`, then every value published after this call and none
+    /// published before it.
+    /// Error: Snapshot larger than `max_frame_bytes` (clients stay held back), or server tasks gone
+    pub(crate) fn admit_pending(&self, snapshot: T) -> OrError<()>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.check_frame_size(snapshot.as_ref().len())?;
+        let clients: Vec<Arc<ClientQueue<T>>> = match self.pending.lock().unwrap().as_mut() {
+            Some(pending) => std::mem::take(pending)
+                .into_iter()
+                .filter(|client| !client.is_closed())
+                .collect(),
+            None => return Ok(()),
+        };
+        self.sender
+            .send(Ingest::Admit { clients, snapshot })
+            .map_err(|_| {
+                anyhow::anyhow!(agora_error!(
+                    "rawstream::RawStreamServer",
+                    "admit_pending",
+                    "channel closed"
+                ))
+            })
+    }
 }
 
 impl<T> Drop for RawStreamServer<T>
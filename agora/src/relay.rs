@@ -1,30 +1,137 @@
 //! Dynamic message relay with switchable source and fixed destination.
 //! `Relay<T>` enables contiguous streaming from discontinuous sources via `swapon()`, useful for cross-metaserver bridging and failover scenarios.
 
+use crate::constants::{
+    RELAY_LATENCY_EWMA_WEIGHT, RELAY_NEW_RETRIES, RELAY_NEW_RETRY_BACKOFF_MS,
+    SWAPON_POLL_INTERVAL_MS, SWAPON_RETRY_TIMEOUT_MS,
+};
+use crate::metaserver::AgoraClient;
 use crate::utils::{ConnectionHandle, OrError};
 use crate::{Agorable, Publisher, Subscriber};
-use crate::agora_error;
+use crate::agora_error_cause;
 use futures_util::StreamExt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::{error, warn};
+
+/// Wait policy for `Relay::swapon` when the source may not be registered yet (e.g. a freshly spawned worker).
+/// `swapon` polls the metaserver every `poll_interval` until the source is confirmed or `timeout` elapses.
+#[derive(Debug, Clone, Copy)]
+pub struct SwaponRetry {
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+impl Default for SwaponRetry {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(SWAPON_RETRY_TIMEOUT_MS),
+            poll_interval: Duration::from_millis(SWAPON_POLL_INTERVAL_MS),
+        }
+    }
+}
+
+/// Retry policy for `Relay::new` when creating the destination publisher fails (e.g. a metaserver or gateway hiccup).
+/// A failed attempt rolls its registration back (see `Publisher::new`), so the next attempt starts from a clean path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayRetry {
+    /// Retries after the first attempt fails
+    pub retries: u32,
+    /// Delay before the first retry; doubles on each further retry
+    pub backoff: Duration,
+}
+
+impl Default for RelayRetry {
+    fn default() -> Self {
+        Self {
+            retries: RELAY_NEW_RETRIES,
+            backoff: Duration::from_millis(RELAY_NEW_RETRY_BACKOFF_MS),
+        }
+    }
+}
+
+/// What a paused relay does with source values (see `Relay::pause`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PauseBehavior {
+    /// Discard values received while paused
+    #[default]
+    Drop,
+    /// Keep only the latest value received while paused; `resume` publishes it before anything newer
+    HoldLast,
+}
+
+/// Pause state shared between `Relay` and its `stream_out` task
+struct PauseGate<T> {
+    paused: AtomicBool,
+    behavior: Mutex<PauseBehavior>,
+    held: Mutex<Option<T>>,
+    resumed: Notify,
+}
+
+/// Snapshot of a relay's forwarding health (see `Relay::stats`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RelayStats {
+    /// Source values published to the destination
+    pub forwarded: u64,
+    /// Moving average of the time from receiving a source value to completing its destination publish;
+    /// `None` until the first value is forwarded. Rising latency means the relay is falling behind its source.
+    pub latency: Option<Duration>,
+    /// Successful `swapon` calls (sources swapped in, e.g. on worker version bumps)
+    pub swapons: u64,
+}
+
+/// Counters shared between `Relay` and its tasks
+#[derive(Default)]
+struct RelayMetrics {
+    forwarded: AtomicU64,
+    swapons: AtomicU64,
+    latency: Mutex<Option<Duration>>,
+}
+
+impl RelayMetrics {
+    /// Records one forwarded value received at `received`
+    fn record_forward(&self, received: Instant) {
+        let sample = received.elapsed();
+        let mut latency = self.latency.lock().unwrap();
+        *latency = Some(match *latency {
+            Some(average) => {
+                average.mul_f64(1.0 - RELAY_LATENCY_EWMA_WEIGHT)
+                    + sample.mul_f64(RELAY_LATENCY_EWMA_WEIGHT)
+            }
+            None => sample,
+        });
+        self.forwarded.fetch_add(1, Ordering::Relaxed);
+    }
+}
 
 /// Dynamic message router with fixed destination and switchable source.
 /// Architecture: Two async tasks (`stream_in`, `stream_out`) communicate via unbounded channel.
 /// Use case: Contiguous streaming from discontinuous sources, cross-metaserver bridging.
 /// Call `swapon()` to atomically switch source without dropping destination subscribers.
+/// `pause()`/`resume()` gate forwarding while keeping both the source subscription and the destination registered.
+/// `stats()` reports forwarded count, forwarding latency and swapons.
 pub struct Relay<T: Agorable> {
     stream_out: tokio::task::JoinHandle<()>,
     stream_in: Option<tokio::task::JoinHandle<()>>,
     src_subscriber: Option<Subscriber<T>>, // We need to keep ownership of this to prevent drop
-    tx: tokio::sync::mpsc::UnboundedSender<T>,
+    tx: tokio::sync::mpsc::UnboundedSender<(T, Instant)>, // Stamped with source receive time
+    gate: Arc<PauseGate<T>>,
+    metrics: Arc<RelayMetrics>,
     dest_path: String,
 }
 
-impl<T: Agorable> Relay<T> {
+impl<T: Agorable + Clone> Relay<T> {
     /// Creates relay with fixed destination publisher and no source yet.
     /// Architecture: Two async tasks communicate via unbounded channel:
     /// - `stream_in`: Subscribes to source → sends values to channel
     /// - `stream_out`: Receives from channel → publishes to destination
-    /// Error: `Publisher` creation fails → propagates to user code.
-    /// Called by: User code
+    ///
+    /// Retries a failed destination publisher with `RelayRetry::default()`.
+    /// Error: `Publisher` creation still fails after all retries → propagates to user code (last error).
+    /// Called by: User code, `TypedRelaySet` (argus)
     pub async fn new(
         name: String,
         dest_path: String,
@@ -32,49 +139,163 @@ impl<T: Agorable> Relay<T> {
         dest_metaserver_connection: ConnectionHandle,
         local_gateway_port: u16,
     ) -> OrError<Self> {
-        // Create destination publisher
-        let mut publisher = Publisher::new(
+        Self::with_retry(
             name,
-            dest_path.clone(),
+            dest_path,
             initial_value,
             dest_metaserver_connection,
             local_gateway_port,
+            RelayRetry::default(),
         )
-        .await?;
+        .await
+    }
 
+    /// Like `new`, retrying the destination publisher according to `retry`
+    pub async fn with_retry(
+        name: String,
+        dest_path: String,
+        initial_value: T,
+        dest_metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+        retry: RelayRetry,
+    ) -> OrError<Self> {
+        let mut attempt: u32 = 0;
+        let publisher = loop {
+            match Publisher::new(
+                name.clone(),
+                dest_path.clone(),
+                initial_value.clone(),
+                dest_metaserver_connection,
+                local_gateway_port,
+            )
+            .await
+            {
+                Ok(publisher) => break publisher,
+                Err(e) if attempt >= retry.retries => return Err(e),
+                Err(e) => {
+                    let delay = retry.backoff.saturating_mul(2u32.saturating_pow(attempt));
+                    attempt += 1;
+                    warn!(
+                        dest = %dest_path,
+                        error = %e,
+                        ?delay,
+                        attempt,
+                        "relay destination publisher failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        };
+        Ok(Self::start(publisher, dest_path))
+    }
+}
+
+impl<T: Agorable> Relay<T> {
+    /// Spawns `stream_out` on the destination publisher
+    fn start(mut publisher: Publisher<T>, dest_path: String) -> Self {
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
         let dest_path_ = dest_path.clone();
+        let gate = Arc::new(PauseGate {
+            paused: AtomicBool::new(false),
+            behavior: Mutex::new(PauseBehavior::default()),
+            held: Mutex::new(None),
+            resumed: Notify::new(),
+        });
+        let gate_ = gate.clone();
+        let metrics = Arc::new(RelayMetrics::default());
+        let metrics_ = metrics.clone();
 
-        // Task: stream_out - receives from channel, publishes to destination
+        // Task: stream_out - receives from channel, publishes to destination unless paused
         let stream_out = tokio::task::spawn(async move {
-            while let Some(t) = rx.recv().await {
-                if let Err(e) = publisher.publish(t).await {
-                    eprintln!("{}. Caused by -> {}",
-                        agora_error!("relay::Relay", "stream_out",
-                            &format!("could not publish to {}", dest_path_)),
-                        e);
+            loop {
+                let (t, received_at) = tokio::select! {
+                    // A value held over a pause goes out before anything queued after it
+                    biased;
+                    // Its latency counts from the resume, not from the time it spent held
+                    _ = gate_.resumed.notified() => match gate_.held.lock().unwrap().take() {
+                        Some(t) => (t, Instant::now()),
+                        None => continue,
+                    },
+                    received = rx.recv() => match received {
+                        Some(received) => received,
+                        None => break,
+                    },
+                };
+                if gate_.paused.load(Ordering::Acquire) {
+                    if *gate_.behavior.lock().unwrap() == PauseBehavior::HoldLast {
+                        *gate_.held.lock().unwrap() = Some(t);
+                    }
+                    continue;
+                }
+                match publisher.publish(t).await {
+                    Ok(()) => metrics_.record_forward(received_at),
+                    Err(e) => warn!(dest = %dest_path_, error = %e, "relay could not publish"),
                 }
             }
         });
 
-        Ok(Self {
+        Self {
             stream_out,
             stream_in: None,
             src_subscriber: None,
             tx,
+            gate,
+            metrics,
             dest_path,
-        })
+        }
+    }
+
+    /// Forwarded count, forwarding latency and swapon count so far
+    pub fn stats(&self) -> RelayStats {
+        RelayStats {
+            forwarded: self.metrics.forwarded.load(Ordering::Relaxed),
+            latency: *self.metrics.latency.lock().unwrap(),
+            swapons: self.metrics.swapons.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Sets what `pause` does with source values (default `PauseBehavior::Drop`)
+    pub fn with_pause_behavior(self, behavior: PauseBehavior) -> Self {
+        *self.gate.behavior.lock().unwrap() = behavior;
+        self
+    }
+
+    pub fn pause_behavior(&self) -> PauseBehavior {
+        *self.gate.behavior.lock().unwrap()
+    }
+
+    /// Stops publishing source values to the destination. The source subscription and destination
+    /// publisher stay alive, so destination subscribers keep their connection and last value.
+    pub fn pause(&self) {
+        self.gate.paused.store(true, Ordering::Release);
+    }
+
+    /// Resumes forwarding; with `PauseBehavior::HoldLast`, first publishes the latest value received while paused.
+    pub fn resume(&self) {
+        if self.gate.paused.swap(false, Ordering::AcqRel) {
+            self.gate.resumed.notify_one();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.gate.paused.load(Ordering::Acquire)
     }
 
     /// Atomically switches source by aborting old `stream_in` task and spawning new subscriber.
     /// Enables seamless source switching for contiguous streams from discontinuous publishers.
-    /// Error: `Subscriber` creation fails → returns to caller, relay keeps old source.
-    /// Called by: User code
+    /// `retry`: if set, waits for the source to be registered and confirmed instead of failing immediately.
+    /// Error: Source absent past the retry timeout, or `Subscriber` creation fails → returns to caller, relay keeps old source.
+    /// Called by: User code, `TypedRelaySet::bump` (argus)
     pub async fn swapon(
         &mut self,
         src_path: String,
         src_metaserver_connection: ConnectionHandle,
+        retry: Option<SwaponRetry>,
     ) -> OrError<()> {
+        if let Some(retry) = retry {
+            Self::wait_for_source(&src_path, src_metaserver_connection, retry).await?;
+        }
+
         // Create new subscriber to source
         let mut src_subscriber =
             Subscriber::<T>::new(src_path.clone(), src_metaserver_connection).await?;
@@ -90,23 +311,24 @@ impl<T: Agorable> Relay<T> {
 
         // Task: stream_in - receives from source subscriber, sends to channel → stream_out
         let stream_in = tokio::task::spawn(async move {
-            let _ = tx.send(value); // Send initial value
+            let _ = tx.send((value, Instant::now())); // Send initial value
             while let Some(result) = stream.next().await {
                 match result {
                     Ok(value) => {
-                        if tx.send(value).is_err() {
+                        if tx.send((value, Instant::now())).is_err() {
                             // Channel closed - stream_out task died
-                            eprintln!("{}", agora_error!("relay::Relay", "swapon",
-                                &format!("publisher task died (relay: {} -> {})", src_path, dest_path)));
+                            error!(src = %src_path, dest = %dest_path, "relay publisher task died");
                             break;
                         }
                     }
                     Err(e) => {
                         // Stream error (deserialization, etc.) - logged, task exits
-                        eprintln!("{}. Caused by -> {}",
-                            agora_error!("relay::Relay", "swapon",
-                                &format!("stream error (relay: {} -> {})", src_path, dest_path)),
-                            e);
+                        warn!(
+                            src = %src_path,
+                            dest = %dest_path,
+                            error = %e,
+                            "relay source stream error"
+                        );
                         break;
                     }
                 }
@@ -116,8 +338,33 @@ impl<T: Agorable> Relay<T> {
         // Store new subscriber and task (keeps subscriber alive, prevents drop)
         self.src_subscriber = Some(src_subscriber);
         self.stream_in = Some(stream_in);
+        self.metrics.swapons.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
+
+    /// Polls the metaserver until `src_path` is registered and confirmed, or the retry timeout elapses.
+    async fn wait_for_source(
+        src_path: &str,
+        src_metaserver_connection: ConnectionHandle,
+        retry: SwaponRetry,
+    ) -> OrError<()> {
+        let client = AgoraClient::new(src_metaserver_connection).await?;
+        let deadline = Instant::now() + retry.timeout;
+        loop {
+            match client.get_publisher_info(src_path).await {
+                Ok(_) => return Ok(()),
+                Err(e) if Instant::now() >= deadline => {
+                    return Err(anyhow::anyhow!(agora_error_cause!(
+                        "relay::Relay",
+                        "swapon",
+                        &format!("source {} not available after {:?}", src_path, retry.timeout),
+                        e
+                    )));
+                }
+                Err(_) => tokio::time::sleep(retry.poll_interval).await,
+            }
+        }
+    }
 }
 
 impl<T: Agorable> Drop for Relay<T> {
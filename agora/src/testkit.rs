@@ -0,0 +1,162 @@
+//! In-process agora cluster for end-to-end tests: metaserver and gateway on ephemeral ports, no external processes.
+//!
+//! ```ignore
+//! let cluster = TestCluster::start().await?;
+//! let publisher = Publisher::<i64>::new(name, path, 0, cluster.metaserver(), cluster.gateway_port()).await?;
+//! let subscriber = Subscriber::<i64>::new(path, cluster.metaserver()).await?;
+//! ```
+//!
+//! Dropping the cluster aborts the metaserver (including its pruning task) and the gateway.
+//! Publishers and subscribers own their sockets and are cleaned up by their own drops.
+//!
+//! Timer-driven behavior (the metaserver's prune pass, heartbeats, relay polling) can be driven without real sleeps:
+//! `pause_clock` freezes tokio's clock and `advance` moves it, so e.g. a prune runs in milliseconds, exactly when
+//! the test says so:
+//!
+//! ```ignore
+//! #[tokio::test] // current-thread runtime, required by a paused clock
+//! async fn stale_publisher_is_pruned() {
+//!     let mut cluster = TestCluster::start().await?;
+//!     // ... register a publisher, then drop it ...
+//!     cluster.pause_clock();
+//!     cluster.advance(Duration::from_millis(CHECK_PUBLISHER_LIVELINESS_EVERY_MS)).await;
+//! }
+//! ```
+
+use crate::agora_error;
+use crate::gateway::Gateway;
+use crate::metaserver::AgoraMetaServer;
+use crate::utils::{ConnectionHandle, OrError};
+use anyhow::{Context, bail};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// How long `start` waits for the metaserver to accept connections
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Scheduler rounds `advance` gives woken tasks, enough for a prune pass over in-process publishers to finish
+const ADVANCE_SETTLE_ROUNDS: usize = 100;
+
+/// Metaserver + gateway running as tasks on the caller's tokio runtime.
+pub struct TestCluster {
+    metaserver: ConnectionHandle,
+    gateway: Gateway,
+    metaserver_task: JoinHandle<OrError<()>>,
+    /// Set by `pause_clock`: keeps a blocking task alive, which stops tokio from auto-advancing the paused clock.
+    /// Dropping it ends the task.
+    clock_hold: Option<std::sync::mpsc::Sender<()>>,
+}
+
+impl TestCluster {
+    /// Starts an open (no auth token) metaserver and a gateway on free local ports.
+    /// Returns once the metaserver accepts TCP connections, so no sleep is needed before use.
+    /// Error: No free port, gateway bind fails, or the metaserver exits or does not come up within 5s
+    pub async fn start() -> OrError<Self> {
+        let metaserver = ConnectionHandle::new_local(free_port()?)?;
+        let metaserver_task = tokio::spawn(AgoraMetaServer::run_server(
+            metaserver.addr(),
+            metaserver.port(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        let gateway = Gateway::new(free_port()?).await?;
+        let cluster = Self {
+            metaserver,
+            gateway,
+            metaserver_task,
+            clock_hold: None,
+        };
+        cluster.wait_for_metaserver().await?;
+        Ok(cluster)
+    }
+
+    /// Metaserver to pass to `Publisher::new`, `Subscriber::new`, `AgoraClient::new`, ...
+    pub fn metaserver(&self) -> ConnectionHandle {
+        self.metaserver
+    }
+
+    pub fn gateway(&self) -> ConnectionHandle {
+        *self.gateway.connection()
+    }
+
+    /// Gateway port to pass to `Publisher::new`
+    pub fn gateway_port(&self) -> u16 {
+        self.gateway.connection().port()
+    }
+
+    /// Freezes tokio's clock: from now on timers only fire when `advance` moves the clock, never on their own.
+    /// Everything in the cluster and the test runs on the test's runtime, so intervals such as the metaserver's
+    /// prune pass (`CHECK_PUBLISHER_LIVELINESS_EVERY_MS`) become deterministic. Code that sleeps or times out waits
+    /// until the test advances past it.
+    /// Panics: Not on a current-thread runtime (`#[tokio::test]` without `flavor = "multi_thread"`), or the clock
+    /// is already paused
+    pub fn pause_clock(&mut self) {
+        tokio::time::pause();
+        // A paused clock still jumps to the next timer whenever the runtime idles, unless a blocking task is running
+        let (hold, released) = std::sync::mpsc::channel::<()>();
+        tokio::task::spawn_blocking(move || {
+            let _ = released.recv();
+        });
+        self.clock_hold = Some(hold);
+    }
+
+    /// Moves the paused clock forward by `duration`, firing every timer due by then, and lets the woken tasks run
+    /// (including their in-process IO) before returning.
+    /// Panics: The clock is not paused (see `pause_clock`)
+    pub async fn advance(&self, duration: Duration) {
+        tokio::time::advance(duration).await;
+        for _ in 0..ADVANCE_SETTLE_ROUNDS {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    async fn wait_for_metaserver(&self) -> OrError<()> {
+        let deadline = tokio::time::Instant::now() + STARTUP_TIMEOUT;
+        loop {
+            if self.metaserver_task.is_finished() {
+                bail!(agora_error!(
+                    "testkit::TestCluster",
+                    "start",
+                    &format!("metaserver on {} exited during startup", self.metaserver)
+                ));
+            }
+            if tokio::net::TcpStream::connect(self.metaserver.addr_port())
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                bail!(agora_error!(
+                    "testkit::TestCluster",
+                    "start",
+                    &format!("metaserver on {} did not come up", self.metaserver)
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
+impl Drop for TestCluster {
+    fn drop(&mut self) {
+        // The gateway aborts its own accept loop when dropped
+        self.metaserver_task.abort();
+    }
+}
+
+/// Asks the OS for an unused local TCP port. The port is released before returning,
+/// so another process could take it in between; `start` then fails rather than hang.
+fn free_port() -> OrError<u16> {
+    let listener = std::net::TcpListener::bind(("0.0.0.0", 0)).context(agora_error!(
+        "testkit",
+        "free_port",
+        "cannot bind an ephemeral port"
+    ))?;
+    Ok(listener
+        .local_addr()
+        .context(agora_error!("testkit", "free_port", "no local address"))?
+        .port())
+}
@@ -1,8 +1,8 @@
 mod common;
-pub use common::{ConnectionHandle, OrError, RpcError, agora_error_msg, prepare_socket_path, strip_and_verify};
+pub use common::{AuthToken, ConnectionHandle, OrError, RpcError, agora_error_msg, prepare_socket_path, strip_and_verify};
 
 mod pathtree;
-pub use pathtree::{TreeNode, TreeNodeRef, TreeTrait};
+pub use pathtree::{PathTreeSnapshot, TreeNode, TreeNodeRef, TreeTrait};
 
 mod stream_to_iter;
 pub use stream_to_iter::{BlockingStreamIterator, stream_to_iter};
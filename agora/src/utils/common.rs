@@ -1,6 +1,9 @@
-//! Shared utilities for Agora: `ConnectionHandle` for network addressing, error helpers (`agora_error!`, `agora_error_cause!`),
-//! path validation (`strip_and_verify`), and socket setup (`prepare_socket_path`). Used across all modules.
+//! Shared utilities for Agora: `ConnectionHandle` for network addressing, `AuthToken` for metaserver authentication,
+//! error helpers (`agora_error!`, `agora_error_cause!`), path validation (`strip_and_verify`), and socket setup (`prepare_socket_path`).
+//! Used across all modules.
 
+use crate::agora_error;
+use crate::constants::AUTH_TOKEN_MAX_LEN;
 use anyhow::{bail, Context};
 use local_ip_address::local_ip;
 use std::fmt::{Display, Formatter};
@@ -9,10 +12,77 @@ use std::net::IpAddr;
 /// Network address handle (IP + port) for gateway and metaserver connections.
 /// Serializable for RPC transmission in `PublisherInfo`. Used by `Publisher`, `Subscriber`, `Gateway` to establish connections.
 /// Display format: IPv4 as `ip:port`, IPv6 as `[ip]:port`.
-#[derive(serde::Deserialize, serde::Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+/// An optional `AuthToken` rides along to the metaserver's mutating RPCs; it is never serialized
+/// (so it does not leak through `PublisherInfo`) and does not take part in equality.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Copy, Clone)]
 pub struct ConnectionHandle {
     addr: IpAddr,
     port: u16,
+    #[serde(skip)]
+    token: Option<AuthToken>,
+}
+
+impl PartialEq for ConnectionHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr == other.addr && self.port == other.port
+    }
+}
+
+impl Eq for ConnectionHandle {}
+
+/// Shared secret gating the metaserver's mutating RPCs (`register_publisher`, `confirm_publisher`, `remove_publisher`).
+/// Fixed-size so `ConnectionHandle` stays `Copy`. `Debug` redacts the secret.
+#[derive(serde::Deserialize, serde::Serialize, Copy, Clone)]
+pub struct AuthToken {
+    len: u8,
+    bytes: [u8; AUTH_TOKEN_MAX_LEN],
+}
+
+impl AuthToken {
+    /// Error: Empty or longer than `AUTH_TOKEN_MAX_LEN` bytes.
+    pub fn new(secret: &str) -> OrError<Self> {
+        let secret = secret.as_bytes();
+        if secret.is_empty() || secret.len() > AUTH_TOKEN_MAX_LEN {
+            bail!(agora_error!(
+                "utils::AuthToken",
+                "new",
+                &format!("token must be 1 to {} bytes, got {}", AUTH_TOKEN_MAX_LEN, secret.len())
+            ));
+        }
+        let mut bytes = [0u8; AUTH_TOKEN_MAX_LEN];
+        bytes[..secret.len()].copy_from_slice(secret);
+        Ok(Self {
+            len: secret.len() as u8,
+            bytes,
+        })
+    }
+
+    /// Compares every byte regardless of where the first mismatch is, so timing does not reveal the secret.
+    pub fn matches(&self, presented: Option<&AuthToken>) -> bool {
+        let Some(presented) = presented else {
+            return false;
+        };
+        let diff = self
+            .bytes
+            .iter()
+            .zip(presented.bytes.iter())
+            .fold(self.len ^ presented.len, |acc, (a, b)| acc | (a ^ b));
+        diff == 0
+    }
+}
+
+impl PartialEq for AuthToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(Some(other))
+    }
+}
+
+impl Eq for AuthToken {}
+
+impl std::fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "AuthToken(<redacted>)")
+    }
 }
 
 pub type OrError<T> = anyhow::Result<T>;
@@ -47,7 +117,23 @@ macro_rules! agora_error_cause {
 
 impl ConnectionHandle {
     pub fn new(addr: IpAddr, port: u16) -> Self {
-        Self { addr, port }
+        Self {
+            addr,
+            port,
+            token: None,
+        }
+    }
+
+    /// Same address, presenting `token` to the metaserver
+    pub fn with_token(self, token: AuthToken) -> Self {
+        Self {
+            token: Some(token),
+            ..self
+        }
+    }
+
+    pub fn token(&self) -> Option<AuthToken> {
+        self.token
     }
 
     pub fn addr(&self) -> IpAddr {
@@ -64,7 +150,7 @@ impl ConnectionHandle {
             "new_local",
             "failed to get local IP"
         ))?;
-        Ok(Self { addr, port })
+        Ok(Self::new(addr, port))
     }
 
     pub fn addr_port(&self) -> (IpAddr, u16) {
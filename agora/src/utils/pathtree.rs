@@ -3,6 +3,7 @@
 
 use crate::utils::OrError;
 use crate::agora_error;
+use crate::constants::{TREE_REPR_MAX_DEPTH, TREE_REPR_MAX_NODES};
 use anyhow::{anyhow, bail};
 use std::fmt;
 use std::sync::{Arc, Weak, Mutex};
@@ -26,6 +27,7 @@ pub trait TreeTrait {
     fn get_child(self: &Arc<Self>, path: &str) -> OrError<TreeNodeRef>;
     fn remove_child(self: &Arc<Self>, name: &str) -> OrError<()>;
     fn remove_child_and_branch(self: &Arc<Self>, path: &str) -> OrError<()>;
+    fn move_subtree(self: &Arc<Self>, from: &str, to: &str) -> OrError<()>;
     fn parent(&self) -> OrError<TreeNodeRef>;
     fn root(self: &Arc<Self>) -> TreeNodeRef;
     fn children(&self) -> Vec<TreeNodeRef>;
@@ -110,6 +112,55 @@ impl TreeTrait for TreeNode {
         Ok(())
     }
 
+    /// Relocates the node at `from`, with all its descendants, to `to` (e.g. `"a/b"` → `"c/d"`).
+    /// The node takes `to`'s last segment as its name; descendants keep theirs and are re-parented onto it.
+    /// Nothing is modified unless every check passes. Emptied source directories are left in place.
+    /// Error: `from` missing or root, `to`'s parent missing, `to` already exists, or `to` lies inside `from`
+    /// → returns to `ServerState::move_subtree`.
+    fn move_subtree(self: &TreeNodeRef, from: &str, to: &str) -> OrError<()> {
+        let source = self.get_child(from)?;
+        if source.is_root() {
+            bail!(agora_error!("utils::TreeNode", "move_subtree", "cannot move root node"));
+        }
+        let to = to.trim_matches('/');
+        let (to_parent, to_name) = to.rsplit_once('/').unwrap_or(("", to));
+        if to_name.is_empty() {
+            bail!(agora_error!("utils::TreeNode", "move_subtree", "destination cannot be the root"));
+        }
+        let destination_parent = self.get_child(to_parent).map_err(|e| {
+            anyhow!(agora_error!("utils::TreeNode", "move_subtree",
+                &format!("destination parent '{}' does not exist: {}", to_parent, e)))
+        })?;
+        if destination_parent.get_immediate_child(to_name).is_ok() {
+            bail!(agora_error!("utils::TreeNode", "move_subtree",
+                &format!("destination '{}' already exists", to)));
+        }
+        // Moving a node below itself would cut the subtree off from the root
+        let mut ancestor = Some(destination_parent.clone());
+        while let Some(node) = ancestor {
+            if Arc::ptr_eq(&node, &source) {
+                bail!(agora_error!("utils::TreeNode", "move_subtree",
+                    &format!("cannot move '{}' into its own subtree '{}'", from, to)));
+            }
+            ancestor = node.parent().ok();
+        }
+
+        source.parent()?.remove_immediate_child(source.name())?;
+        let moved = if source.name() == to_name {
+            source
+        } else {
+            // Names are immutable: rebuild the top node and hand it the children
+            let renamed = TreeNode::new(to_name);
+            let children = std::mem::take(&mut *source.children.lock().unwrap());
+            for child in children {
+                renamed.add_child(child);
+            }
+            renamed
+        };
+        destination_parent.add_child(moved);
+        Ok(())
+    }
+
     fn parent(&self) -> OrError<TreeNodeRef> {
         match self.parent.lock().unwrap().as_ref() {
             Some(parent) => Ok(parent.upgrade().unwrap()),
@@ -158,9 +209,10 @@ impl TreeTrait for TreeNode {
     }
 
     /// Deserializes tree from custom JSON-like format: `"leaf"` or `{"parent":[children...]}`.
-    /// Error: Invalid format → propagates to `AgoraClient::get_path_tree` caller.
+    /// Bounded by `TREE_REPR_MAX_DEPTH` and `TREE_REPR_MAX_NODES`; see `from_repr_with_limits`.
+    /// Error: Invalid format or limit exceeded → propagates to `AgoraClient::get_path_tree` caller.
     fn from_repr(repr: &str) -> OrError<TreeNodeRef> {
-        TreeNode::from_repr_helper(repr)
+        TreeNode::from_repr_with_limits(repr, TREE_REPR_MAX_DEPTH, TREE_REPR_MAX_NODES)
     }
 }
 
@@ -173,6 +225,14 @@ impl fmt::Display for TreeNode {
 }
 
 impl TreeNode {
+    /// `from_repr` with explicit limits. The repr crosses the RPC boundary, so it is treated as untrusted:
+    /// nesting deeper than `max_depth` (root is depth 0), more than `max_nodes` nodes,
+    /// or a node name containing '/' is returned as an error rather than overflowing the stack or panicking.
+    pub fn from_repr_with_limits(repr: &str, max_depth: usize, max_nodes: usize) -> OrError<TreeNodeRef> {
+        let mut node_count = 0;
+        TreeNode::from_repr_helper(repr, 0, max_depth, &mut node_count, max_nodes)
+    }
+
     fn to_string_helper(&self, prefix: &str, is_last: bool) -> String {
         let mut result = String::new();
 
@@ -258,13 +318,28 @@ impl TreeNode {
         }
     }
 
-    fn from_repr_helper(repr: &str) -> OrError<TreeNodeRef> {
+    fn from_repr_helper(
+        repr: &str,
+        depth: usize,
+        max_depth: usize,
+        node_count: &mut usize,
+        max_nodes: usize,
+    ) -> OrError<TreeNodeRef> {
+        if depth > max_depth {
+            bail!(agora_error!("utils::TreeNode", "from_repr",
+                &format!("nesting exceeds max depth {}", max_depth)));
+        }
+        *node_count += 1;
+        if *node_count > max_nodes {
+            bail!(agora_error!("utils::TreeNode", "from_repr",
+                &format!("repr exceeds max node count {}", max_nodes)));
+        }
         let repr = repr.trim();
 
         // Branch 1: Leaf node - just a quoted string "name"
-        if repr.starts_with('"') && repr.ends_with('"') && !repr.contains('[') {
+        if repr.len() >= 2 && repr.starts_with('"') && repr.ends_with('"') && !repr.contains('[') {
             let name = &repr[1..repr.len() - 1];
-            return Ok(TreeNode::new(name));
+            return TreeNode::new_checked(name);
         }
 
         // Branch 2: Parent node - {"name":[children...]}
@@ -278,7 +353,7 @@ impl TreeNode {
         // Parse: name (before ':') and children array (after ':')
         if let Some(colon_pos) = inner.find(':') {
             let name_part = inner[..colon_pos].trim();
-            if !name_part.starts_with('"') || !name_part.ends_with('"') {
+            if name_part.len() < 2 || !name_part.starts_with('"') || !name_part.ends_with('"') {
                 bail!(agora_error!("utils::TreeNode", "from_repr",
                     &format!("invalid name format: {}", name_part)));
             }
@@ -290,14 +365,20 @@ impl TreeNode {
                     &format!("invalid children format: {}", children_part)));
             }
 
-            let node = TreeNode::new(name);
+            let node = TreeNode::new_checked(name)?;
 
             // Recursively parse children array
             let children_inner = &children_part[1..children_part.len() - 1].trim();
             if !children_inner.is_empty() {
                 let child_reprs = TreeNode::split_repr_array(children_inner)?;
                 for child_repr in child_reprs {
-                    let child = TreeNode::from_repr_helper(&child_repr)?;
+                    let child = TreeNode::from_repr_helper(
+                        &child_repr,
+                        depth + 1,
+                        max_depth,
+                        node_count,
+                        max_nodes,
+                    )?;
                     node.add_child(child);
                 }
             }
@@ -309,6 +390,15 @@ impl TreeNode {
         }
     }
 
+    // `TreeNode::new` for names read from a repr: a slash is a format error, not a programming bug
+    fn new_checked(name: &str) -> OrError<TreeNodeRef> {
+        if name.contains('/') {
+            bail!(agora_error!("utils::TreeNode", "from_repr",
+                &format!("node name cannot contain slashes: {}", name)));
+        }
+        Ok(TreeNode::new(name))
+    }
+
     // Splits comma-separated array respecting nesting depth and quotes.
     // Only splits on commas at depth=0 (outside all braces/brackets and quotes).
     fn split_repr_array(s: &str) -> OrError<Vec<String>> {
@@ -360,3 +450,114 @@ impl TreeNode {
         Ok(result)
     }
 }
+
+/// Immutable, lock-free copy of a `TreeNode` subtree.
+/// Built once via `PathTreeSnapshot::of_node` (each node's children lock is held only while its child list is cloned),
+/// after which iteration and matching never touch the live tree, so readers cannot race with or stall registration.
+/// Paths yielded are relative to the snapshot root, matching `ServerState::publishers` keys (e.g. `"a/b/c"`).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PathTreeSnapshot {
+    name: String,
+    children: Vec<PathTreeSnapshot>,
+}
+
+impl PathTreeSnapshot {
+    pub fn of_node(node: &TreeNode) -> Self {
+        let children = node.children();
+        Self {
+            name: node.name().to_string(),
+            children: children.iter().map(|child| Self::of_node(child)).collect(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn children(&self) -> &[PathTreeSnapshot] {
+        &self.children
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Traverses by slash-separated path relative to this node. Empty path returns self.
+    pub fn get(&self, path: &str) -> Option<&PathTreeSnapshot> {
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .try_fold(self, |node, part| node.children.iter().find(|c| c.name == part))
+    }
+
+    /// All descendant paths (directories and leaves), depth-first in insertion order.
+    pub fn iter_paths(&self) -> impl Iterator<Item = String> {
+        let mut paths = Vec::new();
+        self.collect_paths("", false, &mut paths);
+        paths.into_iter()
+    }
+
+    /// Leaf paths only. Under the metaserver invariant these are exactly the publisher paths.
+    pub fn iter_leaves(&self) -> impl Iterator<Item = String> {
+        let mut paths = Vec::new();
+        self.collect_paths("", true, &mut paths);
+        paths.into_iter()
+    }
+
+    /// Descendant paths matching a slash-separated glob.
+    /// `*` matches any run of characters within one segment (e.g. `"bbo/BTC*"`); `**` matches zero or more whole segments.
+    pub fn glob(&self, pattern: &str) -> Vec<String> {
+        let pattern: Vec<&str> = pattern.split('/').filter(|p| !p.is_empty()).collect();
+        self.iter_paths()
+            .filter(|path| {
+                let segments: Vec<&str> = path.split('/').collect();
+                glob_segments(&pattern, &segments)
+            })
+            .collect()
+    }
+
+    fn collect_paths(&self, prefix: &str, leaves_only: bool, out: &mut Vec<String>) {
+        for child in &self.children {
+            let path = if prefix.is_empty() {
+                child.name.clone()
+            } else {
+                format!("{}/{}", prefix, child.name)
+            };
+            if !leaves_only || child.is_leaf() {
+                out.push(path.clone());
+            }
+            child.collect_paths(&path, leaves_only, out);
+        }
+    }
+}
+
+impl fmt::Display for PathTreeSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for path in self.iter_paths() {
+            writeln!(f, "{}", path)?;
+        }
+        Ok(())
+    }
+}
+
+fn glob_segments(pattern: &[&str], segments: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => segments.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=segments.len()).any(|skip| glob_segments(rest, &segments[skip..]))
+        }
+        Some((head, rest)) => match segments.split_first() {
+            Some((segment, remaining)) => {
+                glob_segment(head.as_bytes(), segment.as_bytes()) && glob_segments(rest, remaining)
+            }
+            None => false,
+        },
+    }
+}
+
+fn glob_segment(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_segment(rest, &text[skip..])),
+        Some((c, rest)) => text.first() == Some(c) && glob_segment(rest, &text[1..]),
+    }
+}
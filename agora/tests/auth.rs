@@ -0,0 +1,104 @@
+use agora::gateway::Gateway;
+use agora::metaserver::{AgoraClient, AgoraMetaServer, MetaServerError};
+use agora::utils::TreeTrait;
+use agora::{AuthToken, ConnectionHandle, Publisher};
+use std::time::Duration;
+
+/// Starts a token-protected metaserver and a gateway on ports unique to this test process.
+/// Returns the metaserver connection *without* the token.
+async fn start_agora(offset: u16, token: AuthToken) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        Some(token),
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+fn is_unauthorized(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<MetaServerError>(),
+            Some(MetaServerError::Unauthorized { .. })
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_bounded_and_never_serialized() {
+        assert!(AuthToken::new("").is_err());
+        assert!(AuthToken::new(&"x".repeat(33)).is_err());
+        let token = AuthToken::new("s3cret").unwrap();
+        assert!(token.matches(Some(&AuthToken::new("s3cret").unwrap())));
+        assert!(!token.matches(Some(&AuthToken::new("s3cre").unwrap())));
+        assert!(!token.matches(None));
+        assert!(!format!("{:?}", token).contains("s3cret"));
+
+        // Host connections travel inside `PublisherInfo`: the token must not go with them
+        let plain = ConnectionHandle::new_local(9000).unwrap();
+        let with_token = plain.with_token(token);
+        let json = serde_json::to_string(&with_token).unwrap();
+        assert!(!json.contains("s3cret") && !json.contains("token"));
+        assert_eq!(with_token, plain);
+        let decoded: ConnectionHandle = serde_json::from_str(&json).unwrap();
+        assert!(decoded.token().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn registration_requires_matching_token() {
+        let token = AuthToken::new("shared-secret").unwrap();
+        let (metaserver, gateway_port, _gateway) = start_agora(0, token).await;
+        let path = format!("tests/auth_{}/price", std::process::id());
+
+        // No token, then a wrong token: rejected as Unauthorized, nothing registered
+        let err = Publisher::<i64>::new("p".into(), path.clone(), 0, metaserver, gateway_port)
+            .await
+            .err()
+            .expect("registration without a token should fail");
+        assert!(is_unauthorized(&err), "unexpected error: {:#}", err);
+
+        let wrong = metaserver.with_token(AuthToken::new("guess").unwrap());
+        let err = Publisher::<i64>::new("p".into(), path.clone(), 0, wrong, gateway_port)
+            .await
+            .err()
+            .expect("registration with a wrong token should fail");
+        assert!(is_unauthorized(&err), "unexpected error: {:#}", err);
+
+        // Read-only queries stay open
+        let open_client = AgoraClient::new(metaserver).await.unwrap();
+        assert!(open_client.get_path_tree().await.unwrap().get_child(&path).is_err());
+
+        // Matching token: registers, confirms and publishes
+        let authorized = metaserver.with_token(token);
+        let mut publisher =
+            Publisher::<i64>::new("p".into(), path.clone(), 0, authorized, gateway_port)
+                .await
+                .unwrap();
+        publisher.publish(7).await.unwrap();
+        assert!(open_client.get_path_tree().await.unwrap().get_child(&path).is_ok());
+
+        // Removal is gated too
+        let err = open_client.remove_publisher(&path).await.unwrap_err();
+        assert!(is_unauthorized(&err), "unexpected error: {:#}", err);
+        AgoraClient::new(authorized)
+            .await
+            .unwrap()
+            .remove_publisher(&path)
+            .await
+            .unwrap();
+        assert!(open_client.get_path_tree().await.unwrap().get_child(&path).is_err());
+    }
+}
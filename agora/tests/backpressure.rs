@@ -0,0 +1,75 @@
+use agora::Publisher;
+use agora::testkit::TestCluster;
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::net::UnixStream;
+use tokio_tungstenite::{WebSocketStream, client_async};
+
+/// Connects a raw WebSocket client straight to a publisher's binary rawstream (bypassing the gateway),
+/// so the test controls exactly when it reads.
+async fn connect_bytes_endpoint(path: &str) -> WebSocketStream<UnixStream> {
+    let socket_path = format!("/tmp/agora/{}/bytes/rawstream.sock", path);
+    let stream = UnixStream::connect(socket_path).await.unwrap();
+    let (ws_stream, _) = client_async("ws://localhost/", stream).await.unwrap();
+    ws_stream
+}
+
+/// Polls `condition` until it holds; false on timeout
+async fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalled_subscriber_congests_until_it_drains() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/backpressure_{}/value", std::process::id());
+        let mut publisher = Publisher::<String>::new(
+            "value".to_string(),
+            path.clone(),
+            String::new(),
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+        assert!(!publisher.is_congested());
+        assert_eq!(publisher.congestion_ratio(), 0.0);
+
+        // Connected but never reading: once its socket buffers fill, its send queue does too
+        let mut stalled = connect_bytes_endpoint(&path).await;
+        assert!(wait_until(|| publisher.subscriber_count() == 1).await);
+        let payload = "x".repeat(1024);
+        for seq in 0..12_000 {
+            publisher
+                .publish(format!("{}:{}", seq, payload))
+                .await
+                .unwrap();
+        }
+        assert!(
+            wait_until(|| publisher.is_congested()).await,
+            "congestion ratio {}",
+            publisher.congestion_ratio()
+        );
+        assert!(publisher.congestion_ratio() <= 1.0);
+
+        // Draining the backlog clears congestion
+        let reader = tokio::spawn(async move { while stalled.next().await.is_some() {} });
+        assert!(
+            wait_until(|| !publisher.is_congested()).await,
+            "congestion ratio {}",
+            publisher.congestion_ratio()
+        );
+        assert!(wait_until(|| publisher.congestion_ratio() == 0.0).await);
+        reader.abort();
+    }
+}
@@ -0,0 +1,74 @@
+use agora::ConnectionHandle;
+use agora::blocking::{BlockingPublisher, BlockingSubscriber};
+use agora::gateway::Gateway;
+use agora::metaserver::AgoraMetaServer;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora(offset: u16) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocking_round_trip_from_plain_thread() {
+        // Infrastructure runs on its own runtime; the test thread itself has no tokio context
+        let infra = Runtime::new().unwrap();
+        let (metaserver, gateway_port, _gateway) = infra.block_on(start_agora(0));
+        let path = format!("tests/blocking_{}/counter", std::process::id());
+
+        let mut publisher = BlockingPublisher::<i64>::new(
+            "blocking".to_string(),
+            path.clone(),
+            -1,
+            metaserver,
+            gateway_port,
+        )
+        .unwrap();
+        let mut subscriber = BlockingSubscriber::<i64>::new(path, metaserver).unwrap();
+        assert_eq!(subscriber.get().unwrap(), -1);
+
+        for value in 0..3 {
+            publisher.publish(value).unwrap();
+        }
+        // Values published while nobody was receiving are buffered
+        for value in 0..3 {
+            assert_eq!(subscriber.recv().unwrap(), value);
+        }
+        assert_eq!(subscriber.recv_timeout(Duration::from_millis(200)).unwrap(), None);
+
+        publisher.publish(7).unwrap();
+        assert_eq!(
+            subscriber.recv_timeout(Duration::from_secs(2)).unwrap(),
+            Some(7)
+        );
+        assert_eq!(subscriber.get().unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn blocking_calls_refuse_tokio_context() {
+        let metaserver = ConnectionHandle::new_local(1).unwrap();
+        let err = BlockingSubscriber::<i64>::new("tests/blocking/unused".to_string(), metaserver)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("within a tokio runtime"), "{}", err);
+    }
+}
@@ -0,0 +1,105 @@
+use agora::testkit::TestCluster;
+use agora::{Publisher, Subscriber};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Receives `count` values, failing on lag, close or a 3s stall
+async fn recv_n(receiver: &mut broadcast::Receiver<i64>, count: usize) -> Vec<i64> {
+    let mut received = Vec::new();
+    while received.len() < count {
+        let value = tokio::time::timeout(Duration::from_secs(3), receiver.recv())
+            .await
+            .expect("no value within 3s")
+            .unwrap();
+        received.push(value);
+    }
+    received
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn broadcast_receivers_see_the_same_sequence() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/broadcast_{}/value", std::process::id());
+        let mut publisher = Publisher::<i64>::new(
+            "broadcast".to_string(),
+            path.clone(),
+            -1,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+
+        let handle = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap()
+            .into_broadcast(64)
+            .await
+            .unwrap();
+        assert_eq!(handle.latest(), -1);
+        let mut strategy = handle.subscribe();
+        let mut logger = handle.subscribe();
+        assert_eq!(handle.receiver_count(), 2);
+
+        for value in 0..20 {
+            publisher.publish(value).await.unwrap();
+        }
+        let expected: Vec<i64> = (0..20).collect();
+        assert_eq!(recv_n(&mut strategy, 20).await, expected);
+        assert_eq!(recv_n(&mut logger, 20).await, expected);
+        assert_eq!(handle.latest(), 19);
+
+        // Dropping the handle closes every receiver
+        drop(handle);
+        assert!(matches!(
+            strategy.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn slow_receiver_lags_without_blocking_others() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/broadcast_{}/lag", std::process::id());
+        let mut publisher = Publisher::<i64>::new(
+            "broadcast_lag".to_string(),
+            path.clone(),
+            -1,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+
+        let handle = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap()
+            .into_broadcast(4)
+            .await
+            .unwrap();
+        let mut slow = handle.subscribe();
+
+        for value in 0..10 {
+            publisher.publish(value).await.unwrap();
+        }
+        // Wait until everything went through the channel before the slow receiver reads
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while handle.latest() != 9 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // Only the last `capacity` values are retained; the gap is reported once
+        assert!(matches!(
+            slow.recv().await,
+            Err(broadcast::error::RecvError::Lagged(6))
+        ));
+        assert_eq!(recv_n(&mut slow, 4).await, vec![6, 7, 8, 9]);
+    }
+}
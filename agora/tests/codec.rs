@@ -0,0 +1,216 @@
+use agora::gateway::Gateway;
+use agora::metaserver::AgoraMetaServer;
+use agora::{
+    Agorable, ConnectionHandle, OmniSubscriber, Publisher, SchemaVersionMismatch, Subscriber,
+    WireCodec,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Levels {
+    symbol: String,
+    levels: Vec<(f64, f64, u32)>,
+}
+
+impl fmt::Display for Levels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} levels)", self.symbol, self.levels.len())
+    }
+}
+
+impl agora::Agorable for Levels {}
+
+/// `Levels` after a (hypothetical) layout change: same Rust shape, bumped schema version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LevelsV2 {
+    symbol: String,
+    levels: Vec<(f64, f64, u32)>,
+}
+
+impl fmt::Display for LevelsV2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} levels, v2)", self.symbol, self.levels.len())
+    }
+}
+
+impl agora::Agorable for LevelsV2 {
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+fn levels(n: u32) -> Levels {
+    Levels {
+        symbol: "BTC_PERP".to_string(),
+        levels: (0..n)
+            .map(|i| (100_000.0 - i as f64 * 0.5, 0.25 + i as f64, i))
+            .collect(),
+    }
+}
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora(offset: u16) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    #[test]
+    fn codecs_round_trip() {
+        let value = levels(50);
+        for codec in WireCodec::ALL {
+            let bytes = codec.encode(&value).unwrap();
+            assert_eq!(codec.decode::<Levels>(&bytes).unwrap(), value);
+        }
+        let json = WireCodec::Json.encode(&value).unwrap();
+        let bincode = WireCodec::Bincode.encode(&value).unwrap();
+        assert!(bincode.len() < json.len());
+        assert!(WireCodec::Bincode.decode::<Levels>(&json).is_err());
+    }
+
+    #[test]
+    fn handshake_and_negotiation() {
+        for codec in WireCodec::ALL {
+            assert_eq!(WireCodec::from_handshake(&codec.handshake()).unwrap(), codec);
+        }
+        assert_eq!(WireCodec::default(), WireCodec::Postcard);
+        assert!(WireCodec::from_handshake("agora-codec:msgpack").is_err());
+        assert!(WireCodec::from_handshake("bincode").is_err());
+
+        // Schema versions ride along in the same frame; frames without one still parse
+        let frame = WireCodec::Bincode.handshake_with_schema(7);
+        assert_eq!(frame, "agora-codec:bincode;schema=7");
+        assert_eq!(
+            WireCodec::parse_handshake(&frame).unwrap(),
+            (WireCodec::Bincode, Some(7))
+        );
+        assert_eq!(WireCodec::from_handshake(&frame).unwrap(), WireCodec::Bincode);
+        assert_eq!(
+            WireCodec::parse_handshake("agora-codec:json").unwrap(),
+            (WireCodec::Json, None)
+        );
+        assert!(WireCodec::parse_handshake("agora-codec:json;schema=x").is_err());
+        assert_eq!(agora::AgorableOption::<LevelsV2>::SCHEMA_VERSION, 2);
+        assert_eq!(<Levels as Agorable>::SCHEMA_VERSION, 1);
+
+        let err = WireCodec::negotiate(WireCodec::Bincode, &[WireCodec::Json]).unwrap_err();
+        assert!(err.to_string().contains("only supports [json]"));
+        assert_eq!(
+            WireCodec::negotiate(WireCodec::Json, &WireCodec::ALL).unwrap(),
+            WireCodec::Json
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscriber_adopts_publisher_codec() {
+        let (metaserver, gateway_port, _gateway) = start_agora(0).await;
+        let path = format!("tests/codec_{}/bincode", std::process::id());
+        let mut publisher = Publisher::with_codec(
+            "codec".to_string(),
+            path.clone(),
+            levels(1),
+            metaserver,
+            gateway_port,
+            WireCodec::Bincode,
+        )
+        .await
+        .unwrap();
+        assert_eq!(publisher.codec(), WireCodec::Bincode);
+
+        let mut subscriber = Subscriber::<Levels>::new(path, metaserver).await.unwrap();
+        assert_eq!(subscriber.codec(), WireCodec::Bincode);
+        let (current, mut stream) = subscriber.get_stream().await.unwrap();
+        assert_eq!(current, levels(1));
+
+        publisher.publish(levels(200)).await.unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(3), stream.next())
+            .await
+            .expect("stream should yield the published value")
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, levels(200));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn json_only_subscriber_rejects_bincode_publisher() {
+        let (metaserver, gateway_port, _gateway) = start_agora(1).await;
+        let path = format!("tests/codec_{}/mismatch", std::process::id());
+        let _publisher = Publisher::with_codec(
+            "codec".to_string(),
+            path.clone(),
+            levels(1),
+            metaserver,
+            gateway_port,
+            WireCodec::Bincode,
+        )
+        .await
+        .unwrap();
+
+        let result = Subscriber::<Levels>::with_codecs(path.clone(), metaserver, &[WireCodec::Json])
+            .await;
+        let err = result.err().expect("negotiation should fail");
+        assert!(format!("{:#}", err).contains("publisher uses codec bincode"));
+
+        // Supporting bincode alongside JSON connects fine
+        let subscriber = Subscriber::<Levels>::with_codecs(
+            path,
+            metaserver,
+            &[WireCodec::Json, WireCodec::Bincode],
+        )
+        .await
+        .unwrap();
+        assert_eq!(subscriber.codec(), WireCodec::Bincode);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscriber_refuses_other_schema_version() {
+        let (metaserver, gateway_port, _gateway) = start_agora(2).await;
+        let path = format!("tests/codec_{}/schema", std::process::id());
+        let _publisher = Publisher::new(
+            "schema".to_string(),
+            path.clone(),
+            levels(3),
+            metaserver,
+            gateway_port,
+        )
+        .await
+        .unwrap();
+
+        // Same wire shape, but the publisher announced version 1
+        let err = Subscriber::<LevelsV2>::new(path.clone(), metaserver)
+            .await
+            .err()
+            .expect("schema versions differ");
+        let mismatch = err
+            .downcast_ref::<SchemaVersionMismatch>()
+            .expect("typed mismatch error");
+        assert_eq!(mismatch.publisher_version, 1);
+        assert_eq!(mismatch.subscriber_version, 2);
+
+        let mut omni = OmniSubscriber::new(path.clone(), metaserver).await.unwrap();
+        let err = omni.recv_as::<LevelsV2>().await.unwrap_err();
+        assert!(err.downcast_ref::<SchemaVersionMismatch>().is_some());
+        // Adapting: the matching type still decodes through the same subscriber
+        assert_eq!(omni.recv_as::<Levels>().await.unwrap(), levels(3));
+
+        let subscriber = Subscriber::<Levels>::new(path, metaserver).await.unwrap();
+        assert_eq!(subscriber.publisher_schema_version(), Some(1));
+    }
+}
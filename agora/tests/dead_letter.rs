@@ -0,0 +1,94 @@
+use agora::testkit::TestCluster;
+use agora::{Publisher, Subscriber};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+// Postcard encodes i64 as a zigzag varint: 0 → [0], -1 → [1], 1 → [2].
+// Only [0] and [1] are valid bools, so publishing 1 injects a malformed frame for a `Subscriber<bool>`.
+const FALSE: i64 = 0;
+const TRUE: i64 = -1;
+const MALFORMED: i64 = 1;
+
+/// Publisher of i64 frames under a path unique to this test process
+async fn frame_publisher(cluster: &TestCluster, name: &str) -> (Publisher<i64>, String) {
+    let path = format!("tests/dead_letter_{}/{}", std::process::id(), name);
+    let publisher = Publisher::<i64>::new(
+        name.to_string(),
+        path.clone(),
+        FALSE,
+        cluster.metaserver(),
+        cluster.gateway_port(),
+    )
+    .await
+    .unwrap();
+    (publisher, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn malformed_frame_goes_to_dead_letter_and_stream_continues() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (mut publisher, path) = frame_publisher(&cluster, "channel").await;
+
+        let (mut subscriber, mut dead_letters) =
+            Subscriber::<bool>::new(path, cluster.metaserver())
+                .await
+                .unwrap()
+                .with_dead_letter();
+        let (current, mut stream) = subscriber.get_stream().await.unwrap();
+        assert!(!current);
+
+        for frame in [TRUE, MALFORMED, FALSE, TRUE] {
+            publisher.publish(frame).await.unwrap();
+        }
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            let value = tokio::time::timeout(Duration::from_secs(3), stream.next())
+                .await
+                .expect("no value within 3s")
+                .unwrap();
+            received.push(value.unwrap());
+        }
+        assert_eq!(received, vec![true, false, true]);
+
+        let dead_letter = dead_letters.try_recv().unwrap();
+        assert_eq!(dead_letter.bytes, vec![2]);
+        assert!(
+            dead_letter
+                .error
+                .contains("failed to deserialize stream value")
+        );
+        assert!(dead_letters.try_recv().is_err());
+        assert_eq!(subscriber.decode_error_count(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn without_handler_malformed_frame_is_a_stream_error() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (mut publisher, path) = frame_publisher(&cluster, "default").await;
+
+        let mut subscriber = Subscriber::<bool>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+
+        for frame in [MALFORMED, TRUE] {
+            publisher.publish(frame).await.unwrap();
+        }
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let item = tokio::time::timeout(Duration::from_secs(3), stream.next())
+                .await
+                .expect("no value within 3s")
+                .unwrap();
+            received.push(item);
+        }
+        assert!(received[0].is_err());
+        // The error is per frame: later frames still arrive
+        assert!(*received[1].as_ref().unwrap());
+        assert_eq!(subscriber.decode_error_count(), 1);
+    }
+}
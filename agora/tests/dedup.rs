@@ -0,0 +1,75 @@
+use agora::testkit::TestCluster;
+use agora::{Publisher, Subscriber};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+async fn publisher(cluster: &TestCluster, path: &str) -> Publisher<i64> {
+    Publisher::<i64>::new(
+        "dedup".to_string(),
+        path.to_string(),
+        -1,
+        cluster.metaserver(),
+        cluster.gateway_port(),
+    )
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dedup_forwards_only_changes() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/dedup_{}/changes", std::process::id());
+        let mut publisher = publisher(&cluster, &path).await.with_dedup();
+        let mut subscriber = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+
+        // The first publish goes through even though it equals the initial value
+        for value in [-1, -1, 2, 2, 2, 3, 2, 2] {
+            publisher.publish(value).await.unwrap();
+        }
+        assert_eq!(publisher.deduplicated_count(), 4);
+
+        let received: Vec<i64> = tokio::time::timeout(
+            Duration::from_secs(3),
+            stream.by_ref().take(4).map(|v| v.unwrap()).collect(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(received, vec![-1, 2, 3, 2]);
+        // Nothing else arrives: the trailing repeat was skipped
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), stream.next())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn without_dedup_repeats_are_published() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/dedup_{}/repeats", std::process::id());
+        let mut publisher = publisher(&cluster, &path).await;
+        let mut subscriber = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+
+        for _ in 0..3 {
+            publisher.publish(7).await.unwrap();
+        }
+        assert_eq!(publisher.deduplicated_count(), 0);
+        let received: Vec<i64> = tokio::time::timeout(
+            Duration::from_secs(3),
+            stream.by_ref().take(3).map(|v| v.unwrap()).collect(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(received, vec![7, 7, 7]);
+    }
+}
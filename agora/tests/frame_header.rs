@@ -0,0 +1,186 @@
+use agora::rawstream::{FRAME_HEADER_LEN, FrameHeader, ProtocolMismatch, RawStreamServer};
+use agora::testkit::TestCluster;
+use agora::{Agorable, Publisher, Subscriber, WireCodec};
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::net::UnixStream;
+use tokio_tungstenite::{WebSocketStream, client_async, tungstenite::Message};
+
+/// Connects a raw WebSocket client directly to a rawstream UDS (bypassing the gateway).
+async fn connect_uds(socket_path: &str) -> WebSocketStream<UnixStream> {
+    let stream = UnixStream::connect(socket_path).await.unwrap();
+    let (ws_stream, _) = client_async("ws://localhost/", stream).await.unwrap();
+    ws_stream
+}
+
+/// Next binary frame, skipping the handshake and heartbeat pings
+async fn next_binary(ws: &mut WebSocketStream<UnixStream>) -> Vec<u8> {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(3), ws.next())
+            .await
+            .expect("no frame within 3s")
+            .unwrap()
+            .unwrap();
+        if let Message::Binary(data) = message {
+            return data.to_vec();
+        }
+    }
+}
+
+fn socket_path(name: &str) -> String {
+    format!(
+        "/tmp/agora/tests/frame_header_{}/{}/rawstream.sock",
+        std::process::id(),
+        name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_roundtrip_and_mismatch() {
+        let header = WireCodec::Bincode.frame_header(7);
+        assert_eq!(header, FrameHeader::new(2, 7));
+        let frame = header.frame(b"payload");
+        assert_eq!(frame.len(), FRAME_HEADER_LEN + 7);
+        assert_eq!(FrameHeader::parse(&frame), Some((header, &b"payload"[..])));
+        assert_eq!(header.strip(&frame).unwrap(), b"payload");
+
+        // Same magic, other codec or schema version
+        let err = WireCodec::Json.frame_header(7).strip(&frame).unwrap_err();
+        assert_eq!(err.found, Some(header));
+        assert!(err.to_string().contains("codec id 2"), "{}", err);
+        let err = WireCodec::Bincode
+            .frame_header(8)
+            .strip(&frame)
+            .unwrap_err();
+        assert!(err.to_string().contains("schema version 7"), "{}", err);
+
+        // Truncated headers are not headers
+        assert_eq!(FrameHeader::parse(&frame[..FRAME_HEADER_LEN - 1]), None);
+        assert_eq!(header.strip(&frame[..FRAME_HEADER_LEN]).unwrap(), b"");
+
+        for codec in WireCodec::ALL {
+            assert_eq!(WireCodec::from_id(codec.id()).unwrap(), codec);
+        }
+        assert!(WireCodec::from_id(9).is_err());
+    }
+
+    #[tokio::test]
+    async fn server_puts_header_in_front_of_every_frame() {
+        let path = socket_path("framed");
+        let header = WireCodec::Json.frame_header(3);
+        let server: RawStreamServer<Vec<u8>> = RawStreamServer::with_handshake(
+            &path,
+            None,
+            None,
+            Some(WireCodec::Json.handshake_with_schema(3)),
+            Some(header),
+            Some(16),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(server.frame_header(), Some(header));
+        let mut client = connect_uds(&path).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        server.publish(b"first".to_vec()).unwrap();
+        server.publish(b"second!".to_vec()).unwrap();
+        assert_eq!(
+            header.strip(&next_binary(&mut client).await).unwrap(),
+            b"first"
+        );
+        let frame = next_binary(&mut client).await;
+        assert_eq!(header.strip(&frame).unwrap(), b"second!");
+
+        // A client expecting another codec rejects the frame instead of misdecoding it
+        let err = WireCodec::Postcard
+            .frame_header(3)
+            .strip(&frame)
+            .unwrap_err();
+        assert_eq!(err.found, Some(header));
+
+        // The header counts toward max_frame_bytes: 8 + 9 > 16
+        let err = server.publish(b"eight!!!".to_vec()).unwrap_err();
+        assert!(format!("{:#}", err).contains("exceeds max_frame_bytes"));
+    }
+
+    #[tokio::test]
+    async fn old_format_frames_are_a_protocol_mismatch() {
+        // A server without a header, as publishers predating frame headers
+        let path = socket_path("headerless");
+        let server: RawStreamServer<Vec<u8>> = RawStreamServer::new(&path, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(server.frame_header(), None);
+        let mut client = connect_uds(&path).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let payload = WireCodec::Postcard.encode(&42i64).unwrap();
+        server.publish(payload.clone()).unwrap();
+        let frame = next_binary(&mut client).await;
+        assert_eq!(frame, payload);
+
+        let expected = WireCodec::Postcard.frame_header(i64::SCHEMA_VERSION);
+        let err: ProtocolMismatch = expected.strip(&frame).unwrap_err();
+        assert_eq!(err.found, None);
+        assert!(err.to_string().contains("no agora header"), "{}", err);
+
+        // Downcastable once wrapped, like the stream errors of `Subscriber::get_stream`
+        let wrapped = anyhow::Error::new(err.clone()).context("stream error");
+        assert_eq!(wrapped.downcast_ref::<ProtocolMismatch>(), Some(&err));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscriber_strips_matching_headers() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/frame_header_{}/typed", std::process::id());
+        let mut publisher = Publisher::<i64>::with_codec(
+            "typed".to_string(),
+            path.clone(),
+            0,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+            WireCodec::Bincode,
+        )
+        .await
+        .unwrap();
+        let mut subscriber = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap()
+            .with_history(4);
+        assert_eq!(
+            subscriber.frame_header(),
+            FrameHeader::new(WireCodec::Bincode.id(), i64::SCHEMA_VERSION)
+        );
+        let (current, mut stream) = subscriber.get_stream().await.unwrap();
+        assert_eq!(current, 0);
+
+        for value in 1..=3 {
+            publisher.publish(value).await.unwrap();
+        }
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            let value = tokio::time::timeout(Duration::from_secs(3), stream.next())
+                .await
+                .expect("no value within 3s")
+                .unwrap();
+            received.push(value.unwrap());
+        }
+        assert_eq!(received, vec![1, 2, 3]);
+        // The history loop has its own receiver and may trail the stream slightly
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while subscriber.history().len() < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("history not filled within 3s");
+        assert_eq!(subscriber.history(), vec![1, 2, 3]);
+        assert_eq!(subscriber.latest(), Some(3));
+        assert_eq!(subscriber.decode_error_count(), 0);
+    }
+}
@@ -0,0 +1,76 @@
+use agora::rawstream::{RawStreamClient, RawStreamServer};
+use agora::testkit::TestCluster;
+use futures_util::StreamExt;
+use std::time::Duration;
+
+const MAX_FRAME_BYTES: usize = 1024;
+
+/// Waits until `server` has at least one connected client, so published frames are not missed.
+async fn wait_for_subscriber(server: &RawStreamServer<Vec<u8>>) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.subscriber_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Client should connect through the gateway");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn payload_over_max_frame_bytes_is_rejected_on_publish() {
+        let cluster = TestCluster::start().await.unwrap();
+        let directory = format!("tests/frame_limit_{}", std::process::id());
+        let socket_path = format!("/tmp/agora/{}/rawstream.sock", directory);
+        let server: RawStreamServer<Vec<u8>> =
+            RawStreamServer::new(&socket_path, None, None, Some(MAX_FRAME_BYTES), None)
+                .await
+                .unwrap();
+        assert_eq!(server.max_frame_bytes(), MAX_FRAME_BYTES);
+
+        let client: RawStreamClient<Vec<u8>> = RawStreamClient::new(
+            cluster.gateway(),
+            &directory,
+            None,
+            None,
+            Some(MAX_FRAME_BYTES),
+        )
+        .unwrap();
+        let mut stream = client.subscribe();
+        wait_for_subscriber(&server).await;
+
+        // Just under the limit: delivered intact
+        let under = vec![1u8; MAX_FRAME_BYTES - 1];
+        server.publish(under.clone()).unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Payload under the limit should arrive")
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, under);
+
+        // Just over the limit: rejected with a clear error, nothing sent
+        let error = server.publish(vec![2u8; MAX_FRAME_BYTES + 1]).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("exceeds max_frame_bytes"), "{}", message);
+        assert!(
+            message.contains(&(MAX_FRAME_BYTES + 1).to_string()),
+            "{}",
+            message
+        );
+
+        // The connection survives the rejected publish; exactly at the limit still goes through
+        let exact = vec![3u8; MAX_FRAME_BYTES];
+        server.publish(exact.clone()).unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Payload at the limit should arrive")
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, exact);
+        assert_eq!(server.subscriber_count(), 1);
+    }
+}
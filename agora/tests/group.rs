@@ -0,0 +1,97 @@
+use agora::gateway::Gateway;
+use agora::metaserver::{AgoraClient, AgoraMetaServer};
+use agora::utils::TreeTrait;
+use agora::{ConnectionHandle, PublisherGroup, PublisherSpec};
+use std::time::Duration;
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora(offset: u16) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+fn specs(prefix: &str, names: &[&str]) -> Vec<PublisherSpec<i64>> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| PublisherSpec {
+            name: name.to_string(),
+            path: format!("{}/{}", prefix, name),
+            initial_value: i as i64,
+        })
+        .collect()
+}
+
+/// Number of registered paths under `prefix` (0 if the prefix is gone from the tree)
+async fn children_under(metaserver: ConnectionHandle, prefix: &str) -> usize {
+    let tree = AgoraClient::new(metaserver)
+        .await
+        .unwrap()
+        .get_path_tree()
+        .await
+        .unwrap();
+    tree.get_child(prefix)
+        .map(|node| node.children().len())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn mid_batch_failure_rolls_back_registered_publishers() {
+        let (metaserver, gateway_port, _gateway) = start_agora(0).await;
+        let prefix = format!("tests/group_{}/rollback", std::process::id());
+
+        // "a/nested" cannot live under publisher "a": the third registration fails
+        let result = PublisherGroup::register_all(
+            specs(&prefix, &["a", "b", "a/nested", "c"]),
+            metaserver,
+            gateway_port,
+        )
+        .await;
+        let err = result.err().expect("batch with a conflicting path should fail");
+        assert!(format!("{:#}", err).contains("rolled back 2 registered publishers"));
+        assert_eq!(children_under(metaserver, &prefix).await, 0);
+
+        // The same paths register cleanly afterwards
+        let group = PublisherGroup::register_all(specs(&prefix, &["a", "b"]), metaserver, gateway_port)
+            .await
+            .unwrap();
+        assert_eq!(group.len(), 2);
+        assert_eq!(children_under(metaserver, &prefix).await, 2);
+        group.shutdown().await.unwrap();
+        assert_eq!(children_under(metaserver, &prefix).await, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dropping_group_deregisters_all_paths() {
+        let (metaserver, gateway_port, _gateway) = start_agora(1).await;
+        let prefix = format!("tests/group_{}/drop", std::process::id());
+
+        let mut group =
+            PublisherGroup::register_all(specs(&prefix, &["x", "y", "z"]), metaserver, gateway_port)
+                .await
+                .unwrap();
+        assert_eq!(children_under(metaserver, &prefix).await, 3);
+        group.get_mut(1).unwrap().publish(42).await.unwrap();
+
+        drop(group);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(children_under(metaserver, &prefix).await, 0);
+    }
+}
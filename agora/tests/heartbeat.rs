@@ -0,0 +1,79 @@
+use agora::rawstream::{Heartbeat, RawStreamClient, RawStreamServer};
+use agora::testkit::TestCluster;
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::net::UnixStream;
+use tokio_tungstenite::client_async;
+
+/// Pings every 50ms, drops clients silent for 300ms
+fn fast_heartbeat() -> Heartbeat {
+    Heartbeat {
+        interval: Duration::from_millis(50),
+        timeout: Duration::from_millis(300),
+    }
+}
+
+async fn start_server(directory: &str) -> RawStreamServer<Vec<u8>> {
+    let socket_path = format!("/tmp/agora/{}/rawstream.sock", directory);
+    RawStreamServer::new(&socket_path, None, None, None, Some(fast_heartbeat()))
+        .await
+        .unwrap()
+}
+
+/// Polls `condition` until it holds; false on timeout
+async fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn server_reaps_subscriber_that_stops_acking() {
+        let directory = format!("tests/heartbeat_ghost_{}", std::process::id());
+        let server = start_server(&directory).await;
+
+        // A client that crashed without closing: the socket stays open but nothing reads or acks
+        let stream = UnixStream::connect(format!("/tmp/agora/{}/rawstream.sock", directory))
+            .await
+            .unwrap();
+        let (_ghost, _) = client_async("ws://localhost/", stream).await.unwrap();
+        assert!(wait_until(|| server.subscriber_count() == 1).await);
+
+        assert!(
+            wait_until(|| server.subscriber_count() == 0).await,
+            "Ghost subscriber should be dropped after the heartbeat timeout"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn acking_subscriber_outlives_heartbeat_timeout() {
+        let cluster = TestCluster::start().await.unwrap();
+        let directory = format!("tests/heartbeat_live_{}", std::process::id());
+        let server = start_server(&directory).await;
+
+        let client: RawStreamClient<Vec<u8>> =
+            RawStreamClient::new(cluster.gateway(), &directory, None, None, None).unwrap();
+        let mut stream = client.subscribe();
+        assert!(wait_until(|| server.subscriber_count() == 1).await);
+
+        // Several timeouts pass with no data: acks through the gateway keep the client connected
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+        assert_eq!(server.subscriber_count(), 1);
+
+        server.publish(b"still here".to_vec()).unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Update should arrive")
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, b"still here".to_vec());
+    }
+}
@@ -0,0 +1,104 @@
+use agora::testkit::TestCluster;
+use agora::{Publisher, Subscriber};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+/// Polls `subscriber.latest()` until it equals `expected`
+async fn wait_for_latest(subscriber: &Subscriber<i64>, expected: i64) {
+    tokio::time::timeout(Duration::from_secs(3), async {
+        while subscriber.latest() != Some(expected) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("latest update should arrive");
+}
+
+async fn publisher(cluster: &TestCluster, path: &str) -> Publisher<i64> {
+    Publisher::<i64>::new(
+        "history".to_string(),
+        path.to_string(),
+        -1,
+        cluster.metaserver(),
+        cluster.gateway_port(),
+    )
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn overflowing_history_keeps_the_newest_in_order() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/history_{}/overflow", std::process::id());
+        let mut publisher = publisher(&cluster, &path).await;
+
+        let mut subscriber = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap()
+            .with_history(5);
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+        assert!(subscriber.history().is_empty());
+
+        publisher.publish(0).await.unwrap();
+        publisher.publish(1).await.unwrap();
+        wait_for_latest(&subscriber, 1).await;
+        assert_eq!(subscriber.history(), vec![0, 1]);
+
+        for value in 2..12 {
+            publisher.publish(value).await.unwrap();
+        }
+        wait_for_latest(&subscriber, 11).await;
+        assert_eq!(subscriber.history(), (7..12).collect::<Vec<_>>());
+        // `latest` is the newest entry of the history
+        assert_eq!(subscriber.latest(), subscriber.history().last().copied());
+
+        // The stream still sees every update
+        let received: Vec<i64> = tokio::time::timeout(
+            Duration::from_secs(3),
+            stream.by_ref().take(12).map(|v| v.unwrap()).collect(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(received, (0..12).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn with_latest_and_resizing_share_one_history() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/history_{}/resize", std::process::id());
+        let mut publisher = publisher(&cluster, &path).await;
+
+        // `with_latest` is a history of one; it does not shrink an existing history
+        let latest_only = Subscriber::<i64>::new(path.clone(), cluster.metaserver())
+            .await
+            .unwrap()
+            .with_latest();
+        let subscriber = Subscriber::<i64>::new(path.clone(), cluster.metaserver())
+            .await
+            .unwrap()
+            .with_history(4)
+            .with_latest();
+        let untracked = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+
+        for value in 0..6 {
+            publisher.publish(value).await.unwrap();
+        }
+        wait_for_latest(&subscriber, 5).await;
+        wait_for_latest(&latest_only, 5).await;
+        assert_eq!(subscriber.history(), vec![2, 3, 4, 5]);
+        assert_eq!(latest_only.history(), vec![5]);
+        assert!(untracked.history().is_empty());
+
+        let subscriber = subscriber.with_history(2);
+        assert_eq!(subscriber.history(), vec![4, 5]);
+        publisher.publish(6).await.unwrap();
+        wait_for_latest(&subscriber, 6).await;
+        assert_eq!(subscriber.history(), vec![5, 6]);
+    }
+}
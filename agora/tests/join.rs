@@ -0,0 +1,180 @@
+use agora::testkit::TestCluster;
+use agora::{JoinColdStart, JoinSide, JoinSubscriber, Joined, Publisher};
+use futures_util::StreamExt;
+use futures_util::stream::Stream;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::pin::Pin;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Bbo {
+    bid: f64,
+    ask: f64,
+}
+
+impl fmt::Display for Bbo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} / {}", self.bid, self.ask)
+    }
+}
+
+impl agora::Agorable for Bbo {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Trade {
+    price: f64,
+    size: f64,
+}
+
+impl fmt::Display for Trade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} @ {}", self.size, self.price)
+    }
+}
+
+impl agora::Agorable for Trade {}
+
+fn bbo(bid: f64) -> Bbo {
+    Bbo {
+        bid,
+        ask: bid + 1.0,
+    }
+}
+
+fn trade(price: f64) -> Trade {
+    Trade { price, size: 0.5 }
+}
+
+type JoinedStream = Pin<Box<dyn Stream<Item = agora::utils::OrError<Joined<Bbo, Trade>>> + Send>>;
+
+/// Publishers of one symbol's bbo and trades, plus a join over them.
+/// The join is returned too: its stream ends once the `JoinSubscriber` is dropped.
+async fn joined_feeds(
+    cluster: &TestCluster,
+    name: &str,
+    cold_start: JoinColdStart,
+) -> (
+    Publisher<Bbo>,
+    Publisher<Trade>,
+    JoinSubscriber<Bbo, Trade>,
+    JoinedStream,
+) {
+    let prefix = format!("tests/join_{}_{}", name, std::process::id());
+    let (bbo_path, trade_path) = (
+        format!("{}/bbo/BTC", prefix),
+        format!("{}/last_trade/BTC", prefix),
+    );
+    let bbo_publisher = Publisher::<Bbo>::new(
+        "bbo".to_string(),
+        bbo_path.clone(),
+        bbo(0.0),
+        cluster.metaserver(),
+        cluster.gateway_port(),
+    )
+    .await
+    .unwrap();
+    let trade_publisher = Publisher::<Trade>::new(
+        "trade".to_string(),
+        trade_path.clone(),
+        trade(0.0),
+        cluster.metaserver(),
+        cluster.gateway_port(),
+    )
+    .await
+    .unwrap();
+    let mut join = JoinSubscriber::<Bbo, Trade>::new(
+        bbo_path,
+        trade_path,
+        cluster.metaserver(),
+        Some(cold_start),
+    )
+    .await
+    .unwrap();
+    assert_eq!(join.cold_start(), cold_start);
+    let stream = join.get_stream().await.unwrap();
+    (bbo_publisher, trade_publisher, join, stream)
+}
+
+async fn next(stream: &mut JoinedStream) -> Joined<Bbo, Trade> {
+    tokio::time::timeout(Duration::from_secs(3), stream.next())
+        .await
+        .expect("a joined record should arrive")
+        .unwrap()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn waits_for_both_inputs_then_carries_last_values() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (mut bbos, mut trades, _join, mut joined) =
+            joined_feeds(&cluster, "wait", JoinColdStart::WaitForAll).await;
+
+        // Nothing is emitted until both sides have updated; the second arrival triggers the first record
+        bbos.publish(bbo(100.0)).await.unwrap();
+        trades.publish(trade(100.5)).await.unwrap();
+        let first = next(&mut joined).await;
+        assert_eq!(first.clone().complete(), Some((bbo(100.0), trade(100.5))));
+
+        // From then on every update emits, carrying the other side's last value
+        bbos.publish(bbo(101.0)).await.unwrap();
+        let record = next(&mut joined).await;
+        assert_eq!(record.trigger, JoinSide::Left);
+        assert_eq!(record.complete(), Some((bbo(101.0), trade(100.5))));
+
+        trades.publish(trade(101.5)).await.unwrap();
+        let record = next(&mut joined).await;
+        assert_eq!(record.trigger, JoinSide::Right);
+        assert_eq!(record.complete(), Some((bbo(101.0), trade(101.5))));
+
+        trades.publish(trade(102.0)).await.unwrap();
+        let record = next(&mut joined).await;
+        assert_eq!(record.trigger, JoinSide::Right);
+        assert_eq!(record.left, Some(bbo(101.0)));
+        assert_eq!(record.right, Some(trade(102.0)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn emit_partial_starts_with_missing_sides() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (mut bbos, mut trades, _join, mut joined) =
+            joined_feeds(&cluster, "partial", JoinColdStart::EmitPartial).await;
+
+        trades.publish(trade(100.5)).await.unwrap();
+        assert_eq!(
+            next(&mut joined).await,
+            Joined {
+                left: None,
+                right: Some(trade(100.5)),
+                trigger: JoinSide::Right,
+            }
+        );
+
+        bbos.publish(bbo(100.0)).await.unwrap();
+        assert_eq!(
+            next(&mut joined).await,
+            Joined {
+                left: Some(bbo(100.0)),
+                right: Some(trade(100.5)),
+                trigger: JoinSide::Left,
+            }
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn seed_with_current_joins_against_publisher_values() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (_bbos, mut trades, _join, mut joined) =
+            joined_feeds(&cluster, "seed", JoinColdStart::SeedWithCurrent).await;
+
+        // The bbo publisher never updates: its initial value stands in
+        trades.publish(trade(100.5)).await.unwrap();
+        let record = next(&mut joined).await;
+        assert_eq!(record.trigger, JoinSide::Right);
+        assert_eq!(record.complete(), Some((bbo(0.0), trade(100.5))));
+    }
+}
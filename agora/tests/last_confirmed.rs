@@ -0,0 +1,59 @@
+use agora::gateway::Gateway;
+use agora::metaserver::{AgoraClient, AgoraMetaServer};
+use agora::{ConnectionHandle, Publisher};
+use std::time::Duration;
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora(offset: u16) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn last_confirmed_advances_across_pings() {
+        let (metaserver, gateway_port, _gateway) = start_agora(0).await;
+        let path = format!("tests/last_confirmed_{}/value", std::process::id());
+        let _publisher = Publisher::<i64>::new(
+            "confirmed".to_string(),
+            path.clone(),
+            0,
+            metaserver,
+            gateway_port,
+        )
+        .await
+        .unwrap();
+        let client = AgoraClient::new(metaserver).await.unwrap();
+
+        let first = client.get_publisher_info(&path).await.unwrap();
+        assert!(first.confirmed_ago() < chrono::TimeDelta::seconds(1));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let second = client.get_publisher_info(&path).await.unwrap();
+        assert!(
+            second.last_confirmed() > first.last_confirmed(),
+            "{} should be after {}",
+            second.last_confirmed(),
+            first.last_confirmed()
+        );
+        // Only the timestamp changes between lookups
+        assert_eq!(second.connection(), first.connection());
+        assert_eq!(second.path(), first.path());
+    }
+}
@@ -0,0 +1,65 @@
+use agora::testkit::TestCluster;
+use agora::{Publisher, Subscriber};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+/// Polls `subscriber.latest()` until it equals `expected`; the last value seen on timeout
+async fn wait_for_latest(subscriber: &Subscriber<i64>, expected: i64) -> Option<i64> {
+    let _ = tokio::time::timeout(Duration::from_secs(3), async {
+        while subscriber.latest() != Some(expected) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    subscriber.latest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn latest_tracks_newest_update_without_consuming_the_stream() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/latest_{}/value", std::process::id());
+        let mut publisher = Publisher::<i64>::new(
+            "latest".to_string(),
+            path.clone(),
+            -1,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+
+        let untracked = Subscriber::<i64>::new(path.clone(), cluster.metaserver())
+            .await
+            .unwrap();
+        let mut subscriber = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap()
+            .with_latest();
+        let (current, mut stream) = subscriber.get_stream().await.unwrap();
+        assert_eq!(current, -1);
+        // Nothing received yet: the current value is `get`'s, not `latest`'s
+        assert_eq!(subscriber.latest(), None);
+
+        for value in 0..10 {
+            publisher.publish(value).await.unwrap();
+        }
+        assert_eq!(wait_for_latest(&subscriber, 9).await, Some(9));
+        // Peeking is repeatable and leaves the stream intact
+        assert_eq!(subscriber.latest(), Some(9));
+        let received: Vec<i64> = tokio::time::timeout(
+            Duration::from_secs(3),
+            stream.by_ref().take(10).map(|v| v.unwrap()).collect(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+
+        publisher.publish(42).await.unwrap();
+        assert_eq!(wait_for_latest(&subscriber, 42).await, Some(42));
+        assert_eq!(untracked.latest(), None);
+    }
+}
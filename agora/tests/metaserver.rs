@@ -1,6 +1,10 @@
 mod common;
 
+use agora::ConnectionHandle;
+use agora::metaserver::{PathCharset, PublisherInfo, ServerState};
 use common::{create_test_server_state, default_test_connection, test_connection};
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
 
 #[cfg(test)]
 mod tests {
@@ -61,7 +65,7 @@ mod tests {
             "Should fail to register with empty path"
         );
         assert!(
-            empty_path_result.unwrap_err().contains("cannot be empty"),
+            empty_path_result.unwrap_err().to_string().contains("cannot be empty"),
             "Error message should indicate path cannot be empty"
         );
 
@@ -87,7 +91,7 @@ mod tests {
             "Should fail to register duplicate at same path"
         );
         assert!(
-            duplicate_result.unwrap_err().contains("already registered"),
+            duplicate_result.unwrap_err().to_string().contains("already registered"),
             "Error message should indicate publisher already registered"
         );
     }
@@ -121,6 +125,7 @@ mod tests {
         assert!(
             content_result
                 .unwrap_err()
+                .to_string()
                 .contains("associated with a publisher"),
             "Error message should indicate parent path is associated with a publisher"
         );
@@ -139,6 +144,7 @@ mod tests {
         assert!(
             deep_content_result
                 .unwrap_err()
+                .to_string()
                 .contains("associated with a publisher"),
             "Error message should indicate parent path 'dir' is associated with a publisher"
         );
@@ -174,6 +180,7 @@ mod tests {
         assert!(
             parent_result
                 .unwrap_err()
+                .to_string()
                 .contains("already exists as a directory"),
             "Error message should indicate path already exists as directory"
         );
@@ -199,6 +206,7 @@ mod tests {
         assert!(
             api_result
                 .unwrap_err()
+                .to_string()
                 .contains("already exists as a directory"),
             "Error message should indicate path already exists as directory"
         );
@@ -216,6 +224,7 @@ mod tests {
         assert!(
             api_v1_result
                 .unwrap_err()
+                .to_string()
                 .contains("already exists as a directory"),
             "Error message should indicate path already exists as directory"
         );
@@ -233,6 +242,7 @@ mod tests {
         assert!(
             users_result
                 .unwrap_err()
+                .to_string()
                 .contains("already exists as a directory"),
             "Error message should indicate path already exists as directory"
         );
@@ -251,16 +261,482 @@ mod tests {
             default_test_connection(),
         );
         assert!(register_result.is_err());
-        assert!(register_result.unwrap_err().contains("cannot be empty"));
+        assert!(register_result.unwrap_err().to_string().contains("cannot be empty"));
 
         // Remove with empty path
         let remove_result = process.remove_publisher("");
         assert!(remove_result.is_err());
-        assert!(remove_result.unwrap_err().contains("cannot be empty"));
+        assert!(remove_result.unwrap_err().to_string().contains("cannot be empty"));
 
         // Get publisher info with empty path
         let info_result = process.get_publisher_info("").await;
         assert!(info_result.is_err());
-        assert!(info_result.unwrap_err().contains("cannot be empty"));
+        assert!(info_result.unwrap_err().to_string().contains("cannot be empty"));
+    }
+
+    #[test]
+    fn path_tree_snapshot_does_not_block_registration() {
+        use std::sync::{Arc, RwLock};
+
+        let state = Arc::new(RwLock::new(create_test_server_state()));
+        state
+            .write()
+            .unwrap()
+            .register_publisher(
+                "users_publisher".to_string(),
+                "api/v1/users/stream".to_string(),
+                default_test_connection(),
+            )
+            .unwrap();
+
+        // Snapshot under the read lock, then release it before iterating
+        let snapshot = state.read().unwrap().path_tree_snapshot();
+
+        let writer_state = state.clone();
+        let writer = std::thread::spawn(move || {
+            for i in 0..100 {
+                writer_state
+                    .write()
+                    .unwrap()
+                    .register_publisher(
+                        format!("publisher_{}", i),
+                        format!("api/v2/stream_{}", i),
+                        test_connection(9000 + i as u16),
+                    )
+                    .unwrap();
+            }
+        });
+
+        // Iteration proceeds while registrations land on the live tree
+        let leaves: Vec<String> = snapshot.iter_leaves().collect();
+        writer.join().unwrap();
+
+        assert!(leaves.contains(&"api/v1/users/stream".to_string()));
+        assert!(
+            !leaves.iter().any(|leaf| leaf.starts_with("api/v2/stream_")),
+            "Snapshot should not observe registrations made after it was taken"
+        );
+        assert_eq!(
+            state.read().unwrap().path_tree_snapshot().glob("api/v2/*").len(),
+            100
+        );
+    }
+
+    #[tokio::test]
+    async fn server_status_tracks_registrations_and_prunes() {
+        let mut state = create_test_server_state();
+        let status = state.server_status();
+        assert_eq!(status.registered_count, 0);
+        assert_eq!(status.confirmed_count, 0);
+        assert_eq!(status.last_prune_removed, 0);
+        assert_eq!(status.total_pruned, 0);
+
+        for i in 0..3 {
+            state
+                .register_publisher(
+                    format!("publisher_{}", i),
+                    format!("api/v2/stream_{}", i),
+                    test_connection(9100 + i),
+                )
+                .unwrap();
+        }
+        state.remove_publisher("api/v2/stream_0").unwrap();
+
+        // Unconfirmed publishers are never pinged, so a prune pass removes nothing
+        assert!(state.prune_stale_publishers().await.is_empty());
+
+        let status = state.server_status();
+        assert_eq!(status.registered_count, 2);
+        assert_eq!(status.confirmed_count, 0);
+        assert_eq!(status.last_prune_removed, 0);
+        assert_eq!(status.total_pruned, 0);
+        assert!(state.server_status().uptime >= status.uptime);
+    }
+
+    #[tokio::test]
+    async fn unconfirmed_publishers_are_evicted_after_grace_period() {
+        let grace = Duration::from_millis(200);
+        let mut state = create_test_server_state().with_unconfirmed_grace(grace);
+        // Registered, then its `Publisher::new` crashed before confirming
+        let info = state
+            .register_publisher(
+                "zombie".to_string(),
+                "api/v2/zombie".to_string(),
+                test_connection(9200),
+            )
+            .unwrap();
+        assert!(info.registered_at() <= chrono::Utc::now());
+
+        // Within the grace period the reservation stands
+        assert!(state.prune_stale_publishers().await.is_empty());
+        assert!(state.publishers.contains_key("api/v2/zombie"));
+
+        tokio::time::sleep(grace + Duration::from_millis(100)).await;
+        assert_eq!(state.prune_stale_publishers().await, vec!["api/v2/zombie"]);
+        assert!(state.publishers.is_empty());
+        let status = state.server_status();
+        assert_eq!((status.last_prune_removed, status.total_pruned), (1, 1));
+
+        // The path is free for the next registration, whose grace starts afresh
+        state
+            .register_publisher(
+                "retry".to_string(),
+                "api/v2/zombie".to_string(),
+                test_connection(9201),
+            )
+            .unwrap();
+        assert!(state.prune_stale_publishers().await.is_empty());
+        assert!(state.publishers.contains_key("api/v2/zombie"));
+
+        // Restored publishers were registered long ago, but get a full grace period from the restore
+        let repr = state.to_snapshot_repr().unwrap();
+        tokio::time::sleep(grace + Duration::from_millis(100)).await;
+        let mut restored = ServerState::from_snapshot_repr(&repr)
+            .unwrap()
+            .with_unconfirmed_grace(grace);
+        assert!(restored.prune_stale_publishers().await.is_empty());
+        tokio::time::sleep(grace + Duration::from_millis(100)).await;
+        assert_eq!(
+            restored.prune_stale_publishers().await,
+            vec!["api/v2/zombie"]
+        );
+    }
+
+    #[test]
+    fn remove_subtree_removes_versioned_worker_set() {
+        use agora::utils::TreeTrait;
+
+        let mut state = create_test_server_state();
+        let mut port = 9200;
+        for version in ["perp_1", "perp_2", "perp_10"] {
+            for data_type in ["bbo", "last_trade"] {
+                for symbol in ["BTC", "ETH"] {
+                    state
+                        .register_publisher(
+                            format!("{}_{}", data_type, symbol),
+                            format!("argus/tmp/hyperliquid/{}/{}/{}", version, data_type, symbol),
+                            test_connection(port),
+                        )
+                        .unwrap();
+                    port += 1;
+                }
+            }
+        }
+
+        let removed = state.remove_subtree("argus/tmp/hyperliquid/perp_1").unwrap();
+        assert_eq!(
+            removed,
+            vec![
+                "argus/tmp/hyperliquid/perp_1/bbo/BTC",
+                "argus/tmp/hyperliquid/perp_1/bbo/ETH",
+                "argus/tmp/hyperliquid/perp_1/last_trade/BTC",
+                "argus/tmp/hyperliquid/perp_1/last_trade/ETH",
+            ]
+        );
+
+        // The emptied version directory is pruned; `perp_10` shares the string prefix but is a different segment
+        let tree = state.path_tree();
+        assert!(tree.get_child("argus/tmp/hyperliquid/perp_1").is_err());
+        assert!(tree.get_child("argus/tmp/hyperliquid/perp_10/bbo/BTC").is_ok());
+        assert!(tree.get_child("argus/tmp/hyperliquid/perp_2/last_trade/ETH").is_ok());
+        assert_eq!(state.server_status().registered_count, 8);
+
+        // Removing again is a no-op, as is a directory holding no publishers (which is left in place)
+        assert!(state.remove_subtree("argus/tmp/hyperliquid/perp_1").unwrap().is_empty());
+        assert!(state.remove_subtree("api/v1").unwrap().is_empty());
+        assert!(state.path_tree().get_child("api/v1/users").is_ok());
+
+        // A prefix naming a single publisher removes just that leaf
+        assert_eq!(
+            state.remove_subtree("argus/tmp/hyperliquid/perp_2/bbo/BTC").unwrap(),
+            vec!["argus/tmp/hyperliquid/perp_2/bbo/BTC"]
+        );
+
+        // The root is never a valid prefix
+        assert!(state.remove_subtree("").is_err());
+        assert!(state.remove_subtree("/").is_err());
+        assert_eq!(state.server_status().registered_count, 7);
+    }
+
+    #[test]
+    fn move_subtree_rekeys_publishers_and_prunes_source() {
+        use agora::utils::TreeTrait;
+
+        let mut state = create_test_server_state();
+        for (i, symbol) in ["BTC", "ETH"].iter().enumerate() {
+            state
+                .register_publisher(
+                    format!("bbo_{}", symbol),
+                    format!("argus/tmp/perp_1/bbo/{}", symbol),
+                    test_connection(9300 + i as u16),
+                )
+                .unwrap();
+        }
+        state
+            .register_publisher("solo".to_string(), "misc/solo".to_string(), test_connection(9310))
+            .unwrap();
+
+        // Renaming a directory carries its publishers along, keyed by their new paths
+        let moved = state.move_subtree("argus/tmp/perp_1", "argus/tmp/perp_2").unwrap();
+        assert_eq!(moved, vec!["argus/tmp/perp_2/bbo/BTC", "argus/tmp/perp_2/bbo/ETH"]);
+        assert!(!state.publishers.contains_key("argus/tmp/perp_1/bbo/BTC"));
+        let info = state.publishers.get("argus/tmp/perp_2/bbo/BTC").unwrap();
+        // The publisher itself did not move: it still serves from its original path
+        assert_eq!(info.path(), "argus/tmp/perp_1/bbo/BTC");
+        assert_eq!(*info.connection(), test_connection(9300));
+
+        // Moving the only leaf of a directory prunes the emptied directory
+        assert_eq!(state.move_subtree("misc/solo", "api/solo").unwrap(), vec!["api/solo"]);
+        assert!(state.path_tree().get_child("misc").is_err());
+        assert!(state.publishers.contains_key("api/solo"));
+
+        // Destination taken, or below a publisher
+        assert!(state.move_subtree("api/solo", "argus/tmp/perp_2/bbo/ETH").is_err());
+        assert!(state.move_subtree("api/v2", "api/solo/v2").is_err());
+        assert!(state.path_tree().get_child("api/v2").is_ok());
+        assert_eq!(state.server_status().registered_count, 3);
+    }
+
+    #[test]
+    fn publishers_by_host_partitions_by_gateway_address() {
+        let mut state = create_test_server_state();
+        let host_a = test_connection(8081);
+        let host_a_other_port = test_connection(8082);
+        let host_b = ConnectionHandle::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)), 8081);
+        for (path, host) in [
+            ("feeds/a/BTC", host_a),
+            ("feeds/a/ETH", host_a),
+            ("feeds/a2/SOL", host_a_other_port),
+            ("feeds/b/BTC", host_b),
+        ] {
+            state
+                .register_publisher(path.replace('/', "_"), path.to_string(), host)
+                .unwrap();
+        }
+
+        // By IP alone, both of host A's gateways count; the same port on another IP does not
+        assert_eq!(
+            state.publishers_by_host(&host_a, false),
+            vec!["feeds/a/BTC", "feeds/a/ETH", "feeds/a2/SOL"]
+        );
+        assert_eq!(
+            state.publishers_by_host(&host_a, true),
+            vec!["feeds/a/BTC", "feeds/a/ETH"]
+        );
+        assert_eq!(
+            state.publishers_by_host(&host_b, false),
+            vec!["feeds/b/BTC"]
+        );
+        assert_eq!(
+            state.publishers_by_host(&host_b, true),
+            state.publishers_by_host(&host_b, false)
+        );
+
+        // Nothing served from an unknown host; removed publishers drop out
+        let host_c = ConnectionHandle::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 3)), 8081);
+        assert!(state.publishers_by_host(&host_c, false).is_empty());
+        state.remove_publisher("feeds/a/ETH").unwrap();
+        assert_eq!(state.publishers_by_host(&host_a, true), vec!["feeds/a/BTC"]);
+    }
+
+    #[test]
+    fn find_publishers_matches_registered_names() {
+        let mut state = create_test_server_state();
+        for (name, path) in [
+            ("BTC", "argus/hyperliquid/v2/perp/trades/BTC"),
+            ("BTC", "argus/hyperliquid/v3/perp/trades/BTC"),
+            ("ETH", "argus/hyperliquid/v3/perp/trades/ETH"),
+            ("kBTC", "argus/hyperliquid/v3/spot/trades/kBTC"),
+            ("monitor", "tools/BTC_monitor"),
+        ] {
+            state
+                .register_publisher(name.to_string(), path.to_string(), test_connection(8081))
+                .unwrap();
+        }
+
+        // Names are searched, not paths: `tools/BTC_monitor` is registered as "monitor"
+        let found = state.find_publishers("BTC");
+        let paths: Vec<&str> = found.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "argus/hyperliquid/v2/perp/trades/BTC",
+                "argus/hyperliquid/v3/perp/trades/BTC",
+                "argus/hyperliquid/v3/spot/trades/kBTC",
+            ]
+        );
+        assert_eq!(found[1].1.name(), "BTC");
+        assert_eq!(found[1].1.path(), "argus/hyperliquid/v3/perp/trades/BTC");
+
+        assert_eq!(state.find_publishers("ETH").len(), 1);
+        assert!(state.find_publishers("btc").is_empty());
+        assert!(state.find_publishers("SOL").is_empty());
+        assert_eq!(state.find_publishers("").len(), 5);
+
+        state
+            .remove_publisher("argus/hyperliquid/v2/perp/trades/BTC")
+            .unwrap();
+        assert_eq!(state.find_publishers("BTC").len(), 2);
+    }
+
+    #[test]
+    fn path_validation_rejects_control_characters_and_relative_segments() {
+        let mut state = create_test_server_state();
+        assert_eq!(state.path_charset(), PathCharset::Permissive);
+
+        // Control characters would end up in the scribe's directory names
+        for path in ["bbo/BTC\u{7}", "bb\no/BTC", "bbo/\u{0}BTC", "bbo/BT\u{1b}C"] {
+            let err = state
+                .register_publisher("bad".to_string(), path.to_string(), test_connection(9400))
+                .unwrap_err();
+            assert!(err.to_string().contains("control character"), "{}", err);
+        }
+
+        // "." and ".." would escape the publisher's directory once joined onto a filesystem path
+        for path in ["bbo/../BTC", "../etc", "bbo/."] {
+            let err = state
+                .register_publisher("bad".to_string(), path.to_string(), test_connection(9400))
+                .unwrap_err();
+            assert!(err.to_string().contains("relative segment"), "{}", err);
+        }
+        assert!(state.publishers.is_empty());
+
+        // Permissive still accepts non-portable printable characters
+        for (i, path) in ["bbo/BTC:USDT", "bbo/ビットコイン", "bbo/a b"]
+            .iter()
+            .enumerate()
+        {
+            state
+                .register_publisher(
+                    "ok".to_string(),
+                    path.to_string(),
+                    test_connection(9401 + i as u16),
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn portable_charset_rejects_filesystem_unsafe_characters() {
+        let mut state = create_test_server_state().with_path_charset(PathCharset::Portable);
+
+        // ':' is illegal in Windows filenames, '*'/'?' are globs, '\\' is a Windows separator
+        for (path, bad) in [
+            ("bbo/BTC:USDT", ':'),
+            ("bbo/BTC*", '*'),
+            ("bbo/what?", '?'),
+            ("bbo/a\\b", '\\'),
+            ("bbo/a b", ' '),
+            ("bbo/ビット", 'ビ'),
+        ] {
+            let err = state
+                .register_publisher("bad".to_string(), path.to_string(), test_connection(9410))
+                .unwrap_err();
+            let message = err.to_string();
+            assert!(message.contains("portable charset"), "{}", message);
+            assert!(message.contains(&format!("{:?}", bad)), "{}", message);
+        }
+        assert!(state.publishers.is_empty());
+
+        state
+            .register_publisher(
+                "ok".to_string(),
+                "argus/perp_1.2/bbo-v2/BTC_USDT@binance".to_string(),
+                test_connection(9411),
+            )
+            .unwrap();
+        // Rename destinations are held to the same charset
+        assert!(state.move_subtree("argus", "argus:old").is_err());
+    }
+
+    #[test]
+    fn snapshot_repr_round_trips_publishers_and_directories() {
+        use agora::utils::TreeTrait;
+
+        let mut state = create_test_server_state();
+        for (i, path) in [
+            "api/v1/users/feed",
+            "api/v1/posts/feed",
+            "argus/perp/bbo/BTC",
+        ]
+        .iter()
+        .enumerate()
+        {
+            state
+                .register_publisher(
+                    format!("publisher_{}", i),
+                    path.to_string(),
+                    test_connection(9500 + i as u16),
+                )
+                .unwrap();
+        }
+        // A moved publisher keeps serving from its original path; the snapshot must keep both
+        state.move_subtree("argus/perp", "argus/perp_v2").unwrap();
+
+        let repr = state.to_snapshot_repr().unwrap();
+        let restored = ServerState::from_snapshot_repr(&repr).unwrap();
+        assert_eq!(restored.publishers, state.publishers);
+        assert_eq!(
+            restored.publishers["argus/perp_v2/bbo/BTC"].path(),
+            "argus/perp/bbo/BTC"
+        );
+        // Empty directories survive; the plain repr cannot tell them from publishers, the snapshot can
+        assert_eq!(restored.get_path_tree_repr(), state.get_path_tree_repr());
+        assert!(restored.path_tree().get_child("admin").unwrap().is_leaf());
+        assert!(!restored.publishers.contains_key("admin"));
+
+        // Nothing is confirmed yet: every publisher awaits re-confirmation
+        assert!(restored.confirmed_publishers.is_empty());
+        assert_eq!(
+            restored.unconfirmed_paths(),
+            vec![
+                "api/v1/posts/feed",
+                "api/v1/users/feed",
+                "argus/perp_v2/bbo/BTC"
+            ]
+        );
+        // The restored registry enforces the usual invariants
+        let mut restored = restored;
+        assert!(
+            restored
+                .register_publisher(
+                    "dup".to_string(),
+                    "api/v1/users/feed".to_string(),
+                    default_test_connection()
+                )
+                .is_err()
+        );
+        assert!(
+            restored
+                .register_publisher(
+                    "child".to_string(),
+                    "api/v1/users/feed/child".to_string(),
+                    default_test_connection()
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn snapshot_repr_rejects_invalid_trees() {
+        let info =
+            serde_json::to_value(PublisherInfo::new("p", default_test_connection(), "a")).unwrap();
+        let invalid = [
+            "not json".to_string(),
+            // Publisher with children
+            serde_json::json!({"name": "agora", "children": [
+                {"name": "a", "publisher": info, "children": [{"name": "b"}]}
+            ]})
+            .to_string(),
+            // Duplicate siblings
+            serde_json::json!({"name": "agora", "children": [{"name": "a"}, {"name": "a"}]})
+                .to_string(),
+            // Names that are not single path segments
+            serde_json::json!({"name": "agora", "children": [{"name": "a/b"}]}).to_string(),
+            serde_json::json!({"name": "agora", "children": [{"name": ".."}]}).to_string(),
+        ];
+        for repr in invalid {
+            assert!(ServerState::from_snapshot_repr(&repr).is_err(), "{}", repr);
+        }
     }
 }
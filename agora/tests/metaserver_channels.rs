@@ -0,0 +1,74 @@
+use agora::ConnectionHandle;
+use agora::metaserver::{AgoraClient, AgoraMetaServer};
+use agora::testkit::TestCluster;
+use futures_util::future::join_all;
+use std::time::Duration;
+
+/// Opens `count` metaserver clients at once; each holds its own TARPC channel until dropped.
+async fn open_clients(metaserver: ConnectionHandle, count: usize) -> Vec<AgoraClient> {
+    join_all((0..count).map(|_| AgoraClient::new(metaserver)))
+        .await
+        .into_iter()
+        .map(|client| client.unwrap())
+        .collect()
+}
+
+/// Whether `client` gets a `get_server_status` answer within `timeout`
+async fn is_served(client: &AgoraClient, timeout: Duration) -> bool {
+    matches!(
+        tokio::time::timeout(timeout, client.get_server_status()).await,
+        Ok(Ok(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn serves_more_than_ten_simultaneous_channels() {
+        let cluster = TestCluster::start().await.unwrap();
+        let clients = open_clients(cluster.metaserver(), 64).await;
+
+        // Every channel stays open while all of them query concurrently
+        let served = join_all(
+            clients
+                .iter()
+                .map(|client| is_served(client, Duration::from_secs(5))),
+        )
+        .await;
+        assert!(served.iter().all(|ok| *ok), "{:?}", served);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn channels_beyond_max_channels_wait_for_a_free_slot() {
+        let port = std::net::TcpListener::bind(("0.0.0.0", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let metaserver = ConnectionHandle::new_local(port).unwrap();
+        tokio::spawn(AgoraMetaServer::run_server(
+            metaserver.addr(),
+            port,
+            None,
+            Some(2),
+            None,
+            None,
+        ));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let mut clients = open_clients(metaserver, 2).await;
+        for client in &clients {
+            assert!(is_served(client, Duration::from_secs(5)).await);
+        }
+
+        // Third connection queues behind the cap
+        let waiting = AgoraClient::new(metaserver).await.unwrap();
+        assert!(!is_served(&waiting, Duration::from_millis(300)).await);
+
+        // Closing one channel frees its slot for the waiting one
+        clients.pop();
+        assert!(is_served(&waiting, Duration::from_secs(5)).await);
+    }
+}
@@ -0,0 +1,147 @@
+use agora::rawstream::{MuxConnection, RawStreamClient, RawStreamServer};
+use agora::testkit::TestCluster;
+use agora::{Publisher, Subscriber};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+/// Polls `condition` until it holds; false on timeout
+async fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+/// Collects `count` stream items, failing the test on timeout
+async fn take_items<T>(
+    stream: impl futures_util::Stream<Item = T> + Unpin,
+    count: usize,
+) -> Vec<T> {
+    tokio::time::timeout(Duration::from_secs(5), stream.take(count).collect())
+        .await
+        .expect("stream items did not arrive in time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn logical_subscriptions_share_one_connection_and_get_only_their_path() {
+        let cluster = TestCluster::start().await.unwrap();
+        let prefix = format!("tests/mux_{}/raw", std::process::id());
+        let paths: Vec<String> = (0..16).map(|i| format!("{}/{}", prefix, i)).collect();
+
+        let mut servers = Vec::new();
+        for (i, path) in paths.iter().enumerate() {
+            let server: RawStreamServer<Vec<u8>> = RawStreamServer::with_handshake(
+                &format!("/tmp/agora/{}/rawstream.sock", path),
+                None,
+                None,
+                Some(format!("server {}", i)),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            servers.push(server);
+        }
+
+        let mux = MuxConnection::connect(cluster.gateway(), None);
+        let clients: Vec<RawStreamClient<Vec<u8>>> = paths
+            .iter()
+            .map(|path| RawStreamClient::multiplexed(mux.clone(), path, None))
+            .collect();
+        assert_eq!(mux.subscription_count(), 16);
+
+        // Each handshake reaches only its own subscription
+        for (i, client) in clients.iter().enumerate() {
+            let handshake = client.handshake(Duration::from_secs(5)).await.unwrap();
+            assert_eq!(handshake, format!("server {}", i));
+        }
+        assert!(wait_until(|| servers.iter().all(|s| s.subscriber_count() == 1)).await);
+
+        let streams: Vec<_> = clients.iter().map(|client| client.subscribe()).collect();
+        for seq in 0..5 {
+            for (i, server) in servers.iter().enumerate() {
+                server
+                    .publish(format!("{}:{}", i, seq).into_bytes())
+                    .unwrap();
+            }
+        }
+        for (i, stream) in streams.into_iter().enumerate() {
+            let received: Vec<String> = take_items(stream, 5)
+                .await
+                .into_iter()
+                .map(|frame| String::from_utf8(frame.unwrap()).unwrap())
+                .collect();
+            let expected: Vec<String> = (0..5).map(|seq| format!("{}:{}", i, seq)).collect();
+            assert_eq!(received, expected);
+        }
+
+        // Dropping the clients unsubscribes them and closes their connections behind the gateway
+        drop(clients);
+        assert_eq!(mux.subscription_count(), 0);
+        assert!(wait_until(|| servers.iter().all(|s| s.subscriber_count() == 0)).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn multiplexed_subscribers_share_the_gateway_connection() {
+        let cluster = TestCluster::start().await.unwrap();
+        let prefix = format!("tests/mux_{}/typed", std::process::id());
+
+        let mut publishers = Vec::new();
+        let mut subscribers = Vec::new();
+        for i in 0..8i64 {
+            let path = format!("{}/{}", prefix, i);
+            let publisher = Publisher::<i64>::new(
+                format!("value {}", i),
+                path.clone(),
+                -i,
+                cluster.metaserver(),
+                cluster.gateway_port(),
+            )
+            .await
+            .unwrap();
+            publishers.push(publisher);
+            subscribers.push(
+                Subscriber::<i64>::multiplexed(path, cluster.metaserver())
+                    .await
+                    .unwrap(),
+            );
+        }
+        let mux = MuxConnection::shared(cluster.gateway());
+        assert_eq!(mux.subscription_count(), 8);
+
+        let mut streams = Vec::new();
+        for (i, subscriber) in subscribers.iter_mut().enumerate() {
+            let (current, stream) = subscriber.get_stream().await.unwrap();
+            assert_eq!(current, -(i as i64));
+            streams.push(stream);
+        }
+        assert!(wait_until(|| publishers.iter().all(|p| p.subscriber_count() == 1)).await);
+
+        for (i, publisher) in publishers.iter_mut().enumerate() {
+            for seq in 0..3 {
+                publisher.publish(i as i64 * 100 + seq).await.unwrap();
+            }
+        }
+        for (i, stream) in streams.into_iter().enumerate() {
+            let received: Vec<i64> = take_items(stream, 3)
+                .await
+                .into_iter()
+                .map(|value| value.unwrap())
+                .collect();
+            let base = i as i64 * 100;
+            assert_eq!(received, vec![base, base + 1, base + 2]);
+        }
+
+        drop(subscribers);
+        assert_eq!(mux.subscription_count(), 0);
+        assert!(wait_until(|| publishers.iter().all(|p| p.subscriber_count() == 0)).await);
+    }
+}
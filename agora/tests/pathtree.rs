@@ -1,4 +1,5 @@
-use agora::utils::{TreeNode, TreeTrait};
+use agora::constants::TREE_REPR_MAX_DEPTH;
+use agora::utils::{PathTreeSnapshot, TreeNode, TreeTrait};
 use std::sync::Arc;
 
 fn create_test_sample() -> Arc<TreeNode> {
@@ -10,7 +11,7 @@ fn create_test_sample() -> Arc<TreeNode> {
     target.add_children(&["debug", "release"]);
     let tests = root.get_child("tests").unwrap();
     tests.add_children(&["test1.rs", "test2.rs"]);
-    return root;
+    root
 }
 
 fn create_test_sample_with_branch() -> Arc<TreeNode> {
@@ -22,7 +23,12 @@ fn create_test_sample_with_branch() -> Arc<TreeNode> {
     test.add_children(&["test_folder", "test_file"]);
     let folder1 = test.get_child("test_folder").unwrap();
     folder1.add_children(&["test.rs"]);
-    return root;
+    root
+}
+
+/// `{"d":[{"d":[ ... "leaf" ... ]}]}` with `depth` directories above the leaf
+fn nested_repr(depth: usize) -> String {
+    format!("{}\"leaf\"{}", "{\"d\":[".repeat(depth), "]}".repeat(depth))
 }
 
 #[cfg(test)]
@@ -169,11 +175,10 @@ mod tests {
             "Tree structure should match expected format"
         );
         assert_eq!(
-            root.remove_child_and_branch("test/test_folder"),
-            Err(
-                "Agora utils::TreeNode::remove_child_and_branch Error: cannot remove non-leaf node"
-                    .to_string()
-            ),
+            root.remove_child_and_branch("test/test_folder")
+                .unwrap_err()
+                .to_string(),
+            "Agora utils::TreeNode::remove_child_and_branch Error: cannot remove non-leaf node",
         );
         // Removing root should fail
         match root.remove_child_and_branch("") {
@@ -290,10 +295,127 @@ mod tests {
         // Test case 4: Completely invalid string
         let invalid_repr = "this is not a valid tree representation";
         assert!(
-            TreeNode::from_repr(&invalid_repr).is_err(),
+            TreeNode::from_repr(invalid_repr).is_err(),
             "Should fail with completely invalid string"
         );
 
         println!("All repr conversion tests passed!");
     }
+
+    #[test]
+    fn snapshot_iteration_and_glob() {
+        let root = create_test_sample_with_branch();
+        let snapshot = PathTreeSnapshot::of_node(&root);
+
+        let leaves: Vec<String> = snapshot.iter_leaves().collect();
+        assert_eq!(
+            leaves,
+            vec!["src/main.rs", "test/test_folder/test.rs", "test/test_file"]
+        );
+
+        let paths: Vec<String> = snapshot.iter_paths().collect();
+        assert_eq!(paths.len(), 6);
+        assert!(paths.contains(&"test/test_folder".to_string()));
+
+        assert_eq!(snapshot.glob("test/*"), vec!["test/test_folder", "test/test_file"]);
+        assert_eq!(snapshot.glob("**/*.rs"), vec!["src/main.rs", "test/test_folder/test.rs"]);
+        assert_eq!(snapshot.glob("test/test_f*"), vec!["test/test_folder", "test/test_file"]);
+        assert!(snapshot.glob("missing/*").is_empty());
+
+        assert!(snapshot.get("test/test_folder/test.rs").unwrap().is_leaf());
+        assert!(snapshot.get("test/nonexistent").is_none());
+
+        // Mutating the live tree does not affect the snapshot
+        root.get_child("src").unwrap().add_children(&["lib.rs"]);
+        assert_eq!(snapshot.iter_leaves().count(), 3);
+        assert_eq!(PathTreeSnapshot::of_node(&root).iter_leaves().count(), 4);
+    }
+
+    #[test]
+    fn from_repr_rejects_pathological_depth() {
+        // Within the limit: parses, and the leaf sits TREE_REPR_MAX_DEPTH levels down
+        let tree = TreeNode::from_repr(&nested_repr(TREE_REPR_MAX_DEPTH)).unwrap();
+        let leaf_path = format!("{}leaf", "d/".repeat(TREE_REPR_MAX_DEPTH - 1));
+        assert!(tree.get_child(&leaf_path).unwrap().is_leaf());
+
+        // One past the limit, and far past it: an error instead of a stack overflow
+        assert!(TreeNode::from_repr(&nested_repr(TREE_REPR_MAX_DEPTH + 1)).is_err());
+        let err = TreeNode::from_repr(&nested_repr(200_000)).unwrap_err();
+        assert!(err.to_string().contains("max depth"), "unexpected error: {}", err);
+
+        // Limits are configurable
+        assert!(TreeNode::from_repr_with_limits(&nested_repr(3), 3, 100).is_ok());
+        assert!(TreeNode::from_repr_with_limits(&nested_repr(4), 3, 100).is_err());
+    }
+
+    #[test]
+    fn from_repr_rejects_too_many_nodes() {
+        let repr = create_test_sample().to_repr(); // 11 nodes
+        assert!(TreeNode::from_repr_with_limits(&repr, 8, 11).is_ok());
+        let err = TreeNode::from_repr_with_limits(&repr, 8, 10).unwrap_err();
+        assert!(err.to_string().contains("max node count"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn from_repr_rejects_slash_in_name() {
+        // A malformed repr must not reach the panicking assertion in `TreeNode::new`
+        let leaf = r#"{"project":["src/main.rs"]}"#;
+        let err = TreeNode::from_repr(leaf).unwrap_err();
+        assert!(err.to_string().contains("slashes"), "unexpected error: {}", err);
+
+        let directory = r#"{"project":[{"src/bin":["main.rs"]}]}"#;
+        assert!(TreeNode::from_repr(directory).is_err());
+        assert!(TreeNode::from_repr(r#"{"a/b":["c"]}"#).is_err());
+
+        // Degenerate quotes are format errors, not slicing panics
+        assert!(TreeNode::from_repr("\"").is_err());
+        assert!(TreeNode::from_repr(r#"{":["c"]}"#).is_err());
+    }
+
+    #[test]
+    fn move_subtree_relocates_leaf_and_subtree() {
+        let root = create_test_sample();
+
+        // Leaf, renamed on the way
+        root.move_subtree("src/utils.rs", "docs/helpers.rs").unwrap();
+        assert!(root.get_child("src/utils.rs").is_err());
+        let moved = root.get_child("docs/helpers.rs").unwrap();
+        assert_eq!(moved.path(), "/project/docs/helpers.rs");
+        assert_eq!(root.get_child("src").unwrap().children().len(), 1);
+
+        // Directory with children, renamed: children follow and point at the new parent
+        root.move_subtree("target", "tests/build").unwrap();
+        assert!(root.get_child("target").is_err());
+        let release = root.get_child("tests/build/release").unwrap();
+        assert_eq!(release.path(), "/project/tests/build/release");
+        assert_eq!(release.parent().unwrap().name(), "build");
+        assert_eq!(root.get_child("tests/build").unwrap().children().len(), 2);
+
+        // Same name, new parent
+        root.move_subtree("src", "docs/src").unwrap();
+        assert_eq!(
+            root.get_child("docs/src/main.rs").unwrap().path(),
+            "/project/docs/src/main.rs"
+        );
+        assert_eq!(root.children().len(), 2);
+    }
+
+    #[test]
+    fn move_subtree_rejects_invalid_destinations() {
+        let root = create_test_sample();
+        let before = root.to_repr();
+
+        let err = root.move_subtree("src/main.rs", "tests/test1.rs").unwrap_err();
+        assert!(err.to_string().contains("already exists"), "unexpected error: {}", err);
+        let err = root.move_subtree("src/main.rs", "missing/main.rs").unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "unexpected error: {}", err);
+        let err = root.move_subtree("target", "target/debug/inner").unwrap_err();
+        assert!(err.to_string().contains("own subtree"), "unexpected error: {}", err);
+        assert!(root.move_subtree("missing", "docs/missing").is_err());
+        assert!(root.move_subtree("", "docs/root").is_err());
+        assert!(root.move_subtree("docs", "").is_err());
+
+        // Failed moves leave the tree untouched
+        assert_eq!(root.to_repr(), before);
+    }
 }
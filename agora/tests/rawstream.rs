@@ -0,0 +1,97 @@
+use agora::rawstream::{OverflowPolicy, RawStreamServer};
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::net::UnixStream;
+use tokio_tungstenite::{client_async, tungstenite::Message};
+
+/// Connects a raw WebSocket client directly to a rawstream UDS (bypassing the gateway).
+async fn connect_uds(
+    socket_path: &str,
+) -> tokio_tungstenite::WebSocketStream<UnixStream> {
+    let stream = UnixStream::connect(socket_path).await.unwrap();
+    let (ws_stream, _) = client_async("ws://localhost/", stream).await.unwrap();
+    ws_stream
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stalled_subscriber_does_not_block_fast_subscriber() {
+        let socket_path = format!(
+            "/tmp/agora/tests/rawstream_overflow_{}/rawstream.sock",
+            std::process::id()
+        );
+        let capacity = 16;
+        let server: RawStreamServer<Vec<u8>> = RawStreamServer::new(
+            &socket_path,
+            Some(capacity),
+            Some(OverflowPolicy::DropOldest),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Stalled subscriber connects but never reads
+        let mut stalled = connect_uds(&socket_path).await;
+        let mut fast = connect_uds(&socket_path).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Large payloads so the stalled client's socket buffer fills quickly
+        let num_messages: u32 = 2000;
+        let payload_size = 64 * 1024;
+        let reader = tokio::spawn(async move {
+            let mut received: Vec<u32> = Vec::new();
+            while let Some(Ok(message)) = fast.next().await {
+                if let Message::Binary(data) = message {
+                    let seq = u32::from_le_bytes(data[..4].try_into().unwrap());
+                    received.push(seq);
+                    if seq == num_messages - 1 {
+                        break;
+                    }
+                }
+            }
+            received
+        });
+
+        for seq in 0..num_messages {
+            let mut payload = vec![0u8; payload_size];
+            payload[..4].copy_from_slice(&seq.to_le_bytes());
+            server.publish(payload).unwrap();
+            if seq % capacity as u32 == 0 {
+                // Give the fast reader a chance to drain its queue
+                tokio::task::yield_now().await;
+            }
+        }
+
+        let received = tokio::time::timeout(Duration::from_secs(10), reader)
+            .await
+            .expect("Fast subscriber should not be stalled by the slow one")
+            .unwrap();
+        assert_eq!(*received.last().unwrap(), num_messages - 1);
+        assert!(
+            received.windows(2).all(|w| w[0] < w[1]),
+            "Fast subscriber should receive messages in order"
+        );
+
+        // Once the stalled subscriber resumes, the freshest message is still delivered
+        let last_seen = tokio::time::timeout(Duration::from_secs(10), async {
+            let mut last = None;
+            while let Some(Ok(message)) = stalled.next().await {
+                if let Message::Binary(data) = message {
+                    let seq = u32::from_le_bytes(data[..4].try_into().unwrap());
+                    last = Some(seq);
+                    if seq == num_messages - 1 {
+                        break;
+                    }
+                }
+            }
+            last
+        })
+        .await
+        .expect("Stalled subscriber should eventually catch up to the latest message");
+        assert_eq!(last_seen, Some(num_messages - 1));
+    }
+}
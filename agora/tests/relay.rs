@@ -0,0 +1,275 @@
+use agora::gateway::Gateway;
+use agora::metaserver::{AgoraClient, AgoraMetaServer};
+use agora::{
+    ConnectionHandle, PauseBehavior, Publisher, Relay, RelayRetry, Subscriber, SwaponRetry,
+};
+use std::time::Duration;
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora(offset: u16) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+/// Polls `subscriber` until it reads `expected`; false if it never does within `timeout`
+async fn wait_for_value(subscriber: &mut Subscriber<i64>, expected: i64, timeout: Duration) -> bool {
+    tokio::time::timeout(timeout, async {
+        loop {
+            if subscriber.get().await.unwrap() == expected {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+/// Relay from a fresh source publisher (initial value 1) to a fresh destination (initial value 0)
+async fn relay_pair(
+    offset: u16,
+    name: &str,
+    behavior: PauseBehavior,
+) -> (Relay<i64>, Publisher<i64>, Subscriber<i64>, Gateway) {
+    let (metaserver, gateway_port, gateway) = start_agora(offset).await;
+    let pid = std::process::id();
+    let src_path = format!("tests/relay_{}/{}/src", pid, name);
+    let dest_path = format!("tests/relay_{}/{}/dest", pid, name);
+
+    let source = Publisher::<i64>::new("src".to_string(), src_path.clone(), 1, metaserver, gateway_port)
+        .await
+        .unwrap();
+    let mut relay = Relay::<i64>::new(
+        "relay".to_string(),
+        dest_path.clone(),
+        0,
+        metaserver,
+        gateway_port,
+    )
+    .await
+    .unwrap()
+    .with_pause_behavior(behavior);
+    relay.swapon(src_path, metaserver, None).await.unwrap();
+
+    let mut subscriber = Subscriber::<i64>::new(dest_path, metaserver).await.unwrap();
+    assert!(wait_for_value(&mut subscriber, 1, Duration::from_secs(3)).await);
+    (relay, source, subscriber, gateway)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn swapon_waits_for_delayed_source_registration() {
+        let (metaserver, gateway_port, _gateway) = start_agora(0).await;
+        let pid = std::process::id();
+        let src_path = format!("tests/relay_{}/delayed/src", pid);
+        let dest_path = format!("tests/relay_{}/delayed/dest", pid);
+
+        let mut relay = Relay::<i64>::new(
+            "relay".to_string(),
+            dest_path.clone(),
+            0,
+            metaserver,
+            gateway_port,
+        )
+        .await
+        .unwrap();
+
+        // Source worker comes up well after swapon is called
+        let src_path_ = src_path.clone();
+        let source = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1000)).await;
+            let publisher =
+                Publisher::<i64>::new("src".to_string(), src_path_, 42, metaserver, gateway_port)
+                    .await
+                    .unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(publisher);
+        });
+
+        let retry = SwaponRetry {
+            timeout: Duration::from_secs(5),
+            poll_interval: Duration::from_millis(50),
+        };
+        relay
+            .swapon(src_path, metaserver, Some(retry))
+            .await
+            .expect("swapon should wait for the source to register");
+
+        // The source's initial value reaches the destination
+        let mut subscriber = Subscriber::<i64>::new(dest_path, metaserver).await.unwrap();
+        let relayed = tokio::time::timeout(Duration::from_secs(3), async {
+            loop {
+                if subscriber.get().await.unwrap() == 42 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await;
+        assert!(relayed.is_ok(), "Relay should forward the delayed source's value");
+        source.abort();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn swapon_gives_up_after_retry_timeout() {
+        let (metaserver, gateway_port, _gateway) = start_agora(1).await;
+        let pid = std::process::id();
+        let dest_path = format!("tests/relay_{}/absent/dest", pid);
+
+        let mut relay = Relay::<i64>::new(
+            "relay".to_string(),
+            dest_path,
+            0,
+            metaserver,
+            gateway_port,
+        )
+        .await
+        .unwrap();
+
+        let src_path = format!("tests/relay_{}/absent/src", pid);
+        assert!(relay.swapon(src_path.clone(), metaserver, None).await.is_err());
+
+        let retry = SwaponRetry {
+            timeout: Duration::from_millis(300),
+            poll_interval: Duration::from_millis(50),
+        };
+        let started = std::time::Instant::now();
+        let result = relay.swapon(src_path, metaserver, Some(retry)).await;
+        assert!(result.is_err());
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert!(result.unwrap_err().to_string().contains("not available"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn paused_relay_drops_values_and_resumes_forwarding() {
+        let (relay, mut source, mut subscriber, _gateway) =
+            relay_pair(2, "pause_drop", PauseBehavior::Drop).await;
+
+        relay.pause();
+        assert!(relay.is_paused());
+        source.publish(2).await.unwrap();
+        // Destination stays registered and keeps its last value
+        assert!(!wait_for_value(&mut subscriber, 2, Duration::from_millis(500)).await);
+        assert_eq!(subscriber.get().await.unwrap(), 1);
+
+        relay.resume();
+        assert!(!relay.is_paused());
+        // Dropped value is not replayed on resume
+        assert!(!wait_for_value(&mut subscriber, 2, Duration::from_millis(500)).await);
+        source.publish(3).await.unwrap();
+        assert!(wait_for_value(&mut subscriber, 3, Duration::from_secs(3)).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn hold_last_publishes_latest_value_on_resume() {
+        let (relay, mut source, mut subscriber, _gateway) =
+            relay_pair(3, "pause_hold", PauseBehavior::HoldLast).await;
+        assert_eq!(relay.pause_behavior(), PauseBehavior::HoldLast);
+
+        relay.pause();
+        for value in [2, 3, 4] {
+            source.publish(value).await.unwrap();
+        }
+        assert!(!wait_for_value(&mut subscriber, 4, Duration::from_millis(500)).await);
+
+        // Only the latest held value goes out, without a new source publish
+        relay.resume();
+        assert!(wait_for_value(&mut subscriber, 4, Duration::from_secs(3)).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stats_count_forwarded_values_and_swapons() {
+        let (relay, mut source, mut subscriber, _gateway) =
+            relay_pair(4, "stats", PauseBehavior::Drop).await;
+        assert_eq!(relay.stats().swapons, 1);
+
+        for value in [2, 3, 4] {
+            source.publish(value).await.unwrap();
+        }
+        assert!(wait_for_value(&mut subscriber, 4, Duration::from_secs(3)).await);
+        // The source's initial value plus the three updates
+        let forwarded = tokio::time::timeout(Duration::from_secs(3), async {
+            while relay.stats().forwarded < 4 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(
+            forwarded.is_ok(),
+            "forwarded count should reach 4: {:?}",
+            relay.stats()
+        );
+        let stats = relay.stats();
+        assert_eq!(stats.forwarded, 4);
+        assert!(stats.latency.unwrap() < Duration::from_secs(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn new_retries_through_transient_registration_failure() {
+        let (metaserver, gateway_port, _gateway) = start_agora(5).await;
+        let pid = std::process::id();
+        let dest_path = format!("tests/relay_{}/transient/dest", pid);
+
+        // A stale, never confirmed registration holds the destination path for a while
+        let blocker = AgoraClient::new(metaserver).await.unwrap();
+        blocker
+            .register_publisher("stale".to_string(), dest_path.clone(), gateway_port)
+            .await
+            .unwrap();
+
+        let no_retry = RelayRetry {
+            retries: 0,
+            backoff: Duration::from_millis(50),
+        };
+        let result = Relay::<i64>::with_retry(
+            "relay".to_string(),
+            dest_path.clone(),
+            0,
+            metaserver,
+            gateway_port,
+            no_retry,
+        )
+        .await;
+        assert!(result.is_err());
+
+        let release_path = dest_path.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            blocker.remove_publisher(&release_path).await.unwrap();
+        });
+        let retry = RelayRetry {
+            retries: 5,
+            backoff: Duration::from_millis(50),
+        };
+        let _relay = Relay::<i64>::with_retry(
+            "relay".to_string(),
+            dest_path.clone(),
+            7,
+            metaserver,
+            gateway_port,
+            retry,
+        )
+        .await
+        .unwrap();
+
+        // The destination is registered once and serves the relay's initial value
+        let mut subscriber = Subscriber::<i64>::new(dest_path, metaserver).await.unwrap();
+        assert_eq!(subscriber.get().await.unwrap(), 7);
+    }
+}
@@ -0,0 +1,114 @@
+use agora::gateway::Gateway;
+use agora::metaserver::AgoraMetaServer;
+use agora::{ConnectionHandle, Publisher, Sampling, Subscriber};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora(offset: u16) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+/// Collects stream values until `last` arrives or `timeout` passes
+async fn collect_until(
+    stream: &mut (impl futures_util::Stream<Item = agora::utils::OrError<i64>> + Unpin),
+    last: i64,
+    timeout: Duration,
+) -> Vec<i64> {
+    let mut received = Vec::new();
+    let _ = tokio::time::timeout(timeout, async {
+        while let Some(value) = stream.next().await {
+            let value = value.unwrap();
+            received.push(value);
+            if value == last {
+                break;
+            }
+        }
+    })
+    .await;
+    received
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn every_n_sampling_yields_last_of_each_window() {
+        let (metaserver, gateway_port, _gateway) = start_agora(0).await;
+        let path = format!("tests/sampling_{}/every_n", std::process::id());
+        let mut publisher =
+            Publisher::<i64>::new("sampling".to_string(), path.clone(), -1, metaserver, gateway_port)
+                .await
+                .unwrap();
+
+        let mut subscriber = Subscriber::<i64>::new(path, metaserver)
+            .await
+            .unwrap()
+            .with_sampling(10);
+        assert_eq!(subscriber.sampling(), Some(Sampling::EveryN(10)));
+        let (current, mut stream) = subscriber.get_stream().await.unwrap();
+        assert_eq!(current, -1);
+
+        for value in 0..100 {
+            publisher.publish(value).await.unwrap();
+        }
+        let received = collect_until(&mut stream, 99, Duration::from_secs(3)).await;
+        assert!(
+            (9..=11).contains(&received.len()),
+            "expected ~10 samples, got {:?}",
+            received
+        );
+        assert_eq!(received.last(), Some(&99));
+        assert!(received.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn interval_sampling_yields_freshest_value_per_window() {
+        let (metaserver, gateway_port, _gateway) = start_agora(1).await;
+        let path = format!("tests/sampling_{}/interval", std::process::id());
+        let mut publisher =
+            Publisher::<i64>::new("sampling".to_string(), path.clone(), -1, metaserver, gateway_port)
+                .await
+                .unwrap();
+
+        let mut subscriber = Subscriber::<i64>::new(path, metaserver)
+            .await
+            .unwrap()
+            .with_sample_interval(Duration::from_millis(200));
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+
+        // 100 updates over ~1s → about five 200ms windows
+        let publishing = tokio::spawn(async move {
+            for value in 0..100 {
+                publisher.publish(value).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            publisher
+        });
+        let received = collect_until(&mut stream, 99, Duration::from_secs(5)).await;
+        let _publisher = publishing.await.unwrap();
+
+        assert!(
+            (3..=10).contains(&received.len()),
+            "expected ~5 samples, got {:?}",
+            received
+        );
+        assert_eq!(received.last(), Some(&99));
+        assert!(received.windows(2).all(|w| w[0] < w[1]));
+    }
+}
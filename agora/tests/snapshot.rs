@@ -0,0 +1,188 @@
+use agora::testkit::TestCluster;
+use agora::{Agorable, Publisher, Subscriber};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Price → size levels of a toy diff-maintained book
+type Levels = BTreeMap<i64, i64>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum BookMessage {
+    Snapshot(Levels),
+    /// Sets the size at `price`; 0 removes the level
+    Delta {
+        price: i64,
+        size: i64,
+    },
+}
+
+impl fmt::Display for BookMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookMessage::Snapshot(levels) => write!(f, "snapshot {:?}", levels),
+            BookMessage::Delta { price, size } => write!(f, "delta {}@{}", size, price),
+        }
+    }
+}
+
+impl Agorable for BookMessage {}
+
+fn apply(levels: &mut Levels, price: i64, size: i64) {
+    if size == 0 {
+        levels.remove(&price);
+    } else {
+        levels.insert(price, size);
+    }
+}
+
+/// Applies a delta to the shared book, then publishes it
+async fn publish_delta(
+    publisher: &mut Publisher<BookMessage>,
+    book: &Mutex<Levels>,
+    price: i64,
+    size: i64,
+) {
+    apply(&mut book.lock().unwrap(), price, size);
+    publisher
+        .publish(BookMessage::Delta { price, size })
+        .await
+        .unwrap();
+}
+
+async fn book_publisher(
+    cluster: &TestCluster,
+    path: &str,
+    book: Arc<Mutex<Levels>>,
+) -> Publisher<BookMessage> {
+    Publisher::new(
+        "snapshot".to_string(),
+        path.to_string(),
+        BookMessage::Snapshot(Levels::new()),
+        cluster.metaserver(),
+        cluster.gateway_port(),
+    )
+    .await
+    .unwrap()
+    .with_snapshot_provider(move || Some(BookMessage::Snapshot(book.lock().unwrap().clone())))
+}
+
+/// Polls until `publisher` has `expected` streaming subscribers
+async fn wait_for_subscribers<T: Agorable>(publisher: &Publisher<T>, expected: usize) {
+    tokio::time::timeout(Duration::from_secs(3), async {
+        while publisher.subscriber_count() != expected {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("subscriber should connect");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn late_subscriber_gets_snapshot_then_deltas() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/snapshot_{}/book", std::process::id());
+        let book = Arc::new(Mutex::new(Levels::new()));
+        let mut publisher = book_publisher(&cluster, &path, book.clone()).await;
+
+        publish_delta(&mut publisher, &book, 100, 5).await;
+        publish_delta(&mut publisher, &book, 101, 3).await;
+
+        let mut subscriber = Subscriber::<BookMessage>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+        wait_for_subscribers(&publisher, 1).await;
+
+        // The subscriber is held back until this publish, then greeted with the book it produced
+        publish_delta(&mut publisher, &book, 100, 0).await;
+        for (price, size) in [(102, 7), (101, 4)] {
+            publish_delta(&mut publisher, &book, price, size).await;
+        }
+
+        let received: Vec<BookMessage> = tokio::time::timeout(
+            Duration::from_secs(3),
+            stream
+                .by_ref()
+                .take(3)
+                .map(|message| message.unwrap())
+                .collect(),
+        )
+        .await
+        .expect("snapshot and deltas should arrive");
+        assert_eq!(
+            received,
+            vec![
+                BookMessage::Snapshot(Levels::from([(101, 3)])),
+                BookMessage::Delta {
+                    price: 102,
+                    size: 7
+                },
+                BookMessage::Delta {
+                    price: 101,
+                    size: 4
+                },
+            ]
+        );
+
+        // Snapshot plus deltas rebuild exactly the publisher's book
+        let mut rebuilt = Levels::new();
+        for message in received {
+            match message {
+                BookMessage::Snapshot(levels) => rebuilt = levels,
+                BookMessage::Delta { price, size } => apply(&mut rebuilt, price, size),
+            }
+        }
+        assert_eq!(rebuilt, *book.lock().unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribers_wait_while_there_is_no_snapshot() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/snapshot_{}/not_ready", std::process::id());
+        let ready = Arc::new(Mutex::new(false));
+        let provider_ready = ready.clone();
+        let mut publisher = Publisher::<i64>::new(
+            "snapshot".to_string(),
+            path.clone(),
+            0,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap()
+        .with_snapshot_provider(move || provider_ready.lock().unwrap().then_some(1000));
+
+        let mut subscriber = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+        wait_for_subscribers(&publisher, 1).await;
+
+        publisher.publish(1).await.unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), stream.next())
+                .await
+                .is_err(),
+            "no value should arrive before a snapshot exists"
+        );
+
+        *ready.lock().unwrap() = true;
+        publisher.publish(2).await.unwrap();
+        publisher.publish(3).await.unwrap();
+        let received: Vec<i64> = tokio::time::timeout(
+            Duration::from_secs(3),
+            stream.take(2).map(|value| value.unwrap()).collect(),
+        )
+        .await
+        .expect("snapshot and value should arrive");
+        assert_eq!(received, vec![1000, 3]);
+    }
+}
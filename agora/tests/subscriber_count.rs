@@ -0,0 +1,90 @@
+use agora::gateway::Gateway;
+use agora::metaserver::{AgoraClient, AgoraMetaServer};
+use agora::{ConnectionHandle, OmniSubscriber, Publisher, Subscriber};
+use std::time::Duration;
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora(offset: u16) -> (ConnectionHandle, u16, Gateway) {
+    let base = 20000 + (std::process::id() % 20000) as u16 + offset * 2;
+    let metaserver_port = base;
+    let gateway_port = base + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+/// Polls the metaserver until it reports `expected` subscribers at `path`; false on timeout
+async fn wait_for_count(client: &AgoraClient, path: &str, expected: usize) -> bool {
+    tokio::time::timeout(Duration::from_secs(3), async {
+        loop {
+            if client.get_subscriber_count(path).await.unwrap() == expected {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn metaserver_reports_connected_subscribers() {
+        let (metaserver, gateway_port, _gateway) = start_agora(0).await;
+        let path = format!("tests/subscriber_count_{}/value", std::process::id());
+        let publisher = Publisher::<i64>::new(
+            "count".to_string(),
+            path.clone(),
+            0,
+            metaserver,
+            gateway_port,
+        )
+        .await
+        .unwrap();
+        let client = AgoraClient::new(metaserver).await.unwrap();
+
+        // The metaserver's own health-check pings are not subscribers
+        assert_eq!(client.get_subscriber_count(&path).await.unwrap(), 0);
+
+        let first = Subscriber::<i64>::new(path.clone(), metaserver)
+            .await
+            .unwrap();
+        let second = Subscriber::<i64>::new(path.clone(), metaserver)
+            .await
+            .unwrap();
+        assert!(wait_for_count(&client, &path, 2).await);
+        assert_eq!(publisher.subscriber_count(), 2);
+
+        // OmniSubscribers stream from the string endpoint and count too
+        let omni = OmniSubscriber::new(path.clone(), metaserver).await.unwrap();
+        assert!(wait_for_count(&client, &path, 3).await);
+
+        // Disconnects are seen without any publish
+        drop(first);
+        assert!(wait_for_count(&client, &path, 2).await);
+        drop(omni);
+        drop(second);
+        assert!(wait_for_count(&client, &path, 0).await);
+        assert_eq!(publisher.subscriber_count(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscriber_count_of_unknown_path_fails() {
+        let (metaserver, _gateway_port, _gateway) = start_agora(1).await;
+        let client = AgoraClient::new(metaserver).await.unwrap();
+        let path = format!("tests/subscriber_count_{}/absent", std::process::id());
+        assert!(client.get_subscriber_count(&path).await.is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use agora::testkit::TestCluster;
+use agora::{Agorable, Publisher, Subscriber, WireCodec};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Trade {
+    price: f64,
+    size: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Quote {
+    bid: f64,
+    ask: f64,
+}
+
+agora::agorable_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    enum MarketEvent {
+        Trade(Trade) = "trade",
+        Quote(Quote) = "quote",
+        Book(Vec<(f64, f64)>) = "book",
+    }
+}
+
+impl fmt::Display for MarketEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trade(trade) => write!(f, "trade {}@{}", trade.size, trade.price),
+            Self::Quote(quote) => write!(f, "quote {}/{}", quote.bid, quote.ask),
+            Self::Book(levels) => write!(f, "book ({} levels)", levels.len()),
+        }
+    }
+}
+
+impl Agorable for MarketEvent {}
+
+// `MarketEvent` as a newer publisher might declare it: variants reordered and one added
+agora::agorable_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    enum MarketEventV2 {
+        Book(Vec<(f64, f64)>) = "book",
+        Funding(f64) = "funding",
+        Quote(Quote) = "quote",
+        Trade(Trade) = "trade",
+    }
+}
+
+fn events() -> Vec<MarketEvent> {
+    vec![
+        MarketEvent::Trade(Trade {
+            price: 101.5,
+            size: 0.25,
+        }),
+        MarketEvent::Quote(Quote {
+            bid: 101.0,
+            ask: 102.0,
+        }),
+        MarketEvent::Book(vec![(101.0, 3.0), (100.5, 7.5)]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_round_trips_with_every_codec() {
+        assert_eq!(MarketEvent::TAGS, &["trade", "quote", "book"]);
+        for codec in WireCodec::ALL {
+            for event in events() {
+                let bytes = codec.encode(&event).unwrap();
+                assert_eq!(
+                    codec.decode::<MarketEvent>(&bytes).unwrap(),
+                    event,
+                    "{}",
+                    codec
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn json_carries_the_tag_explicitly() {
+        let json = serde_json::to_string(&events()[1]).unwrap();
+        assert_eq!(json, r#"["quote",{"bid":101.0,"ask":102.0}]"#);
+        assert_eq!(events()[2].tag(), "book");
+    }
+
+    #[test]
+    fn variants_are_matched_by_tag_not_position() {
+        for codec in WireCodec::ALL {
+            let bytes = codec
+                .encode(&MarketEventV2::Quote(Quote { bid: 1.0, ask: 2.0 }))
+                .unwrap();
+            assert_eq!(
+                codec.decode::<MarketEvent>(&bytes).unwrap(),
+                MarketEvent::Quote(Quote { bid: 1.0, ask: 2.0 })
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_tag_is_a_clear_error() {
+        for codec in WireCodec::ALL {
+            let bytes = codec.encode(&MarketEventV2::Funding(0.0001)).unwrap();
+            let message = format!("{:#}", codec.decode::<MarketEvent>(&bytes).unwrap_err());
+            // Postcard drops custom error messages, but still refuses the frame
+            if codec != WireCodec::Postcard {
+                assert!(
+                    message.contains("unknown MarketEvent tag 'funding'"),
+                    "{}: {}",
+                    codec,
+                    message
+                );
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn one_path_carries_mixed_events_in_order() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/tagged_enum_{}/events", std::process::id());
+        let mut publisher = Publisher::<MarketEvent>::new(
+            "events".to_string(),
+            path.clone(),
+            MarketEvent::Book(Vec::new()),
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+        let mut subscriber = Subscriber::<MarketEvent>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+        let (_, mut stream) = subscriber.get_stream().await.unwrap();
+
+        for event in events() {
+            publisher.publish(event).await.unwrap();
+        }
+        let received: Vec<MarketEvent> = tokio::time::timeout(
+            Duration::from_secs(3),
+            stream.by_ref().take(3).map(|v| v.unwrap()).collect(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(received, events());
+    }
+}
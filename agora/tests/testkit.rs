@@ -0,0 +1,90 @@
+use agora::constants::CHECK_PUBLISHER_LIVELINESS_EVERY_MS;
+use agora::metaserver::AgoraClient;
+use agora::testkit::TestCluster;
+use agora::utils::TreeTrait;
+use agora::{Publisher, Subscriber};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn publish_subscribe_round_trip_in_process() {
+        let cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/testkit_{}/value", std::process::id());
+        let mut publisher = Publisher::<i64>::new(
+            "testkit".to_string(),
+            path.clone(),
+            0,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+
+        let mut subscriber = Subscriber::<i64>::new(path, cluster.metaserver())
+            .await
+            .unwrap();
+        let (current, mut stream) = subscriber.get_stream().await.unwrap();
+        assert_eq!(current, 0);
+
+        publisher.publish(42).await.unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(3), stream.next())
+            .await
+            .expect("no value within 3s")
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, 42);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dropped_cluster_releases_its_ports() {
+        let cluster = TestCluster::start().await.unwrap();
+        let metaserver = cluster.metaserver();
+        drop(cluster);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(
+            tokio::net::TcpStream::connect(metaserver.addr_port())
+                .await
+                .is_err()
+        );
+    }
+
+    // Current-thread runtime: a paused clock requires one
+    #[tokio::test]
+    async fn advancing_paused_clock_prunes_dropped_publisher() {
+        let mut cluster = TestCluster::start().await.unwrap();
+        let path = format!("tests/testkit_{}/pruned", std::process::id());
+        let publisher = Publisher::<i64>::new(
+            "testkit".to_string(),
+            path.clone(),
+            0,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+        let client = AgoraClient::new(cluster.metaserver()).await.unwrap();
+
+        cluster.pause_clock();
+        // Dropped without unregistering, like a crashed process: only a prune pass removes it
+        drop(publisher);
+        let half_interval = Duration::from_millis(CHECK_PUBLISHER_LIVELINESS_EVERY_MS / 2);
+
+        cluster.advance(half_interval).await;
+        // Still registered (`get_publisher_info` would ping it, and the dropped publisher no longer answers)
+        let tree = client.get_path_tree().await.unwrap();
+        assert!(tree.get_child(&path).is_ok());
+        assert_eq!(client.get_server_status().await.unwrap().total_pruned, 0);
+
+        // The next prune pass is due exactly one interval after startup
+        cluster.advance(half_interval).await;
+        let status = client.get_server_status().await.unwrap();
+        assert_eq!(status.total_pruned, 1);
+        assert_eq!(status.last_prune_removed, 1);
+        let tree = client.get_path_tree().await.unwrap();
+        assert!(tree.get_child(&path).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use agora::ConnectionHandle;
+use agora::metaserver::ServerState;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+
+/// One captured event: its level and every field (including `message`) rendered as a string
+#[derive(Debug, Clone)]
+struct CapturedEvent {
+    level: Level,
+    fields: HashMap<String, String>,
+}
+
+/// Collects every event into a shared vector
+#[derive(Clone, Default)]
+struct CaptureLayer {
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = HashMap::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.events.lock().unwrap().push(CapturedEvent {
+            level: *event.metadata().level(),
+            fields,
+        });
+    }
+}
+
+impl CaptureLayer {
+    fn with_message(&self, message: &str) -> Vec<CapturedEvent> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.fields.get("message").map(String::as_str) == Some(message))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_publisher_emits_structured_event() {
+        let capture = CaptureLayer::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+        let mut state = ServerState::new();
+        let host = ConnectionHandle::new_local(8080).unwrap();
+        state
+            .register_publisher("prices".to_string(), "market/btc".to_string(), host)
+            .unwrap();
+
+        let events = capture.with_message("registered publisher");
+        assert_eq!(events.len(), 1, "{:?}", events);
+        let event = &events[0];
+        assert_eq!(event.level, Level::INFO);
+        assert_eq!(event.fields["name"], "prices");
+        assert_eq!(event.fields["path"], "market/btc");
+        assert_eq!(event.fields["host"], host.to_string());
+    }
+
+    #[test]
+    fn failed_registration_emits_no_registered_event() {
+        let capture = CaptureLayer::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+        let mut state = ServerState::new();
+        let host = ConnectionHandle::new_local(8080).unwrap();
+        state
+            .register_publisher("prices".to_string(), "market/btc".to_string(), host)
+            .unwrap();
+        assert!(
+            state
+                .register_publisher("prices".to_string(), "market/btc".to_string(), host)
+                .is_err()
+        );
+
+        assert_eq!(capture.with_message("registered publisher").len(), 1);
+    }
+}
@@ -1,10 +1,12 @@
 use argus::constants::ARGUS_DATA_PATH;
 use argus::Archiver;
 use indoc::indoc;
+use std::collections::HashMap;
 use std::io::{self, Write};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    agora::init_tracing();
     println!("Welcome to the Argus Archiver!");
     println!("This tool organizes temporary parquet files into hive-partitioned structure.\n");
 
@@ -47,7 +49,7 @@ async fn main() -> anyhow::Result<()> {
     println!("  Source: {}", src_path);
     println!("  Data types: {:?}", data_types);
 
-    let mut archiver = match Archiver::new(&target_dir, &data_types, &src_path).await {
+    let mut archiver = match Archiver::new(&target_dir, &data_types, &src_path, None, HashMap::new(), None, None, HashMap::new(), None, None).await {
         Ok(archiver) => {
             println!("\n✅ Archiver initialized successfully!");
             archiver
@@ -152,14 +154,18 @@ fn print_last_updates(archiver: &Archiver) {
         if symbols.is_empty() {
             println!("    (no symbols tracked)");
         } else {
-            for (symbol, duration) in symbols {
+            for ((market, symbol), duration) in symbols {
+                let name = match market {
+                    Some(market) => format!("{}.{}", market, symbol.to_string()),
+                    None => symbol.to_string(),
+                };
                 let seconds = duration.num_seconds();
                 if seconds < 60 {
-                    println!("    {}: {}s ago", symbol.to_string(), seconds);
+                    println!("    {}: {}s ago", name, seconds);
                 } else if seconds < 3600 {
-                    println!("    {}: {}m ago", symbol.to_string(), seconds / 60);
+                    println!("    {}: {}m ago", name, seconds / 60);
                 } else {
-                    println!("    {}: {}h ago", symbol.to_string(), seconds / 3600);
+                    println!("    {}: {}h ago", name, seconds / 3600);
                 }
             }
         }
@@ -1,6 +1,9 @@
-use argus::constants::{ARGUS_DATA_PATH, HYPERLIQUID_DATA_SUFFIX};
+use argus::constants::{
+    ARGUS_DATA_PATH, HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS, HYPERLIQUID_DATA_SUFFIX,
+};
 use argus::crypto::hyperliquid::HyperliquidArchiver;
 use clap::Parser;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(
@@ -23,10 +26,29 @@ struct Args {
         help = "Output directory for organized data (default: $ARGUS_DATA_PATH/$HYPERLIQUID_DATA_SUFFIX)"
     )]
     output_dir: Option<String>,
+
+    #[arg(
+        long,
+        help = "Check the archive in the output directory for unreadable files, schema drift, out-of-order or non-finite rows and misdated partitions, then exit"
+    )]
+    validate: bool,
+
+    #[arg(
+        long,
+        help = "Archive orderbooks at exactly this many levels per side, padding with null levels and recording each side's full depth; full depth if unset"
+    )]
+    orderbook_depth: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Seconds between scans of the temporary directory for files to archive (default: HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS)"
+    )]
+    scan_interval: Option<u64>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    agora::init_tracing();
     let args = Args::parse();
 
     // Determine output directory
@@ -34,6 +56,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         format!("{}/{}", ARGUS_DATA_PATH, HYPERLIQUID_DATA_SUFFIX)
     });
 
+    if args.validate {
+        println!("Validating archive in {}...", output_dir);
+        let orderbook_depth = args.orderbook_depth;
+        let report = tokio::task::spawn_blocking(move || {
+            HyperliquidArchiver::validate(&output_dir, orderbook_depth)
+        })
+        .await?
+        .map_err(|e| anyhow::anyhow!(e))?;
+        println!("{}", report);
+        if !report.is_clean() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     println!();
     println!("Configuration:");
     println!("  Temporary dir: {}", args.tmp_dir);
@@ -41,9 +78,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!();
 
     // Initialize archiver
-    let archiver = HyperliquidArchiver::new(&args.tmp_dir, &output_dir)
-        .await
-        .map_err(|e| anyhow::anyhow!(e))?;
+    let scan_interval = Duration::from_secs(
+        args.scan_interval
+            .unwrap_or(HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS),
+    );
+    let archiver = HyperliquidArchiver::new(
+        &args.tmp_dir,
+        &output_dir,
+        args.orderbook_depth,
+        Some(scan_interval),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
 
     println!("Archiver is now running!");
     println!();
@@ -53,7 +99,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Spot data types: last_trade, bbo, orderbook, spot_context");
     println!("Perp data types: last_trade, bbo, orderbook, perp_context");
     println!();
-    println!("Files are scanned every 10 seconds.");
+    println!("Files are scanned every {:?}.", scan_interval);
     println!("Old files are archived when newer data is detected for the same symbol.");
     println!();
     println!("Press Ctrl+C to stop.");
@@ -0,0 +1,361 @@
+use agora::ConnectionHandle;
+use agora::metaserver::AgoraClient;
+use agora::utils::{OrError, TreeTrait};
+use argus::constants::{
+    AGORA_GATEWAY_PORT, AGORA_METASERVER_DEFAULT_PORT, ARGUS_DATA_PATH, HYPERLIQUID_AGORA_PREFIX,
+    HYPERLIQUID_DATA_SUFFIX,
+};
+use argus::crypto::hyperliquid::{
+    HyperliquidArchiver, HyperliquidEndpoints, HyperliquidPipeline, HyperliquidPublisher,
+    HyperliquidScribe, UniverseFilter, UniverseManager,
+};
+use argus::metrics::MetricsExporter;
+use argus::types::TradingSymbol;
+use clap::Parser;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "Hyperliquid Pipeline - runs publisher, scribe and archiver as one supervised service",
+    long_about = None
+)]
+struct Args {
+    #[arg(short, long, default_value_t = AGORA_METASERVER_DEFAULT_PORT)]
+    port: u16,
+
+    #[arg(short = 'H', long, default_value = "localhost")]
+    metaserver_host: String,
+
+    #[arg(short = 'g', long, default_value_t = AGORA_GATEWAY_PORT)]
+    local_gateway_port: u16,
+
+    #[arg(short, long, default_value = HYPERLIQUID_AGORA_PREFIX)]
+    agora_path: String,
+
+    #[arg(
+        short = 't',
+        long,
+        default_value = "/tmp/hyperliquid",
+        help = "Temporary directory the scribe writes to and the archiver reads from"
+    )]
+    tmp_dir: String,
+
+    #[arg(
+        short = 'o',
+        long,
+        help = "Output directory for organized data (default: $ARGUS_DATA_PATH/$HYPERLIQUID_DATA_SUFFIX)"
+    )]
+    output_dir: Option<String>,
+
+    #[arg(long, default_value_t = 60, help = "Seconds between Hyperliquid universe refreshes")]
+    universe_interval: u64,
+
+    #[arg(long, default_value_t = 5, help = "Seconds between checks for universe changes")]
+    check_interval: u64,
+
+    #[arg(short = 'f', long, default_value_t = 30, help = "Seconds between scribe flushes")]
+    flush_interval: u64,
+
+    #[arg(
+        long,
+        default_value_t = 120,
+        help = "Seconds to wait for the publisher's paths before starting the scribe"
+    )]
+    startup_timeout: u64,
+
+    #[arg(
+        long,
+        help = "Serve Prometheus metrics at http://<addr>/metrics (e.g. 0.0.0.0:9100); disabled if unset"
+    )]
+    metrics_addr: Option<SocketAddr>,
+
+    #[arg(long, help = "Validate directories and connectivity, then exit")]
+    dry_run: bool,
+
+    #[arg(long, help = "Stream from the Hyperliquid testnet instead of mainnet")]
+    testnet: bool,
+
+    #[arg(
+        long,
+        help = "Stream at most this many symbols per market (first by name); whole universe if unset"
+    )]
+    max_symbols: Option<usize>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Stream only these normalized symbols, e.g. BTC_PERP,ETH_PERP,PURR-USDC; whole universe if unset"
+    )]
+    symbols: Option<Vec<String>>,
+}
+
+impl Args {
+    fn endpoints(&self) -> HyperliquidEndpoints {
+        if self.testnet {
+            HyperliquidEndpoints::testnet()
+        } else {
+            HyperliquidEndpoints::mainnet()
+        }
+    }
+
+    fn universe_filter(&self) -> OrError<UniverseFilter> {
+        let allowlist = match &self.symbols {
+            Some(symbols) => Some(
+                symbols
+                    .iter()
+                    .map(|symbol| TradingSymbol::from_str(symbol.trim()))
+                    .collect::<OrError<Vec<_>>>()?,
+            ),
+            None => None,
+        };
+        Ok(UniverseFilter {
+            allowlist,
+            max_symbols: self.max_symbols,
+            ..UniverseFilter::default()
+        })
+    }
+}
+
+/// Creates `dir` and checks it is writable
+fn check_writable_dir(dir: &str) -> OrError<()> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| anyhow::anyhow!("cannot create directory {}: {}", dir, e))?;
+    let probe = format!("{}/.hyperliquid-pipeline-probe", dir);
+    std::fs::write(&probe, b"ok")
+        .map_err(|e| anyhow::anyhow!("directory {} is not writable: {}", dir, e))?;
+    std::fs::remove_file(&probe)?;
+    Ok(())
+}
+
+/// Checks directories, metaserver, local gateway and the Hyperliquid API without starting anything
+async fn dry_run(
+    args: &Args,
+    output_dir: &str,
+    metaserver_connection: ConnectionHandle,
+) -> OrError<()> {
+    check_writable_dir(&args.tmp_dir)?;
+    println!("  ✓ Temporary dir writable: {}", args.tmp_dir);
+    check_writable_dir(output_dir)?;
+    println!("  ✓ Output dir writable: {}", output_dir);
+
+    let status = AgoraClient::new(metaserver_connection)
+        .await?
+        .get_server_status()
+        .await?;
+    println!(
+        "  ✓ Metaserver reachable at {} ({} publishers registered, up {:?})",
+        metaserver_connection, status.registered_count, status.uptime
+    );
+
+    tokio::net::TcpStream::connect(("127.0.0.1", args.local_gateway_port))
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "gateway not reachable on port {}: {}",
+                args.local_gateway_port,
+                e
+            )
+        })?;
+    println!("  ✓ Gateway reachable on port {}", args.local_gateway_port);
+
+    let universe = UniverseManager::new(
+        Duration::from_secs(args.universe_interval),
+        Some(args.endpoints()),
+        Some(args.universe_filter()?),
+        None,
+    )
+    .await?;
+    println!(
+        "  ✓ Hyperliquid API reachable ({} perp, {} spot symbols)",
+        universe.perp_universe().await?.len(),
+        universe.spot_universe().await?.len()
+    );
+    Ok(())
+}
+
+/// Waits until every `{agora_path}/{market}/{data_type}` directory of `markets` ("spot", "perp") has symbols,
+/// so the scribe (which enumerates symbols once at startup) sees the full universe.
+async fn wait_for_publisher_paths(
+    metaserver_connection: ConnectionHandle,
+    agora_path: &str,
+    markets: &[&str],
+    timeout: Duration,
+) -> OrError<()> {
+    let directories: Vec<String> = [
+        "spot/last_trade",
+        "spot/bbo",
+        "spot/orderbook",
+        "spot/spot_context",
+        "perp/last_trade",
+        "perp/bbo",
+        "perp/orderbook",
+        "perp/perp_context",
+    ]
+    .iter()
+    .filter(|suffix| {
+        markets
+            .iter()
+            .any(|market| suffix.starts_with(&format!("{}/", market)))
+    })
+    .map(|suffix| format!("{}/{}", agora_path, suffix))
+    .collect();
+
+    let metaclient = AgoraClient::new(metaserver_connection).await?;
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let tree = metaclient.get_path_tree().await?;
+        let missing: Vec<&String> = directories
+            .iter()
+            .filter(|dir| {
+                tree.get_child(dir)
+                    .map(|node| node.children().is_empty())
+                    .unwrap_or(true)
+            })
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(anyhow::anyhow!(
+                "publisher paths not ready after {:?}: {:?}",
+                timeout,
+                missing
+            ));
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    agora::init_tracing();
+    let args = Args::parse();
+    let output_dir = args
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| format!("{}/{}", ARGUS_DATA_PATH, HYPERLIQUID_DATA_SUFFIX));
+
+    println!("========================================");
+    println!("  Hyperliquid Pipeline");
+    println!("========================================");
+    println!();
+    println!("Configuration:");
+    println!("  Metaserver: {}:{}", args.metaserver_host, args.port);
+    println!("  Local gateway port: {}", args.local_gateway_port);
+    println!("  Agora path: {}", args.agora_path);
+    println!("  Temporary dir: {}", args.tmp_dir);
+    println!("  Output dir: {}", output_dir);
+    println!(
+        "  Intervals: universe {}s, change check {}s, flush {}s",
+        args.universe_interval, args.check_interval, args.flush_interval
+    );
+    println!();
+
+    let metaserver_connection = if args.metaserver_host == "localhost" {
+        ConnectionHandle::new_local(args.port)?
+    } else {
+        let addr = args.metaserver_host.parse()?;
+        ConnectionHandle::new(addr, args.port)
+    };
+
+    if args.dry_run {
+        println!("Dry run: validating setup...");
+        dry_run(&args, &output_dir, metaserver_connection)
+            .await
+            .map_err(|e| anyhow::anyhow!("Dry run failed: {}", e))?;
+        println!();
+        println!("✅ Dry run passed. Exiting without starting the pipeline.");
+        return Ok(());
+    }
+
+    let _metrics_exporter = match args.metrics_addr {
+        Some(addr) => {
+            let exporter = MetricsExporter::serve(addr)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+            println!("Serving metrics at http://{}/metrics", exporter.local_addr());
+            println!();
+            Some(exporter)
+        }
+        None => None,
+    };
+
+    // 1. Publisher: UniverseManager → webstream workers → relays on stable paths
+    println!("[1/3] Starting publisher...");
+    let publisher = HyperliquidPublisher::new(
+        &args.agora_path,
+        metaserver_connection,
+        args.local_gateway_port,
+        Duration::from_secs(args.universe_interval),
+        Duration::from_secs(args.check_interval),
+        Some(args.endpoints()),
+        Some(args.universe_filter()?),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+    // With --symbols a market can be empty; its directories never get symbols
+    let mut markets = Vec::new();
+    if !publisher.spot_universe()?.is_empty() {
+        markets.push("spot");
+    }
+    if !publisher.perp_universe()?.is_empty() {
+        markets.push("perp");
+    }
+    wait_for_publisher_paths(
+        metaserver_connection,
+        &args.agora_path,
+        &markets,
+        Duration::from_secs(args.startup_timeout),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+    println!("  ✓ Publisher paths registered under {}", args.agora_path);
+    println!();
+
+    // 2. Scribe: subscribes to the publisher's paths, writes temporary parquet files
+    println!("[2/3] Starting scribe...");
+    let scribe = HyperliquidScribe::new(
+        &args.agora_path,
+        &args.tmp_dir,
+        metaserver_connection,
+        Duration::from_secs(args.flush_interval),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+    println!();
+
+    // 3. Archiver: organizes the scribe's temporary files into hive partitions
+    println!("[3/3] Starting archiver...");
+    let archiver = HyperliquidArchiver::new(&args.tmp_dir, &output_dir, None, None)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let recording = HyperliquidPipeline::new(scribe, archiver);
+
+    println!("========================================");
+    println!("Pipeline is now running!");
+    println!("========================================");
+    println!();
+    println!(
+        "Press Ctrl+C to stop. Shutdown order: scribe flush → final archive pass → archiver → publisher."
+    );
+    println!();
+
+    tokio::signal::ctrl_c().await?;
+
+    println!();
+    println!("Received shutdown signal.");
+
+    // Scribe flush → final archive pass → archiver, so the last interval reaches the hive layout
+    let recording_result = recording.shutdown().await;
+
+    // Publisher last: keeps streaming for other consumers until recording has stopped
+    drop(publisher);
+    println!();
+
+    recording_result.map_err(|e| anyhow::anyhow!(e))?;
+    println!("Shutdown complete. All data flushed to disk.");
+
+    Ok(())
+}
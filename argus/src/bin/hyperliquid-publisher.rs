@@ -7,6 +7,7 @@ use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    agora::init_tracing();
     println!("========================================");
     println!("  Hyperliquid Universal Publisher");
     println!("========================================");
@@ -33,6 +34,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         AGORA_GATEWAY_PORT,
         Duration::from_secs(60), // Check Hyperliquid API for universe changes every 60s
         Duration::from_secs(5),  // Check for detected universe changes every 5s
+        None,                    // Mainnet endpoints
+        None,                    // Whole universe
     )
     .await?;
 
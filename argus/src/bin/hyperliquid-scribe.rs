@@ -34,6 +34,7 @@ struct Args {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    agora::init_tracing();
     let args = Args::parse();
 
     println!("========================================");
@@ -21,6 +21,7 @@
 //! - Message parsing problems
 //! - Or something in the Agora publishing layer
 
+use argus::crypto::hyperliquid::HyperliquidRestClient;
 use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
@@ -28,7 +29,7 @@ use std::time::Instant;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 const WS_URL: &str = "wss://api.hyperliquid.xyz/ws";
-const REST_URL: &str = "https://api.hyperliquid.xyz/info";
+const REST_URL: &str = argus::constants::HYPERLIQUID_INFO_ENDPOINT;
 
 /// Stats tracker for monitoring stream health
 #[derive(Default)]
@@ -201,62 +202,27 @@ async fn fetch_top_perps(n: usize) -> Result<Vec<String>, Box<dyn std::error::Er
         n
     );
 
-    let client = reqwest::Client::new();
-    let request = serde_json::json!({
-        "type": "metaAndAssetCtxs"
-    });
-
-    let response = client.post(REST_URL).json(&request).send().await?;
-
-    if !response.status().is_success() {
-        return Err(format!("REST API error: {}", response.status()).into());
-    }
-
-    let json: serde_json::Value = response.json().await?;
-
-    // Parse response: [metadata, asset_contexts]
-    let metadata = json.get(0).ok_or("Missing metadata in response")?;
-    let asset_contexts = json
-        .get(1)
-        .ok_or("Missing asset contexts in response")?
-        .as_array()
-        .ok_or("Asset contexts is not an array")?;
-
-    let universe = metadata
-        .get("universe")
-        .ok_or("Missing universe in metadata")?
-        .as_array()
-        .ok_or("Universe is not an array")?;
+    // Shared client: rate limited together with anything else in this process polling the info endpoint
+    let (metadata, asset_contexts) = HyperliquidRestClient::shared(REST_URL)
+        .meta_and_asset_ctxs()
+        .await?;
 
     // Build list of (symbol, volume) for non-delisted assets
     let mut perps_with_volume: Vec<(String, f64)> = Vec::new();
 
-    for (i, asset) in universe.iter().enumerate() {
-        let is_delisted = asset
-            .get("isDelisted")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-
-        if is_delisted {
+    for (asset, context) in metadata.universe.iter().zip(&asset_contexts) {
+        if asset.is_delisted {
             continue;
         }
 
-        let name = asset
-            .get("name")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing asset name")?
-            .to_string();
-
-        if i < asset_contexts.len() {
-            let volume = asset_contexts[i]
-                .get("dayNtlVlm")
-                .and_then(|v| v.as_str())
-                .and_then(|s| s.parse::<f64>().ok())
-                .unwrap_or(0.0);
-
-            if volume > 0.0 {
-                perps_with_volume.push((name, volume));
-            }
+        let volume = context
+            .day_ntl_vlm
+            .as_deref()
+            .and_then(|s| s.parse::<f64>().ok())
+            .unwrap_or(0.0);
+
+        if volume > 0.0 {
+            perps_with_volume.push((asset.name.clone(), volume));
         }
     }
 
@@ -331,9 +297,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         loop {
             interval.tick().await;
             let ping_msg = serde_json::json!({"method": "ping"});
-            if let Err(_) = ping_tx
+            if ping_tx
                 .send(Message::Text(ping_msg.to_string().into()))
                 .await
+                .is_err()
             {
                 break;
             }
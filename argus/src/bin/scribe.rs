@@ -9,6 +9,7 @@ type T = BboUpdate;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    agora::init_tracing();
     println!("========================================");
     println!("  Argus File Scribe");
     println!("========================================");
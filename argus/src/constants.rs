@@ -2,16 +2,52 @@ pub const AGORA_METASERVER_DEFAULT_PORT: u16 = 8000; // Port for hosting own ago
 pub const AGORA_GATEWAY_PORT: u16 = 8001;
 pub const ARGUS_DATA_PATH: &str = "/home/nlyu/Data/argus";
 pub const BINANCE_SPOT_WEBSTREAM_ENDPOINT: &str = "wss://ws-api.binance.com:443/ws-api/v3"; // "wss://stream.binance.us:9443/ws";
+pub const BINANCE_SPOT_REST_ENDPOINT: &str = "https://api.binance.com/api/v3";
+pub const BINANCE_DEPTH_SNAPSHOT_LIMIT: u32 = 1000; // Levels per side requested from the REST depth endpoint when (re)syncing a book
+pub const BINANCE_ORDERBOOK_SNAPSHOT_LEVELS: usize = 20; // Levels per side in OrderbookSnapshots published by BinanceBookSyncWorker
+pub const BINANCE_BOOK_SYNC_MAX_BUFFERED_DIFFS: usize = 10_000; // Diffs BinanceBookSynchronizer holds while waiting for a snapshot; oldest dropped beyond this
+pub const BINANCE_BOOK_SYNC_RETRY_DELAY_MS: u64 = 500; // Delay before refetching a depth snapshot that failed or was older than the buffered diffs
 pub const HYPERLIQUID_WEBSTREAM_ENDPOINT: &str = "wss://api.hyperliquid.xyz/ws";
 pub const HYPERLIQUID_INFO_ENDPOINT: &str = "https://api.hyperliquid.xyz/info";
-pub const WORKER_INIT_DELAY_MS: u64 = 500; // Wait for workers to initialize before relay operations. Used in crypto/hyperliquid/publisher.rs
+pub const HYPERLIQUID_TESTNET_WEBSTREAM_ENDPOINT: &str = "wss://api.hyperliquid-testnet.xyz/ws";
+pub const HYPERLIQUID_TESTNET_INFO_ENDPOINT: &str = "https://api.hyperliquid-testnet.xyz/info";
+pub const COINBASE_WEBSTREAM_ENDPOINT: &str = "wss://advanced-trade-ws.coinbase.com";
+pub const WORKER_INIT_TIMEOUT_MS: u64 = 10_000; // Max wait for newly spawned workers to register before relay swapon gives up. Used in crypto/hyperliquid/publisher.rs
 
 // Constant endpoint is published to {..}/{perp | spot}/{data_type}/{date}/{symbol}
 pub const HYPERLIQUID_AGORA_PREFIX: &str = "argus/hyperliquid";
 // {ARGUS_DATA_PATH}/{..}/{perp | spot}
 pub const HYPERLIQUID_DATA_SUFFIX: &str = "hyperliquid";
+// Coinbase spot streams are published to {..}/spot/{data_type}/{symbol}
+pub const COINBASE_AGORA_PREFIX: &str = "argus/coinbase";
+pub const COINBASE_ORDERBOOK_SNAPSHOT_LEVELS: usize = 20; // Levels per side in OrderbookSnapshots built from the local level2 book, matching Hyperliquid's l2Book depth
 
-// Time between reclaiming
-pub const RELAY_BATCH_SIZE: usize = 10;
-pub const RELAY_BATCH_DELAY_MS: u64 = 100;
-pub const HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS: u64 = 10; 
\ No newline at end of file
+pub const HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS: u64 = 10; // Default time between Archiver scan/flush passes; override with Archiver::new's scan_interval
+pub const ARCHIVER_COMPACTION_ROW_GROUP_SIZE: usize = 1 << 20; // Max rows per row group in files written by Archiver::compact_partition
+pub const ARCHIVER_VALIDATION_DATE_SLACK_SECONDS: i64 = 3600; // Archiver::validate_partition accepts rows up to this long before their partition's day: files are dated by flush time, after the rows they hold
+// Webstream reconnect backoff: delay ~ U(0, min(cap, base * 2^attempt)), reset after a stable connection
+pub const HYPERLIQUID_RECONNECT_BASE_MS: u64 = 1000;
+pub const HYPERLIQUID_RECONNECT_CAP_MS: u64 = 60_000;
+pub const HYPERLIQUID_RECONNECT_MIN_STABLE_UPTIME_SECONDS: u64 = 30; // Connection must stay up this long to reset backoff
+pub const HYPERLIQUID_UNIVERSE_FETCH_RETRIES: u32 = 5; // Retries of UniverseManager's initial REST fetch per market before giving up
+pub const HYPERLIQUID_UNIVERSE_RETRY_BACKOFF_MS: u64 = 500; // Delay before the first retry; doubles on each further retry
+pub const HYPERLIQUID_REST_BURST: u32 = 10; // Info requests HyperliquidRestClient sends back to back before pacing; each weighs 20 of the 1200/min per-IP budget
+pub const HYPERLIQUID_REST_REFILL_MS: u64 = 1000; // One info request per second once the burst is spent: 60 of the 1200/min budget, leaving room for other clients on the IP
+pub const HYPERLIQUID_SUBSCRIPTION_GRACE_PERIOD_SECONDS: u64 = 10; // Coins not acknowledged by subscriptionResponse within this window are reported
+pub const HYPERLIQUID_WEBSTREAM_SHUTDOWN_TIMEOUT_MS: u64 = 1000; // Bound on unsubscribing and closing the socket when a webstream worker shuts down or is dropped
+pub const WEBSTREAM_PROXY_MAX_RESPONSE_BYTES: usize = 8192; // Longest reply to CONNECT connect_websocket reads from an HTTP proxy before giving up on it
+pub const HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS: usize = 1024; // Most symbols one HyperliquidWebstreamWorker (one websocket connection) subscribes to
+pub const HYPERLIQUID_SYMBOLS_PER_CONNECTION: Option<usize> = Some(256); // Shard size of the publisher's webstream bundles; smaller shards isolate faults and spread load over more connections
+pub const HYPERLIQUID_ORDERBOOK_MAX_LEVELS: Option<usize> = None; // Book levels kept per side in published OrderbookSnapshots; None keeps the full depth Hyperliquid sends
+pub const HYPERLIQUID_ORDERBOOK_LEVEL_ORDER: crate::crypto::hyperliquid::LevelOrderPolicy =
+    crate::crypto::hyperliquid::LevelOrderPolicy::AutoSort; // What OrderbookSnapshot::of_channel_data does with levels not sorted best first; Strict drops the message instead
+pub const HYPERLIQUID_IMBALANCE_LEVELS: usize = 5; // Top levels per side ImbalanceRelay sums when no level count is given
+pub const HYPERLIQUID_TRADE_VOLUME_WINDOW_SECS: i64 = 60; // Trailing window TradeVolumeRelay aggregates over when none is given
+pub const TRADE_VOLUME_REFRESH_MS: u64 = 1000; // TradeVolumeRelay expires trades out of its window this often between trades
+pub const HYPERLIQUID_TRADE_SEQUENCE_MODE: Option<crate::crypto::SequenceMode> = None; // Gap tracking on trade ids in publisher trade workers; None disables it
+pub const ORDERBOOK_CHECKPOINT_EVERY_UPDATES: usize = 1000; // Diffs between full-book checkpoints in orderbook journals
+pub const ORDERBOOK_CHECKPOINT_EVERY_SECONDS: i64 = 60; // Max seconds between full-book checkpoints in orderbook journals
+pub const BAR_CLOSE_GRACE_MS: i64 = 250; // BarAggregator closes timer-driven bars this far behind wall clock to absorb exchange clock skew
+pub const FUNDING_MONITOR_REFRESH_MS: u64 = 5000; // FundingMonitor rescans perp_context paths this often to follow universe changes
+pub const SUPERVISED_MAX_RESTARTS: u32 = 5; // Default restarts of a panicked task by supervised::spawn_supervised_restarting before it stays down
+pub const SUPERVISED_RESTART_BACKOFF_MS: u64 = 1000; // Default delay before restarting a panicked supervised task
@@ -1,2 +1,10 @@
+pub mod bars;
 pub mod binance;
+pub mod coinbase;
 pub mod hyperliquid;
+pub mod sequence;
+pub mod websocket;
+
+pub use bars::{Bar, BarAggregator, BarBuilder, BarSource};
+pub use sequence::{GapEvent, SequenceMode, SequenceTracker};
+pub use websocket::{WebstreamConfig, WebstreamConnectError, connect_websocket};
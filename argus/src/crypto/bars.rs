@@ -0,0 +1,419 @@
+// OHLCV bar aggregation over trade streams
+//
+// BarBuilder is the pure bucketing state machine, usable directly on archived trades.
+// BarAggregator wraps it around a live agora trade path and publishes closed bars to a derived path:
+//   {source_path} (AgorableOption<TradeUpdate>) -> {output_path} (AgorableOption<Bar>)
+// Bars are persisted like any other stream by pointing a scribe at the output path.
+
+use super::{binance, hyperliquid};
+use crate::constants::BAR_CLOSE_GRACE_MS;
+use crate::recording::{ArgusParquetable, ArgusReplayable, batch_column, millis_to_utc};
+use crate::types::{Price, TradingSymbol};
+use agora::utils::OrError;
+use agora::{Agorable, AgorableOption, ConnectionHandle, Publisher, Subscriber};
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use futures_util::StreamExt;
+use indoc::writedoc;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Trades that can be folded into bars
+pub trait BarSource: Agorable {
+    fn symbol(&self) -> TradingSymbol;
+    fn price(&self) -> Price;
+    fn size(&self) -> f64;
+    /// Exchange-side trade time, used for bucketing
+    fn trade_time(&self) -> DateTime<Utc>;
+}
+
+impl BarSource for hyperliquid::TradeUpdate {
+    fn symbol(&self) -> TradingSymbol {
+        self.symbol.clone()
+    }
+    fn price(&self) -> Price {
+        self.price.clone()
+    }
+    fn size(&self) -> f64 {
+        self.size.to_f64()
+    }
+    fn trade_time(&self) -> DateTime<Utc> {
+        self.trade_time
+    }
+}
+
+impl BarSource for binance::TradeUpdate {
+    fn symbol(&self) -> TradingSymbol {
+        self.symbol.clone()
+    }
+    fn price(&self) -> Price {
+        self.price.clone()
+    }
+    fn size(&self) -> f64 {
+        self.size.to_f64()
+    }
+    fn trade_time(&self) -> DateTime<Utc> {
+        self.trade_time
+    }
+}
+
+/// OHLCV bar over [start, end)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bar {
+    pub symbol: TradingSymbol,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    pub volume: f64,
+    pub trade_count: u64,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Bar {
+    fn of_trade(
+        symbol: TradingSymbol,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        price: Price,
+        size: f64,
+    ) -> Self {
+        Self {
+            symbol,
+            open: price.clone(),
+            high: price.clone(),
+            low: price.clone(),
+            close: price,
+            volume: size,
+            trade_count: 1,
+            start,
+            end,
+        }
+    }
+
+    /// Empty bar carrying forward the previous close with zero volume
+    fn carry_forward(previous: &Bar, interval: Duration) -> Self {
+        Self {
+            symbol: previous.symbol.clone(),
+            open: previous.close.clone(),
+            high: previous.close.clone(),
+            low: previous.close.clone(),
+            close: previous.close.clone(),
+            volume: 0.0,
+            trade_count: 0,
+            start: previous.end,
+            end: previous.end + interval,
+        }
+    }
+
+    fn update(&mut self, price: Price, size: f64) {
+        if self.trade_count == 0 {
+            self.open = price.clone();
+            self.high = price.clone();
+            self.low = price.clone();
+        } else {
+            if price.to_f64() > self.high.to_f64() {
+                self.high = price.clone();
+            }
+            if price.to_f64() < self.low.to_f64() {
+                self.low = price.clone();
+            }
+        }
+        self.close = price;
+        self.volume += size;
+        self.trade_count += 1;
+    }
+}
+
+impl fmt::Display for Bar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writedoc!(
+            f,
+            "(
+                Bar {} [{}, {})
+                OHLC: {} / {} / {} / {}
+                Volume: {} over {} trades
+            )",
+            self.symbol.as_str(),
+            self.start,
+            self.end,
+            self.open.to_f64(),
+            self.high.to_f64(),
+            self.low.to_f64(),
+            self.close.to_f64(),
+            self.volume,
+            self.trade_count
+        )
+    }
+}
+
+impl Agorable for Bar {}
+
+/// Buckets trades of a single symbol into fixed-interval bars aligned to the Unix epoch.
+///
+/// No bars are produced before the first trade. After it, every bucket boundary yields a bar,
+/// including empty ones. Trades older than the open bucket (late arrivals) are folded into it,
+/// since closed bars are never reopened.
+pub struct BarBuilder {
+    symbol: TradingSymbol,
+    interval: Duration,
+    current: Option<Bar>,
+}
+
+impl BarBuilder {
+    pub fn new(symbol: TradingSymbol, interval: Duration) -> OrError<Self> {
+        if interval.num_milliseconds() <= 0 {
+            return Err(anyhow::anyhow!(
+                "BarBuilder error: interval must be at least 1ms, got {}",
+                interval
+            ));
+        }
+        Ok(Self {
+            symbol,
+            interval,
+            current: None,
+        })
+    }
+
+    fn bucket_start(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let interval_ms = self.interval.num_milliseconds();
+        let millis = time.timestamp_millis();
+        DateTime::from_timestamp_millis(millis - millis.rem_euclid(interval_ms)).unwrap_or(time)
+    }
+
+    /// Folds a trade in and returns every bar closed by its arrival
+    pub fn push_trade(&mut self, time: DateTime<Utc>, price: Price, size: f64) -> Vec<Bar> {
+        let closed = self.advance_to(time);
+        match &mut self.current {
+            Some(bar) => bar.update(price, size),
+            None => {
+                let start = self.bucket_start(time);
+                self.current = Some(Bar::of_trade(
+                    self.symbol.clone(),
+                    start,
+                    start + self.interval,
+                    price,
+                    size,
+                ));
+            }
+        }
+        closed
+    }
+
+    /// Closes every bar ending at or before `now`, carrying the close forward through empty buckets
+    pub fn advance_to(&mut self, now: DateTime<Utc>) -> Vec<Bar> {
+        let mut closed = Vec::new();
+        while let Some(bar) = self.current.take() {
+            if bar.end > now {
+                self.current = Some(bar);
+                break;
+            }
+            self.current = Some(Bar::carry_forward(&bar, self.interval));
+            closed.push(bar);
+        }
+        closed
+    }
+
+    /// The bar currently being built, if any trade has been seen
+    pub fn current(&self) -> Option<&Bar> {
+        self.current.as_ref()
+    }
+}
+
+/// Subscribes to a trade path and publishes bars onto a derived path.
+///
+/// Bars close on trade arrival and on a timer tick every interval, so quiet markets still emit.
+/// The timer lags wall clock by `BAR_CLOSE_GRACE_MS` to absorb exchange/local clock skew.
+pub struct BarAggregator {
+    output_path: String,
+    task_handle: JoinHandle<()>,
+}
+
+impl BarAggregator {
+    /// # Arguments
+    /// * `source_path` - Agora path publishing `AgorableOption<T>` trades (e.g., "argus/hyperliquid/perp/last_trade/BTC_PERP")
+    /// * `output_path` - Agora path to publish `AgorableOption<Bar>` onto (e.g., "argus/hyperliquid/perp/bar_1m/BTC_PERP")
+    /// * `interval` - Bar length (e.g., 1s, 1m)
+    pub async fn new<T: BarSource>(
+        name: String,
+        source_path: String,
+        output_path: String,
+        interval: Duration,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        let tick = interval
+            .to_std()
+            .ok()
+            .filter(|d| !d.is_zero())
+            .ok_or_else(|| anyhow::anyhow!("BarAggregator error: interval must be positive, got {}", interval))?;
+
+        let mut subscriber =
+            Subscriber::<AgorableOption<T>>::new(source_path.clone(), metaserver_connection).await?;
+        let (_, mut stream) = subscriber.get_stream().await?;
+        let mut publisher = Publisher::<AgorableOption<Bar>>::new(
+            name,
+            output_path.clone(),
+            AgorableOption(None),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await?;
+
+        let grace = Duration::milliseconds(BAR_CLOSE_GRACE_MS);
+        let source_path_ = source_path.clone();
+        let task_handle = tokio::spawn(async move {
+            let _subscriber = subscriber; // Keep the source connection alive
+            let mut builder: Option<BarBuilder> = None;
+            let mut ticker = tokio::time::interval(tick);
+            loop {
+                let closed = tokio::select! {
+                    item = stream.next() => match item {
+                        Some(Ok(AgorableOption(Some(trade)))) => {
+                            // First trade ever: start bucketing from its bucket
+                            builder
+                                .get_or_insert_with(|| {
+                                    BarBuilder::new(trade.symbol(), interval)
+                                        .expect("interval validated above")
+                                })
+                                .push_trade(trade.trade_time(), trade.price(), trade.size())
+                        }
+                        Some(Ok(AgorableOption(None))) => continue,
+                        Some(Err(e)) => {
+                            warn!(source = %source_path_, error = %e, "BarAggregator: stream error");
+                            continue;
+                        }
+                        None => {
+                            warn!(source = %source_path_, "BarAggregator: source closed");
+                            break;
+                        }
+                    },
+                    _ = ticker.tick() => match &mut builder {
+                        Some(builder) => builder.advance_to(Utc::now() - grace),
+                        None => continue,
+                    },
+                };
+                for bar in closed {
+                    if let Err(e) = publisher.publish(AgorableOption(Some(bar))).await {
+                        warn!(source = %source_path_, error = %e, "BarAggregator: publish error");
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            output_path,
+            task_handle,
+        })
+    }
+
+    pub fn output_path(&self) -> &str {
+        &self.output_path
+    }
+}
+
+impl Drop for BarAggregator {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
+
+impl ArgusParquetable for Bar {
+    fn arrow_schema() -> std::sync::Arc<arrow::datatypes::Schema> {
+        use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+        use std::sync::Arc;
+
+        let timestamp = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+        Arc::new(Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("open", DataType::Float64, false),
+            Field::new("high", DataType::Float64, false),
+            Field::new("low", DataType::Float64, false),
+            Field::new("close", DataType::Float64, false),
+            Field::new("volume", DataType::Float64, false),
+            Field::new("trade_count", DataType::UInt64, false),
+            Field::new("start", timestamp.clone(), false),
+            Field::new("end", timestamp, false),
+        ]))
+    }
+
+    fn to_record_batch(data: Vec<Self>) -> OrError<arrow::record_batch::RecordBatch> {
+        use arrow::array::{ArrayRef, Float64Array, StringArray, TimestampMillisecondArray, UInt64Array};
+        use arrow::record_batch::RecordBatch;
+        use std::sync::Arc;
+
+        let prices = |f: fn(&Bar) -> &Price| -> ArrayRef {
+            Arc::new(Float64Array::from(
+                data.iter().map(|d| f(d).to_f64()).collect::<Vec<_>>(),
+            ))
+        };
+        let times = |f: fn(&Bar) -> DateTime<Utc>| -> ArrayRef {
+            Arc::new(
+                TimestampMillisecondArray::from(
+                    data.iter().map(|d| f(d).timestamp_millis()).collect::<Vec<_>>(),
+                )
+                .with_timezone("UTC"),
+            )
+        };
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(
+                data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
+            )),
+            prices(|d| &d.open),
+            prices(|d| &d.high),
+            prices(|d| &d.low),
+            prices(|d| &d.close),
+            Arc::new(Float64Array::from(
+                data.iter().map(|d| d.volume).collect::<Vec<_>>(),
+            )),
+            Arc::new(UInt64Array::from(
+                data.iter().map(|d| d.trade_count).collect::<Vec<_>>(),
+            )),
+            times(|d| d.start),
+            times(|d| d.end),
+        ];
+
+        RecordBatch::try_new(Self::arrow_schema(), columns).context("Failed to create RecordBatch")
+    }
+}
+
+impl ArgusReplayable for Bar {
+    fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> OrError<Vec<Self>> {
+        use arrow::array::{Float64Array, StringArray, TimestampMillisecondArray, UInt64Array};
+
+        let symbols = batch_column::<StringArray>(batch, "symbol")?;
+        let open = batch_column::<Float64Array>(batch, "open")?;
+        let high = batch_column::<Float64Array>(batch, "high")?;
+        let low = batch_column::<Float64Array>(batch, "low")?;
+        let close = batch_column::<Float64Array>(batch, "close")?;
+        let volume = batch_column::<Float64Array>(batch, "volume")?;
+        let trade_count = batch_column::<UInt64Array>(batch, "trade_count")?;
+        let start = batch_column::<TimestampMillisecondArray>(batch, "start")?;
+        let end = batch_column::<TimestampMillisecondArray>(batch, "end")?;
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(Bar {
+                    symbol: TradingSymbol::from_str(symbols.value(i))?,
+                    open: Price::from_f64(open.value(i))?,
+                    high: Price::from_f64(high.value(i))?,
+                    low: Price::from_f64(low.value(i))?,
+                    close: Price::from_f64(close.value(i))?,
+                    volume: volume.value(i),
+                    trade_count: trade_count.value(i),
+                    start: millis_to_utc(start.value(i))?,
+                    end: millis_to_utc(end.value(i))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Bars become known when they close
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.end
+    }
+}
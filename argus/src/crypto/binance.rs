@@ -1,4 +1,5 @@
 mod bbo;
+mod book_sync;
 mod orderbook;
 mod publisher;
 mod trades;
@@ -27,6 +28,7 @@ pub trait BinanceRest: Agorable + Sized {
 }
 
 pub use bbo::BboUpdate;
+pub use book_sync::{BinanceBookSyncWorker, BinanceBookSynchronizer, fetch_depth_snapshot};
 pub use orderbook::{OrderbookDepthUpdate, OrderbookDiffUpdate};
 pub use publisher::{BinanceWebstreamSymbols, BinanceWebstreamWorker};
 pub use trades::TradeUpdate;
@@ -32,7 +32,7 @@ impl fmt::Display for BboUpdate {
                 {:?}, {:?} | {:?}, {:?} 
             )",
             self.update_id,
-            self.symbol.to_string(),
+            self.symbol.as_str(),
             self.received_time,
             self.bid_size,
             self.bid_price,
@@ -77,7 +77,7 @@ impl BinanceStreamable for BboUpdate {
         )?;
         let bbo_update = BboUpdate {
             symbol: TradingSymbol::from_str(&raw.symbol)?,
-            received_time: received_time,
+            received_time,
             update_id: raw.orderbook_update_id,
             bid_price: Price::from_string(raw.bid_price)?,
             bid_size: TradeSize::from_string(raw.bid_size)?,
@@ -130,9 +130,7 @@ impl ArgusParquetable for BboUpdate {
         let schema = Self::arrow_schema();
 
         let symbols: ArrayRef = Arc::new(StringArray::from(
-            data.iter()
-                .map(|d| d.symbol.to_string())
-                .collect::<Vec<_>>(),
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
         ));
 
         let received_times: ArrayRef = Arc::new(
@@ -0,0 +1,499 @@
+// Local Binance orderbooks maintained from a REST depth snapshot plus `@depth` diffs.
+//
+// Follows Binance's documented procedure: buffer diffs from the stream, fetch a snapshot, drop
+// buffered diffs already contained in it (`u <= lastUpdateId`), then apply diffs as long as each
+// one starts at or before the next expected id (`U <= last + 1 <= u`). A diff starting later means
+// updates were missed: the book is discarded and rebuilt from a fresh snapshot.
+
+use super::{BinanceRest, BinanceStreamable, OrderbookDepthUpdate, OrderbookDiffUpdate};
+use crate::constants::{
+    BINANCE_BOOK_SYNC_MAX_BUFFERED_DIFFS, BINANCE_BOOK_SYNC_RETRY_DELAY_MS,
+    BINANCE_DEPTH_SNAPSHOT_LIMIT, BINANCE_ORDERBOOK_SNAPSHOT_LEVELS, BINANCE_SPOT_REST_ENDPOINT,
+    BINANCE_SPOT_WEBSTREAM_ENDPOINT,
+};
+use crate::crypto::hyperliquid::OrderbookSnapshot;
+use crate::metrics::{self, Counter};
+use crate::recording::BookState;
+use crate::supervised::spawn_supervised;
+use crate::types::{Price, TradeSize, TradingSymbol};
+use agora::utils::OrError;
+use agora::{AgorableOption, ConnectionHandle, Publisher};
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{error, info, warn};
+
+/// Keeps one symbol's book in sync with Binance from depth snapshots and diffs.
+/// Pure state machine: the caller feeds diffs (`on_diff`) and snapshots (`on_snapshot`) and fetches
+/// a new snapshot whenever `needs_snapshot()` is true.
+#[derive(Debug)]
+pub struct BinanceBookSynchronizer {
+    symbol: TradingSymbol,
+    max_levels: usize,
+    book: BookState,
+    /// Final update id reflected in `book`; None while waiting for a snapshot
+    last_update_id: Option<u64>,
+    /// Diffs received while waiting for a snapshot, oldest first
+    buffered: VecDeque<OrderbookDiffUpdate>,
+    gaps: u64,
+}
+
+impl BinanceBookSynchronizer {
+    /// Emitted snapshots carry the best `max_levels` per side (default `BINANCE_ORDERBOOK_SNAPSHOT_LEVELS`)
+    pub fn new(symbol: TradingSymbol, max_levels: Option<usize>) -> Self {
+        Self {
+            symbol,
+            max_levels: max_levels.unwrap_or(BINANCE_ORDERBOOK_SNAPSHOT_LEVELS),
+            book: BookState::default(),
+            last_update_id: None,
+            buffered: VecDeque::new(),
+            gaps: 0,
+        }
+    }
+
+    pub fn symbol(&self) -> &TradingSymbol {
+        &self.symbol
+    }
+
+    /// True until a snapshot has been applied, and again after a gap or `reset`
+    pub fn needs_snapshot(&self) -> bool {
+        self.last_update_id.is_none()
+    }
+
+    /// Final update id the book reflects; None while waiting for a snapshot
+    pub fn last_update_id(&self) -> Option<u64> {
+        self.last_update_id
+    }
+
+    /// Gaps detected in the diff stream since construction; each one forced a re-snapshot
+    pub fn gaps(&self) -> u64 {
+        self.gaps
+    }
+
+    pub fn buffered_len(&self) -> usize {
+        self.buffered.len()
+    }
+
+    /// Discards the book and buffered diffs, e.g. after the stream reconnected and diffs were missed
+    pub fn reset(&mut self) {
+        self.book = BookState::default();
+        self.last_update_id = None;
+        self.buffered.clear();
+    }
+
+    /// Applies `diff` to a synced book and returns the updated top of book.
+    /// While waiting for a snapshot the diff is buffered (at most `BINANCE_BOOK_SYNC_MAX_BUFFERED_DIFFS`, oldest dropped) and None returned.
+    /// A diff starting past the next expected id is a gap: the book is discarded and the diff buffered for the next snapshot.
+    /// Error: Diff for another symbol, or a level size that is negative or non-finite. The book is left unchanged.
+    pub fn on_diff(&mut self, diff: OrderbookDiffUpdate) -> OrError<Option<OrderbookSnapshot>> {
+        if diff.symbol != self.symbol {
+            return Err(anyhow::anyhow!(
+                "BinanceBookSynchronizer error: diff for {} fed to the {} book",
+                diff.symbol.to_string(),
+                self.symbol.to_string()
+            ));
+        }
+        validate_diff_sizes(&diff)?;
+        if self.needs_snapshot() {
+            self.buffer(diff);
+            return Ok(None);
+        }
+        let (time, received_time) = (diff.event_time, diff.received_time);
+        if self.advance(diff) {
+            Ok(Some(self.snapshot(time, received_time)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Rebuilds the book from `depth`, then replays the buffered diffs newer than it.
+    /// Returns the top of book once synced. Returns None, still needing a snapshot, when `depth` is older
+    /// than the first buffered diff (fetch another) or the buffered diffs have a gap after it.
+    /// A snapshot arriving while already synced is ignored.
+    /// Error: Snapshot for another symbol
+    pub fn on_snapshot(
+        &mut self,
+        depth: &OrderbookDepthUpdate,
+    ) -> OrError<Option<OrderbookSnapshot>> {
+        if depth.symbol != self.symbol {
+            return Err(anyhow::anyhow!(
+                "BinanceBookSynchronizer error: snapshot for {} fed to the {} book",
+                depth.symbol.to_string(),
+                self.symbol.to_string()
+            ));
+        }
+        if !self.needs_snapshot() {
+            return Ok(None);
+        }
+        if let Some(first) = self.buffered.front()
+            && depth.last_update_id + 1 < first.first_update_id
+        {
+            warn!(
+                symbol = %self.symbol.to_string(),
+                last_update_id = depth.last_update_id,
+                first_buffered = first.first_update_id,
+                "depth snapshot older than buffered diffs, refetching"
+            );
+            return Ok(None);
+        }
+
+        let to_f64 = |levels: &[(Price, TradeSize)]| -> Vec<(Price, f64)> {
+            levels
+                .iter()
+                .map(|(price, size)| (price.clone(), size.to_f64()))
+                .collect()
+        };
+        self.book = BookState::from_levels(&to_f64(&depth.bid_levels), &to_f64(&depth.ask_levels));
+        self.last_update_id = Some(depth.last_update_id);
+
+        let (mut time, mut received_time) = (depth.received_time, depth.received_time);
+        let mut pending = std::mem::take(&mut self.buffered);
+        while let Some(diff) = pending.pop_front() {
+            if self.needs_snapshot() {
+                // A gap during replay: keep the rest for the next snapshot
+                self.buffered.push_back(diff);
+                continue;
+            }
+            let (diff_time, diff_received_time) = (diff.event_time, diff.received_time);
+            if self.advance(diff) {
+                (time, received_time) = (diff_time, diff_received_time);
+            }
+        }
+        if self.needs_snapshot() {
+            return Ok(None);
+        }
+        info!(
+            symbol = %self.symbol.to_string(),
+            last_update_id = ?self.last_update_id,
+            "orderbook synced"
+        );
+        Ok(Some(self.snapshot(time, received_time)?))
+    }
+
+    /// Applies `diff` to the synced book. False when it was already contained in the book, or on a gap
+    fn advance(&mut self, diff: OrderbookDiffUpdate) -> bool {
+        let Some(last_update_id) = self.last_update_id else {
+            self.buffer(diff);
+            return false;
+        };
+        if diff.final_update_id <= last_update_id {
+            return false;
+        }
+        if diff.first_update_id > last_update_id + 1 {
+            self.gaps += 1;
+            warn!(
+                symbol = %self.symbol.to_string(),
+                expected = last_update_id + 1,
+                first_update_id = diff.first_update_id,
+                "gap in depth diffs, resyncing from a new snapshot"
+            );
+            self.book = BookState::default();
+            self.last_update_id = None;
+            self.buffer(diff);
+            return false;
+        }
+        self.book.apply(&diff.bids, &diff.asks);
+        self.last_update_id = Some(diff.final_update_id);
+        true
+    }
+
+    fn buffer(&mut self, diff: OrderbookDiffUpdate) {
+        if self.buffered.len() >= BINANCE_BOOK_SYNC_MAX_BUFFERED_DIFFS {
+            self.buffered.pop_front();
+        }
+        self.buffered.push_back(diff);
+    }
+
+    fn snapshot(
+        &self,
+        time: DateTime<Utc>,
+        received_time: DateTime<Utc>,
+    ) -> OrError<OrderbookSnapshot> {
+        let levels = |side: Vec<(Price, f64)>| -> OrError<Vec<(Price, TradeSize, u32)>> {
+            side.into_iter()
+                .take(self.max_levels)
+                .map(|(price, size)| Ok((price, TradeSize::from_f64(size)?, 0)))
+                .collect()
+        };
+        Ok(OrderbookSnapshot {
+            symbol: self.symbol.clone(),
+            received_time,
+            time,
+            bid_levels: levels(self.book.bids())?,
+            ask_levels: levels(self.book.asks())?,
+        })
+    }
+}
+
+fn validate_diff_sizes(diff: &OrderbookDiffUpdate) -> OrError<()> {
+    for (price, size) in diff.bids.iter().chain(diff.asks.iter()) {
+        if !size.is_finite() || *size < 0.0 {
+            return Err(anyhow::anyhow!(
+                "BinanceBookSynchronizer error: invalid size {} at price {} in diff {}-{}",
+                size,
+                price.to_f64(),
+                diff.first_update_id,
+                diff.final_update_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Fetches the REST depth snapshot (`BINANCE_DEPTH_SNAPSHOT_LIMIT` levels per side) for `symbol`
+/// Error: Request fails, non-success status, or unparseable body
+pub async fn fetch_depth_snapshot(
+    client: &reqwest::Client,
+    symbol: &TradingSymbol,
+) -> OrError<OrderbookDepthUpdate> {
+    let url = format!(
+        "{}/{}?symbol={}&limit={}",
+        BINANCE_SPOT_REST_ENDPOINT,
+        OrderbookDepthUpdate::rest_suffix(),
+        symbol.to_string().to_uppercase(),
+        BINANCE_DEPTH_SNAPSHOT_LIMIT
+    );
+    let request_time = Utc::now();
+    let response = client.get(&url).send().await.map_err(|e| {
+        anyhow::anyhow!(
+            "Argus Binance depth snapshot error: request to {} failed: {}",
+            url,
+            e
+        )
+    })?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "Argus Binance depth snapshot error: {} returned {}",
+            url,
+            response.status()
+        ));
+    }
+    let body = response.text().await.map_err(|e| {
+        anyhow::anyhow!(
+            "Argus Binance depth snapshot error: cannot read body from {}: {}",
+            url,
+            e
+        )
+    })?;
+    OrderbookDepthUpdate::of_json_bytes(body.into(), &symbol.to_string(), request_time)
+}
+
+/// Publishes synchronized Binance books as `OrderbookSnapshot`s to `{agora_prefix}/orderbook/{symbol}`.
+/// Streams `@depth@100ms` diffs for all symbols over one connection and fetches REST snapshots per
+/// symbol whenever its `BinanceBookSynchronizer` needs one. A reconnect resets every book.
+pub struct BinanceBookSyncWorker {
+    agora_paths: Vec<String>,
+    dispatch_handle: JoinHandle<()>,
+}
+
+impl BinanceBookSyncWorker {
+    pub async fn new(
+        symbols: &[TradingSymbol],
+        agora_prefix: &str,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        if symbols.is_empty() {
+            return Err(anyhow::anyhow!(
+                "BinanceBookSyncWorker error: symbols list cannot be empty"
+            ));
+        }
+        if symbols.len() > 1024 {
+            return Err(anyhow::anyhow!(
+                "BinanceBookSyncWorker error: don't pass in more than 1024 tasks per worker"
+            ));
+        }
+
+        let agora_paths: Vec<String> = symbols
+            .iter()
+            .map(|symbol| format!("{}/orderbook/{}", agora_prefix, symbol.to_string()))
+            .collect();
+        let mut publishers: Vec<Publisher<AgorableOption<OrderbookSnapshot>>> = Vec::new();
+        for (symbol, agora_path) in symbols.iter().zip(agora_paths.iter()) {
+            // Scribe relies on the publisher name to identify symbols
+            let publisher = Publisher::<AgorableOption<OrderbookSnapshot>>::new(
+                symbol.to_string(),
+                agora_path.clone(),
+                AgorableOption(None),
+                metaserver_connection,
+                local_gateway_port,
+            )
+            .await?;
+            publishers.push(publisher);
+        }
+
+        let symbol_to_index: HashMap<String, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(idx, symbol)| (symbol.to_string().to_lowercase(), idx))
+            .collect();
+        let mut books: Vec<BinanceBookSynchronizer> = symbols
+            .iter()
+            .map(|symbol| BinanceBookSynchronizer::new(symbol.clone(), None))
+            .collect();
+        let gap_counters: Vec<Counter> = symbols
+            .iter()
+            .map(|symbol| metrics::sequence_gaps("spot", "orderbook", &symbol.to_string()))
+            .collect();
+        let stream_names: Vec<String> = symbols
+            .iter()
+            .map(|symbol| {
+                format!(
+                    "{}{}",
+                    symbol.to_string().to_lowercase(),
+                    OrderbookDiffUpdate::websocket_suffix()
+                )
+            })
+            .collect();
+        let ws_url = format!(
+            "{}/{}",
+            BINANCE_SPOT_WEBSTREAM_ENDPOINT,
+            stream_names.join("/")
+        );
+        info!(url = %ws_url, "connecting to Binance WebSocket for orderbook sync");
+
+        let client = reqwest::Client::new();
+        let retry_delay = Duration::from_millis(BINANCE_BOOK_SYNC_RETRY_DELAY_MS);
+        let worker = async move {
+            // Snapshot fetches report back here, tagged with the symbol index and connection number
+            let (snapshot_tx, mut snapshot_rx) =
+                mpsc::unbounded_channel::<(usize, u64, OrError<OrderbookDepthUpdate>)>();
+            let mut connection: u64 = 0;
+            loop {
+                match connect_async(&ws_url).await {
+                    Ok((ws_stream, _)) => {
+                        connection += 1;
+                        let (mut write, mut read) = ws_stream.split();
+                        // Diffs were missed while disconnected
+                        books.iter_mut().for_each(BinanceBookSynchronizer::reset);
+                        let mut fetching = vec![false; books.len()];
+                        // Consecutive fetches that did not sync the book; later ones wait `retry_delay`
+                        let mut attempts = vec![0u32; books.len()];
+
+                        loop {
+                            // Once a book's diffs are buffering, it has one snapshot fetch in flight until synced
+                            for (idx, book) in books.iter().enumerate() {
+                                if !book.needs_snapshot() {
+                                    attempts[idx] = 0;
+                                    continue;
+                                }
+                                if fetching[idx] || book.buffered_len() == 0 {
+                                    continue;
+                                }
+                                fetching[idx] = true;
+                                let delay = if attempts[idx] == 0 {
+                                    Duration::ZERO
+                                } else {
+                                    retry_delay
+                                };
+                                attempts[idx] += 1;
+                                let (client, symbol, snapshot_tx) =
+                                    (client.clone(), book.symbol().clone(), snapshot_tx.clone());
+                                tokio::spawn(async move {
+                                    tokio::time::sleep(delay).await;
+                                    let depth = fetch_depth_snapshot(&client, &symbol).await;
+                                    let _ = snapshot_tx.send((idx, connection, depth));
+                                });
+                            }
+
+                            tokio::select! {
+                                Some((idx, fetched_on, depth)) = snapshot_rx.recv() => {
+                                    if fetched_on != connection {
+                                        continue;
+                                    }
+                                    fetching[idx] = false;
+                                    let gaps_before = books[idx].gaps();
+                                    let snapshot = match depth {
+                                        Ok(depth) => books[idx].on_snapshot(&depth),
+                                        Err(e) => {
+                                            warn!(symbol = %books[idx].symbol().to_string(), error = %e, "BinanceBookSyncWorker: snapshot fetch failed");
+                                            continue;
+                                        }
+                                    };
+                                    gap_counters[idx].add(books[idx].gaps() - gaps_before);
+                                    Self::publish(&mut publishers[idx], snapshot).await;
+                                }
+                                message = read.next() => {
+                                    match message {
+                                        Some(Ok(Message::Ping(ping_data))) => {
+                                            if let Err(e) = write.send(Message::Pong(ping_data)).await {
+                                                error!(error = %e, "BinanceBookSyncWorker: failed to send pong");
+                                                break;
+                                            }
+                                        }
+                                        Some(Ok(Message::Text(text))) => {
+                                            let diff = match OrderbookDiffUpdate::of_json_bytes(text) {
+                                                Ok(diff) => diff,
+                                                Err(e) => {
+                                                    warn!(error = %e, "BinanceBookSyncWorker: parse error");
+                                                    continue;
+                                                }
+                                            };
+                                            let symbol = diff.symbol.to_string().to_lowercase();
+                                            let Some(&idx) = symbol_to_index.get(&symbol) else {
+                                                warn!(symbol = %symbol, "BinanceBookSyncWorker: received diff for unknown symbol");
+                                                continue;
+                                            };
+                                            let gaps_before = books[idx].gaps();
+                                            let snapshot = books[idx].on_diff(diff);
+                                            gap_counters[idx].add(books[idx].gaps() - gaps_before);
+                                            Self::publish(&mut publishers[idx], snapshot).await;
+                                        }
+                                        Some(Ok(Message::Close(_))) | None => {
+                                            warn!("BinanceBookSyncWorker: connection closed by server");
+                                            break;
+                                        }
+                                        Some(Ok(_)) => {}
+                                        Some(Err(e)) => {
+                                            error!(error = %e, "BinanceBookSyncWorker: WebSocket error");
+                                            break;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "BinanceBookSyncWorker: connection error, retrying in 5s");
+                    }
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            }
+        };
+        let dispatch_handle = spawn_supervised("binance_book_sync", worker, |_| {});
+
+        Ok(Self {
+            agora_paths,
+            dispatch_handle,
+        })
+    }
+
+    async fn publish(
+        publisher: &mut Publisher<AgorableOption<OrderbookSnapshot>>,
+        snapshot: OrError<Option<OrderbookSnapshot>>,
+    ) {
+        match snapshot {
+            Ok(Some(snapshot)) => {
+                if let Err(e) = publisher.publish(AgorableOption(Some(snapshot))).await {
+                    warn!(error = %e, "BinanceBookSyncWorker: publish error");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!(error = %e, "BinanceBookSyncWorker: rejected book update"),
+        }
+    }
+
+    pub fn agora_paths(&self) -> &[String] {
+        &self.agora_paths
+    }
+}
+
+impl Drop for BinanceBookSyncWorker {
+    fn drop(&mut self) {
+        self.dispatch_handle.abort()
+    }
+}
@@ -50,7 +50,7 @@ impl fmt::Display for OrderbookDiffUpdate {
                 Bids: {} levels
                 Asks: {} levels
             )",
-            self.symbol.to_string(),
+            self.symbol.as_str(),
             self.event_time,
             self.received_time,
             self.first_update_id,
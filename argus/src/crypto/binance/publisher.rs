@@ -1,5 +1,8 @@
-use super::{BboUpdate, BinanceStreamable, OrderbookDiffUpdate, TradeUpdate};
+use super::{
+    BboUpdate, BinanceBookSyncWorker, BinanceStreamable, OrderbookDiffUpdate, TradeUpdate,
+};
 use crate::constants::BINANCE_SPOT_WEBSTREAM_ENDPOINT;
+use crate::supervised::spawn_supervised;
 use crate::types::TradingSymbol;
 use agora::utils::OrError;
 use agora::{AgorableOption, ConnectionHandle, Publisher};
@@ -8,6 +11,7 @@ use std::collections::HashMap;
 use std::marker::PhantomData;
 use tokio::task::JoinHandle;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{error, info, warn};
 
 pub struct BinanceWebstreamWorker<T: BinanceStreamable> {
     agora_paths: Vec<String>,
@@ -83,10 +87,10 @@ impl<T: BinanceStreamable> BinanceWebstreamWorker<T> {
             BINANCE_SPOT_WEBSTREAM_ENDPOINT,
             stream_names.join("/")
         );
-        println!("{}", ws_url);
+        info!(url = %ws_url, "connecting to Binance WebSocket");
 
         // Spawn worker task to handle websocket connection
-        let worker_task = tokio::spawn(async move {
+        let worker = async move {
             loop {
                 match connect_async(&ws_url).await {
                     Ok((ws_stream, _)) => {
@@ -97,10 +101,7 @@ impl<T: BinanceStreamable> BinanceWebstreamWorker<T> {
                                 Ok(Message::Ping(ping_data)) => {
                                     // Respond to Ping with Pong to keep connection alive
                                     if let Err(e) = write.send(Message::Pong(ping_data)).await {
-                                        eprintln!(
-                                            "BinanceWebstreamWorker error sending Pong: {}",
-                                            e
-                                        );
+                                        error!(error = %e, "BinanceWebstreamWorker: failed to send pong");
                                         break;
                                     }
                                 }
@@ -116,31 +117,30 @@ impl<T: BinanceStreamable> BinanceWebstreamWorker<T> {
                                             {
                                                 if let Some(publisher) =
                                                     publishers.get_mut(publisher_idx)
-                                                {
-                                                    if let Err(e) = publisher
+                                                    && let Err(e) = publisher
                                                         .publish(AgorableOption(Some(parsed_msg)))
                                                         .await
-                                                    {
-                                                        eprintln!(
-                                                            "BinanceWebstreamWorker publish error for {}: {}",
-                                                            symbol_str, e
-                                                        );
-                                                    }
+                                                {
+                                                    warn!(
+                                                        symbol = %symbol_str,
+                                                        error = %e,
+                                                        "BinanceWebstreamWorker: publish error"
+                                                    );
                                                 }
                                             } else {
-                                                eprintln!(
-                                                    "BinanceWebstreamWorker: no publisher found for symbol {}",
-                                                    symbol_str
+                                                warn!(
+                                                    symbol = %symbol_str,
+                                                    "BinanceWebstreamWorker: no publisher for symbol"
                                                 );
                                             }
                                         }
                                         Err(e) => {
-                                            eprintln!("BinanceWebstreamWorker parse error: {}", e);
+                                            warn!(error = %e, "BinanceWebstreamWorker: parse error");
                                         }
                                     }
                                 }
                                 Ok(Message::Close(_)) => {
-                                    eprintln!(
+                                    warn!(
                                         "BinanceWebstreamWorker: received Close frame, disconnecting"
                                     );
                                     break;
@@ -149,23 +149,21 @@ impl<T: BinanceStreamable> BinanceWebstreamWorker<T> {
                                     // Ignore other message types (Pong, Binary, Frame)
                                 }
                                 Err(e) => {
-                                    eprintln!("BinanceWebstreamWorker websocket error: {}", e);
+                                    warn!(error = %e, "BinanceWebstreamWorker: websocket error");
                                     break;
                                 }
                             }
                         }
                     }
                     Err(e) => {
-                        eprintln!(
-                            "BinanceWebstreamWorker connection error: {}, retrying in 5s...",
-                            e
-                        );
+                        warn!(error = %e, "BinanceWebstreamWorker: connection error, retrying in 5s");
                     }
                 }
                 // Wait before retry (for both connection failures and disconnections)
                 tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
             }
-        });
+        };
+        let worker_task = spawn_supervised("binance_webstream", worker, |_| {});
 
         Ok(Self {
             agora_paths,
@@ -193,6 +191,7 @@ pub struct BinanceWebstreamSymbols {
     _trade_worker: BinanceWebstreamWorker<TradeUpdate>,
     _bbo_worker: BinanceWebstreamWorker<BboUpdate>,
     _orderbookdiff_worker: BinanceWebstreamWorker<OrderbookDiffUpdate>,
+    _orderbook_worker: BinanceBookSyncWorker,
 }
 
 impl BinanceWebstreamSymbols {
@@ -205,21 +204,28 @@ impl BinanceWebstreamSymbols {
         let trade_worker = BinanceWebstreamWorker::<TradeUpdate>::new(
             symbols,
             agora_prefix,
-            metaserver_connection.clone(),
+            metaserver_connection,
             local_gateway_port,
         )
         .await?;
         let orderbookdiff_worker = BinanceWebstreamWorker::<OrderbookDiffUpdate>::new(
             symbols,
             agora_prefix,
-            metaserver_connection.clone(),
+            metaserver_connection,
             local_gateway_port,
         )
         .await?;
         let bbo_worker = BinanceWebstreamWorker::<BboUpdate>::new(
             symbols,
             agora_prefix,
-            metaserver_connection.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await?;
+        let orderbook_worker = BinanceBookSyncWorker::new(
+            symbols,
+            agora_prefix,
+            metaserver_connection,
             local_gateway_port,
         )
         .await?;
@@ -228,6 +234,7 @@ impl BinanceWebstreamSymbols {
             _trade_worker: trade_worker,
             _bbo_worker: bbo_worker,
             _orderbookdiff_worker: orderbookdiff_worker,
+            _orderbook_worker: orderbook_worker,
         })
     }
 
@@ -183,9 +183,7 @@ impl ArgusParquetable for TradeUpdate {
 
         // Convert each field to Arrow arrays
         let symbols: ArrayRef = Arc::new(StringArray::from(
-            data.iter()
-                .map(|d| d.symbol.to_string())
-                .collect::<Vec<_>>(),
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
         ));
 
         let event_times: ArrayRef = Arc::new(
@@ -0,0 +1,87 @@
+mod bbo;
+mod orderbook;
+mod trades;
+mod webstream;
+
+use crate::types::TradingSymbol;
+use agora::Agorable;
+use agora::utils::OrError;
+use chrono::prelude::{DateTime, Utc};
+use serde::Deserialize;
+
+/// Coinbase Advanced Trade channels publish the same payload types as Hyperliquid
+/// (`TradeUpdate`, `BboUpdate`, `OrderbookSnapshot`), so scribes and archivers take them unchanged.
+/// Fields Coinbase does not send (per-level order counts) are 0.
+pub trait CoinbaseStreamable: Agorable + Sized {
+    /// Per-connection parse state, reset on reconnect. `level2` keeps the local books its diffs apply to.
+    type State: Default + Send + 'static;
+
+    /// Parse one channel message into zero or more instances, one symbol each.
+    /// Symbols are normalized product ids (see `normalize_product_id`).
+    fn of_message(message: &CoinbaseMessage, state: &mut Self::State) -> OrError<Vec<Self>>;
+
+    /// Channel name sent in the subscribe request, e.g. `market_trades`
+    fn channel() -> String;
+    /// Channel name on data messages; Coinbase answers `level2` subscriptions on `l2_data`
+    fn message_channel() -> String {
+        Self::channel()
+    }
+    fn payload_identifier() -> String;
+    fn symbol(&self) -> TradingSymbol;
+}
+
+/// Envelope shared by every Advanced Trade channel:
+/// {"channel": "market_trades", "client_id": "", "timestamp": "2023-02-09T20:19:35.39625135Z", "sequence_num": 0, "events": [...]}
+#[derive(Debug, Deserialize)]
+pub struct CoinbaseMessage {
+    /// Empty on `{"type": "error", "message": ...}` replies, which carry no channel
+    #[serde(default)]
+    pub channel: String,
+    #[serde(default)]
+    pub timestamp: String,
+    #[serde(default)]
+    pub sequence_num: u64,
+    #[serde(default)]
+    pub events: serde_json::Value,
+}
+
+impl CoinbaseMessage {
+    /// Server send time of the message
+    pub fn time(&self) -> OrError<DateTime<Utc>> {
+        parse_time(&self.timestamp)
+    }
+}
+
+/// Parses Coinbase's RFC 3339 timestamps, e.g. "2019-08-14T20:42:27.265Z"
+fn parse_time(s: &str) -> OrError<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| anyhow::anyhow!("Argus Coinbase error: invalid timestamp {:?}. {}", s, e))
+}
+
+/// Normalizes a Coinbase product id to `BASE-QUOTE` in upper case: "btc-usd", "BTC/USD" and "BTC_USD" all give "BTC-USD".
+/// Normalized ids are both the subscription product ids and the agora symbols.
+pub fn normalize_product_id(product_id: &str) -> OrError<TradingSymbol> {
+    let normalized = product_id.trim().to_uppercase().replace(['/', '_'], "-");
+    match normalized.split_once('-') {
+        Some((base, quote)) if !base.is_empty() && !quote.is_empty() && !quote.contains('-') => {
+            TradingSymbol::from_str(&normalized)
+        }
+        _ => Err(anyhow::anyhow!(
+            "Argus Coinbase error: product id {:?} is not of the form BASE-QUOTE",
+            product_id
+        )),
+    }
+}
+
+/// Subscribe request for one channel: {"type": "subscribe", "product_ids": [...], "channel": ...}
+pub fn subscribe_message(channel: &str, product_ids: &[TradingSymbol]) -> serde_json::Value {
+    serde_json::json!({
+        "type": "subscribe",
+        "product_ids": product_ids.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+        "channel": channel,
+    })
+}
+
+pub use orderbook::CoinbaseBooks;
+pub use webstream::{CoinbaseWebstreamSymbols, CoinbaseWebstreamWorker};
@@ -0,0 +1,78 @@
+use super::{CoinbaseMessage, CoinbaseStreamable, normalize_product_id};
+use crate::crypto::hyperliquid::BboUpdate;
+use crate::types::{Price, TradeSize, TradingSymbol};
+use agora::utils::OrError;
+use chrono::prelude::Utc;
+use serde::Deserialize;
+
+/// `ticker` event; only the top-of-book fields are read:
+/// {
+///   "type": "update",
+///   "tickers": [{
+///     "type": "ticker",
+///     "product_id": "BTC-USD",
+///     "price": "21932.98",
+///     "best_bid": "21931.98",
+///     "best_bid_quantity": "8000.21",
+///     "best_ask": "21933.98",
+///     "best_ask_quantity": "8038.07770938",
+///     ...  // 24h / 52w statistics
+///   }]
+/// }
+#[derive(Deserialize)]
+struct RawTickerEvent {
+    tickers: Vec<RawTicker>,
+}
+
+#[derive(Deserialize)]
+struct RawTicker {
+    product_id: String,
+    best_bid: String,
+    best_bid_quantity: String,
+    best_ask: String,
+    best_ask_quantity: String,
+}
+
+impl CoinbaseStreamable for BboUpdate {
+    type State = ();
+
+    /// Ticker entries carry no time of their own; `time` is the message timestamp
+    fn of_message(message: &CoinbaseMessage, _state: &mut ()) -> OrError<Vec<Self>> {
+        let received_time = Utc::now();
+        let time = message.time()?;
+        let events: Vec<RawTickerEvent> = serde_json::from_value(message.events.clone()).map_err(|e| {
+            anyhow::anyhow!(
+                "Argus Coinbase BboUpdate conversion error: cannot convert events into Vec<RawTickerEvent>. Check schema. {}",
+                e
+            )
+        })?;
+
+        let mut parsed_bbos = Vec::new();
+        for raw in events.into_iter().flat_map(|event| event.tickers) {
+            parsed_bbos.push(BboUpdate {
+                symbol: normalize_product_id(&raw.product_id)?,
+                received_time,
+                time,
+                bid_price: Price::from_string(raw.best_bid)?,
+                bid_size: TradeSize::from_string(raw.best_bid_quantity)?,
+                bid_orders: 0,
+                ask_price: Price::from_string(raw.best_ask)?,
+                ask_size: TradeSize::from_string(raw.best_ask_quantity)?,
+                ask_orders: 0,
+            });
+        }
+        Ok(parsed_bbos)
+    }
+
+    fn channel() -> String {
+        String::from("ticker")
+    }
+
+    fn payload_identifier() -> String {
+        String::from("bbo")
+    }
+
+    fn symbol(&self) -> TradingSymbol {
+        self.symbol.clone()
+    }
+}
@@ -0,0 +1,174 @@
+use super::{CoinbaseMessage, CoinbaseStreamable, normalize_product_id};
+use crate::constants::COINBASE_ORDERBOOK_SNAPSHOT_LEVELS;
+use crate::crypto::hyperliquid::OrderbookSnapshot;
+use crate::types::{Price, TradeSize, TradingSymbol};
+use agora::utils::OrError;
+use chrono::prelude::Utc;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+/// One side of a snapshot: `(price, size, order count)`, best first
+type Levels = Vec<(Price, TradeSize, u32)>;
+
+/// Book key ordering prices by value (prices are finite, so `total_cmp` is numeric order)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PriceKey(f64);
+
+impl Eq for PriceKey {}
+
+impl PartialOrd for PriceKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriceKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Book {
+    bids: BTreeMap<PriceKey, (Price, TradeSize)>,
+    asks: BTreeMap<PriceKey, (Price, TradeSize)>,
+}
+
+impl Book {
+    /// Best `max_levels` per side, best first
+    fn snapshot(&self, max_levels: usize) -> (Levels, Levels) {
+        let level = |(price, size): &(Price, TradeSize)| (price.clone(), size.clone(), 0);
+        (
+            self.bids
+                .values()
+                .rev()
+                .take(max_levels)
+                .map(level)
+                .collect(),
+            self.asks.values().take(max_levels).map(level).collect(),
+        )
+    }
+}
+
+/// Local level-2 books per product, rebuilt from the `snapshot` event Coinbase sends on every (re)subscribe
+/// and kept current by the `update` diffs after it.
+#[derive(Debug, Default)]
+pub struct CoinbaseBooks {
+    books: HashMap<TradingSymbol, Book>,
+}
+
+impl CoinbaseBooks {
+    /// Number of price levels held for `symbol` as (bids, asks)
+    pub fn depth(&self, symbol: &TradingSymbol) -> Option<(usize, usize)> {
+        self.books
+            .get(symbol)
+            .map(|book| (book.bids.len(), book.asks.len()))
+    }
+}
+
+/// `level2` event (delivered on channel `l2_data`). `new_quantity` is the level's full size; "0" removes it:
+/// {
+///   "type": "snapshot",  // or "update"
+///   "product_id": "BTC-USD",
+///   "updates": [
+///     {"side": "bid", "event_time": "1970-01-01T00:00:00Z", "price_level": "21921.73", "new_quantity": "0.06317902"},
+///     {"side": "offer", "event_time": "1970-01-01T00:00:00Z", "price_level": "21921.3", "new_quantity": "0.02"}
+///   ]
+/// }
+#[derive(Deserialize)]
+struct RawLevel2Event {
+    #[serde(rename = "type")]
+    event_type: String,
+    product_id: String,
+    updates: Vec<RawLevel2Update>,
+}
+
+#[derive(Deserialize)]
+struct RawLevel2Update {
+    side: String,
+    price_level: String,
+    new_quantity: String,
+}
+
+impl CoinbaseStreamable for OrderbookSnapshot {
+    type State = CoinbaseBooks;
+
+    /// Applies each event to its product's local book and returns the book's top
+    /// `COINBASE_ORDERBOOK_SNAPSHOT_LEVELS` per side after it. `time` is the message timestamp.
+    /// Error: an update for a product that has not received its snapshot on this connection.
+    fn of_message(message: &CoinbaseMessage, state: &mut CoinbaseBooks) -> OrError<Vec<Self>> {
+        let received_time = Utc::now();
+        let time = message.time()?;
+        let events: Vec<RawLevel2Event> = serde_json::from_value(message.events.clone()).map_err(|e| {
+            anyhow::anyhow!(
+                "Argus Coinbase OrderbookSnapshot conversion error: cannot convert events into Vec<RawLevel2Event>. Check schema. {}",
+                e
+            )
+        })?;
+
+        let mut snapshots = Vec::new();
+        for event in events {
+            let symbol = normalize_product_id(&event.product_id)?;
+            if event.event_type == "snapshot" {
+                state.books.insert(symbol.clone(), Book::default());
+            }
+            let Some(book) = state.books.get_mut(&symbol) else {
+                return Err(anyhow::anyhow!(
+                    "Argus Coinbase OrderbookSnapshot error: {} update before its snapshot",
+                    symbol.to_string()
+                ));
+            };
+            for update in event.updates {
+                let side = match update.side.as_str() {
+                    "bid" => &mut book.bids,
+                    "offer" | "ask" => &mut book.asks,
+                    other => {
+                        return Err(anyhow::anyhow!(
+                            "Argus Coinbase OrderbookSnapshot error: unknown side {:?}",
+                            other
+                        ));
+                    }
+                };
+                let price = Price::from_string(update.price_level)?;
+                let quantity: f64 = update.new_quantity.parse().map_err(|e| {
+                    anyhow::anyhow!(
+                        "Argus Coinbase OrderbookSnapshot conversion error: quantity {} cannot be converted to f64. {}",
+                        update.new_quantity, e
+                    )
+                })?;
+                let key = PriceKey(price.to_f64());
+                if quantity == 0.0 {
+                    side.remove(&key);
+                } else {
+                    side.insert(key, (price, TradeSize::from_f64(quantity)?));
+                }
+            }
+            let (bid_levels, ask_levels) = book.snapshot(COINBASE_ORDERBOOK_SNAPSHOT_LEVELS);
+            snapshots.push(OrderbookSnapshot {
+                symbol,
+                received_time,
+                time,
+                bid_levels,
+                ask_levels,
+            });
+        }
+        Ok(snapshots)
+    }
+
+    fn channel() -> String {
+        String::from("level2")
+    }
+
+    fn message_channel() -> String {
+        String::from("l2_data")
+    }
+
+    fn payload_identifier() -> String {
+        String::from("orderbook")
+    }
+
+    fn symbol(&self) -> TradingSymbol {
+        self.symbol.clone()
+    }
+}
@@ -0,0 +1,80 @@
+use super::{CoinbaseMessage, CoinbaseStreamable, normalize_product_id, parse_time};
+use crate::crypto::hyperliquid::TradeUpdate;
+use crate::types::{Price, TradeSize, TradingSymbol};
+use agora::utils::OrError;
+use chrono::prelude::Utc;
+use serde::Deserialize;
+
+/// `market_trades` event. The first event after subscribing is a "snapshot" of recent trades, later ones "update":
+/// {
+///   "type": "update",
+///   "trades": [{
+///     "trade_id": "000000000",
+///     "product_id": "ETH-USD",
+///     "price": "1260.01",
+///     "size": "0.3",
+///     "side": "BUY",  // "BUY" or "SELL"
+///     "time": "2019-08-14T20:42:27.265Z"
+///   }]
+/// }
+#[derive(Deserialize)]
+struct RawTradeEvent {
+    trades: Vec<RawTrade>,
+}
+
+#[derive(Deserialize)]
+struct RawTrade {
+    trade_id: String,
+    product_id: String,
+    price: String,
+    size: String,
+    side: String,
+    time: String,
+}
+
+impl CoinbaseStreamable for TradeUpdate {
+    type State = ();
+
+    fn of_message(message: &CoinbaseMessage, _state: &mut ()) -> OrError<Vec<Self>> {
+        let received_time = Utc::now();
+        let events: Vec<RawTradeEvent> = serde_json::from_value(message.events.clone()).map_err(|e| {
+            anyhow::anyhow!(
+                "Argus Coinbase tradeUpdate conversion error: cannot convert events into Vec<RawTradeEvent>. Check schema. {}",
+                e
+            )
+        })?;
+
+        let mut parsed_trades = Vec::new();
+        for raw in events.into_iter().flat_map(|event| event.trades) {
+            let trade_id: u64 = raw.trade_id.parse().map_err(|e| {
+                anyhow::anyhow!(
+                    "Argus Coinbase tradeUpdate conversion error: trade id {} is not numeric. {}",
+                    raw.trade_id,
+                    e
+                )
+            })?;
+            parsed_trades.push(TradeUpdate {
+                symbol: normalize_product_id(&raw.product_id)?,
+                received_time,
+                trade_id,
+                price: Price::from_string(raw.price)?,
+                size: TradeSize::from_string(raw.size)?,
+                trade_time: parse_time(&raw.time)?,
+                is_buy: raw.side == "BUY",
+            });
+        }
+        Ok(parsed_trades)
+    }
+
+    fn channel() -> String {
+        String::from("market_trades")
+    }
+
+    fn payload_identifier() -> String {
+        String::from("last_trade")
+    }
+
+    fn symbol(&self) -> TradingSymbol {
+        self.symbol.clone()
+    }
+}
@@ -0,0 +1,304 @@
+use super::{CoinbaseMessage, CoinbaseStreamable, normalize_product_id, subscribe_message};
+use crate::constants::COINBASE_WEBSTREAM_ENDPOINT;
+use crate::crypto::hyperliquid::{BboUpdate, OrderbookSnapshot, ReconnectBackoff, TradeUpdate};
+use crate::metrics::{self, Counter};
+use crate::supervised::spawn_supervised;
+use crate::types::TradingSymbol;
+use agora::utils::OrError;
+use agora::{AgorableOption, ConnectionHandle, PublisherGroup, PublisherSpec};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{Instrument, error, info, info_span, warn};
+
+/// Market label for Coinbase worker metrics
+const METRICS_MARKET: &str = "coinbase";
+
+/// Streams one Coinbase Advanced Trade channel for a set of products, publishing each parsed item to
+/// `{agora_prefix}/{payload_identifier}/{symbol}`. Also subscribes to `heartbeats`, without which
+/// Coinbase closes connections whose products see no updates for a while.
+pub struct CoinbaseWebstreamWorker<T: CoinbaseStreamable> {
+    agora_paths: Vec<String>,
+    dispatch_handle: JoinHandle<()>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: CoinbaseStreamable> CoinbaseWebstreamWorker<T> {
+    /// `symbols`: Coinbase product ids, normalized with `normalize_product_id` (e.g. "btc-usd" → "BTC-USD").
+    /// `reconnect_backoff`: retry policy after a dropped or failed connection; `None` falls back to `ReconnectBackoff::default()`.
+    /// `ws_url`: WebSocket to stream from; `None` uses `COINBASE_WEBSTREAM_ENDPOINT`.
+    pub async fn new(
+        symbols: &[TradingSymbol],
+        agora_prefix: &str,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+        reconnect_backoff: Option<ReconnectBackoff>,
+        ws_url: Option<String>,
+    ) -> OrError<Self> {
+        if symbols.is_empty() {
+            return Err(anyhow::anyhow!(
+                "CoinbaseWebstreamWorker error: symbols list cannot be empty"
+            ));
+        }
+        if symbols.len() > 1024 {
+            return Err(anyhow::anyhow!(
+                "CoinbaseWebstreamWorker error: don't pass in more than 1024 tasks per worker"
+            ));
+        }
+        let product_ids = symbols
+            .iter()
+            .map(|symbol| normalize_product_id(&symbol.to_string()))
+            .collect::<OrError<Vec<TradingSymbol>>>()?;
+
+        let agora_paths: Vec<String> = product_ids
+            .iter()
+            .map(|symbol| {
+                format!(
+                    "{}/{}/{}",
+                    agora_prefix,
+                    T::payload_identifier(),
+                    symbol.to_string()
+                )
+            })
+            .collect();
+
+        // All-or-nothing, so a failed init leaves no partial paths
+        let specs = product_ids
+            .iter()
+            .zip(agora_paths.iter())
+            .map(|(symbol, agora_path)| PublisherSpec {
+                name: symbol.to_string(),
+                path: agora_path.clone(),
+                initial_value: AgorableOption(None),
+            })
+            .collect();
+        let mut publishers: PublisherGroup<AgorableOption<T>> =
+            PublisherGroup::register_all(specs, metaserver_connection, local_gateway_port).await?;
+
+        let symbol_to_publisher: HashMap<TradingSymbol, usize> = product_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, symbol)| (symbol.clone(), idx))
+            .collect();
+
+        let data_type = T::payload_identifier();
+        let published: Vec<Counter> = product_ids
+            .iter()
+            .map(|symbol| {
+                metrics::messages_published(METRICS_MARKET, &data_type, &symbol.to_string())
+            })
+            .collect();
+        let publish_errors: Vec<Counter> = product_ids
+            .iter()
+            .map(|symbol| metrics::publish_errors(METRICS_MARKET, &data_type, &symbol.to_string()))
+            .collect();
+        let reconnects = metrics::websocket_reconnects(METRICS_MARKET, &data_type);
+
+        let ws_url = ws_url.unwrap_or_else(|| COINBASE_WEBSTREAM_ENDPOINT.to_string());
+        let subscriptions = [
+            subscribe_message(&T::channel(), &product_ids),
+            subscribe_message("heartbeats", &product_ids),
+        ];
+        let message_channel = T::message_channel();
+
+        let span = info_span!("coinbase_webstream", prefix = agora_prefix, channel = %T::channel());
+        info!(
+            parent: &span,
+            url = %ws_url,
+            products = ?product_ids.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            "connecting to Coinbase WebSocket"
+        );
+        let backoff = reconnect_backoff.unwrap_or_default();
+        let worker_task = spawn_supervised(
+            "coinbase_webstream",
+            async move {
+                // Consecutive failed (or short-lived) connections; drives the backoff exponent
+                let mut attempt: u32 = 0;
+                loop {
+                    match connect_async(&ws_url).await {
+                        Ok((ws_stream, _)) => {
+                            let connected_at = Instant::now();
+                            let (mut write, mut read) = ws_stream.split();
+                            // Coinbase re-sends snapshots on subscribe, so parse state starts over
+                            let mut state = T::State::default();
+
+                            for subscription in &subscriptions {
+                                if let Err(e) = write
+                                    .send(Message::Text(subscription.to_string().into()))
+                                    .await
+                                {
+                                    error!(error = %e, "failed to send subscription");
+                                    break;
+                                }
+                            }
+
+                            while let Some(message) = read.next().await {
+                                match message {
+                                    Ok(Message::Ping(ping_data)) => {
+                                        if let Err(e) = write.send(Message::Pong(ping_data)).await {
+                                            error!(error = %e, "failed to send pong");
+                                            break;
+                                        }
+                                    }
+                                    Ok(Message::Text(text)) => {
+                                        let message =
+                                            match serde_json::from_str::<CoinbaseMessage>(&text) {
+                                                Ok(message) => message,
+                                                Err(e) => {
+                                                    warn!(%text, error = %e, "JSON parse error");
+                                                    continue;
+                                                }
+                                            };
+                                        if message.channel != message_channel {
+                                            // `subscriptions` acks and `heartbeats`; errors arrive with type "error" and no channel
+                                            if message.channel.is_empty() {
+                                                error!(%text, "server error");
+                                            }
+                                            continue;
+                                        }
+                                        let items = match T::of_message(&message, &mut state) {
+                                            Ok(items) => items,
+                                            Err(e) => {
+                                                warn!(error = %e, "parse error");
+                                                continue;
+                                            }
+                                        };
+                                        for item in items {
+                                            let symbol = item.symbol();
+                                            let Some(&publisher_idx) =
+                                                symbol_to_publisher.get(&symbol)
+                                            else {
+                                                continue;
+                                            };
+                                            if let Some(publisher) =
+                                                publishers.get_mut(publisher_idx)
+                                            {
+                                                match publisher
+                                                    .publish(AgorableOption(Some(item)))
+                                                    .await
+                                                {
+                                                    Ok(()) => published[publisher_idx].inc(),
+                                                    Err(e) => {
+                                                        publish_errors[publisher_idx].inc();
+                                                        warn!(
+                                                            symbol = %symbol.to_string(),
+                                                            error = %e,
+                                                            "publish error"
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    Ok(Message::Close(_)) => {
+                                        warn!("received Close frame, disconnecting");
+                                        break;
+                                    }
+                                    Ok(_) => {}
+                                    Err(e) => {
+                                        warn!(error = %e, "websocket error");
+                                        break;
+                                    }
+                                }
+                            }
+                            if connected_at.elapsed() >= backoff.min_stable_uptime {
+                                attempt = 0;
+                            }
+                        }
+                        Err(e) => {
+                            warn!(error = %e, "connection error");
+                        }
+                    }
+                    reconnects.inc();
+                    let delay = backoff.delay(attempt);
+                    attempt = attempt.saturating_add(1);
+                    warn!(?delay, attempt, "reconnecting");
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            .instrument(span),
+            |_| {},
+        );
+
+        Ok(Self {
+            agora_paths,
+            dispatch_handle: worker_task,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the agora paths for all publishers managed by this worker
+    pub fn agora_paths(&self) -> &[String] {
+        &self.agora_paths
+    }
+}
+
+impl<T: CoinbaseStreamable> Drop for CoinbaseWebstreamWorker<T> {
+    fn drop(&mut self) {
+        self.dispatch_handle.abort()
+    }
+}
+
+/// Trades, BBO and level-2 books for a set of Coinbase spot products, published under
+/// `{agora_prefix}/{last_trade | bbo | orderbook}/{symbol}` (e.g. `argus/coinbase/spot/bbo/BTC-USD`).
+/// Dropping it stops all three streams.
+pub struct CoinbaseWebstreamSymbols {
+    symbols: Vec<TradingSymbol>,
+    _trade_worker: CoinbaseWebstreamWorker<TradeUpdate>,
+    _bbo_worker: CoinbaseWebstreamWorker<BboUpdate>,
+    _orderbook_worker: CoinbaseWebstreamWorker<OrderbookSnapshot>,
+}
+
+impl CoinbaseWebstreamSymbols {
+    pub async fn new(
+        symbols: &[TradingSymbol],
+        agora_prefix: &str,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        let trade_worker = CoinbaseWebstreamWorker::<TradeUpdate>::new(
+            symbols,
+            agora_prefix,
+            metaserver_connection,
+            local_gateway_port,
+            None,
+            None,
+        )
+        .await?;
+        let bbo_worker = CoinbaseWebstreamWorker::<BboUpdate>::new(
+            symbols,
+            agora_prefix,
+            metaserver_connection,
+            local_gateway_port,
+            None,
+            None,
+        )
+        .await?;
+        let orderbook_worker = CoinbaseWebstreamWorker::<OrderbookSnapshot>::new(
+            symbols,
+            agora_prefix,
+            metaserver_connection,
+            local_gateway_port,
+            None,
+            None,
+        )
+        .await?;
+        Ok(Self {
+            symbols: symbols
+                .iter()
+                .map(|symbol| normalize_product_id(&symbol.to_string()))
+                .collect::<OrError<Vec<_>>>()?,
+            _trade_worker: trade_worker,
+            _bbo_worker: bbo_worker,
+            _orderbook_worker: orderbook_worker,
+        })
+    }
+
+    /// Normalized product ids being streamed
+    pub fn symbols(&self) -> &[TradingSymbol] {
+        &self.symbols
+    }
+}
@@ -1,20 +1,27 @@
 mod bbo;
+mod funding;
 mod orderbook;
+mod pipeline;
 mod perp_context;
 mod publisher;
+mod rest;
 mod scribe; 
 mod spot_context;
 mod trades;
 mod universe;
 pub mod webstream;
 
+use crate::constants::{
+    HYPERLIQUID_INFO_ENDPOINT, HYPERLIQUID_TESTNET_INFO_ENDPOINT,
+    HYPERLIQUID_TESTNET_WEBSTREAM_ENDPOINT, HYPERLIQUID_WEBSTREAM_ENDPOINT,
+};
 use crate::types::TradingSymbol;
 use agora::Agorable;
 use agora::utils::OrError;
 use bimap::BiMap;
 use serde::Deserialize;
 
-pub trait HyperliquidStreamable: Agorable + Sized {
+pub trait HyperliquidStreamable: Agorable + Clone + Sized {
     /// Parse channel data into one or more instances.
     /// Each implementation extracts symbol from its data structure, translates via symbol_map,
     /// and embeds the normalized symbol in returned items.
@@ -27,6 +34,26 @@ pub trait HyperliquidStreamable: Agorable + Sized {
     fn subscription_type() -> String;
     fn payload_identifier() -> String;
     fn symbol(&self) -> TradingSymbol;
+
+    /// Caps book depth to the best `max_levels` per side before publishing. No-op for non-book types.
+    fn truncate_levels(&mut self, _max_levels: usize) {}
+
+    /// Upstream sequence number or id for gap detection (see `SequenceTracker`); `None` if the channel carries none.
+    fn sequence(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Normalized symbol for a Hyperliquid `coin` field, or the coin itself if `symbol_map` has no entry.
+/// Mapped coins are looked up by `&str` and cloned from the map, so parsing a message allocates no symbol.
+pub fn normalize_coin(
+    coin: &str,
+    symbol_map: &BiMap<TradingSymbol, TradingSymbol>,
+) -> OrError<TradingSymbol> {
+    match symbol_map.get_by_right(coin) {
+        Some(normalized) => Ok(normalized.clone()),
+        None => TradingSymbol::from_str(coin),
+    }
 }
 
 #[derive(Deserialize)]
@@ -35,12 +62,48 @@ pub struct ChannelMessage {
     pub data: serde_json::Value,
 }
 
-pub use bbo::BboUpdate;
-pub use orderbook::OrderbookSnapshot;
+/// Hyperliquid API endpoints: WebSocket for streaming, REST info for universe metadata.
+/// Defaults to mainnet; `testnet()` for integration runs, or set the urls directly to go through a proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperliquidEndpoints {
+    pub ws_url: String,
+    pub info_url: String,
+}
+
+impl HyperliquidEndpoints {
+    pub fn mainnet() -> Self {
+        Self {
+            ws_url: HYPERLIQUID_WEBSTREAM_ENDPOINT.to_string(),
+            info_url: HYPERLIQUID_INFO_ENDPOINT.to_string(),
+        }
+    }
+
+    pub fn testnet() -> Self {
+        Self {
+            ws_url: HYPERLIQUID_TESTNET_WEBSTREAM_ENDPOINT.to_string(),
+            info_url: HYPERLIQUID_TESTNET_INFO_ENDPOINT.to_string(),
+        }
+    }
+}
+
+impl Default for HyperliquidEndpoints {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+pub use bbo::{BboUpdate, MicropriceRelay};
+pub use funding::{FundingMonitor, FundingTracker, FundingUpdate, crosses_threshold};
+pub use orderbook::{BookSide, ImbalanceRelay, LevelOrderPolicy, OrderbookSnapshot};
 pub use perp_context::PerpAssetContext;
-pub use publisher::HyperliquidPublisher;
-pub use scribe::{HyperliquidArchiver, HyperliquidScribe};
+pub use pipeline::HyperliquidPipeline;
+pub use publisher::{HyperliquidPublisher, TypedRelaySet, VersionedWorkers};
+pub use rest::{
+    EvmContract, HyperliquidRestClient, HyperliquidRestError, PerpAsset, PerpAssetCtx, PerpMeta,
+    RestRateLimit, SpotAsset, SpotMeta, SpotToken,
+};
+pub use scribe::{HyperliquidArchiver, HyperliquidScribe, HyperliquidScribeBuilder};
 pub use spot_context::SpotAssetContext;
-pub use trades::TradeUpdate;
-pub use universe::UniverseManager;
-pub use webstream::HyperliquidWebstreamWorker;
+pub use trades::{TradeUpdate, TradeVolumeProfile, TradeVolumeRelay, TradeVolumeUpdate};
+pub use universe::{UniverseFetchError, UniverseFilter, UniverseManager, UniverseManagerConfig};
+pub use webstream::{HyperliquidWebstreamWorker, ReconnectBackoff, WorkerStats, shard_symbols};
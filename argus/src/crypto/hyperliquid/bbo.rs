@@ -1,14 +1,19 @@
-use super::HyperliquidStreamable;
-use crate::ArgusParquetable;
+use super::{HyperliquidStreamable, normalize_coin};
+use crate::constants::HYPERLIQUID_AGORA_PREFIX;
+use crate::recording::{ArgusParquetable, ArgusReplayable, batch_column, millis_to_utc};
+use crate::supervised::spawn_supervised;
 use crate::types::{Price, TradeSize, TradingSymbol};
-use agora::Agorable;
 use agora::utils::OrError;
+use agora::{Agorable, AgorableOption, ConnectionHandle, Publisher, Subscriber};
 use anyhow::Context;
 use bimap::BiMap;
 use chrono::prelude::{DateTime, Utc};
+use futures_util::StreamExt;
 use indoc::writedoc;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use tokio::task::JoinHandle;
+use tracing::warn;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BboUpdate {
@@ -34,7 +39,7 @@ impl fmt::Display for BboUpdate {
                 Time: {}
                 {:?} x {:?} ({} orders) | {:?} x {:?} ({} orders)
             )",
-            self.symbol.to_string(),
+            self.symbol.as_str(),
             self.received_time,
             self.time,
             self.bid_size,
@@ -48,6 +53,132 @@ impl fmt::Display for BboUpdate {
 }
 impl Agorable for BboUpdate {}
 
+impl BboUpdate {
+    /// Unweighted mid: (bid_px + ask_px) / 2
+    pub fn mid_price(&self) -> Option<Price> {
+        Price::from_f64((self.bid_price.to_f64() + self.ask_price.to_f64()) / 2.0).ok()
+    }
+
+    /// Level-1 microprice (size-weighted mid): (bid_px * ask_sz + ask_px * bid_sz) / (bid_sz + ask_sz).
+    /// Leans toward the side with less size, i.e. the side more likely to be taken out next.
+    /// Only top-of-book is used, since that is all a `BboUpdate` carries.
+    /// None: total size at the touch is zero
+    pub fn microprice(&self) -> Option<Price> {
+        let (bid_size, ask_size) = (self.bid_size.to_f64(), self.ask_size.to_f64());
+        let total_size = bid_size + ask_size;
+        if total_size <= 0.0 {
+            return None;
+        }
+        Price::from_f64(
+            (self.bid_price.to_f64() * ask_size + self.ask_price.to_f64() * bid_size) / total_size,
+        )
+        .ok()
+    }
+}
+
+/// Derived stream republishing each BBO update's level-1 microprice:
+///   {source_path} (AgorableOption<BboUpdate>) -> {output_path} (AgorableOption<f64>)
+/// Updates without a microprice (zero size at the touch) are skipped.
+pub struct MicropriceRelay {
+    output_path: String,
+    task_handle: JoinHandle<()>,
+}
+
+impl MicropriceRelay {
+    /// Follows `argus/hyperliquid/perp/bbo/{symbol}` and publishes to `argus/hyperliquid/perp/microprice/{symbol}`
+    pub async fn new(
+        name: String,
+        symbol: &TradingSymbol,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        Self::with_paths(
+            name,
+            format!(
+                "{}/perp/{}/{}",
+                HYPERLIQUID_AGORA_PREFIX,
+                BboUpdate::payload_identifier(),
+                symbol.to_string()
+            ),
+            format!(
+                "{}/perp/microprice/{}",
+                HYPERLIQUID_AGORA_PREFIX,
+                symbol.to_string()
+            ),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `source_path` - Agora path publishing `AgorableOption<BboUpdate>` (e.g., "argus/hyperliquid/perp/bbo/BTC_PERP")
+    /// * `output_path` - Agora path to publish `AgorableOption<f64>` microprices onto
+    pub async fn with_paths(
+        name: String,
+        source_path: String,
+        output_path: String,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        let mut subscriber = Subscriber::<AgorableOption<BboUpdate>>::new(
+            source_path.clone(),
+            metaserver_connection,
+        )
+        .await?;
+        let (current, mut stream) = subscriber.get_stream().await?;
+        let initial = current.0.as_ref().and_then(BboUpdate::microprice);
+        let mut publisher = Publisher::<AgorableOption<f64>>::new(
+            name,
+            output_path.clone(),
+            AgorableOption(initial.map(|price| price.to_f64())),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await?;
+
+        let relay = async move {
+            let _subscriber = subscriber; // Keep the source connection alive
+            while let Some(item) = stream.next().await {
+                let microprice = match item {
+                    Ok(AgorableOption(Some(bbo))) => match bbo.microprice() {
+                        Some(microprice) => microprice,
+                        None => continue,
+                    },
+                    Ok(AgorableOption(None)) => continue,
+                    Err(e) => {
+                        warn!(source = %source_path, error = %e, "MicropriceRelay: stream error");
+                        continue;
+                    }
+                };
+                if let Err(e) = publisher
+                    .publish(AgorableOption(Some(microprice.to_f64())))
+                    .await
+                {
+                    warn!(source = %source_path, error = %e, "MicropriceRelay: publish error");
+                }
+            }
+            warn!(source = %source_path, "MicropriceRelay: source closed");
+        };
+        let task_handle = spawn_supervised("microprice_relay", relay, |_| {});
+
+        Ok(Self {
+            output_path,
+            task_handle,
+        })
+    }
+
+    pub fn output_path(&self) -> &str {
+        &self.output_path
+    }
+}
+
+impl Drop for MicropriceRelay {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
+
 /// Intermediate structs for deserializing Hyperliquid BBO data
 /// Format:
 /// {
@@ -86,11 +217,7 @@ impl HyperliquidStreamable for BboUpdate {
         })?;
 
         // Extract coin from data and normalize
-        let hyperliquid_coin = TradingSymbol::from_str(&raw.coin)?;
-        let normalized_symbol = symbol_map
-            .get_by_right(&hyperliquid_coin)
-            .cloned()
-            .unwrap_or(hyperliquid_coin);
+        let normalized_symbol = normalize_coin(&raw.coin, symbol_map)?;
 
         // Extract bid and ask from the bbo array
         let bid = raw.bbo[0]
@@ -127,6 +254,11 @@ impl HyperliquidStreamable for BboUpdate {
     fn symbol(&self) -> TradingSymbol {
         self.symbol.clone()
     }
+
+    /// Hyperliquid's bbo channel carries no sequence number; `time` is a timestamp, not a sequence
+    fn sequence(&self) -> Option<u64> {
+        None
+    }
 }
 
 impl ArgusParquetable for BboUpdate {
@@ -165,9 +297,7 @@ impl ArgusParquetable for BboUpdate {
         let schema = Self::arrow_schema();
 
         let symbols: ArrayRef = Arc::new(StringArray::from(
-            data.iter()
-                .map(|d| d.symbol.to_string())
-                .collect::<Vec<_>>(),
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
         ));
 
         let received_times: ArrayRef = Arc::new(
@@ -233,3 +363,39 @@ impl ArgusParquetable for BboUpdate {
         .context("Failed to create RecordBatch")
     }
 }
+
+impl ArgusReplayable for BboUpdate {
+    fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> OrError<Vec<Self>> {
+        use arrow::array::{Float64Array, StringArray, TimestampMillisecondArray, UInt32Array};
+
+        let symbols = batch_column::<StringArray>(batch, "symbol")?;
+        let received_times = batch_column::<TimestampMillisecondArray>(batch, "received_time")?;
+        let times = batch_column::<TimestampMillisecondArray>(batch, "time")?;
+        let bid_prices = batch_column::<Float64Array>(batch, "bid_price")?;
+        let bid_sizes = batch_column::<Float64Array>(batch, "bid_size")?;
+        let bid_orders = batch_column::<UInt32Array>(batch, "bid_orders")?;
+        let ask_prices = batch_column::<Float64Array>(batch, "ask_price")?;
+        let ask_sizes = batch_column::<Float64Array>(batch, "ask_size")?;
+        let ask_orders = batch_column::<UInt32Array>(batch, "ask_orders")?;
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(BboUpdate {
+                    symbol: TradingSymbol::from_str(symbols.value(i))?,
+                    received_time: millis_to_utc(received_times.value(i))?,
+                    time: millis_to_utc(times.value(i))?,
+                    bid_price: Price::from_f64(bid_prices.value(i))?,
+                    bid_size: TradeSize::from_f64(bid_sizes.value(i))?,
+                    bid_orders: bid_orders.value(i),
+                    ask_price: Price::from_f64(ask_prices.value(i))?,
+                    ask_size: TradeSize::from_f64(ask_sizes.value(i))?,
+                    ask_orders: ask_orders.value(i),
+                })
+            })
+            .collect()
+    }
+
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.received_time
+    }
+}
@@ -0,0 +1,282 @@
+use super::PerpAssetContext;
+use crate::constants::FUNDING_MONITOR_REFRESH_MS;
+use crate::types::TradingSymbol;
+use agora::metaserver::AgoraClient;
+use agora::utils::{OrError, TreeTrait};
+use agora::{Agorable, AgorableOption, ConnectionHandle, Publisher, Subscriber};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Slimmed `PerpAssetContext`: only the funding rate, republished when it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingUpdate {
+    pub symbol: TradingSymbol,
+    pub funding_rate: f64, // As decimal (e.g., 0.0001 = 0.01%)
+    pub time: DateTime<Utc>,
+}
+
+impl fmt::Display for FundingUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "FundingUpdate({} {:.6}% at {})",
+            self.symbol.as_str(),
+            self.funding_rate * 100.0,
+            self.time
+        )
+    }
+}
+
+impl Agorable for FundingUpdate {}
+
+/// Funding state of one symbol's context stream.
+#[derive(Debug, Default)]
+pub struct FundingTracker {
+    last_rate: Option<f64>,
+}
+
+impl FundingTracker {
+    /// Returns the slimmed update and the previous rate if the funding rate changed.
+    /// The first context always counts as a change (previous rate `None`).
+    pub fn observe(&mut self, context: &PerpAssetContext) -> Option<(FundingUpdate, Option<f64>)> {
+        let previous = self.last_rate;
+        if previous == Some(context.funding_rate) {
+            return None;
+        }
+        self.last_rate = Some(context.funding_rate);
+        let update = FundingUpdate {
+            symbol: context.symbol.clone(),
+            funding_rate: context.funding_rate,
+            time: context.received_time,
+        };
+        Some((update, previous))
+    }
+
+    pub fn last_rate(&self) -> Option<f64> {
+        self.last_rate
+    }
+}
+
+/// True when |funding| moves from below `threshold` to at or above it.
+/// An unseen symbol counts as below, so a symbol that is already past the threshold fires once.
+pub fn crosses_threshold(previous: Option<f64>, current: f64, threshold: f64) -> bool {
+    current.abs() >= threshold && previous.is_none_or(|rate| rate.abs() < threshold)
+}
+
+type FundingCallback = Arc<dyn Fn(FundingUpdate) + Send + Sync>;
+
+struct FundingAlert {
+    threshold: f64,
+    callback: FundingCallback,
+}
+
+/// Follows one symbol's context stream. Aborts its task (dropping subscriber and publisher) on drop.
+struct SymbolTask {
+    handle: JoinHandle<()>,
+}
+
+impl Drop for SymbolTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Subscribes to every symbol under a perp_context prefix, republishes `FundingUpdate`s to
+/// `{output_prefix}/{symbol}` and fires `on_funding_change` callbacks on threshold crossings.
+///
+/// The prefix is rescanned every refresh interval: symbols that appear are followed, symbols that
+/// dropped out of the universe (or whose stream closed) are torn down, together with their derived path.
+pub struct FundingMonitor {
+    alerts: Arc<RwLock<Vec<FundingAlert>>>,
+    symbols: Arc<RwLock<Vec<TradingSymbol>>>,
+    task_handle: JoinHandle<()>,
+}
+
+impl FundingMonitor {
+    /// # Arguments
+    /// * `context_prefix` - Agora directory of `AgorableOption<PerpAssetContext>` paths (e.g., "argus/hyperliquid/perp/perp_context")
+    /// * `output_prefix` - Agora directory for `AgorableOption<FundingUpdate>` paths (e.g., "argus/hyperliquid/perp/funding")
+    /// * `refresh_interval` - How often to rescan `context_prefix`. Defaults to `FUNDING_MONITOR_REFRESH_MS`
+    pub async fn new(
+        name: String,
+        context_prefix: String,
+        output_prefix: String,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+        refresh_interval: Option<Duration>,
+    ) -> OrError<Self> {
+        let refresh_interval =
+            refresh_interval.unwrap_or(Duration::from_millis(FUNDING_MONITOR_REFRESH_MS));
+        let metaclient = AgoraClient::new(metaserver_connection).await?;
+        // Fail fast on a bad prefix; later scan errors are logged and retried
+        Self::list_symbols(&metaclient, &context_prefix).await?;
+
+        let alerts: Arc<RwLock<Vec<FundingAlert>>> = Arc::new(RwLock::new(Vec::new()));
+        let symbols = Arc::new(RwLock::new(Vec::new()));
+        let alerts_ = alerts.clone();
+        let symbols_ = symbols.clone();
+        let task_handle = tokio::spawn(async move {
+            // Dropped with this task when the monitor is dropped, aborting every symbol task
+            let mut active: HashMap<TradingSymbol, SymbolTask> = HashMap::new();
+            let mut ticker = tokio::time::interval(refresh_interval);
+            loop {
+                ticker.tick().await;
+                let listed = match Self::list_symbols(&metaclient, &context_prefix).await {
+                    Ok(listed) => listed,
+                    Err(e) => {
+                        warn!(prefix = %context_prefix, error = %e, "FundingMonitor: cannot list");
+                        continue;
+                    }
+                };
+
+                // Symbols that left the universe, or whose stream closed (resubscribed below if still listed)
+                active.retain(|symbol, task| {
+                    listed.contains(symbol) && !task.handle.is_finished()
+                });
+                for symbol in &listed {
+                    if active.contains_key(symbol) {
+                        continue;
+                    }
+                    let handle = tokio::spawn(Self::follow_symbol(
+                        format!("{}/{}", name, symbol.to_string()),
+                        symbol.clone(),
+                        format!("{}/{}", context_prefix, symbol.to_string()),
+                        format!("{}/{}", output_prefix, symbol.to_string()),
+                        metaserver_connection,
+                        local_gateway_port,
+                        alerts_.clone(),
+                    ));
+                    active.insert(symbol.clone(), SymbolTask { handle });
+                }
+                *symbols_.write().unwrap() = active.keys().cloned().collect();
+            }
+        });
+
+        Ok(Self {
+            alerts,
+            symbols,
+            task_handle,
+        })
+    }
+
+    /// Registers `callback` to run whenever a symbol's |funding rate| crosses `threshold` from below.
+    /// Each call runs on its own blocking task, so a slow handler never stalls context ingestion.
+    pub fn on_funding_change<F>(&self, threshold: f64, callback: F)
+    where
+        F: Fn(FundingUpdate) + Send + Sync + 'static,
+    {
+        self.alerts.write().unwrap().push(FundingAlert {
+            threshold: threshold.abs(),
+            callback: Arc::new(callback),
+        });
+    }
+
+    /// Symbols currently followed, as of the last rescan
+    pub fn symbols(&self) -> Vec<TradingSymbol> {
+        self.symbols.read().unwrap().clone()
+    }
+
+    async fn list_symbols(
+        metaclient: &AgoraClient,
+        context_prefix: &str,
+    ) -> OrError<HashSet<TradingSymbol>> {
+        let pathtree = metaclient.get_path_tree().await?;
+        let children = pathtree.get_child(context_prefix).map_err(|e| {
+            anyhow::anyhow!(
+                "Argus FundingMonitor error: cannot identify children of {}: {}",
+                context_prefix,
+                e
+            )
+        })?;
+        children
+            .children()
+            .into_iter()
+            .map(|node| TradingSymbol::from_str(node.name()))
+            .collect()
+    }
+
+    /// Runs until the context stream closes or errors out at subscription time.
+    async fn follow_symbol(
+        name: String,
+        symbol: TradingSymbol,
+        source_path: String,
+        output_path: String,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+        alerts: Arc<RwLock<Vec<FundingAlert>>>,
+    ) {
+        let setup = async {
+            let mut subscriber = Subscriber::<AgorableOption<PerpAssetContext>>::new(
+                source_path.clone(),
+                metaserver_connection,
+            )
+            .await?;
+            let (current, stream) = subscriber.get_stream().await?;
+            let publisher = Publisher::<AgorableOption<FundingUpdate>>::new(
+                name,
+                output_path,
+                AgorableOption(None),
+                metaserver_connection,
+                local_gateway_port,
+            )
+            .await?;
+            OrError::Ok((subscriber, current, stream, publisher))
+        };
+        let (_subscriber, current, mut stream, mut publisher) = match setup.await {
+            Ok(setup) => setup,
+            Err(e) => {
+                warn!(symbol = %symbol.as_str(), error = %e, "FundingMonitor: cannot follow");
+                return;
+            }
+        };
+
+        let mut tracker = FundingTracker::default();
+        let mut next = Some(current);
+        loop {
+            let context = match next.take() {
+                Some(context) => context,
+                None => match stream.next().await {
+                    Some(Ok(context)) => context,
+                    Some(Err(e)) => {
+                        warn!(source = %source_path, error = %e, "FundingMonitor: stream error");
+                        continue;
+                    }
+                    None => {
+                        warn!(source = %source_path, "FundingMonitor: source closed");
+                        return;
+                    }
+                },
+            };
+            let AgorableOption(Some(context)) = context else {
+                continue;
+            };
+            let Some((update, previous)) = tracker.observe(&context) else {
+                continue;
+            };
+
+            for alert in alerts.read().unwrap().iter() {
+                if crosses_threshold(previous, update.funding_rate, alert.threshold) {
+                    let callback = alert.callback.clone();
+                    let update = update.clone();
+                    tokio::task::spawn_blocking(move || callback(update));
+                }
+            }
+            if let Err(e) = publisher.publish(AgorableOption(Some(update))).await {
+                warn!(symbol = %symbol.as_str(), error = %e, "FundingMonitor: publish error");
+            }
+        }
+    }
+}
+
+impl Drop for FundingMonitor {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
@@ -1,14 +1,40 @@
-use super::HyperliquidStreamable;
-use crate::ArgusParquetable;
+use super::{HyperliquidStreamable, normalize_coin};
+use crate::constants::{
+    HYPERLIQUID_AGORA_PREFIX, HYPERLIQUID_IMBALANCE_LEVELS, HYPERLIQUID_ORDERBOOK_LEVEL_ORDER,
+};
+use crate::recording::{
+    ArgusParquetable, ArgusReplayable, batch_column, downcast_column, millis_to_utc,
+    versioned_schema,
+};
+use crate::supervised::spawn_supervised;
 use crate::types::{Price, TradeSize, TradingSymbol};
-use agora::Agorable;
 use agora::utils::OrError;
+use agora::{Agorable, AgorableOption, ConnectionHandle, Publisher, Subscriber};
 use anyhow::Context;
 use bimap::BiMap;
 use chrono::prelude::{DateTime, Utc};
+use futures_util::StreamExt;
 use indoc::writedoc;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Side of an `OrderbookSnapshot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// What `OrderbookSnapshot::of_channel_data_with_policy` does with levels that are not best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelOrderPolicy {
+    /// Reject the message
+    Strict,
+    /// Sort each side best first; only duplicate prices are rejected
+    AutoSort,
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderbookSnapshot {
@@ -21,6 +47,214 @@ pub struct OrderbookSnapshot {
     pub ask_levels: Vec<(Price, TradeSize, u32)>,
 }
 
+impl OrderbookSnapshot {
+    /// Keeps the best `max_levels` per side: highest bids and lowest asks, each side sorted best first.
+    /// Levels carry only per-level fields (price, size, order count), so the kept levels stay consistent.
+    pub fn truncate(&mut self, max_levels: usize) {
+        self.sort_levels();
+        self.bid_levels.truncate(max_levels);
+        self.ask_levels.truncate(max_levels);
+    }
+
+    /// Sorts each side best first: bids by descending, asks by ascending price
+    pub fn sort_levels(&mut self) {
+        self.bid_levels.sort_by(|a, b| b.0.to_f64().total_cmp(&a.0.to_f64()));
+        self.ask_levels.sort_by(|a, b| a.0.to_f64().total_cmp(&b.0.to_f64()));
+    }
+
+    /// The invariant consumers rely on: bids strictly descending and asks strictly ascending by price,
+    /// so each side is best first with no duplicate price. Crossed books (best bid >= best ask) are not checked.
+    pub fn is_well_formed(&self) -> bool {
+        self.bid_levels.windows(2).all(|pair| pair[0].0 > pair[1].0)
+            && self.ask_levels.windows(2).all(|pair| pair[0].0 < pair[1].0)
+    }
+
+    /// Best `levels` levels of `side`, best first. Does not assume the snapshot is sorted.
+    fn top_levels(&self, side: BookSide, levels: usize) -> Vec<&(Price, TradeSize, u32)> {
+        let mut sorted: Vec<_> = match side {
+            BookSide::Bid => self.bid_levels.iter().collect(),
+            BookSide::Ask => self.ask_levels.iter().collect(),
+        };
+        match side {
+            BookSide::Bid => sorted.sort_by(|a, b| b.0.to_f64().total_cmp(&a.0.to_f64())),
+            BookSide::Ask => sorted.sort_by(|a, b| a.0.to_f64().total_cmp(&b.0.to_f64())),
+        }
+        sorted.truncate(levels);
+        sorted
+    }
+
+    /// Order-book imbalance over the top `levels` per side: (bid_sz - ask_sz) / (bid_sz + ask_sz), in [-1, 1].
+    /// Positive when resting bids outweigh asks. A side with fewer levels contributes all it has.
+    /// None: `levels` is 0 or either side is empty
+    pub fn book_imbalance(&self, levels: usize) -> Option<f64> {
+        let side_size = |side: BookSide| -> f64 {
+            self.top_levels(side, levels)
+                .iter()
+                .map(|(_, size, _)| size.to_f64())
+                .sum()
+        };
+        let (bid_size, ask_size) = (side_size(BookSide::Bid), side_size(BookSide::Ask));
+        if bid_size <= 0.0 || ask_size <= 0.0 {
+            return None;
+        }
+        Some((bid_size - ask_size) / (bid_size + ask_size))
+    }
+
+    /// Total size resting on `side` at `price` or better: bids priced at least `price`, asks at most `price`.
+    /// Sums exactly (see `TradeSize`), so depth curves built from it do not drift.
+    /// None: no level of `side` is at `price` or better
+    pub fn cumulative_depth(&self, side: BookSide, price: &Price) -> Option<TradeSize> {
+        let levels = match side {
+            BookSide::Bid => &self.bid_levels,
+            BookSide::Ask => &self.ask_levels,
+        };
+        levels
+            .iter()
+            .filter(|(level_price, _, _)| match side {
+                BookSide::Bid => level_price >= price,
+                BookSide::Ask => level_price <= price,
+            })
+            .map(|(_, size, _)| size.clone())
+            .reduce(|total, size| (total.clone() + size).unwrap_or(total))
+    }
+
+    /// Size-weighted mid over the top `levels` per side: each side's volume-weighted price, weighted by the
+    /// opposite side's size, (bid_vwap * ask_sz + ask_vwap * bid_sz) / (bid_sz + ask_sz).
+    /// With `levels` = 1 this is the level-1 microprice (see `BboUpdate::microprice`).
+    /// None: `levels` is 0 or either side is empty
+    pub fn weighted_mid(&self, levels: usize) -> Option<Price> {
+        // (size-weighted price sum, size) of one side
+        let side_totals = |side: BookSide| {
+            self.top_levels(side, levels).iter().fold(
+                (0.0, 0.0),
+                |(notional, size), (price, level_size, _)| {
+                    (
+                        notional + price.to_f64() * level_size.to_f64(),
+                        size + level_size.to_f64(),
+                    )
+                },
+            )
+        };
+        let (bid_notional, bid_size) = side_totals(BookSide::Bid);
+        let (ask_notional, ask_size) = side_totals(BookSide::Ask);
+        if bid_size <= 0.0 || ask_size <= 0.0 {
+            return None;
+        }
+        let (bid_vwap, ask_vwap) = (bid_notional / bid_size, ask_notional / ask_size);
+        Price::from_f64((bid_vwap * ask_size + ask_vwap * bid_size) / (bid_size + ask_size)).ok()
+    }
+}
+
+/// Derived stream republishing each orderbook snapshot's top-of-book imbalance (see `OrderbookSnapshot::book_imbalance`):
+///   {source_path} (AgorableOption<OrderbookSnapshot>) -> {output_path} (AgorableOption<f64>)
+/// Snapshots without an imbalance (an empty side) are skipped.
+pub struct ImbalanceRelay {
+    output_path: String,
+    task_handle: JoinHandle<()>,
+}
+
+impl ImbalanceRelay {
+    /// Follows `argus/hyperliquid/perp/orderbook/{symbol}` and publishes to `argus/hyperliquid/perp/imbalance/{symbol}`
+    /// # Arguments
+    /// * `levels` - Top levels per side to sum (default: `HYPERLIQUID_IMBALANCE_LEVELS`)
+    pub async fn new(
+        name: String,
+        symbol: &TradingSymbol,
+        levels: Option<usize>,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        Self::with_paths(
+            name,
+            format!(
+                "{}/perp/{}/{}",
+                HYPERLIQUID_AGORA_PREFIX,
+                OrderbookSnapshot::payload_identifier(),
+                symbol.to_string()
+            ),
+            format!(
+                "{}/perp/imbalance/{}",
+                HYPERLIQUID_AGORA_PREFIX,
+                symbol.to_string()
+            ),
+            levels,
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `source_path` - Agora path publishing `AgorableOption<OrderbookSnapshot>` (e.g., "argus/hyperliquid/perp/orderbook/BTC_PERP")
+    /// * `output_path` - Agora path to publish `AgorableOption<f64>` imbalances onto
+    /// * `levels` - Top levels per side to sum (default: `HYPERLIQUID_IMBALANCE_LEVELS`)
+    pub async fn with_paths(
+        name: String,
+        source_path: String,
+        output_path: String,
+        levels: Option<usize>,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        let levels = levels.unwrap_or(HYPERLIQUID_IMBALANCE_LEVELS);
+        let mut subscriber = Subscriber::<AgorableOption<OrderbookSnapshot>>::new(
+            source_path.clone(),
+            metaserver_connection,
+        )
+        .await?;
+        let (current, mut stream) = subscriber.get_stream().await?;
+        let initial = current
+            .0
+            .as_ref()
+            .and_then(|book| book.book_imbalance(levels));
+        let mut publisher = Publisher::<AgorableOption<f64>>::new(
+            name,
+            output_path.clone(),
+            AgorableOption(initial),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await?;
+
+        let relay = async move {
+            let _subscriber = subscriber; // Keep the source connection alive
+            while let Some(item) = stream.next().await {
+                let imbalance = match item {
+                    Ok(AgorableOption(Some(book))) => match book.book_imbalance(levels) {
+                        Some(imbalance) => imbalance,
+                        None => continue,
+                    },
+                    Ok(AgorableOption(None)) => continue,
+                    Err(e) => {
+                        warn!(source = %source_path, error = %e, "ImbalanceRelay: stream error");
+                        continue;
+                    }
+                };
+                if let Err(e) = publisher.publish(AgorableOption(Some(imbalance))).await {
+                    warn!(source = %source_path, error = %e, "ImbalanceRelay: publish error");
+                }
+            }
+            warn!(source = %source_path, "ImbalanceRelay: source closed");
+        };
+        let task_handle = spawn_supervised("imbalance_relay", relay, |_| {});
+
+        Ok(Self {
+            output_path,
+            task_handle,
+        })
+    }
+
+    pub fn output_path(&self) -> &str {
+        &self.output_path
+    }
+}
+
+impl Drop for ImbalanceRelay {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
+
 impl fmt::Display for OrderbookSnapshot {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writedoc!(
@@ -33,7 +267,7 @@ impl fmt::Display for OrderbookSnapshot {
                 Bid levels: {}
                 Ask levels: {}
             )",
-            self.symbol.to_string(),
+            self.symbol.as_str(),
             self.time,
             self.received_time,
             self.bid_levels.len(),
@@ -68,10 +302,14 @@ struct RawOrderbookSnapshot {
     levels: [Vec<WsLevel>; 2],
 }
 
-impl HyperliquidStreamable for OrderbookSnapshot {
-    fn of_channel_data(
+impl OrderbookSnapshot {
+    /// `of_channel_data` with an explicit `policy` for levels Hyperliquid sends out of order.
+    /// The returned snapshot is always `is_well_formed`.
+    /// Error: malformed data, levels out of order under `Strict`, or duplicate prices on a side
+    pub fn of_channel_data_with_policy(
         data: serde_json::Value,
         symbol_map: &BiMap<TradingSymbol, TradingSymbol>,
+        policy: LevelOrderPolicy,
     ) -> OrError<Vec<Self>> {
         let received_time = Utc::now();
         let raw: RawOrderbookSnapshot = serde_json::from_value(data).map_err(|e| {
@@ -82,11 +320,7 @@ impl HyperliquidStreamable for OrderbookSnapshot {
         })?;
 
         // Extract coin from data and normalize
-        let hyperliquid_coin = TradingSymbol::from_str(&raw.coin)?;
-        let normalized_symbol = symbol_map
-            .get_by_right(&hyperliquid_coin)
-            .cloned()
-            .unwrap_or(hyperliquid_coin);
+        let normalized_symbol = normalize_coin(&raw.coin, symbol_map)?;
 
         // Parse bid levels
         let mut bid_levels = Vec::new();
@@ -104,17 +338,42 @@ impl HyperliquidStreamable for OrderbookSnapshot {
             ask_levels.push((price, size, level.n));
         }
 
-        let orderbook = OrderbookSnapshot {
+        let mut orderbook = OrderbookSnapshot {
             symbol: normalized_symbol,
             received_time,
             time: DateTime::from_timestamp_millis(raw.time as i64).ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?,
             bid_levels,
             ask_levels,
         };
+        if !orderbook.is_well_formed() {
+            if policy == LevelOrderPolicy::Strict {
+                return Err(anyhow::anyhow!(
+                    "Argus Hyperliquid OrderbookSnapshot conversion error: {} levels are not sorted best first",
+                    orderbook.symbol.as_str()
+                ));
+            }
+            orderbook.sort_levels();
+            if !orderbook.is_well_formed() {
+                return Err(anyhow::anyhow!(
+                    "Argus Hyperliquid OrderbookSnapshot conversion error: {} has duplicate price levels",
+                    orderbook.symbol.as_str()
+                ));
+            }
+        }
 
         // Return single item in a vector
         Ok(vec![orderbook])
     }
+}
+
+impl HyperliquidStreamable for OrderbookSnapshot {
+    /// Levels out of order are handled as `HYPERLIQUID_ORDERBOOK_LEVEL_ORDER` prescribes
+    fn of_channel_data(
+        data: serde_json::Value,
+        symbol_map: &BiMap<TradingSymbol, TradingSymbol>,
+    ) -> OrError<Vec<Self>> {
+        Self::of_channel_data_with_policy(data, symbol_map, HYPERLIQUID_ORDERBOOK_LEVEL_ORDER)
+    }
 
     fn subscription_type() -> String {
         String::from("l2Book")
@@ -127,6 +386,10 @@ impl HyperliquidStreamable for OrderbookSnapshot {
     fn symbol(&self) -> TradingSymbol {
         self.symbol.clone()
     }
+
+    fn truncate_levels(&mut self, max_levels: usize) {
+        self.truncate(max_levels);
+    }
 }
 
 impl ArgusParquetable for OrderbookSnapshot {
@@ -182,9 +445,7 @@ impl ArgusParquetable for OrderbookSnapshot {
 
         // Basic fields
         let symbols: ArrayRef = Arc::new(StringArray::from(
-            data.iter()
-                .map(|d| d.symbol.to_string())
-                .collect::<Vec<_>>(),
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
         ));
 
         let received_times: ArrayRef = Arc::new(
@@ -306,3 +567,243 @@ impl ArgusParquetable for OrderbookSnapshot {
         .context("Failed to create RecordBatch")
     }
 }
+
+/// Fields of one level in `OrderbookSnapshot::fixed_depth_arrow_schema`: nullable, since padding levels are null
+fn fixed_depth_level_fields() -> Vec<arrow::datatypes::Field> {
+    use arrow::datatypes::{DataType, Field};
+
+    vec![
+        Field::new("price", DataType::Float64, true),
+        Field::new("size", DataType::Float64, true),
+        Field::new("n_orders", DataType::UInt32, true),
+    ]
+}
+
+/// One side's levels as a List<Struct{price, size, n_orders}> column of exactly `depth` entries per row,
+/// null entries standing in for missing levels. Each side must already be truncated to `depth`, best first.
+fn fixed_depth_level_list<'a>(
+    sides: impl Iterator<Item = &'a Vec<(Price, TradeSize, u32)>>,
+    depth: usize,
+) -> OrError<arrow::array::ArrayRef> {
+    use arrow::array::{ArrayRef, Float64Array, ListArray, StructArray, UInt32Array};
+    use arrow::buffer::{NullBuffer, OffsetBuffer, ScalarBuffer};
+    use arrow::datatypes::{DataType, Field};
+    use std::sync::Arc;
+
+    let mut prices = Vec::new();
+    let mut sizes = Vec::new();
+    let mut n_orders = Vec::new();
+    let mut offsets = vec![0i32];
+    for levels in sides {
+        for i in 0..depth {
+            let level = levels.get(i);
+            prices.push(level.map(|(price, _, _)| price.to_f64()));
+            sizes.push(level.map(|(_, size, _)| size.to_f64()));
+            n_orders.push(level.map(|(_, _, n)| *n));
+        }
+        offsets.push(prices.len() as i32);
+    }
+    let valid = NullBuffer::from(prices.iter().map(Option::is_some).collect::<Vec<bool>>());
+
+    let entries = StructArray::try_new(
+        fixed_depth_level_fields().into(),
+        vec![
+            Arc::new(Float64Array::from(prices)) as ArrayRef,
+            Arc::new(Float64Array::from(sizes)) as ArrayRef,
+            Arc::new(UInt32Array::from(n_orders)) as ArrayRef,
+        ],
+        Some(valid),
+    )
+    .context("Failed to create fixed-depth level StructArray")?;
+    let list = ListArray::try_new(
+        Arc::new(Field::new(
+            "item",
+            DataType::Struct(fixed_depth_level_fields().into()),
+            true,
+        )),
+        OffsetBuffer::new(ScalarBuffer::from(offsets)),
+        Arc::new(entries),
+        None,
+    )
+    .context("Failed to create fixed-depth level ListArray")?;
+    Ok(Arc::new(list))
+}
+
+impl OrderbookSnapshot {
+    /// Archive layout of `to_fixed_depth_record_batch`: `arrow_schema` with nullable level entries, plus
+    /// `max_levels` (the depth every row is padded or truncated to) and `bid_full_depth`/`ask_full_depth`
+    /// (each side's level count as received). Carries `SCHEMA_VERSION` like `versioned_schema`.
+    pub fn fixed_depth_arrow_schema() -> std::sync::Arc<arrow::datatypes::Schema> {
+        use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+        use std::sync::Arc;
+
+        let levels = || {
+            DataType::List(Arc::new(Field::new(
+                "item",
+                DataType::Struct(fixed_depth_level_fields().into()),
+                true,
+            )))
+        };
+        Arc::new(Schema::new_with_metadata(
+            vec![
+                Field::new("symbol", DataType::Utf8, false),
+                Field::new(
+                    "received_time",
+                    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                    false,
+                ),
+                Field::new(
+                    "time",
+                    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                    false,
+                ),
+                Field::new("bid_levels", levels(), false),
+                Field::new("ask_levels", levels(), false),
+                Field::new("max_levels", DataType::UInt32, false),
+                Field::new("bid_full_depth", DataType::UInt32, false),
+                Field::new("ask_full_depth", DataType::UInt32, false),
+            ],
+            versioned_schema::<Self>().metadata().clone(),
+        ))
+    }
+
+    /// Encodes snapshots with exactly `depth` levels per side, so analytics can assume a regular layout.
+    /// Deeper sides keep their best `depth` levels (see `truncate`); shallower sides are padded with null
+    /// levels, never with zero-size ones. Each side's original depth is kept in `{bid|ask}_full_depth`.
+    /// Error: `depth` is 0
+    pub fn to_fixed_depth_record_batch(
+        data: Vec<Self>,
+        depth: usize,
+    ) -> OrError<arrow::record_batch::RecordBatch> {
+        use arrow::array::{ArrayRef, StringArray, TimestampMillisecondArray, UInt32Array};
+        use arrow::record_batch::RecordBatch;
+        use std::sync::Arc;
+
+        if depth == 0 {
+            return Err(anyhow::anyhow!(
+                "Argus OrderbookSnapshot fixed-depth error: depth must be positive"
+            ));
+        }
+        let bid_full_depth: ArrayRef = Arc::new(UInt32Array::from(
+            data.iter()
+                .map(|d| d.bid_levels.len() as u32)
+                .collect::<Vec<_>>(),
+        ));
+        let ask_full_depth: ArrayRef = Arc::new(UInt32Array::from(
+            data.iter()
+                .map(|d| d.ask_levels.len() as u32)
+                .collect::<Vec<_>>(),
+        ));
+        let max_levels: ArrayRef = Arc::new(UInt32Array::from(vec![depth as u32; data.len()]));
+        let data: Vec<Self> = data
+            .into_iter()
+            .map(|mut d| {
+                d.truncate(depth);
+                d
+            })
+            .collect();
+
+        let symbols: ArrayRef = Arc::new(StringArray::from(
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
+        ));
+        let received_times: ArrayRef = Arc::new(
+            TimestampMillisecondArray::from(
+                data.iter()
+                    .map(|d| d.received_time.timestamp_millis())
+                    .collect::<Vec<_>>(),
+            )
+            .with_timezone("UTC"),
+        );
+        let times: ArrayRef = Arc::new(
+            TimestampMillisecondArray::from(
+                data.iter()
+                    .map(|d| d.time.timestamp_millis())
+                    .collect::<Vec<_>>(),
+            )
+            .with_timezone("UTC"),
+        );
+        let bid_levels = fixed_depth_level_list(data.iter().map(|d| &d.bid_levels), depth)?;
+        let ask_levels = fixed_depth_level_list(data.iter().map(|d| &d.ask_levels), depth)?;
+
+        RecordBatch::try_new(
+            Self::fixed_depth_arrow_schema(),
+            vec![
+                symbols,
+                received_times,
+                times,
+                bid_levels,
+                ask_levels,
+                max_levels,
+                bid_full_depth,
+                ask_full_depth,
+            ],
+        )
+        .context("Failed to create fixed-depth RecordBatch")
+    }
+
+    /// `to_fixed_depth_record_batch` on a batch in the `arrow_schema` layout, e.g. a scribe's temporary file.
+    /// Used by `Archiver` for data types archived at a fixed depth.
+    pub fn fixed_depth_batch(
+        batch: &arrow::record_batch::RecordBatch,
+        depth: usize,
+    ) -> OrError<arrow::record_batch::RecordBatch> {
+        Self::to_fixed_depth_record_batch(Self::from_record_batch(batch)?, depth)
+    }
+}
+
+/// Reads row `row` of a List<Struct{price, size, n_orders}> column back into levels.
+/// Null entries (fixed-depth padding, see `OrderbookSnapshot::to_fixed_depth_record_batch`) are skipped.
+fn levels_from_list(
+    list: &arrow::array::ListArray,
+    row: usize,
+) -> OrError<Vec<(Price, TradeSize, u32)>> {
+    use arrow::array::{Array, Float64Array, StructArray, UInt32Array};
+
+    let entries = list.value(row);
+    let levels = entries
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .ok_or_else(|| anyhow::anyhow!("Orderbook levels are not a struct array"))?;
+    let prices = downcast_column::<Float64Array>(levels.column_by_name("price"), "price")?;
+    let sizes = downcast_column::<Float64Array>(levels.column_by_name("size"), "size")?;
+    let n_orders = downcast_column::<UInt32Array>(levels.column_by_name("n_orders"), "n_orders")?;
+
+    (0..levels.len())
+        .filter(|&i| levels.is_valid(i))
+        .map(|i| {
+            Ok((
+                Price::from_f64(prices.value(i))?,
+                TradeSize::from_f64(sizes.value(i))?,
+                n_orders.value(i),
+            ))
+        })
+        .collect()
+}
+
+impl ArgusReplayable for OrderbookSnapshot {
+    fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> OrError<Vec<Self>> {
+        use arrow::array::{ListArray, StringArray, TimestampMillisecondArray};
+
+        let symbols = batch_column::<StringArray>(batch, "symbol")?;
+        let received_times = batch_column::<TimestampMillisecondArray>(batch, "received_time")?;
+        let times = batch_column::<TimestampMillisecondArray>(batch, "time")?;
+        let bid_levels = batch_column::<ListArray>(batch, "bid_levels")?;
+        let ask_levels = batch_column::<ListArray>(batch, "ask_levels")?;
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(OrderbookSnapshot {
+                    symbol: TradingSymbol::from_str(symbols.value(i))?,
+                    received_time: millis_to_utc(received_times.value(i))?,
+                    time: millis_to_utc(times.value(i))?,
+                    bid_levels: levels_from_list(bid_levels, i)?,
+                    ask_levels: levels_from_list(ask_levels, i)?,
+                })
+            })
+            .collect()
+    }
+
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.received_time
+    }
+}
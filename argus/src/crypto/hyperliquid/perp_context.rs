@@ -1,5 +1,5 @@
-use super::HyperliquidStreamable;
-use crate::ArgusParquetable;
+use super::{HyperliquidStreamable, normalize_coin};
+use crate::recording::{ArgusParquetable, ArgusReplayable, batch_column, millis_to_utc};
 use crate::types::{Price, TradingSymbol};
 use agora::Agorable;
 use agora::utils::OrError;
@@ -20,6 +20,9 @@ pub struct PerpAssetContext {
     pub open_interest: Option<f64>, // In USD
     pub volume_24h: Option<f64>,    // In USD
     pub oracle_price: Option<Price>,
+    pub premium: Option<f64>,         // Mark-oracle premium as decimal
+    pub prev_day_price: Option<Price>, // Mark price 24h ago
+    pub day_base_volume: Option<f64>, // In base units
 }
 
 impl fmt::Display for PerpAssetContext {
@@ -36,15 +39,21 @@ impl fmt::Display for PerpAssetContext {
                 Open Interest: {:?}
                 24h Volume: {:?}
                 Oracle Price: {:?}
+                Premium: {:?}
+                Prev Day Price: {:?}
+                24h Base Volume: {:?}
             )",
-            self.symbol.to_string(),
+            self.symbol.as_str(),
             self.received_time,
             self.mark_price,
             self.mid_price,
             self.funding_rate * 100.0,
             self.open_interest,
             self.volume_24h,
-            self.oracle_price
+            self.oracle_price,
+            self.premium,
+            self.prev_day_price,
+            self.day_base_volume
         )
     }
 }
@@ -62,6 +71,9 @@ impl Agorable for PerpAssetContext {}
 ///     "openInterest": "1000000.0",
 ///     "dayNtlVlm": "5000000.0",
 ///     "oraclePx": "50000.2",
+///     "premium": "0.00031",
+///     "prevDayPx": "49000.0",
+///     "dayBaseVlm": "100.5",
 ///     ...
 ///   }
 /// }
@@ -78,6 +90,11 @@ struct RawAssetCtx {
     day_ntl_vlm: Option<String>,
     #[serde(rename = "oraclePx")]
     oracle_px: Option<String>,
+    premium: Option<String>,
+    #[serde(rename = "prevDayPx")]
+    prev_day_px: Option<String>,
+    #[serde(rename = "dayBaseVlm")]
+    day_base_vlm: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -100,11 +117,7 @@ impl HyperliquidStreamable for PerpAssetContext {
         })?;
 
         // Extract coin from data and normalize
-        let hyperliquid_coin = TradingSymbol::from_str(&raw.coin)?;
-        let normalized_symbol = symbol_map
-            .get_by_right(&hyperliquid_coin)
-            .cloned()
-            .unwrap_or(hyperliquid_coin);
+        let normalized_symbol = normalize_coin(&raw.coin, symbol_map)?;
 
         let mark_price = Price::from_string(raw.ctx.mark_px)?;
 
@@ -149,6 +162,34 @@ impl HyperliquidStreamable for PerpAssetContext {
             None
         };
 
+        let premium = if let Some(premium_str) = raw.ctx.premium {
+            Some(premium_str.parse().map_err(|e| {
+                anyhow::anyhow!(
+                    "Argus Hyperliquid PerpAssetContext conversion error: cannot parse premium {}: {}",
+                    premium_str, e
+                )
+            })?)
+        } else {
+            None
+        };
+
+        let prev_day_price = if let Some(prev_str) = raw.ctx.prev_day_px {
+            Some(Price::from_string(prev_str)?)
+        } else {
+            None
+        };
+
+        let day_base_volume = if let Some(vol_str) = raw.ctx.day_base_vlm {
+            Some(vol_str.parse().map_err(|e| {
+                anyhow::anyhow!(
+                    "Argus Hyperliquid PerpAssetContext conversion error: cannot parse base volume {}: {}",
+                    vol_str, e
+                )
+            })?)
+        } else {
+            None
+        };
+
         let context = PerpAssetContext {
             symbol: normalized_symbol,
             received_time,
@@ -158,6 +199,9 @@ impl HyperliquidStreamable for PerpAssetContext {
             open_interest,
             volume_24h,
             oracle_price,
+            premium,
+            prev_day_price,
+            day_base_volume,
         };
 
         // Return single item in a vector
@@ -195,6 +239,9 @@ impl ArgusParquetable for PerpAssetContext {
             Field::new("open_interest", DataType::Float64, true),
             Field::new("volume_24h", DataType::Float64, true),
             Field::new("oracle_price", DataType::Float64, true),
+            Field::new("premium", DataType::Float64, true),
+            Field::new("prev_day_price", DataType::Float64, true),
+            Field::new("day_base_volume", DataType::Float64, true),
         ]))
     }
 
@@ -206,9 +253,7 @@ impl ArgusParquetable for PerpAssetContext {
         let schema = Self::arrow_schema();
 
         let symbols: ArrayRef = Arc::new(StringArray::from(
-            data.iter()
-                .map(|d| d.symbol.to_string())
-                .collect::<Vec<_>>(),
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
         ));
 
         let received_times: ArrayRef = Arc::new(
@@ -250,6 +295,20 @@ impl ArgusParquetable for PerpAssetContext {
                 .collect::<Vec<_>>(),
         ));
 
+        let premiums: ArrayRef = Arc::new(Float64Array::from(
+            data.iter().map(|d| d.premium).collect::<Vec<_>>(),
+        ));
+
+        let prev_day_prices: ArrayRef = Arc::new(Float64Array::from(
+            data.iter()
+                .map(|d| d.prev_day_price.as_ref().map(|p| p.to_f64()))
+                .collect::<Vec<_>>(),
+        ));
+
+        let day_base_volumes: ArrayRef = Arc::new(Float64Array::from(
+            data.iter().map(|d| d.day_base_volume).collect::<Vec<_>>(),
+        ));
+
         RecordBatch::try_new(
             schema.clone(),
             vec![
@@ -261,8 +320,56 @@ impl ArgusParquetable for PerpAssetContext {
                 open_interests,
                 volumes_24h,
                 oracle_prices,
+                premiums,
+                prev_day_prices,
+                day_base_volumes,
             ],
         )
         .context("Failed to create RecordBatch")
     }
 }
+
+impl ArgusReplayable for PerpAssetContext {
+    fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> OrError<Vec<Self>> {
+        use arrow::array::{Array, Float64Array, StringArray, TimestampMillisecondArray};
+
+        let symbols = batch_column::<StringArray>(batch, "symbol")?;
+        let received_times = batch_column::<TimestampMillisecondArray>(batch, "received_time")?;
+        let mark_prices = batch_column::<Float64Array>(batch, "mark_price")?;
+        let mid_prices = batch_column::<Float64Array>(batch, "mid_price")?;
+        let funding_rates = batch_column::<Float64Array>(batch, "funding_rate")?;
+        let open_interests = batch_column::<Float64Array>(batch, "open_interest")?;
+        let volumes_24h = batch_column::<Float64Array>(batch, "volume_24h")?;
+        let oracle_prices = batch_column::<Float64Array>(batch, "oracle_price")?;
+        let premiums = batch_column::<Float64Array>(batch, "premium")?;
+        let prev_day_prices = batch_column::<Float64Array>(batch, "prev_day_price")?;
+        let day_base_volumes = batch_column::<Float64Array>(batch, "day_base_volume")?;
+
+        let optional = |column: &Float64Array, i: usize| (!column.is_null(i)).then(|| column.value(i));
+        let optional_price = |column: &Float64Array, i: usize| -> OrError<Option<Price>> {
+            optional(column, i).map(Price::from_f64).transpose()
+        };
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(PerpAssetContext {
+                    symbol: TradingSymbol::from_str(symbols.value(i))?,
+                    received_time: millis_to_utc(received_times.value(i))?,
+                    mark_price: Price::from_f64(mark_prices.value(i))?,
+                    mid_price: optional_price(mid_prices, i)?,
+                    funding_rate: funding_rates.value(i),
+                    open_interest: optional(open_interests, i),
+                    volume_24h: optional(volumes_24h, i),
+                    oracle_price: optional_price(oracle_prices, i)?,
+                    premium: optional(premiums, i),
+                    prev_day_price: optional_price(prev_day_prices, i)?,
+                    day_base_volume: optional(day_base_volumes, i),
+                })
+            })
+            .collect()
+    }
+
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.received_time
+    }
+}
@@ -0,0 +1,53 @@
+use super::{HyperliquidArchiver, HyperliquidScribe};
+use agora::utils::OrError;
+use tracing::{error, info};
+
+/// Scribe and archiver recording one Hyperliquid stream, shut down in an order that loses no data.
+///
+/// Dropping the archiver aborts its timer, so the scribe's final flush would otherwise sit in the tmp dir.
+/// `shutdown` instead flushes the scribe, archives everything it wrote, then stops the archiver.
+pub struct HyperliquidPipeline {
+    scribe: HyperliquidScribe,
+    archiver: HyperliquidArchiver,
+}
+
+impl HyperliquidPipeline {
+    /// `archiver` must read the tmp dir `scribe` writes to
+    pub fn new(scribe: HyperliquidScribe, archiver: HyperliquidArchiver) -> Self {
+        Self { scribe, archiver }
+    }
+
+    /// Shuts down the scribe, runs a final archive pass over the tmp dir, then shuts down the archiver.
+    /// Every step runs even if an earlier one fails, so a scribe error does not strand flushed files;
+    /// the first error is returned.
+    pub async fn shutdown(self) -> OrError<()> {
+        let scribe_result = self.scribe.shutdown().await;
+        if let Err(e) = &scribe_result {
+            error!(error = %e, "scribe shutdown failed");
+        }
+
+        info!("archiving the scribe's final files");
+        let flush_result = self.archiver.flush_now().await;
+        match &flush_result {
+            Ok(count) => info!(files = count, "archived final files"),
+            Err(e) => error!(error = %e, "final archive pass failed"),
+        }
+
+        let archiver_result = self.archiver.shutdown().await;
+        if let Err(e) = &archiver_result {
+            error!(error = %e, "archiver shutdown failed");
+        }
+
+        scribe_result?;
+        flush_result?;
+        archiver_result
+    }
+
+    pub fn scribe(&self) -> &HyperliquidScribe {
+        &self.scribe
+    }
+
+    pub fn archiver(&self) -> &HyperliquidArchiver {
+        &self.archiver
+    }
+}
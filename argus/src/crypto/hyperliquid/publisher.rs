@@ -1,20 +1,26 @@
-use super::HyperliquidStreamable;
-use super::UniverseManager;
+use super::{UniverseFilter, UniverseManager};
 use super::webstream::{HyperliquidPerpWebstreamSymbols, HyperliquidSpotWebstreamSymbols};
 use super::{BboUpdate, OrderbookSnapshot, PerpAssetContext, SpotAssetContext, TradeUpdate};
-use crate::constants::{RELAY_BATCH_DELAY_MS, RELAY_BATCH_SIZE, WORKER_INIT_DELAY_MS};
+use super::{HyperliquidEndpoints, HyperliquidStreamable};
+use crate::constants::{
+    HYPERLIQUID_ORDERBOOK_MAX_LEVELS, HYPERLIQUID_SYMBOLS_PER_CONNECTION,
+    HYPERLIQUID_TRADE_SEQUENCE_MODE, WORKER_INIT_TIMEOUT_MS,
+};
+use crate::metrics::{self, Counter};
+use crate::supervised::spawn_supervised;
 use crate::types::TradingSymbol;
 use agora::utils::OrError;
-use agora::{AgorableOption, ConnectionHandle, Relay};
+use agora::{AgorableOption, ConnectionHandle, Relay, SwaponRetry};
 use bimap::BiMap;
 use futures::future;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
+use tracing::{error, info};
 
 /// Publisher that manages Hyperliquid market data streaming with automatic universe updates.
 ///
@@ -51,7 +57,6 @@ use tokio::task::JoinHandle;
 ///     ↓
 /// Downstream Consumers (never see version changes)
 /// ```
-
 pub struct HyperliquidPublisher {
     perp_universe: Arc<tokio::sync::RwLock<Vec<TradingSymbol>>>,
     spot_universe: Arc<tokio::sync::RwLock<Vec<TradingSymbol>>>,
@@ -59,6 +64,12 @@ pub struct HyperliquidPublisher {
     _agora_path: String,
 }
 
+#[derive(Debug, Clone, Copy)]
+enum MarketKind {
+    Spot,
+    Perp,
+}
+
 fn agora_spot_prefix(version: u32) -> String {
     format!("argus/tmp/hyperliquid/spot_{}", version)
 }
@@ -91,83 +102,63 @@ fn translate_normalized_to_hyperliquid(
 ///
 /// Manages relay instances for all symbols of a specific data type (e.g., all TradeUpdate relays).
 /// Simplifies the repetitive relay management logic.
-struct TypedRelaySet<T: HyperliquidStreamable> {
+/// A symbol whose relay cannot be created is left out rather than failing the set; `bump` retries it.
+pub struct TypedRelaySet<T: HyperliquidStreamable> {
     relays: HashMap<TradingSymbol, Relay<AgorableOption<T>>>,
+    source_versions: HashMap<TradingSymbol, u32>, // Version each relay is currently swapped on to
     market_type: String, // "spot" or "perp"
     agora_prefix: String,
     metaserver_connection: ConnectionHandle,
     local_gateway_port: u16,
+    swapons: Counter,
     _phantom: PhantomData<T>,
 }
 
 impl<T: HyperliquidStreamable> TypedRelaySet<T> {
     /// Creates a new TypedRelaySet for the given symbols.
     ///
+    /// Each relay retries transient registration failures itself (see `Relay::new`); a symbol that still fails
+    /// is logged and skipped, so one bad path does not abort the rest of the set.
+    ///
     /// # Arguments
     /// * `market_type` - Either "spot" or "perp"
     /// * `symbols` - **Normalized symbols** (e.g., "BTC_PERP", "WOW-USDC")
-    async fn new(
+    ///
+    /// # Returns
+    /// The set with a relay for every symbol that succeeded, and the symbols that failed
+    pub async fn new(
         market_type: String,
         symbols: &[TradingSymbol],
         agora_prefix: String,
         metaserver_connection: ConnectionHandle,
         local_gateway_port: u16,
-    ) -> OrError<Self> {
-        let mut relays = HashMap::new();
-
-        for (batch_idx, chunk) in symbols.chunks(RELAY_BATCH_SIZE).enumerate() {
-            for symbol in chunk {
-                let dest_path = format!(
-                    "{}/{}/{}/{}",
-                    agora_prefix,
-                    market_type,
-                    T::payload_identifier(),
-                    symbol.to_string()
-                );
-                let relay = Relay::new(
-                    symbol.to_string(),
-                    dest_path,
-                    AgorableOption(None),
-                    metaserver_connection.clone(),
-                    local_gateway_port,
-                )
-                .await?;
-                relays.insert(symbol.clone(), relay);
-            }
-
-            // Small delay between batches to allow TCP connections to close
-            if batch_idx < (symbols.len() + RELAY_BATCH_SIZE - 1) / RELAY_BATCH_SIZE - 1 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(RELAY_BATCH_DELAY_MS)).await;
-            }
-        }
-
-        Ok(Self {
-            relays,
+    ) -> (Self, Vec<TradingSymbol>) {
+        let swapons = metrics::relay_swapons(&market_type, &T::payload_identifier());
+        let mut relay_set = Self {
+            relays: HashMap::new(),
+            source_versions: HashMap::new(),
             market_type,
             agora_prefix,
             metaserver_connection,
             local_gateway_port,
+            swapons,
             _phantom: PhantomData,
-        })
+        };
+        let failed = relay_set.add_relays(symbols).await;
+        (relay_set, failed)
     }
 
-    /// Updates relays when the universe changes.
-    ///
-    /// # Arguments
-    /// * `new_universe` - **Normalized symbols** for the new universe
-    /// * `version` - Version number for the versioned temporary paths
-    async fn bump(&mut self, new_universe: &[TradingSymbol], version: u32) -> OrError<()> {
-        let current: Vec<_> = self.relays.keys().cloned().collect();
-        let (new_symbols, removed_symbols) = universe_difference(&current, new_universe);
-
-        let versioned_prefix = if self.market_type == "spot" {
-            agora_spot_prefix(version)
-        } else {
-            agora_perp_prefix(version)
-        };
+    /// Normalized symbols with a relay, sorted
+    pub fn symbols(&self) -> Vec<TradingSymbol> {
+        let mut symbols: Vec<_> = self.relays.keys().cloned().collect();
+        symbols.sort_by_key(|symbol| symbol.to_string());
+        symbols
+    }
 
-        // Add new symbols
-        for symbol in &new_symbols {
+    /// Creates a relay on the stable path of each symbol; returns the symbols whose relay failed
+    async fn add_relays(&mut self, symbols: &[TradingSymbol]) -> Vec<TradingSymbol> {
+        let mut failed = Vec::new();
+        for symbol in symbols {
             let dest_path = format!(
                 "{}/{}/{}/{}",
                 self.agora_prefix,
@@ -175,20 +166,65 @@ impl<T: HyperliquidStreamable> TypedRelaySet<T> {
                 T::payload_identifier(),
                 symbol.to_string()
             );
-            let relay = Relay::new(
+            match Relay::new(
                 symbol.to_string(),
-                dest_path,
+                dest_path.clone(),
                 AgorableOption(None),
-                self.metaserver_connection.clone(),
+                self.metaserver_connection,
                 self.local_gateway_port,
             )
-            .await?;
-            self.relays.insert(symbol.clone(), relay);
+            .await
+            {
+                Ok(relay) => {
+                    self.relays.insert(symbol.clone(), relay);
+                }
+                Err(e) => {
+                    error!(dest = %dest_path, error = %e, "failed to create relay");
+                    failed.push(symbol.clone());
+                }
+            }
         }
+        failed
+    }
+
+    /// Updates relays when the universe changes.
+    ///
+    /// Only relays whose symbol moved to a different worker version are swapped; relays of
+    /// unchanged symbols keep streaming from their existing source. Symbols without a relay
+    /// (added, or failed in an earlier attempt) get one; those that fail again wait for the next bump.
+    ///
+    /// # Arguments
+    /// * `new_universe` - **Normalized symbols** for the new universe
+    /// * `symbol_versions` - Version of the worker currently serving each symbol (see `VersionedWorkers`)
+    async fn bump(
+        &mut self,
+        new_universe: &[TradingSymbol],
+        symbol_versions: &HashMap<TradingSymbol, u32>,
+    ) -> OrError<()> {
+        let current: Vec<_> = self.relays.keys().cloned().collect();
+        let (new_symbols, removed_symbols) = universe_difference(&current, new_universe);
 
-        // Swapon all symbols to new versioned source
+        // Add new symbols
+        self.add_relays(&new_symbols).await;
+
+        // Swapon symbols whose worker version changed, waiting for slow workers to register
+        let retry = SwaponRetry {
+            timeout: Duration::from_millis(WORKER_INIT_TIMEOUT_MS),
+            ..SwaponRetry::default()
+        };
         for symbol in new_universe {
+            let Some(&version) = symbol_versions.get(symbol) else {
+                continue;
+            };
+            if self.source_versions.get(symbol) == Some(&version) {
+                continue;
+            }
             if let Some(relay) = self.relays.get_mut(symbol) {
+                let versioned_prefix = if self.market_type == "spot" {
+                    agora_spot_prefix(version)
+                } else {
+                    agora_perp_prefix(version)
+                };
                 let src_path = format!(
                     "{}/{}/{}",
                     versioned_prefix,
@@ -196,14 +232,17 @@ impl<T: HyperliquidStreamable> TypedRelaySet<T> {
                     symbol.to_string()
                 );
                 relay
-                    .swapon(src_path, self.metaserver_connection.clone())
+                    .swapon(src_path, self.metaserver_connection, Some(retry))
                     .await?;
+                self.swapons.inc();
+                self.source_versions.insert(symbol.clone(), version);
             }
         }
 
         // Remove deleted symbols
         for symbol in &removed_symbols {
             self.relays.remove(symbol);
+            self.source_versions.remove(symbol);
         }
 
         Ok(())
@@ -255,11 +294,117 @@ fn universe_difference(
     (new_symbols, removed_symbols)
 }
 
+/// Webstream workers for one market, spawned in batches on versioned paths.
+///
+/// Each symbol is served by exactly one batch, tagged with the version it was spawned under.
+/// A bump only spawns a batch for added symbols and drops batches once all of their symbols
+/// have been removed, so unchanged symbols keep their websocket across universe changes.
+/// Removed symbols sharing a batch with surviving ones keep streaming to their (no longer
+/// relayed) versioned path until the whole batch retires.
+pub struct VersionedWorkers {
+    batches: HashMap<u32, WorkerBatch>,
+    symbol_versions: HashMap<TradingSymbol, u32>,
+}
+
+struct WorkerBatch {
+    symbols: HashSet<TradingSymbol>,
+    handle: JoinHandle<()>,
+}
+
+impl Drop for WorkerBatch {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+impl Default for VersionedWorkers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VersionedWorkers {
+    pub fn new() -> Self {
+        Self {
+            batches: HashMap::new(),
+            symbol_versions: HashMap::new(),
+        }
+    }
+
+    /// Moves to `new_universe`. `spawn` is called at most once, with only the added symbols,
+    /// and must return the task keeping their workers alive under `version`.
+    /// Returns (added, removed) symbols.
+    pub fn bump<F>(
+        &mut self,
+        new_universe: &[TradingSymbol],
+        version: u32,
+        spawn: F,
+    ) -> (Vec<TradingSymbol>, Vec<TradingSymbol>)
+    where
+        F: FnOnce(Vec<TradingSymbol>) -> JoinHandle<()>,
+    {
+        let current = self.symbols();
+        let (new_symbols, removed_symbols) = universe_difference(&current, new_universe);
+
+        for symbol in &removed_symbols {
+            if let Some(old_version) = self.symbol_versions.remove(symbol)
+                && let Some(batch) = self.batches.get_mut(&old_version)
+            {
+                batch.symbols.remove(symbol);
+            }
+        }
+        // Dropping a batch aborts its task, which drops its workers
+        self.batches.retain(|_, batch| !batch.symbols.is_empty());
+
+        if !new_symbols.is_empty() {
+            let handle = spawn(new_symbols.clone());
+            for symbol in &new_symbols {
+                self.symbol_versions.insert(symbol.clone(), version);
+            }
+            self.batches.insert(
+                version,
+                WorkerBatch {
+                    symbols: new_symbols.iter().cloned().collect(),
+                    handle,
+                },
+            );
+        }
+
+        (new_symbols, removed_symbols)
+    }
+
+    /// Symbols currently served, in no particular order
+    pub fn symbols(&self) -> Vec<TradingSymbol> {
+        self.symbol_versions.keys().cloned().collect()
+    }
+
+    pub fn symbol_versions(&self) -> &HashMap<TradingSymbol, u32> {
+        &self.symbol_versions
+    }
+
+    /// Version of the batch serving `symbol`
+    pub fn version_of(&self, symbol: &TradingSymbol) -> Option<u32> {
+        self.symbol_versions.get(symbol).copied()
+    }
+
+    /// True if the batch serving `symbol` is still running
+    pub fn is_running(&self, symbol: &TradingSymbol) -> bool {
+        self.version_of(symbol)
+            .and_then(|version| self.batches.get(&version))
+            .is_some_and(|batch| !batch.handle.is_finished())
+    }
+
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+}
+
 impl PublisherRelays {
     /// Creates a new PublisherRelays instance with initial relays for the given universes.
     ///
     /// This initializes all relay instances with stable destination paths. The relays start
     /// with no source; use `bump()` to connect them to the first versioned sources.
+    /// Symbols whose relay could not be created are logged and get another attempt on the next `bump()`.
     ///
     /// # Arguments
     /// * `spot_universe` - **Normalized spot symbols** (e.g., "WOW-USDC", "PURR-USDC")
@@ -270,101 +415,132 @@ impl PublisherRelays {
         local_gateway_port: u16,
         spot_universe: &[TradingSymbol],
         perp_universe: &[TradingSymbol],
-    ) -> OrError<Self> {
-        Ok(Self {
-            spot_last_trade: TypedRelaySet::new(
-                "spot".into(),
-                spot_universe,
-                agora_prefix.clone(),
-                metaserver_connection.clone(),
-                local_gateway_port,
-            )
-            .await?,
-            spot_bbo: TypedRelaySet::new(
-                "spot".into(),
-                spot_universe,
-                agora_prefix.clone(),
-                metaserver_connection.clone(),
-                local_gateway_port,
-            )
-            .await?,
-            spot_orderbook: TypedRelaySet::new(
-                "spot".into(),
-                spot_universe,
-                agora_prefix.clone(),
-                metaserver_connection.clone(),
-                local_gateway_port,
-            )
-            .await?,
-            spot_context: TypedRelaySet::new(
-                "spot".into(),
-                spot_universe,
-                agora_prefix.clone(),
-                metaserver_connection.clone(),
-                local_gateway_port,
-            )
-            .await?,
-            perp_last_trade: TypedRelaySet::new(
-                "perp".into(),
-                perp_universe,
-                agora_prefix.clone(),
-                metaserver_connection.clone(),
-                local_gateway_port,
-            )
-            .await?,
-            perp_bbo: TypedRelaySet::new(
-                "perp".into(),
-                perp_universe,
-                agora_prefix.clone(),
-                metaserver_connection.clone(),
-                local_gateway_port,
-            )
-            .await?,
-            perp_orderbook: TypedRelaySet::new(
-                "perp".into(),
-                perp_universe,
-                agora_prefix.clone(),
-                metaserver_connection.clone(),
-                local_gateway_port,
-            )
-            .await?,
-            perp_context: TypedRelaySet::new(
-                "perp".into(),
-                perp_universe,
-                agora_prefix,
-                metaserver_connection,
-                local_gateway_port,
-            )
-            .await?,
-        })
+    ) -> Self {
+        let (spot_last_trade, spot_last_trade_failed) = TypedRelaySet::new(
+            "spot".into(),
+            spot_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+        let (spot_bbo, spot_bbo_failed) = TypedRelaySet::new(
+            "spot".into(),
+            spot_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+        let (spot_orderbook, spot_orderbook_failed) = TypedRelaySet::new(
+            "spot".into(),
+            spot_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+        let (spot_context, spot_context_failed) = TypedRelaySet::new(
+            "spot".into(),
+            spot_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+        let (perp_last_trade, perp_last_trade_failed) = TypedRelaySet::new(
+            "perp".into(),
+            perp_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+        let (perp_bbo, perp_bbo_failed) = TypedRelaySet::new(
+            "perp".into(),
+            perp_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+        let (perp_orderbook, perp_orderbook_failed) = TypedRelaySet::new(
+            "perp".into(),
+            perp_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+        let (perp_context, perp_context_failed) = TypedRelaySet::new(
+            "perp".into(),
+            perp_universe,
+            agora_prefix.clone(),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await;
+
+        for (relay_set, failed) in [
+            ("spot_last_trade", spot_last_trade_failed),
+            ("spot_bbo", spot_bbo_failed),
+            ("spot_orderbook", spot_orderbook_failed),
+            ("spot_context", spot_context_failed),
+            ("perp_last_trade", perp_last_trade_failed),
+            ("perp_bbo", perp_bbo_failed),
+            ("perp_orderbook", perp_orderbook_failed),
+            ("perp_context", perp_context_failed),
+        ] {
+            if !failed.is_empty() {
+                error!(
+                    relay_set,
+                    ?failed,
+                    "relays not created, retrying on the next bump"
+                );
+            }
+        }
+
+        Self {
+            spot_last_trade,
+            spot_bbo,
+            spot_orderbook,
+            spot_context,
+            perp_last_trade,
+            perp_bbo,
+            perp_orderbook,
+            perp_context,
+        }
     }
 
     /// Updates relays when the universe changes.
     ///
     /// For new symbols: Creates new relay instances.
-    /// For existing symbols: Calls `swapon()` to switch to new versioned source paths.
+    /// For symbols whose worker version changed: Calls `swapon()` to switch to the new versioned source path.
+    /// For unchanged symbols: Leaves the relay on its current source.
     /// For removed symbols: Drops the relay (removing it from the HashMap).
     ///
     /// # Arguments
-    /// * `spot_universe` - **Normalized spot symbols** for the new universe
-    /// * `perp_universe` - **Normalized perp symbols** for the new universe
-    /// * `version` - Version number for the versioned paths
+    /// * `spot_workers` / `perp_workers` - Worker sets already bumped to the new universe
     pub async fn bump(
         &mut self,
-        spot_universe: &[TradingSymbol],
-        perp_universe: &[TradingSymbol],
-        version: u32,
+        spot_workers: &VersionedWorkers,
+        perp_workers: &VersionedWorkers,
     ) -> OrError<()> {
+        let spot_universe = spot_workers.symbols();
+        let perp_universe = perp_workers.symbols();
+        let spot_versions = spot_workers.symbol_versions();
+        let perp_versions = perp_workers.symbol_versions();
+
         // Delegate to TypedRelaySet::bump for each data type
-        self.spot_last_trade.bump(spot_universe, version).await?;
-        self.spot_bbo.bump(spot_universe, version).await?;
-        self.spot_orderbook.bump(spot_universe, version).await?;
-        self.spot_context.bump(spot_universe, version).await?;
+        self.spot_last_trade.bump(&spot_universe, spot_versions).await?;
+        self.spot_bbo.bump(&spot_universe, spot_versions).await?;
+        self.spot_orderbook.bump(&spot_universe, spot_versions).await?;
+        self.spot_context.bump(&spot_universe, spot_versions).await?;
 
-        self.perp_last_trade.bump(perp_universe, version).await?;
-        self.perp_bbo.bump(perp_universe, version).await?;
-        self.perp_orderbook.bump(perp_universe, version).await?;
-        self.perp_context.bump(perp_universe, version).await?;
+        self.perp_last_trade.bump(&perp_universe, perp_versions).await?;
+        self.perp_bbo.bump(&perp_universe, perp_versions).await?;
+        self.perp_orderbook.bump(&perp_universe, perp_versions).await?;
+        self.perp_context.bump(&perp_universe, perp_versions).await?;
 
         Ok(())
     }
@@ -380,6 +556,8 @@ impl HyperliquidPublisher {
     /// * `local_gateway_port` - Port for the local Agora gateway
     /// * `universe_update_interval` - How often to check Hyperliquid API for universe changes
     /// * `check_interval` - How often to check if the universe has changed and needs version bump
+    /// * `endpoints` - Hyperliquid API to stream from (e.g. `HyperliquidEndpoints::testnet()`); `None` uses mainnet
+    /// * `universe_filter` - Symbols to stream (e.g. the top 50 perps); `None` streams the whole universe
     ///
     /// # Returns
     ///
@@ -390,18 +568,29 @@ impl HyperliquidPublisher {
         local_gateway_port: u16,
         universe_update_interval: Duration,
         check_interval: Duration,
+        endpoints: Option<HyperliquidEndpoints>,
+        universe_filter: Option<UniverseFilter>,
     ) -> OrError<Self> {
-        let universe_manager = Arc::new(UniverseManager::new(universe_update_interval).await?);
+        let endpoints = endpoints.unwrap_or_default();
+        let universe_manager = Arc::new(
+            UniverseManager::new(
+                universe_update_interval,
+                Some(endpoints.clone()),
+                universe_filter,
+                None,
+            )
+            .await?,
+        );
         let perp_universe = Arc::new(RwLock::new(universe_manager.perp_universe().await?));
         let spot_universe = Arc::new(RwLock::new(universe_manager.spot_universe().await?));
 
         let agora_path_clone = agora_path.to_string();
         let perp_clone = perp_universe.clone();
         let spot_clone = spot_universe.clone();
-        let metaserver_connection_clone = metaserver_connection.clone();
+        let metaserver_connection_clone = metaserver_connection;
         let universe_manager_clone = universe_manager.clone();
 
-        let task_handle = tokio::spawn(async move {
+        let relay_manager = async move {
             // Publish to versioned paths: argus/tmp/hyperliquid/spot_{version}/{type}/{symbol}
             let mut version: u32 = 0;
 
@@ -412,32 +601,45 @@ impl HyperliquidPublisher {
             // Get symbol mapper snapshot from universe manager
             let symbol_mapper = universe_manager_clone.symbol_map().await;
 
-            // Spawn initial monitor for version 0
-            let mut monitor_handle = Self::monitor_symbols(
-                current_perp_universe.clone(),
-                current_spot_universe.clone(),
-                version,
-                metaserver_connection_clone.clone(),
-                local_gateway_port,
-                symbol_mapper,
-            );
-            // Sleep to let all the agora paths set up
-            tokio::time::sleep(Duration::from_millis(WORKER_INIT_DELAY_MS)).await;
+            // Spawn initial workers for version 0
+            let mut spot_workers = VersionedWorkers::new();
+            let mut perp_workers = VersionedWorkers::new();
+            spot_workers.bump(&current_spot_universe, version, |symbols| {
+                Self::spawn_workers(
+                    MarketKind::Spot,
+                    symbols,
+                    version,
+                    metaserver_connection_clone,
+                    local_gateway_port,
+                    symbol_mapper.clone(),
+                    endpoints.clone(),
+                )
+            });
+            perp_workers.bump(&current_perp_universe, version, |symbols| {
+                Self::spawn_workers(
+                    MarketKind::Perp,
+                    symbols,
+                    version,
+                    metaserver_connection_clone,
+                    local_gateway_port,
+                    symbol_mapper.clone(),
+                    endpoints.clone(),
+                )
+            });
 
             // Initialize relays with stable destination paths
             let mut relays = PublisherRelays::new(
                 agora_path_clone.clone(),
-                metaserver_connection_clone.clone(),
+                metaserver_connection_clone,
                 local_gateway_port,
                 &current_spot_universe,
                 &current_perp_universe,
             )
-            .await
-            .expect("Failed to initialize relays");
+            .await;
 
-            // Connect relays to initial versioned sources (version 0)
+            // Connect relays to initial versioned sources (version 0). Swapon waits for workers to register
             relays
-                .bump(&current_spot_universe, &current_perp_universe, version)
+                .bump(&spot_workers, &perp_workers)
                 .await
                 .expect("Failed to connect initial relays");
 
@@ -460,39 +662,53 @@ impl HyperliquidPublisher {
 
                 if universe_changed {
                     version += 1;
-                    println!(
-                        "HyperliquidPublisher: Universe changed! Bumping version to {}",
-                        version
-                    );
-
-                    // Spawn new monitor with FRESH symbol_map snapshot (critical for universe changes!)
-                    let new_monitor_handle = Self::monitor_symbols(
-                        new_perp.clone(),
-                        new_spot.clone(),
+                    info!(
                         version,
-                        metaserver_connection_clone.clone(),
-                        local_gateway_port,
-                        universe_manager_clone.symbol_map().await,
+                        added_spot = ?new_spot_symbols,
+                        removed_spot = ?removed_spot_symbols,
+                        added_perp = ?new_perp_symbols,
+                        removed_perp = ?removed_perp_symbols,
+                        "universe changed, bumping version"
                     );
 
-                    // Sleep to let new workers initialize
-                    tokio::time::sleep(Duration::from_millis(WORKER_INIT_DELAY_MS)).await;
-
-                    // Update relays to point to new versioned sources
-                    if let Err(e) = relays.bump(&new_spot, &new_perp, version).await {
-                        eprintln!("HyperliquidPublisher: Error bumping relays: {}", e);
+                    // Spawn workers for added symbols only, with FRESH symbol_map snapshot
+                    // (critical for universe changes!). Unchanged symbols keep their workers.
+                    let symbol_mapper = universe_manager_clone.symbol_map().await;
+                    spot_workers.bump(&new_spot, version, |symbols| {
+                        Self::spawn_workers(
+                            MarketKind::Spot,
+                            symbols,
+                            version,
+                            metaserver_connection_clone,
+                            local_gateway_port,
+                            symbol_mapper.clone(),
+                            endpoints.clone(),
+                        )
+                    });
+                    perp_workers.bump(&new_perp, version, |symbols| {
+                        Self::spawn_workers(
+                            MarketKind::Perp,
+                            symbols,
+                            version,
+                            metaserver_connection_clone,
+                            local_gateway_port,
+                            symbol_mapper.clone(),
+                            endpoints.clone(),
+                        )
+                    });
+
+                    // Point relays of added symbols at the new versioned sources (waits for new workers to register)
+                    if let Err(e) = relays.bump(&spot_workers, &perp_workers).await {
+                        error!(version, error = %e, "failed to bump relays");
                     }
 
                     // Update current universe
                     current_perp_universe = new_perp;
                     current_spot_universe = new_spot;
-
-                    // Abort old monitor (this drops old workers)
-                    monitor_handle.abort();
-                    monitor_handle = new_monitor_handle;
                 }
             }
-        });
+        };
+        let task_handle = spawn_supervised("hyperliquid_relays", relay_manager, |_| {});
 
         Ok(Self {
             perp_universe,
@@ -510,54 +726,64 @@ impl HyperliquidPublisher {
         Ok(read_guard.clone())
     }
 
-    /// Spawns workers for the given normalized symbol universes
+    /// Spawns one batch of spot or perp workers for the given normalized symbols on `version`'s paths.
+    /// The workers live until the returned task is aborted (see `VersionedWorkers`).
     ///
     /// # Arguments
-    /// * `perp_universe` - Normalized perpetual symbols (e.g., "BTC_PERP", "ETH_PERP")
-    /// * `spot_universe` - Normalized spot symbols (e.g., "WOW-USDC", "PURR-USDC")
+    /// * `symbols` - Normalized symbols (e.g., "BTC_PERP" or "WOW-USDC")
     /// * `symbol_mapper` - BiMap for normalized↔Hyperliquid translation (immutable snapshot)
-    fn monitor_symbols(
-        perp_universe: Vec<TradingSymbol>,
-        spot_universe: Vec<TradingSymbol>,
+    /// * `endpoints` - Hyperliquid API the workers connect to
+    fn spawn_workers(
+        market: MarketKind,
+        symbols: Vec<TradingSymbol>,
         version: u32,
         metaserver_connection: ConnectionHandle,
         local_gateway_port: u16,
         symbol_mapper: BiMap<TradingSymbol, TradingSymbol>,
+        endpoints: HyperliquidEndpoints,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
-            let spot_prefix = agora_spot_prefix(version);
-            let perp_prefix = agora_perp_prefix(version);
-
             // Convert normalized symbols to Hyperliquid format for webstream workers
-            let hyperliquid_spot =
-                translate_normalized_to_hyperliquid(&spot_universe, &symbol_mapper);
-            let hyperliquid_perp =
-                translate_normalized_to_hyperliquid(&perp_universe, &symbol_mapper);
+            let hyperliquid_symbols = translate_normalized_to_hyperliquid(&symbols, &symbol_mapper);
 
             // These workers must stay in scope for the lifetime of this task
             // When the task is aborted, they will be dropped and stop streaming
-            let _spot_worker = HyperliquidSpotWebstreamSymbols::new(
-                &hyperliquid_spot,
-                &spot_prefix,
-                metaserver_connection.clone(),
-                local_gateway_port,
-                symbol_mapper.clone(),
-            )
-            .await
-            .unwrap();
-
-            let _perp_worker = HyperliquidPerpWebstreamSymbols::new(
-                &hyperliquid_perp,
-                &perp_prefix,
-                metaserver_connection,
-                local_gateway_port,
-                symbol_mapper,
-            )
-            .await
-            .unwrap();
-
-            // Keep workers alive until task is aborted
-            future::pending::<()>().await;
+            match market {
+                MarketKind::Spot => {
+                    let _spot_worker = HyperliquidSpotWebstreamSymbols::new(
+                        &hyperliquid_symbols,
+                        &agora_spot_prefix(version),
+                        metaserver_connection,
+                        local_gateway_port,
+                        symbol_mapper,
+                        HYPERLIQUID_ORDERBOOK_MAX_LEVELS,
+                        HYPERLIQUID_TRADE_SEQUENCE_MODE,
+                        Some(endpoints),
+                        HYPERLIQUID_SYMBOLS_PER_CONNECTION,
+                        None,
+                    )
+                    .await
+                    .unwrap();
+                    future::pending::<()>().await;
+                }
+                MarketKind::Perp => {
+                    let _perp_worker = HyperliquidPerpWebstreamSymbols::new(
+                        &hyperliquid_symbols,
+                        &agora_perp_prefix(version),
+                        metaserver_connection,
+                        local_gateway_port,
+                        symbol_mapper,
+                        HYPERLIQUID_ORDERBOOK_MAX_LEVELS,
+                        HYPERLIQUID_TRADE_SEQUENCE_MODE,
+                        Some(endpoints),
+                        HYPERLIQUID_SYMBOLS_PER_CONNECTION,
+                        None,
+                    )
+                    .await
+                    .unwrap();
+                    future::pending::<()>().await;
+                }
+            }
         })
     }
 
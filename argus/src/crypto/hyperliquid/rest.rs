@@ -0,0 +1,321 @@
+use crate::constants::{HYPERLIQUID_REST_BURST, HYPERLIQUID_REST_REFILL_MS};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Hyperliquid REST API response for perpetuals metadata (`meta`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PerpMeta {
+    pub universe: Vec<PerpAsset>,
+    #[serde(rename = "marginTables")]
+    pub margin_tables: Vec<serde_json::Value>, // We don't need to parse this for now
+}
+
+/// Hyperliquid perpetual asset metadata
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PerpAsset {
+    pub name: String,
+    #[serde(rename = "szDecimals")]
+    pub sz_decimals: u8,
+    #[serde(rename = "maxLeverage")]
+    pub max_leverage: u32,
+    #[serde(rename = "marginTableId")]
+    pub margin_table_id: u32,
+    #[serde(rename = "isDelisted")]
+    #[serde(default)]
+    pub is_delisted: bool,
+    #[serde(rename = "onlyIsolated")]
+    #[serde(default)]
+    pub only_isolated: bool,
+}
+
+/// Hyperliquid REST API response for spot metadata (`spotMeta`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpotMeta {
+    pub universe: Vec<SpotAsset>,
+    pub tokens: Vec<SpotToken>,
+}
+
+/// Hyperliquid spot asset (trading pair) metadata
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpotAsset {
+    pub name: String,
+    pub tokens: [u32; 2], // Indices into the tokens array
+    pub index: u32,
+    #[serde(rename = "isCanonical")]
+    pub is_canonical: bool,
+}
+
+/// Hyperliquid EVM contract info
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EvmContract {
+    pub address: String,
+    pub evm_extra_wei_decimals: i32, // Can be negative (e.g., -2 for UBTC)
+}
+
+/// Hyperliquid spot token metadata
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpotToken {
+    pub name: String,
+    #[serde(rename = "szDecimals")]
+    pub sz_decimals: u8,
+    #[serde(rename = "weiDecimals")]
+    pub wei_decimals: u8,
+    pub index: u32,
+    #[serde(rename = "tokenId")]
+    pub token_id: String,
+    #[serde(rename = "isCanonical")]
+    pub is_canonical: bool,
+    #[serde(rename = "evmContract")]
+    pub evm_contract: Option<EvmContract>,
+    #[serde(rename = "fullName")]
+    pub full_name: Option<String>,
+    #[serde(rename = "deployerTradingFeeShare")]
+    pub deployer_trading_fee_share: String,
+}
+
+/// Context of one perpetual in a `metaAndAssetCtxs` response, in `PerpMeta::universe` order.
+/// Numbers are decimal strings, as Hyperliquid sends them.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PerpAssetCtx {
+    pub funding: String,
+    #[serde(rename = "markPx")]
+    pub mark_px: String,
+    #[serde(rename = "midPx")]
+    pub mid_px: Option<String>,
+    #[serde(rename = "oraclePx")]
+    pub oracle_px: Option<String>,
+    #[serde(rename = "openInterest")]
+    pub open_interest: Option<String>,
+    #[serde(rename = "dayNtlVlm")]
+    pub day_ntl_vlm: Option<String>,
+    #[serde(rename = "dayBaseVlm")]
+    pub day_base_vlm: Option<String>,
+    pub premium: Option<String>,
+    #[serde(rename = "prevDayPx")]
+    pub prev_day_px: Option<String>,
+}
+
+/// Request body for info requests: `{"type": request_type}`
+#[derive(Debug, Serialize)]
+struct InfoRequest<'a> {
+    #[serde(rename = "type")]
+    request_type: &'a str,
+}
+
+/// Failure of one Hyperliquid info request. `request` is the request type, e.g. "meta".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HyperliquidRestError {
+    /// Request never got a response (connection refused, timeout, DNS, ...)
+    Request {
+        request: &'static str,
+        reason: String,
+    },
+    /// HTTP 429: Hyperliquid's rate limit was hit. `retry_after` is the server's `Retry-After`, if it sent one.
+    RateLimited {
+        request: &'static str,
+        retry_after: Option<Duration>,
+    },
+    /// Response with another non-success HTTP status
+    Status { request: &'static str, status: u16 },
+    /// Response body is not the expected type
+    Parse {
+        request: &'static str,
+        reason: String,
+    },
+}
+
+impl HyperliquidRestError {
+    /// Whether the same request may succeed later: rate limits, connection failures and server errors
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            HyperliquidRestError::Request { .. } | HyperliquidRestError::RateLimited { .. } => true,
+            HyperliquidRestError::Status { status, .. } => *status >= 500,
+            HyperliquidRestError::Parse { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for HyperliquidRestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HyperliquidRestError::Request { request, reason } => write!(
+                f,
+                "Hyperliquid REST API request error ({}): {}",
+                request, reason
+            ),
+            HyperliquidRestError::RateLimited {
+                request,
+                retry_after,
+            } => write!(
+                f,
+                "Hyperliquid REST API rate limited ({}): HTTP 429, retry after {:?}",
+                request, retry_after
+            ),
+            HyperliquidRestError::Status { request, status } => write!(
+                f,
+                "Hyperliquid REST API error ({}): HTTP {}",
+                request, status
+            ),
+            HyperliquidRestError::Parse { request, reason } => write!(
+                f,
+                "Hyperliquid REST API response parse error ({}): {}",
+                request, reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HyperliquidRestError {}
+
+/// Client-side limit on info requests: bursts of up to `burst` requests, then one per `refill_interval`.
+/// The default (`HYPERLIQUID_REST_BURST`, `HYPERLIQUID_REST_REFILL_MS`) keeps a process well within Hyperliquid's
+/// per-IP budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestRateLimit {
+    pub burst: u32,
+    pub refill_interval: Duration,
+}
+
+impl Default for RestRateLimit {
+    fn default() -> Self {
+        Self {
+            burst: HYPERLIQUID_REST_BURST,
+            refill_interval: Duration::from_millis(HYPERLIQUID_REST_REFILL_MS),
+        }
+    }
+}
+
+/// Token bucket kept as the time the bucket is next full (GCRA), so waiting callers are served in arrival order
+struct TokenBucket {
+    limit: RestRateLimit,
+    full_at: Mutex<Instant>,
+}
+
+impl TokenBucket {
+    fn new(limit: RestRateLimit) -> Self {
+        Self {
+            limit,
+            full_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Takes a token, waiting until one is free
+    async fn acquire(&self) {
+        let start = {
+            let mut full_at = self.full_at.lock().unwrap();
+            let now = Instant::now();
+            let burst_span = self.limit.refill_interval * self.limit.burst.max(1).saturating_sub(1);
+            let base = (*full_at).max(now);
+            *full_at = base + self.limit.refill_interval;
+            base.checked_sub(burst_span).unwrap_or(now).max(now)
+        };
+        tokio::time::sleep_until(start).await;
+    }
+}
+
+/// Shared client for Hyperliquid's REST info endpoint: one connection pool and one `RestRateLimit` for every caller.
+/// Clones share both. `shared` hands every component in the process the same client per endpoint, so concurrent
+/// universe polling and tools stay within the rate limit together.
+#[derive(Clone)]
+pub struct HyperliquidRestClient {
+    http: reqwest::Client,
+    info_url: String,
+    limiter: Arc<TokenBucket>,
+}
+
+impl HyperliquidRestClient {
+    /// Client of its own for `info_url`, limited by `rate_limit` (defaults to `RestRateLimit::default()`)
+    pub fn new(info_url: &str, rate_limit: Option<RestRateLimit>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            info_url: info_url.to_string(),
+            limiter: Arc::new(TokenBucket::new(rate_limit.unwrap_or_default())),
+        }
+    }
+
+    /// The process-wide client for `info_url`, created with the default rate limit on first use
+    pub fn shared(info_url: &str) -> Self {
+        static CLIENTS: OnceLock<Mutex<HashMap<String, HyperliquidRestClient>>> = OnceLock::new();
+        CLIENTS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap()
+            .entry(info_url.to_string())
+            .or_insert_with(|| Self::new(info_url, None))
+            .clone()
+    }
+
+    pub fn info_url(&self) -> &str {
+        &self.info_url
+    }
+
+    /// Perpetuals metadata
+    pub async fn meta(&self) -> Result<PerpMeta, HyperliquidRestError> {
+        self.info("meta").await
+    }
+
+    /// Spot metadata
+    pub async fn spot_meta(&self) -> Result<SpotMeta, HyperliquidRestError> {
+        self.info("spotMeta").await
+    }
+
+    /// Perpetuals metadata with each asset's context (volume, funding, ...), in `PerpMeta::universe` order
+    pub async fn meta_and_asset_ctxs(
+        &self,
+    ) -> Result<(PerpMeta, Vec<PerpAssetCtx>), HyperliquidRestError> {
+        self.info("metaAndAssetCtxs").await
+    }
+
+    /// Posts `{"type": request}` to the info endpoint once a rate limit token is free, and parses the response as `R`
+    async fn info<R: DeserializeOwned>(
+        &self,
+        request: &'static str,
+    ) -> Result<R, HyperliquidRestError> {
+        self.limiter.acquire().await;
+        let response = self
+            .http
+            .post(&self.info_url)
+            .json(&InfoRequest {
+                request_type: request,
+            })
+            .send()
+            .await
+            .map_err(|e| HyperliquidRestError::Request {
+                request,
+                reason: e.to_string(),
+            })?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(HyperliquidRestError::RateLimited {
+                request,
+                retry_after,
+            });
+        }
+        if !status.is_success() {
+            return Err(HyperliquidRestError::Status {
+                request,
+                status: status.as_u16(),
+            });
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| HyperliquidRestError::Parse {
+                request,
+                reason: e.to_string(),
+            })
+    }
+}
@@ -1,9 +1,15 @@
 use super::{BboUpdate, OrderbookSnapshot, PerpAssetContext, SpotAssetContext, TradeUpdate};
+use crate::recording::{
+    ArgusParquetable, DedupKey, FlushSchedule, ValidationReport, versioned_schema,
+};
+use crate::constants::HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS;
 use crate::{AgoraDirScribe, Archiver};
+use std::collections::HashMap;
 use agora::utils::OrError;
-use agora::{AgorableOption, ConnectionHandle};
+use agora::{Agorable, AgorableOption, ConnectionHandle};
 use anyhow::Context;
 use std::time::Duration;
+use tracing::error;
 
 /// Manages scribing of all Hyperliquid market data to disk
 ///
@@ -11,20 +17,23 @@ use std::time::Duration;
 /// to temporary parquet files. These files should then be organized by the Archiver.
 ///
 /// # Data Structure
-/// Writes to: `{output_dir}/{data_type}/{symbol}_{timestamp}.pq`
+/// Writes to: `{output_dir}/{spot|perp}/{data_type}/{symbol}_{timestamp}.pq`
+/// (`{spot|perp}.{symbol}_{timestamp}.pq` when built `with_market_tags(.., true)`)
 /// Where data_type is: last_trade, bbo, orderbook, spot_context, perp_context
+///
+/// A scribe built with `HyperliquidScribeBuilder::allow_partial` may lack some data types (`None` below).
 pub struct HyperliquidScribe {
     // Spot market scribes
-    spot_trade_scribe: AgoraDirScribe<AgorableOption<TradeUpdate>>,
-    spot_bbo_scribe: AgoraDirScribe<AgorableOption<BboUpdate>>,
-    spot_orderbook_scribe: AgoraDirScribe<AgorableOption<OrderbookSnapshot>>,
-    spot_context_scribe: AgoraDirScribe<AgorableOption<SpotAssetContext>>,
+    spot_trade_scribe: Option<AgoraDirScribe<AgorableOption<TradeUpdate>>>,
+    spot_bbo_scribe: Option<AgoraDirScribe<AgorableOption<BboUpdate>>>,
+    spot_orderbook_scribe: Option<AgoraDirScribe<AgorableOption<OrderbookSnapshot>>>,
+    spot_context_scribe: Option<AgoraDirScribe<AgorableOption<SpotAssetContext>>>,
 
     // Perp market scribes
-    perp_trade_scribe: AgoraDirScribe<AgorableOption<TradeUpdate>>,
-    perp_bbo_scribe: AgoraDirScribe<AgorableOption<BboUpdate>>,
-    perp_orderbook_scribe: AgoraDirScribe<AgorableOption<OrderbookSnapshot>>,
-    perp_context_scribe: AgoraDirScribe<AgorableOption<PerpAssetContext>>,
+    perp_trade_scribe: Option<AgoraDirScribe<AgorableOption<TradeUpdate>>>,
+    perp_bbo_scribe: Option<AgoraDirScribe<AgorableOption<BboUpdate>>>,
+    perp_orderbook_scribe: Option<AgoraDirScribe<AgorableOption<OrderbookSnapshot>>>,
+    perp_context_scribe: Option<AgoraDirScribe<AgorableOption<PerpAssetContext>>>,
 }
 
 impl HyperliquidScribe {
@@ -34,165 +43,326 @@ impl HyperliquidScribe {
     /// * `agora_path` - Base Agora path where HyperliquidPublisher publishes (e.g., "argus/hyperliquid")
     /// * `output_dir` - Base filesystem directory for temporary parquet files (e.g., "/tmp/hyperliquid")
     /// * `metaserver_connection` - Connection to the Agora metaserver
-    /// * `flush_duration` - How often to flush accumulated data to disk
+    /// * `flush_duration` - How often to flush accumulated data to disk; symbols are staggered across the interval.
+    ///   Shared by every data type (see `HyperliquidScribeBuilder::flush_interval` to set one per data type)
     ///
     /// # Returns
-    /// A `HyperliquidScribe` that continuously writes market data to parquet files
+    /// A `HyperliquidScribe` that continuously writes market data to parquet files.
+    /// All or nothing: if any data type fails to start, the scribes already started are shut down
+    /// and the error is returned (see `HyperliquidScribeBuilder::allow_partial` to continue without it).
     pub async fn new(
         agora_path: &str,
         output_dir: &str,
         metaserver_connection: ConnectionHandle,
         flush_duration: Duration,
     ) -> OrError<Self> {
-        println!("Initializing HyperliquidScribe...");
-        println!("  Agora path: {}", agora_path);
-        println!("  Output dir: {}", output_dir);
-        println!("  Flush interval: {:?}", flush_duration);
-
-        // Create output directories
-        std::fs::create_dir_all(output_dir)
-            .map_err(|e| anyhow::anyhow!("Failed to create output directory {}: {}", output_dir, e))?;
-
-        let data_types = vec![
-            "last_trade",
-            "bbo",
-            "orderbook",
-            "spot_context",
-            "perp_context",
-        ];
-
-        for data_type in &data_types {
-            let spot_dir = format!("{}/spot/{}", output_dir, data_type);
-            let perp_dir = format!("{}/perp/{}", output_dir, data_type);
-            std::fs::create_dir_all(&spot_dir)
-                .context("Failed to create spot directory")?;
-            std::fs::create_dir_all(&perp_dir)
-                .context("Failed to create perp directory")?;
-        }
-
-        println!("  Created output directory structure");
+        Self::with_market_tags(agora_path, output_dir, metaserver_connection, flush_duration, false)
+            .await
+    }
 
-        // Initialize spot scribes
-        println!("\nInitializing spot market scribes...");
-        let spot_trade_scribe = AgoraDirScribe::new(
-            &format!("{}/spot/last_trade", agora_path),
-            metaserver_connection.clone(),
+    /// Like `new`; with `embed_market`, filenames carry the market (`spot.{symbol}_...`, `perp.{symbol}_...`),
+    /// so a symbol trading both spot and perp stays unambiguous regardless of directory layout.
+    /// The archiver then adds a `market={market}` hive partition.
+    pub async fn with_market_tags(
+        agora_path: &str,
+        output_dir: &str,
+        metaserver_connection: ConnectionHandle,
+        flush_duration: Duration,
+        embed_market: bool,
+    ) -> OrError<Self> {
+        let (scribe, _) = HyperliquidScribeBuilder::new(
+            agora_path,
+            output_dir,
+            metaserver_connection,
             flush_duration,
-            &format!("{}/spot/last_trade", output_dir),
         )
+        .embed_market(embed_market)
+        .build()
         .await?;
-        println!("  ✓ Spot trades: {} symbols", spot_trade_scribe.count());
+        Ok(scribe)
+    }
 
-        let spot_bbo_scribe = AgoraDirScribe::new(
-            &format!("{}/spot/bbo", agora_path),
-            metaserver_connection.clone(),
-            flush_duration,
-            &format!("{}/spot/bbo", output_dir),
-        )
-        .await?;
-        println!("  ✓ Spot BBO: {} symbols", spot_bbo_scribe.count());
+    fn empty() -> Self {
+        Self {
+            spot_trade_scribe: None,
+            spot_bbo_scribe: None,
+            spot_orderbook_scribe: None,
+            spot_context_scribe: None,
+            perp_trade_scribe: None,
+            perp_bbo_scribe: None,
+            perp_orderbook_scribe: None,
+            perp_context_scribe: None,
+        }
+    }
 
-        let spot_orderbook_scribe = AgoraDirScribe::new(
-            &format!("{}/spot/orderbook", agora_path),
-            metaserver_connection.clone(),
-            flush_duration,
-            &format!("{}/spot/orderbook", output_dir),
-        )
-        .await?;
-        println!(
-            "  ✓ Spot orderbook: {} symbols",
-            spot_orderbook_scribe.count()
-        );
+    /// Data types being recorded, as `{spot|perp}/{data_type}`
+    pub fn data_types(&self) -> Vec<&'static str> {
+        [
+            ("spot/last_trade", self.spot_trade_scribe.is_some()),
+            ("spot/bbo", self.spot_bbo_scribe.is_some()),
+            ("spot/orderbook", self.spot_orderbook_scribe.is_some()),
+            ("spot/spot_context", self.spot_context_scribe.is_some()),
+            ("perp/last_trade", self.perp_trade_scribe.is_some()),
+            ("perp/bbo", self.perp_bbo_scribe.is_some()),
+            ("perp/orderbook", self.perp_orderbook_scribe.is_some()),
+            ("perp/perp_context", self.perp_context_scribe.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(data_type, running)| running.then_some(data_type))
+        .collect()
+    }
 
-        let spot_context_scribe = AgoraDirScribe::new(
-            &format!("{}/spot/spot_context", agora_path),
-            metaserver_connection.clone(),
-            flush_duration,
-            &format!("{}/spot/spot_context", output_dir),
-        )
-        .await?;
-        println!("  ✓ Spot context: {} symbols", spot_context_scribe.count());
+    /// Gracefully shutdown all scribes, flushing remaining data to disk
+    pub async fn shutdown(self) -> OrError<()> {
+        println!("\nShutting down HyperliquidScribe...");
 
-        // Initialize perp scribes
-        println!("\nInitializing perp market scribes...");
-        let perp_trade_scribe = AgoraDirScribe::new(
-            &format!("{}/perp/last_trade", agora_path),
-            metaserver_connection.clone(),
-            flush_duration,
-            &format!("{}/perp/last_trade", output_dir),
-        )
-        .await?;
-        println!("  ✓ Perp trades: {} symbols", perp_trade_scribe.count());
+        if let Some(scribe) = self.spot_trade_scribe {
+            println!("  Flushing spot trades...");
+            scribe.shutdown().await?;
+        }
+        if let Some(scribe) = self.spot_bbo_scribe {
+            println!("  Flushing spot BBO...");
+            scribe.shutdown().await?;
+        }
+        if let Some(scribe) = self.spot_orderbook_scribe {
+            println!("  Flushing spot orderbook...");
+            scribe.shutdown().await?;
+        }
+        if let Some(scribe) = self.spot_context_scribe {
+            println!("  Flushing spot context...");
+            scribe.shutdown().await?;
+        }
 
-        let perp_bbo_scribe = AgoraDirScribe::new(
-            &format!("{}/perp/bbo", agora_path),
-            metaserver_connection.clone(),
-            flush_duration,
-            &format!("{}/perp/bbo", output_dir),
-        )
-        .await?;
-        println!("  ✓ Perp BBO: {} symbols", perp_bbo_scribe.count());
+        if let Some(scribe) = self.perp_trade_scribe {
+            println!("  Flushing perp trades...");
+            scribe.shutdown().await?;
+        }
+        if let Some(scribe) = self.perp_bbo_scribe {
+            println!("  Flushing perp BBO...");
+            scribe.shutdown().await?;
+        }
+        if let Some(scribe) = self.perp_orderbook_scribe {
+            println!("  Flushing perp orderbook...");
+            scribe.shutdown().await?;
+        }
+        if let Some(scribe) = self.perp_context_scribe {
+            println!("  Flushing perp context...");
+            scribe.shutdown().await?;
+        }
 
-        let perp_orderbook_scribe = AgoraDirScribe::new(
-            &format!("{}/perp/orderbook", agora_path),
-            metaserver_connection.clone(),
-            flush_duration,
-            &format!("{}/perp/orderbook", output_dir),
-        )
-        .await?;
-        println!(
-            "  ✓ Perp orderbook: {} symbols",
-            perp_orderbook_scribe.count()
-        );
+        println!("✅ HyperliquidScribe shutdown complete");
+        Ok(())
+    }
+}
+
+/// Data types HyperliquidPublisher serves, as named in scribe paths (`{spot|perp}/{data_type}`)
+const DATA_TYPES: [&str; 5] = [
+    "last_trade",
+    "bbo",
+    "orderbook",
+    "spot_context",
+    "perp_context",
+];
 
-        let perp_context_scribe = AgoraDirScribe::new(
-            &format!("{}/perp/perp_context", agora_path),
+/// Configures and starts a `HyperliquidScribe`, one `AgoraDirScribe` per market and data type.
+///
+/// By default the build is all or nothing, like `HyperliquidScribe::new`. With `allow_partial(true)`,
+/// a data type whose scribe cannot start (e.g. no perp orderbook publishers registered yet) is skipped
+/// and reported in the returned warnings, and the scribe records the rest.
+pub struct HyperliquidScribeBuilder {
+    agora_path: String,
+    output_dir: String,
+    metaserver_connection: ConnectionHandle,
+    flush_duration: Duration,
+    flush_intervals: HashMap<String, Duration>,
+    embed_market: bool,
+    allow_partial: bool,
+}
+
+impl HyperliquidScribeBuilder {
+    /// Arguments as in `HyperliquidScribe::new`
+    pub fn new(
+        agora_path: &str,
+        output_dir: &str,
+        metaserver_connection: ConnectionHandle,
+        flush_duration: Duration,
+    ) -> Self {
+        Self {
+            agora_path: agora_path.to_string(),
+            output_dir: output_dir.to_string(),
             metaserver_connection,
             flush_duration,
-            &format!("{}/perp/perp_context", output_dir),
-        )
-        .await?;
-        println!("  ✓ Perp context: {} symbols", perp_context_scribe.count());
+            flush_intervals: HashMap::new(),
+            embed_market: false,
+            allow_partial: false,
+        }
+    }
 
-        println!("\n✅ HyperliquidScribe initialized successfully!");
+    /// Flush `data_type` (`last_trade`, `bbo`, `orderbook`, `spot_context` or `perp_context`) every `flush_duration`
+    /// in both markets, instead of the shared interval: e.g. trades often to bound memory, contexts rarely to avoid
+    /// tiny files. Unknown data types fail the build.
+    pub fn flush_interval(mut self, data_type: &str, flush_duration: Duration) -> Self {
+        self.flush_intervals.insert(data_type.to_string(), flush_duration);
+        self
+    }
 
-        Ok(Self {
-            spot_trade_scribe,
-            spot_bbo_scribe,
-            spot_orderbook_scribe,
-            spot_context_scribe,
-            perp_trade_scribe,
-            perp_bbo_scribe,
-            perp_orderbook_scribe,
-            perp_context_scribe,
-        })
+    /// Interval `data_type` is flushed at: its own if set with `flush_interval`, the shared one otherwise
+    pub fn flush_duration_of(&self, data_type: &str) -> Duration {
+        self.flush_intervals
+            .get(data_type)
+            .copied()
+            .unwrap_or(self.flush_duration)
     }
 
-    /// Gracefully shutdown all scribes, flushing remaining data to disk
-    pub async fn shutdown(self) -> OrError<()> {
-        println!("\nShutting down HyperliquidScribe...");
+    /// Tag filenames with their market (see `HyperliquidScribe::with_market_tags`)
+    pub fn embed_market(mut self, embed_market: bool) -> Self {
+        self.embed_market = embed_market;
+        self
+    }
 
-        println!("  Flushing spot trades...");
-        self.spot_trade_scribe.shutdown().await?;
-        println!("  Flushing spot BBO...");
-        self.spot_bbo_scribe.shutdown().await?;
-        println!("  Flushing spot orderbook...");
-        self.spot_orderbook_scribe.shutdown().await?;
-        println!("  Flushing spot context...");
-        self.spot_context_scribe.shutdown().await?;
-
-        println!("  Flushing perp trades...");
-        self.perp_trade_scribe.shutdown().await?;
-        println!("  Flushing perp BBO...");
-        self.perp_bbo_scribe.shutdown().await?;
-        println!("  Flushing perp orderbook...");
-        self.perp_orderbook_scribe.shutdown().await?;
-        println!("  Flushing perp context...");
-        self.perp_context_scribe.shutdown().await?;
+    /// Skip data types whose scribe fails to start instead of failing the build
+    pub fn allow_partial(mut self, allow_partial: bool) -> Self {
+        self.allow_partial = allow_partial;
+        self
+    }
 
-        println!("✅ HyperliquidScribe shutdown complete");
+    /// Starts the scribes. Returns the scribe and one warning per skipped data type
+    /// (`{spot|perp}/{data_type}: {error}`, always empty unless `allow_partial`).
+    /// Error: a data type failed without `allow_partial` (the scribes already started are shut down first),
+    /// or every data type failed.
+    pub async fn build(self) -> OrError<(HyperliquidScribe, Vec<String>)> {
+        println!("Initializing HyperliquidScribe...");
+        println!("  Agora path: {}", self.agora_path);
+        println!("  Output dir: {}", self.output_dir);
+        println!("  Flush interval: {:?}", self.flush_duration);
+        let mut flush_intervals: Vec<(&String, &Duration)> = self.flush_intervals.iter().collect();
+        flush_intervals.sort();
+        for (data_type, flush_duration) in flush_intervals {
+            if !DATA_TYPES.contains(&data_type.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "Argus HyperliquidScribe error: unknown data type '{}' for flush_interval",
+                    data_type
+                ));
+            }
+            println!("    {}: {:?}", data_type, flush_duration);
+        }
+
+        // Create output directories
+        std::fs::create_dir_all(&self.output_dir).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to create output directory {}: {}",
+                self.output_dir,
+                e
+            )
+        })?;
+
+        for data_type in DATA_TYPES {
+            let spot_dir = format!("{}/spot/{}", self.output_dir, data_type);
+            let perp_dir = format!("{}/perp/{}", self.output_dir, data_type);
+            std::fs::create_dir_all(&spot_dir)
+                .context("Failed to create spot directory")?;
+            std::fs::create_dir_all(&perp_dir)
+                .context("Failed to create perp directory")?;
+        }
+
+        println!("  Created output directory structure");
+
+        let mut scribe = HyperliquidScribe::empty();
+        let mut warnings = Vec::new();
+        if let Err(e) = self.start_all(&mut scribe, &mut warnings).await {
+            // Dropping the started scribes would leave their subscribers churning until the tasks notice
+            println!("  Shutting down the scribes already started...");
+            if let Err(shutdown_error) = scribe.shutdown().await {
+                error!(error = %shutdown_error, "failed to shut down partially built scribe");
+            }
+            return Err(e);
+        }
+        if scribe.data_types().is_empty() {
+            return Err(anyhow::anyhow!(
+                "Argus HyperliquidScribe error: no data type could be scribed under {}: {}",
+                self.agora_path,
+                warnings.join("; ")
+            ));
+        }
+
+        if warnings.is_empty() {
+            println!("\n✅ HyperliquidScribe initialized successfully!");
+        } else {
+            println!(
+                "\n⚠️  HyperliquidScribe initialized without {} data type(s):",
+                warnings.len()
+            );
+            for warning in &warnings {
+                println!("  {}", warning);
+            }
+        }
+        Ok((scribe, warnings))
+    }
+
+    async fn start_all(
+        &self,
+        scribe: &mut HyperliquidScribe,
+        warnings: &mut Vec<String>,
+    ) -> OrError<()> {
+        // Initialize spot scribes
+        println!("\nInitializing spot market scribes...");
+        scribe.spot_trade_scribe = self
+            .start("spot", "last_trade", "Spot trades", warnings)
+            .await?;
+        scribe.spot_bbo_scribe = self.start("spot", "bbo", "Spot BBO", warnings).await?;
+        scribe.spot_orderbook_scribe = self
+            .start("spot", "orderbook", "Spot orderbook", warnings)
+            .await?;
+        scribe.spot_context_scribe = self
+            .start("spot", "spot_context", "Spot context", warnings)
+            .await?;
+
+        // Initialize perp scribes
+        println!("\nInitializing perp market scribes...");
+        scribe.perp_trade_scribe = self
+            .start("perp", "last_trade", "Perp trades", warnings)
+            .await?;
+        scribe.perp_bbo_scribe = self.start("perp", "bbo", "Perp BBO", warnings).await?;
+        scribe.perp_orderbook_scribe = self
+            .start("perp", "orderbook", "Perp orderbook", warnings)
+            .await?;
+        scribe.perp_context_scribe = self
+            .start("perp", "perp_context", "Perp context", warnings)
+            .await?;
         Ok(())
     }
+
+    /// Scribe for `{market}/{data_type}`; `None` (with a warning) if it fails to start and partial builds are allowed
+    async fn start<T: Agorable + ArgusParquetable>(
+        &self,
+        market: &'static str,
+        data_type: &str,
+        label: &str,
+        warnings: &mut Vec<String>,
+    ) -> OrError<Option<AgoraDirScribe<T>>> {
+        let started = AgoraDirScribe::with_market(
+            &format!("{}/{}/{}", self.agora_path, market, data_type),
+            self.metaserver_connection,
+            self.flush_duration_of(data_type),
+            &format!("{}/{}/{}", self.output_dir, market, data_type),
+            self.embed_market.then_some(market),
+            Some(FlushSchedule::Staggered),
+            None,
+        )
+        .await;
+        match started {
+            Ok(scribe) => {
+                println!("  ✓ {}: {} symbols", label, scribe.count());
+                Ok(Some(scribe))
+            }
+            Err(e) if self.allow_partial => {
+                println!("  ✗ {}: skipped", label);
+                warnings.push(format!("{}/{}: {}", market, data_type, e));
+                Ok(None)
+            }
+            Err(e) => Err(e.context(format!("Failed to start {} scribe", label))),
+        }
+    }
 }
 
 /// Manages archiving of all Hyperliquid temporary parquet files
@@ -205,6 +375,7 @@ impl HyperliquidScribe {
 ///
 /// # Output Structure (hive-partitioned)
 /// Writes to: `{output_dir}/{spot|perp}/{data_type}/date={date}/symbol={symbol}/data.parquet`
+/// (with a `market={spot|perp}` level before `symbol=` for files from a market-tagged scribe)
 pub struct HyperliquidArchiver {
     spot_archiver: Archiver,
     perp_archiver: Archiver,
@@ -216,10 +387,22 @@ impl HyperliquidArchiver {
     /// # Arguments
     /// * `tmp_dir` - Base temporary directory where HyperliquidScribe writes (e.g., "/tmp/hyperliquid")
     /// * `output_dir` - Base directory for organized data (e.g., "/home/nlyu/Data/argus/hyperliquid")
+    /// * `orderbook_depth` - Levels per side orderbooks are padded/truncated to (see `OrderbookSnapshot::to_fixed_depth_record_batch`);
+    ///   `None` archives them at the depth received
+    /// * `scan_interval` - Time between scan/flush passes over the temporary directory;
+    ///   `None` uses `HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS`
     ///
     /// # Returns
     /// A `HyperliquidArchiver` that continuously organizes market data files
-    pub async fn new(tmp_dir: &str, output_dir: &str) -> OrError<Self> {
+    pub async fn new(
+        tmp_dir: &str,
+        output_dir: &str,
+        orderbook_depth: Option<usize>,
+        scan_interval: Option<Duration>,
+    ) -> OrError<Self> {
+        let scan_interval = scan_interval.unwrap_or(Duration::from_secs(
+            HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS,
+        ));
         println!("========================================");
         println!("  Hyperliquid Archiver");
         println!("========================================");
@@ -227,6 +410,9 @@ impl HyperliquidArchiver {
         println!("Initializing HyperliquidArchiver...");
         println!("  Temporary dir: {}", tmp_dir);
         println!("  Output dir: {}", output_dir);
+        if let Some(depth) = orderbook_depth {
+            println!("  Orderbook depth: {} levels per side", depth);
+        }
         println!();
 
         // Define data types for each market
@@ -244,13 +430,38 @@ impl HyperliquidArchiver {
             "perp_context".to_string(),
         ];
 
+        // Hyperliquid never emits two distinct books for one exchange timestamp, so overlapping
+        // scribe files (e.g. across restarts) are deduplicated on `time`. Contexts carry no exchange time.
+        let dedup_keys = || {
+            HashMap::from([(
+                "orderbook".to_string(),
+                DedupKey::TimeColumn("time".to_string()),
+            )])
+        };
+        let fixed_depths = || {
+            orderbook_depth
+                .map(|depth| HashMap::from([("orderbook".to_string(), depth)]))
+                .unwrap_or_default()
+        };
+
         // Paths for spot market
         let spot_tmp_dir = format!("{}/spot", tmp_dir);
         let spot_output_dir = format!("{}/spot", output_dir);
 
         println!("Initializing spot market archiver...");
-        let spot_archiver =
-            Archiver::new(&spot_output_dir, &spot_data_types, &spot_tmp_dir).await?;
+        let spot_archiver = Archiver::new(
+            &spot_output_dir,
+            &spot_data_types,
+            &spot_tmp_dir,
+            None,
+            dedup_keys(),
+            None,
+            None,
+            fixed_depths(),
+            None,
+            Some(scan_interval),
+        )
+        .await?;
         println!("  ✓ Spot archiver initialized");
         println!("    Source: {}", spot_tmp_dir);
         println!("    Target: {}", spot_output_dir);
@@ -262,8 +473,19 @@ impl HyperliquidArchiver {
         let perp_output_dir = format!("{}/perp", output_dir);
 
         println!("Initializing perp market archiver...");
-        let perp_archiver =
-            Archiver::new(&perp_output_dir, &perp_data_types, &perp_tmp_dir).await?;
+        let perp_archiver = Archiver::new(
+            &perp_output_dir,
+            &perp_data_types,
+            &perp_tmp_dir,
+            None,
+            dedup_keys(),
+            None,
+            None,
+            fixed_depths(),
+            None,
+            Some(scan_interval),
+        )
+        .await?;
         println!("  ✓ Perp archiver initialized");
         println!("    Source: {}", perp_tmp_dir);
         println!("    Target: {}", perp_output_dir);
@@ -282,7 +504,10 @@ impl HyperliquidArchiver {
             "Output format: {{output_dir}}/{{spot|perp}}/{{data_type}}/date={{date}}/symbol={{symbol}}/data.parquet"
         );
         println!();
-        println!("Background tasks are scanning for files every 10 seconds.");
+        println!(
+            "Background tasks are scanning for files every {:?}.",
+            scan_interval
+        );
         println!("Files are archived when newer data is detected for the same symbol.");
         println!();
 
@@ -292,6 +517,51 @@ impl HyperliquidArchiver {
         })
     }
 
+    /// One immediate archive pass over both markets, including each symbol's newest file.
+    /// Call once the scribe has shut down; returns the number of files flushed.
+    pub async fn flush_now(&self) -> OrError<usize> {
+        let spot = self.spot_archiver.flush_now().await?;
+        let perp = self.perp_archiver.flush_now().await?;
+        Ok(spot + perp)
+    }
+
+    /// Validates the whole archive under `output_dir` (see `Archiver::validate_all`): each market's data types
+    /// against their types' current schemas, with the UTC partition dates `new` archives by.
+    /// `orderbook_depth` must match the one the archive was written with: fixed-depth orderbooks have their own layout.
+    /// Blocking: reads every archived file.
+    pub fn validate(output_dir: &str, orderbook_depth: Option<usize>) -> OrError<ValidationReport> {
+        let orderbook_schema = match orderbook_depth {
+            Some(_) => OrderbookSnapshot::fixed_depth_arrow_schema(),
+            None => versioned_schema::<OrderbookSnapshot>(),
+        };
+        let shared = || {
+            HashMap::from([
+                ("last_trade".to_string(), versioned_schema::<TradeUpdate>()),
+                ("bbo".to_string(), versioned_schema::<BboUpdate>()),
+                ("orderbook".to_string(), orderbook_schema.clone()),
+            ])
+        };
+        let mut spot_schemas = shared();
+        spot_schemas.insert(
+            "spot_context".to_string(),
+            versioned_schema::<SpotAssetContext>(),
+        );
+        let mut perp_schemas = shared();
+        perp_schemas.insert(
+            "perp_context".to_string(),
+            versioned_schema::<PerpAssetContext>(),
+        );
+
+        let mut report =
+            Archiver::validate_all(&format!("{}/spot", output_dir), &spot_schemas, None)?;
+        report.merge(Archiver::validate_all(
+            &format!("{}/perp", output_dir),
+            &perp_schemas,
+            None,
+        )?);
+        Ok(report)
+    }
+
     /// Gracefully shutdown all archivers
     pub async fn shutdown(mut self) -> OrError<()> {
         println!();
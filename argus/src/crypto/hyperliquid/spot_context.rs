@@ -1,5 +1,5 @@
-use super::HyperliquidStreamable;
-use crate::ArgusParquetable;
+use super::{HyperliquidStreamable, normalize_coin};
+use crate::recording::{ArgusParquetable, ArgusReplayable, batch_column, millis_to_utc};
 use crate::types::{Price, TradingSymbol};
 use agora::Agorable;
 use agora::utils::OrError;
@@ -19,6 +19,8 @@ pub struct SpotAssetContext {
     pub volume_24h: Option<f64>,         // In USD
     pub circulating_supply: Option<f64>, // Spot-specific
     pub total_supply: Option<f64>,       // Spot-specific
+    pub prev_day_price: Option<Price>,   // Mark price 24h ago
+    pub day_base_volume: Option<f64>,    // In base units
 }
 
 impl fmt::Display for SpotAssetContext {
@@ -34,14 +36,18 @@ impl fmt::Display for SpotAssetContext {
                 24h Volume: {:?}
                 Circulating Supply: {:?}
                 Total Supply: {:?}
+                Prev Day Price: {:?}
+                24h Base Volume: {:?}
             )",
-            self.symbol.to_string(),
+            self.symbol.as_str(),
             self.received_time,
             self.mark_price,
             self.mid_price,
             self.volume_24h,
             self.circulating_supply,
-            self.total_supply
+            self.total_supply,
+            self.prev_day_price,
+            self.day_base_volume
         )
     }
 }
@@ -58,6 +64,8 @@ impl Agorable for SpotAssetContext {}
 ///     "dayNtlVlm": "500000.0",
 ///     "circulatingSupply": "1000000000",
 ///     "totalSupply": "10000000000",
+///     "prevDayPx": "0.049",
+///     "dayBaseVlm": "10000000.0",
 ///     ...
 ///   }
 /// }
@@ -73,6 +81,10 @@ struct RawSpotAssetCtx {
     circulating_supply: Option<String>,
     #[serde(rename = "totalSupply")]
     total_supply: Option<String>,
+    #[serde(rename = "prevDayPx")]
+    prev_day_px: Option<String>,
+    #[serde(rename = "dayBaseVlm")]
+    day_base_vlm: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -95,11 +107,7 @@ impl HyperliquidStreamable for SpotAssetContext {
         })?;
 
         // Extract coin from data and normalize
-        let hyperliquid_coin = TradingSymbol::from_str(&raw.coin)?;
-        let normalized_symbol = symbol_map
-            .get_by_right(&hyperliquid_coin)
-            .cloned()
-            .unwrap_or(hyperliquid_coin);
+        let normalized_symbol = normalize_coin(&raw.coin, symbol_map)?;
 
         let mark_price = Price::from_string(raw.ctx.mark_px)?;
 
@@ -142,6 +150,23 @@ impl HyperliquidStreamable for SpotAssetContext {
             None
         };
 
+        let prev_day_price = if let Some(prev_str) = raw.ctx.prev_day_px {
+            Some(Price::from_string(prev_str)?)
+        } else {
+            None
+        };
+
+        let day_base_volume = if let Some(vol_str) = raw.ctx.day_base_vlm {
+            Some(vol_str.parse().map_err(|e| {
+                anyhow::anyhow!(
+                    "Argus Hyperliquid SpotAssetContext conversion error: cannot parse base volume {}: {}",
+                    vol_str, e
+                )
+            })?)
+        } else {
+            None
+        };
+
         let context = SpotAssetContext {
             symbol: normalized_symbol,
             received_time,
@@ -150,6 +175,8 @@ impl HyperliquidStreamable for SpotAssetContext {
             volume_24h,
             circulating_supply,
             total_supply,
+            prev_day_price,
+            day_base_volume,
         };
 
         // Return single item in a vector
@@ -186,6 +213,8 @@ impl ArgusParquetable for SpotAssetContext {
             Field::new("volume_24h", DataType::Float64, true),
             Field::new("circulating_supply", DataType::Float64, true),
             Field::new("total_supply", DataType::Float64, true),
+            Field::new("prev_day_price", DataType::Float64, true),
+            Field::new("day_base_volume", DataType::Float64, true),
         ]))
     }
 
@@ -197,9 +226,7 @@ impl ArgusParquetable for SpotAssetContext {
         let schema = Self::arrow_schema();
 
         let symbols: ArrayRef = Arc::new(StringArray::from(
-            data.iter()
-                .map(|d| d.symbol.to_string())
-                .collect::<Vec<_>>(),
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
         ));
 
         let received_times: ArrayRef = Arc::new(
@@ -237,6 +264,16 @@ impl ArgusParquetable for SpotAssetContext {
             data.iter().map(|d| d.total_supply).collect::<Vec<_>>(),
         ));
 
+        let prev_day_prices: ArrayRef = Arc::new(Float64Array::from(
+            data.iter()
+                .map(|d| d.prev_day_price.as_ref().map(|p| p.to_f64()))
+                .collect::<Vec<_>>(),
+        ));
+
+        let day_base_volumes: ArrayRef = Arc::new(Float64Array::from(
+            data.iter().map(|d| d.day_base_volume).collect::<Vec<_>>(),
+        ));
+
         RecordBatch::try_new(
             schema.clone(),
             vec![
@@ -247,8 +284,51 @@ impl ArgusParquetable for SpotAssetContext {
                 volumes_24h,
                 circulating_supplies,
                 total_supplies,
+                prev_day_prices,
+                day_base_volumes,
             ],
         )
         .context("Failed to create RecordBatch")
     }
 }
+
+impl ArgusReplayable for SpotAssetContext {
+    fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> OrError<Vec<Self>> {
+        use arrow::array::{Array, Float64Array, StringArray, TimestampMillisecondArray};
+
+        let symbols = batch_column::<StringArray>(batch, "symbol")?;
+        let received_times = batch_column::<TimestampMillisecondArray>(batch, "received_time")?;
+        let mark_prices = batch_column::<Float64Array>(batch, "mark_price")?;
+        let mid_prices = batch_column::<Float64Array>(batch, "mid_price")?;
+        let volumes_24h = batch_column::<Float64Array>(batch, "volume_24h")?;
+        let circulating_supplies = batch_column::<Float64Array>(batch, "circulating_supply")?;
+        let total_supplies = batch_column::<Float64Array>(batch, "total_supply")?;
+        let prev_day_prices = batch_column::<Float64Array>(batch, "prev_day_price")?;
+        let day_base_volumes = batch_column::<Float64Array>(batch, "day_base_volume")?;
+
+        let optional = |column: &Float64Array, i: usize| (!column.is_null(i)).then(|| column.value(i));
+        let optional_price = |column: &Float64Array, i: usize| -> OrError<Option<Price>> {
+            optional(column, i).map(Price::from_f64).transpose()
+        };
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(SpotAssetContext {
+                    symbol: TradingSymbol::from_str(symbols.value(i))?,
+                    received_time: millis_to_utc(received_times.value(i))?,
+                    mark_price: Price::from_f64(mark_prices.value(i))?,
+                    mid_price: optional_price(mid_prices, i)?,
+                    volume_24h: optional(volumes_24h, i),
+                    circulating_supply: optional(circulating_supplies, i),
+                    total_supply: optional(total_supplies, i),
+                    prev_day_price: optional_price(prev_day_prices, i)?,
+                    day_base_volume: optional(day_base_volumes, i),
+                })
+            })
+            .collect()
+    }
+
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.received_time
+    }
+}
@@ -1,14 +1,23 @@
-use super::HyperliquidStreamable;
-use crate::recording::ArgusParquetable;
+use super::{HyperliquidStreamable, normalize_coin};
+use crate::constants::{
+    HYPERLIQUID_AGORA_PREFIX, HYPERLIQUID_TRADE_VOLUME_WINDOW_SECS, TRADE_VOLUME_REFRESH_MS,
+};
+use crate::recording::{ArgusParquetable, ArgusReplayable, batch_column, millis_to_utc};
+use crate::supervised::spawn_supervised;
 use crate::types::{Price, TradeSize, TradingSymbol};
 use agora::utils::OrError;
-use agora::Agorable;
+use agora::{Agorable, AgorableOption, ConnectionHandle, Publisher, Subscriber};
 use anyhow::Context;
 use bimap::BiMap;
+use chrono::Duration;
 use chrono::prelude::{DateTime, Utc};
+use futures_util::StreamExt;
 use indoc::writedoc;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fmt;
+use tokio::task::JoinHandle;
+use tracing::warn;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TradeUpdate {
@@ -86,11 +95,7 @@ impl HyperliquidStreamable for TradeUpdate {
 
         // Extract coin from first trade and normalize
         // All trades in the same message are for the same symbol
-        let hyperliquid_coin = TradingSymbol::from_str(&raw_trades[0].coin)?;
-        let normalized_symbol = symbol_map
-            .get_by_right(&hyperliquid_coin)
-            .cloned()
-            .unwrap_or(hyperliquid_coin);
+        let normalized_symbol = normalize_coin(&raw_trades[0].coin, symbol_map)?;
 
         // Parse ALL trades in the array (critical fix!)
         let mut parsed_trades = Vec::new();
@@ -138,6 +143,296 @@ impl HyperliquidStreamable for TradeUpdate {
     fn symbol(&self) -> TradingSymbol {
         self.symbol.clone()
     }
+
+    /// Hyperliquid trade id (`tid`)
+    fn sequence(&self) -> Option<u64> {
+        Some(self.trade_id)
+    }
+}
+
+/// Taker buy/sell volume and VWAP of one symbol's trades over a trailing window, as `TradeVolumeRelay` publishes it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeVolumeUpdate {
+    pub symbol: TradingSymbol,
+    pub time: DateTime<Utc>, // End of the window (trade time of the newest trade, or later once advanced)
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub vwap: Option<Price>, // None when the window holds no trades
+    pub trade_count: u64,
+}
+
+impl fmt::Display for TradeVolumeUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TradeVolumeUpdate({} buy {} / sell {} over {} trades, VWAP {:?} at {})",
+            self.symbol.as_str(),
+            self.buy_volume,
+            self.sell_volume,
+            self.trade_count,
+            self.vwap.as_ref().map(Price::to_f64),
+            self.time
+        )
+    }
+}
+impl Agorable for TradeVolumeUpdate {}
+
+/// Rolling taker buy/sell volume and VWAP over the trades of a single symbol within a trailing `window`.
+///
+/// The window ends at the newest trade time seen, or later once `advance_to` moves it, and holds the trades
+/// with `end - window < trade_time <= end`. Sides follow `is_buy`. Trades already older than the window
+/// when they arrive are dropped; late trades still inside it are counted.
+#[derive(Debug, Clone)]
+pub struct TradeVolumeProfile {
+    symbol: TradingSymbol,
+    window: Duration,
+    end: Option<DateTime<Utc>>,
+    trades: VecDeque<(DateTime<Utc>, f64, f64, bool)>, // (trade time, price, size, is_buy), oldest first
+    buy_volume: f64,
+    sell_volume: f64,
+    notional: f64,
+}
+
+impl TradeVolumeProfile {
+    pub fn new(symbol: TradingSymbol, window: Duration) -> OrError<Self> {
+        if window.num_milliseconds() <= 0 {
+            return Err(anyhow::anyhow!(
+                "TradeVolumeProfile error: window must be at least 1ms, got {}",
+                window
+            ));
+        }
+        Ok(Self {
+            symbol,
+            window,
+            end: None,
+            trades: VecDeque::new(),
+            buy_volume: 0.0,
+            sell_volume: 0.0,
+            notional: 0.0,
+        })
+    }
+
+    /// Adds a trade, moving the window end up to its trade time.
+    /// Returns false if the trade was already outside the window and was dropped.
+    pub fn push_trade(&mut self, trade: &TradeUpdate) -> bool {
+        let time = trade.trade_time;
+        self.advance_to(time);
+        if self.end.is_some_and(|end| time <= end - self.window) {
+            return false;
+        }
+        let (price, size) = (trade.price.to_f64(), trade.size.to_f64());
+        if trade.is_buy {
+            self.buy_volume += size;
+        } else {
+            self.sell_volume += size;
+        }
+        self.notional += price * size;
+        // Keep trade-time order so eviction can stop at the first trade still inside the window
+        let index = self
+            .trades
+            .iter()
+            .rposition(|(queued, ..)| *queued <= time)
+            .map_or(0, |i| i + 1);
+        self.trades.insert(index, (time, price, size, trade.is_buy));
+        true
+    }
+
+    /// Moves the window end to `now` (never backwards) and evicts the trades that fell out.
+    /// Returns the number of trades evicted.
+    pub fn advance_to(&mut self, now: DateTime<Utc>) -> usize {
+        let end = match self.end {
+            Some(end) if end >= now => return 0,
+            _ => now,
+        };
+        self.end = Some(end);
+        let cutoff = end - self.window;
+        let mut evicted = 0;
+        while let Some(&(time, price, size, is_buy)) = self.trades.front() {
+            if time > cutoff {
+                break;
+            }
+            self.trades.pop_front();
+            if is_buy {
+                self.buy_volume -= size;
+            } else {
+                self.sell_volume -= size;
+            }
+            self.notional -= price * size;
+            evicted += 1;
+        }
+        // Running sums of an empty window are exactly zero, whatever rounding accumulated
+        if self.trades.is_empty() {
+            self.buy_volume = 0.0;
+            self.sell_volume = 0.0;
+            self.notional = 0.0;
+        }
+        evicted
+    }
+
+    /// Total size of taker buys in the window
+    pub fn buy_volume(&self) -> f64 {
+        self.buy_volume
+    }
+
+    /// Total size of taker sells in the window
+    pub fn sell_volume(&self) -> f64 {
+        self.sell_volume
+    }
+
+    pub fn trade_count(&self) -> usize {
+        self.trades.len()
+    }
+
+    /// Size-weighted average price of the window's trades: sum(price * size) / sum(size).
+    /// None: no trades in the window
+    pub fn vwap(&self) -> Option<Price> {
+        let volume = self.buy_volume + self.sell_volume;
+        if self.trades.is_empty() || volume <= 0.0 {
+            return None;
+        }
+        Price::from_f64(self.notional / volume).ok()
+    }
+
+    /// Current window as an update; None before the first trade
+    pub fn snapshot(&self) -> Option<TradeVolumeUpdate> {
+        Some(TradeVolumeUpdate {
+            symbol: self.symbol.clone(),
+            time: self.end?,
+            buy_volume: self.buy_volume,
+            sell_volume: self.sell_volume,
+            vwap: self.vwap(),
+            trade_count: self.trades.len() as u64,
+        })
+    }
+}
+
+/// Derived stream republishing a symbol's rolling buy/sell volume and VWAP:
+///   {source_path} (AgorableOption<TradeUpdate>) -> {output_path} (AgorableOption<TradeVolumeUpdate>)
+///
+/// Publishes on every trade, and every `TRADE_VOLUME_REFRESH_MS` on which trades expired from the window,
+/// so a quiet market's volume decays to zero (and its VWAP to `None`) instead of going stale.
+/// Expiry on the timer uses wall clock against exchange trade times.
+pub struct TradeVolumeRelay {
+    output_path: String,
+    task_handle: JoinHandle<()>,
+}
+
+impl TradeVolumeRelay {
+    /// Follows `argus/hyperliquid/perp/last_trade/{symbol}` and publishes to `argus/hyperliquid/perp/trade_volume/{symbol}`
+    /// # Arguments
+    /// * `window` - Trailing window to aggregate over (default: `HYPERLIQUID_TRADE_VOLUME_WINDOW_SECS`)
+    pub async fn new(
+        name: String,
+        symbol: &TradingSymbol,
+        window: Option<Duration>,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        Self::with_paths(
+            name,
+            symbol.clone(),
+            format!(
+                "{}/perp/{}/{}",
+                HYPERLIQUID_AGORA_PREFIX,
+                TradeUpdate::payload_identifier(),
+                symbol.to_string()
+            ),
+            format!(
+                "{}/perp/trade_volume/{}",
+                HYPERLIQUID_AGORA_PREFIX,
+                symbol.to_string()
+            ),
+            window,
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await
+    }
+
+    /// # Arguments
+    /// * `symbol` - Symbol of the source path's trades
+    /// * `source_path` - Agora path publishing `AgorableOption<TradeUpdate>` (e.g., "argus/hyperliquid/perp/last_trade/BTC_PERP")
+    /// * `output_path` - Agora path to publish `AgorableOption<TradeVolumeUpdate>` onto
+    /// * `window` - Trailing window to aggregate over (default: `HYPERLIQUID_TRADE_VOLUME_WINDOW_SECS`)
+    pub async fn with_paths(
+        name: String,
+        symbol: TradingSymbol,
+        source_path: String,
+        output_path: String,
+        window: Option<Duration>,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+    ) -> OrError<Self> {
+        let window = window.unwrap_or(Duration::seconds(HYPERLIQUID_TRADE_VOLUME_WINDOW_SECS));
+        let mut profile = TradeVolumeProfile::new(symbol, window)?;
+        let mut subscriber = Subscriber::<AgorableOption<TradeUpdate>>::new(
+            source_path.clone(),
+            metaserver_connection,
+        )
+        .await?;
+        let (current, mut stream) = subscriber.get_stream().await?;
+        if let Some(trade) = &current.0 {
+            profile.push_trade(trade);
+        }
+        let mut publisher = Publisher::<AgorableOption<TradeVolumeUpdate>>::new(
+            name,
+            output_path.clone(),
+            AgorableOption(profile.snapshot()),
+            metaserver_connection,
+            local_gateway_port,
+        )
+        .await?;
+
+        let relay = async move {
+            let _subscriber = subscriber; // Keep the source connection alive
+            let mut ticker =
+                tokio::time::interval(std::time::Duration::from_millis(TRADE_VOLUME_REFRESH_MS));
+            loop {
+                tokio::select! {
+                    item = stream.next() => match item {
+                        Some(Ok(AgorableOption(Some(trade)))) => {
+                            if !profile.push_trade(&trade) {
+                                continue;
+                            }
+                        }
+                        Some(Ok(AgorableOption(None))) => continue,
+                        Some(Err(e)) => {
+                            warn!(source = %source_path, error = %e, "TradeVolumeRelay: stream error");
+                            continue;
+                        }
+                        None => {
+                            warn!(source = %source_path, "TradeVolumeRelay: source closed");
+                            break;
+                        }
+                    },
+                    _ = ticker.tick() => {
+                        if profile.advance_to(Utc::now()) == 0 {
+                            continue;
+                        }
+                    }
+                }
+                if let Err(e) = publisher.publish(AgorableOption(profile.snapshot())).await {
+                    warn!(source = %source_path, error = %e, "TradeVolumeRelay: publish error");
+                }
+            }
+        };
+        let task_handle = spawn_supervised("trade_volume_relay", relay, |_| {});
+
+        Ok(Self {
+            output_path,
+            task_handle,
+        })
+    }
+
+    pub fn output_path(&self) -> &str {
+        &self.output_path
+    }
+}
+
+impl Drop for TradeVolumeRelay {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
 }
 
 impl ArgusParquetable for TradeUpdate {
@@ -176,9 +471,7 @@ impl ArgusParquetable for TradeUpdate {
 
         // Convert each field to Arrow arrays
         let symbols: ArrayRef = Arc::new(StringArray::from(
-            data.iter()
-                .map(|d| d.symbol.to_string())
-                .collect::<Vec<_>>(),
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
         ));
 
         let received_times: ArrayRef = Arc::new(
@@ -231,3 +524,37 @@ impl ArgusParquetable for TradeUpdate {
         .context("Failed to create RecordBatch")
     }
 }
+
+impl ArgusReplayable for TradeUpdate {
+    fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> OrError<Vec<Self>> {
+        use arrow::array::{
+            BooleanArray, Float64Array, StringArray, TimestampMillisecondArray, UInt64Array,
+        };
+
+        let symbols = batch_column::<StringArray>(batch, "symbol")?;
+        let received_times = batch_column::<TimestampMillisecondArray>(batch, "received_time")?;
+        let trade_ids = batch_column::<UInt64Array>(batch, "trade_id")?;
+        let prices = batch_column::<Float64Array>(batch, "price")?;
+        let sizes = batch_column::<Float64Array>(batch, "size")?;
+        let trade_times = batch_column::<TimestampMillisecondArray>(batch, "trade_time")?;
+        let is_buys = batch_column::<BooleanArray>(batch, "is_buy")?;
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(TradeUpdate {
+                    symbol: TradingSymbol::from_str(symbols.value(i))?,
+                    received_time: millis_to_utc(received_times.value(i))?,
+                    trade_id: trade_ids.value(i),
+                    price: Price::from_f64(prices.value(i))?,
+                    size: TradeSize::from_f64(sizes.value(i))?,
+                    trade_time: millis_to_utc(trade_times.value(i))?,
+                    is_buy: is_buys.value(i),
+                })
+            })
+            .collect()
+    }
+
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.received_time
+    }
+}
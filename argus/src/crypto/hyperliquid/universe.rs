@@ -1,158 +1,109 @@
-use crate::constants::HYPERLIQUID_INFO_ENDPOINT;
+use super::HyperliquidEndpoints;
+use super::rest::{HyperliquidRestClient, HyperliquidRestError, PerpMeta, SpotMeta};
+use crate::constants::{HYPERLIQUID_UNIVERSE_FETCH_RETRIES, HYPERLIQUID_UNIVERSE_RETRY_BACKOFF_MS};
 use crate::types::TradingSymbol;
 use agora::utils::OrError;
 use bimap::BiMap;
-use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-
-/// Hyperliquid REST API response for perpetuals metadata
-#[derive(Debug, Deserialize, Serialize)]
-struct PerpMeta {
-    universe: Vec<PerpAsset>,
-    #[serde(rename = "marginTables")]
-    margin_tables: Vec<serde_json::Value>, // We don't need to parse this for now
-}
-
-/// Hyperliquid perpetual asset metadata
-#[derive(Debug, Deserialize, Serialize)]
-struct PerpAsset {
-    name: String,
-    #[serde(rename = "szDecimals")]
-    sz_decimals: u8,
-    #[serde(rename = "maxLeverage")]
-    max_leverage: u32,
-    #[serde(rename = "marginTableId")]
-    margin_table_id: u32,
-    #[serde(rename = "isDelisted")]
-    #[serde(default)]
-    is_delisted: bool,
-    #[serde(rename = "onlyIsolated")]
-    #[serde(default)]
-    only_isolated: bool,
-}
-
-/// Hyperliquid REST API response for spot metadata
-#[derive(Debug, Deserialize, Serialize)]
-struct SpotMeta {
-    universe: Vec<SpotAsset>,
-    tokens: Vec<SpotToken>,
+use tracing::{info, warn};
+
+/// Failure of one Hyperliquid REST metadata request. `market` is "perp" or "spot".
+/// Returned (wrapped in `anyhow::Error`, downcastable) by `UniverseManager::new` when no market could be loaded.
+#[derive(Debug)]
+pub enum UniverseFetchError {
+    /// Request never got a response (connection refused, timeout, DNS, ...)
+    Request {
+        market: &'static str,
+        reason: String,
+    },
+    /// HTTP 429 from Hyperliquid; `retry_after` is the server's `Retry-After`, if it sent one
+    RateLimited {
+        market: &'static str,
+        retry_after: Option<Duration>,
+    },
+    /// Response with another non-success HTTP status
+    Status { market: &'static str, status: u16 },
+    /// Response body is not the expected metadata
+    Parse {
+        market: &'static str,
+        reason: String,
+    },
 }
 
-/// Hyperliquid spot asset (trading pair) metadata
-#[derive(Debug, Deserialize, Serialize)]
-struct SpotAsset {
-    name: String,
-    tokens: [u32; 2], // Indices into the tokens array
-    index: u32,
-    #[serde(rename = "isCanonical")]
-    is_canonical: bool,
-}
-
-/// Hyperliquid EVM contract info
-#[derive(Debug, Deserialize, Serialize)]
-struct EvmContract {
-    address: String,
-    evm_extra_wei_decimals: i32, // Can be negative (e.g., -2 for UBTC)
-}
-
-/// Hyperliquid spot token metadata
-#[derive(Debug, Deserialize, Serialize)]
-struct SpotToken {
-    name: String,
-    #[serde(rename = "szDecimals")]
-    sz_decimals: u8,
-    #[serde(rename = "weiDecimals")]
-    wei_decimals: u8,
-    index: u32,
-    #[serde(rename = "tokenId")]
-    token_id: String,
-    #[serde(rename = "isCanonical")]
-    is_canonical: bool,
-    #[serde(rename = "evmContract")]
-    evm_contract: Option<EvmContract>,
-    #[serde(rename = "fullName")]
-    full_name: Option<String>,
-    #[serde(rename = "deployerTradingFeeShare")]
-    deployer_trading_fee_share: String,
+impl fmt::Display for UniverseFetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UniverseFetchError::Request { market, reason } => write!(
+                f,
+                "Hyperliquid REST API request error ({} meta): {}",
+                market, reason
+            ),
+            UniverseFetchError::RateLimited {
+                market,
+                retry_after,
+            } => write!(
+                f,
+                "Hyperliquid REST API rate limited ({} meta): HTTP 429, retry after {:?}",
+                market, retry_after
+            ),
+            UniverseFetchError::Status { market, status } => write!(
+                f,
+                "Hyperliquid REST API error ({} meta): HTTP {}",
+                market, status
+            ),
+            UniverseFetchError::Parse { market, reason } => write!(
+                f,
+                "Hyperliquid REST API response parse error ({} meta): {}",
+                market, reason
+            ),
+        }
+    }
 }
 
-/// Request body for fetching perpetuals metadata
-#[derive(Debug, Serialize)]
-struct MetaRequest {
-    #[serde(rename = "type")]
-    request_type: String,
-}
+impl std::error::Error for UniverseFetchError {}
 
-/// Request body for fetching spot metadata
-#[derive(Debug, Serialize)]
-struct SpotMetaRequest {
-    #[serde(rename = "type")]
-    request_type: String,
+impl UniverseFetchError {
+    /// Tags a REST client error with the market whose metadata was requested
+    fn from_rest(market: &'static str, error: HyperliquidRestError) -> Self {
+        match error {
+            HyperliquidRestError::Request { reason, .. } => {
+                UniverseFetchError::Request { market, reason }
+            }
+            HyperliquidRestError::RateLimited { retry_after, .. } => {
+                UniverseFetchError::RateLimited {
+                    market,
+                    retry_after,
+                }
+            }
+            HyperliquidRestError::Status { status, .. } => {
+                UniverseFetchError::Status { market, status }
+            }
+            HyperliquidRestError::Parse { reason, .. } => {
+                UniverseFetchError::Parse { market, reason }
+            }
+        }
+    }
 }
 
 /// Fetches perpetual universe from Hyperliquid REST API
-async fn fetch_perp_meta() -> OrError<PerpMeta> {
-    let client = reqwest::Client::new();
-    let request = MetaRequest {
-        request_type: "meta".to_string(),
-    };
-
-    let response = client
-        .post(HYPERLIQUID_INFO_ENDPOINT)
-        .json(&request)
-        .send()
+async fn fetch_perp_meta(client: &HyperliquidRestClient) -> Result<PerpMeta, UniverseFetchError> {
+    client
+        .meta()
         .await
-        .map_err(|e| anyhow::anyhow!("Hyperliquid REST API request error (perp meta): {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Hyperliquid REST API error (perp meta): HTTP {}",
-            response.status()
-        ));
-    }
-
-    let meta: PerpMeta = response.json().await.map_err(|e| {
-        anyhow::anyhow!(
-            "Hyperliquid REST API response parse error (perp meta): {}",
-            e
-        )
-    })?;
-
-    Ok(meta)
+        .map_err(|e| UniverseFetchError::from_rest("perp", e))
 }
 
 /// Fetches spot universe from Hyperliquid REST API
-async fn fetch_spot_meta() -> OrError<SpotMeta> {
-    let client = reqwest::Client::new();
-    let request = SpotMetaRequest {
-        request_type: "spotMeta".to_string(),
-    };
-
-    let response = client
-        .post(HYPERLIQUID_INFO_ENDPOINT)
-        .json(&request)
-        .send()
+async fn fetch_spot_meta(client: &HyperliquidRestClient) -> Result<SpotMeta, UniverseFetchError> {
+    client
+        .spot_meta()
         .await
-        .map_err(|e| anyhow::anyhow!("Hyperliquid REST API request error (spot meta): {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Hyperliquid REST API error (spot meta): HTTP {}",
-            response.status()
-        ));
-    }
-
-    let meta: SpotMeta = response.json().await.map_err(|e| {
-        anyhow::anyhow!(
-            "Hyperliquid REST API response parse error (spot meta): {}",
-            e
-        )
-    })?;
-
-    Ok(meta)
+        .map_err(|e| UniverseFetchError::from_rest("spot", e))
 }
 
 /// Sanitizes a symbol string to contain only alphanumeric characters and hyphens
@@ -243,6 +194,145 @@ fn extract_active_spot_symbols(
     Ok(symbols)
 }
 
+/// Restricts the symbols `UniverseManager` exposes; applied after every fetch, to perps and spot separately.
+/// Symbols are normalized names (e.g. "BTC_PERP", "PURR-USDC"), so one filter can name both markets.
+/// The default keeps the whole universe.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UniverseFilter {
+    /// Keep only these symbols, in this order; `None` keeps every listed symbol
+    pub allowlist: Option<Vec<TradingSymbol>>,
+    /// Always dropped, even if allowlisted
+    pub denylist: Vec<TradingSymbol>,
+    /// Caps each market: the first allowlisted symbols still listed, or without an allowlist the first by name
+    pub max_symbols: Option<usize>,
+}
+
+impl UniverseFilter {
+    /// Filters one market's freshly fetched symbols. Deterministic: the same listing always gives the same result.
+    pub fn apply(&self, symbols: Vec<TradingSymbol>) -> Vec<TradingSymbol> {
+        let mut kept = match &self.allowlist {
+            Some(allowlist) => {
+                let listed: HashSet<&TradingSymbol> = symbols.iter().collect();
+                let mut seen = HashSet::new();
+                allowlist
+                    .iter()
+                    .filter(|symbol| listed.contains(symbol) && seen.insert(*symbol))
+                    .cloned()
+                    .collect()
+            }
+            None => symbols,
+        };
+        kept.retain(|symbol| !self.denylist.contains(symbol));
+        if let Some(max_symbols) = self.max_symbols {
+            if self.allowlist.is_none() {
+                kept.sort_by_key(|symbol| symbol.to_string());
+            }
+            kept.truncate(max_symbols);
+        }
+        kept
+    }
+
+    /// Allowlisted symbols missing from their market's full (unfiltered) listing; "_PERP" symbols are perps.
+    /// A market passed as `None` (e.g. its fetch failed) is not checked.
+    pub fn unlisted(
+        &self,
+        perps: Option<&[TradingSymbol]>,
+        spot: Option<&[TradingSymbol]>,
+    ) -> Vec<TradingSymbol> {
+        let Some(allowlist) = &self.allowlist else {
+            return Vec::new();
+        };
+        allowlist
+            .iter()
+            .filter(|symbol| {
+                let listed = if symbol.to_string().ends_with("_PERP") {
+                    perps
+                } else {
+                    spot
+                };
+                listed.is_some_and(|listed| !listed.contains(symbol))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Copies the mappings of `symbols` from `extracted` into `symbol_map`, so filtered-out symbols stay untranslatable
+fn insert_mappings(
+    symbol_map: &mut BiMap<TradingSymbol, TradingSymbol>,
+    extracted: &BiMap<TradingSymbol, TradingSymbol>,
+    symbols: &[TradingSymbol],
+) {
+    for normalized in symbols {
+        if let Some(hyperliquid) = extracted.get_by_left(normalized) {
+            symbol_map.insert(normalized.clone(), hyperliquid.clone());
+        }
+    }
+}
+
+/// Startup behavior of `UniverseManager::new`. The default retries `HYPERLIQUID_UNIVERSE_FETCH_RETRIES` times,
+/// starting `HYPERLIQUID_UNIVERSE_RETRY_BACKOFF_MS` apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UniverseManagerConfig {
+    /// Retries of each market's initial fetch after the first attempt fails
+    pub initial_fetch_retries: u32,
+    /// Delay before the first retry; doubles on each further retry
+    pub retry_backoff: Duration,
+}
+
+impl Default for UniverseManagerConfig {
+    fn default() -> Self {
+        Self {
+            initial_fetch_retries: HYPERLIQUID_UNIVERSE_FETCH_RETRIES,
+            retry_backoff: Duration::from_millis(HYPERLIQUID_UNIVERSE_RETRY_BACKOFF_MS),
+        }
+    }
+}
+
+impl UniverseManagerConfig {
+    /// Calls `fetch` until it succeeds or `initial_fetch_retries` retries have failed; returns the last error.
+    /// A rate-limited attempt waits at least the server's `Retry-After` before the next one.
+    async fn fetch_with_retry<M, F, Fut>(
+        &self,
+        market: &str,
+        fetch: F,
+    ) -> Result<M, UniverseFetchError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<M, UniverseFetchError>>,
+    {
+        let mut attempt: u32 = 0;
+        loop {
+            match fetch().await {
+                Ok(meta) => return Ok(meta),
+                Err(e) if attempt >= self.initial_fetch_retries => return Err(e),
+                Err(e) => {
+                    let mut delay = self
+                        .retry_backoff
+                        .saturating_mul(2u32.saturating_pow(attempt));
+                    // Retrying before the server's Retry-After would only be rate limited again
+                    if let UniverseFetchError::RateLimited {
+                        retry_after: Some(retry_after),
+                        ..
+                    } = &e
+                    {
+                        delay = delay.max(*retry_after);
+                    }
+                    attempt += 1;
+                    warn!(
+                        market,
+                        error = %e,
+                        ?delay,
+                        attempt,
+                        "initial universe fetch failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
 /// Manages the universe of Hyperliquid trading symbols
 ///
 /// Automatically polls REST API to keep universe up to date with active (non-delisted) symbols.
@@ -263,86 +353,130 @@ impl UniverseManager {
     ///
     /// # Arguments
     /// * `update_duration` - How often to poll the REST API (e.g., Duration::from_secs(60) for 1 minute)
+    /// * `endpoints` - REST endpoint to poll; `None` uses `HyperliquidEndpoints::mainnet()`
+    /// * `filter` - Symbols to expose, reapplied after every fetch; `None` exposes the whole universe
+    /// * `config` - Retries of the initial fetch; `None` uses `UniverseManagerConfig::default()`
     ///
     /// # Returns
-    /// * `OrError<Self>` - UniverseManager instance or error message
-    pub async fn new(update_duration: Duration) -> OrError<Self> {
+    /// * `OrError<Self>` - UniverseManager instance, or an error if neither perps nor spot could be fetched
+    ///   after all retries (the underlying `UniverseFetchError` is the perp one), or if an allowlisted symbol is not
+    ///   listed by a market that loaded. Later refreshes only log failures.
+    pub async fn new(
+        update_duration: Duration,
+        endpoints: Option<HyperliquidEndpoints>,
+        filter: Option<UniverseFilter>,
+        config: Option<UniverseManagerConfig>,
+    ) -> OrError<Self> {
+        let endpoints = endpoints.unwrap_or_default();
+        // Shared with every other caller of this endpoint in the process, so polling stays within its rate limit
+        let client = HyperliquidRestClient::shared(&endpoints.info_url);
+        let filter = filter.unwrap_or_default();
+        let config = config.unwrap_or_default();
         // Initialize with empty universes
         let perp_universe = Arc::new(RwLock::new(Vec::new()));
         let spot_universe = Arc::new(RwLock::new(Vec::new()));
         let symbol_map = Arc::new(RwLock::new(BiMap::new()));
 
-        // Do initial fetch to populate universes and symbol map
-        match fetch_perp_meta().await {
-            Ok(meta) => {
-                let mut map_write = symbol_map.write().await;
-                match extract_active_perp_symbols(&meta, &mut map_write) {
-                    Ok(symbols) => {
-                        let mut perp_write = perp_universe.write().await;
-                        *perp_write = symbols;
-                        println!(
-                            "Hyperliquid UniverseManager: Initial perp universe loaded ({} symbols)",
-                            perp_write.len()
-                        );
-                    }
-                    Err(e) => {
-                        eprintln!(
-                            "Hyperliquid UniverseManager: Error extracting perp symbols: {}",
-                            e
-                        );
-                    }
-                }
+        // Initial fetch populates universes and symbol map; at least one market must load
+        let mut failures = Vec::new();
+        // Full listings of the markets that loaded, to check the allowlist against
+        let mut perp_listed = None;
+        let mut spot_listed = None;
+        let perp_meta = config
+            .fetch_with_retry("perp", || fetch_perp_meta(&client))
+            .await;
+        match perp_meta.and_then(|meta| {
+            let mut extracted = BiMap::new();
+            extract_active_perp_symbols(&meta, &mut extracted)
+                .map(|symbols| (symbols, extracted))
+                .map_err(|e| UniverseFetchError::Parse {
+                    market: "perp",
+                    reason: e.to_string(),
+                })
+        }) {
+            Ok((symbols, extracted)) => {
+                perp_listed = Some(symbols.clone());
+                let symbols = filter.apply(symbols);
+                insert_mappings(&mut *symbol_map.write().await, &extracted, &symbols);
+                info!(
+                    market = "perp",
+                    symbols = symbols.len(),
+                    "initial universe loaded"
+                );
+                *perp_universe.write().await = symbols;
             }
             Err(e) => {
-                eprintln!(
-                    "Hyperliquid UniverseManager: Initial perp fetch failed: {}",
-                    e
-                );
+                warn!(market = "perp", error = %e, "initial universe fetch failed");
+                failures.push(e);
             }
         }
 
-        match fetch_spot_meta().await {
-            Ok(meta) => {
-                let mut map_write = symbol_map.write().await;
-                match extract_active_spot_symbols(&meta, &mut map_write) {
-                    Ok(symbols) => {
-                        let mut spot_write = spot_universe.write().await;
-                        *spot_write = symbols;
-                        println!(
-                            "Hyperliquid UniverseManager: Initial spot universe loaded ({} symbols)",
-                            spot_write.len()
-                        );
-                    }
-                    Err(e) => {
-                        eprintln!(
-                            "Hyperliquid UniverseManager: Error extracting spot symbols: {}",
-                            e
-                        );
-                    }
-                }
+        let spot_meta = config
+            .fetch_with_retry("spot", || fetch_spot_meta(&client))
+            .await;
+        match spot_meta.and_then(|meta| {
+            let mut extracted = BiMap::new();
+            extract_active_spot_symbols(&meta, &mut extracted)
+                .map(|symbols| (symbols, extracted))
+                .map_err(|e| UniverseFetchError::Parse {
+                    market: "spot",
+                    reason: e.to_string(),
+                })
+        }) {
+            Ok((symbols, extracted)) => {
+                spot_listed = Some(symbols.clone());
+                let symbols = filter.apply(symbols);
+                insert_mappings(&mut *symbol_map.write().await, &extracted, &symbols);
+                info!(
+                    market = "spot",
+                    symbols = symbols.len(),
+                    "initial universe loaded"
+                );
+                *spot_universe.write().await = symbols;
             }
             Err(e) => {
-                eprintln!(
-                    "Hyperliquid UniverseManager: Initial spot fetch failed: {}",
-                    e
-                );
+                warn!(market = "spot", error = %e, "initial universe fetch failed");
+                failures.push(e);
             }
         }
 
+        if failures.len() == 2 {
+            let spot_error = failures.pop().unwrap();
+            let perp_error = failures.pop().unwrap();
+            return Err(anyhow::Error::new(perp_error).context(format!(
+                "UniverseManager error: no market loaded after {} retries each from {} (spot: {})",
+                config.initial_fetch_retries, endpoints.info_url, spot_error
+            )));
+        }
+
+        // A requested symbol that is not listed is most likely a typo; fail rather than silently stream less
+        let unlisted = filter.unlisted(perp_listed.as_deref(), spot_listed.as_deref());
+        if !unlisted.is_empty() {
+            let unlisted: Vec<String> = unlisted.iter().map(|symbol| symbol.to_string()).collect();
+            return Err(anyhow::anyhow!(
+                "UniverseManager error: requested symbols not in the live universe of {}: {}",
+                endpoints.info_url,
+                unlisted.join(", ")
+            ));
+        }
+
         // Spawn background task for perp universe updates
         let perp_universe_clone = perp_universe.clone();
         let symbol_map_clone_perp = symbol_map.clone();
+        let perp_client = client.clone();
+        let perp_filter = filter.clone();
         let perp_update_handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(update_duration);
             loop {
                 interval.tick().await;
 
-                match fetch_perp_meta().await {
+                match fetch_perp_meta(&perp_client).await {
                     Ok(meta) => {
                         // Need to rebuild symbol map for perps
                         let mut temp_map = BiMap::new();
                         match extract_active_perp_symbols(&meta, &mut temp_map) {
                             Ok(symbols) => {
+                                let symbols = perp_filter.apply(symbols);
                                 // Update universe
                                 let mut perp_write = perp_universe_clone.write().await;
                                 let old_count = perp_write.len();
@@ -355,28 +489,23 @@ impl UniverseManager {
                                 map_write.retain(|normalized, _| {
                                     !normalized.to_string().ends_with("_PERP")
                                 });
-                                // Insert new perp mappings
-                                for (normalized, hyperliquid) in temp_map.iter() {
-                                    map_write.insert(normalized.clone(), hyperliquid.clone());
-                                }
+                                // Insert new perp mappings (filtered-out symbols excluded)
+                                insert_mappings(&mut map_write, &temp_map, &perp_write);
 
                                 if old_count != new_count {
-                                    println!(
-                                        "Hyperliquid UniverseManager: Perp universe updated ({} -> {} symbols)",
-                                        old_count, new_count
+                                    info!(
+                                        market = "perp",
+                                        old_count, new_count, "universe updated"
                                     );
                                 }
                             }
                             Err(e) => {
-                                eprintln!(
-                                    "Hyperliquid UniverseManager: Error extracting perp symbols: {}",
-                                    e
-                                );
+                                warn!(market = "perp", error = %e, "failed to extract symbols");
                             }
                         }
                     }
                     Err(e) => {
-                        eprintln!("Hyperliquid UniverseManager: Perp fetch error: {}", e);
+                        warn!(market = "perp", error = %e, "universe fetch failed");
                     }
                 }
             }
@@ -385,17 +514,20 @@ impl UniverseManager {
         // Spawn background task for spot universe updates
         let spot_universe_clone = spot_universe.clone();
         let symbol_map_clone_spot = symbol_map.clone();
+        let spot_client = client;
+        let spot_filter = filter.clone();
         let spot_update_handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(update_duration);
             loop {
                 interval.tick().await;
 
-                match fetch_spot_meta().await {
+                match fetch_spot_meta(&spot_client).await {
                     Ok(meta) => {
                         // Need to rebuild symbol map for spots
                         let mut temp_map = BiMap::new();
                         match extract_active_spot_symbols(&meta, &mut temp_map) {
                             Ok(symbols) => {
+                                let symbols = spot_filter.apply(symbols);
                                 // Update universe
                                 let mut spot_write = spot_universe_clone.write().await;
                                 let old_count = spot_write.len();
@@ -408,28 +540,23 @@ impl UniverseManager {
                                 map_write.retain(|normalized, _| {
                                     normalized.to_string().ends_with("_PERP")
                                 });
-                                // Insert new spot mappings
-                                for (normalized, hyperliquid) in temp_map.iter() {
-                                    map_write.insert(normalized.clone(), hyperliquid.clone());
-                                }
+                                // Insert new spot mappings (filtered-out symbols excluded)
+                                insert_mappings(&mut map_write, &temp_map, &spot_write);
 
                                 if old_count != new_count {
-                                    println!(
-                                        "Hyperliquid UniverseManager: Spot universe updated ({} -> {} symbols)",
-                                        old_count, new_count
+                                    info!(
+                                        market = "spot",
+                                        old_count, new_count, "universe updated"
                                     );
                                 }
                             }
                             Err(e) => {
-                                eprintln!(
-                                    "Hyperliquid UniverseManager: Error extracting spot symbols: {}",
-                                    e
-                                );
+                                warn!(market = "spot", error = %e, "failed to extract symbols");
                             }
                         }
                     }
                     Err(e) => {
-                        eprintln!("Hyperliquid UniverseManager: Spot fetch error: {}", e);
+                        warn!(market = "spot", error = %e, "universe fetch failed");
                     }
                 }
             }
@@ -450,7 +577,6 @@ impl UniverseManager {
     /// * `OrError<Vec<TradingSymbol>>` - List of normalized perpetual symbols (e.g., "BTC_PERP", "ETH_PERP")
     pub async fn perp_universe(&self) -> OrError<Vec<TradingSymbol>> {
         let perp_read = self.perp_universe.read().await;
-        // Ok(perp_read.clone()[0..120].to_vec())
         Ok(perp_read.clone())
     }
 
@@ -1,39 +1,231 @@
-use super::HyperliquidStreamable;
 use super::{BboUpdate, OrderbookSnapshot, PerpAssetContext, SpotAssetContext, TradeUpdate};
-use crate::constants::HYPERLIQUID_WEBSTREAM_ENDPOINT;
+use super::{HyperliquidEndpoints, HyperliquidStreamable};
+use crate::constants::{
+    HYPERLIQUID_RECONNECT_BASE_MS, HYPERLIQUID_RECONNECT_CAP_MS,
+    HYPERLIQUID_RECONNECT_MIN_STABLE_UPTIME_SECONDS, HYPERLIQUID_SUBSCRIPTION_GRACE_PERIOD_SECONDS,
+    HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS, HYPERLIQUID_WEBSTREAM_SHUTDOWN_TIMEOUT_MS,
+};
+use crate::crypto::{SequenceMode, SequenceTracker, WebstreamConfig, connect_websocket};
+use crate::metrics::{self, Counter};
+use crate::supervised::spawn_supervised;
 use crate::types::TradingSymbol;
 use agora::utils::OrError;
-use agora::{AgorableOption, ConnectionHandle, Publisher};
+use agora::{AgorableOption, ConnectionHandle, PublisherGroup, PublisherSpec};
 use bimap::BiMap;
+use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{Instrument, debug, error, info, info_span, warn};
 
+/// Exponential reconnect backoff with full jitter.
+/// Attempt `n` sleeps `U(0, min(cap, base * 2^n))`, so workers dropped at the same time
+/// (e.g. exchange maintenance) spread their reconnects instead of retrying in lockstep.
+/// The attempt counter resets once a connection stays up for `min_stable_uptime`.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectBackoff {
+    pub base: Duration,
+    pub cap: Duration,
+    pub min_stable_uptime: Duration,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(HYPERLIQUID_RECONNECT_BASE_MS),
+            cap: Duration::from_millis(HYPERLIQUID_RECONNECT_CAP_MS),
+            min_stable_uptime: Duration::from_secs(HYPERLIQUID_RECONNECT_MIN_STABLE_UPTIME_SECONDS),
+        }
+    }
+}
+
+impl ReconnectBackoff {
+    /// Jittered delay before reconnect attempt number `attempt` (0-indexed)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .base
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.cap);
+        let ceiling_ms = ceiling.as_millis().min(u64::MAX as u128) as u64;
+        Duration::from_millis(rand::random_range(0..=ceiling_ms))
+    }
+}
+
+/// Per-connection bookkeeping of subscriptions sent vs. acknowledged by `subscriptionResponse`.
+/// Reset on every (re)connect; coins still pending after the grace period never produced an ack
+/// (typo'd, delisted, or rejected symbols) and will not produce data.
+#[derive(Debug, Default)]
+struct SubscriptionTracker {
+    pending: HashSet<String>,
+    sent_at: Option<Instant>,
+}
+
+impl SubscriptionTracker {
+    fn reset(&mut self, coins: &[String]) {
+        self.pending = coins.iter().cloned().collect();
+        self.sent_at = Some(Instant::now());
+    }
+
+    /// Marks the coin of a `subscriptionResponse` payload as confirmed.
+    /// Payload: {"method": "subscribe", "subscription": {"type": ..., "coin": ...}}
+    fn acknowledge(&mut self, data: &serde_json::Value) {
+        if let Some(coin) = data["subscription"]["coin"].as_str() {
+            self.pending.remove(coin);
+        }
+    }
+
+    fn unconfirmed(&self, grace_period: Duration) -> Vec<TradingSymbol> {
+        match self.sent_at {
+            Some(sent_at) if sent_at.elapsed() >= grace_period => {
+                let mut coins: Vec<&String> = self.pending.iter().collect();
+                coins.sort();
+                coins
+                    .into_iter()
+                    .filter_map(|coin| TradingSymbol::from_str(coin).ok())
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Totals of one worker since it started, read with `HyperliquidWebstreamWorker::stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WorkerStats {
+    /// Text frames received over every connection, acknowledgements and pongs included
+    pub messages_received: u64,
+    /// Items published to agora
+    pub messages_published: u64,
+    pub publish_errors: u64,
+    /// Frames that were not JSON, lacked data, or whose data `T::of_channel_data` rejected
+    pub parse_errors: u64,
+    /// Dropped or failed connections that were retried
+    pub reconnects: u64,
+    /// Receive time of the latest text frame; `None` before the first
+    pub last_message_at: Option<DateTime<Utc>>,
+}
+
+impl WorkerStats {
+    /// Adds the counts of `other` and keeps the later `last_message_at`, e.g. to total the workers of a bundle
+    pub fn merge(&mut self, other: WorkerStats) {
+        self.messages_received += other.messages_received;
+        self.messages_published += other.messages_published;
+        self.publish_errors += other.publish_errors;
+        self.parse_errors += other.parse_errors;
+        self.reconnects += other.reconnects;
+        self.last_message_at = self.last_message_at.max(other.last_message_at);
+    }
+}
+
+/// Counters behind `WorkerStats`, shared with the dispatch loop. Relaxed atomics, so `stats()` never
+/// contends with the loop; counts read together may be a message apart.
+#[derive(Debug, Default)]
+struct WorkerCounters {
+    messages_received: AtomicU64,
+    messages_published: AtomicU64,
+    publish_errors: AtomicU64,
+    parse_errors: AtomicU64,
+    reconnects: AtomicU64,
+    /// Epoch milliseconds of the latest text frame; 0 before the first
+    last_message_at_ms: AtomicI64,
+}
+
+impl WorkerCounters {
+    fn snapshot(&self) -> WorkerStats {
+        let last_message_at_ms = self.last_message_at_ms.load(Ordering::Relaxed);
+        WorkerStats {
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            messages_published: self.messages_published.load(Ordering::Relaxed),
+            publish_errors: self.publish_errors.load(Ordering::Relaxed),
+            parse_errors: self.parse_errors.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            last_message_at: (last_message_at_ms != 0)
+                .then(|| DateTime::from_timestamp_millis(last_message_at_ms))
+                .flatten(),
+        }
+    }
+}
+
+/// `{"method": "subscribe" | "unsubscribe", "subscription": {"type": ..., "coin": ...}}`
+fn subscription_message(method: &str, subscription_type: &str, coin: &str) -> Message {
+    let message = serde_json::json!({
+        "method": method,
+        "subscription": {
+            "type": subscription_type,
+            "coin": coin
+        }
+    });
+    Message::Text(message.to_string().into())
+}
+
+/// Market label for metrics: last segment of the worker prefix without its version,
+/// e.g. `argus/tmp/hyperliquid/perp_3` → `perp`
+fn market_label(agora_prefix: &str) -> &str {
+    let segment = agora_prefix.rsplit('/').next().unwrap_or(agora_prefix);
+    match segment.rsplit_once('_') {
+        Some((market, version)) if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) => {
+            market
+        }
+        _ => segment,
+    }
+}
+
+/// Streams one Hyperliquid channel for a set of coins and publishes each coin to its own agora path.
+///
+/// Stopping: `shutdown()` unsubscribes every coin and closes the socket, waiting at most
+/// `HYPERLIQUID_WEBSTREAM_SHUTDOWN_TIMEOUT_MS`. Dropping the worker starts the same close in the background
+/// and aborts the task after that timeout, so retired workers do not leave subscriptions to time out server-side.
 pub struct HyperliquidWebstreamWorker<T: HyperliquidStreamable> {
     agora_paths: Vec<String>,
+    subscriptions: Arc<Mutex<SubscriptionTracker>>,
+    counters: Arc<WorkerCounters>,
+    stop_tx: watch::Sender<bool>,
     dispatch_handle: JoinHandle<()>,
     _phantom: PhantomData<T>,
 }
 
 impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
+    /// `reconnect_backoff`: retry policy after a dropped or failed connection;
+    /// `None` falls back to `ReconnectBackoff::default()`.
+    /// `max_levels`: book depth kept per side (see `HyperliquidStreamable::truncate_levels`); `None` keeps all levels.
+    /// `sequence_mode`: log and count gaps in `HyperliquidStreamable::sequence` per symbol; `None` disables tracking.
+    /// `endpoints`: WebSocket to stream from; `None` uses `HyperliquidEndpoints::mainnet()`.
+    /// `webstream_config`: connect timeout, proxy and TLS (see `WebstreamConfig`); `None` connects directly.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         symbols: &[TradingSymbol],
         agora_prefix: &str,
         metaserver_connection: ConnectionHandle,
         local_gateway_port: u16,
         symbol_mapper: BiMap<TradingSymbol, TradingSymbol>,
+        reconnect_backoff: Option<ReconnectBackoff>,
+        max_levels: Option<usize>,
+        sequence_mode: Option<SequenceMode>,
+        endpoints: Option<HyperliquidEndpoints>,
+        webstream_config: Option<WebstreamConfig>,
     ) -> OrError<Self> {
+        if max_levels == Some(0) {
+            return Err(anyhow::anyhow!(
+                "HyperliquidWebstreamWorker error: max_levels must be at least 1"
+            ));
+        }
         if symbols.is_empty() {
             return Err(anyhow::anyhow!(
                 "HyperliquidWebstreamWorker error: symbols list cannot be empty"
             ));
         }
-        if symbols.len() > 1024 {
+        if symbols.len() > HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS {
             return Err(anyhow::anyhow!(
-                "HyperliquidWebstreamWorker error: don't pass in more than 1024 tasks per worker"
+                "HyperliquidWebstreamWorker error: don't pass in more than {} tasks per worker",
+                HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS
             ));
         }
 
@@ -42,9 +234,9 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
             if let Some(normalized) = symbol_mapper.get_by_right(hyperliquid_symbol) {
                 normalized_symbols.push(normalized.clone());
             } else {
-                eprintln!(
-                    "HyperliquidWebstreamWorker: Warning - no mapping found for Hyperliquid symbol {}",
-                    hyperliquid_symbol.to_string()
+                warn!(
+                    symbol = %hyperliquid_symbol.as_str(),
+                    "no normalized mapping for Hyperliquid symbol, publishing under its raw name"
                 );
                 normalized_symbols.push(hyperliquid_symbol.clone());
             }
@@ -61,64 +253,86 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
             })
             .collect();
 
-        // Create publishers for each normalized symbol
-        let mut publishers: Vec<Publisher<AgorableOption<T>>> = Vec::new();
-        for (normalized_symbol, agora_path) in normalized_symbols.iter().zip(agora_paths.iter()) {
-            let publisher_name = normalized_symbol.to_string();
-            let publisher = Publisher::<AgorableOption<T>>::new(
-                publisher_name,
-                agora_path.clone(),
-                AgorableOption(None),
-                metaserver_connection,
-                local_gateway_port,
-            )
-            .await?;
-            publishers.push(publisher);
-        }
+        // Create publishers for each normalized symbol, all-or-nothing so a failed init leaves no partial paths
+        let specs = normalized_symbols
+            .iter()
+            .zip(agora_paths.iter())
+            .map(|(normalized_symbol, agora_path)| PublisherSpec {
+                name: normalized_symbol.to_string(),
+                path: agora_path.clone(),
+                initial_value: AgorableOption(None),
+            })
+            .collect();
+        let mut publishers: PublisherGroup<AgorableOption<T>> =
+            PublisherGroup::register_all(specs, metaserver_connection, local_gateway_port).await?;
 
         // Build normalized_symbol->publisher mapping for message dispatch
-        let symbol_to_publisher: HashMap<String, usize> = normalized_symbols
+        let symbol_to_publisher: HashMap<TradingSymbol, usize> = normalized_symbols
             .iter()
             .enumerate()
-            .map(|(idx, normalized_symbol)| (normalized_symbol.to_string(), idx))
+            .map(|(idx, normalized_symbol)| (normalized_symbol.clone(), idx))
+            .collect();
+
+        // Per-publisher counters, indexed like `symbol_to_publisher`
+        let market = market_label(agora_prefix);
+        let data_type = T::payload_identifier();
+        let published: Vec<Counter> = normalized_symbols
+            .iter()
+            .map(|symbol| metrics::messages_published(market, &data_type, &symbol.to_string()))
+            .collect();
+        let publish_errors: Vec<Counter> = normalized_symbols
+            .iter()
+            .map(|symbol| metrics::publish_errors(market, &data_type, &symbol.to_string()))
+            .collect();
+        let sequence_gaps: Vec<Counter> = normalized_symbols
+            .iter()
+            .map(|symbol| metrics::sequence_gaps(market, &data_type, &symbol.to_string()))
             .collect();
+        let reconnects = metrics::websocket_reconnects(market, &data_type);
+        let mut sequence_tracker = sequence_mode.map(SequenceTracker::new);
 
-        let ws_url = HYPERLIQUID_WEBSTREAM_ENDPOINT.to_string();
+        let ws_url = endpoints.unwrap_or_default().ws_url;
+        let webstream_config = webstream_config.unwrap_or_default();
         let subscription_type = T::subscription_type();
         let coins: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
 
-        println!("Connecting to Hyperliquid WebSocket: {}", ws_url);
-        println!(
-            "Subscribing to {} for coins: {:?}",
-            subscription_type, coins
-        );
-        let agora_prefix_clone = agora_prefix.to_string();
-        let worker_task = tokio::spawn(async move {
+        let span = info_span!("webstream", prefix = agora_prefix, channel = %subscription_type);
+        info!(parent: &span, url = %ws_url, ?coins, "connecting to Hyperliquid WebSocket");
+        let backoff = reconnect_backoff.unwrap_or_default();
+        let subscriptions = Arc::new(Mutex::new(SubscriptionTracker::default()));
+        let subscriptions_clone = subscriptions.clone();
+        let counters = Arc::new(WorkerCounters::default());
+        let counters_clone = counters.clone();
+        let grace_period = Duration::from_secs(HYPERLIQUID_SUBSCRIPTION_GRACE_PERIOD_SECONDS);
+        let (stop_tx, mut stop_rx) = watch::channel(false);
+        let worker = async move {
+            // Consecutive failed (or short-lived) connections; drives the backoff exponent
+            let mut attempt: u32 = 0;
             loop {
-                match connect_async(&ws_url).await {
-                    Ok((ws_stream, _)) => {
+                let connection = tokio::select! {
+                    connection = connect_websocket(&ws_url, &webstream_config) => connection,
+                    _ = stop_rx.changed() => return,
+                };
+                match connection {
+                    Ok(ws_stream) => {
+                        let connected_at = Instant::now();
                         let (mut write, mut read) = ws_stream.split();
+                        subscriptions_clone.lock().unwrap().reset(&coins);
+                        // Messages missed while disconnected are expected; track gaps within a connection
+                        if let Some(tracker) = sequence_tracker.as_mut() {
+                            tracker.reset();
+                        }
 
                         for coin in &coins {
-                            let subscription = serde_json::json!({
-                                "method": "subscribe",
-                                "subscription": {
-                                    "type": subscription_type,
-                                    "coin": coin
-                                }
-                            });
                             if let Err(e) = write
-                                .send(Message::Text(subscription.to_string().into()))
+                                .send(subscription_message("subscribe", &subscription_type, coin))
                                 .await
                             {
-                                eprintln!(
-                                    "HyperliquidWebstreamWorker error sending subscription for {}: {}",
-                                    coin, e
-                                );
+                                error!(coin = %coin, error = %e, "failed to send subscription");
                                 break;
                             }
                         }
-                        println!("Sent {} subscriptions", coins.len());
+                        info!(count = coins.len(), "sent subscriptions");
 
                         let (ping_tx, mut ping_rx) = tokio::sync::mpsc::channel::<Message>(10);
                         let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
@@ -130,7 +344,7 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
                                 tokio::select! {
                                     _ = interval.tick() => {
                                         let ping_msg = serde_json::json!({"method": "ping"});
-                                        if let Err(_) = ping_tx.send(Message::Text(ping_msg.to_string().into())).await {
+                                        if ping_tx.send(Message::Text(ping_msg.to_string().into())).await.is_err() {
                                             break;
                                         }
                                     }
@@ -141,21 +355,65 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
                             }
                         });
 
+                        // One-shot check for subscriptions never acknowledged by the server
+                        let grace_timer = tokio::time::sleep(grace_period);
+                        tokio::pin!(grace_timer);
+                        let mut grace_checked = false;
+
                         // Process incoming messages and heartbeat pings
+                        let mut stopping = false;
                         loop {
                             tokio::select! {
+                                _ = stop_rx.changed() => {
+                                    stopping = true;
+                                    // Unsubscribe and close; the caller bounds how long this may take
+                                    for coin in &coins {
+                                        if let Err(e) = write
+                                            .send(subscription_message("unsubscribe", &subscription_type, coin))
+                                            .await
+                                        {
+                                            warn!(coin = %coin, error = %e, "failed to send unsubscribe");
+                                            break;
+                                        }
+                                    }
+                                    if write.send(Message::Close(None)).await.is_ok() {
+                                        // Wait for the server's Close so the socket shuts down cleanly
+                                        while let Some(Ok(message)) = read.next().await {
+                                            if matches!(message, Message::Close(_)) {
+                                                break;
+                                            }
+                                        }
+                                    }
+                                    info!(count = coins.len(), "unsubscribed and closed");
+                                    break;
+                                }
+                                _ = &mut grace_timer, if !grace_checked => {
+                                    grace_checked = true;
+                                    let unconfirmed = subscriptions_clone
+                                        .lock()
+                                        .unwrap()
+                                        .unconfirmed(grace_period);
+                                    if !unconfirmed.is_empty() {
+                                        warn!(
+                                            ?grace_period,
+                                            unconfirmed = ?unconfirmed.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+                                            "subscriptions unconfirmed after grace period"
+                                        );
+                                    }
+                                }
                                 message = read.next() => {
                                     match message {
                                         Some(Ok(Message::Ping(ping_data))) => {
                                             if let Err(e) = write.send(Message::Pong(ping_data)).await {
-                                                eprintln!(
-                                                    "HyperliquidWebstreamWorker error sending Pong: {}",
-                                                    e
-                                                );
+                                                error!(error = %e, "failed to send pong");
                                                 break;
                                             }
                                         }
                                         Some(Ok(Message::Text(text))) => {
+                                    counters_clone.messages_received.fetch_add(1, Ordering::Relaxed);
+                                    counters_clone
+                                        .last_message_at_ms
+                                        .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
                                     #[derive(Deserialize)]
                                     struct ChannelMessage {
                                         channel: String,
@@ -165,6 +423,16 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
                                     match serde_json::from_str::<ChannelMessage>(&text) {
                                         Ok(msg) => {
                                             if msg.channel == "subscriptionResponse" {
+                                                if let Some(data) = &msg.data {
+                                                    subscriptions_clone.lock().unwrap().acknowledge(data);
+                                                }
+                                                continue;
+                                            }
+                                            if msg.channel == "error" {
+                                                error!(
+                                                    data = %msg.data.unwrap_or_default(),
+                                                    "server error"
+                                                );
                                                 continue;
                                             }
 
@@ -173,69 +441,85 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
                                             }
                                             if msg.channel != subscription_type {
                                                 if msg.channel == "subscriptionResponse" {
-                                                    println!("Diverted for channel {:?}", msg.channel);
+                                                    debug!(channel = %msg.channel, "diverted message");
                                                 }
                                                 continue;
                                             }
                                             let Some(data) = msg.data else {
-                                                eprintln!("HyperliquidWebstreamWorker: message missing data field");
+                                                counters_clone.parse_errors.fetch_add(1, Ordering::Relaxed);
+                                                warn!("message missing data field");
                                                 continue;
                                             };
                                             match T::of_channel_data(data, &symbol_mapper) {
                                                 Ok(parsed_items) => {
-                                                    for item in parsed_items {
-                                                        let normalized_symbol = item.symbol().to_string();
+                                                    for mut item in parsed_items {
+                                                        if let Some(max_levels) = max_levels {
+                                                            item.truncate_levels(max_levels);
+                                                        }
+                                                        let normalized_symbol = item.symbol();
 
                                                         if let Some(&publisher_idx) =
                                                             symbol_to_publisher.get(&normalized_symbol)
                                                         {
+                                                            if let (Some(tracker), Some(sequence)) =
+                                                                (sequence_tracker.as_mut(), item.sequence())
+                                                                && let Some(gap) = tracker.observe(&normalized_symbol, sequence)
+                                                            {
+                                                                sequence_gaps[publisher_idx].inc();
+                                                                warn!(%gap, "sequence gap");
+                                                            }
                                                             if let Some(publisher) = publishers
                                                                 .get_mut(publisher_idx)
                                                             {
-                                                                if let Err(e) = publisher
+                                                                match publisher
                                                                     .publish(AgorableOption(Some(item)))
                                                                     .await
                                                                 {
-                                                                    eprintln!(
-                                                                        "HyperliquidWebstreamWorker publish error for {}: {}",
-                                                                        normalized_symbol, e
-                                                                    );
+                                                                    Ok(()) => {
+                                                                        published[publisher_idx].inc();
+                                                                        counters_clone
+                                                                            .messages_published
+                                                                            .fetch_add(1, Ordering::Relaxed);
+                                                                    }
+                                                                    Err(e) => {
+                                                                        publish_errors[publisher_idx].inc();
+                                                                        counters_clone
+                                                                            .publish_errors
+                                                                            .fetch_add(1, Ordering::Relaxed);
+                                                                        warn!(
+                                                                            symbol = normalized_symbol.as_str(),
+                                                                            error = %e,
+                                                                            "publish error"
+                                                                        );
+                                                                    }
                                                                 }
                                                             }
                                                         }
                                                     }
                                                 }
                                                 Err(e) => {
-                                                    eprintln!(
-                                                        "HyperliquidWebstreamWorker parse error: {}",
-                                                        e
-                                                    );
+                                                    counters_clone.parse_errors.fetch_add(1, Ordering::Relaxed);
+                                                    warn!(error = %e, "parse error");
                                                 }
                                             }
                                         }
                                         Err(e) => {
-                                            eprintln!(
-                                                "HyperliquidWebstreamWorker {} JSON parse error for {}: {}",
-                                                agora_prefix_clone,
-                                                text,
-                                                e
-                                            );
+                                            counters_clone.parse_errors.fetch_add(1, Ordering::Relaxed);
+                                            warn!(%text, error = %e, "JSON parse error");
                                         }
                                     }
                                         }
                                         Some(Ok(Message::Close(_))) => {
-                                            eprintln!(
-                                                "HyperliquidWebstreamWorker: received Close frame, disconnecting"
-                                            );
+                                            warn!("received Close frame, disconnecting");
                                             break;
                                         }
                                         Some(Ok(_)) => {}
                                         Some(Err(e)) => {
-                                            eprintln!("HyperliquidWebstreamWorker websocket error: {}", e);
+                                            warn!(error = %e, "websocket error");
                                             break;
                                         }
                                         None => {
-                                            eprintln!("HyperliquidWebstreamWorker: connection closed");
+                                            warn!("connection closed");
                                             break;
                                         }
                                     }
@@ -243,7 +527,7 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
                                 ping_msg = ping_rx.recv() => {
                                     if let Some(msg) = ping_msg {
                                         if let Err(e) = write.send(msg).await {
-                                            eprintln!("HyperliquidWebstreamWorker error sending heartbeat ping: {}", e);
+                                            error!(error = %e, "failed to send heartbeat ping");
                                             break;
                                         }
                                     } else {
@@ -254,165 +538,398 @@ impl<T: HyperliquidStreamable> HyperliquidWebstreamWorker<T> {
                         }
                         let _ = shutdown_tx.send(()).await;
                         heartbeat_task.abort();
+                        if stopping {
+                            return;
+                        }
+                        if connected_at.elapsed() >= backoff.min_stable_uptime {
+                            attempt = 0;
+                        }
                     }
                     Err(e) => {
-                        eprintln!(
-                            "HyperliquidWebstreamWorker connection error: {}, retrying in 5s...",
-                            e
-                        );
+                        warn!(error = %e, "connection error");
                     }
                 }
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                reconnects.inc();
+                counters_clone.reconnects.fetch_add(1, Ordering::Relaxed);
+                let delay = backoff.delay(attempt);
+                attempt = attempt.saturating_add(1);
+                warn!(?delay, attempt, "reconnecting");
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = stop_rx.changed() => return,
+                }
             }
-        });
+        }
+        .instrument(span);
+        let worker_task = spawn_supervised("hyperliquid_webstream", worker, |_| {});
 
         Ok(Self {
             agora_paths,
+            subscriptions,
+            counters,
+            stop_tx,
             dispatch_handle: worker_task,
             _phantom: PhantomData,
         })
     }
 
+    /// Unsubscribes every coin and closes the WebSocket, then stops the worker.
+    /// Waits at most `HYPERLIQUID_WEBSTREAM_SHUTDOWN_TIMEOUT_MS`; a close still in flight after that is aborted.
+    pub async fn shutdown(mut self) {
+        let _ = self.stop_tx.send(true);
+        let timeout = Duration::from_millis(HYPERLIQUID_WEBSTREAM_SHUTDOWN_TIMEOUT_MS);
+        if tokio::time::timeout(timeout, &mut self.dispatch_handle)
+            .await
+            .is_err()
+        {
+            warn!(?timeout, "webstream did not close in time, aborting");
+            self.dispatch_handle.abort();
+        }
+    }
+
     /// Returns the agora paths for all publishers managed by this worker
     pub fn agora_paths(&self) -> &[String] {
         &self.agora_paths
     }
+
+    /// Hyperliquid symbols whose subscription on the current connection was not acknowledged
+    /// within the grace period. Empty while the grace period is still running.
+    pub fn unconfirmed_subscriptions(&self) -> Vec<TradingSymbol> {
+        let grace_period = Duration::from_secs(HYPERLIQUID_SUBSCRIPTION_GRACE_PERIOD_SECONDS);
+        self.subscriptions.lock().unwrap().unconfirmed(grace_period)
+    }
+
+    /// Message, error and reconnect totals since the worker started. Lock-free: safe to poll from exporters.
+    pub fn stats(&self) -> WorkerStats {
+        self.counters.snapshot()
+    }
 }
 
 impl<T: HyperliquidStreamable> Drop for HyperliquidWebstreamWorker<T> {
+    /// Drop cannot wait: signal the close and abort whatever is left of it after the shutdown timeout.
+    /// Outside a runtime (nothing could drive the close) the task is aborted right away.
     fn drop(&mut self) {
-        self.dispatch_handle.abort()
+        let _ = self.stop_tx.send(true);
+        if self.dispatch_handle.is_finished() {
+            return;
+        }
+        let handle = self.dispatch_handle.abort_handle();
+        let timeout = Duration::from_millis(HYPERLIQUID_WEBSTREAM_SHUTDOWN_TIMEOUT_MS);
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(async move {
+                    tokio::time::sleep(timeout).await;
+                    handle.abort();
+                });
+            }
+            Err(_) => handle.abort(),
+        }
+    }
+}
+
+/// Splits `symbols` into consecutive shards of at most `symbols_per_connection` symbols (default
+/// `HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS`), each streamed over connections of its own by the webstream bundles.
+/// Error: `symbols_per_connection` is 0 or above `HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS`
+pub fn shard_symbols(
+    symbols: &[TradingSymbol],
+    symbols_per_connection: Option<usize>,
+) -> OrError<Vec<Vec<TradingSymbol>>> {
+    let symbols_per_connection =
+        symbols_per_connection.unwrap_or(HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS);
+    if symbols_per_connection == 0 || symbols_per_connection > HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS {
+        return Err(anyhow::anyhow!(
+            "HyperliquidWebstreamSymbols error: symbols_per_connection must be between 1 and {}, got {}",
+            HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS,
+            symbols_per_connection
+        ));
+    }
+    Ok(symbols
+        .chunks(symbols_per_connection)
+        .map(|shard| shard.to_vec())
+        .collect())
+}
+
+/// One `HyperliquidWebstreamWorker<T>` per shard, so each shard streams `T` over its own connection
+#[allow(clippy::too_many_arguments)]
+async fn spawn_shard_workers<T: HyperliquidStreamable>(
+    shards: &[Vec<TradingSymbol>],
+    agora_prefix: &str,
+    metaserver_connection: &ConnectionHandle,
+    local_gateway_port: u16,
+    symbol_mapper: &BiMap<TradingSymbol, TradingSymbol>,
+    max_levels: Option<usize>,
+    sequence_mode: Option<SequenceMode>,
+    endpoints: &Option<HyperliquidEndpoints>,
+    webstream_config: &Option<WebstreamConfig>,
+) -> OrError<Vec<HyperliquidWebstreamWorker<T>>> {
+    let mut workers = Vec::with_capacity(shards.len());
+    for shard in shards {
+        workers.push(
+            HyperliquidWebstreamWorker::<T>::new(
+                shard,
+                agora_prefix,
+                *metaserver_connection,
+                local_gateway_port,
+                symbol_mapper.clone(),
+                None,
+                max_levels,
+                sequence_mode,
+                endpoints.clone(),
+                webstream_config.clone(),
+            )
+            .await?,
+        );
+    }
+    Ok(workers)
+}
+
+/// `WorkerStats` of all shards' workers of one data type, merged
+fn merged_stats<T: HyperliquidStreamable>(
+    workers: &[HyperliquidWebstreamWorker<T>],
+) -> WorkerStats {
+    let mut stats = WorkerStats::default();
+    for worker in workers {
+        stats.merge(worker.stats());
     }
+    stats
 }
 
+/// Trades, BBO, orderbooks and asset contexts of a perp universe. The universe is sharded (see `shard_symbols`) and
+/// every shard gets one worker per data type, so a misbehaving symbol or connection only affects its shard and no
+/// connection carries more than `symbols_per_connection` symbols.
 pub struct HyperliquidPerpWebstreamSymbols {
     symbols: Vec<TradingSymbol>,
-    _trade_worker: HyperliquidWebstreamWorker<TradeUpdate>,
-    _bbo_worker: HyperliquidWebstreamWorker<BboUpdate>,
-    _orderbook_worker: HyperliquidWebstreamWorker<OrderbookSnapshot>,
-    _context_worker: HyperliquidWebstreamWorker<PerpAssetContext>,
+    shard_count: usize,
+    trade_workers: Vec<HyperliquidWebstreamWorker<TradeUpdate>>,
+    bbo_workers: Vec<HyperliquidWebstreamWorker<BboUpdate>>,
+    orderbook_workers: Vec<HyperliquidWebstreamWorker<OrderbookSnapshot>>,
+    context_workers: Vec<HyperliquidWebstreamWorker<PerpAssetContext>>,
 }
 
 impl HyperliquidPerpWebstreamSymbols {
+    /// `symbols_per_connection`: shard size (see `shard_symbols`); `None` packs up to `HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS`
+    /// symbols per connection.
+    /// `webstream_config`: how every worker connects (see `WebstreamConfig`); `None` connects directly.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         symbols: &[TradingSymbol],
         agora_prefix: &str,
         metaserver_connection: ConnectionHandle,
         local_gateway_port: u16,
         symbol_mapper: BiMap<TradingSymbol, TradingSymbol>,
+        orderbook_max_levels: Option<usize>,
+        trade_sequence_mode: Option<SequenceMode>,
+        endpoints: Option<HyperliquidEndpoints>,
+        symbols_per_connection: Option<usize>,
+        webstream_config: Option<WebstreamConfig>,
     ) -> OrError<Self> {
-        let trade_worker = HyperliquidWebstreamWorker::<TradeUpdate>::new(
-            symbols,
+        let shards = shard_symbols(symbols, symbols_per_connection)?;
+        let trade_workers = spawn_shard_workers::<TradeUpdate>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper.clone(),
+            &symbol_mapper,
+            None,
+            trade_sequence_mode,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
-
-        let bbo_worker = HyperliquidWebstreamWorker::<BboUpdate>::new(
-            symbols,
+        let bbo_workers = spawn_shard_workers::<BboUpdate>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper.clone(),
+            &symbol_mapper,
+            None,
+            None,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
-
-        let orderbook_worker = HyperliquidWebstreamWorker::<OrderbookSnapshot>::new(
-            symbols,
+        let orderbook_workers = spawn_shard_workers::<OrderbookSnapshot>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper.clone(),
+            &symbol_mapper,
+            orderbook_max_levels,
+            None,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
-
-        let context_worker = HyperliquidWebstreamWorker::<PerpAssetContext>::new(
-            symbols,
+        let context_workers = spawn_shard_workers::<PerpAssetContext>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper,
+            &symbol_mapper,
+            None,
+            None,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
 
         Ok(Self {
             symbols: symbols.to_vec(),
-            _trade_worker: trade_worker,
-            _bbo_worker: bbo_worker,
-            _orderbook_worker: orderbook_worker,
-            _context_worker: context_worker,
+            shard_count: shards.len(),
+            trade_workers,
+            bbo_workers,
+            orderbook_workers,
+            context_workers,
         })
     }
 
     pub fn symbols(&self) -> &[TradingSymbol] {
         &self.symbols
     }
+
+    /// Shards the universe was split into; each has one connection per data type
+    pub fn shard_count(&self) -> usize {
+        self.shard_count
+    }
+
+    /// `WorkerStats` per data type (keyed by `payload_identifier()`), summed over shards
+    pub fn stats(&self) -> HashMap<String, WorkerStats> {
+        HashMap::from([
+            (
+                TradeUpdate::payload_identifier(),
+                merged_stats(&self.trade_workers),
+            ),
+            (
+                BboUpdate::payload_identifier(),
+                merged_stats(&self.bbo_workers),
+            ),
+            (
+                OrderbookSnapshot::payload_identifier(),
+                merged_stats(&self.orderbook_workers),
+            ),
+            (
+                PerpAssetContext::payload_identifier(),
+                merged_stats(&self.context_workers),
+            ),
+        ])
+    }
 }
 
+/// Trades, BBO, orderbooks and asset contexts of a spot universe, sharded like `HyperliquidPerpWebstreamSymbols`
 pub struct HyperliquidSpotWebstreamSymbols {
     symbols: Vec<TradingSymbol>,
-    _trade_worker: HyperliquidWebstreamWorker<TradeUpdate>,
-    _bbo_worker: HyperliquidWebstreamWorker<BboUpdate>,
-    _orderbook_worker: HyperliquidWebstreamWorker<OrderbookSnapshot>,
-    _context_worker: HyperliquidWebstreamWorker<SpotAssetContext>,
+    shard_count: usize,
+    trade_workers: Vec<HyperliquidWebstreamWorker<TradeUpdate>>,
+    bbo_workers: Vec<HyperliquidWebstreamWorker<BboUpdate>>,
+    orderbook_workers: Vec<HyperliquidWebstreamWorker<OrderbookSnapshot>>,
+    context_workers: Vec<HyperliquidWebstreamWorker<SpotAssetContext>>,
 }
 
 impl HyperliquidSpotWebstreamSymbols {
+    /// `symbols_per_connection`: shard size (see `shard_symbols`); `None` packs up to `HYPERLIQUID_WEBSTREAM_MAX_SYMBOLS`
+    /// symbols per connection.
+    /// `webstream_config`: how every worker connects (see `WebstreamConfig`); `None` connects directly.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         symbols: &[TradingSymbol],
         agora_prefix: &str,
         metaserver_connection: ConnectionHandle,
         local_gateway_port: u16,
         symbol_mapper: BiMap<TradingSymbol, TradingSymbol>,
+        orderbook_max_levels: Option<usize>,
+        trade_sequence_mode: Option<SequenceMode>,
+        endpoints: Option<HyperliquidEndpoints>,
+        symbols_per_connection: Option<usize>,
+        webstream_config: Option<WebstreamConfig>,
     ) -> OrError<Self> {
-        let trade_worker = HyperliquidWebstreamWorker::<TradeUpdate>::new(
-            symbols,
+        let shards = shard_symbols(symbols, symbols_per_connection)?;
+        let trade_workers = spawn_shard_workers::<TradeUpdate>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper.clone(),
+            &symbol_mapper,
+            None,
+            trade_sequence_mode,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
-
-        let bbo_worker = HyperliquidWebstreamWorker::<BboUpdate>::new(
-            symbols,
+        let bbo_workers = spawn_shard_workers::<BboUpdate>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper.clone(),
+            &symbol_mapper,
+            None,
+            None,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
-
-        let orderbook_worker = HyperliquidWebstreamWorker::<OrderbookSnapshot>::new(
-            symbols,
+        let orderbook_workers = spawn_shard_workers::<OrderbookSnapshot>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper.clone(),
+            &symbol_mapper,
+            orderbook_max_levels,
+            None,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
-
-        let context_worker = HyperliquidWebstreamWorker::<SpotAssetContext>::new(
-            symbols,
+        let context_workers = spawn_shard_workers::<SpotAssetContext>(
+            &shards,
             agora_prefix,
-            metaserver_connection.clone(),
+            &metaserver_connection,
             local_gateway_port,
-            symbol_mapper,
+            &symbol_mapper,
+            None,
+            None,
+            &endpoints,
+            &webstream_config,
         )
         .await?;
 
         Ok(Self {
             symbols: symbols.to_vec(),
-            _trade_worker: trade_worker,
-            _bbo_worker: bbo_worker,
-            _orderbook_worker: orderbook_worker,
-            _context_worker: context_worker,
+            shard_count: shards.len(),
+            trade_workers,
+            bbo_workers,
+            orderbook_workers,
+            context_workers,
         })
     }
 
     pub fn symbols(&self) -> &[TradingSymbol] {
         &self.symbols
     }
+
+    /// Shards the universe was split into; each has one connection per data type
+    pub fn shard_count(&self) -> usize {
+        self.shard_count
+    }
+
+    /// `WorkerStats` per data type (keyed by `payload_identifier()`), summed over shards
+    pub fn stats(&self) -> HashMap<String, WorkerStats> {
+        HashMap::from([
+            (
+                TradeUpdate::payload_identifier(),
+                merged_stats(&self.trade_workers),
+            ),
+            (
+                BboUpdate::payload_identifier(),
+                merged_stats(&self.bbo_workers),
+            ),
+            (
+                OrderbookSnapshot::payload_identifier(),
+                merged_stats(&self.orderbook_workers),
+            ),
+            (
+                SpotAssetContext::payload_identifier(),
+                merged_stats(&self.context_workers),
+            ),
+        ])
+    }
 }
@@ -0,0 +1,135 @@
+// Per-symbol gap detection on upstream sequence numbers / ids
+//
+// SequenceTracker remembers the highest id seen per symbol and reports a GapEvent when a new id
+// skips ahead (ids lost in between) or does not advance (duplicate or reordered delivery).
+// Webstream workers run one per connection when given a SequenceMode (see HyperliquidStreamable::sequence).
+
+use crate::types::TradingSymbol;
+use std::collections::HashMap;
+use std::fmt;
+
+/// What the upstream guarantees about its ids, i.e. what counts as a gap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceMode {
+    /// Ids advance by exactly 1 per message: any skipped id was lost
+    Contiguous,
+    /// Ids only increase (e.g. exchange-wide trade ids): only non-increasing ids are reported
+    Monotonic,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GapEvent {
+    /// Ids strictly between `last` and `received` never arrived (`Contiguous` only)
+    Skipped {
+        symbol: TradingSymbol,
+        last: u64,
+        received: u64,
+    },
+    /// `received` does not advance past `last`: duplicate or out-of-order delivery
+    OutOfOrder {
+        symbol: TradingSymbol,
+        last: u64,
+        received: u64,
+    },
+}
+
+impl GapEvent {
+    pub fn symbol(&self) -> &TradingSymbol {
+        match self {
+            GapEvent::Skipped { symbol, .. } | GapEvent::OutOfOrder { symbol, .. } => symbol,
+        }
+    }
+
+    /// Number of ids lost; 0 for out-of-order events
+    pub fn missing(&self) -> u64 {
+        match self {
+            GapEvent::Skipped { last, received, .. } => received - last - 1,
+            GapEvent::OutOfOrder { .. } => 0,
+        }
+    }
+}
+
+impl fmt::Display for GapEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GapEvent::Skipped {
+                symbol,
+                last,
+                received,
+            } => write!(
+                f,
+                "{}: skipped {} ids between {} and {}",
+                symbol.to_string(),
+                self.missing(),
+                last,
+                received
+            ),
+            GapEvent::OutOfOrder {
+                symbol,
+                last,
+                received,
+            } => write!(
+                f,
+                "{}: id {} received after {}",
+                symbol.to_string(),
+                received,
+                last
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SequenceTracker {
+    mode: SequenceMode,
+    last: HashMap<TradingSymbol, u64>,
+}
+
+impl SequenceTracker {
+    pub fn new(mode: SequenceMode) -> Self {
+        Self {
+            mode,
+            last: HashMap::new(),
+        }
+    }
+
+    pub fn mode(&self) -> SequenceMode {
+        self.mode
+    }
+
+    /// Records `sequence` for `symbol` and returns the gap it reveals, if any.
+    /// The first id of a symbol never is a gap. Out-of-order ids leave the high-water mark unchanged.
+    pub fn observe(&mut self, symbol: &TradingSymbol, sequence: u64) -> Option<GapEvent> {
+        let Some(last) = self.last.get_mut(symbol) else {
+            self.last.insert(symbol.clone(), sequence);
+            return None;
+        };
+        let previous = *last;
+        if sequence <= previous {
+            return Some(GapEvent::OutOfOrder {
+                symbol: symbol.clone(),
+                last: previous,
+                received: sequence,
+            });
+        }
+        *last = sequence;
+        if self.mode == SequenceMode::Contiguous && sequence > previous + 1 {
+            return Some(GapEvent::Skipped {
+                symbol: symbol.clone(),
+                last: previous,
+                received: sequence,
+            });
+        }
+        None
+    }
+
+    /// Highest id seen for `symbol`
+    pub fn last(&self, symbol: &TradingSymbol) -> Option<u64> {
+        self.last.get(symbol).copied()
+    }
+
+    /// Forgets every symbol, e.g. after a reconnect where the upstream may legitimately resume elsewhere
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+}
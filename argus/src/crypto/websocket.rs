@@ -0,0 +1,165 @@
+// Opening exchange WebSockets: optional connect timeout, HTTP CONNECT proxy tunnel and custom TLS,
+// for hosts where a direct `connect_async` is blocked or would hang.
+
+use crate::constants::WEBSTREAM_PROXY_MAX_RESPONSE_BYTES;
+use agora::utils::OrError;
+use anyhow::Context;
+use std::fmt;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    Connector, MaybeTlsStream, WebSocketStream, client_async_tls_with_config,
+    connect_async_tls_with_config,
+};
+use url::Url;
+
+/// How webstream workers open their WebSocket. The default connects directly, with native-tls's system roots
+/// and no timeout, exactly like `connect_async`.
+#[derive(Clone, Default)]
+pub struct WebstreamConfig {
+    /// Bound on the whole connect (proxy tunnel, TLS and WebSocket upgrade); `None` waits indefinitely
+    pub connect_timeout: Option<Duration>,
+    /// HTTP proxy to tunnel through with `CONNECT`, e.g. `http://proxy.internal:3128`
+    pub proxy: Option<Url>,
+    /// TLS for `wss://` URLs, e.g. from `with_root_certificate`; `None` uses native-tls defaults
+    pub tls_config: Option<Connector>,
+}
+
+impl WebstreamConfig {
+    /// Trusts the PEM certificate `pem` (e.g. a corporate CA) on top of the system roots
+    pub fn with_root_certificate(mut self, pem: &[u8]) -> OrError<Self> {
+        let certificate =
+            native_tls::Certificate::from_pem(pem).context("Invalid root certificate")?;
+        let connector = native_tls::TlsConnector::builder()
+            .add_root_certificate(certificate)
+            .build()
+            .context("Failed to build TLS connector")?;
+        self.tls_config = Some(Connector::NativeTls(connector));
+        Ok(self)
+    }
+}
+
+/// Why `connect_websocket` could not open a WebSocket.
+/// Workers log it and retry with their `ReconnectBackoff`.
+#[derive(Debug)]
+pub enum WebstreamConnectError {
+    /// Nothing connected within `WebstreamConfig::connect_timeout`
+    Timeout(Duration),
+    /// Proxy unreachable, refused the tunnel, or replied with something other than HTTP
+    Proxy(String),
+    /// TCP, TLS or WebSocket handshake error with the endpoint itself
+    WebSocket(String),
+}
+
+impl fmt::Display for WebstreamConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebstreamConnectError::Timeout(timeout) => {
+                write!(f, "WebSocket connect error: timed out after {:?}", timeout)
+            }
+            WebstreamConnectError::Proxy(reason) => {
+                write!(f, "WebSocket connect error: proxy {}", reason)
+            }
+            WebstreamConnectError::WebSocket(reason) => {
+                write!(f, "WebSocket connect error: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WebstreamConnectError {}
+
+/// Opens a WebSocket to `url` as `config` prescribes.
+/// Error: see `WebstreamConnectError`
+pub async fn connect_websocket(
+    url: &str,
+    config: &WebstreamConfig,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WebstreamConnectError> {
+    let connect = async {
+        let ws_stream = match &config.proxy {
+            Some(proxy) => {
+                let stream = proxy_tunnel(proxy, url).await?;
+                client_async_tls_with_config(url, stream, None, config.tls_config.clone()).await
+            }
+            None => {
+                connect_async_tls_with_config(url, None, false, config.tls_config.clone()).await
+            }
+        };
+        ws_stream
+            .map(|(ws_stream, _)| ws_stream)
+            .map_err(|e| WebstreamConnectError::WebSocket(e.to_string()))
+    };
+    match config.connect_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, connect)
+            .await
+            .map_err(|_| WebstreamConnectError::Timeout(timeout))?,
+        None => connect.await,
+    }
+}
+
+/// TCP stream to `url`'s host through an HTTP `CONNECT` tunnel of `proxy`
+async fn proxy_tunnel(proxy: &Url, url: &str) -> Result<TcpStream, WebstreamConnectError> {
+    let proxy_error =
+        |reason: String| WebstreamConnectError::Proxy(format!("{}: {}", proxy, reason));
+    if proxy.scheme() != "http" {
+        return Err(proxy_error(format!(
+            "unsupported scheme {}, only http proxies are supported",
+            proxy.scheme()
+        )));
+    }
+    if !proxy.username().is_empty() || proxy.password().is_some() {
+        return Err(proxy_error("credentials are not supported".to_string()));
+    }
+    let proxy_host = proxy
+        .host_str()
+        .ok_or_else(|| proxy_error("missing host".to_string()))?;
+    let proxy_port = proxy.port_or_known_default().unwrap_or(80);
+    let target = Url::parse(url)
+        .map_err(|e| WebstreamConnectError::WebSocket(format!("invalid URL {}: {}", url, e)))?;
+    let target_host = target
+        .host_str()
+        .ok_or_else(|| WebstreamConnectError::WebSocket(format!("URL {} has no host", url)))?;
+    let target_port = target
+        .port_or_known_default()
+        .ok_or_else(|| WebstreamConnectError::WebSocket(format!("URL {} has no port", url)))?;
+    let authority = format!("{}:{}", target_host, target_port);
+
+    let mut stream = TcpStream::connect(format!("{}:{}", proxy_host, proxy_port))
+        .await
+        .map_err(|e| proxy_error(format!("unreachable: {}", e)))?;
+    stream
+        .write_all(format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", authority).as_bytes())
+        .await
+        .map_err(|e| proxy_error(format!("failed to send CONNECT: {}", e)))?;
+
+    // One byte at a time: whatever follows the reply's blank line already belongs to the tunnel
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= WEBSTREAM_PROXY_MAX_RESPONSE_BYTES {
+            return Err(proxy_error(format!(
+                "reply to CONNECT exceeds {} bytes",
+                WEBSTREAM_PROXY_MAX_RESPONSE_BYTES
+            )));
+        }
+        match stream.read(&mut byte).await {
+            Ok(0) => {
+                return Err(proxy_error(
+                    "closed the connection during CONNECT".to_string(),
+                ));
+            }
+            Ok(_) => response.push(byte[0]),
+            Err(e) => return Err(proxy_error(format!("failed to read CONNECT reply: {}", e))),
+        }
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some("200") => Ok(stream),
+        _ => Err(proxy_error(format!(
+            "refused CONNECT {}: {}",
+            authority, status_line
+        ))),
+    }
+}
@@ -1,7 +1,12 @@
 pub mod constants;
 pub mod crypto;
+pub mod metrics;
 pub mod recording;
+pub mod supervised;
 pub mod types;
 
 
-pub use recording::{ArgusParquetable, AgoraDirScribe, Archiver}; 
\ No newline at end of file
+pub use recording::{
+    ArgusParquetable, ArgusReplayable, AgoraDirScribe, Archiver, ParquetCompressionConfig,
+    ReplayPublisher,
+}; 
\ No newline at end of file
@@ -0,0 +1,299 @@
+//! Process-wide counters rendered in Prometheus text format.
+//!
+//! Workers, relays and the archiver register their counters in the global `registry()` when they
+//! start, so series show up as 0 before the first event. `MetricsExporter` serves the registry at
+//! `GET /metrics`; without one the counters are still updated but never exposed.
+//!
+//! Labels are restricted to bounded sets: market, data type and symbol (bounded by the exchange universe).
+
+use agora::utils::OrError;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Largest request head the exporter reads before answering
+const MAX_REQUEST_BYTES: usize = 8192;
+
+/// Monotonic counter handle; clones share the same value
+#[derive(Debug, Clone, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type Labels = Vec<(String, String)>;
+
+struct CounterFamily {
+    help: &'static str,
+    series: BTreeMap<Labels, Counter>,
+}
+
+/// Named counter families, each holding one series per label set
+#[derive(Default)]
+pub struct MetricsRegistry {
+    families: RwLock<BTreeMap<&'static str, CounterFamily>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counter of `name` with `labels`, creating it at 0 on first use.
+    /// The same name and labels (in any order) always return the same counter.
+    pub fn counter(&self, name: &'static str, help: &'static str, labels: &[(&str, &str)]) -> Counter {
+        let mut key: Labels = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        key.sort();
+        if let Some(counter) = self
+            .families
+            .read()
+            .unwrap()
+            .get(name)
+            .and_then(|family| family.series.get(&key))
+        {
+            return counter.clone();
+        }
+        let mut families = self.families.write().unwrap();
+        families
+            .entry(name)
+            .or_insert_with(|| CounterFamily {
+                help,
+                series: BTreeMap::new(),
+            })
+            .series
+            .entry(key)
+            .or_default()
+            .clone()
+    }
+
+    /// Prometheus text exposition format (version 0.0.4), families and series in sorted order
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, family) in self.families.read().unwrap().iter() {
+            let _ = writeln!(out, "# HELP {} {}", name, family.help);
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            for (labels, counter) in &family.series {
+                if labels.is_empty() {
+                    let _ = writeln!(out, "{} {}", name, counter.get());
+                    continue;
+                }
+                let labels: Vec<String> = labels
+                    .iter()
+                    .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+                    .collect();
+                let _ = writeln!(out, "{}{{{}}} {}", name, labels.join(","), counter.get());
+            }
+        }
+        out
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Registry shared by every argus component in this process
+pub fn registry() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+/// Messages a webstream worker published for one symbol
+pub fn messages_published(market: &str, data_type: &str, symbol: &str) -> Counter {
+    registry().counter(
+        "argus_messages_published_total",
+        "Messages published to agora by webstream workers",
+        &[("market", market), ("data_type", data_type), ("symbol", symbol)],
+    )
+}
+
+/// Failed publishes of a webstream worker for one symbol
+pub fn publish_errors(market: &str, data_type: &str, symbol: &str) -> Counter {
+    registry().counter(
+        "argus_publish_errors_total",
+        "Failed agora publishes by webstream workers",
+        &[("market", market), ("data_type", data_type), ("symbol", symbol)],
+    )
+}
+
+/// Sequence gaps (skipped or out-of-order ids) a webstream worker saw for one symbol
+pub fn sequence_gaps(market: &str, data_type: &str, symbol: &str) -> Counter {
+    registry().counter(
+        "argus_sequence_gaps_total",
+        "Upstream sequence gaps detected by webstream workers",
+        &[("market", market), ("data_type", data_type), ("symbol", symbol)],
+    )
+}
+
+/// Websocket reconnects (after a dropped or failed connection) of one worker kind
+pub fn websocket_reconnects(market: &str, data_type: &str) -> Counter {
+    registry().counter(
+        "argus_websocket_reconnects_total",
+        "Webstream worker websocket reconnect attempts",
+        &[("market", market), ("data_type", data_type)],
+    )
+}
+
+/// Relay source swaps performed on universe changes
+pub fn relay_swapons(market: &str, data_type: &str) -> Counter {
+    registry().counter(
+        "argus_relay_swapons_total",
+        "Relay swapons onto new versioned worker paths",
+        &[("market", market), ("data_type", data_type)],
+    )
+}
+
+/// Stream frames a scribe could not decode for one agora path (publisher type or version skew)
+pub fn scribe_decode_errors(path: &str) -> Counter {
+    registry().counter(
+        "argus_scribe_decode_errors_total",
+        "Undecodable frames skipped by scribes",
+        &[("path", path)],
+    )
+}
+
+/// Records the archiver moved into hive partitions
+pub fn archiver_records_flushed(data_type: &str) -> Counter {
+    registry().counter(
+        "argus_archiver_records_flushed_total",
+        "Records flushed by the archiver into hive partitions",
+        &[("data_type", data_type)],
+    )
+}
+
+/// Flushed rows the archiver dropped for holding NaN or infinite floats (`NonFinitePolicy::Lenient`)
+pub fn archiver_non_finite_rows_dropped(data_type: &str) -> Counter {
+    registry().counter(
+        "argus_archiver_non_finite_rows_dropped_total",
+        "Rows with non-finite floats dropped by the archiver",
+        &[("data_type", data_type)],
+    )
+}
+
+/// Exits of supervised background tasks of one kind, by outcome (`completed` or `panicked`)
+pub fn task_exits(task: &str, outcome: &str) -> Counter {
+    registry().counter(
+        "argus_task_exits_total",
+        "Supervised background task exits",
+        &[("task", task), ("outcome", outcome)],
+    )
+}
+
+/// Restarts of panicked supervised background tasks of one kind
+pub fn task_restarts(task: &str) -> Counter {
+    registry().counter(
+        "argus_task_restarts_total",
+        "Supervised background task restarts after a panic",
+        &[("task", task)],
+    )
+}
+
+/// Minimal HTTP endpoint serving `registry().render()` at `GET /metrics`.
+/// Opt-in: nothing listens unless one is started. Stops serving on drop.
+pub struct MetricsExporter {
+    local_addr: SocketAddr,
+    task_handle: JoinHandle<()>,
+}
+
+impl MetricsExporter {
+    /// Binds `bind_addr` (port 0 picks a free port, see `local_addr`).
+    /// Error: Address cannot be bound
+    pub async fn serve(bind_addr: SocketAddr) -> OrError<Self> {
+        let listener = TcpListener::bind(bind_addr)
+            .await
+            .map_err(|e| anyhow::anyhow!("MetricsExporter error: cannot bind {}: {}", bind_addr, e))?;
+        let local_addr = listener.local_addr()?;
+        let task_handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(async move {
+                            if let Err(e) = Self::respond(stream).await {
+                                warn!(error = %e, "MetricsExporter: failed to respond");
+                            }
+                        });
+                    }
+                    Err(e) => warn!(error = %e, "MetricsExporter: accept error"),
+                }
+            }
+        });
+        Ok(Self {
+            local_addr,
+            task_handle,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    async fn respond(mut stream: TcpStream) -> OrError<()> {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let mut request_line = request.lines().next().unwrap_or_default().split_whitespace();
+        let (method, target) = (request_line.next(), request_line.next());
+        // Scrapers may append a query string
+        let path = target.map(|t| t.split('?').next().unwrap_or(t));
+
+        let (status, content_type, body) = match (method, path) {
+            (Some("GET"), Some("/metrics")) => (
+                "200 OK",
+                "text/plain; version=0.0.4; charset=utf-8",
+                registry().render(),
+            ),
+            (Some("GET"), _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain",
+                "method not allowed\n".to_string(),
+            ),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
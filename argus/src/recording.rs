@@ -1,19 +1,66 @@
-use agora::AgorableOption;
 use agora::utils::OrError;
+use agora::{Agorable, AgorableOption};
 use anyhow::Context;
+use arrow::datatypes::Schema;
 use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
 mod archiver;
+mod checkpoint;
+mod compression;
+mod filepath;
+mod replay;
 mod tempfile;
-pub use archiver::Archiver;
-pub use tempfile::{AgoraDirScribe, SinglePathScribe};
+mod validate;
+mod wal;
+pub use archiver::{Archiver, DedupKey, NonFinitePolicy, PartitionTz, SymbolKey};
+pub use checkpoint::{
+    BookJournalEntry, BookState, CheckpointPolicy, OrderbookEngine, read_book_at, reconstruct_book_at,
+};
+pub use compression::ParquetCompressionConfig;
+pub use filepath::FilePathTemplate;
+pub use replay::{ArgusReplayable, ReplayPublisher, read_replayable_parquet};
+pub(crate) use replay::{batch_column, downcast_column, millis_to_utc};
+pub use tempfile::{AgoraDirScribe, FlushSchedule, SinglePathScribe, tmp_file_stem};
+pub use validate::{ArchiveViolation, ValidationReport, ViolationKind};
+pub use wal::{WAL_SUFFIX, WriteAheadLog};
+
+/// Arrow schema metadata key holding the writer's `Agorable::SCHEMA_VERSION` in every parquet file argus writes
+pub const SCHEMA_VERSION_METADATA_KEY: &str = "argus.schema_version";
+
+/// `T::arrow_schema()` stamped with `T::SCHEMA_VERSION` under `SCHEMA_VERSION_METADATA_KEY`
+pub fn versioned_schema<T: ArgusParquetable>() -> std::sync::Arc<Schema> {
+    let schema = T::arrow_schema();
+    let mut metadata = schema.metadata().clone();
+    metadata.insert(
+        SCHEMA_VERSION_METADATA_KEY.to_string(),
+        T::SCHEMA_VERSION.to_string(),
+    );
+    std::sync::Arc::new(Schema::new_with_metadata(schema.fields().clone(), metadata))
+}
+
+/// Schema version a parquet file was written with. Files without the key predate versioning and are version 1.
+/// Error: Key present but not a number
+pub fn parquet_schema_version(schema: &Schema) -> OrError<u32> {
+    match schema.metadata().get(SCHEMA_VERSION_METADATA_KEY) {
+        None => Ok(1),
+        Some(version) => version.parse::<u32>().map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid {} metadata {:?}",
+                SCHEMA_VERSION_METADATA_KEY,
+                version
+            )
+        }),
+    }
+}
 
 /// Trait for writing Rust structs to Parquet files with DuckDB compatibility
 ///
 /// Uses Apache Arrow for schema definition and efficient columnar storage.
 /// Implements datetime handling with UTC timestamps and supports nested data structures.
-pub trait ArgusParquetable: Sized + Clone + Send + Sync + 'static {
+/// Files carry the type's `Agorable::SCHEMA_VERSION` in their schema metadata (see `versioned_schema`),
+/// so readers can tell archives of an older layout apart (see `ArgusReplayable::from_versioned_record_batch`).
+pub trait ArgusParquetable: Agorable + Sized + Clone + Send + Sync + 'static {
     /// Returns the Arrow schema for this type.
     ///
     /// Use:
@@ -34,10 +81,11 @@ pub trait ArgusParquetable: Sized + Clone + Send + Sync + 'static {
 
     /// Writes a vector of this type to a Parquet file at the given path.
     ///
-    /// Default implementation uses Snappy compression and writes a single RecordBatch.
+    /// Default implementation uses Snappy compression and writes a single RecordBatch,
+    /// recording `Self::SCHEMA_VERSION` in the file's schema metadata.
     /// Override for custom behavior (e.g., batching, different compression).
     fn write_to_parquet(data: Vec<Self>, output_path: String) -> OrError<()> {
-        let schema = Self::arrow_schema();
+        let schema = versioned_schema::<Self>();
         let batch = Self::to_record_batch(data)?;
 
         let file = std::fs::File::create(&output_path)
@@ -3,59 +3,350 @@
 // Input directory example: src_dir = /tmp/hyperliquid/
 // dir will contain list of subdirs consisting of types, e.g. last_trade, bbo, etc
 // Each src_dir/{data_type}/subdir will consist of /{symbol}_{time}.pq, see behavior in file.rs
+// Scribes built with a market tag write /{market}.{symbol}_{time}.pq instead.
+// Names are parsed with the same FilePathTemplate the scribe wrote them with (see filepath.rs).
 //
 // We'll also be given: target_dir (e.g.) = /tmp/agora/hyperliquid
 // Should consist of two-level tree /{data_type}/date={date}/symbol={symbol}/data.parquet, in hive-partitioned format.
+// Market-tagged files go to /{data_type}/date={date}/market={market}/symbol={symbol}/data.parquet.
 // Archiver is responsible for systematically moving temp files into this organized structure.
 // TODO: add flushing summary.
 
-use crate::constants::HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS;
+use super::{ParquetCompressionConfig, parquet_schema_version};
+use super::filepath::FilePathTemplate;
+use crate::constants::{
+    ARCHIVER_COMPACTION_ROW_GROUP_SIZE, HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS,
+};
+use crate::crypto::hyperliquid::OrderbookSnapshot;
+use crate::metrics;
+use crate::supervised::spawn_supervised_restarting;
 use crate::types::TradingSymbol;
 use agora::utils::OrError;
 use anyhow::Context;
-use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone};
+use arrow::array::{
+    Array, BooleanArray, Float32Array, Float64Array, Int64Array, ListArray, StringArray,
+    StructArray, TimestampMillisecondArray, UInt32Array, UInt64Array,
+};
+use arrow::compute::{concat_batches, filter_record_batch, take_record_batch};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Duration, FixedOffset, Local, Utc};
 use parquet::arrow::ArrowWriter;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use parquet::basic::{Compression, ZstdLevel};
 use parquet::file::properties::WriterProperties;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio::time;
+use tracing::{debug, error, info, warn};
 
 type DataType = String;
 
+/// Columns compaction sorts rows on, by preference: exchange time, then receipt time
+pub(super) const COMPACTION_TIME_COLUMNS: [&str; 3] = ["time", "trade_time", "received_time"];
+
+/// Identity of a symbol's temporary files: the market tag (if the scribe embedded one) and the symbol.
+/// The same symbol in two markets is two keys.
+pub type SymbolKey = (Option<String>, TradingSymbol);
+
+/// Newest flush time seen per data type and symbol, shared by the timer tasks and `flush_now`
+type LastUpdates = Arc<RwLock<HashMap<DataType, HashMap<SymbolKey, DateTime<Local>>>>>;
+
+/// `(date, market, symbol, dir)` of one archived partition
+pub(super) type Partition = (String, Option<String>, TradingSymbol, PathBuf);
+
+/// Rows of a flushed file that count as already archived, keyed on `(symbol, column)`.
+/// Matching rows are dropped when merging into an existing partition, as are repeats within the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DedupKey {
+    /// Integer id column, e.g. `trade_id`
+    Column(String),
+    /// Millisecond timestamp column compared exactly, e.g. the exchange `time` of orderbooks.
+    /// Never `received_time`: two receipts of the same book differ there.
+    TimeColumn(String),
+}
+
+impl DedupKey {
+    pub fn column(&self) -> &str {
+        match self {
+            DedupKey::Column(name) | DedupKey::TimeColumn(name) => name,
+        }
+    }
+
+    /// `(symbol, key)` of every row; symbol is empty for batches without a `symbol` column
+    fn row_keys(&self, batch: &RecordBatch) -> OrError<Vec<(String, i128)>> {
+        let name = self.column();
+        let column = batch
+            .column_by_name(name)
+            .ok_or_else(|| anyhow::anyhow!("DedupKey error: missing column {}", name))?;
+        let values: Vec<i128> = match self {
+            DedupKey::TimeColumn(_) => column
+                .as_any()
+                .downcast_ref::<TimestampMillisecondArray>()
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "DedupKey error: column {} is {}, expected a millisecond timestamp",
+                        name,
+                        column.data_type()
+                    )
+                })?
+                .values()
+                .iter()
+                .map(|&t| t as i128)
+                .collect(),
+            DedupKey::Column(_) => {
+                if let Some(ids) = column.as_any().downcast_ref::<UInt64Array>() {
+                    ids.values().iter().map(|&id| id as i128).collect()
+                } else if let Some(ids) = column.as_any().downcast_ref::<Int64Array>() {
+                    ids.values().iter().map(|&id| id as i128).collect()
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "DedupKey error: column {} is {}, expected a 64-bit integer",
+                        name,
+                        column.data_type()
+                    ));
+                }
+            }
+        };
+        let symbols = batch
+            .column_by_name("symbol")
+            .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+        Ok(values
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let symbol = symbols.map(|s| s.value(i).to_string()).unwrap_or_default();
+                (symbol, value)
+            })
+            .collect())
+    }
+
+    /// Drops rows whose key is in `seen` or repeats an earlier row; kept keys are added to `seen`.
+    /// Returns the filtered batches and the number of rows dropped.
+    fn drop_duplicates(
+        &self,
+        batches: Vec<RecordBatch>,
+        seen: &mut HashSet<(String, i128)>,
+    ) -> OrError<(Vec<RecordBatch>, usize)> {
+        let mut kept = Vec::with_capacity(batches.len());
+        let mut dropped = 0;
+        for batch in batches {
+            let keys = self.row_keys(&batch)?;
+            let mask = BooleanArray::from(
+                keys.into_iter()
+                    .map(|key| seen.insert(key))
+                    .collect::<Vec<bool>>(),
+            );
+            dropped += mask.false_count();
+            kept.push(filter_record_batch(&batch, &mask).context("Failed to filter duplicate rows")?);
+        }
+        Ok((kept, dropped))
+    }
+}
+
+/// What the archiver does with flushed rows holding a NaN or infinite float, including inside
+/// list or struct columns such as book levels. Checked on the rows a flush adds, before they reach the archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// Fail the flush, naming the column; the temporary file is kept for inspection
+    Strict,
+    /// Drop the offending rows, logging and counting them (`argus_archiver_non_finite_rows_dropped_total`)
+    #[default]
+    Lenient,
+}
+
+impl NonFinitePolicy {
+    /// Under `Lenient`, returns the batches without rows holding non-finite floats and the number of rows dropped.
+    /// Error: under `Strict`, any such row in `file`
+    fn apply(&self, batches: Vec<RecordBatch>, file: &str) -> OrError<(Vec<RecordBatch>, usize)> {
+        let mut kept = Vec::with_capacity(batches.len());
+        let mut dropped = 0;
+        for batch in batches {
+            let mut bad_rows = vec![false; batch.num_rows()];
+            for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+                let column_bad_rows = non_finite_rows(column.as_ref());
+                if *self == NonFinitePolicy::Strict
+                    && let Some(row) = column_bad_rows.iter().position(|bad| *bad)
+                {
+                    return Err(anyhow::anyhow!(
+                        "NonFinitePolicy error: non-finite value in column {} (row {}) of {}",
+                        field.name(),
+                        row,
+                        file
+                    ));
+                }
+                for (bad, column_bad) in bad_rows.iter_mut().zip(column_bad_rows) {
+                    *bad |= column_bad;
+                }
+            }
+            let mask = BooleanArray::from(bad_rows.iter().map(|bad| !bad).collect::<Vec<bool>>());
+            dropped += mask.false_count();
+            kept.push(
+                filter_record_batch(&batch, &mask).context("Failed to filter non-finite rows")?,
+            );
+        }
+        Ok((kept, dropped))
+    }
+}
+
+/// Per row of `array`: whether it holds a NaN or infinite float, looking through structs and lists. Nulls are finite.
+pub(super) fn non_finite_rows(array: &dyn Array) -> Vec<bool> {
+    let any = array.as_any();
+    if let Some(values) = any.downcast_ref::<Float64Array>() {
+        values
+            .iter()
+            .map(|v| v.is_some_and(|v| !v.is_finite()))
+            .collect()
+    } else if let Some(values) = any.downcast_ref::<Float32Array>() {
+        values
+            .iter()
+            .map(|v| v.is_some_and(|v| !v.is_finite()))
+            .collect()
+    } else if let Some(structs) = any.downcast_ref::<StructArray>() {
+        let mut rows = vec![false; structs.len()];
+        for column in structs.columns() {
+            for (row, bad) in rows.iter_mut().zip(non_finite_rows(column.as_ref())) {
+                *row |= bad;
+            }
+        }
+        rows
+    } else if let Some(lists) = any.downcast_ref::<ListArray>() {
+        let items = non_finite_rows(lists.values().as_ref());
+        lists
+            .value_offsets()
+            .windows(2)
+            .map(|w| items[w[0] as usize..w[1] as usize].iter().any(|bad| *bad))
+            .collect()
+    } else {
+        vec![false; array.len()]
+    }
+}
+
+/// Timezone whose calendar day names the `date=` partition of an archived file.
+/// Scribe filenames carry the host's local time; the partition date is that instant's day in this timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartitionTz {
+    /// UTC day, the usual market data convention; the same on every host
+    #[default]
+    Utc,
+    /// The host's local day
+    Local,
+    /// Day at a fixed UTC offset, e.g. `FixedOffset::east_opt(9 * 3600)` for a venue on JST
+    Offset(FixedOffset),
+}
+
+impl PartitionTz {
+    /// `%Y-%m-%d` partition date of a file stamped `time`
+    pub fn partition_date(&self, time: &DateTime<Local>) -> String {
+        match self {
+            PartitionTz::Utc => time.with_timezone(&Utc).format("%Y-%m-%d").to_string(),
+            PartitionTz::Local => time.format("%Y-%m-%d").to_string(),
+            PartitionTz::Offset(offset) => {
+                time.with_timezone(offset).format("%Y-%m-%d").to_string()
+            }
+        }
+    }
+}
+
 pub struct Archiver {
     data_types: Vec<String>,
     target_dir: String,
+    compression: ParquetCompressionConfig,
+    dedup_keys: HashMap<DataType, DedupKey>,
+    non_finite: NonFinitePolicy,
+    partition_tz: PartitionTz,
+    fixed_depths: HashMap<DataType, usize>,
+    template: FilePathTemplate,
     src_dir: Arc<RwLock<String>>,
-    last_updates: Arc<RwLock<HashMap<DataType, HashMap<TradingSymbol, DateTime<Local>>>>>,
+    last_updates: LastUpdates,
+    // Held for the duration of a scan/flush pass, so `flush_now` never races the timer on a file
+    pass_locks: HashMap<DataType, Arc<Mutex<()>>>,
     bg_handles: Vec<JoinHandle<()>>,
 }
 
 impl Archiver {
     /// Creates a new Archiver instance
+    ///
+    /// `compression` applies to archived files (defaults to ZSTD level 3).
+    /// `dedup_keys` maps data types to the key their flushed rows are deduplicated on; other types are appended as-is.
+    /// `non_finite` handles rows with NaN/infinite floats (defaults to `NonFinitePolicy::Lenient`).
+    /// `partition_tz` picks the day files are partitioned by (defaults to `PartitionTz::Utc`).
+    /// `fixed_depths` maps `OrderbookSnapshot` data types to the levels per side they are archived at
+    /// (see `OrderbookSnapshot::to_fixed_depth_record_batch`); other types keep the layout they were written in.
+    /// `template` is the scribes' filename layout (defaults to `FilePathTemplate::default()`); other files are skipped.
+    /// `scan_interval` is the time between background scan/flush passes (defaults to
+    /// `HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS`): shorter keeps the archive fresher and holds less in the
+    /// source directory, longer merges fewer small files. The first pass runs immediately.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         target_dir: &str,
         data_types: &[String],
         initial_src_dir: &str,
+        compression: Option<ParquetCompressionConfig>,
+        dedup_keys: HashMap<String, DedupKey>,
+        non_finite: Option<NonFinitePolicy>,
+        partition_tz: Option<PartitionTz>,
+        fixed_depths: HashMap<String, usize>,
+        template: Option<FilePathTemplate>,
+        scan_interval: Option<time::Duration>,
     ) -> OrError<Self> {
-        // Validate source directory
+        // Validate source directory, codec and filename layout up front
         Self::is_valid_src_dir(initial_src_dir)?;
+        let compression = compression.unwrap_or_default().validated()?;
+        let template = template.unwrap_or_default().validated()?;
+        let non_finite = non_finite.unwrap_or_default();
+        let partition_tz = partition_tz.unwrap_or_default();
+        let scan_interval = scan_interval.unwrap_or(time::Duration::from_secs(
+            HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS,
+        ));
+        if scan_interval.is_zero() {
+            return Err(anyhow::anyhow!("Archiver scan interval must be positive"));
+        }
+        if let Some(data_type) = dedup_keys.keys().find(|t| !data_types.contains(*t)) {
+            return Err(anyhow::anyhow!(
+                "Dedup key given for untracked data type {}",
+                data_type
+            ));
+        }
+        if let Some(data_type) = fixed_depths.keys().find(|t| !data_types.contains(*t)) {
+            return Err(anyhow::anyhow!(
+                "Fixed depth given for untracked data type {}",
+                data_type
+            ));
+        }
+        if let Some((data_type, _)) = fixed_depths.iter().find(|(_, depth)| **depth == 0) {
+            return Err(anyhow::anyhow!(
+                "Fixed depth of data type {} must be positive",
+                data_type
+            ));
+        }
 
         // Report existing data
-        println!("Initializing Archiver...");
-        println!("  Source dir: {}", initial_src_dir);
-        println!("  Target dir: {}", target_dir);
-        println!("  Data types: {:?}", data_types);
+        info!(
+            src_dir = initial_src_dir,
+            target_dir,
+            ?data_types,
+            %compression,
+            ?non_finite,
+            ?partition_tz,
+            ?scan_interval,
+            "initializing archiver"
+        );
+        for (data_type, dedup_key) in &dedup_keys {
+            info!(data_type = %data_type, ?dedup_key, "dedup enabled");
+        }
+        for (data_type, depth) in &fixed_depths {
+            info!(data_type = %data_type, depth, "fixed-depth layout enabled");
+        }
 
         for data_type in data_types {
             let src_path = format!("{}/{}", initial_src_dir, data_type);
             if let Ok(entries) = fs::read_dir(&src_path) {
                 let count = entries.count();
-                println!("  Found {} files in {}", count, data_type);
+                info!(data_type = %data_type, files = count, "found existing files");
             }
         }
 
@@ -69,10 +360,10 @@ impl Archiver {
                 .map_err(|e| anyhow::anyhow!("Failed to create data type directory {}: {}", type_dir, e))?;
         }
 
-        println!("Target directory structure created successfully");
+        debug!(target_dir, "target directory structure created");
 
         // Initialize last_updates with empty maps for each data type
-        let mut last_updates_map: HashMap<DataType, HashMap<TradingSymbol, DateTime<Local>>> =
+        let mut last_updates_map: HashMap<DataType, HashMap<SymbolKey, DateTime<Local>>> =
             HashMap::new();
         for data_type in data_types {
             last_updates_map.insert(data_type.clone(), HashMap::new());
@@ -83,49 +374,78 @@ impl Archiver {
 
         // Spawn background tasks for each data type
         let mut bg_handles = Vec::new();
+        let mut pass_locks = HashMap::new();
         for data_type in data_types {
             let data_type = data_type.clone();
+            let pass_lock = Arc::new(Mutex::new(()));
+            pass_locks.insert(data_type.clone(), Arc::clone(&pass_lock));
             let src_dir_clone = Arc::clone(&src_dir);
             let target_dir_clone = target_dir.to_string();
             let last_updates_clone = Arc::clone(&last_updates);
-
-            let handle = tokio::spawn(async move {
-                Self::track_single_data_type(
-                    data_type,
-                    src_dir_clone,
-                    target_dir_clone,
-                    last_updates_clone,
-                )
-                .await;
-            });
+            let dedup_key = dedup_keys.get(&data_type).cloned();
+            let fixed_depth = fixed_depths.get(&data_type).copied();
+
+            // Panics in a flush pass restart tracking from the shared state instead of silently stopping archival
+            let handle = spawn_supervised_restarting(
+                "archiver",
+                move || {
+                    Self::track_single_data_type(
+                        data_type.clone(),
+                        Arc::clone(&src_dir_clone),
+                        target_dir_clone.clone(),
+                        compression,
+                        dedup_key.clone(),
+                        non_finite,
+                        partition_tz,
+                        fixed_depth,
+                        template,
+                        scan_interval,
+                        Arc::clone(&last_updates_clone),
+                        Arc::clone(&pass_lock),
+                    )
+                },
+                None,
+                |_| {},
+            );
 
             bg_handles.push(handle);
         }
 
-        println!(
-            "Archiver initialized with {} background tasks",
-            bg_handles.len()
-        );
+        info!(tasks = bg_handles.len(), "archiver initialized");
 
         Ok(Self {
             data_types: data_types.to_vec(),
             target_dir: target_dir.to_string(),
+            compression,
+            dedup_keys,
+            non_finite,
+            partition_tz,
+            fixed_depths,
+            template,
             src_dir,
             last_updates,
+            pass_locks,
             bg_handles,
         })
     }
 
     /// Background task that tracks and archives files for a single data type
+    #[allow(clippy::too_many_arguments)]
     async fn track_single_data_type(
         data_type: String,
         src_dir: Arc<RwLock<String>>,
         target_dir: String,
-        last_updates: Arc<RwLock<HashMap<DataType, HashMap<TradingSymbol, DateTime<Local>>>>>,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<DedupKey>,
+        non_finite: NonFinitePolicy,
+        partition_tz: PartitionTz,
+        fixed_depth: Option<usize>,
+        template: FilePathTemplate,
+        scan_interval: time::Duration,
+        last_updates: LastUpdates,
+        pass_lock: Arc<Mutex<()>>,
     ) {
-        let mut interval = time::interval(time::Duration::from_secs(
-            HYPERLIQUID_ARCHIVER_FLUSH_INTERVAL_SECONDS,
-        ));
+        let mut interval = time::interval(scan_interval);
 
         loop {
             interval.tick().await;
@@ -135,159 +455,165 @@ impl Archiver {
                 guard.clone()
             };
 
-            let scan_path = format!("{}/{}", current_src_dir, data_type);
+            let _pass = pass_lock.lock().await;
+            if let Err(e) = Self::archive_pass(
+                &data_type,
+                &current_src_dir,
+                &target_dir,
+                compression,
+                dedup_key.as_ref(),
+                non_finite,
+                partition_tz,
+                fixed_depth,
+                template,
+                &last_updates,
+                false,
+            )
+            .await
+            {
+                error!(data_type = %data_type, error = %e, "archive pass failed");
+            }
+        }
+    }
 
-            // Read all files in this data type directory
-            let entries = match fs::read_dir(&scan_path) {
+    /// One scan/flush cycle over `{src_dir}/{data_type}`. Returns (flushed, failed) file counts;
+    /// failed flushes are logged and keep their source file.
+    /// Each symbol's newest file may still be written to, so it is only flushed with `include_latest`.
+    #[allow(clippy::too_many_arguments)]
+    async fn archive_pass(
+        data_type: &str,
+        src_dir: &str,
+        target_dir: &str,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<&DedupKey>,
+        non_finite: NonFinitePolicy,
+        partition_tz: PartitionTz,
+        fixed_depth: Option<usize>,
+        template: FilePathTemplate,
+        last_updates: &RwLock<HashMap<DataType, HashMap<SymbolKey, DateTime<Local>>>>,
+        include_latest: bool,
+    ) -> OrError<(usize, usize)> {
+        let scan_path = format!("{}/{}", src_dir, data_type);
+
+        // Read all files in this data type directory
+        let entries = fs::read_dir(&scan_path)
+            .map_err(|e| anyhow::anyhow!("Failed to read directory {}: {}", scan_path, e))?;
+
+        let mut files_with_metadata = Vec::new();
+
+        // First pass: parse all files and collect metadata
+        for entry in entries {
+            let entry = match entry {
                 Ok(e) => e,
-                Err(e) => {
-                    eprintln!("Failed to read directory {}: {}", scan_path, e);
-                    continue;
-                }
+                Err(_) => continue,
             };
 
-            let mut files_with_metadata = Vec::new();
-
-            // First pass: parse all files and collect metadata
-            for entry in entries {
-                let entry = match entry {
-                    Ok(e) => e,
-                    Err(_) => continue,
-                };
-
-                let path = entry.path();
-                if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("pq") {
-                    continue;
-                }
-
-                let filepath = match path.to_str() {
-                    Some(s) => s,
-                    None => continue,
-                };
-
-                match Self::parse_tmp_filepath(filepath) {
-                    Ok((parsed_data_type, symbol, timestamp)) => {
-                        if parsed_data_type == data_type {
-                            files_with_metadata.push((filepath.to_string(), symbol, timestamp));
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to parse filepath {}: {}", filepath, e);
-                    }
-                }
-            }
-
-            if files_with_metadata.is_empty() {
+            let path = entry.path();
+            if !path.is_file() || !template.matches_extension(&path) {
                 continue;
             }
 
-            // Update last_updates with the latest timestamps
-            {
-                let mut updates = last_updates.write().unwrap();
-                let data_type_updates = updates.get_mut(&data_type).unwrap();
-
-                for (_, symbol, timestamp) in &files_with_metadata {
-                    data_type_updates
-                        .entry(symbol.clone())
-                        .and_modify(|t| {
-                            if timestamp > t {
-                                *t = *timestamp;
-                            }
-                        })
-                        .or_insert(*timestamp);
-                }
-            }
-
-            // Second pass: flush files that are older than the latest update
-            let last_updates_snapshot = {
-                let updates = last_updates.read().unwrap();
-                updates.get(&data_type).unwrap().clone()
+            let filepath = match path.to_str() {
+                Some(s) => s,
+                None => continue,
             };
-            let mut flushed_file_count = 0;
-            let mut flushed_record_count = 0;
-
-            for (filepath, symbol, timestamp) in files_with_metadata {
-                if let Some(latest_time) = last_updates_snapshot.get(&symbol) {
-                    if timestamp < *latest_time {
-                        // This file is older than the latest, safe to flush
-                        match Self::flush_tmp_file(&filepath, &target_dir).await {
-                            Err(e) => {
-                                eprintln!("Failed to flush {}: {}", filepath, e);
-                            }
-                            Ok(record_count) => {
-                                flushed_file_count += 1;
-                                flushed_record_count = flushed_record_count + record_count;
-                            }
-                        }
+
+            match template.parse(filepath) {
+                Ok((parsed_data_type, market, symbol, timestamp)) => {
+                    if parsed_data_type == data_type {
+                        files_with_metadata.push((
+                            filepath.to_string(),
+                            (market, symbol),
+                            timestamp,
+                        ));
                     }
                 }
+                Err(e) => {
+                    warn!(%filepath, error = %e, "failed to parse filepath");
+                }
             }
-            eprintln!(
-                "Data type {}: flushed {} records across {} files",
-                &data_type, flushed_record_count, flushed_file_count
-            );
         }
-    }
 
-    /// Parses a temporary filepath to extract data type, symbol, and timestamp
-    /// Expected format: {src_dir}/{data_type}/{symbol}_{YY-MM-DD HH:MM:SS}.pq
-    fn parse_tmp_filepath(filepath: &str) -> OrError<(String, TradingSymbol, DateTime<Local>)> {
-        let path = Path::new(filepath);
-
-        // Get the parent directory name (data_type)
-        let data_type = path
-            .parent()
-            .and_then(|p| p.file_name())
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| anyhow::anyhow!("Cannot extract data type from path: {}", filepath))?
-            .to_string();
-
-        // Get the filename
-        let filename = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| anyhow::anyhow!("Cannot extract filename from path: {}", filepath))?;
-
-        // Remove .pq extension
-        if !filename.ends_with(".pq") {
-            return Err(anyhow::anyhow!("File does not have .pq extension: {}", filename));
+        if files_with_metadata.is_empty() {
+            return Ok((0, 0));
         }
-        let without_ext = &filename[..filename.len() - 3];
 
-        // The format is {symbol}_{YY-MM-DD HH:MM:SS}
-        // The timestamp part is always 17 characters: "25-01-15 10:30:45"
-        // Plus the underscore makes it 18 characters from the end
-        if without_ext.len() < 18 {
-            return Err(anyhow::anyhow!(
-                "Filename too short to contain timestamp: {}",
-                filename
-            ));
+        // Update last_updates with the latest timestamps
+        {
+            let mut updates = last_updates.write().unwrap();
+            let data_type_updates = updates.get_mut(data_type).unwrap();
+
+            for (_, key, timestamp) in &files_with_metadata {
+                data_type_updates
+                    .entry(key.clone())
+                    .and_modify(|t| {
+                        if timestamp > t {
+                            *t = *timestamp;
+                        }
+                    })
+                    .or_insert(*timestamp);
+            }
         }
 
-        let split_pos = without_ext.len() - 17;
-        if &without_ext[split_pos - 1..split_pos] != "_" {
-            return Err(anyhow::anyhow!(
-                "Expected underscore before timestamp in: {}",
-                filename
-            ));
+        // Second pass: flush files that are older than the latest update
+        let last_updates_snapshot = {
+            let updates = last_updates.read().unwrap();
+            updates.get(data_type).unwrap().clone()
+        };
+        let mut flushed_file_count = 0;
+        let mut failed_file_count = 0;
+        let mut flushed_record_count = 0;
+
+        // Oldest first, so each symbol's files merge into its partition in write order
+        files_with_metadata.sort_by_key(|(_, _, timestamp)| *timestamp);
+        for (filepath, key, timestamp) in files_with_metadata {
+            if let Some(latest_time) = last_updates_snapshot.get(&key)
+                && (include_latest || timestamp < *latest_time)
+            {
+                // Older than the latest (or its writer has stopped), safe to flush
+                let flushed = Self::flush_templated_tmp_file(
+                    &filepath,
+                    template,
+                    target_dir,
+                    compression,
+                    dedup_key,
+                    Some(non_finite),
+                    Some(partition_tz),
+                    fixed_depth,
+                )
+                .await;
+                match flushed {
+                    Err(e) => {
+                        error!(%filepath, error = %e, "failed to flush");
+                        failed_file_count += 1;
+                    }
+                    Ok(record_count) => {
+                        flushed_file_count += 1;
+                        flushed_record_count += record_count;
+                    }
+                }
+            }
         }
+        metrics::archiver_records_flushed(data_type).add(flushed_record_count as u64);
+        info!(
+            data_type = %data_type,
+            records = flushed_record_count,
+            files = flushed_file_count,
+            failed = failed_file_count,
+            "archive pass flushed"
+        );
+        Ok((flushed_file_count, failed_file_count))
+    }
 
-        let symbol_str = &without_ext[..split_pos - 1];
-        let timestamp_str = &without_ext[split_pos..];
-
-        // Parse symbol
-        let symbol = TradingSymbol::from_str(symbol_str)?;
-
-        // Parse timestamp using the format from file.rs: %y-%m-%d %H:%M:%S
-        let naive_dt = NaiveDateTime::parse_from_str(timestamp_str, "%y-%m-%d %H:%M:%S")
-            .map_err(|e| anyhow::anyhow!("Failed to parse timestamp '{}': {}", timestamp_str, e))?;
-
-        let dt = Local
-            .from_local_datetime(&naive_dt)
-            .single()
-            .ok_or_else(|| anyhow::anyhow!("Ambiguous or invalid local datetime: {}", timestamp_str))?;
-
-        Ok((data_type, symbol, dt))
+    /// Parses a temporary filepath to extract data type, market, symbol, and timestamp
+    /// Expected format: {src_dir}/{data_type}/{symbol}_{YY-MM-DD HH:MM:SS}Z.pq,
+    /// or {src_dir}/{data_type}/{market}.{symbol}_{YY-MM-DD HH:MM:SS}Z.pq (market is `None` for the former).
+    /// The time is UTC; without the `Z` it is host-local (older scribes).
+    /// Same as `FilePathTemplate::default().parse`.
+    pub fn parse_tmp_filepath(
+        filepath: &str,
+    ) -> OrError<(String, Option<String>, TradingSymbol, DateTime<Local>)> {
+        FilePathTemplate::default().parse(filepath)
     }
 
     /// Validates that a source directory exists and has the expected structure
@@ -323,14 +649,65 @@ impl Archiver {
         Ok(())
     }
 
-    /// Atomically flushes a temporary file to the target hive-partitioned structure
-    async fn flush_tmp_file(filepath: &str, target_dir: &str) -> OrError<usize> {
+    /// Atomically flushes a temporary file to the target hive-partitioned structure and removes it.
+    /// With `dedup_key`, rows already in the target partition are dropped.
+    /// Rows with NaN/infinite floats are handled by `non_finite` (defaults to `NonFinitePolicy::Lenient`).
+    /// The file's date partition is its timestamp's day in `partition_tz` (defaults to `PartitionTz::Utc`).
+    /// With `fixed_depth`, the file holds `OrderbookSnapshot` rows, archived at that many levels per side.
+    /// The file is named as `FilePathTemplate::default()` prescribes.
+    /// Returns the record count of the target file.
+    pub async fn flush_tmp_file(
+        filepath: &str,
+        target_dir: &str,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<&DedupKey>,
+        non_finite: Option<NonFinitePolicy>,
+        partition_tz: Option<PartitionTz>,
+        fixed_depth: Option<usize>,
+    ) -> OrError<usize> {
+        Self::flush_templated_tmp_file(
+            filepath,
+            FilePathTemplate::default(),
+            target_dir,
+            compression,
+            dedup_key,
+            non_finite,
+            partition_tz,
+            fixed_depth,
+        )
+        .await
+    }
+
+    /// `flush_tmp_file` for a file named by `template`
+    #[allow(clippy::too_many_arguments)]
+    async fn flush_templated_tmp_file(
+        filepath: &str,
+        template: FilePathTemplate,
+        target_dir: &str,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<&DedupKey>,
+        non_finite: Option<NonFinitePolicy>,
+        partition_tz: Option<PartitionTz>,
+        fixed_depth: Option<usize>,
+    ) -> OrError<usize> {
         let filepath_clone = filepath.to_string();
         let target_dir_clone = target_dir.to_string();
+        let dedup_key = dedup_key.cloned();
+        let non_finite = non_finite.unwrap_or_default();
+        let partition_tz = partition_tz.unwrap_or_default();
 
         // Use spawn_blocking for heavy I/O operations
         let flushed_record_count = tokio::task::spawn_blocking(move || {
-            Self::flush_tmp_file_blocking(&filepath_clone, &target_dir_clone)
+            Self::flush_tmp_file_blocking(
+                &filepath_clone,
+                template,
+                &target_dir_clone,
+                compression,
+                dedup_key.as_ref(),
+                non_finite,
+                partition_tz,
+                fixed_depth,
+            )
         })
         .await
         .context("Task join error")??;
@@ -339,20 +716,30 @@ impl Archiver {
     }
 
     /// Blocking implementation of flush_tmp_file
-    fn flush_tmp_file_blocking(filepath: &str, target_dir: &str) -> OrError<usize> {
+    #[allow(clippy::too_many_arguments)]
+    fn flush_tmp_file_blocking(
+        filepath: &str,
+        template: FilePathTemplate,
+        target_dir: &str,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<&DedupKey>,
+        non_finite: NonFinitePolicy,
+        partition_tz: PartitionTz,
+        fixed_depth: Option<usize>,
+    ) -> OrError<usize> {
         // Parse the filepath
-        let (data_type, symbol, datetime) = Self::parse_tmp_filepath(filepath)?;
+        let (data_type, market, symbol, datetime) = template.parse(filepath)?;
 
-        // Extract date in YYYY-MM-DD format
-        let date_str = datetime.format("%Y-%m-%d").to_string();
+        // Extract date in YYYY-MM-DD format, in the partition timezone
+        let date_str = partition_tz.partition_date(&datetime);
 
-        // Build target path: {target_dir}/{data_type}/date={date}/symbol={symbol}/data.parquet
-        let target_subdir = format!(
-            "{}/{}/date={}/symbol={}",
+        // Build target path: {target_dir}/{data_type}/date={date}[/market={market}]/symbol={symbol}/data.parquet
+        let target_subdir = Self::hive_partition_dir(
             target_dir,
-            data_type,
-            date_str,
-            symbol.to_string()
+            &data_type,
+            &date_str,
+            market.as_deref(),
+            &symbol,
         );
         fs::create_dir_all(&target_subdir)
             .map_err(|e| anyhow::anyhow!("Failed to create target directory {}: {}", target_subdir, e))?;
@@ -360,25 +747,81 @@ impl Archiver {
         let target_path = format!("{}/data.parquet", target_subdir);
 
         // Check if target file exists
-        if !Path::new(&target_path).exists() {
-            // Recompress with ZSTD for optimal storage
-            let record_count = Self::recompress_parquet_file(filepath, &target_path)?;
-            // Delete the source file
-            fs::remove_file(filepath)
-                .map_err(|e| anyhow::anyhow!("Failed to remove source file {}: {}", filepath, e))?;
-            Ok(record_count)
+        let (record_count, non_finite_dropped) = if !Path::new(&target_path).exists() {
+            // Recompress with the archive codec
+            Self::recompress_parquet_file(
+                filepath,
+                &target_path,
+                compression,
+                dedup_key,
+                non_finite,
+                fixed_depth,
+            )?
         } else {
             // Complex case: merge with existing file
-            let record_count = Self::merge_parquet_files(filepath, &target_path)?;
-            // Delete the source file after successful merge
-            fs::remove_file(filepath)
-                .map_err(|e| anyhow::anyhow!("Failed to remove source file {}: {}", filepath, e))?;
-            Ok(record_count)
+            Self::merge_parquet_files(
+                filepath,
+                &target_path,
+                compression,
+                dedup_key,
+                non_finite,
+                fixed_depth,
+            )?
+        };
+        if non_finite_dropped > 0 {
+            warn!(
+                file = %filepath,
+                data_type = %data_type,
+                dropped = non_finite_dropped,
+                "dropped rows with non-finite values"
+            );
+            metrics::archiver_non_finite_rows_dropped(&data_type).add(non_finite_dropped as u64);
         }
+        // Delete the source file once its rows are in the target
+        fs::remove_file(filepath)
+            .map_err(|e| anyhow::anyhow!("Failed to remove source file {}: {}", filepath, e))?;
+        Ok(record_count)
     }
 
-    /// Recompresses a parquet file with ZSTD compression
-    fn recompress_parquet_file(src_file: &str, dest_file: &str) -> OrError<usize> {
+    /// Hive directory for one file: `{target_dir}/{data_type}/date={date}/symbol={symbol}`,
+    /// with a `market={market}` level before `symbol=` for market-tagged files
+    pub fn hive_partition_dir(
+        target_dir: &str,
+        data_type: &str,
+        date: &str,
+        market: Option<&str>,
+        symbol: &TradingSymbol,
+    ) -> String {
+        match market {
+            Some(market) => format!(
+                "{}/{}/date={}/market={}/symbol={}",
+                target_dir,
+                data_type,
+                date,
+                market,
+                symbol.to_string()
+            ),
+            None => format!(
+                "{}/{}/date={}/symbol={}",
+                target_dir,
+                data_type,
+                date,
+                symbol.to_string()
+            ),
+        }
+    }
+
+    /// Recompresses a parquet file with the archive codec, dropping repeated rows under `dedup_key`
+    /// and reshaping orderbook rows to `fixed_depth` levels per side.
+    /// Returns the records written and the rows dropped for non-finite values.
+    fn recompress_parquet_file(
+        src_file: &str,
+        dest_file: &str,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<&DedupKey>,
+        non_finite: NonFinitePolicy,
+        fixed_depth: Option<usize>,
+    ) -> OrError<(usize, usize)> {
         // Read source file
         let src_file_handle = fs::File::open(src_file)
             .context("Failed to open source file")?;
@@ -386,7 +829,7 @@ impl Archiver {
         let src_builder = ParquetRecordBatchReaderBuilder::try_new(src_file_handle)
             .context("Failed to create reader for source file")?;
 
-        let schema = src_builder.schema().clone();
+        let mut schema = src_builder.schema().clone();
 
         let mut batches = Vec::new();
         let mut src_reader = src_builder
@@ -399,13 +842,23 @@ impl Archiver {
             batches.push(batch);
         }
 
-        // Write with ZSTD compression
+        let (mut batches, non_finite_dropped) = non_finite.apply(batches, src_file)?;
+        if let Some(dedup_key) = dedup_key {
+            let (kept, dropped) = dedup_key.drop_duplicates(batches, &mut HashSet::new())?;
+            if dropped > 0 {
+                info!(file = %src_file, dropped, "dropped duplicate rows");
+            }
+            batches = kept;
+        }
+        if let Some(depth) = fixed_depth {
+            (schema, batches) = Self::to_fixed_depth(batches, depth)?;
+        }
+
         let dest_file_handle = fs::File::create(dest_file)
             .context("Failed to create destination file")?;
 
-        // ZSTD level 3: optimal balance of compression ratio and speed for time-series data
         let props = WriterProperties::builder()
-            .set_compression(Compression::ZSTD(ZstdLevel::try_new(3).unwrap()))
+            .set_compression(compression.to_parquet()?)
             .build();
 
         let mut writer = ArrowWriter::try_new(dest_file_handle, schema, Some(props))
@@ -423,17 +876,30 @@ impl Archiver {
             .close()
             .context("Failed to close writer")?;
 
-        Ok(total_records)
+        Ok((total_records, non_finite_dropped))
     }
 
-    /// Merges a new parquet file into an existing one with ZSTD compression
-    fn merge_parquet_files(new_file: &str, existing_file: &str) -> OrError<usize> {
+    /// Merges a new parquet file into an existing one with the archive codec.
+    /// Both must have the same schema version (see `parquet_schema_version`).
+    /// With `dedup_key`, new rows whose key the existing file already holds are dropped; existing rows are kept as-is.
+    /// With `fixed_depth`, new orderbook rows are reshaped first; the existing file must share the resulting columns.
+    /// Returns the records in the merged file and the new rows dropped for non-finite values.
+    fn merge_parquet_files(
+        new_file: &str,
+        existing_file: &str,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<&DedupKey>,
+        non_finite: NonFinitePolicy,
+        fixed_depth: Option<usize>,
+    ) -> OrError<(usize, usize)> {
         // Read existing file
         let existing_file_handle = fs::File::open(existing_file)
             .context("Failed to open existing file")?;
 
         let existing_builder = ParquetRecordBatchReaderBuilder::try_new(existing_file_handle)
             .context("Failed to create reader for existing file")?;
+        let existing_version = parquet_schema_version(existing_builder.schema())?;
+        let existing_schema = existing_builder.schema().clone();
 
         let mut existing_batches = Vec::new();
         let mut existing_reader = existing_builder
@@ -453,7 +919,19 @@ impl Archiver {
         let new_builder = ParquetRecordBatchReaderBuilder::try_new(new_file_handle)
             .context("Failed to create reader for new file")?;
 
-        let schema = new_builder.schema().clone();
+        let mut schema = new_builder.schema().clone();
+
+        // Layouts of different schema versions cannot share a file; keep the new file for inspection
+        let new_version = parquet_schema_version(&schema)?;
+        if new_version != existing_version {
+            return Err(anyhow::anyhow!(
+                "Cannot merge schema version {} file {} into schema version {} file {}",
+                new_version,
+                new_file,
+                existing_version,
+                existing_file
+            ));
+        }
 
         let mut new_batches = Vec::new();
         let mut new_reader = new_builder
@@ -466,6 +944,30 @@ impl Archiver {
             new_batches.push(batch);
         }
 
+        let (mut new_batches, non_finite_dropped) = non_finite.apply(new_batches, new_file)?;
+        if let Some(dedup_key) = dedup_key {
+            let mut seen = HashSet::new();
+            for batch in &existing_batches {
+                seen.extend(dedup_key.row_keys(batch)?);
+            }
+            let (kept, dropped) = dedup_key.drop_duplicates(new_batches, &mut seen)?;
+            if dropped > 0 {
+                info!(file = %new_file, dropped, "dropped duplicate rows");
+            }
+            new_batches = kept;
+        }
+        if let Some(depth) = fixed_depth {
+            (schema, new_batches) = Self::to_fixed_depth(new_batches, depth)?;
+        }
+        // E.g. a partition archived at full depth before a fixed depth was configured
+        if existing_schema.fields() != schema.fields() {
+            return Err(anyhow::anyhow!(
+                "Cannot merge {} into {}: their columns differ",
+                new_file,
+                existing_file
+            ));
+        }
+
         // Concatenate all batches
         let mut all_batches = existing_batches;
         all_batches.extend(new_batches);
@@ -475,9 +977,8 @@ impl Archiver {
         let temp_file = fs::File::create(&temp_path)
             .context("Failed to create temp file")?;
 
-        // ZSTD level 3: optimal balance of compression ratio and speed for time-series data
         let props = WriterProperties::builder()
-            .set_compression(Compression::ZSTD(ZstdLevel::try_new(3).unwrap()))
+            .set_compression(compression.to_parquet()?)
             .build();
 
         let mut writer = ArrowWriter::try_new(temp_file, schema, Some(props))
@@ -499,7 +1000,256 @@ impl Archiver {
         fs::rename(&temp_path, existing_file)
             .map_err(|e| anyhow::anyhow!("Failed to rename {} to {}: {}", temp_path, existing_file, e))?;
 
-        Ok(total_records)
+        Ok((total_records, non_finite_dropped))
+    }
+
+    /// Orderbook rows of `batches` at `depth` levels per side, with their schema (see `OrderbookSnapshot::fixed_depth_batch`)
+    fn to_fixed_depth(
+        batches: Vec<RecordBatch>,
+        depth: usize,
+    ) -> OrError<(Arc<arrow::datatypes::Schema>, Vec<RecordBatch>)> {
+        let batches = batches
+            .iter()
+            .map(|batch| OrderbookSnapshot::fixed_depth_batch(batch, depth))
+            .collect::<OrError<Vec<_>>>()?;
+        Ok((OrderbookSnapshot::fixed_depth_arrow_schema(), batches))
+    }
+
+    /// Rewrites the archived partition `{data_type}/date={date}[/market={market}]/symbol={symbol}` as a single
+    /// `data.parquet`: every parquet file in it is read, deduplicated on the data type's `DedupKey` (rows of
+    /// `data.parquet` win, then other files by name), sorted by time (see `COMPACTION_TIME_COLUMNS`; partitions
+    /// with none keep their row order) and written with the archive codec. The other files are removed afterwards.
+    /// Maintenance only: the live flush path never leaves more than one file per partition. Holds the data type's
+    /// pass lock, so it never races a flush into the same partition.
+    /// Returns the record count of the compacted file.
+    /// Error: untracked data type, no parquet file in the partition, or files of different schema versions
+    pub async fn compact_partition(
+        &self,
+        data_type: &str,
+        date: &str,
+        market: Option<&str>,
+        symbol: &TradingSymbol,
+    ) -> OrError<usize> {
+        let pass_lock = self.pass_locks.get(data_type).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Archiver compaction error: untracked data type {}",
+                data_type
+            )
+        })?;
+        let _pass = pass_lock.lock().await;
+        let partition_dir =
+            Self::hive_partition_dir(&self.target_dir, data_type, date, market, symbol);
+        let compression = self.compression;
+        let dedup_key = self.dedup_keys.get(data_type).cloned();
+        tokio::task::spawn_blocking(move || {
+            Self::compact_partition_blocking(&partition_dir, compression, dedup_key.as_ref())
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    /// Compacts (see `compact_partition`) every archived partition of every data type holding more than one
+    /// parquet file, and returns the number of partitions compacted.
+    /// Error: a data type directory is unreadable or a partition fails to compact; the remaining partitions are still attempted.
+    pub async fn compact_all(&self) -> OrError<usize> {
+        let mut compacted = 0;
+        let mut failed = 0;
+        for data_type in &self.data_types {
+            let type_dir = format!("{}/{}", self.target_dir, data_type);
+            for (date, market, symbol, dir) in Self::list_partitions(&type_dir)? {
+                if Self::partition_files(&dir)?.len() < 2 {
+                    continue;
+                }
+                match self
+                    .compact_partition(data_type, &date, market.as_deref(), &symbol)
+                    .await
+                {
+                    Ok(_) => compacted += 1,
+                    Err(e) => {
+                        failed += 1;
+                        error!(partition = ?dir, error = %e, "compaction failed");
+                    }
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "Archiver compact_all error: {} partitions in {} failed to compact",
+                failed,
+                self.target_dir
+            ));
+        }
+        Ok(compacted)
+    }
+
+    /// `(date, market, symbol, dir)` of every partition under `{target_dir}/{data_type}`
+    pub(super) fn list_partitions(
+        type_dir: &str,
+    ) -> OrError<Vec<Partition>> {
+        let subdirs = |dir: &Path, key: &str| -> OrError<Vec<(String, PathBuf)>> {
+            let prefix = format!("{}=", key);
+            let mut found = Vec::new();
+            for entry in fs::read_dir(dir)
+                .map_err(|e| anyhow::anyhow!("Failed to read directory {:?}: {}", dir, e))?
+            {
+                let path = entry.context("Failed to read directory entry")?.path();
+                let value = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| n.strip_prefix(&prefix))
+                    .map(str::to_string);
+                if let (Some(value), true) = (value, path.is_dir()) {
+                    found.push((value, path));
+                }
+            }
+            found.sort();
+            Ok(found)
+        };
+
+        let mut partitions = Vec::new();
+        for (date, date_dir) in subdirs(Path::new(type_dir), "date")? {
+            for (symbol, dir) in subdirs(&date_dir, "symbol")? {
+                partitions.push((date.clone(), None, TradingSymbol::from_str(&symbol)?, dir));
+            }
+            for (market, market_dir) in subdirs(&date_dir, "market")? {
+                for (symbol, dir) in subdirs(&market_dir, "symbol")? {
+                    partitions.push((
+                        date.clone(),
+                        Some(market.clone()),
+                        TradingSymbol::from_str(&symbol)?,
+                        dir,
+                    ));
+                }
+            }
+        }
+        Ok(partitions)
+    }
+
+    /// Parquet files in a partition directory: `data.parquet` first, then the rest by name
+    pub(super) fn partition_files(dir: &Path) -> OrError<Vec<PathBuf>> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to read partition {:?}: {}", dir, e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("parquet")
+            })
+            .collect();
+        files.sort_by_key(|path| {
+            (
+                path.file_name() != Some(OsStr::new("data.parquet")),
+                path.clone(),
+            )
+        });
+        Ok(files)
+    }
+
+    /// Blocking implementation of compact_partition
+    fn compact_partition_blocking(
+        partition_dir: &str,
+        compression: ParquetCompressionConfig,
+        dedup_key: Option<&DedupKey>,
+    ) -> OrError<usize> {
+        let files = Self::partition_files(Path::new(partition_dir))?;
+        if files.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Archiver compaction error: no parquet files in {}",
+                partition_dir
+            ));
+        }
+
+        // Read every file; layouts of different schema versions cannot share a file
+        let mut schema = None;
+        let mut first_version = None;
+        let mut batches = Vec::new();
+        for file in &files {
+            let builder = ParquetRecordBatchReaderBuilder::try_new(
+                fs::File::open(file).context("Failed to open partition file")?,
+            )
+            .context("Failed to create reader for partition file")?;
+            let version = parquet_schema_version(builder.schema())?;
+            let expected = *first_version.get_or_insert(version);
+            if version != expected {
+                return Err(anyhow::anyhow!(
+                    "Archiver compaction error: {:?} has schema version {} but {:?} has {}",
+                    file,
+                    version,
+                    files[0],
+                    expected
+                ));
+            }
+            schema.get_or_insert_with(|| builder.schema().clone());
+            for batch in builder
+                .build()
+                .context("Failed to build reader for partition file")?
+            {
+                batches.push(batch.context("Failed to read batch from partition file")?);
+            }
+        }
+        let schema = schema.unwrap();
+
+        let mut dropped = 0;
+        if let Some(dedup_key) = dedup_key {
+            (batches, dropped) = dedup_key.drop_duplicates(batches, &mut HashSet::new())?;
+        }
+
+        let mut batch =
+            concat_batches(&schema, &batches).context("Failed to concatenate batches")?;
+        if let Some(name) = COMPACTION_TIME_COLUMNS
+            .iter()
+            .find(|name| batch.column_by_name(name).is_some())
+        {
+            let times = batch
+                .column_by_name(name)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<TimestampMillisecondArray>()
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Archiver compaction error: column {} of {} is not a millisecond timestamp",
+                        name,
+                        partition_dir
+                    )
+                })?;
+            // Stable, so rows with equal times keep their dedup order
+            let mut order: Vec<u32> = (0..batch.num_rows() as u32).collect();
+            order.sort_by_key(|&row| times.value(row as usize));
+            batch = take_record_batch(&batch, &UInt32Array::from(order))
+                .context("Failed to sort partition rows")?;
+        }
+
+        // Write to a temporary file, then atomically replace data.parquet and drop the fragments
+        let target_path = format!("{}/data.parquet", partition_dir);
+        let temp_path = format!("{}.tmp", target_path);
+        let props = WriterProperties::builder()
+            .set_compression(compression.to_parquet()?)
+            .set_max_row_group_size(ARCHIVER_COMPACTION_ROW_GROUP_SIZE)
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            fs::File::create(&temp_path).context("Failed to create temp file")?,
+            schema,
+            Some(props),
+        )
+        .context("Failed to create ArrowWriter")?;
+        writer.write(&batch).context("Failed to write batch")?;
+        writer.close().context("Failed to close writer")?;
+        fs::rename(&temp_path, &target_path).map_err(|e| {
+            anyhow::anyhow!("Failed to rename {} to {}: {}", temp_path, target_path, e)
+        })?;
+        for file in &files {
+            if file != Path::new(&target_path) {
+                fs::remove_file(file)
+                    .map_err(|e| anyhow::anyhow!("Failed to remove fragment {:?}: {}", file, e))?;
+            }
+        }
+
+        info!(
+            partition = partition_dir,
+            files = files.len(),
+            records = batch.num_rows(),
+            dropped,
+            "compacted partition"
+        );
+        Ok(batch.num_rows())
     }
 
     /// Swaps the source directory to a new one
@@ -509,7 +1259,7 @@ impl Archiver {
         let mut guard = self.src_dir.write().unwrap();
         *guard = new_src_dir.to_string();
 
-        println!("Swapped source directory to: {}", new_src_dir);
+        info!(src_dir = new_src_dir, "swapped source directory");
         Ok(())
     }
 
@@ -519,8 +1269,8 @@ impl Archiver {
         guard.clone()
     }
 
-    /// Returns time since last update for each data type and symbol
-    pub fn time_since_last_update(&self) -> HashMap<String, HashMap<TradingSymbol, Duration>> {
+    /// Returns time since last update for each data type and (market, symbol)
+    pub fn time_since_last_update(&self) -> HashMap<String, HashMap<SymbolKey, Duration>> {
         let updates = self.last_updates.read().unwrap();
         let now = Local::now();
 
@@ -528,9 +1278,9 @@ impl Archiver {
 
         for (data_type, symbols) in updates.iter() {
             let mut symbol_durations = HashMap::new();
-            for (symbol, last_time) in symbols {
+            for (key, last_time) in symbols {
                 let duration = now.signed_duration_since(*last_time);
-                symbol_durations.insert(symbol.clone(), duration);
+                symbol_durations.insert(key.clone(), duration);
             }
             result.insert(data_type.clone(), symbol_durations);
         }
@@ -538,16 +1288,57 @@ impl Archiver {
         result
     }
 
+    /// Runs one scan/flush cycle for every data type now, outside the timer, and returns the number of files flushed.
+    /// Unlike the timer, it also flushes each symbol's newest file: call it once the writers have stopped
+    /// (e.g. after the scribe's shutdown) so their final flush reaches the hive layout.
+    /// Error: a data type directory is unreadable or a file fails to flush; the remaining files are still attempted.
+    pub async fn flush_now(&self) -> OrError<usize> {
+        let src_dir = self.src_dir();
+        let mut flushed = 0;
+        let mut failed = 0;
+        for data_type in &self.data_types {
+            // A data type nothing was written for has nothing to flush
+            if !Path::new(&format!("{}/{}", src_dir, data_type)).is_dir() {
+                continue;
+            }
+            let _pass = self.pass_locks[data_type].lock().await;
+            let (type_flushed, type_failed) = Self::archive_pass(
+                data_type,
+                &src_dir,
+                &self.target_dir,
+                self.compression,
+                self.dedup_keys.get(data_type),
+                self.non_finite,
+                self.partition_tz,
+                self.fixed_depth(data_type),
+                self.template,
+                &self.last_updates,
+                true,
+            )
+            .await?;
+            flushed += type_flushed;
+            failed += type_failed;
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "Archiver flush_now error: {} files in {} failed to flush",
+                failed,
+                src_dir
+            ));
+        }
+        Ok(flushed)
+    }
+
     /// Gracefully shutdown the archiver
     pub async fn shutdown(&mut self) -> OrError<()> {
-        println!("Shutting down Archiver...");
+        info!("shutting down archiver");
 
         let handles = std::mem::take(&mut self.bg_handles);
         for handle in handles {
             handle.abort();
         }
 
-        println!("Archiver shutdown complete");
+        info!("archiver shutdown complete");
         Ok(())
     }
 
@@ -555,6 +1346,26 @@ impl Archiver {
         &self.target_dir
     }
 
+    pub fn compression(&self) -> ParquetCompressionConfig {
+        self.compression
+    }
+
+    pub fn non_finite_policy(&self) -> NonFinitePolicy {
+        self.non_finite
+    }
+
+    pub fn partition_tz(&self) -> PartitionTz {
+        self.partition_tz
+    }
+
+    pub fn fixed_depth(&self, data_type: &str) -> Option<usize> {
+        self.fixed_depths.get(data_type).copied()
+    }
+
+    pub fn dedup_key(&self, data_type: &str) -> Option<&DedupKey> {
+        self.dedup_keys.get(data_type)
+    }
+
     pub fn data_types(&self) -> Vec<&str> {
         self.data_types.iter().map(|s| s.as_str()).collect()
     }
@@ -565,9 +1376,9 @@ impl Drop for Archiver {
         for handle in &self.bg_handles {
             handle.abort();
         }
-        eprintln!(
-            "Warning: Archiver dropped without calling shutdown(). {} background tasks aborted.",
-            self.bg_handles.len()
+        warn!(
+            tasks = self.bg_handles.len(),
+            "Archiver dropped without calling shutdown(); background tasks aborted"
         );
     }
 }
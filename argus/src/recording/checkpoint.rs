@@ -0,0 +1,375 @@
+// Periodic full-book checkpoints for diff-based orderbook streams
+//
+// OrderbookEngine folds incremental diffs into a full book and emits a journal: every diff is
+// recorded as-is, and every N updates or T seconds a full snapshot of the book is interleaved
+// (is_snapshot = true). Reconstructing the book at time t then only needs the last checkpoint at
+// or before t plus the diffs recorded after it, instead of replaying the whole day.
+
+use super::{ArgusParquetable, ArgusReplayable, batch_column, downcast_column, millis_to_utc};
+use crate::constants::{ORDERBOOK_CHECKPOINT_EVERY_SECONDS, ORDERBOOK_CHECKPOINT_EVERY_UPDATES};
+use crate::crypto::binance::OrderbookDiffUpdate;
+use crate::types::{Price, TradingSymbol};
+use agora::Agorable;
+use agora::utils::OrError;
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use indoc::writedoc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// When OrderbookEngine interleaves a full snapshot into its journal.
+/// A checkpoint is emitted once either threshold is reached.
+#[derive(Debug, Clone, Copy)]
+pub struct CheckpointPolicy {
+    pub every_updates: usize,
+    pub every_duration: Duration,
+}
+
+impl Default for CheckpointPolicy {
+    fn default() -> Self {
+        Self {
+            every_updates: ORDERBOOK_CHECKPOINT_EVERY_UPDATES,
+            every_duration: Duration::seconds(ORDERBOOK_CHECKPOINT_EVERY_SECONDS),
+        }
+    }
+}
+
+/// One row of an orderbook journal.
+///
+/// Snapshots carry the full book (best level first). Diffs carry only the changed levels,
+/// where a size of 0 removes the level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookJournalEntry {
+    pub symbol: TradingSymbol,
+    pub time: DateTime<Utc>,
+    pub is_snapshot: bool,
+    pub bids: Vec<(Price, f64)>,
+    pub asks: Vec<(Price, f64)>,
+}
+
+impl fmt::Display for BookJournalEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writedoc!(
+            f,
+            "(
+                BookJournalEntry
+                Symbol: {}
+                Time: {}
+                Kind: {}
+                Bid levels: {}
+                Ask levels: {}
+            )",
+            self.symbol.as_str(),
+            self.time,
+            if self.is_snapshot { "snapshot" } else { "diff" },
+            self.bids.len(),
+            self.asks.len()
+        )
+    }
+}
+
+impl Agorable for BookJournalEntry {}
+
+/// Full orderbook state, keyed by price
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookState {
+    bids: BTreeMap<u64, (Price, f64)>,
+    asks: BTreeMap<u64, (Price, f64)>,
+}
+
+impl BookState {
+    /// Prices are finite and nonnegative, so their bit patterns sort like the values themselves.
+    /// Adding 0.0 folds -0.0 into 0.0.
+    fn level_key(price: &Price) -> u64 {
+        (price.to_f64() + 0.0).to_bits()
+    }
+
+    fn apply_side(side: &mut BTreeMap<u64, (Price, f64)>, levels: &[(Price, f64)]) {
+        for (price, size) in levels {
+            let key = Self::level_key(price);
+            if *size == 0.0 {
+                side.remove(&key);
+            } else {
+                side.insert(key, (price.clone(), *size));
+            }
+        }
+    }
+
+    /// Applies a diff: levels with size 0 are removed, others are replaced
+    pub fn apply(&mut self, bids: &[(Price, f64)], asks: &[(Price, f64)]) {
+        Self::apply_side(&mut self.bids, bids);
+        Self::apply_side(&mut self.asks, asks);
+    }
+
+    /// Builds a book from full snapshot levels
+    pub fn from_levels(bids: &[(Price, f64)], asks: &[(Price, f64)]) -> Self {
+        let mut book = Self::default();
+        book.apply(bids, asks);
+        book
+    }
+
+    /// Bid levels, highest price first
+    pub fn bids(&self) -> Vec<(Price, f64)> {
+        self.bids.values().rev().cloned().collect()
+    }
+
+    /// Ask levels, lowest price first
+    pub fn asks(&self) -> Vec<(Price, f64)> {
+        self.asks.values().cloned().collect()
+    }
+}
+
+/// Maintains the full book for one symbol from a diff stream and emits the checkpointed journal.
+pub struct OrderbookEngine {
+    symbol: TradingSymbol,
+    book: BookState,
+    policy: CheckpointPolicy,
+    updates_since_checkpoint: usize,
+    last_checkpoint: Option<DateTime<Utc>>,
+}
+
+impl OrderbookEngine {
+    pub fn new(symbol: TradingSymbol, policy: CheckpointPolicy) -> OrError<Self> {
+        if policy.every_updates == 0 {
+            return Err(anyhow::anyhow!(
+                "OrderbookEngine error: every_updates must be positive"
+            ));
+        }
+        if policy.every_duration <= Duration::zero() {
+            return Err(anyhow::anyhow!(
+                "OrderbookEngine error: every_duration must be positive"
+            ));
+        }
+        Ok(Self {
+            symbol,
+            book: BookState::default(),
+            policy,
+            updates_since_checkpoint: 0,
+            last_checkpoint: None,
+        })
+    }
+
+    pub fn book(&self) -> &BookState {
+        &self.book
+    }
+
+    /// Applies one diff and returns the journal entries it produces: the diff itself,
+    /// followed by a snapshot when a checkpoint is due. The first diff always checkpoints.
+    pub fn ingest(
+        &mut self,
+        time: DateTime<Utc>,
+        bids: Vec<(Price, f64)>,
+        asks: Vec<(Price, f64)>,
+    ) -> Vec<BookJournalEntry> {
+        self.book.apply(&bids, &asks);
+        self.updates_since_checkpoint += 1;
+
+        let mut entries = vec![BookJournalEntry {
+            symbol: self.symbol.clone(),
+            time,
+            is_snapshot: false,
+            bids,
+            asks,
+        }];
+        let due = match self.last_checkpoint {
+            None => true,
+            Some(last) => {
+                self.updates_since_checkpoint >= self.policy.every_updates
+                    || time - last >= self.policy.every_duration
+            }
+        };
+        if due {
+            entries.push(self.checkpoint(time));
+        }
+        entries
+    }
+
+    /// Convenience wrapper for Binance depth diffs, keyed on exchange event time
+    pub fn ingest_binance_diff(&mut self, diff: &OrderbookDiffUpdate) -> Vec<BookJournalEntry> {
+        self.ingest(diff.event_time, diff.bids.clone(), diff.asks.clone())
+    }
+
+    /// Forces a snapshot of the current book and resets the checkpoint counters
+    pub fn checkpoint(&mut self, time: DateTime<Utc>) -> BookJournalEntry {
+        self.updates_since_checkpoint = 0;
+        self.last_checkpoint = Some(time);
+        BookJournalEntry {
+            symbol: self.symbol.clone(),
+            time,
+            is_snapshot: true,
+            bids: self.book.bids(),
+            asks: self.book.asks(),
+        }
+    }
+}
+
+/// Reconstructs the book as of `target` from a journal in recorded order.
+///
+/// Seeks to the last checkpoint at or before `target` and applies only the diffs after it.
+/// Without such a checkpoint, every diff up to `target` is applied to an empty book.
+pub fn reconstruct_book_at(journal: &[BookJournalEntry], target: DateTime<Utc>) -> BookState {
+    let end = journal.partition_point(|entry| entry.time <= target);
+    let start = journal[..end].iter().rposition(|entry| entry.is_snapshot);
+
+    let (mut book, diffs) = match start {
+        Some(i) => (
+            BookState::from_levels(&journal[i].bids, &journal[i].asks),
+            &journal[i + 1..end],
+        ),
+        None => (BookState::default(), &journal[..end]),
+    };
+    for entry in diffs.iter().filter(|entry| !entry.is_snapshot) {
+        book.apply(&entry.bids, &entry.asks);
+    }
+    book
+}
+
+/// Reads a journal parquet file and reconstructs the book as of `target`
+pub fn read_book_at(path: &str, target: DateTime<Utc>) -> OrError<BookState> {
+    let journal = super::read_replayable_parquet::<BookJournalEntry>(path)?;
+    Ok(reconstruct_book_at(&journal, target))
+}
+
+fn level_fields() -> Vec<arrow::datatypes::Field> {
+    use arrow::datatypes::{DataType, Field};
+    vec![
+        Field::new("price", DataType::Float64, false),
+        Field::new("size", DataType::Float64, false),
+    ]
+}
+
+/// Builds a List<Struct{price, size}> column from one side of every entry
+fn levels_to_list(
+    data: &[BookJournalEntry],
+    side: fn(&BookJournalEntry) -> &Vec<(Price, f64)>,
+) -> OrError<arrow::array::ListArray> {
+    use arrow::array::{ArrayRef, Float64Array, StructArray};
+    use arrow::datatypes::{DataType, Field};
+    use std::sync::Arc;
+
+    let mut prices = Vec::new();
+    let mut sizes = Vec::new();
+    let mut offsets = vec![0i32];
+    for entry in data {
+        for (price, size) in side(entry) {
+            prices.push(price.to_f64());
+            sizes.push(*size);
+        }
+        offsets.push(prices.len() as i32);
+    }
+
+    let levels = StructArray::from(vec![
+        (
+            Arc::new(Field::new("price", DataType::Float64, false)),
+            Arc::new(Float64Array::from(prices)) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("size", DataType::Float64, false)),
+            Arc::new(Float64Array::from(sizes)) as ArrayRef,
+        ),
+    ]);
+    arrow::array::ListArray::try_new(
+        Arc::new(Field::new("item", DataType::Struct(level_fields().into()), false)),
+        arrow::buffer::OffsetBuffer::new(arrow::buffer::ScalarBuffer::from(offsets)),
+        Arc::new(levels),
+        None,
+    )
+    .context("Failed to create levels ListArray")
+}
+
+/// Reads row `row` of a List<Struct{price, size}> column back into levels
+fn levels_from_list(list: &arrow::array::ListArray, row: usize) -> OrError<Vec<(Price, f64)>> {
+    use arrow::array::{Array, Float64Array, StructArray};
+
+    let entries = list.value(row);
+    let levels = entries
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .ok_or_else(|| anyhow::anyhow!("Journal levels are not a struct array"))?;
+    let prices = downcast_column::<Float64Array>(levels.column_by_name("price"), "price")?;
+    let sizes = downcast_column::<Float64Array>(levels.column_by_name("size"), "size")?;
+
+    (0..levels.len())
+        .map(|i| Ok((Price::from_f64(prices.value(i))?, sizes.value(i))))
+        .collect()
+}
+
+impl ArgusParquetable for BookJournalEntry {
+    fn arrow_schema() -> std::sync::Arc<arrow::datatypes::Schema> {
+        use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+        use std::sync::Arc;
+
+        let levels = DataType::List(Arc::new(Field::new(
+            "item",
+            DataType::Struct(level_fields().into()),
+            false,
+        )));
+        Arc::new(Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                false,
+            ),
+            Field::new("is_snapshot", DataType::Boolean, false),
+            Field::new("bids", levels.clone(), false),
+            Field::new("asks", levels, false),
+        ]))
+    }
+
+    fn to_record_batch(data: Vec<Self>) -> OrError<arrow::record_batch::RecordBatch> {
+        use arrow::array::{ArrayRef, BooleanArray, StringArray, TimestampMillisecondArray};
+        use arrow::record_batch::RecordBatch;
+        use std::sync::Arc;
+
+        let symbols: ArrayRef = Arc::new(StringArray::from(
+            data.iter().map(|d| d.symbol.as_str()).collect::<Vec<_>>(),
+        ));
+        let times: ArrayRef = Arc::new(
+            TimestampMillisecondArray::from(
+                data.iter().map(|d| d.time.timestamp_millis()).collect::<Vec<_>>(),
+            )
+            .with_timezone("UTC"),
+        );
+        let is_snapshot: ArrayRef = Arc::new(BooleanArray::from(
+            data.iter().map(|d| d.is_snapshot).collect::<Vec<_>>(),
+        ));
+        let bids: ArrayRef = Arc::new(levels_to_list(&data, |d| &d.bids)?);
+        let asks: ArrayRef = Arc::new(levels_to_list(&data, |d| &d.asks)?);
+
+        RecordBatch::try_new(
+            Self::arrow_schema(),
+            vec![symbols, times, is_snapshot, bids, asks],
+        )
+        .context("Failed to create RecordBatch")
+    }
+}
+
+impl ArgusReplayable for BookJournalEntry {
+    fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> OrError<Vec<Self>> {
+        use arrow::array::{BooleanArray, ListArray, StringArray, TimestampMillisecondArray};
+
+        let symbols = batch_column::<StringArray>(batch, "symbol")?;
+        let times = batch_column::<TimestampMillisecondArray>(batch, "time")?;
+        let is_snapshot = batch_column::<BooleanArray>(batch, "is_snapshot")?;
+        let bids = batch_column::<ListArray>(batch, "bids")?;
+        let asks = batch_column::<ListArray>(batch, "asks")?;
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(BookJournalEntry {
+                    symbol: TradingSymbol::from_str(symbols.value(i))?,
+                    time: millis_to_utc(times.value(i))?,
+                    is_snapshot: is_snapshot.value(i),
+                    bids: levels_from_list(bids, i)?,
+                    asks: levels_from_list(asks, i)?,
+                })
+            })
+            .collect()
+    }
+
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.time
+    }
+}
@@ -0,0 +1,124 @@
+// Parquet compression codec shared by every writer in the recording layer
+//
+// Archiver (argus), BinanceTradeBook and the L2 book exporter (mnemosyne) all take this config, so
+// storage-vs-speed tradeoffs are chosen per call site instead of being hardcoded per writer.
+
+use agora::utils::OrError;
+use parquet::basic::{BrotliLevel, Compression, ZstdLevel};
+use std::fmt;
+
+pub const ZSTD_LEVELS: std::ops::RangeInclusive<i32> = 1..=22;
+pub const BROTLI_LEVELS: std::ops::RangeInclusive<u32> = 0..=11;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetCompressionConfig {
+    Zstd(i32),
+    Lz4,
+    Brotli(u32),
+    Snappy,
+    Uncompressed,
+}
+
+impl Default for ParquetCompressionConfig {
+    /// ZSTD level 3: good balance of compression ratio and speed for time-series data
+    fn default() -> Self {
+        Self::Zstd(3)
+    }
+}
+
+impl ParquetCompressionConfig {
+    pub fn zstd(level: i32) -> OrError<Self> {
+        Self::Zstd(level).validated()
+    }
+
+    pub fn brotli(level: u32) -> OrError<Self> {
+        Self::Brotli(level).validated()
+    }
+
+    /// Returns self if the level (if any) is in the codec's supported range
+    pub fn validated(self) -> OrError<Self> {
+        match self {
+            Self::Zstd(level) if !ZSTD_LEVELS.contains(&level) => Err(anyhow::anyhow!(
+                "ParquetCompressionConfig error: zstd level {} out of range {}..={}",
+                level,
+                ZSTD_LEVELS.start(),
+                ZSTD_LEVELS.end()
+            )),
+            Self::Brotli(level) if !BROTLI_LEVELS.contains(&level) => Err(anyhow::anyhow!(
+                "ParquetCompressionConfig error: brotli level {} out of range {}..={}",
+                level,
+                BROTLI_LEVELS.start(),
+                BROTLI_LEVELS.end()
+            )),
+            _ => Ok(self),
+        }
+    }
+
+    /// Parses `zstd`, `zstd:5`, `lz4`, `brotli`, `brotli:3`, `snappy` or `uncompressed`.
+    /// Codecs with levels default to level 3 when none is given.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> OrError<Self> {
+        let normalized = s.trim().to_ascii_lowercase();
+        let (codec, level) = match normalized.split_once(':') {
+            Some((codec, level)) => (codec, Some(level)),
+            None => (normalized.as_str(), None),
+        };
+        let parse_level = |level: Option<&str>| -> OrError<i64> {
+            match level {
+                None => Ok(3),
+                Some(level) => level.parse().map_err(|e| {
+                    anyhow::anyhow!(
+                        "ParquetCompressionConfig error: invalid level '{}' in '{}': {}",
+                        level,
+                        s,
+                        e
+                    )
+                }),
+            }
+        };
+        let no_level = |config: Self| -> OrError<Self> {
+            match level {
+                None => Ok(config),
+                Some(_) => Err(anyhow::anyhow!(
+                    "ParquetCompressionConfig error: codec '{}' does not take a level",
+                    codec
+                )),
+            }
+        };
+        match codec {
+            "zstd" => Self::zstd(i32::try_from(parse_level(level)?)?),
+            "brotli" => Self::brotli(u32::try_from(parse_level(level)?)?),
+            "lz4" => no_level(Self::Lz4),
+            "snappy" => no_level(Self::Snappy),
+            "uncompressed" | "none" => no_level(Self::Uncompressed),
+            _ => Err(anyhow::anyhow!(
+                "ParquetCompressionConfig error: unknown codec '{}'. Expected one of zstd, lz4, brotli, snappy, uncompressed",
+                codec
+            )),
+        }
+    }
+
+    /// Codec for the `parquet` crate's WriterProperties. LZ4 maps to LZ4_RAW, the
+    /// non-deprecated framing that polars and DuckDB also write.
+    pub fn to_parquet(&self) -> OrError<Compression> {
+        Ok(match self.validated()? {
+            Self::Zstd(level) => Compression::ZSTD(ZstdLevel::try_new(level)?),
+            Self::Lz4 => Compression::LZ4_RAW,
+            Self::Brotli(level) => Compression::BROTLI(BrotliLevel::try_new(level)?),
+            Self::Snappy => Compression::SNAPPY,
+            Self::Uncompressed => Compression::UNCOMPRESSED,
+        })
+    }
+}
+
+impl fmt::Display for ParquetCompressionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Zstd(level) => write!(f, "zstd:{}", level),
+            Self::Lz4 => write!(f, "lz4"),
+            Self::Brotli(level) => write!(f, "brotli:{}", level),
+            Self::Snappy => write!(f, "snappy"),
+            Self::Uncompressed => write!(f, "uncompressed"),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+// Layout of the temporary files scribes flush and the archiver collects.
+//
+// Both sides go through one FilePathTemplate: `AgoraDirScribe` renders `{flush_path}_{time}Z.pq` with it and
+// `Archiver` parses the same names back into (data type, market, symbol, time), so the two cannot drift apart.
+
+use crate::types::TradingSymbol;
+use agora::utils::OrError;
+use chrono::{DateTime, Duration, Local, LocalResult, NaiveDateTime, TimeZone, Utc};
+use std::path::Path;
+
+/// Separates an optional market tag from the symbol in temporary filenames: `{market}.{symbol}_{time}.pq`
+pub(crate) const MARKET_SEPARATOR: char = '.';
+
+/// Format of the flush time in temporary filenames, `YY-MM-DD HH:MM:SS`
+pub(crate) const TMP_FILE_TIME_FORMAT: &str = "%y-%m-%d %H:%M:%S";
+
+/// Marks a flush time as UTC: `{stem}_{YY-MM-DD HH:MM:SS}Z.pq`.
+/// Files without it carry the host's local time (written before scribes switched to UTC).
+pub(crate) const TMP_FILE_UTC_SUFFIX: char = 'Z';
+
+pub(crate) fn is_market_tag(market: &str) -> bool {
+    !market.is_empty()
+        && market
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+/// Temporary file layout: `{output_dir}/{stem}{time_separator}{time}Z.{extension}` inside a `{data_type}` directory,
+/// where the stem is `{symbol}`, or `{market}{market_separator}{symbol}` for market-tagged scribes.
+/// The default is the layout scribes have always written, `[{market}.]{symbol}_{YY-MM-DD HH:MM:SS}Z.pq`.
+/// A scribe and the archiver collecting its files must be given the same template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilePathTemplate {
+    /// Between the market tag and the symbol; must not be a lowercase ASCII alphanumeric
+    pub market_separator: char,
+    /// Between the stem and the flush time
+    pub time_separator: char,
+    /// chrono format of the flush time (UTC). Must render at a fixed width and parse back to the second.
+    pub time_format: &'static str,
+    /// File extension, without the dot
+    pub extension: &'static str,
+}
+
+impl Default for FilePathTemplate {
+    fn default() -> Self {
+        Self {
+            market_separator: MARKET_SEPARATOR,
+            time_separator: '_',
+            time_format: TMP_FILE_TIME_FORMAT,
+            extension: "pq",
+        }
+    }
+}
+
+impl FilePathTemplate {
+    /// Checks that filenames rendered with this template parse back unambiguously.
+    /// Error: market separator is a tag character, time format is variable-width or loses precision,
+    /// or the extension is empty or contains a dot or path separator
+    pub fn validated(self) -> OrError<Self> {
+        let error = |reason: String| anyhow::anyhow!("Argus FilePathTemplate error: {}", reason);
+        if is_market_tag(&self.market_separator.to_string()) || self.market_separator == '/' {
+            return Err(error(format!(
+                "market separator {:?} would be read as part of a market tag or path",
+                self.market_separator
+            )));
+        }
+        if self.extension.is_empty() || self.extension.contains(['.', '/']) {
+            return Err(error(format!("invalid extension {:?}", self.extension)));
+        }
+        // Longest and shortest month and weekday names, single and double digits everywhere.
+        // Years stay within the range a two-digit `%y` parses back to (2000-2068).
+        let samples = [
+            Utc.with_ymd_and_hms(2001, 2, 3, 4, 5, 6).unwrap(),
+            Utc.with_ymd_and_hms(2025, 9, 24, 13, 59, 58).unwrap(),
+            Utc.with_ymd_and_hms(2068, 12, 31, 23, 59, 59).unwrap(),
+        ];
+        let width = self.time_width();
+        for sample in samples {
+            let rendered = sample.format(self.time_format).to_string();
+            if rendered.len() != width || rendered.contains('/') {
+                return Err(error(format!(
+                    "time format {:?} does not render at a fixed width without '/'",
+                    self.time_format
+                )));
+            }
+            match NaiveDateTime::parse_from_str(&rendered, self.time_format) {
+                Ok(parsed) if parsed == sample.naive_utc() => {}
+                _ => {
+                    return Err(error(format!(
+                        "time format {:?} does not parse back to the second",
+                        self.time_format
+                    )));
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Width of a rendered flush time
+    fn time_width(&self) -> usize {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0)
+            .unwrap()
+            .format(self.time_format)
+            .to_string()
+            .len()
+    }
+
+    /// Filename stem of `symbol`'s files: `{symbol}`, or `{market}{market_separator}{symbol}` when tagged.
+    /// Markets must be non-empty lowercase ASCII alphanumerics so the archiver can split them back off unambiguously.
+    pub fn stem(&self, symbol: &TradingSymbol, market: Option<&str>) -> OrError<String> {
+        match market {
+            None => Ok(symbol.to_string()),
+            Some(market) if is_market_tag(market) => Ok(format!(
+                "{}{}{}",
+                market,
+                self.market_separator,
+                symbol.to_string()
+            )),
+            Some(market) => Err(anyhow::anyhow!(
+                "Argus scribe error: invalid market tag {:?}; expected lowercase ASCII alphanumerics",
+                market
+            )),
+        }
+    }
+
+    /// Path of the file flushed at `time` for `flush_path` (`{output_dir}/{stem}`)
+    pub fn file_path(&self, flush_path: &str, time: DateTime<Utc>) -> String {
+        format!(
+            "{}{}{}{}.{}",
+            flush_path,
+            self.time_separator,
+            time.format(self.time_format),
+            TMP_FILE_UTC_SUFFIX,
+            self.extension
+        )
+    }
+
+    /// Whether `path` carries this template's extension, i.e. could be a temporary file
+    pub fn matches_extension(&self, path: &Path) -> bool {
+        path.extension().and_then(|s| s.to_str()) == Some(self.extension)
+    }
+
+    /// Parses a temporary filepath into (data type, market, symbol, flush time); inverse of `stem` + `file_path`.
+    /// The data type is the parent directory's name. Market is `None` for untagged stems.
+    /// The time is UTC; without the `Z` it is host-local (older scribes), see `local_flush_time`.
+    pub fn parse(
+        &self,
+        filepath: &str,
+    ) -> OrError<(String, Option<String>, TradingSymbol, DateTime<Local>)> {
+        let path = Path::new(filepath);
+
+        // Get the parent directory name (data_type)
+        let data_type = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Cannot extract data type from path: {}", filepath))?
+            .to_string();
+
+        // Get the filename
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Cannot extract filename from path: {}", filepath))?;
+
+        // Remove the extension
+        let without_ext = filename
+            .strip_suffix(self.extension)
+            .and_then(|name| name.strip_suffix('.'))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "File does not have .{} extension: {}",
+                    self.extension,
+                    filename
+                )
+            })?;
+        let (without_ext, is_utc) = match without_ext.strip_suffix(TMP_FILE_UTC_SUFFIX) {
+            Some(stamped) => (stamped, true),
+            None => (without_ext, false),
+        };
+
+        // The format is {stem}{time_separator}{time}, the time always `time_width` bytes long
+        let width = self.time_width();
+        let separator_width = self.time_separator.len_utf8();
+        if without_ext.len() < width + separator_width + 1 {
+            return Err(anyhow::anyhow!(
+                "Filename too short to contain timestamp: {}",
+                filename
+            ));
+        }
+        let split_pos = without_ext.len() - width;
+        let stem = without_ext
+            .get(..split_pos)
+            .and_then(|stem| stem.strip_suffix(self.time_separator))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Expected {:?} before timestamp in: {}",
+                    self.time_separator,
+                    filename
+                )
+            })?;
+        let timestamp_str = &without_ext[split_pos..];
+
+        // Optional market tag: only a valid tag before the first separator counts, so untagged symbols parse as before
+        let (market, symbol_str) = match stem.split_once(self.market_separator) {
+            Some((market, symbol)) if is_market_tag(market) => (Some(market.to_string()), symbol),
+            _ => (None, stem),
+        };
+
+        // Parse symbol
+        let symbol = TradingSymbol::from_str(symbol_str)?;
+
+        // Parse timestamp using the scribe's format
+        let naive_dt = NaiveDateTime::parse_from_str(timestamp_str, self.time_format)
+            .map_err(|e| anyhow::anyhow!("Failed to parse timestamp '{}': {}", timestamp_str, e))?;
+
+        let dt = if is_utc {
+            Utc.from_utc_datetime(&naive_dt).with_timezone(&Local)
+        } else {
+            local_flush_time(&naive_dt)?
+        };
+
+        Ok((data_type, market, symbol, dt))
+    }
+}
+
+/// Instant of a host-local flush time, as scribes wrote them before stamping UTC.
+/// Resolves DST transitions instead of failing, which would leave the file in tmp forever: a repeated wall
+/// time (clocks set back) is its earlier instant, a skipped one (clocks set forward) is read at the offset in
+/// force before the gap.
+fn local_flush_time(naive_dt: &NaiveDateTime) -> OrError<DateTime<Local>> {
+    match Local.from_local_datetime(naive_dt) {
+        LocalResult::Single(dt) => Ok(dt),
+        // Recheck both candidates through their UTC instant: with a POSIX `TZ` rule chrono neither orders them
+        // nor places the repeated hour exactly (it reports the first wall time after it as ambiguous too)
+        LocalResult::Ambiguous(a, b) => [a, b]
+            .into_iter()
+            .filter(|dt| dt.with_timezone(&Utc).with_timezone(&Local).naive_local() == *naive_dt)
+            .min()
+            .ok_or_else(|| anyhow::anyhow!("No local instant for {}", naive_dt)),
+        LocalResult::None => {
+            let offset = Local
+                .offset_from_local_datetime(&(*naive_dt - Duration::days(1)))
+                .earliest()
+                .ok_or_else(|| anyhow::anyhow!("No local offset before {}", naive_dt))?;
+            let utc = *naive_dt - Duration::seconds(offset.local_minus_utc() as i64);
+            Ok(Utc.from_utc_datetime(&utc).with_timezone(&Local))
+        }
+    }
+}
@@ -0,0 +1,286 @@
+// Replays archived hive-partitioned parquet back onto agora paths
+//
+// Input: the hive directory for one data type, as written by the Archiver, e.g.
+//   archive_dir = {output_dir}/perp/last_trade
+//   containing /date={date}/symbol={symbol}/data.parquet
+//
+// Output: one Publisher<AgorableOption<T>> per symbol at {agora_prefix}/{symbol} (same layout as
+// HyperliquidPublisher's stable paths), unless overridden per symbol. Subscribers written against the
+// live stream work unchanged against the replay.
+
+use super::{ArgusParquetable, parquet_schema_version};
+use crate::types::TradingSymbol;
+use agora::utils::OrError;
+use agora::{Agorable, AgorableOption, ConnectionHandle, Publisher};
+use anyhow::Context;
+use arrow::array::{Array, ArrayRef};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, NaiveDate, Utc};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::collections::HashMap;
+use std::fs;
+use std::marker::PhantomData;
+use std::path::Path;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Types that can be reconstructed from the record batches they were archived as.
+pub trait ArgusReplayable: ArgusParquetable + Agorable {
+    /// Inverse of `ArgusParquetable::to_record_batch`.
+    fn from_record_batch(batch: &RecordBatch) -> OrError<Vec<Self>>;
+
+    /// Reads a batch from a file written at `schema_version` (see `parquet_schema_version`).
+    /// The default reads the current layout and refuses archives newer than `Self::SCHEMA_VERSION`.
+    /// Override when the layout changes so older archives keep reading.
+    ///
+    /// Migration example: `OrderbookSnapshot` gains `sequence: Option<u64>`. Bump
+    /// `impl Agorable for OrderbookSnapshot { const SCHEMA_VERSION: u32 = 2; }`, add the column to
+    /// `arrow_schema`/`to_record_batch`, and fill the field in for v1 files:
+    /// ```ignore
+    /// fn from_versioned_record_batch(batch: &RecordBatch, schema_version: u32) -> OrError<Vec<Self>> {
+    ///     match schema_version {
+    ///         // v1 archives have no sequence column
+    ///         1 => read_v1_columns(batch),
+    ///         2 => Self::from_record_batch(batch),
+    ///         v => Err(anyhow::anyhow!("Unsupported OrderbookSnapshot schema version {}", v)),
+    ///     }
+    /// }
+    /// ```
+    fn from_versioned_record_batch(batch: &RecordBatch, schema_version: u32) -> OrError<Vec<Self>> {
+        if schema_version > Self::SCHEMA_VERSION {
+            return Err(anyhow::anyhow!(
+                "Archive schema version {} is newer than {} version {}",
+                schema_version,
+                std::any::type_name::<Self>(),
+                Self::SCHEMA_VERSION
+            ));
+        }
+        Self::from_record_batch(batch)
+    }
+
+    /// Timestamp used to pace replay. Should be the local arrival time, so replay reproduces
+    /// the inter-arrival gaps live subscribers originally observed.
+    fn replay_time(&self) -> DateTime<Utc>;
+}
+
+/// Downcasts an optional column (from `RecordBatch::column_by_name` or `StructArray::column_by_name`).
+pub(crate) fn downcast_column<'a, A: Array + 'static>(
+    column: Option<&'a ArrayRef>,
+    name: &str,
+) -> OrError<&'a A> {
+    column
+        .ok_or_else(|| anyhow::anyhow!("Missing column {}", name))?
+        .as_any()
+        .downcast_ref::<A>()
+        .ok_or_else(|| anyhow::anyhow!("Column {} has unexpected type", name))
+}
+
+pub(crate) fn batch_column<'a, A: Array + 'static>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> OrError<&'a A> {
+    downcast_column(batch.column_by_name(name), name)
+}
+
+pub(crate) fn millis_to_utc(millis: i64) -> OrError<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(millis)
+        .ok_or_else(|| anyhow::anyhow!("Invalid millisecond timestamp {}", millis))
+}
+
+/// Reads every record batch of a parquet file back into `T`, in file order,
+/// dispatching on the file's schema version (see `ArgusReplayable::from_versioned_record_batch`).
+pub fn read_replayable_parquet<T: ArgusReplayable>(path: &str) -> OrError<Vec<T>> {
+    let file = fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .with_context(|| format!("Failed to create reader for {}", path))?;
+    let schema_version = parquet_schema_version(builder.schema())
+        .with_context(|| format!("Failed to read schema version of {}", path))?;
+    let reader = builder
+        .build()
+        .with_context(|| format!("Failed to build reader for {}", path))?;
+
+    let mut values = Vec::new();
+    for batch in reader {
+        let batch = batch.with_context(|| format!("Failed to read batch from {}", path))?;
+        values.extend(T::from_versioned_record_batch(&batch, schema_version)?);
+    }
+    Ok(values)
+}
+
+/// Republishes archived data for a set of symbols and dates onto agora.
+///
+/// Records of all symbols are merged and replayed in `replay_time` order, one date at a time.
+/// `speed` scales the original inter-arrival gaps: 1.0 is real time, 10.0 is ten times faster,
+/// and 0.0 publishes as fast as possible.
+pub struct ReplayPublisher<T: ArgusReplayable> {
+    agora_paths: Vec<String>,
+    replay_handle: JoinHandle<()>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: ArgusReplayable> ReplayPublisher<T> {
+    /// # Arguments
+    /// * `archive_dir` - Hive directory of one data type (e.g., "{output_dir}/perp/last_trade")
+    /// * `agora_prefix` - Prefix to publish under (e.g., "argus/hyperliquid/perp/last_trade")
+    /// * `symbols` - Symbols to replay; must match the `symbol={symbol}` partitions
+    /// * `dates` - Dates to replay, in order
+    /// * `symbol_paths` - Per-symbol destination overrides; symbols not in the map publish to `{agora_prefix}/{symbol}`
+    /// * `speed` - Replay speed multiplier (0 = as fast as possible)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        archive_dir: &str,
+        agora_prefix: &str,
+        symbols: &[TradingSymbol],
+        dates: &[NaiveDate],
+        symbol_paths: HashMap<TradingSymbol, String>,
+        metaserver_connection: ConnectionHandle,
+        local_gateway_port: u16,
+        speed: f64,
+    ) -> OrError<Self> {
+        if !speed.is_finite() || speed < 0.0 {
+            return Err(anyhow::anyhow!(
+                "ReplayPublisher error: speed must be finite and nonnegative, got {}",
+                speed
+            ));
+        }
+        if symbols.is_empty() {
+            return Err(anyhow::anyhow!(
+                "ReplayPublisher error: symbols list cannot be empty"
+            ));
+        }
+
+        let agora_paths: Vec<String> = symbols
+            .iter()
+            .map(|symbol| {
+                symbol_paths
+                    .get(symbol)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}/{}", agora_prefix, symbol.to_string()))
+            })
+            .collect();
+
+        let mut publishers: HashMap<TradingSymbol, Publisher<AgorableOption<T>>> = HashMap::new();
+        for (symbol, agora_path) in symbols.iter().zip(agora_paths.iter()) {
+            let publisher = Publisher::<AgorableOption<T>>::new(
+                symbol.to_string(),
+                agora_path.clone(),
+                AgorableOption(None),
+                metaserver_connection,
+                local_gateway_port,
+            )
+            .await?;
+            publishers.insert(symbol.clone(), publisher);
+        }
+
+        let archive_dir = archive_dir.to_string();
+        let symbols = symbols.to_vec();
+        let dates = dates.to_vec();
+        let replay_handle = tokio::spawn(async move {
+            for date in dates {
+                let records = match Self::load_date(&archive_dir, &symbols, date).await {
+                    Ok(records) => records,
+                    Err(e) => {
+                        warn!(%date, %archive_dir, error = %e, "ReplayPublisher: failed to load date");
+                        continue;
+                    }
+                };
+                info!(
+                    records = records.len(),
+                    %date,
+                    %archive_dir,
+                    "ReplayPublisher: replaying"
+                );
+
+                let Some(first) = records.first() else {
+                    continue;
+                };
+                let data_start = first.replay_time();
+                let wall_start = Instant::now();
+                for record in records {
+                    if speed > 0.0 {
+                        let offset_ms = (record.replay_time() - data_start).num_milliseconds().max(0);
+                        let scaled = std::time::Duration::from_secs_f64(offset_ms as f64 / 1000.0 / speed);
+                        tokio::time::sleep_until(wall_start + scaled).await;
+                    }
+                    let symbol = record.symbol.clone();
+                    if let Some(publisher) = publishers.get_mut(&symbol)
+                        && let Err(e) = publisher.publish(AgorableOption(Some(record.value))).await
+                    {
+                        warn!(symbol = %symbol.as_str(), error = %e, "ReplayPublisher: publish error");
+                    }
+                }
+            }
+            info!(%archive_dir, "ReplayPublisher: finished replaying");
+        });
+
+        Ok(Self {
+            agora_paths,
+            replay_handle,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Reads every symbol's partition for `date` and merges them in replay order
+    async fn load_date(
+        archive_dir: &str,
+        symbols: &[TradingSymbol],
+        date: NaiveDate,
+    ) -> OrError<Vec<ReplayRecord<T>>> {
+        let archive_dir = archive_dir.to_string();
+        let symbols = symbols.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let mut records = Vec::new();
+            for symbol in symbols {
+                let path = format!(
+                    "{}/date={}/symbol={}/data.parquet",
+                    archive_dir,
+                    date.format("%Y-%m-%d"),
+                    symbol.to_string()
+                );
+                if !Path::new(&path).exists() {
+                    warn!(%path, "ReplayPublisher: no archive, skipping");
+                    continue;
+                }
+                for value in read_replayable_parquet::<T>(&path)? {
+                    records.push(ReplayRecord {
+                        symbol: symbol.clone(),
+                        value,
+                    });
+                }
+            }
+            // Stable sort keeps per-symbol file order for equal timestamps
+            records.sort_by_key(|record| record.replay_time());
+            Ok::<_, anyhow::Error>(records)
+        })
+        .await
+        .context("Replay load task join error")?
+    }
+
+    /// Returns the agora paths being replayed onto, in the order of `symbols`
+    pub fn agora_paths(&self) -> &[String] {
+        &self.agora_paths
+    }
+
+    /// True once every requested date has been replayed
+    pub fn is_finished(&self) -> bool {
+        self.replay_handle.is_finished()
+    }
+}
+
+impl<T: ArgusReplayable> Drop for ReplayPublisher<T> {
+    fn drop(&mut self) {
+        self.replay_handle.abort();
+    }
+}
+
+struct ReplayRecord<T: ArgusReplayable> {
+    symbol: TradingSymbol,
+    value: T,
+}
+
+impl<T: ArgusReplayable> ReplayRecord<T> {
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.value.replay_time()
+    }
+}
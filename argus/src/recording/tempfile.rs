@@ -1,18 +1,59 @@
 use super::ArgusParquetable;
+use super::filepath::FilePathTemplate;
+use super::wal::WriteAheadLog;
+use crate::metrics;
 use crate::types::TradingSymbol;
 use agora::metaserver::AgoraClient;
 use agora::utils::{OrError, TreeTrait};
 use agora::{Agorable, ConnectionHandle, Subscriber};
 use anyhow::Context;
-use chrono::Local;
+use chrono::Utc;
 use futures_util::StreamExt;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Stem of a temporary file before the `_{time}.pq` suffix: `{symbol}`, or `{market}.{symbol}` when tagged.
+/// Same as `FilePathTemplate::default().stem`.
+pub fn tmp_file_stem(symbol: &TradingSymbol, market: Option<&str>) -> OrError<String> {
+    FilePathTemplate::default().stem(symbol, market)
+}
+
+/// When each symbol of an `AgoraDirScribe` flushes within the shared `flush_duration`.
+/// Every scribe still flushes once per interval; only the phase of its first flush differs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlushSchedule {
+    /// All symbols flush on the same tick, `flush_duration` after start
+    #[default]
+    Aligned,
+    /// Symbol `i` of `n` first flushes after `flush_duration * (i + 1) / n`, spreading files evenly over the interval
+    Staggered,
+    /// Each symbol first flushes after a uniformly random delay in `(0, flush_duration]`
+    Jittered,
+}
+
+impl FlushSchedule {
+    /// Delay before the first flush of symbol `index` out of `count`
+    fn first_flush(&self, index: usize, count: usize, flush_duration: Duration) -> Duration {
+        match self {
+            FlushSchedule::Aligned => flush_duration,
+            FlushSchedule::Staggered => {
+                flush_duration.mul_f64((index + 1) as f64 / count.max(1) as f64)
+            }
+            FlushSchedule::Jittered => {
+                let ceiling_ms = flush_duration.as_millis().clamp(1, u64::MAX as u128) as u64;
+                Duration::from_millis(rand::random_range(1..=ceiling_ms))
+            }
+        }
+    }
+}
 
 pub struct SinglePathScribe<T: Agorable + ArgusParquetable> {
     data: Arc<Mutex<Vec<T>>>,
+    wal: Option<Arc<Mutex<WriteAheadLog<T>>>>,
     flush_path: String,
+    template: FilePathTemplate,
     collection_handle: JoinHandle<()>,
     flush_handle: JoinHandle<()>,
 }
@@ -24,81 +65,187 @@ impl<T: Agorable + ArgusParquetable> SinglePathScribe<T> {
         flush_duration: Duration,
         flush_path: &str, // Example: /argus/tmp/binance/last_trade/solusdt. We manually add the time and .pq suffix
     ) -> OrError<Self> {
-        let data = Arc::new(Mutex::new(Vec::new()));
+        Self::start(
+            agora_path,
+            agora_metaserver_connection,
+            flush_duration,
+            flush_duration,
+            flush_path,
+            FilePathTemplate::default(),
+            false,
+        )
+        .await
+    }
+
+    /// Like `new`, but logs every message to `{flush_path}.wal` as it arrives (see `WriteAheadLog`).
+    /// Messages left in the log by a crashed predecessor are replayed into the first flush.
+    /// Error: Log cannot be opened or replayed
+    pub async fn new_with_wal(
+        agora_path: String,
+        agora_metaserver_connection: ConnectionHandle,
+        flush_duration: Duration,
+        flush_path: &str,
+    ) -> OrError<Self> {
+        Self::start(
+            agora_path,
+            agora_metaserver_connection,
+            flush_duration,
+            flush_duration,
+            flush_path,
+            FilePathTemplate::default(),
+            true,
+        )
+        .await
+    }
+
+    /// `first_flush`: delay before the first flush; later flushes follow every `flush_duration`.
+    /// Files are named by `template` (see `FilePathTemplate::file_path`)
+    async fn start(
+        agora_path: String,
+        agora_metaserver_connection: ConnectionHandle,
+        flush_duration: Duration,
+        first_flush: Duration,
+        flush_path: &str,
+        template: FilePathTemplate,
+        with_wal: bool,
+    ) -> OrError<Self> {
+        let (wal, recovered) = if with_wal {
+            let (wal, recovered) = WriteAheadLog::<T>::open(flush_path)?;
+            if !recovered.is_empty() {
+                info!(
+                    records = recovered.len(),
+                    wal = ?wal.path(),
+                    "recovered records from WAL"
+                );
+            }
+            (Some(Arc::new(Mutex::new(wal))), recovered)
+        } else {
+            (None, Vec::new())
+        };
+        let data = Arc::new(Mutex::new(recovered));
         let data_clone = Arc::clone(&data);
+        let wal_clone = wal.clone();
 
         // Data collection task: continuously collect data from subscriber
         let collection_handle = tokio::spawn(async move {
+            // Skip (and count) undecodable frames instead of stalling on them
+            let decode_errors = metrics::scribe_decode_errors(&agora_path);
+            let error_path = agora_path.clone();
             let mut subscriber = Subscriber::<T>::new(agora_path, agora_metaserver_connection)
                 .await
-                .unwrap();
+                .unwrap()
+                .on_decode_error(move |bytes, e| {
+                    decode_errors.inc();
+                    warn!(
+                        path = %error_path,
+                        bytes = bytes.len(),
+                        error = format!("{:#}", e),
+                        "skipped undecodable frame"
+                    );
+                });
             let (current_value, mut stream) = subscriber.get_stream().await.unwrap();
 
-            data_clone.lock().unwrap().push(current_value);
+            Self::collect(&data_clone, wal_clone.as_deref(), current_value);
 
             while let Some(result) = stream.next().await {
                 if let Ok(message) = result {
-                    data_clone.lock().unwrap().push(message);
+                    Self::collect(&data_clone, wal_clone.as_deref(), message);
                 }
             }
         });
 
         // Flush task: periodically flush data to disk
         let data_clone = Arc::clone(&data);
+        let wal_clone = wal.clone();
         let flush_path_clone = flush_path.to_string();
         let flush_handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(flush_duration);
-            interval.tick().await; // Skip immediate first tick
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + first_flush, flush_duration);
             loop {
                 interval.tick().await;
-                let time_appendix = format!("{}", Local::now().format("%y-%m-%d %H:%M:%S"));
-                let flush_file_path = format!("{}_{}.pq", flush_path_clone, time_appendix);
-                if let Err(e) = Self::flush(Arc::clone(&data_clone), &flush_file_path).await {
-                    eprintln!("Flush error for {:?}: {}", flush_file_path, e);
+                let flush_file_path = template.file_path(&flush_path_clone, Utc::now());
+                if let Err(e) =
+                    Self::flush(Arc::clone(&data_clone), wal_clone.clone(), &flush_file_path).await
+                {
+                    error!(file = %flush_file_path, error = %e, "flush failed");
                 }
             }
         });
 
         Ok(Self {
             data,
+            wal,
             flush_path: flush_path.to_string(),
+            template,
             collection_handle,
             flush_handle,
         })
     }
 
-    /// Atomically flushes accumulated data to disk using blocking I/O
-    async fn flush(data: Arc<Mutex<Vec<T>>>, path: &str) -> OrError<()> {
+    /// Buffers one message, logging it first when a WAL is attached.
+    /// Lock order (data, then WAL) matches `flush`, so a snapshot and its log segment always agree.
+    fn collect(data: &Mutex<Vec<T>>, wal: Option<&Mutex<WriteAheadLog<T>>>, message: T) {
+        let mut data_guard = data.lock().unwrap();
+        if let Some(wal) = wal
+            && let Err(e) = wal.lock().unwrap().append(&message)
+        {
+            error!(error = %e, "WAL append failed");
+        }
+        data_guard.push(message);
+    }
+
+    /// Atomically flushes accumulated data to disk using blocking I/O.
+    /// With a WAL, the snapshot's log segment is dropped only once the parquet is written;
+    /// on failure the snapshot goes back to the front of the buffer to be retried by the next flush.
+    async fn flush(
+        data: Arc<Mutex<Vec<T>>>,
+        wal: Option<Arc<Mutex<WriteAheadLog<T>>>>,
+        path: &str,
+    ) -> OrError<()> {
         // Atomically swap data with empty vector (no race condition)
         let data_snapshot = {
             let mut data_guard = data.lock().unwrap();
+            if data_guard.is_empty() {
+                return Ok(()); // Nothing to flush
+            }
+            if let Some(wal) = &wal {
+                wal.lock().unwrap().begin_flush()?;
+            }
             std::mem::take(&mut *data_guard)
         };
 
         let record_count = data_snapshot.len();
-        if record_count == 0 {
-            return Ok(()); // Nothing to flush
-        }
-
         let path_clone = path.to_string();
         // Use spawn_blocking to avoid blocking tokio runtime
-        tokio::task::spawn_blocking(move || {
+        let retained = wal.as_ref().map(|_| data_snapshot.clone());
+        let written = tokio::task::spawn_blocking(move || {
             ArgusParquetable::write_to_parquet(data_snapshot, path_clone)
         })
         .await
-        .context("Flush task join error")??;
+        .context("Flush task join error")
+        .and_then(|written| written);
+        if let Err(e) = written {
+            if let Some(mut snapshot) = retained {
+                let mut data_guard = data.lock().unwrap();
+                snapshot.append(&mut data_guard);
+                *data_guard = snapshot;
+            }
+            return Err(e);
+        }
+        if let Some(wal) = &wal {
+            wal.lock().unwrap().commit_flush()?;
+        }
 
-        println!(
-            "Argus filescribe: flushed {} records to {:?}",
-            record_count, path
-        );
+        info!(records = record_count, file = ?path, "flushed records");
         Ok(())
     }
 }
 
 impl<T: Agorable + ArgusParquetable> SinglePathScribe<T> {
+    /// Flushes what is buffered to a last file named like the periodic ones, then stops collecting
     pub async fn shutdown(self) -> OrError<()> {
-        Self::flush(Arc::clone(&self.data), &self.flush_path).await?;
+        let flush_file_path = self.template.file_path(&self.flush_path, Utc::now());
+        Self::flush(Arc::clone(&self.data), self.wal.clone(), &flush_file_path).await?;
         self.collection_handle.abort();
         self.flush_handle.abort();
         Ok(())
@@ -114,7 +261,9 @@ impl<T: Agorable + ArgusParquetable> Drop for SinglePathScribe<T> {
 
 /// Manages multiple SinglePathScribe instances for an agora directory prefix
 /// Looks under {agora_prefix}/{symbol} and starts one SinglePathScribe for each symbol.
-/// Publishes to {output_dir}/{symbol}.pq
+/// Publishes to {output_dir}/{symbol}_{time}.pq, or {output_dir}/{market}.{symbol}_{time}.pq when built `with_market`
+/// (or whatever `FilePathTemplate` it is given; the `Archiver` collecting the files must use the same one).
+/// Symbols are flushed together unless built with a `FlushSchedule` that offsets them.
 /// Caller needs to ensure that agora values under path are valid of type T, and that children are registered under name=symbol.
 pub struct AgoraDirScribe<T: Agorable + ArgusParquetable> {
     scribes: Vec<SinglePathScribe<T>>,
@@ -127,7 +276,82 @@ impl<T: Agorable + ArgusParquetable> AgoraDirScribe<T> {
         flush_duration: Duration,
         output_dir: &str,
     ) -> OrError<Self> {
-        let metaclient = AgoraClient::new(agora_metaserver_connection.clone())
+        Self::with_market(
+            agora_prefix,
+            agora_metaserver_connection,
+            flush_duration,
+            output_dir,
+            None,
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Like `new`, but tags every filename with `market` (e.g., "spot", "perp"), so a symbol
+    /// trading in several markets stays unambiguous even if the per-market directories are flattened.
+    /// `schedule`: phase of each symbol's flushes (see `FlushSchedule`); `None` flushes all symbols together.
+    /// `template`: filename layout (defaults to `FilePathTemplate::default()`)
+    pub async fn with_market(
+        agora_prefix: &str,
+        agora_metaserver_connection: ConnectionHandle,
+        flush_duration: Duration,
+        output_dir: &str,
+        market: Option<&str>,
+        schedule: Option<FlushSchedule>,
+        template: Option<FilePathTemplate>,
+    ) -> OrError<Self> {
+        Self::start(
+            agora_prefix,
+            agora_metaserver_connection,
+            flush_duration,
+            output_dir,
+            market,
+            schedule,
+            template,
+            false,
+        )
+        .await
+    }
+
+    /// Like `with_market`, but every scribe keeps a write-ahead log next to its temporary files
+    /// (`{output_dir}/{stem}.wal`, see `SinglePathScribe::new_with_wal`), so a crash between flushes loses no data.
+    /// Restarting on the same `output_dir` replays what the crashed run had not flushed.
+    pub async fn new_with_wal(
+        agora_prefix: &str,
+        agora_metaserver_connection: ConnectionHandle,
+        flush_duration: Duration,
+        output_dir: &str,
+        market: Option<&str>,
+        schedule: Option<FlushSchedule>,
+        template: Option<FilePathTemplate>,
+    ) -> OrError<Self> {
+        Self::start(
+            agora_prefix,
+            agora_metaserver_connection,
+            flush_duration,
+            output_dir,
+            market,
+            schedule,
+            template,
+            true,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn start(
+        agora_prefix: &str,
+        agora_metaserver_connection: ConnectionHandle,
+        flush_duration: Duration,
+        output_dir: &str,
+        market: Option<&str>,
+        schedule: Option<FlushSchedule>,
+        template: Option<FilePathTemplate>,
+        with_wal: bool,
+    ) -> OrError<Self> {
+        let template = template.unwrap_or_default().validated()?;
+        let metaclient = AgoraClient::new(agora_metaserver_connection)
             .await
             .map_err(|e| {
                 anyhow::anyhow!(
@@ -137,7 +361,7 @@ impl<T: Agorable + ArgusParquetable> AgoraDirScribe<T> {
             })?;
         let pathtree = metaclient.get_path_tree().await?;
         let children = pathtree
-            .get_child(&agora_prefix)
+            .get_child(agora_prefix)
             .map_err(|e| {
                 anyhow::anyhow!(
                     "Argus AgoraDirScribe error: cannot identify children of {}: {}. Filetree: \n{}",
@@ -154,28 +378,30 @@ impl<T: Agorable + ArgusParquetable> AgoraDirScribe<T> {
             .collect();
 
         let symbols = symbols?;
-        println!("Found {} symbols: {:?}", symbols.len(), symbols);
+        info!(count = symbols.len(), ?symbols, "found symbols");
 
         // Start one SinglePathScribe for each symbol
+        let schedule = schedule.unwrap_or_default();
+        let count = symbols.len();
         let mut scribes = Vec::new();
-        for symbol in symbols {
+        for (index, symbol) in symbols.into_iter().enumerate() {
             let symbol_str = symbol.to_string();
             let agora_path = format!("{}/{}", agora_prefix, symbol_str);
-            let flush_path = format!("{}/{}", output_dir, symbol_str);
+            let flush_path = format!("{}/{}", output_dir, template.stem(&symbol, market)?);
 
-            let scribe = SinglePathScribe::<T>::new(
+            let scribe = SinglePathScribe::<T>::start(
                 agora_path.clone(),
                 agora_metaserver_connection,
                 flush_duration,
+                schedule.first_flush(index, count, flush_duration),
                 &flush_path,
+                template,
+                with_wal,
             )
             .await
             .map_err(|e| anyhow::anyhow!("Failed to start scribe for {}: {}", agora_path, e))?;
 
-            println!(
-                "Started scribe for {} (agora) -> {} (filesystem)",
-                agora_path, flush_path
-            );
+            info!(%agora_path, %flush_path, "started scribe");
             scribes.push(scribe);
         }
 
@@ -187,7 +413,7 @@ impl<T: Agorable + ArgusParquetable> AgoraDirScribe<T> {
         let scribes = std::mem::take(&mut self.scribes);
         for scribe in scribes {
             if let Err(e) = scribe.shutdown().await {
-                eprintln!("Error shutting down scribe: {}", e);
+                error!(error = %e, "failed to shut down scribe");
             }
         }
         Ok(())
@@ -203,9 +429,9 @@ impl<T: Agorable + ArgusParquetable> Drop for AgoraDirScribe<T> {
     fn drop(&mut self) {
         // Note: Individual scribes will be dropped and their Drop impls will handle cleanup
         // Use shutdown() for graceful termination with final flush
-        eprintln!(
-            "Warning: AgoraDirScribe dropped without calling shutdown(). {} scribe(s) may have unflushed data.",
-            self.scribes.len()
+        warn!(
+            scribes = self.scribes.len(),
+            "AgoraDirScribe dropped without calling shutdown(); scribes may have unflushed data"
         );
     }
 }
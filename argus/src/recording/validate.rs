@@ -0,0 +1,346 @@
+// Integrity checks over an archived hive: every partition file must be readable, carry the data type's schema,
+// hold time-ordered rows with finite floats, and sit in the partition of the day its rows fall on.
+
+use super::archiver::{COMPACTION_TIME_COLUMNS, non_finite_rows};
+use super::{Archiver, PartitionTz, millis_to_utc, parquet_schema_version};
+use crate::constants::ARCHIVER_VALIDATION_DATE_SLACK_SECONDS;
+use crate::types::TradingSymbol;
+use agora::utils::OrError;
+use anyhow::Context;
+use arrow::array::TimestampMillisecondArray;
+use arrow::compute::concat_batches;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+use chrono::{Duration, Local};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// One problem `Archiver::validate_partition` found in an archived file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveViolation {
+    pub file: PathBuf,
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// File could not be opened or decoded as parquet; no further checks ran on it
+    Unreadable(String),
+    /// File was written with another `Agorable::SCHEMA_VERSION` than the expected schema's
+    SchemaVersion { found: u32, expected: u32 },
+    /// Columns differ from the expected schema (first difference)
+    SchemaMismatch(String),
+    /// `rows` rows have an earlier time than the row before them; `first_row` is the first of them
+    NonMonotonicTime {
+        column: String,
+        rows: usize,
+        first_row: usize,
+    },
+    /// `rows` rows hold a NaN or infinite float in `column` (including inside list or struct columns)
+    NonFinite { column: String, rows: usize },
+    /// `rows` rows have a time on another day than the partition's, `first_date` being the first such day
+    DateMismatch {
+        column: String,
+        rows: usize,
+        first_date: String,
+    },
+}
+
+impl fmt::Display for ArchiveViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.file.display())?;
+        match &self.kind {
+            ViolationKind::Unreadable(error) => write!(f, "unreadable: {}", error),
+            ViolationKind::SchemaVersion { found, expected } => {
+                write!(f, "schema version {} where {} is expected", found, expected)
+            }
+            ViolationKind::SchemaMismatch(reason) => write!(f, "schema mismatch: {}", reason),
+            ViolationKind::NonMonotonicTime {
+                column,
+                rows,
+                first_row,
+            } => write!(
+                f,
+                "{} rows go back in {} (first at row {})",
+                rows, column, first_row
+            ),
+            ViolationKind::NonFinite { column, rows } => {
+                write!(f, "{} rows hold non-finite values in {}", rows, column)
+            }
+            ViolationKind::DateMismatch {
+                column,
+                rows,
+                first_date,
+            } => write!(
+                f,
+                "{} rows have a {} outside the partition date (first on {})",
+                rows, column, first_date
+            ),
+        }
+    }
+}
+
+/// What a validation pass looked at and the violations it found
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub partitions: usize,
+    pub files: usize,
+    pub rows: usize,
+    pub violations: Vec<ArchiveViolation>,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Adds the counts and violations of `other`, e.g. to report several archives at once
+    pub fn merge(&mut self, other: ValidationReport) {
+        self.partitions += other.partitions;
+        self.files += other.files;
+        self.rows += other.rows;
+        self.violations.extend(other.violations);
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} partitions, {} files, {} rows: {} violations",
+            self.partitions,
+            self.files,
+            self.rows,
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl Archiver {
+    /// Checks every parquet file of the archived partition `{data_type}/date={date}[/market={market}]/symbol={symbol}`
+    /// under `target_dir`: readable, same columns and schema version as `expected_schema` (use `versioned_schema::<T>()`),
+    /// rows non-decreasing in time (see `COMPACTION_TIME_COLUMNS`), finite floats, and times on the partition's day in
+    /// `partition_tz` (defaults to `PartitionTz::Utc`, as in `Archiver::new`). Files are dated by their flush time, so
+    /// rows up to `ARCHIVER_VALIDATION_DATE_SLACK_SECONDS` before the day are accepted.
+    /// Blocking: reads every file of the partition.
+    /// Returns the violations found; a clean partition has none.
+    /// Error: the partition directory is unreadable
+    pub fn validate_partition(
+        target_dir: &str,
+        data_type: &str,
+        date: &str,
+        market: Option<&str>,
+        symbol: &TradingSymbol,
+        expected_schema: &Schema,
+        partition_tz: Option<PartitionTz>,
+    ) -> OrError<ValidationReport> {
+        let partition_dir = Self::hive_partition_dir(target_dir, data_type, date, market, symbol);
+        let partition_tz = partition_tz.unwrap_or_default();
+        let files = Self::partition_files(Path::new(&partition_dir))?;
+        let mut report = ValidationReport {
+            partitions: 1,
+            files: files.len(),
+            ..Default::default()
+        };
+        for file in files {
+            let (rows, kinds) = match Self::read_partition_file(&file) {
+                Ok((schema, batch)) => (
+                    batch.num_rows(),
+                    Self::file_violations(&schema, &batch, expected_schema, date, partition_tz),
+                ),
+                Err(e) => (0, vec![ViolationKind::Unreadable(format!("{:#}", e))]),
+            };
+            report.rows += rows;
+            report
+                .violations
+                .extend(kinds.into_iter().map(|kind| ArchiveViolation {
+                    file: file.clone(),
+                    kind,
+                }));
+        }
+        Ok(report)
+    }
+
+    /// Validates (see `validate_partition`) every archived partition of each data type in `schemas` under
+    /// `target_dir`, against that data type's schema. Data types without a directory have nothing archived and are skipped.
+    /// Blocking: reads the whole archive.
+    /// Error: a data type or partition directory is unreadable
+    pub fn validate_all(
+        target_dir: &str,
+        schemas: &HashMap<String, Arc<Schema>>,
+        partition_tz: Option<PartitionTz>,
+    ) -> OrError<ValidationReport> {
+        let mut data_types: Vec<&String> = schemas.keys().collect();
+        data_types.sort();
+        let mut report = ValidationReport::default();
+        for data_type in data_types {
+            let type_dir = format!("{}/{}", target_dir, data_type);
+            if !Path::new(&type_dir).is_dir() {
+                continue;
+            }
+            for (date, market, symbol, _) in Self::list_partitions(&type_dir)? {
+                report.merge(Self::validate_partition(
+                    target_dir,
+                    data_type,
+                    &date,
+                    market.as_deref(),
+                    &symbol,
+                    &schemas[data_type],
+                    partition_tz,
+                )?);
+            }
+        }
+        for violation in &report.violations {
+            warn!(%violation, "archive violation");
+        }
+        info!(
+            target_dir,
+            partitions = report.partitions,
+            files = report.files,
+            rows = report.rows,
+            violations = report.violations.len(),
+            "archive validated"
+        );
+        Ok(report)
+    }
+
+    /// Schema and rows of one partition file, as a single batch
+    fn read_partition_file(file: &Path) -> OrError<(Arc<Schema>, RecordBatch)> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(
+            fs::File::open(file).context("Failed to open partition file")?,
+        )
+        .context("Failed to create reader for partition file")?;
+        let schema = builder.schema().clone();
+        let batches = builder
+            .build()
+            .context("Failed to build reader for partition file")?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to read batch from partition file")?;
+        let batch = concat_batches(&schema, &batches).context("Failed to concatenate batches")?;
+        Ok((schema, batch))
+    }
+
+    fn file_violations(
+        schema: &Schema,
+        batch: &RecordBatch,
+        expected_schema: &Schema,
+        date: &str,
+        partition_tz: PartitionTz,
+    ) -> Vec<ViolationKind> {
+        let mut violations = Vec::new();
+
+        let found = parquet_schema_version(schema);
+        let expected = parquet_schema_version(expected_schema);
+        match (found, expected) {
+            (Ok(found), Ok(expected)) if found != expected => {
+                // Another layout: column checks against the expected schema would only repeat this
+                violations.push(ViolationKind::SchemaVersion { found, expected });
+                return violations;
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                violations.push(ViolationKind::SchemaMismatch(e.to_string()));
+            }
+            _ => {}
+        }
+        if let Some(reason) = schema_difference(schema, expected_schema) {
+            violations.push(ViolationKind::SchemaMismatch(reason));
+        }
+
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            let rows = non_finite_rows(column.as_ref())
+                .into_iter()
+                .filter(|bad| *bad)
+                .count();
+            if rows > 0 {
+                violations.push(ViolationKind::NonFinite {
+                    column: field.name().clone(),
+                    rows,
+                });
+            }
+        }
+
+        let Some((column, times)) = COMPACTION_TIME_COLUMNS.iter().find_map(|name| {
+            batch
+                .column_by_name(name)?
+                .as_any()
+                .downcast_ref::<TimestampMillisecondArray>()
+                .map(|times| (name.to_string(), times))
+        }) else {
+            return violations;
+        };
+
+        let regressions: Vec<usize> = (1..times.len())
+            .filter(|&row| times.value(row) < times.value(row - 1))
+            .collect();
+        if let Some(&first_row) = regressions.first() {
+            violations.push(ViolationKind::NonMonotonicTime {
+                column: column.clone(),
+                rows: regressions.len(),
+                first_row,
+            });
+        }
+
+        let slack = Duration::seconds(ARCHIVER_VALIDATION_DATE_SLACK_SECONDS);
+        let mut misdated = 0;
+        let mut first_date = None;
+        for millis in times.values().iter() {
+            let Ok(time) = millis_to_utc(*millis) else {
+                continue;
+            };
+            let day = partition_tz.partition_date(&time.with_timezone(&Local));
+            let slack_day = partition_tz.partition_date(&(time + slack).with_timezone(&Local));
+            if day != date && slack_day != date {
+                misdated += 1;
+                first_date.get_or_insert(day);
+            }
+        }
+        if let Some(first_date) = first_date {
+            violations.push(ViolationKind::DateMismatch {
+                column,
+                rows: misdated,
+                first_date,
+            });
+        }
+        violations
+    }
+}
+
+/// First difference in columns (name, type, nullability, order) between `found` and `expected`; metadata is ignored
+fn schema_difference(found: &Schema, expected: &Schema) -> Option<String> {
+    for (index, field) in expected.fields().iter().enumerate() {
+        let Some(actual) = found.fields().get(index) else {
+            return Some(format!("missing column {}", field.name()));
+        };
+        if actual.name() != field.name() {
+            return Some(format!(
+                "column {} is {} where {} is expected",
+                index,
+                actual.name(),
+                field.name()
+            ));
+        }
+        if actual.data_type() != field.data_type() || actual.is_nullable() != field.is_nullable() {
+            return Some(format!(
+                "column {} is {:?} (nullable: {}) where {:?} (nullable: {}) is expected",
+                field.name(),
+                actual.data_type(),
+                actual.is_nullable(),
+                field.data_type(),
+                field.is_nullable()
+            ));
+        }
+    }
+    found
+        .fields()
+        .get(expected.fields().len())
+        .map(|extra| format!("unexpected column {}", extra.name()))
+}
@@ -0,0 +1,163 @@
+//! Append-only write-ahead log backing a scribe's in-memory buffer between parquet flushes.
+//!
+//! Records are `[u32 little-endian length][postcard payload]`, written with one `write` per message
+//! (no fsync: survives a process crash, not a power loss). Two files per scribe:
+//! - `{flush_path}.wal`: messages not yet handed to a flush
+//! - `{flush_path}.wal.flushing`: messages of the flush in progress, removed once its parquet is written
+//!
+//! On startup both are replayed (flushing first), so a crash at any point loses no received message.
+
+use agora::utils::OrError;
+use agora::{Agorable, WireCodec};
+use anyhow::Context;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Suffix appended to a scribe's flush path for its log
+pub const WAL_SUFFIX: &str = ".wal";
+/// Suffix of the log segment owned by an in-progress flush
+const FLUSHING_SUFFIX: &str = ".flushing";
+/// Largest record accepted on replay; anything bigger is a torn or corrupt length prefix
+const MAX_RECORD_BYTES: usize = 64 << 20;
+
+pub struct WriteAheadLog<T: Agorable> {
+    path: PathBuf,
+    flushing_path: PathBuf,
+    file: File,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Agorable> WriteAheadLog<T> {
+    /// Opens the log of `flush_path` and returns the messages it still holds, oldest first.
+    /// A torn record at the end of a file (crash mid-write) is dropped with a warning and truncated away.
+    /// Error: Log files cannot be read or opened for append, or hold an undecodable record
+    pub fn open(flush_path: &str) -> OrError<(Self, Vec<T>)> {
+        let path = PathBuf::from(format!("{}{}", flush_path, WAL_SUFFIX));
+        let flushing_path =
+            PathBuf::from(format!("{}{}{}", flush_path, WAL_SUFFIX, FLUSHING_SUFFIX));
+        let mut recovered = Self::read_records(&flushing_path)?;
+        recovered.extend(Self::read_records(&path)?);
+        let file = Self::open_append(&path)?;
+        Ok((
+            Self {
+                path,
+                flushing_path,
+                file,
+                _marker: PhantomData,
+            },
+            recovered,
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends one message. Call before the message enters the in-memory buffer.
+    pub fn append(&mut self, value: &T) -> OrError<()> {
+        let payload = WireCodec::Postcard.encode(value)?;
+        let mut record = Vec::with_capacity(4 + payload.len());
+        record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        record.extend_from_slice(&payload);
+        self.file
+            .write_all(&record)
+            .with_context(|| format!("Argus WAL error: cannot append to {:?}", self.path))
+    }
+
+    /// Hands the current log to a flush: its records move to the flushing segment and the log restarts empty.
+    /// Call under the same lock that takes the buffer snapshot, so the segment holds exactly the snapshot
+    /// (plus the records of any earlier failed flush, whose values were put back into the buffer).
+    pub fn begin_flush(&mut self) -> OrError<()> {
+        if self.flushing_path.exists() {
+            let pending = std::fs::read(&self.path)
+                .with_context(|| format!("Argus WAL error: cannot read {:?}", self.path))?;
+            Self::open_append(&self.flushing_path)?
+                .write_all(&pending)
+                .with_context(|| {
+                    format!("Argus WAL error: cannot extend {:?}", self.flushing_path)
+                })?;
+        } else {
+            std::fs::rename(&self.path, &self.flushing_path)
+                .with_context(|| format!("Argus WAL error: cannot rotate {:?}", self.path))?;
+        }
+        self.file = File::create(&self.path)
+            .with_context(|| format!("Argus WAL error: cannot recreate {:?}", self.path))?;
+        Ok(())
+    }
+
+    /// The flush started by `begin_flush` is on disk: drops its segment
+    pub fn commit_flush(&mut self) -> OrError<()> {
+        match std::fs::remove_file(&self.flushing_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(anyhow::anyhow!(
+                "Argus WAL error: cannot remove {:?}: {}",
+                self.flushing_path,
+                e
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn open_append(path: &Path) -> OrError<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Argus WAL error: cannot open {:?}", path))
+    }
+
+    fn read_records(path: &Path) -> OrError<Vec<T>> {
+        let mut bytes = Vec::new();
+        match File::open(path) {
+            Ok(mut file) => {
+                file.read_to_end(&mut bytes)
+                    .with_context(|| format!("Argus WAL error: cannot read {:?}", path))?;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Argus WAL error: cannot open {:?}: {}",
+                    path,
+                    e
+                ));
+            }
+        }
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let body = offset + 4;
+            let len = bytes
+                .get(offset..body)
+                .map(|prefix| u32::from_le_bytes(prefix.try_into().unwrap()) as usize);
+            let Some(len) = len.filter(|len| *len <= MAX_RECORD_BYTES && body + len <= bytes.len())
+            else {
+                warn!(
+                    offset,
+                    path = ?path,
+                    trailing_bytes = bytes.len() - offset,
+                    "dropping torn WAL record"
+                );
+                // Later appends must start on a record boundary
+                OpenOptions::new()
+                    .write(true)
+                    .open(path)
+                    .and_then(|file| file.set_len(offset as u64))
+                    .with_context(|| format!("Argus WAL error: cannot truncate {:?}", path))?;
+                break;
+            };
+            let value = WireCodec::Postcard
+                .decode(&bytes[body..body + len])
+                .with_context(|| {
+                    format!(
+                        "Argus WAL error: corrupt record at byte {} of {:?}",
+                        offset, path
+                    )
+                })?;
+            records.push(value);
+            offset = body + len;
+        }
+        Ok(records)
+    }
+}
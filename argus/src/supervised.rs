@@ -0,0 +1,137 @@
+//! Background tasks whose exits and panics are observable.
+//!
+//! A plain `tokio::spawn`ed task that panics only reports it through its `JoinHandle`, which argus
+//! components keep solely to `abort()` on drop, so the task vanishes silently. `spawn_supervised`
+//! logs every exit, counts it in `metrics::task_exits`, and hands it to an `on_exit` callback;
+//! `spawn_supervised_restarting` also rebuilds panicked tasks under a `RestartPolicy`.
+//!
+//! Aborting the returned handle drops the task without calling `on_exit`: cancellation is the owner's doing, not a failure.
+
+use crate::constants::{SUPERVISED_MAX_RESTARTS, SUPERVISED_RESTART_BACKOFF_MS};
+use crate::metrics;
+use futures_util::FutureExt;
+use std::any::Any;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// How a supervised task ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskExit {
+    /// The future returned
+    Completed,
+    /// The future panicked; holds the panic message (or a placeholder for non-string payloads)
+    Panicked(String),
+}
+
+impl TaskExit {
+    pub fn is_panic(&self) -> bool {
+        matches!(self, TaskExit::Panicked(_))
+    }
+
+    fn outcome(&self) -> &'static str {
+        match self {
+            TaskExit::Completed => "completed",
+            TaskExit::Panicked(_) => "panicked",
+        }
+    }
+}
+
+/// Restarts of panicked tasks in `spawn_supervised_restarting`. Tasks that return are never restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Restarts after which a further panic ends the task for good
+    pub max_restarts: u32,
+    /// Delay before each restart
+    pub backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: SUPERVISED_MAX_RESTARTS,
+            backoff: Duration::from_millis(SUPERVISED_RESTART_BACKOFF_MS),
+        }
+    }
+}
+
+/// Spawns `fut`, reporting how it ended: logged, counted under `name`, then passed to `on_exit`.
+/// `name` labels the exit counter, so use the kind of task (e.g. "archiver"), not a per-symbol name.
+pub fn spawn_supervised<Fut>(
+    name: &str,
+    fut: Fut,
+    on_exit: impl FnOnce(&TaskExit) + Send + 'static,
+) -> JoinHandle<()>
+where
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let name = name.to_string();
+    tokio::spawn(async move {
+        let exit = run_to_exit(fut).await;
+        report_exit(&name, &exit, false);
+        on_exit(&exit);
+    })
+}
+
+/// Like `spawn_supervised`, but a panicked task is rebuilt with `make_fut` and run again, up to
+/// `restart.max_restarts` times (default `RestartPolicy::default()`). `on_exit` sees every exit, including those followed by a restart.
+pub fn spawn_supervised_restarting<F, Fut>(
+    name: &str,
+    mut make_fut: F,
+    restart: Option<RestartPolicy>,
+    mut on_exit: impl FnMut(&TaskExit) + Send + 'static,
+) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let name = name.to_string();
+    let restart = restart.unwrap_or_default();
+    tokio::spawn(async move {
+        let mut restarts: u32 = 0;
+        loop {
+            let exit = run_to_exit(make_fut()).await;
+            let will_restart = exit.is_panic() && restarts < restart.max_restarts;
+            report_exit(&name, &exit, will_restart);
+            on_exit(&exit);
+            if !will_restart {
+                break;
+            }
+            restarts += 1;
+            metrics::task_restarts(&name).inc();
+            tokio::time::sleep(restart.backoff).await;
+        }
+    })
+}
+
+async fn run_to_exit<Fut: Future<Output = ()>>(fut: Fut) -> TaskExit {
+    match AssertUnwindSafe(fut).catch_unwind().await {
+        Ok(()) => TaskExit::Completed,
+        Err(payload) => TaskExit::Panicked(panic_message(payload.as_ref())),
+    }
+}
+
+fn report_exit(name: &str, exit: &TaskExit, will_restart: bool) {
+    metrics::task_exits(name, exit.outcome()).inc();
+    match exit {
+        TaskExit::Completed => info!(task = name, "supervised task completed"),
+        TaskExit::Panicked(message) if will_restart => {
+            warn!(task = name, panic = %message, "supervised task panicked, restarting")
+        }
+        TaskExit::Panicked(message) => {
+            error!(task = name, panic = %message, "supervised task panicked")
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
@@ -1,28 +1,140 @@
 use agora::utils::OrError;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::ops::{Add, Sub};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct TradingSymbol(String);
+/// Shared, immutable symbol name: clones bump a reference count instead of copying the string,
+/// so symbols can be embedded in every parsed message and used as map keys without allocating.
+/// `Borrow<str>` lets maps keyed by `TradingSymbol` be queried with a plain `&str`.
+/// Serialized as a plain string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TradingSymbol(Arc<str>);
 
 impl TradingSymbol {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> OrError<Self> {
         if s.is_empty() {
             return Err(anyhow::anyhow!("TradingSymbol cannot be empty"));
         }
-        Ok(Self(s.to_string()))
+        Ok(Self(Arc::from(s)))
     }
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
-        self.0.clone()
+        self.0.to_string()
     }
+    /// Borrowed name; prefer over `to_string` in hot paths
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for TradingSymbol {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for TradingSymbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for TradingSymbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self(Arc::from(String::deserialize(deserializer)?)))
+    }
+}
+
+/// Exact decimal `mantissa * 10^exponent`. Prices and sizes are stored (and serialized) as f64, but each
+/// f64 stands for the shortest decimal that round-trips to it (e.g. 0.1 for the f64 nearest 0.1),
+/// which is the exchange's own decimal string. Arithmetic runs on those decimals, so it is exact and
+/// order-independent as long as results keep at most 15 significant digits.
+#[derive(Debug, Clone, Copy)]
+struct ExactDecimal {
+    mantissa: i128,
+    exponent: i32,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl ExactDecimal {
+    fn of_f64(f: f64) -> Self {
+        // `{:e}` prints the shortest round-trip digits, e.g. "1.5e10", "2.5e-5"
+        let formatted = format!("{:e}", f);
+        let (digits, exponent) = formatted.split_once('e').unwrap();
+        let fraction_len = digits
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len());
+        Self {
+            mantissa: digits.replace('.', "").parse().unwrap(),
+            exponent: exponent.parse::<i32>().unwrap() - fraction_len as i32,
+        }
+    }
+
+    /// Nearest f64 (the decimal parser rounds correctly)
+    fn to_f64(self) -> f64 {
+        format!("{}e{}", self.mantissa, self.exponent)
+            .parse()
+            .unwrap()
+    }
+
+    /// `None` if aligning the exponents overflows, i.e. the operands differ by more than ~20 orders of magnitude
+    fn checked_add(self, other: Self) -> Option<Self> {
+        let (high, low) = if self.exponent >= other.exponent {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let scale = 10i128.checked_pow((high.exponent - low.exponent) as u32)?;
+        Some(Self {
+            mantissa: high
+                .mantissa
+                .checked_mul(scale)?
+                .checked_add(low.mantissa)?,
+            exponent: low.exponent,
+        })
+    }
+
+    fn negated(self) -> Self {
+        Self {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+        }
+    }
+}
+
+/// `a + b` on the decimals `a` and `b` stand for; plain f64 addition when they are too far apart to align
+/// (the smaller one is then below f64 precision of the larger anyway)
+fn exact_add(a: f64, b: f64) -> f64 {
+    ExactDecimal::of_f64(a)
+        .checked_add(ExactDecimal::of_f64(b))
+        .map_or(a + b, ExactDecimal::to_f64)
+}
+
+fn exact_sub(a: f64, b: f64) -> f64 {
+    ExactDecimal::of_f64(a)
+        .checked_add(ExactDecimal::of_f64(b).negated())
+        .map_or(a - b, ExactDecimal::to_f64)
+}
+
+/// Nonnegative finite price. `+`/`-` are exact on decimal prices (see `ExactDecimal`) and return
+/// `OrError`, since a difference can be negative and a sum can overflow.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Price(f64);
 
 impl Price {
     pub fn from_f64(f: f64) -> OrError<Self> {
+        Self::from_f64_checked(f)
+    }
+
+    /// Like `from_f64`, naming the rejected value. Use for computed floats, which may be NaN or infinite.
+    /// Error: `f` is non-finite or negative.
+    pub fn from_f64_checked(f: f64) -> OrError<Self> {
         if !f.is_finite() || f < 0.0 {
-            return Err(anyhow::anyhow!("Price needs to be finite and nonnegative"));
+            return Err(anyhow::anyhow!(
+                "Price needs to be finite and nonnegative, got {}",
+                f
+            ));
         }
         Ok(Self(f))
     }
@@ -40,13 +152,40 @@ impl Price {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl Add for Price {
+    type Output = OrError<Price>;
+
+    fn add(self, other: Price) -> OrError<Price> {
+        Price::from_f64_checked(exact_add(self.0, other.0))
+    }
+}
+
+impl Sub for Price {
+    type Output = OrError<Price>;
+
+    /// Error: `other` is larger than `self`
+    fn sub(self, other: Price) -> OrError<Price> {
+        Price::from_f64_checked(exact_sub(self.0, other.0))
+    }
+}
+
+/// Positive finite size. `+`/`-` are exact like `Price`'s, so cumulative sizes do not drift.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct TradeSize(f64);
 
 impl TradeSize {
     pub fn from_f64(f: f64) -> OrError<Self> {
+        Self::from_f64_checked(f)
+    }
+
+    /// Like `from_f64`, naming the rejected value. Use for computed floats, which may be NaN or infinite.
+    /// Error: `f` is non-finite, zero or negative.
+    pub fn from_f64_checked(f: f64) -> OrError<Self> {
         if !f.is_finite() || f <= 0.0 {
-            return Err(anyhow::anyhow!("TradeSize needs to be finite and positive"));
+            return Err(anyhow::anyhow!(
+                "TradeSize needs to be finite and positive, got {}",
+                f
+            ));
         }
         Ok(Self(f))
     }
@@ -64,4 +203,21 @@ impl TradeSize {
     }
 }
 
+impl Add for TradeSize {
+    type Output = OrError<TradeSize>;
+
+    fn add(self, other: TradeSize) -> OrError<TradeSize> {
+        TradeSize::from_f64_checked(exact_add(self.0, other.0))
+    }
+}
+
+impl Sub for TradeSize {
+    type Output = OrError<TradeSize>;
+
+    /// Error: `other` is at least `self` (sizes stay positive)
+    fn sub(self, other: TradeSize) -> OrError<TradeSize> {
+        TradeSize::from_f64_checked(exact_sub(self.0, other.0))
+    }
+}
+
 pub type PriceLevel = (Price, TradeSize);
@@ -0,0 +1,240 @@
+use argus::crypto::hyperliquid::{HyperliquidArchiver, OrderbookSnapshot, TradeUpdate};
+use argus::recording::{Archiver, ArgusParquetable, PartitionTz, ViolationKind, versioned_schema};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use arrow::array::Float64Array;
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use parquet::arrow::ArrowWriter;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn symbol(name: &str) -> TradingSymbol {
+    TradingSymbol::from_str(name).unwrap()
+}
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("argus_validation_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn at(secs: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 10, 5, 12, 0, 0).unwrap() + Duration::seconds(secs)
+}
+
+/// BTC_PERP book at 2025-10-05 12:00:00 + `secs`
+fn book(secs: i64) -> OrderbookSnapshot {
+    OrderbookSnapshot {
+        symbol: symbol("BTC_PERP"),
+        received_time: at(secs),
+        time: at(secs),
+        bid_levels: vec![(
+            Price::from_f64(99.5).unwrap(),
+            TradeSize::from_f64(1.0).unwrap(),
+            1,
+        )],
+        ask_levels: vec![(
+            Price::from_f64(100.5).unwrap(),
+            TradeSize::from_f64(2.0).unwrap(),
+            1,
+        )],
+    }
+}
+
+fn trade(trade_id: u64) -> TradeUpdate {
+    TradeUpdate {
+        symbol: symbol("BTC_PERP"),
+        received_time: at(trade_id as i64),
+        trade_id,
+        price: Price::from_f64(100.0).unwrap(),
+        size: TradeSize::from_f64(1.0).unwrap(),
+        trade_time: at(trade_id as i64),
+        is_buy: true,
+    }
+}
+
+/// Directory of the BTC_PERP partition of `data_type` on `date`
+fn partition(target: &Path, data_type: &str, date: &str) -> String {
+    let dir = Archiver::hive_partition_dir(
+        target.to_str().unwrap(),
+        data_type,
+        date,
+        None,
+        &symbol("BTC_PERP"),
+    );
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes trades 0..count as `{dir}/data.parquet` with the price of `nan_trade` set to NaN.
+/// `Price` rejects NaN, so it is patched into the record batch.
+fn write_trades_with_nan(dir: &str, count: u64, nan_trade: u64) {
+    let batch = TradeUpdate::to_record_batch((0..count).map(trade).collect()).unwrap();
+    let price_index = batch.schema().index_of("price").unwrap();
+    let prices: Float64Array = (0..count)
+        .map(|id| if id == nan_trade { f64::NAN } else { 100.0 })
+        .collect::<Vec<f64>>()
+        .into();
+    let mut columns = batch.columns().to_vec();
+    columns[price_index] = Arc::new(prices);
+    let schema = versioned_schema::<TradeUpdate>();
+    let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+    let file = std::fs::File::create(format!("{}/data.parquet", dir)).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+}
+
+fn schemas() -> HashMap<String, Arc<arrow::datatypes::Schema>> {
+    HashMap::from([
+        (
+            "orderbook".to_string(),
+            versioned_schema::<OrderbookSnapshot>(),
+        ),
+        ("last_trade".to_string(), versioned_schema::<TradeUpdate>()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn well_formed_archive_is_clean() {
+        let root = scratch_dir("clean");
+        let dir = partition(&root, "orderbook", "2025-10-05");
+        OrderbookSnapshot::write_to_parquet(
+            (0..10).map(book).collect(),
+            format!("{}/data.parquet", dir),
+        )
+        .unwrap();
+        // Flushed just after midnight: rows from the end of the previous day are expected
+        let dir = partition(&root, "orderbook", "2025-10-06");
+        let late = vec![book(12 * 3600 - 5), book(12 * 3600 + 5)];
+        OrderbookSnapshot::write_to_parquet(late, format!("{}/data.parquet", dir)).unwrap();
+
+        let report = Archiver::validate_all(root.to_str().unwrap(), &schemas(), None).unwrap();
+        assert!(report.is_clean(), "{}", report);
+        assert_eq!((report.partitions, report.files, report.rows), (2, 2, 12));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn corrupt_partitions_are_flagged() {
+        let root = scratch_dir("corrupt");
+        let target = root.to_str().unwrap();
+
+        // Out of order, with a row two days after the partition date
+        let books = vec![book(0), book(2), book(1), book(2 * 86400)];
+        let dir = partition(&root, "orderbook", "2025-10-05");
+        OrderbookSnapshot::write_to_parquet(books, format!("{}/data.parquet", dir)).unwrap();
+        // A truncated fragment next to it
+        std::fs::write(format!("{}/fragment.parquet", dir), b"PAR1 not really").unwrap();
+
+        let report = Archiver::validate_partition(
+            target,
+            "orderbook",
+            "2025-10-05",
+            None,
+            &symbol("BTC_PERP"),
+            &versioned_schema::<OrderbookSnapshot>(),
+            None,
+        )
+        .unwrap();
+        assert_eq!((report.files, report.rows), (2, 4));
+        let kinds: Vec<&ViolationKind> = report.violations.iter().map(|v| &v.kind).collect();
+        assert_eq!(kinds.len(), 3, "{}", report);
+        assert_eq!(
+            kinds[0],
+            &ViolationKind::NonMonotonicTime {
+                column: "time".to_string(),
+                rows: 1,
+                first_row: 2,
+            }
+        );
+        assert_eq!(
+            kinds[1],
+            &ViolationKind::DateMismatch {
+                column: "time".to_string(),
+                rows: 1,
+                first_date: "2025-10-07".to_string(),
+            }
+        );
+        assert!(matches!(kinds[2], ViolationKind::Unreadable(_)));
+        assert!(report.violations[2].file.ends_with("fragment.parquet"));
+
+        // The same partition read as the wrong type
+        let report = Archiver::validate_partition(
+            target,
+            "orderbook",
+            "2025-10-05",
+            None,
+            &symbol("BTC_PERP"),
+            &versioned_schema::<TradeUpdate>(),
+            None,
+        )
+        .unwrap();
+        assert!(
+            report
+                .violations
+                .iter()
+                .any(|v| matches!(v.kind, ViolationKind::SchemaMismatch(_)))
+        );
+
+        // NaN prices
+        write_trades_with_nan(&partition(&root, "last_trade", "2025-10-05"), 5, 3);
+        let report = Archiver::validate_all(target, &schemas(), None).unwrap();
+        assert_eq!(report.partitions, 2);
+        assert!(report.violations.iter().any(|v| v.kind
+            == ViolationKind::NonFinite {
+                column: "price".to_string(),
+                rows: 1,
+            }));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn partition_dates_follow_the_archiving_timezone() {
+        let root = scratch_dir("partition_tz");
+        // 20:00 UTC on 2025-10-05 is already 2025-10-06 at UTC+9
+        let dir = partition(&root, "orderbook", "2025-10-06");
+        OrderbookSnapshot::write_to_parquet(vec![book(8 * 3600)], format!("{}/data.parquet", dir))
+            .unwrap();
+        let target = root.to_str().unwrap();
+
+        let utc = Archiver::validate_all(target, &schemas(), None).unwrap();
+        assert_eq!(utc.violations.len(), 1, "{}", utc);
+        let jst = PartitionTz::Offset(chrono::FixedOffset::east_opt(9 * 3600).unwrap());
+        let report = Archiver::validate_all(target, &schemas(), Some(jst)).unwrap();
+        assert!(report.is_clean(), "{}", report);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn hyperliquid_validation_covers_both_markets() {
+        let root = scratch_dir("hyperliquid");
+        let spot = root.join("spot");
+        let dir = partition(&spot, "orderbook", "2025-10-05");
+        OrderbookSnapshot::write_to_parquet(
+            vec![book(1), book(0)],
+            format!("{}/data.parquet", dir),
+        )
+        .unwrap();
+        let perp = root.join("perp");
+        write_trades_with_nan(&partition(&perp, "last_trade", "2025-10-05"), 3, 0);
+
+        let report = HyperliquidArchiver::validate(root.to_str().unwrap(), None).unwrap();
+        assert_eq!(report.partitions, 2);
+        assert_eq!(report.violations.len(), 2, "{}", report);
+        assert!(report.violations[0].file.starts_with(&spot));
+        assert!(report.violations[1].file.starts_with(&perp));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,831 @@
+use agora::Publisher;
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::{OrderbookSnapshot, TradeUpdate};
+use argus::metrics;
+use argus::recording::{
+    AgoraDirScribe, ArgusParquetable, Archiver, DedupKey, NonFinitePolicy,
+    ParquetCompressionConfig, PartitionTz, read_replayable_parquet, tmp_file_stem,
+    versioned_schema,
+};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use arrow::array::{Array, Float64Array, ListArray, StructArray, UInt32Array};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, TimeZone, Timelike, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn symbol(name: &str) -> TradingSymbol {
+    TradingSymbol::from_str(name).unwrap()
+}
+
+/// Temporary filepath as `SinglePathScribe` writes it: `{dir}/{stem}_{YY-MM-DD HH:MM:SS}.pq`
+fn tmp_filepath(dir: &str, symbol: &TradingSymbol, market: Option<&str>) -> String {
+    format!(
+        "{}/{}_25-10-05 12:30:45.pq",
+        dir,
+        tmp_file_stem(symbol, market).unwrap()
+    )
+}
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("argus_archiver_{}_{}", name, std::process::id()))
+}
+
+/// BTC_PERP book at exchange time 2025-10-05 00:00:{secs}, received `lag_ms` later
+fn book(secs: i64, lag_ms: i64) -> OrderbookSnapshot {
+    let time = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap() + Duration::seconds(secs);
+    OrderbookSnapshot {
+        symbol: symbol("BTC_PERP"),
+        received_time: time + Duration::milliseconds(lag_ms),
+        time,
+        bid_levels: vec![(
+            Price::from_f64(99.5).unwrap(),
+            TradeSize::from_f64(1.0).unwrap(),
+            1,
+        )],
+        ask_levels: vec![(
+            Price::from_f64(100.5).unwrap(),
+            TradeSize::from_f64(2.0).unwrap(),
+            1,
+        )],
+    }
+}
+
+/// BTC_PERP book at exchange time 2025-10-05 00:00:{secs} with bids 99, 98, .. and asks 101, 102, ..
+/// `depth` levels per side, listed worst first; level `i` from the touch has size `i + 1`
+fn deep_book(secs: i64, depth: u32) -> OrderbookSnapshot {
+    let side = |sign: f64| {
+        (0..depth)
+            .rev()
+            .map(|i| {
+                (
+                    Price::from_f64(100.0 + sign * (1.0 + i as f64)).unwrap(),
+                    TradeSize::from_f64(1.0 + i as f64).unwrap(),
+                    i + 1,
+                )
+            })
+            .collect()
+    };
+    OrderbookSnapshot {
+        bid_levels: side(-1.0),
+        ask_levels: side(1.0),
+        ..book(secs, 0)
+    }
+}
+
+/// Reads the archived BTC_PERP orderbook partition of 2025-10-05 as one batch, in the file's own layout
+fn archived_book_batch(target: &Path) -> RecordBatch {
+    let dir = Archiver::hive_partition_dir(
+        target.to_str().unwrap(),
+        "orderbook",
+        "2025-10-05",
+        None,
+        &symbol("BTC_PERP"),
+    );
+    let file = std::fs::File::open(format!("{}/data.parquet", dir)).unwrap();
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+    let schema = builder.schema().clone();
+    let batches: Vec<RecordBatch> = builder.build().unwrap().map(|b| b.unwrap()).collect();
+    arrow::compute::concat_batches(&schema, &batches).unwrap()
+}
+
+/// Writes `books` as an orderbook scribe file stamped 2025-10-05 `hms` under `{tmp}/orderbook`
+fn write_tmp_books(tmp: &Path, hms: &str, books: Vec<OrderbookSnapshot>) -> String {
+    write_tmp_books_stamped(tmp, &format!("25-10-05 {}", hms), books)
+}
+
+/// Writes `books` as an orderbook scribe file stamped with the host-local `YY-MM-DD HH:MM:SS` of `instant`
+fn write_tmp_books_at(tmp: &Path, instant: DateTime<Utc>, books: Vec<OrderbookSnapshot>) -> String {
+    let stamp = instant.with_timezone(&Local).format("%y-%m-%d %H:%M:%S");
+    write_tmp_books_stamped(tmp, &stamp.to_string(), books)
+}
+
+fn write_tmp_books_stamped(tmp: &Path, stamp: &str, books: Vec<OrderbookSnapshot>) -> String {
+    let dir = tmp.join("orderbook");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir
+        .join(format!("BTC_PERP_{}.pq", stamp))
+        .to_string_lossy()
+        .to_string();
+    OrderbookSnapshot::write_to_parquet(books, path.clone()).unwrap();
+    path
+}
+
+/// Whether the BTC_PERP orderbook partition of `date` holds an archived file
+fn has_book_partition(target: &Path, date: &str) -> bool {
+    let dir = Archiver::hive_partition_dir(
+        target.to_str().unwrap(),
+        "orderbook",
+        date,
+        None,
+        &symbol("BTC_PERP"),
+    );
+    Path::new(&format!("{}/data.parquet", dir)).exists()
+}
+
+fn archived_books(target: &Path) -> Vec<OrderbookSnapshot> {
+    let dir = Archiver::hive_partition_dir(
+        target.to_str().unwrap(),
+        "orderbook",
+        "2025-10-05",
+        None,
+        &symbol("BTC_PERP"),
+    );
+    read_replayable_parquet(&format!("{}/data.parquet", dir)).unwrap()
+}
+
+/// Writes `books` as `{name}` in the archived BTC_PERP orderbook partition of 2025-10-05
+fn write_partition_books(target: &Path, name: &str, books: Vec<OrderbookSnapshot>) {
+    let dir = Archiver::hive_partition_dir(
+        target.to_str().unwrap(),
+        "orderbook",
+        "2025-10-05",
+        None,
+        &symbol("BTC_PERP"),
+    );
+    std::fs::create_dir_all(&dir).unwrap();
+    OrderbookSnapshot::write_to_parquet(books, format!("{}/{}", dir, name)).unwrap();
+}
+
+fn trade(trade_id: u64) -> TradeUpdate {
+    let time =
+        Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap() + Duration::seconds(trade_id as i64);
+    TradeUpdate {
+        symbol: symbol("BTC_PERP"),
+        received_time: time,
+        trade_id,
+        price: Price::from_f64(100.0 + trade_id as f64).unwrap(),
+        size: TradeSize::from_f64(1.0).unwrap(),
+        trade_time: time,
+        is_buy: trade_id.is_multiple_of(2),
+    }
+}
+
+/// Writes trades 0..count as a last_trade scribe file with the price of `nan_trade` set to NaN.
+/// `Price` rejects NaN, so it is patched into the record batch, as an upstream parsing bug would leave it.
+fn write_tmp_trades_with_nan(tmp: &Path, count: u64, nan_trade: u64) -> String {
+    let dir = tmp.join("last_trade");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir
+        .join("BTC_PERP_25-10-05 00:00:00.pq")
+        .to_string_lossy()
+        .to_string();
+    let batch = TradeUpdate::to_record_batch((0..count).map(trade).collect()).unwrap();
+    let price_index = batch.schema().index_of("price").unwrap();
+    let prices: Float64Array = batch
+        .column(price_index)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap()
+        .values()
+        .iter()
+        .enumerate()
+        .map(|(i, &price)| {
+            if i as u64 == nan_trade {
+                f64::NAN
+            } else {
+                price
+            }
+        })
+        .collect::<Vec<f64>>()
+        .into();
+    let mut columns = batch.columns().to_vec();
+    columns[price_index] = Arc::new(prices);
+    let schema = versioned_schema::<TradeUpdate>();
+    let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+    let mut writer =
+        ArrowWriter::try_new(std::fs::File::create(&path).unwrap(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    path
+}
+
+/// Trade ids in every archived partition under `{target}/last_trade`, whatever the date
+fn archived_trade_ids(target: &Path) -> Vec<u64> {
+    fn visit(dir: &Path, ids: &mut Vec<u64>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                visit(&path, ids);
+            } else if path.file_name().and_then(|n| n.to_str()) == Some("data.parquet") {
+                let trades =
+                    read_replayable_parquet::<TradeUpdate>(path.to_str().unwrap()).unwrap();
+                ids.extend(trades.iter().map(|t| t.trade_id));
+            }
+        }
+    }
+    let mut ids = Vec::new();
+    visit(&target.join("last_trade"), &mut ids);
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untagged_filenames_parse_as_before() {
+        let path = tmp_filepath("/tmp/hyperliquid/perp/bbo", &symbol("BTC_PERP"), None);
+        assert_eq!(path, "/tmp/hyperliquid/perp/bbo/BTC_PERP_25-10-05 12:30:45.pq");
+
+        let (data_type, market, parsed, time) = Archiver::parse_tmp_filepath(&path).unwrap();
+        assert_eq!(data_type, "bbo");
+        assert_eq!(market, None);
+        assert_eq!(parsed, symbol("BTC_PERP"));
+        assert_eq!((time.year(), time.month(), time.day()), (2025, 10, 5));
+        assert_eq!((time.hour(), time.minute(), time.second()), (12, 30, 45));
+    }
+
+    #[test]
+    fn symbol_in_both_markets_stays_distinct_in_a_flat_directory() {
+        // Same symbol, same data type, same timestamp: only the market tag tells them apart
+        let hype = symbol("HYPE");
+        let spot = tmp_filepath("/tmp/flat/last_trade", &hype, Some("spot"));
+        let perp = tmp_filepath("/tmp/flat/last_trade", &hype, Some("perp"));
+        assert_ne!(spot, perp);
+        assert!(spot.ends_with("/spot.HYPE_25-10-05 12:30:45.pq"));
+
+        let (spot_type, spot_market, spot_symbol, _) = Archiver::parse_tmp_filepath(&spot).unwrap();
+        let (perp_type, perp_market, perp_symbol, _) = Archiver::parse_tmp_filepath(&perp).unwrap();
+        assert_eq!((spot_type.as_str(), perp_type.as_str()), ("last_trade", "last_trade"));
+        assert_eq!(spot_symbol, hype);
+        assert_eq!(perp_symbol, hype);
+        assert_eq!(spot_market.as_deref(), Some("spot"));
+        assert_eq!(perp_market.as_deref(), Some("perp"));
+
+        let spot_dir = Archiver::hive_partition_dir(
+            "/data",
+            &spot_type,
+            "2025-10-05",
+            spot_market.as_deref(),
+            &spot_symbol,
+        );
+        let perp_dir = Archiver::hive_partition_dir(
+            "/data",
+            &perp_type,
+            "2025-10-05",
+            perp_market.as_deref(),
+            &perp_symbol,
+        );
+        assert_eq!(spot_dir, "/data/last_trade/date=2025-10-05/market=spot/symbol=HYPE");
+        assert_eq!(perp_dir, "/data/last_trade/date=2025-10-05/market=perp/symbol=HYPE");
+        assert_eq!(
+            Archiver::hive_partition_dir("/data", "bbo", "2025-10-05", None, &hype),
+            "/data/bbo/date=2025-10-05/symbol=HYPE"
+        );
+    }
+
+    #[test]
+    fn market_tags_must_be_lowercase_alphanumeric() {
+        // Underscores inside the symbol never split off a market
+        let path = tmp_filepath("/tmp/spot/bbo", &symbol("PURR_USDC"), Some("spot"));
+        let (_, market, parsed, _) = Archiver::parse_tmp_filepath(&path).unwrap();
+        assert_eq!(market.as_deref(), Some("spot"));
+        assert_eq!(parsed, symbol("PURR_USDC"));
+
+        assert!(tmp_file_stem(&symbol("BTC"), Some("")).is_err());
+        assert!(tmp_file_stem(&symbol("BTC"), Some("Spot")).is_err());
+        assert!(tmp_file_stem(&symbol("BTC"), Some("sp.ot")).is_err());
+
+        // A dot after a non-tag prefix stays part of the symbol
+        let (_, market, parsed, _) =
+            Archiver::parse_tmp_filepath("/tmp/spot/bbo/K.PEPE_25-10-05 12:30:45.pq").unwrap();
+        assert_eq!(market, None);
+        assert_eq!(parsed, symbol("K.PEPE"));
+    }
+
+    #[tokio::test]
+    async fn time_dedup_drops_overlapping_orderbooks() {
+        let root = scratch_dir("time_dedup");
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let target_dir = target.to_str().unwrap();
+        let dedup = DedupKey::TimeColumn("time".to_string());
+        let compression = ParquetCompressionConfig::default();
+        // Files are stamped at local midnight: partition by local day so the date is host-independent
+        let tz = Some(PartitionTz::Local);
+
+        // First file repeats its last book (same exchange time, later receipt)
+        let books = (0..5).map(|s| book(s, 10)).chain([book(4, 900)]).collect();
+        let first = write_tmp_books(&tmp, "00:00:00", books);
+        let count = Archiver::flush_tmp_file(
+            &first,
+            target_dir,
+            compression,
+            Some(&dedup),
+            None,
+            tz,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 5);
+        assert!(!Path::new(&first).exists());
+
+        // Second file overlaps seconds 3..5; its receipts differ, exchange times do not
+        let second = write_tmp_books(&tmp, "00:00:04", (3..8).map(|s| book(s, 500)).collect());
+        let count = Archiver::flush_tmp_file(
+            &second,
+            target_dir,
+            compression,
+            Some(&dedup),
+            None,
+            tz,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 8);
+
+        let archived = archived_books(&target);
+        let times: Vec<_> = archived.iter().map(|b| b.time).collect();
+        assert_eq!(times.len(), times.iter().collect::<HashSet<_>>().len());
+        assert_eq!(times, (0..8).map(|s| book(s, 0).time).collect::<Vec<_>>());
+        // Already archived rows win
+        assert_eq!(archived[3], book(3, 10));
+        assert_eq!(archived[5], book(5, 500));
+
+        // Without a key, overlapping rows are appended as before
+        let third = write_tmp_books(&tmp, "00:00:08", vec![book(7, 0)]);
+        let count = Archiver::flush_tmp_file(&third, target_dir, compression, None, None, tz, None)
+            .await
+            .unwrap();
+        assert_eq!(count, 9);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn dedup_key_must_name_matching_column() {
+        let root = scratch_dir("dedup_column");
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let path = write_tmp_books(&tmp, "00:00:00", vec![book(0, 0)]);
+        let compression = ParquetCompressionConfig::default();
+
+        for key in [
+            DedupKey::TimeColumn("symbol".to_string()),
+            DedupKey::TimeColumn("missing".to_string()),
+            DedupKey::Column("time".to_string()),
+        ] {
+            let target_dir = target.to_str().unwrap();
+            let err = Archiver::flush_tmp_file(
+                &path,
+                target_dir,
+                compression,
+                Some(&key),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+            assert!(err.to_string().contains("DedupKey error"), "{}", err);
+            // Failed flushes keep the source file
+            assert!(Path::new(&path).exists());
+        }
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn lenient_policy_drops_and_counts_nan_rows() {
+        let root = scratch_dir("non_finite_lenient");
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let path = write_tmp_trades_with_nan(&tmp, 4, 2);
+        let dropped = metrics::archiver_non_finite_rows_dropped("last_trade");
+        let dropped_before = dropped.get();
+
+        let count = Archiver::flush_tmp_file(
+            &path,
+            target.to_str().unwrap(),
+            ParquetCompressionConfig::default(),
+            None,
+            Some(NonFinitePolicy::Lenient),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(archived_trade_ids(&target), vec![0, 1, 3]);
+        assert_eq!(dropped.get() - dropped_before, 1);
+        assert!(!Path::new(&path).exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn strict_policy_rejects_nan_and_keeps_the_file() {
+        let root = scratch_dir("non_finite_strict");
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let path = write_tmp_trades_with_nan(&tmp, 4, 2);
+
+        let err = Archiver::flush_tmp_file(
+            &path,
+            target.to_str().unwrap(),
+            ParquetCompressionConfig::default(),
+            None,
+            Some(NonFinitePolicy::Strict),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("non-finite value in column price"),
+            "{}",
+            err
+        );
+        assert!(Path::new(&path).exists());
+        assert!(archived_trade_ids(&target).is_empty());
+
+        // Finite files pass the strict check untouched
+        let clean = write_tmp_books(&tmp, "00:00:00", (0..3).map(|s| book(s, 0)).collect());
+        let count = Archiver::flush_tmp_file(
+            &clean,
+            target.to_str().unwrap(),
+            ParquetCompressionConfig::default(),
+            None,
+            Some(NonFinitePolicy::Strict),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 3);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn partition_date_follows_the_configured_timezone() {
+        let before_midnight = Utc.with_ymd_and_hms(2025, 10, 5, 23, 30, 0).unwrap();
+        let after_midnight = Utc.with_ymd_and_hms(2025, 10, 6, 0, 30, 0).unwrap();
+        let local = |t: DateTime<Utc>| t.with_timezone(&Local);
+        let offset = |hours: i32| PartitionTz::Offset(FixedOffset::east_opt(hours * 3600).unwrap());
+
+        assert_eq!(PartitionTz::default(), PartitionTz::Utc);
+        assert_eq!(
+            PartitionTz::Utc.partition_date(&local(before_midnight)),
+            "2025-10-05"
+        );
+        assert_eq!(
+            PartitionTz::Utc.partition_date(&local(after_midnight)),
+            "2025-10-06"
+        );
+        assert_eq!(
+            offset(1).partition_date(&local(before_midnight)),
+            "2025-10-06"
+        );
+        assert_eq!(
+            offset(-5).partition_date(&local(after_midnight)),
+            "2025-10-05"
+        );
+        assert_eq!(
+            PartitionTz::Local.partition_date(&local(before_midnight)),
+            local(before_midnight).format("%Y-%m-%d").to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn files_around_utc_midnight_split_by_utc_day() {
+        let root = scratch_dir("partition_tz");
+        let _ = std::fs::remove_dir_all(&root);
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let target_dir = target.to_str().unwrap();
+        let compression = ParquetCompressionConfig::default();
+        let midnight = Utc.with_ymd_and_hms(2025, 10, 6, 0, 0, 0).unwrap();
+
+        // Stamped one second either side of UTC midnight, in whatever timezone the host is in
+        for instant in [
+            midnight - Duration::seconds(1),
+            midnight + Duration::seconds(1),
+        ] {
+            let path = write_tmp_books_at(&tmp, instant, vec![book(0, 0)]);
+            Archiver::flush_tmp_file(&path, target_dir, compression, None, None, None, None)
+                .await
+                .unwrap();
+        }
+        assert!(has_book_partition(&target, "2025-10-05"));
+        assert!(has_book_partition(&target, "2025-10-06"));
+
+        // At UTC+2, 23:00 UTC is already the next day: joins the 2025-10-06 partition
+        let path = write_tmp_books_at(&tmp, midnight - Duration::hours(1), vec![book(1, 0)]);
+        let tz = PartitionTz::Offset(FixedOffset::east_opt(2 * 3600).unwrap());
+        let count =
+            Archiver::flush_tmp_file(&path, target_dir, compression, None, None, Some(tz), None)
+                .await
+                .unwrap();
+        assert_eq!(count, 2);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn flush_now_archives_the_scribes_final_file() {
+        let cluster = TestCluster::start().await.unwrap();
+        let root = scratch_dir("flush_now");
+        let _ = std::fs::remove_dir_all(&root);
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let trade_dir = tmp.join("last_trade");
+        std::fs::create_dir_all(&trade_dir).unwrap();
+        let prefix = format!("tests/archiver_{}/last_trade", std::process::id());
+
+        let mut publisher = Publisher::<TradeUpdate>::new(
+            "BTC_PERP".to_string(),
+            format!("{}/BTC_PERP", prefix),
+            trade(0),
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await
+        .unwrap();
+        // Flush interval far beyond the test: only the shutdown flush writes a file
+        let scribe = AgoraDirScribe::<TradeUpdate>::new(
+            &prefix,
+            cluster.metaserver(),
+            std::time::Duration::from_secs(3600),
+            trade_dir.to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+        let mut archiver = Archiver::new(
+            target.to_str().unwrap(),
+            &["last_trade".to_string()],
+            tmp.to_str().unwrap(),
+            None,
+            Default::default(),
+            None,
+            None,
+            HashMap::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        for id in 1..=3 {
+            publisher.publish(trade(id)).await.unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+        // The final file is its symbol's newest, which the timer never archives
+        scribe.shutdown().await.unwrap();
+        assert!(std::fs::read_dir(&trade_dir).unwrap().count() > 0);
+        assert!(archived_trade_ids(&target).is_empty());
+
+        assert!(archiver.flush_now().await.unwrap() > 0);
+        archiver.shutdown().await.unwrap();
+        assert_eq!(archived_trade_ids(&target), vec![0, 1, 2, 3]);
+        let leftover: Vec<_> = std::fs::read_dir(&trade_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("pq"))
+            .collect();
+        assert!(leftover.is_empty(), "{:?}", leftover);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn scan_interval_sets_how_often_the_timer_archives() {
+        let root = scratch_dir("scan_interval");
+        let _ = std::fs::remove_dir_all(&root);
+        let start = |name: &str, scan_interval: Option<std::time::Duration>| {
+            let (tmp, target) = (root.join(name).join("tmp"), root.join(name).join("target"));
+            std::fs::create_dir_all(tmp.join("orderbook")).unwrap();
+            async move {
+                let archiver = Archiver::new(
+                    target.to_str().unwrap(),
+                    &["orderbook".to_string()],
+                    tmp.to_str().unwrap(),
+                    None,
+                    HashMap::new(),
+                    None,
+                    None,
+                    HashMap::new(),
+                    None,
+                    scan_interval,
+                )
+                .await
+                .unwrap();
+                (archiver, tmp)
+            }
+        };
+        let (mut fast, fast_tmp) = start("fast", Some(std::time::Duration::from_millis(100))).await;
+        let (mut slow, slow_tmp) = start("slow", None).await;
+
+        // Written after the immediate first pass, so only a later tick can archive the older file
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let mut older = Vec::new();
+        for tmp in [&fast_tmp, &slow_tmp] {
+            older.push(write_tmp_books(tmp, "12:00:00", vec![book(0, 0)]));
+            write_tmp_books(tmp, "12:01:00", vec![book(60, 0)]);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+        assert!(!Path::new(&older[0]).exists());
+        assert!(Path::new(&older[1]).exists());
+
+        assert!(
+            Archiver::new(
+                root.join("fast/target").to_str().unwrap(),
+                &["orderbook".to_string()],
+                fast_tmp.to_str().unwrap(),
+                None,
+                HashMap::new(),
+                None,
+                None,
+                HashMap::new(),
+                None,
+                Some(std::time::Duration::ZERO),
+            )
+            .await
+            .is_err()
+        );
+
+        fast.shutdown().await.unwrap();
+        slow.shutdown().await.unwrap();
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn compaction_merges_fragments_into_one_sorted_deduped_file() {
+        let root = scratch_dir("compaction");
+        let _ = std::fs::remove_dir_all(&root);
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        std::fs::create_dir_all(tmp.join("orderbook")).unwrap();
+        let archiver = Archiver::new(
+            target.to_str().unwrap(),
+            &["orderbook".to_string()],
+            tmp.to_str().unwrap(),
+            None,
+            HashMap::from([(
+                "orderbook".to_string(),
+                DedupKey::TimeColumn("time".to_string()),
+            )]),
+            None,
+            None,
+            HashMap::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Fragments overlap the archived seconds 0..4 and each other, out of time order
+        write_partition_books(
+            &target,
+            "data.parquet",
+            (0..4).map(|s| book(s, 10)).collect(),
+        );
+        write_partition_books(
+            &target,
+            "part-b.parquet",
+            vec![book(6, 500), book(5, 500), book(3, 500)],
+        );
+        write_partition_books(
+            &target,
+            "part-a.parquet",
+            vec![book(4, 900), book(2, 900), book(7, 900), book(6, 900)],
+        );
+        let partition = Archiver::hive_partition_dir(
+            target.to_str().unwrap(),
+            "orderbook",
+            "2025-10-05",
+            None,
+            &symbol("BTC_PERP"),
+        );
+        let files = || std::fs::read_dir(&partition).unwrap().count();
+        assert_eq!(files(), 3);
+
+        let count = archiver
+            .compact_partition("orderbook", "2025-10-05", None, &symbol("BTC_PERP"))
+            .await
+            .unwrap();
+        assert_eq!(count, 8);
+        assert_eq!(files(), 1);
+        let archived = archived_books(&target);
+        let times: Vec<_> = archived.iter().map(|b| b.time).collect();
+        assert_eq!(times, (0..8).map(|s| book(s, 0).time).collect::<Vec<_>>());
+        // data.parquet wins, then fragments by name
+        assert_eq!(archived[2], book(2, 10));
+        assert_eq!(archived[3], book(3, 10));
+        assert_eq!(archived[6], book(6, 900));
+        assert_eq!(archived[5], book(5, 500));
+
+        // compact_all skips partitions already down to one file
+        assert_eq!(archiver.compact_all().await.unwrap(), 0);
+        write_partition_books(&target, "part-c.parquet", vec![book(8, 0), book(1, 0)]);
+        assert_eq!(archiver.compact_all().await.unwrap(), 1);
+        assert_eq!(files(), 1);
+        assert_eq!(archived_books(&target).len(), 9);
+
+        let err = archiver
+            .compact_partition("last_trade", "2025-10-05", None, &symbol("BTC_PERP"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("untracked data type"), "{}", err);
+
+        drop(archiver);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn fixed_depth_pads_shallow_and_truncates_deep_books() {
+        let root = scratch_dir("fixed_depth");
+        let _ = std::fs::remove_dir_all(&root);
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let target_dir = target.to_str().unwrap();
+        let compression = ParquetCompressionConfig::default();
+
+        // Deeper than the target depth, then shallower
+        let path = write_tmp_books(&tmp, "00:00:02", vec![deep_book(0, 5), deep_book(1, 1)]);
+        let count =
+            Archiver::flush_tmp_file(&path, target_dir, compression, None, None, None, Some(3))
+                .await
+                .unwrap();
+        assert_eq!(count, 2);
+
+        let batch = archived_book_batch(&target);
+        assert_eq!(
+            batch.schema().fields(),
+            OrderbookSnapshot::fixed_depth_arrow_schema().fields()
+        );
+        let column = |name: &str| -> Vec<u32> {
+            batch
+                .column_by_name(name)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .unwrap()
+                .values()
+                .to_vec()
+        };
+        assert_eq!(column("max_levels"), vec![3, 3]);
+        assert_eq!(column("bid_full_depth"), vec![5, 1]);
+        assert_eq!(column("ask_full_depth"), vec![5, 1]);
+
+        let bids = batch
+            .column_by_name("bid_levels")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap();
+        // (price, size) per entry of one row, None for padding
+        let bid_row = |row: usize| -> Vec<Option<(f64, f64)>> {
+            let entries = bids.value(row);
+            let levels = entries.as_any().downcast_ref::<StructArray>().unwrap();
+            let values = |name: &str| {
+                levels
+                    .column_by_name(name)
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<Float64Array>()
+                    .unwrap()
+                    .clone()
+            };
+            let (prices, sizes) = (values("price"), values("size"));
+            (0..levels.len())
+                .map(|i| {
+                    levels
+                        .is_valid(i)
+                        .then(|| (prices.value(i), sizes.value(i)))
+                })
+                .collect()
+        };
+        // Best three bids, best first, though the book listed them worst first
+        assert_eq!(
+            bid_row(0),
+            vec![Some((99.0, 1.0)), Some((98.0, 2.0)), Some((97.0, 3.0))]
+        );
+        // Padding is null, never a zero-size level
+        assert_eq!(bid_row(1), vec![Some((99.0, 1.0)), None, None]);
+
+        // Replay skips the padding
+        let archived = archived_books(&target);
+        assert_eq!(archived[0].bid_levels.len(), 3);
+        assert_eq!(archived[0].ask_levels[0].0.to_f64(), 101.0);
+        assert_eq!(archived[1], deep_book(1, 1));
+
+        // Full-depth rows cannot join a fixed-depth partition; the source file is kept
+        let path = write_tmp_books(&tmp, "00:00:04", vec![deep_book(3, 2)]);
+        let err = Archiver::flush_tmp_file(&path, target_dir, compression, None, None, None, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("columns differ"), "{}", err);
+        assert!(Path::new(&path).exists());
+        let count =
+            Archiver::flush_tmp_file(&path, target_dir, compression, None, None, None, Some(3))
+                .await
+                .unwrap();
+        assert_eq!(count, 3);
+
+        assert!(OrderbookSnapshot::to_fixed_depth_record_batch(vec![deep_book(0, 1)], 0).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,141 @@
+use argus::crypto::hyperliquid::OrderbookSnapshot;
+use argus::recording::{Archiver, ArgusParquetable, ParquetCompressionConfig};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use std::path::Path;
+
+/// US Eastern time as a POSIX rule, so no tz database is needed: clocks go back at 02:00 on 2025-11-02
+/// (01:00-02:00 happens twice) and forward at 02:00 on 2025-03-09 (02:00-03:00 never happens).
+/// Every test in this binary sets the same value, so running them in parallel is safe.
+fn use_eastern_time() {
+    // SAFETY: all tests of this binary write the same value and none reads the environment otherwise
+    unsafe { std::env::set_var("TZ", "EST5EDT,M3.2.0,M11.1.0") };
+}
+
+fn parsed_time(filename: &str) -> DateTime<Utc> {
+    let path = format!("/tmp/hyperliquid/perp/bbo/{}", filename);
+    let (_, _, _, time) = Archiver::parse_tmp_filepath(&path).unwrap();
+    time.with_timezone(&Utc)
+}
+
+fn utc(day: (i32, u32, u32), hms: (u32, u32, u32)) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(day.0, day.1, day.2, hms.0, hms.1, hms.2)
+        .unwrap()
+}
+
+fn book(time: DateTime<Utc>) -> OrderbookSnapshot {
+    OrderbookSnapshot {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: time,
+        time,
+        bid_levels: vec![(
+            Price::from_f64(99.5).unwrap(),
+            TradeSize::from_f64(1.0).unwrap(),
+            1,
+        )],
+        ask_levels: vec![(
+            Price::from_f64(100.5).unwrap(),
+            TradeSize::from_f64(2.0).unwrap(),
+            1,
+        )],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_local_time_parses_as_its_earlier_instant() {
+        use_eastern_time();
+        // 01:30 happens at 05:30 UTC (EDT) and again at 06:30 UTC (EST)
+        let ambiguous = chrono::NaiveDate::from_ymd_opt(2025, 11, 2)
+            .unwrap()
+            .and_hms_opt(1, 30, 0)
+            .unwrap();
+        assert!(Local.from_local_datetime(&ambiguous).single().is_none());
+
+        assert_eq!(
+            parsed_time("BTC_PERP_25-11-02 01:30:00.pq"),
+            utc((2025, 11, 2), (5, 30, 0))
+        );
+        // Either side of the repeated hour is unaffected
+        assert_eq!(
+            parsed_time("BTC_PERP_25-11-02 00:59:59.pq"),
+            utc((2025, 11, 2), (4, 59, 59))
+        );
+        assert_eq!(
+            parsed_time("BTC_PERP_25-11-02 02:00:00.pq"),
+            utc((2025, 11, 2), (7, 0, 0))
+        );
+    }
+
+    #[test]
+    fn skipped_local_time_parses_at_the_earlier_offset() {
+        use_eastern_time();
+        // 02:30 does not exist; read at EST it is 07:30 UTC, i.e. 03:30 EDT
+        assert_eq!(
+            parsed_time("BTC_PERP_25-03-09 02:30:00.pq"),
+            utc((2025, 3, 9), (7, 30, 0))
+        );
+    }
+
+    #[test]
+    fn utc_stamps_are_unambiguous_across_the_change() {
+        use_eastern_time();
+        // Both 01:30 local instants have distinct UTC stamps
+        let first = parsed_time("BTC_PERP_25-11-02 05:30:00Z.pq");
+        let second = parsed_time("spot.BTC_PERP_25-11-02 06:30:00Z.pq");
+        assert_eq!(first, utc((2025, 11, 2), (5, 30, 0)));
+        assert_eq!(second, utc((2025, 11, 2), (6, 30, 0)));
+        assert!(first < second);
+
+        let (_, market, symbol, _) =
+            Archiver::parse_tmp_filepath("/tmp/flat/bbo/spot.HYPE_25-11-02 06:30:00Z.pq").unwrap();
+        assert_eq!(market.as_deref(), Some("spot"));
+        assert_eq!(symbol, TradingSymbol::from_str("HYPE").unwrap());
+    }
+
+    #[tokio::test]
+    async fn file_stamped_in_the_repeated_hour_is_archived() {
+        use_eastern_time();
+        let root = std::env::temp_dir().join(format!("argus_archiver_dst_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let dir = tmp.join("orderbook");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir
+            .join("BTC_PERP_25-11-02 01:30:00.pq")
+            .to_string_lossy()
+            .to_string();
+        OrderbookSnapshot::write_to_parquet(
+            vec![book(utc((2025, 11, 2), (5, 30, 0)))],
+            path.clone(),
+        )
+        .unwrap();
+
+        let count = Archiver::flush_tmp_file(
+            &path,
+            target.to_str().unwrap(),
+            ParquetCompressionConfig::default(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 1);
+        assert!(!Path::new(&path).exists());
+        let partition = Archiver::hive_partition_dir(
+            target.to_str().unwrap(),
+            "orderbook",
+            "2025-11-02",
+            None,
+            &TradingSymbol::from_str("BTC_PERP").unwrap(),
+        );
+        assert!(Path::new(&format!("{}/data.parquet", partition)).exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
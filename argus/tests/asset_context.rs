@@ -0,0 +1,174 @@
+use agora::AgorableOption;
+use argus::crypto::hyperliquid::{HyperliquidStreamable, PerpAssetContext, SpotAssetContext};
+use argus::recording::{ArgusParquetable, ArgusReplayable};
+use argus::types::{Price, TradingSymbol};
+use bimap::BiMap;
+use chrono::{Duration, TimeZone, Utc};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::path::PathBuf;
+
+fn price(p: f64) -> Price {
+    Price::from_f64(p).unwrap()
+}
+
+fn perp_contexts() -> Vec<PerpAssetContext> {
+    let start = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    vec![
+        PerpAssetContext {
+            symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+            received_time: start,
+            mark_price: price(100_000.0),
+            mid_price: Some(price(100_000.5)),
+            funding_rate: 0.0001,
+            open_interest: Some(25_000.0),
+            volume_24h: Some(1.5e9),
+            oracle_price: Some(price(99_990.0)),
+            premium: Some(0.0003),
+            prev_day_price: Some(price(98_000.0)),
+            day_base_volume: Some(15_000.0),
+        },
+        // Illiquid market: every optional field missing
+        PerpAssetContext {
+            symbol: TradingSymbol::from_str("XYZ_PERP").unwrap(),
+            received_time: start + Duration::seconds(1),
+            mark_price: price(1.25),
+            mid_price: None,
+            funding_rate: -0.00005,
+            open_interest: None,
+            volume_24h: None,
+            oracle_price: None,
+            premium: None,
+            prev_day_price: None,
+            day_base_volume: None,
+        },
+    ]
+}
+
+fn spot_contexts() -> Vec<SpotAssetContext> {
+    let start = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    vec![
+        SpotAssetContext {
+            symbol: TradingSymbol::from_str("PURR_USDC").unwrap(),
+            received_time: start,
+            mark_price: price(0.05),
+            mid_price: Some(price(0.051)),
+            volume_24h: Some(500_000.0),
+            circulating_supply: Some(1e9),
+            total_supply: Some(1e10),
+            prev_day_price: Some(price(0.049)),
+            day_base_volume: Some(1e7),
+        },
+        SpotAssetContext {
+            symbol: TradingSymbol::from_str("NEW_USDC").unwrap(),
+            received_time: start + Duration::seconds(1),
+            mark_price: price(3.0),
+            mid_price: None,
+            volume_24h: None,
+            circulating_supply: None,
+            total_supply: None,
+            prev_day_price: None,
+            day_base_volume: None,
+        },
+    ]
+}
+
+fn temp_parquet(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("argus_asset_context_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+/// Writes `values` the way the scribe does (as `AgorableOption`, with a `None` in between)
+/// and reads them back with `ParquetRecordBatchReaderBuilder`.
+fn round_trip<T: ArgusReplayable>(values: Vec<T>, name: &str) -> Vec<T> {
+    let path = temp_parquet(name);
+    let mut options: Vec<AgorableOption<T>> =
+        values.into_iter().map(|v| AgorableOption(Some(v))).collect();
+    options.insert(1, AgorableOption(None));
+    AgorableOption::<T>::write_to_parquet(options, path.to_string_lossy().to_string()).unwrap();
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+        .unwrap();
+    assert_eq!(builder.schema().fields(), T::arrow_schema().fields());
+    let mut read = Vec::new();
+    for batch in builder.build().unwrap() {
+        read.extend(T::from_record_batch(&batch.unwrap()).unwrap());
+    }
+    let _ = std::fs::remove_file(&path);
+    read
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perp_context_parquet_round_trip() {
+        let original = perp_contexts();
+        assert_eq!(round_trip(original.clone(), "perp_context.parquet"), original);
+    }
+
+    #[test]
+    fn spot_context_parquet_round_trip() {
+        let original = spot_contexts();
+        assert_eq!(round_trip(original.clone(), "spot_context.parquet"), original);
+    }
+
+    #[test]
+    fn spot_and_perp_schemas_differ_in_market_specific_columns() {
+        let perp = PerpAssetContext::arrow_schema();
+        let spot = SpotAssetContext::arrow_schema();
+        for column in ["funding_rate", "open_interest", "oracle_price", "premium"] {
+            assert!(perp.field_with_name(column).is_ok(), "perp missing {}", column);
+            assert!(spot.field_with_name(column).is_err(), "spot has perp column {}", column);
+        }
+        for column in ["circulating_supply", "total_supply"] {
+            assert!(spot.field_with_name(column).is_ok(), "spot missing {}", column);
+            assert!(perp.field_with_name(column).is_err(), "perp has spot column {}", column);
+        }
+        for column in ["mark_price", "mid_price", "volume_24h", "prev_day_price", "day_base_volume"] {
+            assert!(perp.field_with_name(column).is_ok() && spot.field_with_name(column).is_ok());
+        }
+    }
+
+    #[test]
+    fn parses_active_asset_ctx_fields() {
+        let mut symbol_map = BiMap::new();
+        symbol_map.insert(
+            TradingSymbol::from_str("BTC_PERP").unwrap(),
+            TradingSymbol::from_str("BTC").unwrap(),
+        );
+        let data = serde_json::json!({
+            "coin": "BTC",
+            "ctx": {
+                "markPx": "100000.0",
+                "midPx": "100000.5",
+                "funding": "0.0001",
+                "openInterest": "25000.0",
+                "dayNtlVlm": "1500000000.0",
+                "oraclePx": "99990.0",
+                "premium": "0.0003",
+                "prevDayPx": "98000.0",
+                "dayBaseVlm": "15000.0",
+                "impactPxs": ["99999.0", "100001.0"]
+            }
+        });
+        let parsed = PerpAssetContext::of_channel_data(data, &symbol_map).unwrap();
+        assert_eq!(parsed.len(), 1);
+        let context = &parsed[0];
+        assert_eq!(context.symbol.to_string(), "BTC_PERP");
+        assert_eq!(context.premium, Some(0.0003));
+        assert_eq!(context.prev_day_price, Some(price(98_000.0)));
+        assert_eq!(context.day_base_volume, Some(15_000.0));
+
+        // Spot contexts carry no funding fields; missing optional fields parse as None
+        let data = serde_json::json!({
+            "coin": "PURR/USDC",
+            "ctx": {"markPx": "0.05", "prevDayPx": "0.049", "dayBaseVlm": "10000000.0"}
+        });
+        let spot = SpotAssetContext::of_channel_data(data, &BiMap::new()).unwrap();
+        assert_eq!(spot[0].prev_day_price, Some(price(0.049)));
+        assert_eq!(spot[0].day_base_volume, Some(1e7));
+        assert_eq!(spot[0].mid_price, None);
+    }
+}
@@ -0,0 +1,103 @@
+use argus::crypto::{Bar, BarBuilder};
+use argus::recording::{ArgusParquetable, ArgusReplayable};
+use argus::types::{Price, TradingSymbol};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+fn at(seconds: i64, millis: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 10, 5, 12, 0, 0).unwrap()
+        + Duration::seconds(seconds)
+        + Duration::milliseconds(millis)
+}
+
+fn price(p: f64) -> Price {
+    Price::from_f64(p).unwrap()
+}
+
+fn one_second_builder() -> BarBuilder {
+    BarBuilder::new(TradingSymbol::from_str("BTC_PERP").unwrap(), Duration::seconds(1)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_trade_opens_aligned_bucket() {
+        let mut builder = one_second_builder();
+        assert!(builder.current().is_none());
+        assert!(builder.advance_to(at(10, 0)).is_empty(), "No bars before the first trade");
+
+        assert!(builder.push_trade(at(0, 250), price(100.0), 1.0).is_empty());
+        let bar = builder.current().unwrap();
+        assert_eq!(bar.start, at(0, 0));
+        assert_eq!(bar.end, at(1, 0));
+        assert_eq!(bar.trade_count, 1);
+    }
+
+    #[test]
+    fn aggregates_ohlcv_within_bucket() {
+        let mut builder = one_second_builder();
+        let trades = [
+            (100, 100.0, 1.0),
+            (200, 103.0, 0.5),
+            (300, 98.0, 2.0),
+            (900, 101.0, 0.25),
+        ];
+        for (millis, p, size) in trades {
+            assert!(builder.push_trade(at(0, millis), price(p), size).is_empty());
+        }
+
+        let closed = builder.push_trade(at(1, 10), price(102.0), 1.0);
+        assert_eq!(closed.len(), 1);
+        let bar = &closed[0];
+        assert_eq!(bar.open.to_f64(), 100.0);
+        assert_eq!(bar.high.to_f64(), 103.0);
+        assert_eq!(bar.low.to_f64(), 98.0);
+        assert_eq!(bar.close.to_f64(), 101.0);
+        assert_eq!(bar.volume, 3.75);
+        assert_eq!(bar.trade_count, 4);
+    }
+
+    #[test]
+    fn empty_buckets_carry_forward_previous_close() {
+        let mut builder = one_second_builder();
+        builder.push_trade(at(0, 500), price(100.0), 1.0);
+
+        // Timer-driven close with no trades for three full buckets
+        let closed = builder.advance_to(at(3, 0));
+        assert_eq!(closed.len(), 3);
+        for (i, bar) in closed.iter().enumerate().skip(1) {
+            assert_eq!(bar.start, at(i as i64, 0));
+            assert_eq!(bar.volume, 0.0);
+            assert_eq!(bar.trade_count, 0);
+            assert_eq!(bar.open.to_f64(), 100.0);
+            assert_eq!(bar.close.to_f64(), 100.0);
+        }
+
+        // A trade after a gap closes the skipped buckets too
+        let closed = builder.push_trade(at(5, 100), price(105.0), 2.0);
+        assert_eq!(closed.len(), 2);
+        assert!(closed.iter().all(|bar| bar.trade_count == 0));
+        let bar = builder.current().unwrap();
+        assert_eq!(bar.start, at(5, 0));
+        assert_eq!(bar.open.to_f64(), 105.0);
+    }
+
+    #[test]
+    fn rejects_nonpositive_interval() {
+        let symbol = TradingSymbol::from_str("BTC_PERP").unwrap();
+        assert!(BarBuilder::new(symbol.clone(), Duration::zero()).is_err());
+        assert!(BarBuilder::new(symbol, Duration::seconds(-1)).is_err());
+    }
+
+    #[test]
+    fn bars_round_trip_through_record_batch() {
+        let mut builder = one_second_builder();
+        builder.push_trade(at(0, 100), price(100.0), 1.0);
+        builder.push_trade(at(0, 200), price(99.5), 1.0);
+        let bars: Vec<Bar> = builder.advance_to(at(2, 0));
+
+        let batch = Bar::to_record_batch(bars.clone()).unwrap();
+        assert_eq!(Bar::from_record_batch(&batch).unwrap(), bars);
+    }
+}
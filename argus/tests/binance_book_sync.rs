@@ -0,0 +1,219 @@
+use argus::crypto::binance::{
+    BinanceBookSynchronizer, BinanceRest, BinanceStreamable, OrderbookDepthUpdate,
+    OrderbookDiffUpdate,
+};
+use argus::crypto::hyperliquid::OrderbookSnapshot;
+use argus::types::TradingSymbol;
+use chrono::Utc;
+use serde_json::json;
+
+fn symbol() -> TradingSymbol {
+    TradingSymbol::from_str("BNBBTC").unwrap()
+}
+
+/// REST `depth` response as Binance sends it
+fn depth(
+    last_update_id: u64,
+    bids: &[(&str, &str)],
+    asks: &[(&str, &str)],
+) -> OrderbookDepthUpdate {
+    let body = json!({"lastUpdateId": last_update_id, "bids": bids, "asks": asks});
+    OrderbookDepthUpdate::of_json_bytes(body.to_string().into(), "BNBBTC", Utc::now()).unwrap()
+}
+
+/// `@depth` stream event as Binance sends it, covering update ids `first..=last`
+fn diff(
+    first: u64,
+    last: u64,
+    bids: &[(&str, &str)],
+    asks: &[(&str, &str)],
+) -> OrderbookDiffUpdate {
+    let event = json!({
+        "e": "depthUpdate",
+        "E": 1_675_216_573_000u64 + last,
+        "s": "BNBBTC",
+        "U": first,
+        "u": last,
+        "b": bids,
+        "a": asks,
+    });
+    OrderbookDiffUpdate::of_json_bytes(event.to_string().into()).unwrap()
+}
+
+/// (price, size) of one side, best first
+type Levels = Vec<(f64, f64)>;
+
+/// Both sides of `snapshot`
+fn levels(snapshot: &OrderbookSnapshot) -> (Levels, Levels) {
+    let side = |levels: &[(argus::types::Price, argus::types::TradeSize, u32)]| -> Vec<(f64, f64)> {
+        levels
+            .iter()
+            .map(|(price, size, _)| (price.to_f64(), size.to_f64()))
+            .collect()
+    };
+    (side(&snapshot.bid_levels), side(&snapshot.ask_levels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_buffered_diffs_after_snapshot() {
+        let mut book = BinanceBookSynchronizer::new(symbol(), None);
+        assert!(book.needs_snapshot());
+
+        // Recorded stream: diffs arrive before the snapshot and are buffered
+        let recorded = [
+            diff(151, 155, &[("0.0024", "5")], &[]),
+            diff(156, 158, &[("0.0023", "1")], &[("0.0027", "4")]),
+            diff(159, 162, &[("0.0024", "0")], &[("0.0026", "90")]),
+        ];
+        for event in recorded {
+            assert!(book.on_diff(event).unwrap().is_none());
+        }
+        assert_eq!(book.buffered_len(), 3);
+
+        // Snapshot at 157: the first diff is already in it, the second straddles it
+        let snapshot = book
+            .on_snapshot(&depth(
+                157,
+                &[("0.0024", "10"), ("0.0022", "3")],
+                &[("0.0026", "100")],
+            ))
+            .unwrap()
+            .expect("snapshot covering the buffer syncs the book");
+        assert_eq!(book.last_update_id(), Some(162));
+        assert_eq!(book.buffered_len(), 0);
+        assert_eq!(
+            levels(&snapshot),
+            (
+                vec![(0.0023, 1.0), (0.0022, 3.0)],
+                vec![(0.0026, 90.0), (0.0027, 4.0)]
+            )
+        );
+
+        // Live diffs apply directly; a repeat of applied ids changes nothing
+        let snapshot = book
+            .on_diff(diff(163, 163, &[("0.0025", "2")], &[]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(levels(&snapshot).0[0], (0.0025, 2.0));
+        assert!(book.on_diff(diff(160, 163, &[], &[])).unwrap().is_none());
+        assert_eq!(book.gaps(), 0);
+    }
+
+    #[test]
+    fn gap_forces_resnapshot_and_recovers() {
+        let mut book = BinanceBookSynchronizer::new(symbol(), None);
+        book.on_snapshot(&depth(100, &[("1.0", "1")], &[("2.0", "1")]))
+            .unwrap()
+            .unwrap();
+        book.on_diff(diff(101, 103, &[("1.1", "1")], &[]))
+            .unwrap()
+            .unwrap();
+
+        // 104..=107 never arrived
+        assert!(
+            book.on_diff(diff(108, 110, &[("1.2", "1")], &[]))
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(book.gaps(), 1);
+        assert!(book.needs_snapshot());
+        assert_eq!(book.buffered_len(), 1);
+        assert!(
+            book.on_diff(diff(111, 111, &[], &[("1.9", "2")]))
+                .unwrap()
+                .is_none()
+        );
+
+        // A snapshot older than the buffered diffs cannot bridge the gap
+        assert!(
+            book.on_snapshot(&depth(105, &[("1.0", "1")], &[("2.0", "1")]))
+                .unwrap()
+                .is_none()
+        );
+        assert!(book.needs_snapshot());
+
+        // A fresh snapshot at 109 rebuilds the book; 108..=110 straddles it and 111 follows
+        let snapshot = book
+            .on_snapshot(&depth(
+                109,
+                &[("1.1", "1"), ("1.05", "4"), ("1.0", "1")],
+                &[("2.0", "1")],
+            ))
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.last_update_id(), Some(111));
+        assert_eq!(
+            levels(&snapshot),
+            (
+                vec![(1.2, 1.0), (1.1, 1.0), (1.05, 4.0), (1.0, 1.0)],
+                vec![(1.9, 2.0), (2.0, 1.0)]
+            )
+        );
+        assert_eq!(book.gaps(), 1);
+    }
+
+    #[test]
+    fn gap_inside_buffer_keeps_waiting_for_a_later_snapshot() {
+        let mut book = BinanceBookSynchronizer::new(symbol(), None);
+        for event in [
+            diff(10, 12, &[("1.0", "1")], &[]),
+            diff(13, 14, &[("1.1", "1")], &[]),
+            // 15..=19 were lost
+            diff(20, 21, &[("1.2", "1")], &[]),
+        ] {
+            book.on_diff(event).unwrap();
+        }
+
+        assert!(book.on_snapshot(&depth(11, &[], &[])).unwrap().is_none());
+        assert_eq!(book.gaps(), 1);
+        assert_eq!(book.buffered_len(), 1);
+
+        let snapshot = book
+            .on_snapshot(&depth(20, &[("1.1", "1"), ("1.0", "1")], &[]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.last_update_id(), Some(21));
+        assert_eq!(
+            levels(&snapshot).0,
+            vec![(1.2, 1.0), (1.1, 1.0), (1.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn snapshot_truncates_levels_and_reset_drops_book() {
+        let mut book = BinanceBookSynchronizer::new(symbol(), Some(2));
+        let snapshot = book
+            .on_snapshot(&depth(
+                1,
+                &[("1.0", "1"), ("1.1", "1"), ("0.9", "1")],
+                &[("2.0", "1"), ("1.9", "1"), ("2.1", "1")],
+            ))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            levels(&snapshot),
+            (vec![(1.1, 1.0), (1.0, 1.0)], vec![(1.9, 1.0), (2.0, 1.0)])
+        );
+
+        book.reset();
+        assert!(book.needs_snapshot());
+        assert!(
+            book.on_diff(diff(2, 2, &[("1.2", "1")], &[]))
+                .unwrap()
+                .is_none()
+        );
+
+        // Diffs for another symbol or with invalid sizes are rejected
+        let mut other = diff(3, 3, &[], &[]);
+        other.symbol = TradingSymbol::from_str("ETHBTC").unwrap();
+        assert!(book.on_diff(other).is_err());
+        let mut negative = diff(3, 3, &[], &[]);
+        negative.bids = vec![(argus::types::Price::from_f64(1.0).unwrap(), -1.0)];
+        assert!(book.on_diff(negative).is_err());
+        assert_eq!(book.buffered_len(), 1);
+    }
+}
@@ -0,0 +1,111 @@
+use argus::recording::{
+    ArgusParquetable, BookJournalEntry, BookState, CheckpointPolicy, OrderbookEngine,
+    read_book_at, reconstruct_book_at,
+};
+use argus::types::{Price, TradingSymbol};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+/// (time, bids, asks) of one diff
+type Diff = (DateTime<Utc>, Vec<(Price, f64)>, Vec<(Price, f64)>);
+
+/// Deterministic diff stream: one diff per second over a day, touching a few levels around 100.0
+fn synthetic_diffs() -> Vec<Diff> {
+    let start = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    let mut state: u64 = 42;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        state >> 33
+    };
+
+    (0..86_400)
+        .map(|i| {
+            let mut bids = Vec::new();
+            let mut asks = Vec::new();
+            for _ in 0..3 {
+                let tick = (next() % 20) as f64 * 0.5;
+                // One in four updates removes the level
+                let size = if next() % 4 == 0 { 0.0 } else { (next() % 100) as f64 / 10.0 };
+                if next() % 2 == 0 {
+                    bids.push((Price::from_f64(99.5 - tick).unwrap(), size));
+                } else {
+                    asks.push((Price::from_f64(100.5 + tick).unwrap(), size));
+                }
+            }
+            (start + Duration::seconds(i), bids, asks)
+        })
+        .collect()
+}
+
+fn journal_and_reference(target: DateTime<Utc>) -> (Vec<BookJournalEntry>, BookState) {
+    let symbol = TradingSymbol::from_str("BTCUSDT").unwrap();
+    let policy = CheckpointPolicy {
+        every_updates: 500,
+        every_duration: Duration::minutes(5),
+    };
+    let mut engine = OrderbookEngine::new(symbol, policy).unwrap();
+    let mut journal = Vec::new();
+    let mut reference = None;
+    for (time, bids, asks) in synthetic_diffs() {
+        if time > target && reference.is_none() {
+            reference = Some(engine.book().clone());
+        }
+        journal.extend(engine.ingest(time, bids, asks));
+    }
+    (journal, reference.unwrap_or_else(|| engine.book().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_interleaves_checkpoints() {
+        let target = Utc.with_ymd_and_hms(2025, 10, 5, 13, 37, 29).unwrap();
+        let (journal, _) = journal_and_reference(target);
+
+        let diffs = journal.iter().filter(|e| !e.is_snapshot).count();
+        let snapshots = journal.iter().filter(|e| e.is_snapshot).count();
+        assert_eq!(diffs, 86_400);
+        // 300s cadence with 500-update cap: the time threshold fires first
+        assert_eq!(snapshots, 86_400 / 300);
+        assert!(journal.windows(2).all(|w| w[0].time <= w[1].time));
+    }
+
+    #[test]
+    fn reconstructs_mid_day_book_from_nearest_checkpoint() {
+        let target = Utc.with_ymd_and_hms(2025, 10, 5, 13, 37, 29).unwrap();
+        let (journal, reference) = journal_and_reference(target);
+
+        let book = reconstruct_book_at(&journal, target);
+        assert_eq!(book, reference);
+        assert!(!book.bids().is_empty() && !book.asks().is_empty());
+        assert!(book.bids()[0].0.to_f64() < book.asks()[0].0.to_f64());
+    }
+
+    #[test]
+    fn reconstructs_before_first_checkpoint_and_at_checkpoint_boundary() {
+        let start = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+        for target in [
+            start - Duration::seconds(1),
+            start,
+            start + Duration::minutes(5),
+        ] {
+            let (journal, reference) = journal_and_reference(target);
+            assert_eq!(reconstruct_book_at(&journal, target), reference);
+        }
+    }
+
+    #[test]
+    fn reconstructs_from_parquet_journal() {
+        let target = Utc.with_ymd_and_hms(2025, 10, 5, 18, 2, 11).unwrap();
+        let (journal, reference) = journal_and_reference(target);
+
+        let dir = std::env::temp_dir().join(format!("argus_checkpoint_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("journal.parquet").to_string_lossy().to_string();
+        BookJournalEntry::write_to_parquet(journal, path.clone()).unwrap();
+
+        assert_eq!(read_book_at(&path, target).unwrap(), reference);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
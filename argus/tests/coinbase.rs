@@ -0,0 +1,173 @@
+use argus::crypto::coinbase::{
+    CoinbaseBooks, CoinbaseMessage, CoinbaseStreamable, normalize_product_id, subscribe_message,
+};
+use argus::crypto::hyperliquid::{BboUpdate, OrderbookSnapshot, TradeUpdate};
+use argus::types::TradingSymbol;
+
+// Messages as sent by wss://advanced-trade-ws.coinbase.com
+const MARKET_TRADES: &str = r#"{"channel":"market_trades","client_id":"","timestamp":"2023-02-09T20:19:35.39625135Z","sequence_num":0,"events":[{"type":"update","trades":[{"trade_id":"000000000","product_id":"ETH-USD","price":"1260.01","size":"0.3","side":"BUY","time":"2019-08-14T20:42:27.265Z"},{"trade_id":"000000001","product_id":"ETH-USD","price":"1260.00","size":"1.25","side":"SELL","time":"2019-08-14T20:42:27.301Z"}]}]}"#;
+
+const TICKER: &str = r#"{"channel":"ticker","client_id":"","timestamp":"2023-02-09T20:30:37.167359596Z","sequence_num":0,"events":[{"type":"snapshot","tickers":[{"type":"ticker","product_id":"BTC-USD","price":"21932.98","volume_24_h":"16038.28770938","low_24_h":"21835.29","high_24_h":"23011.18","low_52_w":"15460","high_52_w":"48240","price_percent_chg_24_h":"-4.15775596190603","best_bid":"21931.98","best_bid_quantity":"8000.21","best_ask":"21933.98","best_ask_quantity":"8038.07770938"}]}]}"#;
+
+const L2_SNAPSHOT: &str = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:50.714964855Z","sequence_num":0,"events":[{"type":"snapshot","product_id":"BTC-USD","updates":[{"side":"bid","event_time":"1970-01-01T00:00:00Z","price_level":"21921.73","new_quantity":"0.06317902"},{"side":"bid","event_time":"1970-01-01T00:00:00Z","price_level":"21921.3","new_quantity":"0.02"},{"side":"offer","event_time":"1970-01-01T00:00:00Z","price_level":"21922.5","new_quantity":"0.5"},{"side":"offer","event_time":"1970-01-01T00:00:00Z","price_level":"21923.1","new_quantity":"1.1"}]}]}"#;
+
+const L2_UPDATE: &str = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:51.002120000Z","sequence_num":3,"events":[{"type":"update","product_id":"BTC-USD","updates":[{"side":"bid","event_time":"2023-02-09T20:32:50.98Z","price_level":"21921.73","new_quantity":"0"},{"side":"bid","event_time":"2023-02-09T20:32:50.98Z","price_level":"21921.9","new_quantity":"0.4"},{"side":"offer","event_time":"2023-02-09T20:32:50.99Z","price_level":"21923.1","new_quantity":"2.0"}]}]}"#;
+
+fn message(json: &str) -> CoinbaseMessage {
+    serde_json::from_str(json).unwrap()
+}
+
+fn symbol(s: &str) -> TradingSymbol {
+    TradingSymbol::from_str(s).unwrap()
+}
+
+fn levels(levels: &[(argus::types::Price, argus::types::TradeSize, u32)]) -> Vec<(f64, f64)> {
+    levels
+        .iter()
+        .map(|(price, size, _)| (price.to_f64(), size.to_f64()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_market_trades() {
+        let trades = TradeUpdate::of_message(&message(MARKET_TRADES), &mut ()).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].symbol, symbol("ETH-USD"));
+        assert_eq!(trades[0].trade_id, 0);
+        assert_eq!(trades[0].price.to_f64(), 1260.01);
+        assert_eq!(trades[0].size.to_f64(), 0.3);
+        assert!(trades[0].is_buy);
+        assert_eq!(trades[0].trade_time.timestamp_millis(), 1565815347265);
+        assert_eq!(trades[1].trade_id, 1);
+        assert!(!trades[1].is_buy);
+    }
+
+    #[test]
+    fn parses_ticker_into_bbo() {
+        let msg = message(TICKER);
+        let bbos = BboUpdate::of_message(&msg, &mut ()).unwrap();
+        assert_eq!(bbos.len(), 1);
+        let bbo = &bbos[0];
+        assert_eq!(bbo.symbol, symbol("BTC-USD"));
+        assert_eq!(bbo.bid_price.to_f64(), 21931.98);
+        assert_eq!(bbo.bid_size.to_f64(), 8000.21);
+        assert_eq!(bbo.ask_price.to_f64(), 21933.98);
+        assert_eq!(bbo.ask_size.to_f64(), 8038.07770938);
+        assert_eq!((bbo.bid_orders, bbo.ask_orders), (0, 0));
+        assert_eq!(bbo.time, msg.time().unwrap());
+    }
+
+    #[test]
+    fn level2_snapshot_then_update_maintains_book() {
+        let mut books = CoinbaseBooks::default();
+
+        let book = OrderbookSnapshot::of_message(&message(L2_SNAPSHOT), &mut books)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(book.symbol, symbol("BTC-USD"));
+        assert_eq!(
+            levels(&book.bid_levels),
+            vec![(21921.73, 0.06317902), (21921.3, 0.02)]
+        );
+        assert_eq!(
+            levels(&book.ask_levels),
+            vec![(21922.5, 0.5), (21923.1, 1.1)]
+        );
+
+        // Zero quantity removes the level; others insert or replace
+        let book = OrderbookSnapshot::of_message(&message(L2_UPDATE), &mut books)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(
+            levels(&book.bid_levels),
+            vec![(21921.9, 0.4), (21921.3, 0.02)]
+        );
+        assert_eq!(
+            levels(&book.ask_levels),
+            vec![(21922.5, 0.5), (21923.1, 2.0)]
+        );
+        assert_eq!(books.depth(&symbol("BTC-USD")), Some((2, 2)));
+    }
+
+    #[test]
+    fn level2_update_without_snapshot_fails() {
+        let mut books = CoinbaseBooks::default();
+        let error = OrderbookSnapshot::of_message(&message(L2_UPDATE), &mut books).unwrap_err();
+        assert!(
+            error.to_string().contains("before its snapshot"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn level2_snapshot_is_truncated_to_top_levels() {
+        let updates: Vec<String> = (0..50)
+            .flat_map(|i| {
+                [
+                    format!(
+                        r#"{{"side":"bid","event_time":"1970-01-01T00:00:00Z","price_level":"{}","new_quantity":"1"}}"#,
+                        100 - i
+                    ),
+                    format!(
+                        r#"{{"side":"offer","event_time":"1970-01-01T00:00:00Z","price_level":"{}","new_quantity":"1"}}"#,
+                        101 + i
+                    ),
+                ]
+            })
+            .collect();
+        let json = format!(
+            r#"{{"channel":"l2_data","timestamp":"2023-02-09T20:32:50Z","sequence_num":0,"events":[{{"type":"snapshot","product_id":"BTC-USD","updates":[{}]}}]}}"#,
+            updates.join(",")
+        );
+        let mut books = CoinbaseBooks::default();
+        let book = OrderbookSnapshot::of_message(&message(&json), &mut books)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(book.bid_levels.len(), 20);
+        assert_eq!(book.ask_levels.len(), 20);
+        assert_eq!(book.bid_levels[0].0.to_f64(), 100.0);
+        assert_eq!(book.ask_levels[0].0.to_f64(), 101.0);
+        assert_eq!(books.depth(&symbol("BTC-USD")), Some((50, 50)));
+    }
+
+    #[test]
+    fn error_reply_parses_with_empty_channel() {
+        let msg = message(r#"{"type":"error","message":"Failed to subscribe"}"#);
+        assert!(msg.channel.is_empty());
+    }
+
+    #[test]
+    fn normalizes_product_ids() {
+        for raw in ["BTC-USD", "btc-usd", "BTC/USD", "btc_usd", " BTC-USD "] {
+            assert_eq!(normalize_product_id(raw).unwrap(), symbol("BTC-USD"));
+        }
+        for bad in ["BTCUSD", "-USD", "BTC-", "BTC-USD-X", ""] {
+            assert!(normalize_product_id(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn subscribe_message_format() {
+        let request = subscribe_message("level2", &[symbol("BTC-USD"), symbol("ETH-USD")]);
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "type": "subscribe",
+                "product_ids": ["BTC-USD", "ETH-USD"],
+                "channel": "level2",
+            })
+        );
+        assert_eq!(TradeUpdate::channel(), "market_trades");
+        assert_eq!(BboUpdate::channel(), "ticker");
+        assert_eq!(OrderbookSnapshot::channel(), "level2");
+        assert_eq!(OrderbookSnapshot::message_channel(), "l2_data");
+    }
+}
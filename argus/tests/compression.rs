@@ -0,0 +1,70 @@
+use argus::recording::ParquetCompressionConfig;
+use parquet::basic::Compression;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_are_validated_at_construction() {
+        assert!(ParquetCompressionConfig::zstd(1).is_ok());
+        assert!(ParquetCompressionConfig::zstd(22).is_ok());
+        assert!(ParquetCompressionConfig::zstd(0).is_err());
+        assert!(ParquetCompressionConfig::zstd(23).is_err());
+        assert!(ParquetCompressionConfig::brotli(11).is_ok());
+        assert!(ParquetCompressionConfig::brotli(12).is_err());
+
+        let err = ParquetCompressionConfig::Zstd(99).validated().unwrap_err();
+        assert!(err.to_string().contains("out of range"));
+        assert!(ParquetCompressionConfig::Brotli(42).to_parquet().is_err());
+    }
+
+    #[test]
+    fn parses_codec_strings() {
+        assert_eq!(
+            ParquetCompressionConfig::from_str("zstd:5").unwrap(),
+            ParquetCompressionConfig::Zstd(5)
+        );
+        assert_eq!(
+            ParquetCompressionConfig::from_str("Brotli").unwrap(),
+            ParquetCompressionConfig::Brotli(3)
+        );
+        assert_eq!(
+            ParquetCompressionConfig::from_str("lz4").unwrap(),
+            ParquetCompressionConfig::Lz4
+        );
+        assert!(ParquetCompressionConfig::from_str("snappy:3").is_err());
+        assert!(ParquetCompressionConfig::from_str("brotli:-1").is_err());
+        assert!(ParquetCompressionConfig::from_str("gzip").is_err());
+
+        for config in [
+            ParquetCompressionConfig::Zstd(7),
+            ParquetCompressionConfig::Lz4,
+            ParquetCompressionConfig::Brotli(0),
+            ParquetCompressionConfig::Snappy,
+            ParquetCompressionConfig::Uncompressed,
+        ] {
+            assert_eq!(
+                ParquetCompressionConfig::from_str(&config.to_string()).unwrap(),
+                config
+            );
+        }
+    }
+
+    #[test]
+    fn maps_to_parquet_codecs() {
+        assert_eq!(ParquetCompressionConfig::default(), ParquetCompressionConfig::Zstd(3));
+        assert_eq!(
+            ParquetCompressionConfig::Lz4.to_parquet().unwrap(),
+            Compression::LZ4_RAW
+        );
+        assert_eq!(
+            ParquetCompressionConfig::Uncompressed.to_parquet().unwrap(),
+            Compression::UNCOMPRESSED
+        );
+        assert!(matches!(
+            ParquetCompressionConfig::Zstd(3).to_parquet().unwrap(),
+            Compression::ZSTD(_)
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+use agora::Publisher;
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::TradeUpdate;
+use argus::recording::{
+    AgoraDirScribe, Archiver, FilePathTemplate, read_replayable_parquet, tmp_file_stem,
+};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn symbol(name: &str) -> TradingSymbol {
+    TradingSymbol::from_str(name).unwrap()
+}
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "argus_file_path_template_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Compact layout unlike the default in every field: `{market}+{symbol}@{YYYYMMDDTHHMMSS}Z.parquet`
+fn compact_template() -> FilePathTemplate {
+    FilePathTemplate {
+        market_separator: '+',
+        time_separator: '@',
+        time_format: "%Y%m%dT%H%M%S",
+        extension: "parquet",
+    }
+}
+
+fn trade(symbol_name: &str, trade_id: u64) -> TradeUpdate {
+    let time = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    TradeUpdate {
+        symbol: symbol(symbol_name),
+        received_time: time,
+        trade_id,
+        price: Price::from_f64(100.0).unwrap(),
+        size: TradeSize::from_f64(1.0).unwrap(),
+        trade_time: time,
+        is_buy: true,
+    }
+}
+
+fn archived_trade_ids(dir: &Path, ids: &mut Vec<u64>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            archived_trade_ids(&path, ids);
+        } else if path.file_name().and_then(|n| n.to_str()) == Some("data.parquet") {
+            let trades = read_replayable_parquet::<TradeUpdate>(path.to_str().unwrap()).unwrap();
+            ids.extend(trades.iter().map(|t| t.trade_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendered_paths_parse_back_with_the_same_template() {
+        let flushed_at = Utc.with_ymd_and_hms(2025, 10, 5, 12, 30, 45).unwrap();
+        for template in [FilePathTemplate::default(), compact_template()] {
+            for market in [None, Some("spot")] {
+                for name in ["BTC_PERP", "PURR_USDC", "K.PEPE"] {
+                    let stem = template.stem(&symbol(name), market).unwrap();
+                    let path = template.file_path(&format!("/tmp/spot/bbo/{}", stem), flushed_at);
+                    let (data_type, parsed_market, parsed, time) = template.parse(&path).unwrap();
+                    assert_eq!(data_type, "bbo");
+                    assert_eq!(parsed_market.as_deref(), market, "{}", path);
+                    assert_eq!(parsed, symbol(name));
+                    assert_eq!(time, flushed_at);
+                    assert!(template.matches_extension(Path::new(&path)));
+                }
+            }
+        }
+
+        // The default template is the layout scribes and the archiver have always agreed on
+        let template = FilePathTemplate::default();
+        let stem = template.stem(&symbol("HYPE"), Some("perp")).unwrap();
+        assert_eq!(stem, tmp_file_stem(&symbol("HYPE"), Some("perp")).unwrap());
+        let path = template.file_path(&format!("/tmp/perp/last_trade/{}", stem), flushed_at);
+        assert_eq!(path, "/tmp/perp/last_trade/perp.HYPE_25-10-05 12:30:45Z.pq");
+        assert_eq!(
+            Archiver::parse_tmp_filepath(&path).unwrap(),
+            template.parse(&path).unwrap()
+        );
+
+        // Files of one layout are not mistaken for the other's
+        let compact = compact_template();
+        let path = compact.file_path("/tmp/perp/last_trade/perp+HYPE", flushed_at);
+        assert_eq!(
+            path,
+            "/tmp/perp/last_trade/perp+HYPE@20251005T123045Z.parquet"
+        );
+        assert!(template.parse(&path).is_err());
+        assert!(!template.matches_extension(Path::new(&path)));
+    }
+
+    #[test]
+    fn ambiguous_templates_are_rejected() {
+        assert!(FilePathTemplate::default().validated().is_ok());
+        assert!(compact_template().validated().is_ok());
+        let invalid = [
+            // Month names vary in width
+            FilePathTemplate {
+                time_format: "%d %B %Y %H:%M:%S",
+                ..Default::default()
+            },
+            // Loses the time of day
+            FilePathTemplate {
+                time_format: "%Y-%m-%d",
+                ..Default::default()
+            },
+            // Would read as a directory
+            FilePathTemplate {
+                time_format: "%y/%m/%d %H:%M:%S",
+                ..Default::default()
+            },
+            // Would read as part of the market tag
+            FilePathTemplate {
+                market_separator: 'x',
+                ..Default::default()
+            },
+            FilePathTemplate {
+                extension: "",
+                ..Default::default()
+            },
+            FilePathTemplate {
+                extension: "tar.pq",
+                ..Default::default()
+            },
+        ];
+        for template in invalid {
+            let err = template.validated().unwrap_err();
+            assert!(err.to_string().contains("FilePathTemplate"), "{}", err);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scribe_output_always_parses_and_archives_with_its_template() {
+        let cluster = TestCluster::start().await.unwrap();
+        let root = scratch_dir("scribe");
+        let (tmp, target) = (root.join("tmp"), root.join("target"));
+        let trade_dir = tmp.join("last_trade");
+        std::fs::create_dir_all(&trade_dir).unwrap();
+        let prefix = format!("tests/file_path_template_{}/last_trade", std::process::id());
+
+        let mut publishers = Vec::new();
+        for name in ["BTC_PERP", "ETH_PERP"] {
+            publishers.push((
+                name,
+                Publisher::<TradeUpdate>::new(
+                    name.to_string(),
+                    format!("{}/{}", prefix, name),
+                    trade(name, 0),
+                    cluster.metaserver(),
+                    cluster.gateway_port(),
+                )
+                .await
+                .unwrap(),
+            ));
+        }
+        let template = compact_template();
+        let scribe = AgoraDirScribe::<TradeUpdate>::with_market(
+            &prefix,
+            cluster.metaserver(),
+            Duration::from_secs(1),
+            trade_dir.to_str().unwrap(),
+            Some("perp"),
+            None,
+            Some(template),
+        )
+        .await
+        .unwrap();
+        // Flushes must land in different seconds, or the later file replaces the earlier one:
+        // the initial values flush at 1s, the updates at shutdown, over a second later
+        tokio::time::sleep(Duration::from_millis(2200)).await;
+        for (id, (name, publisher)) in (1..).zip(publishers.iter_mut()) {
+            publisher.publish(trade(name, id)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // The shutdown flush is named like the periodic ones
+        scribe.shutdown().await.unwrap();
+
+        let files: Vec<PathBuf> = std::fs::read_dir(&trade_dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        assert!(!files.is_empty());
+        for file in &files {
+            let (data_type, market, parsed, _) = template.parse(file.to_str().unwrap()).unwrap();
+            assert_eq!(data_type, "last_trade");
+            assert_eq!(market.as_deref(), Some("perp"));
+            assert!(parsed == symbol("BTC_PERP") || parsed == symbol("ETH_PERP"));
+        }
+
+        let mut archiver = Archiver::new(
+            target.to_str().unwrap(),
+            &["last_trade".to_string()],
+            tmp.to_str().unwrap(),
+            None,
+            HashMap::new(),
+            None,
+            None,
+            HashMap::new(),
+            Some(template),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(archiver.flush_now().await.unwrap() >= 2);
+        archiver.shutdown().await.unwrap();
+        let mut ids = Vec::new();
+        archived_trade_ids(&target.join("last_trade"), &mut ids);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids, vec![0, 1, 2]);
+        let leftover: Vec<PathBuf> = std::fs::read_dir(&trade_dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|path| template.matches_extension(path))
+            .collect();
+        assert!(leftover.is_empty(), "{:?}", leftover);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
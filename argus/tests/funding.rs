@@ -0,0 +1,60 @@
+use argus::crypto::hyperliquid::{FundingTracker, PerpAssetContext, crosses_threshold};
+use argus::types::{Price, TradingSymbol};
+use chrono::{Duration, TimeZone, Utc};
+
+fn context(seconds: i64, funding_rate: f64) -> PerpAssetContext {
+    PerpAssetContext {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap()
+            + Duration::seconds(seconds),
+        mark_price: Price::from_f64(100_000.0).unwrap(),
+        mid_price: None,
+        funding_rate,
+        open_interest: Some(1e9),
+        volume_24h: None,
+        oracle_price: None,
+        premium: None,
+        prev_day_price: None,
+        day_base_volume: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_emits_only_on_funding_changes() {
+        let mut tracker = FundingTracker::default();
+
+        let (update, previous) = tracker.observe(&context(0, 0.0001)).unwrap();
+        assert_eq!(previous, None);
+        assert_eq!(update.funding_rate, 0.0001);
+        assert_eq!(update.symbol, TradingSymbol::from_str("BTC_PERP").unwrap());
+        assert_eq!(update.time, context(0, 0.0).received_time);
+
+        // Mark price and OI updates with unchanged funding are not republished
+        assert!(tracker.observe(&context(1, 0.0001)).is_none());
+
+        let (update, previous) = tracker.observe(&context(2, -0.0003)).unwrap();
+        assert_eq!(previous, Some(0.0001));
+        assert_eq!(update.funding_rate, -0.0003);
+        assert_eq!(tracker.last_rate(), Some(-0.0003));
+    }
+
+    #[test]
+    fn threshold_fires_on_upward_crossing_of_magnitude() {
+        let threshold = 0.0005;
+        // Crossing from below, in either sign
+        assert!(crosses_threshold(Some(0.0001), 0.0006, threshold));
+        assert!(crosses_threshold(Some(0.0001), -0.0005, threshold));
+        // Unseen symbol already past the threshold
+        assert!(crosses_threshold(None, 0.001, threshold));
+        // Staying above, staying below, or falling back do not fire
+        assert!(!crosses_threshold(Some(0.0007), 0.0009, threshold));
+        assert!(!crosses_threshold(Some(-0.0007), 0.0009, threshold));
+        assert!(!crosses_threshold(Some(0.0001), 0.0002, threshold));
+        assert!(!crosses_threshold(Some(0.0009), 0.0001, threshold));
+        assert!(!crosses_threshold(None, 0.0001, threshold));
+    }
+}
@@ -0,0 +1,249 @@
+use agora::testkit::TestCluster;
+use agora::{Agorable, AgorableOption, ConnectionHandle, Publisher};
+use argus::crypto::hyperliquid::{
+    BboUpdate, HyperliquidScribe, HyperliquidScribeBuilder, OrderbookSnapshot, PerpAssetContext,
+    SpotAssetContext, TradeUpdate,
+};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::Utc;
+use std::any::Any;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "argus_hyperliquid_scribe_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Registers a BTC publisher (holding `None`) under `{agora_path}/{market}/{data_type}`
+async fn publisher<T: Agorable>(
+    cluster: &TestCluster,
+    agora_path: &str,
+    market: &str,
+    data_type: &str,
+) -> Publisher<AgorableOption<T>> {
+    Publisher::<AgorableOption<T>>::new(
+        "BTC".to_string(),
+        format!("{}/{}/{}/BTC", agora_path, market, data_type),
+        AgorableOption(None),
+        cluster.metaserver(),
+        cluster.gateway_port(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Every data type HyperliquidPublisher serves except the perp orderbook.
+/// The returned publishers must be kept alive for the scribe to find them.
+async fn publishers_without_perp_orderbook(
+    cluster: &TestCluster,
+    agora_path: &str,
+) -> Vec<Box<dyn Any>> {
+    vec![
+        Box::new(publisher::<TradeUpdate>(cluster, agora_path, "spot", "last_trade").await),
+        Box::new(publisher::<BboUpdate>(cluster, agora_path, "spot", "bbo").await),
+        Box::new(publisher::<OrderbookSnapshot>(cluster, agora_path, "spot", "orderbook").await),
+        Box::new(publisher::<SpotAssetContext>(cluster, agora_path, "spot", "spot_context").await),
+        Box::new(publisher::<TradeUpdate>(cluster, agora_path, "perp", "last_trade").await),
+        Box::new(publisher::<BboUpdate>(cluster, agora_path, "perp", "bbo").await),
+        Box::new(publisher::<PerpAssetContext>(cluster, agora_path, "perp", "perp_context").await),
+    ]
+}
+
+fn trade() -> TradeUpdate {
+    TradeUpdate {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: Utc::now(),
+        trade_id: 1,
+        price: Price::from_f64(100.0).unwrap(),
+        size: TradeSize::from_f64(0.5).unwrap(),
+        trade_time: Utc::now(),
+        is_buy: true,
+    }
+}
+
+fn bbo() -> BboUpdate {
+    BboUpdate {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: Utc::now(),
+        time: Utc::now(),
+        bid_price: Price::from_f64(99.5).unwrap(),
+        bid_size: TradeSize::from_f64(1.0).unwrap(),
+        bid_orders: 1,
+        ask_price: Price::from_f64(100.5).unwrap(),
+        ask_size: TradeSize::from_f64(1.0).unwrap(),
+        ask_orders: 1,
+    }
+}
+
+/// Files the scribe has written under `dir` so far
+fn file_count(dir: &Path) -> usize {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.count())
+        .unwrap_or(0)
+}
+
+/// Polls until a file appears under `dir`; false on timeout
+async fn wait_for_file(dir: &Path, timeout: Duration) -> bool {
+    tokio::time::timeout(timeout, async {
+        while file_count(dir) == 0 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+fn builder(
+    agora_path: &str,
+    output_dir: &Path,
+    metaserver: ConnectionHandle,
+) -> HyperliquidScribeBuilder {
+    HyperliquidScribeBuilder::new(
+        agora_path,
+        output_dir.to_str().unwrap(),
+        metaserver,
+        Duration::from_secs(3600),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn partial_build_skips_the_data_type_without_publishers() {
+        let cluster = TestCluster::start().await.unwrap();
+        let agora_path = format!("tests/hyperliquid_scribe_{}/partial", std::process::id());
+        let output_dir = scratch_dir("partial");
+        let _publishers = publishers_without_perp_orderbook(&cluster, &agora_path).await;
+
+        let (scribe, warnings) = builder(&agora_path, &output_dir, cluster.metaserver())
+            .allow_partial(true)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(
+            warnings[0].starts_with("perp/orderbook: "),
+            "{}",
+            warnings[0]
+        );
+        assert_eq!(
+            scribe.data_types(),
+            vec![
+                "spot/last_trade",
+                "spot/bbo",
+                "spot/orderbook",
+                "spot/spot_context",
+                "perp/last_trade",
+                "perp/bbo",
+                "perp/perp_context",
+            ]
+        );
+        scribe.shutdown().await.unwrap();
+
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn strict_build_fails_on_the_data_type_without_publishers() {
+        let cluster = TestCluster::start().await.unwrap();
+        let agora_path = format!("tests/hyperliquid_scribe_{}/strict", std::process::id());
+        let output_dir = scratch_dir("strict");
+        let _publishers = publishers_without_perp_orderbook(&cluster, &agora_path).await;
+
+        // The six scribes started before the perp orderbook are shut down, not leaked
+        let err = builder(&agora_path, &output_dir, cluster.metaserver())
+            .build()
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Perp orderbook"), "{:#}", err);
+        let err = HyperliquidScribe::new(
+            &agora_path,
+            output_dir.to_str().unwrap(),
+            cluster.metaserver(),
+            Duration::from_secs(3600),
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("Perp orderbook"), "{:#}", err);
+
+        // Nothing to record at all is an error even for partial builds
+        let err = builder(
+            "tests/hyperliquid_scribe_missing",
+            &output_dir,
+            cluster.metaserver(),
+        )
+        .allow_partial(true)
+        .build()
+        .await
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("no data type"), "{:#}", err);
+
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn data_types_flush_at_their_own_intervals() {
+        let cluster = TestCluster::start().await.unwrap();
+        let agora_path = format!("tests/hyperliquid_scribe_{}/intervals", std::process::id());
+        let output_dir = scratch_dir("intervals");
+        let mut trades =
+            publisher::<TradeUpdate>(&cluster, &agora_path, "perp", "last_trade").await;
+        let mut bbos = publisher::<BboUpdate>(&cluster, &agora_path, "perp", "bbo").await;
+
+        let intervals = builder(&agora_path, &output_dir, cluster.metaserver())
+            .flush_interval("last_trade", Duration::from_millis(300))
+            .flush_interval("bbo", Duration::from_secs(2))
+            .allow_partial(true);
+        assert_eq!(
+            intervals.flush_duration_of("last_trade"),
+            Duration::from_millis(300)
+        );
+        // Data types without their own interval keep the shared one
+        assert_eq!(
+            intervals.flush_duration_of("orderbook"),
+            Duration::from_secs(3600)
+        );
+        let started = Instant::now();
+        let (scribe, _) = intervals.build().await.unwrap();
+        assert_eq!(scribe.data_types(), vec!["perp/last_trade", "perp/bbo"]);
+        trades.publish(AgorableOption(Some(trade()))).await.unwrap();
+        bbos.publish(AgorableOption(Some(bbo()))).await.unwrap();
+
+        // Trades flush after 300ms, while BBOs are still buffered until 2s
+        let trade_dir = output_dir.join("perp/last_trade");
+        let bbo_dir = output_dir.join("perp/bbo");
+        assert!(wait_for_file(&trade_dir, Duration::from_secs(1)).await);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(file_count(&bbo_dir), 0);
+        assert!(wait_for_file(&bbo_dir, Duration::from_secs(5)).await);
+        assert!(started.elapsed() >= Duration::from_secs(2));
+        scribe.shutdown().await.unwrap();
+
+        // A misspelt data type fails the build instead of silently keeping the shared interval
+        let err = builder(&agora_path, &output_dir, cluster.metaserver())
+            .flush_interval("trades", Duration::from_secs(5))
+            .build()
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains("unknown data type 'trades'"),
+            "{:#}",
+            err
+        );
+
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+}
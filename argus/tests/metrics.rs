@@ -0,0 +1,96 @@
+use argus::metrics::{self, MetricsExporter, MetricsRegistry};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Sends a raw HTTP/1.1 request and returns the full response (the exporter closes the connection)
+async fn http_request(addr: SocketAddr, request_line: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("{}\r\nHost: localhost\r\n\r\n", request_line);
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_groups_series_under_one_family() {
+        let registry = MetricsRegistry::new();
+        let btc = registry.counter(
+            "argus_messages_published_total",
+            "Messages published",
+            &[("market", "perp"), ("symbol", "BTC_PERP")],
+        );
+        // Label order does not matter: same series
+        let btc_again = registry.counter(
+            "argus_messages_published_total",
+            "Messages published",
+            &[("symbol", "BTC_PERP"), ("market", "perp")],
+        );
+        let eth = registry.counter(
+            "argus_messages_published_total",
+            "Messages published",
+            &[("market", "perp"), ("symbol", "ETH_PERP")],
+        );
+        btc.add(3);
+        btc_again.inc();
+        eth.inc();
+        registry.counter("argus_unlabeled_total", "No labels", &[]);
+
+        assert_eq!(btc.get(), 4);
+        assert_eq!(
+            registry.render(),
+            "# HELP argus_messages_published_total Messages published\n\
+             # TYPE argus_messages_published_total counter\n\
+             argus_messages_published_total{market=\"perp\",symbol=\"BTC_PERP\"} 4\n\
+             argus_messages_published_total{market=\"perp\",symbol=\"ETH_PERP\"} 1\n\
+             # HELP argus_unlabeled_total No labels\n\
+             # TYPE argus_unlabeled_total counter\n\
+             argus_unlabeled_total 0\n"
+        );
+    }
+
+    #[test]
+    fn render_escapes_label_values() {
+        let registry = MetricsRegistry::new();
+        registry
+            .counter("argus_test_total", "Escaping", &[("symbol", "a\"b\\c\nd")])
+            .inc();
+        assert!(
+            registry
+                .render()
+                .contains("argus_test_total{symbol=\"a\\\"b\\\\c\\nd\"} 1\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn exporter_serves_global_registry() {
+        metrics::archiver_records_flushed("exporter_test").add(42);
+        let exporter = MetricsExporter::serve("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let addr = exporter.local_addr();
+
+        let response = http_request(addr, "GET /metrics HTTP/1.1").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("# TYPE argus_archiver_records_flushed_total counter\n"));
+        assert!(
+            response.contains("argus_archiver_records_flushed_total{data_type=\"exporter_test\"} 42\n")
+        );
+
+        let response = http_request(addr, "GET /other HTTP/1.1").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", response);
+        let response = http_request(addr, "POST /metrics HTTP/1.1").await;
+        assert!(response.starts_with("HTTP/1.1 405"), "{}", response);
+
+        // Dropping the exporter stops serving
+        drop(exporter);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+}
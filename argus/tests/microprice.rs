@@ -0,0 +1,58 @@
+use argus::crypto::hyperliquid::BboUpdate;
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::{TimeZone, Utc};
+
+/// Sizes go through serde so that the degenerate zero size (rejected by `TradeSize::from_f64`) can be built
+fn bbo(bid_price: f64, bid_size: f64, ask_price: f64, ask_size: f64) -> BboUpdate {
+    let time = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    BboUpdate {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: time,
+        time,
+        bid_price: Price::from_f64(bid_price).unwrap(),
+        bid_size: serde_json::from_value::<TradeSize>(serde_json::json!(bid_size)).unwrap(),
+        bid_orders: 1,
+        ask_price: Price::from_f64(ask_price).unwrap(),
+        ask_size: serde_json::from_value::<TradeSize>(serde_json::json!(ask_size)).unwrap(),
+        ask_orders: 1,
+    }
+}
+
+fn assert_close(actual: Option<Price>, expected: f64) {
+    let actual = actual.expect("expected a price").to_f64();
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn microprice_matches_hand_computed_values() {
+        // (100 * 1 + 101 * 3) / 4 = 403 / 4: heavy bid pulls toward the ask
+        assert_close(bbo(100.0, 3.0, 101.0, 1.0).microprice(), 100.75);
+        // (100 * 3 + 101 * 1) / 4 = 401 / 4: heavy ask pulls toward the bid
+        assert_close(bbo(100.0, 1.0, 101.0, 3.0).microprice(), 100.25);
+        // Equal sizes: microprice is the mid
+        let balanced = bbo(2500.0, 2.5, 2500.5, 2.5);
+        assert_close(balanced.microprice(), 2500.25);
+        assert_close(balanced.mid_price(), 2500.25);
+        // (0.1 * 0.5 + 0.102 * 1.5) / 2 = 0.203 / 2
+        assert_close(bbo(0.1, 1.5, 0.102, 0.5).microprice(), 0.1015);
+    }
+
+    #[test]
+    fn microprice_handles_empty_sides() {
+        // No size at the touch: undefined
+        let empty = bbo(100.0, 0.0, 101.0, 0.0);
+        assert!(empty.microprice().is_none());
+        assert_close(empty.mid_price(), 100.5);
+        // Empty ask side: all weight on the ask price
+        assert_close(bbo(100.0, 2.0, 101.0, 0.0).microprice(), 101.0);
+    }
+}
@@ -0,0 +1,246 @@
+use agora::utils::OrError;
+use argus::crypto::hyperliquid::{
+    BookSide, HyperliquidStreamable, LevelOrderPolicy, OrderbookSnapshot,
+};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use bimap::BiMap;
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+/// Raw l2Book payload with `depth` levels per side around 100.0, listed in the given order
+fn raw_book(depth: u32, reversed: bool) -> serde_json::Value {
+    let side = |sign: f64| {
+        let mut levels: Vec<_> = (0..depth)
+            .map(|i| {
+                json!({
+                    "px": format!("{}", 100.0 + sign * (0.5 + i as f64 * 0.5)),
+                    "sz": format!("{}", 1.0 + i as f64),
+                    "n": i + 1,
+                })
+            })
+            .collect();
+        if reversed {
+            levels.reverse();
+        }
+        levels
+    };
+    json!({
+        "coin": "BTC",
+        "time": 1759622400000u64,
+        "levels": [side(-1.0), side(1.0)],
+    })
+}
+
+fn symbol_map() -> BiMap<TradingSymbol, TradingSymbol> {
+    let mut map = BiMap::new();
+    map.insert(
+        TradingSymbol::from_str("BTC_PERP").unwrap(),
+        TradingSymbol::from_str("BTC").unwrap(),
+    );
+    map
+}
+
+fn parse(data: serde_json::Value) -> OrderbookSnapshot {
+    OrderbookSnapshot::of_channel_data(data, &symbol_map())
+        .unwrap()
+        .pop()
+        .unwrap()
+}
+
+/// Hand-built book from (price, size) levels, kept in the given (not necessarily best-first) order
+fn book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> OrderbookSnapshot {
+    let time = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    let levels = |side: &[(f64, f64)]| {
+        side.iter()
+            .map(|(price, size)| {
+                (
+                    Price::from_f64(*price).unwrap(),
+                    TradeSize::from_f64(*size).unwrap(),
+                    1,
+                )
+            })
+            .collect()
+    };
+    OrderbookSnapshot {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: time,
+        time,
+        bid_levels: levels(bids),
+        ask_levels: levels(asks),
+    }
+}
+
+fn parse_with(data: serde_json::Value, policy: LevelOrderPolicy) -> OrError<OrderbookSnapshot> {
+    OrderbookSnapshot::of_channel_data_with_policy(data, &symbol_map(), policy)
+        .map(|mut books| books.pop().unwrap())
+}
+
+/// `raw_book(3, false)` with the first two levels of `side` (0 bids, 1 asks) swapped
+fn swapped_book(side: usize) -> serde_json::Value {
+    let mut data = raw_book(3, false);
+    data["levels"][side].as_array_mut().unwrap().swap(0, 1);
+    data
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_fifty_level_book_to_best_five() {
+        let mut book = parse(raw_book(50, false));
+        assert_eq!(book.bid_levels.len(), 50);
+        assert_eq!(book.symbol, TradingSymbol::from_str("BTC_PERP").unwrap());
+
+        book.truncate_levels(5);
+        assert_eq!(book.bid_levels.len(), 5);
+        assert_eq!(book.ask_levels.len(), 5);
+
+        let bids: Vec<f64> = book.bid_levels.iter().map(|l| l.0.to_f64()).collect();
+        let asks: Vec<f64> = book.ask_levels.iter().map(|l| l.0.to_f64()).collect();
+        assert_eq!(bids, vec![99.5, 99.0, 98.5, 98.0, 97.5]);
+        assert_eq!(asks, vec![100.5, 101.0, 101.5, 102.0, 102.5]);
+        // Per-level size and order count travel with their price
+        for (i, (_, size, n)) in book.bid_levels.iter().enumerate() {
+            assert_eq!(size.to_f64(), 1.0 + i as f64);
+            assert_eq!(*n, i as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn truncation_keeps_best_levels_regardless_of_input_order() {
+        let mut book = parse(raw_book(50, true));
+        book.truncate(5);
+        assert_eq!(book.bid_levels[0].0.to_f64(), 99.5);
+        assert_eq!(book.ask_levels[0].0.to_f64(), 100.5);
+        assert_eq!(book.ask_levels[4].2, 5);
+
+        // Shallower books are left intact
+        let mut shallow = parse(raw_book(3, false));
+        shallow.truncate(20);
+        assert_eq!(shallow.bid_levels.len(), 3);
+        assert_eq!(shallow.ask_levels.len(), 3);
+    }
+
+    #[test]
+    fn analytics_match_hand_computed_values() {
+        // Bid sizes 3, 2, 5 and ask sizes 1, 4, 2 going away from the touch, listed out of order
+        let book = book(
+            &[(99.0, 2.0), (100.0, 3.0), (98.0, 5.0)],
+            &[(103.0, 2.0), (101.0, 1.0), (102.0, 4.0)],
+        );
+
+        // (3 - 1) / (3 + 1)
+        assert_close(book.book_imbalance(1).unwrap(), 0.5);
+        // (5 - 5) / 10
+        assert_close(book.book_imbalance(2).unwrap(), 0.0);
+        // (10 - 7) / 17; deeper requests use every level there is
+        assert_close(book.book_imbalance(3).unwrap(), 3.0 / 17.0);
+        assert_close(book.book_imbalance(50).unwrap(), 3.0 / 17.0);
+        assert!(book.book_imbalance(0).is_none());
+
+        // Level 1 is the microprice: (100 * 1 + 101 * 3) / 4
+        assert_close(book.weighted_mid(1).unwrap().to_f64(), 100.75);
+        // Bid VWAP (300 + 198) / 5 = 99.6, ask VWAP (101 + 408) / 5 = 101.8, equal sizes: plain average
+        assert_close(book.weighted_mid(2).unwrap().to_f64(), 100.7);
+        assert!(book.weighted_mid(0).is_none());
+
+        let depth = |side: BookSide, price: f64| {
+            book.cumulative_depth(side, &Price::from_f64(price).unwrap())
+                .map(|size| size.to_f64())
+        };
+        assert_eq!(depth(BookSide::Bid, 100.0), Some(3.0));
+        assert_eq!(depth(BookSide::Bid, 98.5), Some(5.0));
+        assert_eq!(depth(BookSide::Bid, 98.0), Some(10.0));
+        assert_eq!(depth(BookSide::Bid, 100.5), None);
+        assert_eq!(depth(BookSide::Ask, 102.0), Some(5.0));
+        assert_eq!(depth(BookSide::Ask, 1000.0), Some(7.0));
+        assert_eq!(depth(BookSide::Ask, 100.0), None);
+    }
+
+    #[test]
+    fn analytics_are_undefined_on_empty_sides() {
+        let one_sided = book(&[(100.0, 3.0)], &[]);
+        assert!(one_sided.book_imbalance(5).is_none());
+        assert!(one_sided.weighted_mid(5).is_none());
+        assert!(
+            one_sided
+                .cumulative_depth(BookSide::Ask, &Price::from_f64(1000.0).unwrap())
+                .is_none()
+        );
+        assert_eq!(
+            one_sided
+                .cumulative_depth(BookSide::Bid, &Price::from_f64(0.0).unwrap())
+                .map(|size| size.to_f64()),
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn strict_policy_rejects_out_of_order_levels() {
+        for side in [0, 1] {
+            let err = parse_with(swapped_book(side), LevelOrderPolicy::Strict).unwrap_err();
+            assert!(err.to_string().contains("not sorted best first"), "{}", err);
+        }
+        assert!(parse_with(raw_book(50, true), LevelOrderPolicy::Strict).is_err());
+
+        // Books Hyperliquid sends in order pass untouched
+        let book = parse_with(raw_book(3, false), LevelOrderPolicy::Strict).unwrap();
+        assert!(book.is_well_formed());
+        assert_eq!(book.bid_levels[0].0.to_f64(), 99.5);
+    }
+
+    #[test]
+    fn auto_sort_policy_restores_best_first_order() {
+        for side in [0, 1] {
+            let book = parse_with(swapped_book(side), LevelOrderPolicy::AutoSort).unwrap();
+            assert!(book.is_well_formed());
+            let expected = parse(raw_book(3, false));
+            assert_eq!(book.bid_levels, expected.bid_levels);
+            assert_eq!(book.ask_levels, expected.ask_levels);
+        }
+        let book = parse_with(raw_book(50, true), LevelOrderPolicy::AutoSort).unwrap();
+        assert!(book.is_well_formed());
+        let bids: Vec<f64> = book
+            .bid_levels
+            .iter()
+            .take(3)
+            .map(|l| l.0.to_f64())
+            .collect();
+        assert_eq!(bids, vec![99.5, 99.0, 98.5]);
+        // Size and order count move with their price
+        assert_eq!(
+            (book.ask_levels[0].1.to_f64(), book.ask_levels[0].2),
+            (1.0, 1)
+        );
+
+        // Sorting cannot fix a price listed twice
+        let mut data = raw_book(3, false);
+        data["levels"][1][1]["px"] = json!("100.5");
+        for policy in [LevelOrderPolicy::Strict, LevelOrderPolicy::AutoSort] {
+            assert!(parse_with(data.clone(), policy).is_err());
+        }
+    }
+
+    #[test]
+    fn well_formed_requires_strict_best_first_order() {
+        assert!(book(&[(100.0, 1.0), (99.0, 1.0)], &[(101.0, 1.0), (102.0, 1.0)]).is_well_formed());
+        assert!(book(&[], &[]).is_well_formed());
+        assert!(!book(&[(99.0, 1.0), (100.0, 1.0)], &[(101.0, 1.0)]).is_well_formed());
+        assert!(!book(&[(100.0, 1.0)], &[(102.0, 1.0), (101.0, 1.0)]).is_well_formed());
+        assert!(!book(&[(100.0, 1.0), (100.0, 2.0)], &[]).is_well_formed());
+
+        let mut unsorted = book(&[(98.0, 1.0), (100.0, 1.0)], &[(103.0, 1.0), (101.0, 1.0)]);
+        unsorted.sort_levels();
+        assert!(unsorted.is_well_formed());
+    }
+}
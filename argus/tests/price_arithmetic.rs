@@ -0,0 +1,88 @@
+use argus::types::{Price, TradeSize};
+
+fn size(f: f64) -> TradeSize {
+    TradeSize::from_f64(f).unwrap()
+}
+
+fn price(f: f64) -> Price {
+    Price::from_f64(f).unwrap()
+}
+
+/// Cumulative sum of `sizes` in the given order
+fn total(sizes: &[f64]) -> TradeSize {
+    let mut sizes = sizes.iter().map(|f| size(*f));
+    let first = sizes.next().unwrap();
+    sizes.fold(first, |acc, s| (acc + s).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_are_exact_decimals() {
+        assert_ne!(0.1 + 0.2, 0.3);
+        assert_eq!((size(0.1) + size(0.2)).unwrap(), size(0.3));
+        assert_eq!((price(100000.1) + price(0.2)).unwrap(), price(100000.3));
+        assert_eq!((price(0.3) - price(0.1)).unwrap(), price(0.2));
+        assert_eq!(
+            (TradeSize::from_string("1.5e10".to_string()).unwrap() + size(2.5e-5)).unwrap(),
+            size(15000000000.000025)
+        );
+    }
+
+    #[test]
+    fn summation_is_order_independent() {
+        let sizes = [0.1, 0.2, 0.3, 0.7, 1.1, 2.01, 0.03, 5.55];
+        let reversed: Vec<f64> = sizes.iter().rev().copied().collect();
+        let mut interleaved: Vec<f64> = sizes.iter().step_by(2).copied().collect();
+        interleaved.extend(sizes.iter().skip(1).step_by(2));
+
+        let forward = total(&sizes);
+        assert_eq!(forward, size(9.99));
+        assert_eq!(total(&reversed), forward);
+        assert_eq!(total(&interleaved), forward);
+
+        // Plain f64 accumulation drifts with the order
+        let f64_total = |sizes: &[f64]| sizes.iter().fold(0.0, |acc, s| acc + s);
+        assert_ne!(f64_total(&sizes), f64_total(&reversed));
+    }
+
+    #[test]
+    fn invalid_results_are_errors() {
+        // Prices stay nonnegative, sizes positive
+        assert!((price(0.1) - price(0.2)).is_err());
+        assert_eq!((price(0.2) - price(0.2)).unwrap(), price(0.0));
+        assert!((size(0.2) - size(0.2)).is_err());
+        assert!((size(0.2) - size(0.3)).is_err());
+
+        // Overflow to infinity
+        let err = (price(f64::MAX) + price(f64::MAX)).unwrap_err();
+        assert!(err.to_string().contains("finite"), "{}", err);
+    }
+
+    #[test]
+    fn operands_far_apart_fall_back_to_f64() {
+        assert_eq!((size(1.0) + size(f64::MIN_POSITIVE)).unwrap(), size(1.0));
+        assert_eq!((price(1e300) + price(1.0)).unwrap().to_f64(), 1e300);
+    }
+
+    #[test]
+    fn from_f64_checked_rejects_non_finite() {
+        for f in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = Price::from_f64_checked(f).unwrap_err();
+            assert!(err.to_string().contains("finite"), "{}", err);
+            assert!(TradeSize::from_f64_checked(f).is_err());
+        }
+        assert!(Price::from_f64_checked(-1.0).is_err());
+        assert_eq!(Price::from_f64_checked(0.0).unwrap(), price(0.0));
+        assert!(TradeSize::from_f64_checked(0.0).is_err());
+    }
+
+    #[test]
+    fn comparisons_follow_values() {
+        assert!(price(99.5) < price(100.5));
+        assert!(size(2.0) > size(1.5));
+        assert!(price(1.0) <= price(1.0));
+    }
+}
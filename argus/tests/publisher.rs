@@ -0,0 +1,120 @@
+use agora::metaserver::AgoraClient;
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::{TradeUpdate, TypedRelaySet, VersionedWorkers};
+use argus::types::TradingSymbol;
+use futures::future;
+use std::cell::Cell;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+fn symbols(names: &[&str]) -> Vec<TradingSymbol> {
+    names
+        .iter()
+        .map(|name| TradingSymbol::from_str(name).unwrap())
+        .collect()
+}
+
+/// Stand-in for a webstream worker batch: runs until aborted
+fn idle_worker(_symbols: Vec<TradingSymbol>) -> JoinHandle<()> {
+    tokio::spawn(future::pending::<()>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn bump_keeps_workers_of_unchanged_symbols() {
+        let btc = TradingSymbol::from_str("BTC_PERP").unwrap();
+        let eth = TradingSymbol::from_str("ETH_PERP").unwrap();
+        let sol = TradingSymbol::from_str("SOL_PERP").unwrap();
+        let mut workers = VersionedWorkers::new();
+
+        let (added, removed) = workers.bump(&symbols(&["BTC_PERP", "ETH_PERP"]), 0, idle_worker);
+        assert_eq!(added.len(), 2);
+        assert!(removed.is_empty());
+
+        // Adding a symbol spawns only the new symbol; BTC and ETH stay on version 0
+        let spawned = Cell::new(Vec::new());
+        let (added, removed) = workers.bump(
+            &symbols(&["BTC_PERP", "ETH_PERP", "SOL_PERP"]),
+            1,
+            |new_symbols| {
+                spawned.set(new_symbols.clone());
+                idle_worker(new_symbols)
+            },
+        );
+        assert_eq!(added, vec![sol.clone()]);
+        assert!(removed.is_empty());
+        assert_eq!(spawned.take(), vec![sol.clone()]);
+        tokio::task::yield_now().await;
+        assert_eq!(workers.version_of(&btc), Some(0));
+        assert_eq!(workers.version_of(&eth), Some(0));
+        assert_eq!(workers.version_of(&sol), Some(1));
+        assert!(workers.is_running(&btc));
+        assert!(workers.is_running(&sol));
+        assert_eq!(workers.batch_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn bump_without_changes_spawns_nothing_and_retires_emptied_batches() {
+        let btc = TradingSymbol::from_str("BTC_PERP").unwrap();
+        let sol = TradingSymbol::from_str("SOL_PERP").unwrap();
+        let mut workers = VersionedWorkers::new();
+        workers.bump(&symbols(&["BTC_PERP", "ETH_PERP"]), 0, idle_worker);
+        workers.bump(&symbols(&["BTC_PERP", "ETH_PERP", "SOL_PERP"]), 1, idle_worker);
+
+        let (added, removed) = workers.bump(
+            &symbols(&["BTC_PERP", "ETH_PERP", "SOL_PERP"]),
+            2,
+            |_| panic!("no symbols were added"),
+        );
+        assert!(added.is_empty() && removed.is_empty());
+        assert_eq!(workers.batch_count(), 2);
+
+        // Removing every version-0 symbol retires that batch; SOL's batch survives
+        let (_, removed) = workers.bump(&symbols(&["SOL_PERP"]), 3, idle_worker);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(workers.batch_count(), 1);
+        assert_eq!(workers.version_of(&btc), None);
+        assert!(!workers.is_running(&btc));
+        assert_eq!(workers.version_of(&sol), Some(1));
+        assert!(workers.is_running(&sol));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn relay_set_keeps_going_past_failed_symbols() {
+        let cluster = TestCluster::start().await.unwrap();
+        let prefix = format!("tests/publisher_{}", std::process::id());
+        let path = |symbol: &str| format!("{}/perp/last_trade/{}", prefix, symbol);
+
+        // Stale registrations hold two paths mid-batch: ETH_PERP's briefly, SOL_PERP's for good
+        let metaclient = AgoraClient::new(cluster.metaserver()).await.unwrap();
+        for symbol in ["ETH_PERP", "SOL_PERP"] {
+            metaclient
+                .register_publisher("stale".to_string(), path(symbol), cluster.gateway_port())
+                .await
+                .unwrap();
+        }
+        let eth_path = path("ETH_PERP");
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            metaclient.remove_publisher(&eth_path).await.unwrap();
+        });
+
+        let (relays, failed) = TypedRelaySet::<TradeUpdate>::new(
+            "perp".to_string(),
+            &symbols(&["BTC_PERP", "ETH_PERP", "SOL_PERP", "AVAX_PERP"]),
+            prefix.clone(),
+            cluster.metaserver(),
+            cluster.gateway_port(),
+        )
+        .await;
+        // ETH_PERP's relay retried until its path was released; AVAX_PERP was still created after SOL_PERP failed
+        assert_eq!(failed, symbols(&["SOL_PERP"]));
+        assert_eq!(
+            relays.symbols(),
+            symbols(&["AVAX_PERP", "BTC_PERP", "ETH_PERP"])
+        );
+    }
+}
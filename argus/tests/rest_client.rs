@@ -0,0 +1,177 @@
+use argus::crypto::hyperliquid::{HyperliquidRestClient, HyperliquidRestError, RestRateLimit};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::time::Instant;
+
+/// `meta` response listing BTC and a delisted LUNA
+fn perp_meta() -> serde_json::Value {
+    let asset = |name: &str, delisted: bool| {
+        serde_json::json!({
+            "name": name, "szDecimals": 5, "maxLeverage": 40, "marginTableId": 0, "isDelisted": delisted
+        })
+    };
+    serde_json::json!({"universe": [asset("BTC", false), asset("LUNA", true)], "marginTables": []})
+}
+
+/// `metaAndAssetCtxs` response: `perp_meta()` and one context per asset
+fn meta_and_asset_ctxs() -> serde_json::Value {
+    let ctx = |volume: Option<&str>| {
+        serde_json::json!({
+            "funding": "0.0000125", "markPx": "100.5", "midPx": "100.45", "oraclePx": "100.4",
+            "openInterest": "12.5", "dayNtlVlm": volume, "premium": null, "prevDayPx": "99.0"
+        })
+    };
+    serde_json::json!([perp_meta(), [ctx(Some("123456.5")), ctx(None)]])
+}
+
+/// Minimal Hyperliquid info endpoint answering `meta` and `metaAndAssetCtxs`, recording when each request arrives.
+/// The first `rate_limited` requests get HTTP 429 with `Retry-After: 7` instead.
+async fn serve_info(arrivals: Arc<Mutex<Vec<Instant>>>, rate_limited: usize) -> SocketAddr {
+    let rate_limited = Arc::new(AtomicUsize::new(rate_limited));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let arrivals = arrivals.clone();
+            let rate_limited = rate_limited.clone();
+            tokio::spawn(async move {
+                // Read headers, then the body announced by Content-Length
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                let header_end = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                arrivals.lock().unwrap().push(Instant::now());
+                let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                let content_length: usize = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|value| value.trim().parse().unwrap())
+                    .unwrap_or(0);
+                while request.len() < header_end + content_length {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let limited = rate_limited
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                if limited {
+                    let _ = stream
+                        .write_all(
+                            b"HTTP/1.1 429 Too Many Requests\r\nRetry-After: 7\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        )
+                        .await;
+                    return;
+                }
+                let body = String::from_utf8_lossy(&request[header_end..]);
+                let response = if body.contains("metaAndAssetCtxs") {
+                    meta_and_asset_ctxs()
+                } else {
+                    perp_meta()
+                }
+                .to_string();
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                );
+                let _ = stream.write_all(reply.as_bytes()).await;
+            });
+        }
+    });
+    addr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn limiter_spaces_requests_after_burst() {
+        let arrivals = Arc::new(Mutex::new(Vec::new()));
+        let addr = serve_info(arrivals.clone(), 0).await;
+        let client = HyperliquidRestClient::new(
+            &format!("http://{}", addr),
+            Some(RestRateLimit {
+                burst: 2,
+                refill_interval: Duration::from_millis(100),
+            }),
+        );
+
+        let requests: Vec<_> = (0..5)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.meta().await })
+            })
+            .collect();
+        for request in requests {
+            request.await.unwrap().unwrap();
+        }
+
+        let mut arrivals = arrivals.lock().unwrap().clone();
+        arrivals.sort();
+        assert_eq!(arrivals.len(), 5);
+        let offsets: Vec<Duration> = arrivals.iter().map(|t| *t - arrivals[0]).collect();
+        // The burst goes out at once; clones share the limiter, so the rest follow one per interval
+        assert!(offsets[1] < Duration::from_millis(80), "{:?}", offsets);
+        for (i, offset) in offsets.iter().enumerate().skip(2) {
+            let earliest = Duration::from_millis(100 * (i as u64 - 1) - 20);
+            assert!(*offset >= earliest, "request {}: {:?}", i, offsets);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rate_limited_response_is_retryable() {
+        let arrivals = Arc::new(Mutex::new(Vec::new()));
+        let addr = serve_info(arrivals, 1).await;
+        let client = HyperliquidRestClient::new(&format!("http://{}", addr), None);
+
+        let error = client.meta().await.unwrap_err();
+        assert_eq!(
+            error,
+            HyperliquidRestError::RateLimited {
+                request: "meta",
+                retry_after: Some(Duration::from_secs(7)),
+            }
+        );
+        assert!(error.is_retryable());
+        assert!(client.meta().await.is_ok());
+
+        let parse = HyperliquidRestError::Parse {
+            request: "meta",
+            reason: "missing field".to_string(),
+        };
+        assert!(!parse.is_retryable());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn meta_and_asset_ctxs_pairs_assets_with_contexts() {
+        let arrivals = Arc::new(Mutex::new(Vec::new()));
+        let addr = serve_info(arrivals, 0).await;
+        let client = HyperliquidRestClient::shared(&format!("http://{}", addr));
+
+        let (meta, contexts) = client.meta_and_asset_ctxs().await.unwrap();
+        assert_eq!(meta.universe.len(), 2);
+        assert_eq!(contexts.len(), 2);
+        assert_eq!(meta.universe[0].name, "BTC");
+        assert_eq!(contexts[0].day_ntl_vlm.as_deref(), Some("123456.5"));
+        assert_eq!(contexts[0].mark_px, "100.5");
+        assert!(meta.universe[1].is_delisted);
+        assert_eq!(contexts[1].day_ntl_vlm, None);
+    }
+}
@@ -0,0 +1,187 @@
+use agora::Agorable;
+use agora::utils::OrError;
+use argus::crypto::hyperliquid::OrderbookSnapshot;
+use argus::recording::{
+    ArgusParquetable, ArgusReplayable, SCHEMA_VERSION_METADATA_KEY, parquet_schema_version,
+    read_replayable_parquet,
+};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use arrow::array::{Array, ArrayRef, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// `OrderbookSnapshot` after the documented migration: gains `sequence`, schema version 2.
+/// Version 1 archives (no `sequence` column) still read, with `sequence: None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SequencedBook {
+    book: OrderbookSnapshot,
+    sequence: Option<u64>,
+}
+
+impl fmt::Display for SequencedBook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (sequence {:?})", self.book, self.sequence)
+    }
+}
+
+impl Agorable for SequencedBook {
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+impl ArgusParquetable for SequencedBook {
+    fn arrow_schema() -> Arc<Schema> {
+        let mut fields: Vec<Field> = OrderbookSnapshot::arrow_schema()
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone())
+            .collect();
+        fields.push(Field::new("sequence", DataType::UInt64, true));
+        Arc::new(Schema::new(fields))
+    }
+
+    fn to_record_batch(data: Vec<Self>) -> OrError<RecordBatch> {
+        let sequences: ArrayRef = Arc::new(UInt64Array::from(
+            data.iter().map(|value| value.sequence).collect::<Vec<_>>(),
+        ));
+        let books = OrderbookSnapshot::to_record_batch(data.into_iter().map(|v| v.book).collect())?;
+        let mut columns = books.columns().to_vec();
+        columns.push(sequences);
+        Ok(RecordBatch::try_new(Self::arrow_schema(), columns)?)
+    }
+}
+
+impl ArgusReplayable for SequencedBook {
+    fn from_record_batch(batch: &RecordBatch) -> OrError<Vec<Self>> {
+        let sequences = batch
+            .column_by_name("sequence")
+            .and_then(|column| column.as_any().downcast_ref::<UInt64Array>())
+            .ok_or_else(|| anyhow::anyhow!("Missing column sequence"))?;
+        Ok(OrderbookSnapshot::from_record_batch(batch)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, book)| SequencedBook {
+                book,
+                sequence: (!sequences.is_null(i)).then(|| sequences.value(i)),
+            })
+            .collect())
+    }
+
+    fn from_versioned_record_batch(batch: &RecordBatch, schema_version: u32) -> OrError<Vec<Self>> {
+        match schema_version {
+            1 => Ok(OrderbookSnapshot::from_record_batch(batch)?
+                .into_iter()
+                .map(|book| SequencedBook {
+                    book,
+                    sequence: None,
+                })
+                .collect()),
+            2 => Self::from_record_batch(batch),
+            v => Err(anyhow::anyhow!("Unsupported SequencedBook schema version {}", v)),
+        }
+    }
+
+    fn replay_time(&self) -> DateTime<Utc> {
+        self.book.received_time
+    }
+}
+
+fn books() -> Vec<OrderbookSnapshot> {
+    let start = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    (0..3)
+        .map(|i| OrderbookSnapshot {
+            symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+            received_time: start + Duration::seconds(i),
+            time: start + Duration::seconds(i),
+            bid_levels: vec![(
+                Price::from_f64(99.5).unwrap(),
+                TradeSize::from_f64(1.0 + i as f64).unwrap(),
+                2,
+            )],
+            ask_levels: vec![(
+                Price::from_f64(100.5).unwrap(),
+                TradeSize::from_f64(2.0).unwrap(),
+                1,
+            )],
+        })
+        .collect()
+}
+
+fn temp_parquet(name: &str) -> String {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("argus_schema_version_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name).to_string_lossy().to_string()
+}
+
+fn file_schema_version(path: &str) -> u32 {
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(path).unwrap()).unwrap();
+    parquet_schema_version(builder.schema()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parquet_files_record_schema_version() {
+        let path = temp_parquet("v1.parquet");
+        OrderbookSnapshot::write_to_parquet(books(), path.clone()).unwrap();
+        assert_eq!(file_schema_version(&path), 1);
+
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(
+            builder.schema().metadata().get(SCHEMA_VERSION_METADATA_KEY),
+            Some(&"1".to_string())
+        );
+
+        // Files written before versioning carry no key and count as version 1
+        assert_eq!(parquet_schema_version(&Schema::empty()).unwrap(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn v1_archive_reads_after_migration() {
+        let path = temp_parquet("migrate_v1.parquet");
+        OrderbookSnapshot::write_to_parquet(books(), path.clone()).unwrap();
+
+        let migrated = read_replayable_parquet::<SequencedBook>(&path).unwrap();
+        let expected: Vec<SequencedBook> = books()
+            .into_iter()
+            .map(|book| SequencedBook {
+                book,
+                sequence: None,
+            })
+            .collect();
+        assert_eq!(migrated, expected);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn v2_archive_round_trips_and_is_refused_by_v1_reader() {
+        let path = temp_parquet("v2.parquet");
+        let sequenced: Vec<SequencedBook> = books()
+            .into_iter()
+            .enumerate()
+            .map(|(i, book)| SequencedBook {
+                book,
+                sequence: (i != 1).then_some(i as u64 * 10),
+            })
+            .collect();
+        SequencedBook::write_to_parquet(sequenced.clone(), path.clone()).unwrap();
+        assert_eq!(file_schema_version(&path), 2);
+        assert_eq!(read_replayable_parquet::<SequencedBook>(&path).unwrap(), sequenced);
+
+        // The v1 type cannot know what a v2 layout means
+        let err = read_replayable_parquet::<OrderbookSnapshot>(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("newer"), "{:#}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,110 @@
+use agora::Publisher;
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::TradeUpdate;
+use argus::recording::{AgoraDirScribe, FlushSchedule};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "argus_scribe_flush_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn trade(symbol: &str, trade_id: u64) -> TradeUpdate {
+    let time = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap();
+    TradeUpdate {
+        symbol: TradingSymbol::from_str(symbol).unwrap(),
+        received_time: time,
+        trade_id,
+        price: Price::from_f64(100.0).unwrap(),
+        size: TradeSize::from_f64(1.0).unwrap(),
+        trade_time: time,
+        is_buy: true,
+    }
+}
+
+/// Symbols (filename stems before `_{time}.pq`) with at least one flushed file in `dir`
+fn flushed_symbols(dir: &Path) -> Vec<String> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("pq"))
+        .filter_map(|p| {
+            let name = p.file_stem()?.to_str()?.to_string();
+            name.split_once('_').map(|(symbol, _)| symbol.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn staggered_symbols_flush_at_offset_times() {
+        let cluster = TestCluster::start().await.unwrap();
+        let dir = scratch_dir("staggered");
+        let prefix = format!("tests/scribe_flush_{}/last_trade", std::process::id());
+
+        let mut publishers = Vec::new();
+        for symbol in ["BTC", "ETH"] {
+            publishers.push(
+                Publisher::<TradeUpdate>::new(
+                    symbol.to_string(),
+                    format!("{}/{}", prefix, symbol),
+                    trade(symbol, 0),
+                    cluster.metaserver(),
+                    cluster.gateway_port(),
+                )
+                .await
+                .unwrap(),
+            );
+        }
+
+        // Two symbols over a 2s interval: first flushes 1s and 2s after start
+        let start = Instant::now();
+        let scribe = AgoraDirScribe::<TradeUpdate>::with_market(
+            &prefix,
+            cluster.metaserver(),
+            Duration::from_secs(2),
+            dir.to_str().unwrap(),
+            None,
+            Some(FlushSchedule::Staggered),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(scribe.count(), 2);
+
+        let mut first_flush: HashMap<String, Duration> = HashMap::new();
+        while first_flush.len() < 2 && start.elapsed() < Duration::from_secs(4) {
+            for symbol in flushed_symbols(&dir) {
+                first_flush.entry(symbol).or_insert_with(|| start.elapsed());
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(first_flush.len(), 2, "{:?}", first_flush);
+
+        let mut times: Vec<Duration> = first_flush.values().copied().collect();
+        times.sort();
+        assert!(
+            times[1] - times[0] >= Duration::from_millis(600),
+            "symbols flushed together: {:?}",
+            first_flush
+        );
+
+        scribe.shutdown().await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
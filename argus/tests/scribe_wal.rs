@@ -0,0 +1,183 @@
+use agora::gateway::Gateway;
+use agora::metaserver::AgoraMetaServer;
+use agora::{ConnectionHandle, Publisher};
+use argus::crypto::hyperliquid::TradeUpdate;
+use argus::recording::{AgoraDirScribe, WriteAheadLog, read_replayable_parquet};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::{TimeZone, Utc};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("argus_scribe_wal_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn trade(trade_id: u64) -> TradeUpdate {
+    let time = Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap()
+        + chrono::Duration::seconds(trade_id as i64);
+    TradeUpdate {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: time,
+        trade_id,
+        price: Price::from_f64(100.0 + trade_id as f64).unwrap(),
+        size: TradeSize::from_f64(1.0).unwrap(),
+        trade_time: time,
+        is_buy: trade_id.is_multiple_of(2),
+    }
+}
+
+fn trade_ids(trades: &[TradeUpdate]) -> Vec<u64> {
+    trades.iter().map(|t| t.trade_id).collect()
+}
+
+/// Starts a metaserver and gateway on ports unique to this test process.
+async fn start_agora() -> (ConnectionHandle, u16, Gateway) {
+    let metaserver_port = 30000 + (std::process::id() % 20000) as u16;
+    let gateway_port = metaserver_port + 1;
+    let metaserver = ConnectionHandle::new_local(metaserver_port).unwrap();
+    tokio::spawn(AgoraMetaServer::run_server(
+        metaserver.addr(),
+        metaserver_port,
+        None,
+        None,
+        None,
+        None,
+    ));
+    let gateway = Gateway::new(gateway_port).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    (metaserver, gateway_port, gateway)
+}
+
+/// Trade ids in every `.pq` file the scribes flushed into `dir`
+fn flushed_trade_ids(dir: &PathBuf) -> Vec<u64> {
+    let mut ids = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|s| s.to_str()) == Some("pq") {
+            let trades = read_replayable_parquet::<TradeUpdate>(path.to_str().unwrap()).unwrap();
+            ids.extend(trade_ids(&trades));
+        }
+    }
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wal_replays_unflushed_records_and_drops_torn_tail() {
+        let dir = scratch_dir("replay");
+        let flush_path = dir.join("BTC_PERP").to_string_lossy().to_string();
+
+        let (mut wal, recovered) = WriteAheadLog::<TradeUpdate>::open(&flush_path).unwrap();
+        assert!(recovered.is_empty());
+        for id in 1..=3 {
+            wal.append(&trade(id)).unwrap();
+        }
+        drop(wal);
+
+        // Crash mid-write: half a length prefix at the end of the log
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(format!("{}.wal", flush_path))
+            .unwrap()
+            .write_all(&[7, 0])
+            .unwrap();
+        let (mut wal, recovered) = WriteAheadLog::<TradeUpdate>::open(&flush_path).unwrap();
+        assert_eq!(trade_ids(&recovered), vec![1, 2, 3]);
+
+        // Crash during a flush: the flushing segment replays ahead of newer records
+        wal.begin_flush().unwrap();
+        wal.append(&trade(4)).unwrap();
+        drop(wal);
+        let (mut wal, recovered) = WriteAheadLog::<TradeUpdate>::open(&flush_path).unwrap();
+        assert_eq!(trade_ids(&recovered), vec![1, 2, 3, 4]);
+
+        // A committed flush leaves nothing to replay
+        wal.begin_flush().unwrap();
+        wal.commit_flush().unwrap();
+        drop(wal);
+        let (_, recovered) = WriteAheadLog::<TradeUpdate>::open(&flush_path).unwrap();
+        assert!(recovered.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scribe_recovers_from_wal_after_crash() {
+        let (metaserver, gateway_port, _gateway) = start_agora().await;
+        let dir = scratch_dir("crash");
+        let output_dir = dir.to_string_lossy().to_string();
+        let prefix = format!("tests/scribe_wal_{}/trades", std::process::id());
+
+        let mut publisher = Publisher::<TradeUpdate>::new(
+            "BTC_PERP".to_string(),
+            format!("{}/BTC_PERP", prefix),
+            trade(0),
+            metaserver,
+            gateway_port,
+        )
+        .await
+        .unwrap();
+
+        // Flush interval far beyond the test: nothing reaches parquet before the crash
+        let scribe = AgoraDirScribe::<TradeUpdate>::new_with_wal(
+            &prefix,
+            metaserver,
+            Duration::from_secs(3600),
+            &output_dir,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        for id in 1..=3 {
+            publisher.publish(trade(id)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // Crash: dropped without shutdown()
+        drop(scribe);
+        assert!(flushed_trade_ids(&dir).is_empty());
+        assert!(dir.join("BTC_PERP.wal").is_file());
+
+        // Restarted scribe replays the log into its first flush
+        let scribe = AgoraDirScribe::<TradeUpdate>::new_with_wal(
+            &prefix,
+            metaserver,
+            Duration::from_secs(1),
+            &output_dir,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let mut flushed = flushed_trade_ids(&dir);
+        flushed.dedup();
+        assert_eq!(flushed, vec![0, 1, 2, 3]);
+        // Flushed records left the log
+        assert!(!dir.join("BTC_PERP.wal.flushing").exists());
+        let (_, pending) =
+            WriteAheadLog::<TradeUpdate>::open(&dir.join("BTC_PERP").to_string_lossy()).unwrap();
+        assert!(
+            trade_ids(&pending).iter().all(|id| *id == 3),
+            "{:?}",
+            trade_ids(&pending)
+        );
+
+        scribe.shutdown().await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,98 @@
+use argus::crypto::{GapEvent, SequenceMode, SequenceTracker};
+use argus::types::TradingSymbol;
+
+fn symbol(name: &str) -> TradingSymbol {
+    TradingSymbol::from_str(name).unwrap()
+}
+
+/// Feeds `ids` for one symbol and collects the gaps reported
+fn gaps(tracker: &mut SequenceTracker, name: &str, ids: &[u64]) -> Vec<GapEvent> {
+    ids.iter()
+        .filter_map(|&id| tracker.observe(&symbol(name), id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_order_sequences_report_nothing() {
+        let mut contiguous = SequenceTracker::new(SequenceMode::Contiguous);
+        assert!(gaps(&mut contiguous, "BTC_PERP", &[10, 11, 12, 13]).is_empty());
+        assert_eq!(contiguous.last(&symbol("BTC_PERP")), Some(13));
+
+        // Monotonic ids may jump freely
+        let mut monotonic = SequenceTracker::new(SequenceMode::Monotonic);
+        assert!(gaps(&mut monotonic, "BTC_PERP", &[5, 90, 91, 4000]).is_empty());
+    }
+
+    #[test]
+    fn skipped_ids_are_gaps_only_when_contiguous() {
+        let mut tracker = SequenceTracker::new(SequenceMode::Contiguous);
+        let reported = gaps(&mut tracker, "ETH_PERP", &[1, 2, 5, 6, 8]);
+        assert_eq!(
+            reported,
+            vec![
+                GapEvent::Skipped {
+                    symbol: symbol("ETH_PERP"),
+                    last: 2,
+                    received: 5
+                },
+                GapEvent::Skipped {
+                    symbol: symbol("ETH_PERP"),
+                    last: 6,
+                    received: 8
+                },
+            ]
+        );
+        assert_eq!(reported.iter().map(GapEvent::missing).sum::<u64>(), 3);
+        assert_eq!(reported[0].to_string(), "ETH_PERP: skipped 2 ids between 2 and 5");
+
+        let mut monotonic = SequenceTracker::new(SequenceMode::Monotonic);
+        assert!(gaps(&mut monotonic, "ETH_PERP", &[1, 2, 5, 6, 8]).is_empty());
+    }
+
+    #[test]
+    fn out_of_order_and_duplicate_ids_keep_high_water_mark() {
+        for mode in [SequenceMode::Contiguous, SequenceMode::Monotonic] {
+            let mut tracker = SequenceTracker::new(mode);
+            let reported = gaps(&mut tracker, "SOL_PERP", &[7, 8, 6, 8, 9]);
+            assert_eq!(
+                reported,
+                vec![
+                    GapEvent::OutOfOrder {
+                        symbol: symbol("SOL_PERP"),
+                        last: 8,
+                        received: 6
+                    },
+                    GapEvent::OutOfOrder {
+                        symbol: symbol("SOL_PERP"),
+                        last: 8,
+                        received: 8
+                    },
+                ]
+            );
+            assert_eq!(reported[0].missing(), 0);
+            // 9 follows 8, the high-water mark, so it is in order
+            assert_eq!(tracker.last(&symbol("SOL_PERP")), Some(9));
+        }
+    }
+
+    #[test]
+    fn symbols_are_tracked_independently_and_reset() {
+        let mut tracker = SequenceTracker::new(SequenceMode::Contiguous);
+        assert!(tracker.observe(&symbol("BTC_PERP"), 100).is_none());
+        // First id of another symbol is never a gap
+        assert!(tracker.observe(&symbol("ETH_PERP"), 7).is_none());
+        assert!(tracker.observe(&symbol("BTC_PERP"), 101).is_none());
+        assert_eq!(
+            tracker.observe(&symbol("ETH_PERP"), 3).map(|gap| gap.symbol().clone()),
+            Some(symbol("ETH_PERP"))
+        );
+
+        tracker.reset();
+        assert_eq!(tracker.last(&symbol("BTC_PERP")), None);
+        assert!(tracker.observe(&symbol("BTC_PERP"), 5).is_none());
+    }
+}
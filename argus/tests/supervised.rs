@@ -0,0 +1,161 @@
+use argus::metrics;
+use argus::supervised::{RestartPolicy, TaskExit, spawn_supervised, spawn_supervised_restarting};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Restarts immediately, at most twice
+fn quick_restarts() -> RestartPolicy {
+    RestartPolicy {
+        max_restarts: 2,
+        backoff: Duration::from_millis(10),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn on_exit_receives_panic_message() {
+        let (exit_tx, exit_rx) = oneshot::channel();
+        let handle = spawn_supervised(
+            "test_panicking_worker",
+            async {
+                panic!("worker lost its book");
+            },
+            move |exit| {
+                let _ = exit_tx.send(exit.clone());
+            },
+        );
+
+        let exit = tokio::time::timeout(Duration::from_secs(5), exit_rx)
+            .await
+            .expect("on_exit should be called")
+            .unwrap();
+        assert_eq!(exit, TaskExit::Panicked("worker lost its book".to_string()));
+        // The panic is caught: the supervising task itself finishes cleanly
+        assert!(handle.await.is_ok());
+        assert_eq!(
+            metrics::task_exits("test_panicking_worker", "panicked").get(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn on_exit_reports_completion_and_formatted_panics() {
+        let (exit_tx, exit_rx) = oneshot::channel();
+        spawn_supervised("test_completing_worker", async {}, move |exit| {
+            let _ = exit_tx.send(exit.clone());
+        });
+        assert_eq!(exit_rx.await.unwrap(), TaskExit::Completed);
+        assert_eq!(
+            metrics::task_exits("test_completing_worker", "completed").get(),
+            1
+        );
+
+        // `panic!` with arguments carries a `String` payload rather than `&str`
+        let (exit_tx, exit_rx) = oneshot::channel();
+        let symbol = "BTC";
+        spawn_supervised(
+            "test_formatted_panic_worker",
+            async move {
+                panic!("no book for {}", symbol);
+            },
+            move |exit| {
+                let _ = exit_tx.send(exit.clone());
+            },
+        );
+        assert_eq!(
+            exit_rx.await.unwrap(),
+            TaskExit::Panicked("no book for BTC".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn abort_skips_on_exit() {
+        let called = Arc::new(Mutex::new(false));
+        let called_clone = Arc::clone(&called);
+        let handle = spawn_supervised(
+            "test_aborted_worker",
+            std::future::pending::<()>(),
+            move |_| *called_clone.lock().unwrap() = true,
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert!(!*called.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn restarting_reruns_panicked_task_up_to_limit() {
+        let runs = Arc::new(AtomicU32::new(0));
+        let runs_clone = Arc::clone(&runs);
+        let exits = Arc::new(Mutex::new(Vec::new()));
+        let exits_clone = Arc::clone(&exits);
+        let handle = spawn_supervised_restarting(
+            "test_always_panicking_worker",
+            move || {
+                let run = runs_clone.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    panic!("run {}", run);
+                }
+            },
+            Some(quick_restarts()),
+            move |exit| exits_clone.lock().unwrap().push(exit.clone()),
+        );
+
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("supervisor should give up after max_restarts")
+            .unwrap();
+        // One initial run plus two restarts, each exit reported
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            *exits.lock().unwrap(),
+            (0..3)
+                .map(|run| TaskExit::Panicked(format!("run {}", run)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            metrics::task_restarts("test_always_panicking_worker").get(),
+            2
+        );
+        assert_eq!(
+            metrics::task_exits("test_always_panicking_worker", "panicked").get(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn restarting_recovers_and_does_not_restart_completed_task() {
+        let runs = Arc::new(AtomicU32::new(0));
+        let runs_clone = Arc::clone(&runs);
+        let (exit_tx, mut exit_rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = spawn_supervised_restarting(
+            "test_flaky_worker",
+            move || {
+                let run = runs_clone.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if run == 0 {
+                        panic!("first run fails");
+                    }
+                }
+            },
+            Some(quick_restarts()),
+            move |exit| {
+                let _ = exit_tx.send(exit.clone());
+            },
+        );
+
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert!(exit_rx.recv().await.unwrap().is_panic());
+        assert_eq!(exit_rx.recv().await.unwrap(), TaskExit::Completed);
+        assert!(exit_rx.recv().await.is_none());
+    }
+}
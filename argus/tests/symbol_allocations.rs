@@ -0,0 +1,136 @@
+use argus::crypto::hyperliquid::normalize_coin;
+use argus::types::TradingSymbol;
+use bimap::BiMap;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::Instant;
+
+/// System allocator that counts allocations made by the current thread, so parallel tests do not interfere
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Allocations made by `f` on this thread
+fn allocations_in(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+const MESSAGES: usize = 10_000;
+
+fn universe() -> Vec<TradingSymbol> {
+    ["BTC", "ETH", "SOL", "HYPE", "kPEPE"]
+        .iter()
+        .map(|s| TradingSymbol::from_str(s).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The webstream dispatch step: find the publisher of each parsed item's symbol.
+    /// Keyed by `String` (the old layout) every lookup allocates; keyed by `TradingSymbol` none do.
+    #[test]
+    fn dispatch_lookup_does_not_allocate() {
+        let symbols = universe();
+        let by_string: HashMap<String, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(idx, symbol)| (symbol.to_string(), idx))
+            .collect();
+        let by_symbol: HashMap<TradingSymbol, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(idx, symbol)| (symbol.clone(), idx))
+            .collect();
+
+        let start = Instant::now();
+        let string_keyed = allocations_in(|| {
+            for i in 0..MESSAGES {
+                let item_symbol = &symbols[i % symbols.len()];
+                black_box(by_string.get(&item_symbol.to_string()));
+            }
+        });
+        let string_keyed_time = start.elapsed();
+
+        let start = Instant::now();
+        let symbol_keyed = allocations_in(|| {
+            for i in 0..MESSAGES {
+                // `HyperliquidStreamable::symbol` hands out a clone, as the dispatch loop sees it
+                let item_symbol = symbols[i % symbols.len()].clone();
+                black_box(by_symbol.get(&item_symbol));
+                black_box(by_symbol.get(item_symbol.as_str()));
+            }
+        });
+        let symbol_keyed_time = start.elapsed();
+
+        println!(
+            "{} dispatches: String keys {} allocations in {:?}, TradingSymbol keys {} allocations in {:?}",
+            MESSAGES, string_keyed, string_keyed_time, symbol_keyed, symbol_keyed_time
+        );
+        assert!(string_keyed >= MESSAGES);
+        assert_eq!(symbol_keyed, 0);
+    }
+
+    #[test]
+    fn mapped_coins_normalize_without_allocating() {
+        let mut symbol_map: BiMap<TradingSymbol, TradingSymbol> = BiMap::new();
+        for (normalized, coin) in [("BTC_PERP", "BTC"), ("PURR-USDC", "@1")] {
+            symbol_map.insert(
+                TradingSymbol::from_str(normalized).unwrap(),
+                TradingSymbol::from_str(coin).unwrap(),
+            );
+        }
+
+        let mut normalized = Vec::with_capacity(MESSAGES);
+        let mapped = allocations_in(|| {
+            for i in 0..MESSAGES {
+                let coin = if i % 2 == 0 { "BTC" } else { "@1" };
+                normalized.push(normalize_coin(coin, &symbol_map).unwrap());
+            }
+        });
+        assert_eq!(mapped, 0);
+        // Every message shares the map's symbol rather than a copy of it
+        assert_eq!(normalized[0].as_str(), "BTC_PERP");
+        assert_eq!(normalized[1].as_str(), "PURR-USDC");
+
+        // Unmapped coins are passed through under their raw name, which does allocate
+        let unmapped = allocations_in(|| {
+            assert_eq!(
+                normalize_coin("NEWCOIN", &symbol_map).unwrap().as_str(),
+                "NEWCOIN"
+            );
+        });
+        assert!(unmapped > 0);
+        assert!(normalize_coin("", &symbol_map).is_err());
+    }
+
+    #[test]
+    fn serialized_as_plain_string() {
+        let symbol = TradingSymbol::from_str("BTC_PERP").unwrap();
+        assert_eq!(serde_json::to_string(&symbol).unwrap(), "\"BTC_PERP\"");
+        let decoded: TradingSymbol = serde_json::from_str("\"BTC_PERP\"").unwrap();
+        assert_eq!(decoded, symbol);
+        assert_eq!(decoded.to_string(), "BTC_PERP");
+    }
+}
@@ -0,0 +1,120 @@
+use argus::crypto::hyperliquid::{TradeUpdate, TradeVolumeProfile};
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+fn at(secs: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 10, 5, 0, 0, 0).unwrap() + Duration::seconds(secs)
+}
+
+/// BTC_PERP trade at 2025-10-05 00:00:{secs}
+fn trade(secs: i64, price: f64, size: f64, is_buy: bool) -> TradeUpdate {
+    TradeUpdate {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: at(secs),
+        trade_id: secs as u64,
+        price: Price::from_f64(price).unwrap(),
+        size: TradeSize::from_f64(size).unwrap(),
+        trade_time: at(secs),
+        is_buy,
+    }
+}
+
+fn profile(window_secs: i64) -> TradeVolumeProfile {
+    TradeVolumeProfile::new(
+        TradingSymbol::from_str("BTC_PERP").unwrap(),
+        Duration::seconds(window_secs),
+    )
+    .unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vwap_and_side_split_match_hand_computed_values() {
+        let mut profile = profile(60);
+        assert!(profile.snapshot().is_none());
+        assert!(profile.vwap().is_none());
+
+        assert!(profile.push_trade(&trade(0, 100.0, 1.0, true)));
+        assert!(profile.push_trade(&trade(1, 102.0, 3.0, false)));
+        assert!(profile.push_trade(&trade(2, 101.0, 2.0, true)));
+        // (100 * 1 + 102 * 3 + 101 * 2) / 6 = 608 / 6
+        assert_close(profile.vwap().unwrap().to_f64(), 608.0 / 6.0);
+        assert_close(profile.buy_volume(), 3.0);
+        assert_close(profile.sell_volume(), 3.0);
+        assert_eq!(profile.trade_count(), 3);
+
+        let snapshot = profile.snapshot().unwrap();
+        assert_eq!(snapshot.time, at(2));
+        assert_eq!(snapshot.trade_count, 3);
+        assert_close(snapshot.vwap.unwrap().to_f64(), 608.0 / 6.0);
+    }
+
+    #[test]
+    fn trades_older_than_the_window_are_evicted() {
+        let mut profile = profile(10);
+        profile.push_trade(&trade(0, 100.0, 1.0, true));
+        profile.push_trade(&trade(5, 110.0, 1.0, false));
+
+        // At 10s the window is (0s, 10s]: the first trade just left
+        assert!(profile.push_trade(&trade(10, 120.0, 2.0, true)));
+        assert_eq!(profile.trade_count(), 2);
+        assert_close(profile.buy_volume(), 2.0);
+        assert_close(profile.sell_volume(), 1.0);
+        // (110 * 1 + 120 * 2) / 3
+        assert_close(profile.vwap().unwrap().to_f64(), 350.0 / 3.0);
+
+        // A late trade still inside the window counts; one already outside it is dropped
+        assert!(profile.push_trade(&trade(3, 90.0, 1.0, false)));
+        assert!(!profile.push_trade(&trade(-1, 80.0, 5.0, true)));
+        assert_eq!(profile.trade_count(), 3);
+        assert_close(profile.sell_volume(), 2.0);
+        assert_close(profile.vwap().unwrap().to_f64(), 440.0 / 4.0);
+
+        // The late trade leaves in trade-time order, before the one at 5s
+        assert_eq!(profile.advance_to(at(14)), 1);
+        assert_close(profile.vwap().unwrap().to_f64(), 350.0 / 3.0);
+        // Time never moves backwards
+        assert_eq!(profile.advance_to(at(12)), 0);
+        assert_eq!(profile.snapshot().unwrap().time, at(14));
+    }
+
+    #[test]
+    fn empty_window_has_no_vwap() {
+        let mut profile = profile(10);
+        profile.push_trade(&trade(0, 100.1, 0.3, true));
+        profile.push_trade(&trade(1, 100.7, 0.1, false));
+        assert_eq!(profile.advance_to(at(30)), 2);
+
+        assert_eq!(profile.trade_count(), 0);
+        assert!(profile.vwap().is_none());
+        assert_eq!(profile.buy_volume(), 0.0);
+        assert_eq!(profile.sell_volume(), 0.0);
+        let snapshot = profile.snapshot().unwrap();
+        assert_eq!((snapshot.time, snapshot.trade_count), (at(30), 0));
+        assert!(snapshot.vwap.is_none());
+
+        // The window refills from scratch
+        profile.push_trade(&trade(35, 99.0, 2.0, false));
+        assert_close(profile.vwap().unwrap().to_f64(), 99.0);
+        assert_close(profile.sell_volume(), 2.0);
+    }
+
+    #[test]
+    fn window_must_be_positive() {
+        let symbol = TradingSymbol::from_str("BTC_PERP").unwrap();
+        assert!(TradeVolumeProfile::new(symbol.clone(), Duration::zero()).is_err());
+        assert!(TradeVolumeProfile::new(symbol, Duration::seconds(-1)).is_err());
+    }
+}
@@ -21,7 +21,7 @@ mod trading_symbol_tests {
         let result = TradingSymbol::from_str("");
         assert!(result.is_err(), "Empty string should fail");
         assert!(
-            result.unwrap_err().contains("cannot be empty"),
+            result.unwrap_err().to_string().contains("cannot be empty"),
             "Error message should indicate symbol cannot be empty"
         );
     }
@@ -61,7 +61,7 @@ mod price_tests {
         let result = Price::from_f64(-1.0);
         assert!(result.is_err(), "Negative price should fail");
         assert!(
-            result.unwrap_err().contains("nonnegative"),
+            result.unwrap_err().to_string().contains("nonnegative"),
             "Error message should indicate price must be nonnegative"
         );
 
@@ -74,7 +74,7 @@ mod price_tests {
         let result = Price::from_f64(f64::INFINITY);
         assert!(result.is_err(), "Infinity should fail");
         assert!(
-            result.unwrap_err().contains("finite"),
+            result.unwrap_err().to_string().contains("finite"),
             "Error message should indicate price must be finite"
         );
 
@@ -87,7 +87,7 @@ mod price_tests {
         let result = Price::from_f64(f64::NAN);
         assert!(result.is_err(), "NaN should fail");
         assert!(
-            result.unwrap_err().contains("finite"),
+            result.unwrap_err().to_string().contains("finite"),
             "Error message should indicate price must be finite"
         );
     }
@@ -108,7 +108,7 @@ mod price_tests {
         let result = Price::from_string("not_a_number".to_string());
         assert!(result.is_err(), "Invalid string should fail");
         assert!(
-            result.unwrap_err().contains("cannot interpret string"),
+            result.unwrap_err().to_string().contains("cannot interpret string"),
             "Error message should indicate conversion failure"
         );
 
@@ -148,7 +148,7 @@ mod trade_size_tests {
         let result = TradeSize::from_f64(0.0);
         assert!(result.is_err(), "Zero trade size should fail");
         assert!(
-            result.unwrap_err().contains("positive"),
+            result.unwrap_err().to_string().contains("positive"),
             "Error message should indicate trade size must be positive"
         );
     }
@@ -158,7 +158,7 @@ mod trade_size_tests {
         let result = TradeSize::from_f64(-1.0);
         assert!(result.is_err(), "Negative trade size should fail");
         assert!(
-            result.unwrap_err().contains("positive"),
+            result.unwrap_err().to_string().contains("positive"),
             "Error message should indicate trade size must be positive"
         );
 
@@ -171,7 +171,7 @@ mod trade_size_tests {
         let result = TradeSize::from_f64(f64::INFINITY);
         assert!(result.is_err(), "Infinity should fail");
         assert!(
-            result.unwrap_err().contains("finite"),
+            result.unwrap_err().to_string().contains("finite"),
             "Error message should indicate trade size must be finite"
         );
 
@@ -184,7 +184,7 @@ mod trade_size_tests {
         let result = TradeSize::from_f64(f64::NAN);
         assert!(result.is_err(), "NaN should fail");
         assert!(
-            result.unwrap_err().contains("finite"),
+            result.unwrap_err().to_string().contains("finite"),
             "Error message should indicate trade size must be finite"
         );
     }
@@ -205,7 +205,7 @@ mod trade_size_tests {
         let result = TradeSize::from_string("not_a_number".to_string());
         assert!(result.is_err(), "Invalid string should fail");
         assert!(
-            result.unwrap_err().contains("cannot interpret string"),
+            result.unwrap_err().to_string().contains("cannot interpret string"),
             "Error message should indicate conversion failure"
         );
 
@@ -268,8 +268,8 @@ mod edge_case_tests {
         let result = Price::from_string("  123.45  ".to_string());
         // This might actually succeed depending on parse() behavior, but let's document it
         // The parse() method trims whitespace automatically in Rust
-        if result.is_ok() {
-            assert_eq!(result.unwrap().to_f64(), 123.45);
+        if let Ok(price) = result {
+            assert_eq!(price.to_f64(), 123.45);
         }
     }
 }
@@ -0,0 +1,488 @@
+use agora::metaserver::AgoraClient;
+use agora::testkit::TestCluster;
+use agora::utils::TreeTrait;
+use argus::crypto::hyperliquid::{
+    HyperliquidEndpoints, HyperliquidPublisher, UniverseFetchError, UniverseFilter,
+    UniverseManager, UniverseManagerConfig,
+};
+use argus::types::TradingSymbol;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn symbol(name: &str) -> TradingSymbol {
+    TradingSymbol::from_str(name).unwrap()
+}
+
+fn symbols(names: &[&str]) -> Vec<TradingSymbol> {
+    names.iter().map(|name| symbol(name)).collect()
+}
+
+/// `meta` response listing `perps` (Hyperliquid names, e.g. "BTC")
+fn perp_meta(perps: &[&str]) -> String {
+    let universe: Vec<_> = perps
+        .iter()
+        .map(|name| {
+            serde_json::json!({"name": name, "szDecimals": 5, "maxLeverage": 40, "marginTableId": 0})
+        })
+        .collect();
+    serde_json::json!({"universe": universe, "marginTables": []}).to_string()
+}
+
+/// `spotMeta` response listing PURR/USDC and @107 (HYPE/USDC)
+fn spot_meta() -> String {
+    let token = |name: &str, index: u32| {
+        serde_json::json!({
+            "name": name, "szDecimals": 2, "weiDecimals": 8, "index": index, "tokenId": "0x0",
+            "isCanonical": true, "evmContract": null, "fullName": null, "deployerTradingFeeShare": "0.0"
+        })
+    };
+    serde_json::json!({
+        "universe": [
+            {"name": "PURR/USDC", "tokens": [1, 0], "index": 0, "isCanonical": true},
+            {"name": "@107", "tokens": [2, 0], "index": 107, "isCanonical": false}
+        ],
+        "tokens": [token("USDC", 0), token("PURR", 1), token("HYPE", 2)]
+    })
+    .to_string()
+}
+
+/// Minimal Hyperliquid info endpoint answering `meta` with the current `perps` and `spotMeta` with `spot_meta()`.
+/// The first `failures` requests (of either kind) get HTTP 503 instead.
+async fn serve_info(perps: Arc<Mutex<Vec<&'static str>>>, failures: usize) -> SocketAddr {
+    let failures = Arc::new(AtomicUsize::new(failures));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let perps = perps.clone();
+            let failures = failures.clone();
+            tokio::spawn(async move {
+                // Read headers, then the body announced by Content-Length
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                let header_end = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                let content_length: usize = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|value| value.trim().parse().unwrap())
+                    .unwrap_or(0);
+                while request.len() < header_end + content_length {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let failing = failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                if failing {
+                    let _ = stream
+                        .write_all(
+                            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        )
+                        .await;
+                    return;
+                }
+                let body = String::from_utf8_lossy(&request[header_end..]);
+                let response = if body.contains("spotMeta") {
+                    spot_meta()
+                } else {
+                    perp_meta(&perps.lock().unwrap())
+                };
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                );
+                let _ = stream.write_all(reply.as_bytes()).await;
+            });
+        }
+    });
+    addr
+}
+
+/// Endpoints of a `serve_info` mock; the WebSocket is unreachable, so workers register and keep reconnecting
+fn mock_endpoints(addr: SocketAddr) -> HyperliquidEndpoints {
+    HyperliquidEndpoints {
+        ws_url: "ws://127.0.0.1:1".to_string(),
+        info_url: format!("http://{}", addr),
+    }
+}
+
+/// Symbols registered under every `{agora_path}/{market}/{data_type}` directory, waiting until all are non-empty.
+/// Returns one sorted list per directory, in the order given.
+async fn registered_symbols(
+    cluster: &TestCluster,
+    agora_path: &str,
+    directories: &[&str],
+) -> Vec<Vec<String>> {
+    let metaclient = AgoraClient::new(cluster.metaserver()).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let tree = metaclient.get_path_tree().await.unwrap();
+            let registered: Vec<Vec<String>> = directories
+                .iter()
+                .map(|dir| {
+                    let mut names: Vec<String> = tree
+                        .get_child(&format!("{}/{}", agora_path, dir))
+                        .map(|node| {
+                            node.children()
+                                .iter()
+                                .map(|child| child.name().to_string())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    names.sort();
+                    names
+                })
+                .collect();
+            if registered.iter().all(|names| !names.is_empty()) {
+                return registered;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("publisher paths not registered within 10s")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_filter_keeps_listing_order() {
+        let listed = symbols(&["SOL_PERP", "BTC_PERP", "ETH_PERP"]);
+        assert_eq!(UniverseFilter::default().apply(listed.clone()), listed);
+    }
+
+    #[test]
+    fn denylist_drops_symbols() {
+        let filter = UniverseFilter {
+            denylist: symbols(&["BTC_PERP", "DOGE_PERP"]),
+            ..UniverseFilter::default()
+        };
+        let listed = symbols(&["SOL_PERP", "BTC_PERP", "ETH_PERP"]);
+        assert_eq!(filter.apply(listed), symbols(&["SOL_PERP", "ETH_PERP"]));
+    }
+
+    #[test]
+    fn allowlist_keeps_listed_symbols_in_allowlist_order() {
+        let filter = UniverseFilter {
+            allowlist: Some(symbols(&[
+                "ETH_PERP",
+                "DELISTED_PERP",
+                "BTC_PERP",
+                "ETH_PERP",
+            ])),
+            ..UniverseFilter::default()
+        };
+        let listed = symbols(&["SOL_PERP", "BTC_PERP", "ETH_PERP"]);
+        assert_eq!(filter.apply(listed), symbols(&["ETH_PERP", "BTC_PERP"]));
+    }
+
+    #[test]
+    fn max_symbols_picks_first_by_name() {
+        let filter = UniverseFilter {
+            max_symbols: Some(2),
+            ..UniverseFilter::default()
+        };
+        let listed = symbols(&["SOL_PERP", "BTC_PERP", "ETH_PERP"]);
+        assert_eq!(
+            filter.apply(listed.clone()),
+            symbols(&["BTC_PERP", "ETH_PERP"])
+        );
+        // Independent of listing order
+        let reversed: Vec<_> = listed.into_iter().rev().collect();
+        assert_eq!(filter.apply(reversed), symbols(&["BTC_PERP", "ETH_PERP"]));
+    }
+
+    #[test]
+    fn max_symbols_with_allowlist_takes_allowlist_priority() {
+        let filter = UniverseFilter {
+            allowlist: Some(symbols(&["SOL_PERP", "BTC_PERP", "ETH_PERP"])),
+            denylist: Vec::new(),
+            max_symbols: Some(2),
+        };
+        let listed = symbols(&["ETH_PERP", "BTC_PERP", "SOL_PERP"]);
+        assert_eq!(filter.apply(listed), symbols(&["SOL_PERP", "BTC_PERP"]));
+    }
+
+    #[test]
+    fn denylist_applies_before_max_symbols() {
+        let filter = UniverseFilter {
+            allowlist: Some(symbols(&["SOL_PERP", "BTC_PERP", "ETH_PERP"])),
+            denylist: symbols(&["SOL_PERP"]),
+            max_symbols: Some(2),
+        };
+        let listed = symbols(&["ETH_PERP", "BTC_PERP", "SOL_PERP"]);
+        assert_eq!(filter.apply(listed), symbols(&["BTC_PERP", "ETH_PERP"]));
+
+        let filter = UniverseFilter {
+            allowlist: None,
+            denylist: symbols(&["BTC_PERP"]),
+            max_symbols: Some(1),
+        };
+        let listed = symbols(&["ETH_PERP", "BTC_PERP", "SOL_PERP"]);
+        assert_eq!(filter.apply(listed), symbols(&["ETH_PERP"]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn filter_reapplies_after_universe_update() {
+        let perps = Arc::new(Mutex::new(vec!["ETH", "BTC", "SOL", "AVAX"]));
+        let addr = serve_info(perps.clone(), 0).await;
+        let endpoints = HyperliquidEndpoints {
+            ws_url: "ws://127.0.0.1:1".to_string(),
+            info_url: format!("http://{}", addr),
+        };
+        let filter = UniverseFilter {
+            allowlist: None,
+            denylist: symbols(&["BTC_PERP"]),
+            max_symbols: Some(2),
+        };
+        let manager = UniverseManager::new(
+            Duration::from_millis(100),
+            Some(endpoints),
+            Some(filter),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            manager.perp_universe().await.unwrap(),
+            symbols(&["AVAX_PERP", "ETH_PERP"])
+        );
+        // The cap applies per market
+        assert_eq!(
+            manager.spot_universe().await.unwrap(),
+            symbols(&["HYPE-USDC", "PURR-USDC"])
+        );
+        assert_eq!(
+            manager.translate_to_hyperliquid(&symbol("HYPE-USDC")).await,
+            Some(symbol("@107"))
+        );
+        // Filtered-out symbols are not in the symbol map
+        assert_eq!(
+            manager.translate_to_hyperliquid(&symbol("SOL_PERP")).await,
+            None
+        );
+        assert_eq!(
+            manager.translate_to_hyperliquid(&symbol("BTC_PERP")).await,
+            None
+        );
+
+        *perps.lock().unwrap() = vec!["BTC", "ARB", "SOL", "ETH"];
+        let expected = symbols(&["ARB_PERP", "ETH_PERP"]);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while manager.perp_universe().await.unwrap() != expected {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("perp universe did not update within 5s");
+
+        assert_eq!(
+            manager.translate_to_hyperliquid(&symbol("ARB_PERP")).await,
+            Some(symbol("ARB"))
+        );
+        assert_eq!(
+            manager.translate_to_hyperliquid(&symbol("AVAX_PERP")).await,
+            None
+        );
+        assert_eq!(manager.symbol_map().await.len(), 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn initial_fetch_retries_through_transient_failures() {
+        let addr = serve_info(Arc::new(Mutex::new(vec!["BTC", "ETH"])), 3).await;
+        let endpoints = HyperliquidEndpoints {
+            ws_url: "ws://127.0.0.1:1".to_string(),
+            info_url: format!("http://{}", addr),
+        };
+        let config = UniverseManagerConfig {
+            initial_fetch_retries: 3,
+            retry_backoff: Duration::from_millis(10),
+        };
+        // All three failures hit the perp fetch, which succeeds on its last retry
+        let manager = UniverseManager::new(
+            Duration::from_secs(3600),
+            Some(endpoints),
+            None,
+            Some(config),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            manager.perp_universe().await.unwrap(),
+            symbols(&["BTC_PERP", "ETH_PERP"])
+        );
+        assert_eq!(
+            manager.spot_universe().await.unwrap(),
+            symbols(&["PURR-USDC", "HYPE-USDC"])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn initial_fetch_fails_hard_when_no_market_loads() {
+        let addr = serve_info(Arc::new(Mutex::new(vec!["BTC"])), usize::MAX).await;
+        let endpoints = HyperliquidEndpoints {
+            ws_url: "ws://127.0.0.1:1".to_string(),
+            info_url: format!("http://{}", addr),
+        };
+        let config = UniverseManagerConfig {
+            initial_fetch_retries: 2,
+            retry_backoff: Duration::from_millis(10),
+        };
+        let error = UniverseManager::new(
+            Duration::from_secs(3600),
+            Some(endpoints),
+            None,
+            Some(config),
+        )
+        .await
+        .err()
+        .expect("UniverseManager started without a universe");
+        assert!(
+            matches!(
+                error.downcast_ref::<UniverseFetchError>(),
+                Some(UniverseFetchError::Status {
+                    market: "perp",
+                    status: 503
+                })
+            ),
+            "{:#}",
+            error
+        );
+        assert!(error.to_string().contains("no market loaded"), "{}", error);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn one_loaded_market_is_enough_to_start() {
+        // Perp fetch exhausts its two retries; spot succeeds
+        let addr = serve_info(Arc::new(Mutex::new(vec!["BTC"])), 3).await;
+        let endpoints = HyperliquidEndpoints {
+            ws_url: "ws://127.0.0.1:1".to_string(),
+            info_url: format!("http://{}", addr),
+        };
+        let config = UniverseManagerConfig {
+            initial_fetch_retries: 2,
+            retry_backoff: Duration::from_millis(10),
+        };
+        let manager = UniverseManager::new(
+            Duration::from_secs(3600),
+            Some(endpoints),
+            None,
+            Some(config),
+        )
+        .await
+        .unwrap();
+        assert!(manager.perp_universe().await.unwrap().is_empty());
+        assert_eq!(manager.spot_universe().await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn unlisted_checks_each_symbol_against_its_market() {
+        let filter = UniverseFilter {
+            allowlist: Some(symbols(&["BTC_PERP", "DOGE_PERP", "PURR-USDC", "WOW-USDC"])),
+            ..UniverseFilter::default()
+        };
+        let perps = symbols(&["BTC_PERP", "ETH_PERP"]);
+        let spot = symbols(&["PURR-USDC", "HYPE-USDC"]);
+        assert_eq!(
+            filter.unlisted(Some(&perps), Some(&spot)),
+            symbols(&["DOGE_PERP", "WOW-USDC"])
+        );
+        // A market that did not load cannot be checked
+        assert_eq!(filter.unlisted(Some(&perps), None), symbols(&["DOGE_PERP"]));
+        assert!(
+            UniverseFilter::default()
+                .unlisted(Some(&perps), Some(&spot))
+                .is_empty()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn allowlisted_symbol_missing_from_universe_is_an_error() {
+        let addr = serve_info(Arc::new(Mutex::new(vec!["BTC", "ETH"])), 0).await;
+        let filter = UniverseFilter {
+            allowlist: Some(symbols(&["BTC_PERP", "DOGE_PERP", "PURR-USDC"])),
+            ..UniverseFilter::default()
+        };
+        let error = UniverseManager::new(
+            Duration::from_secs(3600),
+            Some(mock_endpoints(addr)),
+            Some(filter),
+            None,
+        )
+        .await
+        .err()
+        .expect("UniverseManager started with an unlisted symbol");
+        assert!(
+            error.to_string().contains("not in the live universe"),
+            "{}",
+            error
+        );
+        assert!(error.to_string().ends_with(": DOGE_PERP"), "{}", error);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn publisher_streams_only_allowlisted_symbols() {
+        let cluster = TestCluster::start().await.unwrap();
+        let addr = serve_info(Arc::new(Mutex::new(vec!["BTC", "ETH", "SOL"])), 0).await;
+        let agora_path = format!("tests/universe_{}/hyperliquid", std::process::id());
+        let filter = UniverseFilter {
+            allowlist: Some(symbols(&["BTC_PERP", "PURR-USDC"])),
+            ..UniverseFilter::default()
+        };
+        let publisher = HyperliquidPublisher::new(
+            &agora_path,
+            cluster.metaserver(),
+            cluster.gateway_port(),
+            Duration::from_secs(3600),
+            Duration::from_secs(3600),
+            Some(mock_endpoints(addr)),
+            Some(filter),
+        )
+        .await
+        .unwrap();
+        assert_eq!(publisher.perp_universe().unwrap(), symbols(&["BTC_PERP"]));
+        assert_eq!(publisher.spot_universe().unwrap(), symbols(&["PURR-USDC"]));
+
+        let directories = [
+            "perp/last_trade",
+            "perp/bbo",
+            "perp/orderbook",
+            "perp/perp_context",
+            "spot/last_trade",
+            "spot/bbo",
+            "spot/orderbook",
+            "spot/spot_context",
+        ];
+        let registered = registered_symbols(&cluster, &agora_path, &directories).await;
+        for (dir, names) in directories.iter().zip(registered) {
+            let expected = if dir.starts_with("perp/") {
+                "BTC_PERP"
+            } else {
+                "PURR-USDC"
+            };
+            assert_eq!(names, vec![expected.to_string()], "{}", dir);
+        }
+    }
+}
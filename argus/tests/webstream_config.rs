@@ -0,0 +1,181 @@
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::{
+    HyperliquidEndpoints, HyperliquidWebstreamWorker, ReconnectBackoff, TradeUpdate,
+};
+use argus::crypto::{WebstreamConfig, WebstreamConnectError, connect_websocket};
+use argus::types::TradingSymbol;
+use bimap::BiMap;
+use futures_util::StreamExt;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::accept_async;
+use url::Url;
+
+/// How the mock proxy answers `CONNECT`
+#[derive(Clone, Copy)]
+enum ProxyBehavior {
+    /// Accepts the TCP connection and never replies
+    Silent,
+    /// Replies 403
+    Refuse,
+    /// Replies 200 and relays bytes to the requested target
+    Tunnel,
+}
+
+/// Reads a `CONNECT` request up to its blank line and returns its target authority
+async fn read_connect(stream: &mut TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut byte = [0u8; 1];
+    while !request.ends_with(b"\r\n\r\n") {
+        stream.read_exact(&mut byte).await.unwrap();
+        request.push(byte[0]);
+    }
+    let request = String::from_utf8(request).unwrap();
+    request.split_whitespace().nth(1).unwrap().to_string()
+}
+
+/// HTTP proxy on a local port behaving as `behavior`; returns its URL
+async fn mock_proxy(behavior: ProxyBehavior) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                match behavior {
+                    ProxyBehavior::Silent => {
+                        let _ = stream.read(&mut [0u8; 1024]).await;
+                        std::future::pending::<()>().await;
+                    }
+                    ProxyBehavior::Refuse => {
+                        read_connect(&mut stream).await;
+                        let _ = stream.write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n").await;
+                    }
+                    ProxyBehavior::Tunnel => {
+                        let target = read_connect(&mut stream).await;
+                        let mut upstream = TcpStream::connect(target).await.unwrap();
+                        stream
+                            .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                            .await
+                            .unwrap();
+                        let _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+                    }
+                }
+            });
+        }
+    });
+    url
+}
+
+/// WebSocket server that accepts connections and drains them; returns its ws:// URL
+async fn mock_websocket() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                if let Ok(mut ws) = accept_async(stream).await {
+                    while let Some(Ok(_)) = ws.next().await {}
+                }
+            });
+        }
+    });
+    url
+}
+
+fn proxied(proxy: Url, connect_timeout: Option<Duration>) -> WebstreamConfig {
+    WebstreamConfig {
+        connect_timeout,
+        proxy: Some(proxy),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn silent_proxy_times_out_promptly() {
+        let proxy = mock_proxy(ProxyBehavior::Silent).await;
+        let timeout = Duration::from_millis(200);
+        let started = Instant::now();
+        let result = connect_websocket("ws://127.0.0.1:1/ws", &proxied(proxy, Some(timeout))).await;
+        assert!(matches!(result, Err(WebstreamConnectError::Timeout(t)) if t == timeout));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn bad_proxies_fail_with_proxy_errors() {
+        let refusing = mock_proxy(ProxyBehavior::Refuse).await;
+        match connect_websocket("ws://127.0.0.1:1/ws", &proxied(refusing, None)).await {
+            Err(WebstreamConnectError::Proxy(reason)) => {
+                assert!(reason.contains("403"), "{}", reason)
+            }
+            other => panic!("expected a proxy error, got {:?}", other.map(|_| ())),
+        }
+
+        // Nothing listens on a port whose listener was dropped
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        drop(listener);
+        let result = connect_websocket("ws://127.0.0.1:1/ws", &proxied(closed, None)).await;
+        assert!(matches!(result, Err(WebstreamConnectError::Proxy(_))));
+
+        let socks = Url::parse("socks5://127.0.0.1:1080").unwrap();
+        let result = connect_websocket("ws://127.0.0.1:1/ws", &proxied(socks, None)).await;
+        assert!(matches!(result, Err(WebstreamConnectError::Proxy(_))));
+    }
+
+    #[tokio::test]
+    async fn proxy_tunnel_reaches_websocket() {
+        let ws_url = mock_websocket().await;
+        let proxy = mock_proxy(ProxyBehavior::Tunnel).await;
+        let config = proxied(proxy, Some(Duration::from_secs(5)));
+        assert!(connect_websocket(&ws_url, &config).await.is_ok());
+        // The default config connects directly
+        assert!(
+            connect_websocket(&ws_url, &WebstreamConfig::default())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn worker_behind_silent_proxy_keeps_retrying() {
+        let cluster = TestCluster::start().await.unwrap();
+        let proxy = mock_proxy(ProxyBehavior::Silent).await;
+        let worker = HyperliquidWebstreamWorker::<TradeUpdate>::new(
+            &[TradingSymbol::from_str("BTC").unwrap()],
+            &format!("tests/webstream_config_{}", std::process::id()),
+            cluster.metaserver(),
+            cluster.gateway_port(),
+            BiMap::new(),
+            Some(ReconnectBackoff {
+                base: Duration::from_millis(10),
+                cap: Duration::from_millis(10),
+                min_stable_uptime: Duration::from_secs(60),
+            }),
+            None,
+            None,
+            Some(HyperliquidEndpoints {
+                ws_url: "ws://127.0.0.1:1/ws".to_string(),
+                info_url: String::new(),
+            }),
+            Some(proxied(proxy, Some(Duration::from_millis(100)))),
+        )
+        .await
+        .unwrap();
+
+        // Each attempt times out and counts as a reconnect instead of hanging the worker
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while worker.stats().reconnects < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("connect attempts should time out");
+        assert_eq!(worker.stats().messages_received, 0);
+        worker.shutdown().await;
+    }
+}
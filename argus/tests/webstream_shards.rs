@@ -0,0 +1,153 @@
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::webstream::HyperliquidPerpWebstreamSymbols;
+use argus::crypto::hyperliquid::{HyperliquidEndpoints, shard_symbols};
+use argus::types::TradingSymbol;
+use bimap::BiMap;
+use futures_util::StreamExt;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
+
+fn symbol(name: &str) -> TradingSymbol {
+    TradingSymbol::from_str(name).unwrap()
+}
+
+/// `count` distinct symbols, "S0" to "S{count-1}"
+fn universe(count: usize) -> Vec<TradingSymbol> {
+    (0..count).map(|i| symbol(&format!("S{}", i))).collect()
+}
+
+/// Accepts any number of WebSocket connections and reports (connection index, subscription type, coin)
+/// for every subscribe request. Returns the endpoints to point workers at.
+async fn mock_server() -> (
+    HyperliquidEndpoints,
+    mpsc::UnboundedReceiver<(usize, String, String)>,
+) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut connection = 0;
+        while let Ok((stream, _)) = listener.accept().await {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let Ok(mut ws) = accept_async(stream).await else {
+                    return;
+                };
+                while let Some(Ok(message)) = ws.next().await {
+                    if let Message::Text(text) = message {
+                        let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                        if request["method"] == "subscribe" {
+                            let subscription = &request["subscription"];
+                            let _ = tx.send((
+                                connection,
+                                subscription["type"].as_str().unwrap().to_string(),
+                                subscription["coin"].as_str().unwrap().to_string(),
+                            ));
+                        }
+                    }
+                }
+            });
+            connection += 1;
+        }
+    });
+    let endpoints = HyperliquidEndpoints {
+        ws_url,
+        info_url: String::new(),
+    };
+    (endpoints, rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_universe_splits_into_expected_shards() {
+        let symbols = universe(2500);
+
+        // Default: as few connections as the per-worker cap allows
+        let shards = shard_symbols(&symbols, None).unwrap();
+        let sizes: Vec<usize> = shards.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![1024, 1024, 452]);
+
+        let shards = shard_symbols(&symbols, Some(250)).unwrap();
+        assert_eq!(shards.len(), 10);
+        assert!(shards.iter().all(|shard| shard.len() == 250));
+        // Every symbol lands in exactly one shard, in universe order
+        assert_eq!(shards.concat(), symbols);
+
+        let shards = shard_symbols(&symbols, Some(300)).unwrap();
+        assert_eq!(shards.len(), 9);
+        assert_eq!(shards[8].len(), 100);
+
+        assert!(shard_symbols(&symbols, Some(0)).is_err());
+        assert!(shard_symbols(&symbols, Some(1025)).is_err());
+        assert!(shard_symbols(&[], None).unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bundle_streams_each_shard_over_its_own_connections() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (endpoints, mut received) = mock_server().await;
+        let coins = ["BTC", "ETH", "SOL", "ARB", "DOGE"];
+        let mut symbol_mapper = BiMap::new();
+        for coin in coins {
+            symbol_mapper.insert(symbol(&format!("{}_PERP", coin)), symbol(coin));
+        }
+        let hyperliquid_symbols: Vec<TradingSymbol> = coins.iter().map(|c| symbol(c)).collect();
+
+        let bundle = HyperliquidPerpWebstreamSymbols::new(
+            &hyperliquid_symbols,
+            &format!("tests/webstream_shards_{}", std::process::id()),
+            cluster.metaserver(),
+            cluster.gateway_port(),
+            symbol_mapper,
+            None,
+            None,
+            Some(endpoints),
+            Some(2),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(bundle.shard_count(), 3);
+        assert_eq!(bundle.symbols(), hyperliquid_symbols.as_slice());
+
+        // 5 coins x 4 data types, spread over 3 shards x 4 data types = 12 connections
+        let mut connections: BTreeMap<usize, (BTreeSet<String>, BTreeSet<String>)> =
+            BTreeMap::new();
+        for _ in 0..coins.len() * 4 {
+            let (connection, subscription_type, coin) =
+                tokio::time::timeout(Duration::from_secs(5), received.recv())
+                    .await
+                    .expect("every coin should be subscribed")
+                    .unwrap();
+            let (types, coins) = connections.entry(connection).or_default();
+            types.insert(subscription_type);
+            coins.insert(coin);
+        }
+        assert_eq!(connections.len(), 12);
+        // Each connection carries one data type for one shard
+        assert!(connections.values().all(|(types, _)| types.len() == 1));
+        let shards: BTreeMap<BTreeSet<String>, usize> =
+            connections
+                .values()
+                .fold(BTreeMap::new(), |mut shards, (_, coins)| {
+                    *shards.entry(coins.clone()).or_default() += 1;
+                    shards
+                });
+        let shard = |coins: &[&str]| coins.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            shards,
+            BTreeMap::from([
+                (shard(&["BTC", "ETH"]), 4),
+                (shard(&["SOL", "ARB"]), 4),
+                (shard(&["DOGE"]), 4),
+            ])
+        );
+    }
+}
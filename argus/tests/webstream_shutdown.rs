@@ -0,0 +1,124 @@
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::{HyperliquidEndpoints, HyperliquidWebstreamWorker, TradeUpdate};
+use argus::types::TradingSymbol;
+use bimap::BiMap;
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
+
+/// What the mock Hyperliquid server received, in order
+#[derive(Debug, PartialEq)]
+enum Received {
+    /// (method, coin) of a subscribe/unsubscribe request
+    Request(String, String),
+    Close,
+}
+
+/// Accepts one WebSocket connection and reports every (un)subscribe request and the Close frame.
+/// Returns the endpoints to point a worker at.
+async fn mock_server() -> (HyperliquidEndpoints, mpsc::UnboundedReceiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(message)) = ws.next().await {
+            match message {
+                Message::Text(text) => {
+                    let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    // Heartbeat pings carry no subscription
+                    if let Some(coin) = request["subscription"]["coin"].as_str() {
+                        let _ = tx.send(Received::Request(
+                            request["method"].as_str().unwrap().to_string(),
+                            coin.to_string(),
+                        ));
+                    }
+                }
+                Message::Close(_) => {
+                    let _ = tx.send(Received::Close);
+                }
+                _ => {}
+            }
+        }
+    });
+    let endpoints = HyperliquidEndpoints {
+        ws_url,
+        info_url: String::new(),
+    };
+    (endpoints, rx)
+}
+
+async fn next(rx: &mut mpsc::UnboundedReceiver<Received>) -> Received {
+    tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("mock server should receive a frame")
+        .unwrap()
+}
+
+fn request(method: &str, coin: &str) -> Received {
+    Received::Request(method.to_string(), coin.to_string())
+}
+
+async fn trade_worker(
+    cluster: &TestCluster,
+    name: &str,
+    endpoints: HyperliquidEndpoints,
+) -> HyperliquidWebstreamWorker<TradeUpdate> {
+    let symbols = [
+        TradingSymbol::from_str("BTC").unwrap(),
+        TradingSymbol::from_str("ETH").unwrap(),
+    ];
+    HyperliquidWebstreamWorker::<TradeUpdate>::new(
+        &symbols,
+        &format!("tests/webstream_shutdown_{}_{}", name, std::process::id()),
+        cluster.metaserver(),
+        cluster.gateway_port(),
+        BiMap::new(),
+        None,
+        None,
+        None,
+        Some(endpoints),
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shutdown_unsubscribes_each_coin_then_closes() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (endpoints, mut received) = mock_server().await;
+        let worker = trade_worker(&cluster, "explicit", endpoints).await;
+        assert_eq!(next(&mut received).await, request("subscribe", "BTC"));
+        assert_eq!(next(&mut received).await, request("subscribe", "ETH"));
+
+        tokio::time::timeout(Duration::from_secs(5), worker.shutdown())
+            .await
+            .expect("shutdown is bounded");
+        assert_eq!(next(&mut received).await, request("unsubscribe", "BTC"));
+        assert_eq!(next(&mut received).await, request("unsubscribe", "ETH"));
+        assert_eq!(next(&mut received).await, Received::Close);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn drop_still_unsubscribes_in_background() {
+        let cluster = TestCluster::start().await.unwrap();
+        let (endpoints, mut received) = mock_server().await;
+        let worker = trade_worker(&cluster, "drop", endpoints).await;
+        assert_eq!(next(&mut received).await, request("subscribe", "BTC"));
+        assert_eq!(next(&mut received).await, request("subscribe", "ETH"));
+
+        drop(worker);
+        assert_eq!(next(&mut received).await, request("unsubscribe", "BTC"));
+        assert_eq!(next(&mut received).await, request("unsubscribe", "ETH"));
+        assert_eq!(next(&mut received).await, Received::Close);
+    }
+}
@@ -0,0 +1,171 @@
+use agora::testkit::TestCluster;
+use argus::crypto::hyperliquid::{
+    HyperliquidEndpoints, HyperliquidWebstreamWorker, ReconnectBackoff, TradeUpdate, WorkerStats,
+};
+use argus::types::TradingSymbol;
+use bimap::BiMap;
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
+
+fn trades(coin: &str, count: usize) -> String {
+    let trades: Vec<serde_json::Value> = (0..count)
+        .map(|i| {
+            serde_json::json!({
+                "coin": coin,
+                "side": "B",
+                "px": "50000.0",
+                "sz": "0.01",
+                "time": 1672515782136u64 + i as u64,
+                "tid": i,
+            })
+        })
+        .collect();
+    serde_json::json!({"channel": "trades", "data": trades}).to_string()
+}
+
+/// Replays `frames` on the first connection once both coins are subscribed, then closes it;
+/// later connections are held open without traffic. Returns the endpoints to point a worker at.
+async fn mock_server(frames: Vec<String>) -> HyperliquidEndpoints {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut frames = Some(frames);
+        while let Ok((stream, _)) = listener.accept().await {
+            let frames = frames.take();
+            tokio::spawn(async move {
+                let Ok(mut ws) = accept_async(stream).await else {
+                    return;
+                };
+                let Some(frames) = frames else {
+                    while let Some(Ok(_)) = ws.next().await {}
+                    return;
+                };
+                for _ in 0..2 {
+                    ws.next().await.unwrap().unwrap();
+                }
+                for frame in frames {
+                    ws.send(Message::Text(frame.into())).await.unwrap();
+                }
+                let _ = ws.close(None).await;
+            });
+        }
+    });
+    HyperliquidEndpoints {
+        ws_url,
+        info_url: String::new(),
+    }
+}
+
+/// Polls `stats` until `done` holds, failing after 5 seconds
+async fn wait_for_stats(
+    worker: &HyperliquidWebstreamWorker<TradeUpdate>,
+    done: impl Fn(&WorkerStats) -> bool,
+) -> WorkerStats {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let stats = worker.stats();
+            if done(&stats) {
+                return stats;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("worker stats should settle")
+}
+
+fn at(millis: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn counters_track_synthetic_messages_and_reconnects() {
+        let cluster = TestCluster::start().await.unwrap();
+        let frames = vec![
+            r#"{"channel":"subscriptionResponse","data":{"method":"subscribe","subscription":{"type":"trades","coin":"BTC"}}}"#.to_string(),
+            trades("BTC", 2),
+            trades("ETH", 1),
+            "not json".to_string(),
+            // Empty trade arrays are rejected by `TradeUpdate::of_channel_data`
+            trades("BTC", 0),
+            r#"{"channel":"trades"}"#.to_string(),
+        ];
+        let endpoints = mock_server(frames).await;
+        let symbols = [
+            TradingSymbol::from_str("BTC").unwrap(),
+            TradingSymbol::from_str("ETH").unwrap(),
+        ];
+        let started_at = Utc::now();
+        let worker = HyperliquidWebstreamWorker::<TradeUpdate>::new(
+            &symbols,
+            &format!("tests/webstream_stats_{}", std::process::id()),
+            cluster.metaserver(),
+            cluster.gateway_port(),
+            BiMap::new(),
+            Some(ReconnectBackoff {
+                base: Duration::from_millis(10),
+                cap: Duration::from_millis(10),
+                min_stable_uptime: Duration::from_secs(60),
+            }),
+            None,
+            None,
+            Some(endpoints),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The reconnect follows the server's close, so every frame before it has been dispatched
+        let stats = wait_for_stats(&worker, |stats| stats.reconnects >= 1).await;
+        assert_eq!(stats.messages_received, 6);
+        assert_eq!(stats.messages_published, 3);
+        assert_eq!(stats.publish_errors, 0);
+        assert_eq!(stats.parse_errors, 3);
+        assert_eq!(stats.reconnects, 1);
+        let last_message_at = stats.last_message_at.expect("frames were received");
+        assert!(last_message_at >= started_at - chrono::Duration::milliseconds(1));
+        assert!(last_message_at <= Utc::now());
+
+        worker.shutdown().await;
+    }
+
+    #[test]
+    fn merge_sums_counts_and_keeps_latest_message() {
+        let mut total = WorkerStats::default();
+        total.merge(WorkerStats {
+            messages_received: 10,
+            messages_published: 8,
+            publish_errors: 1,
+            parse_errors: 1,
+            reconnects: 2,
+            last_message_at: at(2_000),
+        });
+        total.merge(WorkerStats {
+            messages_received: 5,
+            messages_published: 5,
+            last_message_at: at(1_000),
+            ..Default::default()
+        });
+        // Workers that have not received anything leave the latest time alone
+        total.merge(WorkerStats::default());
+        assert_eq!(
+            total,
+            WorkerStats {
+                messages_received: 15,
+                messages_published: 13,
+                publish_errors: 1,
+                parse_errors: 1,
+                reconnects: 2,
+                last_message_at: at(2_000),
+            }
+        );
+    }
+}
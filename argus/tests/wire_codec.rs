@@ -0,0 +1,67 @@
+use agora::WireCodec;
+use argus::crypto::hyperliquid::OrderbookSnapshot;
+use argus::types::{Price, TradeSize, TradingSymbol};
+use chrono::Utc;
+use std::time::{Duration, Instant};
+
+fn snapshot(levels: u32) -> OrderbookSnapshot {
+    let side = |sign: f64| {
+        (1..=levels)
+            .map(|i| {
+                (
+                    Price::from_f64(100_000.0 + sign * i as f64 * 0.5).unwrap(),
+                    TradeSize::from_f64(0.001 * i as f64).unwrap(),
+                    i,
+                )
+            })
+            .collect()
+    };
+    OrderbookSnapshot {
+        symbol: TradingSymbol::from_str("BTC_PERP").unwrap(),
+        received_time: Utc::now(),
+        time: Utc::now(),
+        bid_levels: side(-1.0),
+        ask_levels: side(1.0),
+    }
+}
+
+/// Encodes and decodes `value` `rounds` times; returns (payload size, elapsed)
+fn round_trip(codec: WireCodec, value: &OrderbookSnapshot, rounds: u32) -> (usize, Duration) {
+    let started = Instant::now();
+    let mut size = 0;
+    for _ in 0..rounds {
+        let bytes = codec.encode(value).unwrap();
+        size = bytes.len();
+        let decoded: OrderbookSnapshot = codec.decode(&bytes).unwrap();
+        assert_eq!(&decoded, value);
+    }
+    (size, started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Size/CPU comparison of the wire codecs on a 100-level snapshot.
+    /// Run with `cargo test --release --test wire_codec -- --nocapture` for representative timings.
+    #[test]
+    fn orderbook_snapshot_round_trip_benchmark() {
+        let value = snapshot(100);
+        let rounds = 1000;
+        let mut sizes = Vec::new();
+        for codec in WireCodec::ALL {
+            let (size, elapsed) = round_trip(codec, &value, rounds);
+            println!(
+                "{:>8}: {:>6} bytes, {:>8.2}us per round trip",
+                codec,
+                size,
+                elapsed.as_secs_f64() * 1e6 / rounds as f64
+            );
+            sizes.push((codec, size));
+        }
+        let size_of = |target: WireCodec| sizes.iter().find(|(c, _)| *c == target).unwrap().1;
+        // Fixed-width f64s plus varint counts, no field names or decimal text
+        assert!(size_of(WireCodec::Bincode) < size_of(WireCodec::Json));
+        assert!(size_of(WireCodec::Postcard) < size_of(WireCodec::Json));
+    }
+}
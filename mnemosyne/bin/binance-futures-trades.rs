@@ -22,6 +22,10 @@ struct Args {
     #[arg(long, action=ArgAction::SetTrue)]
     recompute_universe: bool,
 
+    /// Extend the cached universe with dates listed since its latest date (and newly listed symbols)
+    #[arg(long, action=ArgAction::SetTrue)]
+    update_universe: bool,
+
     /// Recompute on-hive symbol dates
     #[arg(long, action=ArgAction::SetTrue)]
     recompute_onhive: bool,
@@ -41,6 +45,10 @@ struct Args {
     /// Skip confirmation prompt
     #[arg(long, default_value_t = false)]
     yes: bool,
+
+    /// Recover CSVs whose header/columns drifted from the expected schema instead of failing the day
+    #[arg(long, action=ArgAction::SetTrue)]
+    lenient_csv: bool,
 }
 
 #[tokio::main]
@@ -69,10 +77,16 @@ async fn main() -> Result<()> {
         peg_symbol.to_string(),
         Some(earliest_date),
         None,
-    ).await?);
+        None,
+    ).await?
+    .with_lenient_csv(args.lenient_csv));
 
     // Initialize universe
-    tb.initialize_universe(args.recompute_universe).await?;
+    if args.update_universe && !args.recompute_universe {
+        tb.update_universe_incremental().await?;
+    } else {
+        tb.initialize_universe(args.recompute_universe).await?;
+    }
 
     // Get universe_df and print head
     let universe_df = tb.get_universe_df().await?;
@@ -111,7 +125,7 @@ async fn main() -> Result<()> {
     }
 
     let update_stats = tb
-        .update_universe(args.parallelism, args.recompute_onhive)
+        .update_universe(args.parallelism, args.recompute_onhive, None)
         .await?;
     println!("{:?}", update_stats);
     Ok(())
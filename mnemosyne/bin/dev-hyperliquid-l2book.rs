@@ -80,7 +80,7 @@ fn main() -> Result<()> {
         }
 
         println!("Calling read_hyperliquid_l2book_bydate()...");
-        match read_hyperliquid_l2book_bydate(&args.raw_data_path, date) {
+        match read_hyperliquid_l2book_bydate(&args.raw_data_path, date, None) {
             Ok(df) => {
                 println!("\n✓ SUCCESS: Aggregated function completed");
                 println!("  Shape: {:?}", df.shape());
@@ -79,12 +79,11 @@ async fn get_all_trade_pairs(client: &Client, prefix: &str) -> Result<Vec<String
 
                 // Parse subdirectory name from full S3 prefix
                 // Example: "data/spot/daily/trades/BTCUSDT/" -> "BTCUSDT"
-                if let Some(pair) = prefix_str.strip_prefix(&fixed_prefix) {
-                    if let Some(pair) = pair.strip_suffix('/') {
-                        if !pair.is_empty() {
-                            trade_pairs.insert(pair.to_string());
-                        }
-                    }
+                if let Some(pair) = prefix_str.strip_prefix(&fixed_prefix)
+                    && let Some(pair) = pair.strip_suffix('/')
+                    && !pair.is_empty()
+                {
+                    trade_pairs.insert(pair.to_string());
                 }
             }
         }
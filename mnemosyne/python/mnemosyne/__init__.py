@@ -1,4 +1,5 @@
 from . import mnemosyne as _mnemosyne_ext
+from .mnemosyne import hive_range_paths, load, scan_hyperliquid_l2book_bydate, schema_for
 # Use pure Python DatasetType instead of Rust version for picklability
 from .dataset.dataset_types import DatasetType
 from . import dataset
@@ -7,6 +8,10 @@ from . import engines
 
 __all__ = [
     "DatasetType",
+    "hive_range_paths",
+    "load",
+    "scan_hyperliquid_l2book_bydate",
+    "schema_for",
     "dataset",
     "binance",
     "engines"
@@ -6,12 +6,13 @@
 ///
 /// ## Data Flow
 /// 1. **Universe Initialization**: Fetch (symbol, date) pairs from S3 → cache to `universe.parquet`
+///    (`update_universe_incremental` extends the cache with only the dates listed since)
 /// 2. **Local Inventory**: Scan hive directory → validate parquets → cache to `hive_symbol_date_pairs.parquet`
 /// 3. **Diff Calculation**: Anti-join `universe - hive` → missing pairs
 /// 4. **Parallel Download**: Rayon workers with per-thread tokio runtimes → download + process → parquet
 ///
 /// ## Caching Strategy
-/// - **Universe cache**: Avoid expensive S3 listing (recompute with `--recompute-universe`)
+/// - **Universe cache**: Avoid expensive S3 listing (recompute with `--recompute-universe`, extend with `--update-universe`)
 /// - **Hive cache**: Incremental validation (only new files), recompute with `--recompute-onhive`
 /// - **Corrupted files**: Auto-deleted during validation to prevent bad data accumulation
 pub mod binance;
@@ -19,13 +20,15 @@ pub use binance::{BinanceSpotTradeBook, BinanceUmFuturesTradeBook};
 pub mod hyperliquid;
 
 use anyhow::{Context, Result};
+use argus::ParquetCompressionConfig;
 use chrono::NaiveDate;
 use polars::prelude::*;
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
 /// Convert Polars Date (i32 days since Unix epoch) to chrono::NaiveDate.
@@ -36,6 +39,44 @@ fn polars_date_to_naive(days_since_epoch: i32) -> Option<NaiveDate> {
     NaiveDate::from_num_days_from_ce_opt(days_since_epoch + 719163)
 }
 
+/// Convert the shared recording-layer codec config into polars' ParquetCompression.
+/// Levels are validated first, so out-of-range values fail with the config's error message.
+pub fn polars_compression(config: ParquetCompressionConfig) -> Result<ParquetCompression> {
+    Ok(match config.validated()? {
+        ParquetCompressionConfig::Zstd(level) => {
+            ParquetCompression::Zstd(Some(ZstdLevel::try_new(level)?))
+        }
+        ParquetCompressionConfig::Lz4 => ParquetCompression::Lz4Raw,
+        ParquetCompressionConfig::Brotli(level) => {
+            ParquetCompression::Brotli(Some(BrotliLevel::try_new(level)?))
+        }
+        ParquetCompressionConfig::Snappy => ParquetCompression::Snappy,
+        ParquetCompressionConfig::Uncompressed => ParquetCompression::Uncompressed,
+    })
+}
+
+/// Maps `items` in parallel, at most `max_in_flight` at a time, handing each chunk's results to `consume`
+/// (in input order) before the next chunk starts. Bounds how many mapped results are alive at once,
+/// trading the idle tail of each chunk for a memory ceiling. `None` maps everything as one chunk.
+/// Error: `max_in_flight` is zero, or `consume` fails (later chunks are not mapped)
+pub fn par_map_chunked<T, U>(
+    items: &[T],
+    max_in_flight: Option<usize>,
+    map: impl Fn(&T) -> U + Sync + Send,
+    mut consume: impl FnMut(Vec<U>) -> Result<()>,
+) -> Result<()>
+where
+    T: Sync,
+    U: Send,
+{
+    anyhow::ensure!(max_in_flight != Some(0), "max_in_flight must be positive");
+    let chunk_size = max_in_flight.unwrap_or(items.len()).max(1);
+    for chunk in items.chunks(chunk_size) {
+        consume(chunk.par_iter().map(&map).collect())?;
+    }
+    Ok(())
+}
+
 /// Extract (symbol, date) from first row of parquet file for validation.
 /// Validates both metadata AND data pages (catches more corruption than just metadata check).
 /// Returns None if corrupted → file is automatically deleted to prevent accumulation of bad data.
@@ -85,6 +126,40 @@ fn extract_symbol_date_first_row(path: &Path) -> Option<(String, NaiveDate)> {
     Some((symbol.to_string(), date))
 }
 
+/// Latest date of each symbol in a universe DataFrame ["symbol": String, "date": Date]
+pub fn latest_universe_dates(universe_df: &DataFrame) -> Result<HashMap<String, NaiveDate>> {
+    let latest = universe_df
+        .clone()
+        .lazy()
+        .group_by([col("symbol")])
+        .agg([col("date").max()])
+        .collect()?;
+    let symbols = latest.column("symbol")?.str()?;
+    let dates = latest.column("date")?.date()?;
+    Ok(symbols
+        .into_iter()
+        .zip(&dates.phys)
+        .filter_map(|(symbol, date)| Some((symbol?.to_string(), polars_date_to_naive(date?)?)))
+        .collect())
+}
+
+/// Union of a cached universe and newly fetched rows, deduplicated and sorted by (symbol, date).
+/// Cached symbols missing from `fetched` (delisted, or their listing failed) keep all their dates.
+pub fn merge_universe(cached: DataFrame, fetched: DataFrame) -> Result<DataFrame> {
+    let select = [col("symbol").cast(DataType::String), col("date")];
+    let merged = concat(
+        [
+            cached.lazy().select(select.clone()),
+            fetched.lazy().select(select),
+        ],
+        UnionArgs::default(),
+    )?
+    .unique(None, UniqueKeepStrategy::First)
+    .sort(["symbol", "date"], SortMultipleOptions::default())
+    .collect()?;
+    Ok(merged)
+}
+
 #[derive(Debug, Default)]
 pub struct UpdateStats {
     pub total: usize,
@@ -93,6 +168,75 @@ pub struct UpdateStats {
     pub failed: usize,
 }
 
+/// Snapshot of an `update_universe` run, sent after every finished (symbol, date) pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackfillProgress {
+    /// Pairs scheduled in this run
+    pub total: usize,
+    /// Pairs finished so far, whatever their outcome (`success + skipped + failed`)
+    pub completed: usize,
+    pub success: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub elapsed: Duration,
+    /// Finished pairs per second since the run started
+    pub throughput: f64,
+    /// Time left at the current throughput
+    pub eta: Duration,
+}
+
+/// Counts shared by the rayon workers of one `update_universe` run.
+/// Events are sent under the lock, so their counts only grow.
+struct ProgressTracker {
+    total: usize,
+    started: Instant,
+    stats: Mutex<UpdateStats>,
+    sender: Option<mpsc::Sender<BackfillProgress>>,
+}
+
+impl ProgressTracker {
+    fn new(total: usize, sender: Option<mpsc::Sender<BackfillProgress>>) -> Self {
+        Self {
+            total,
+            started: Instant::now(),
+            stats: Mutex::new(UpdateStats::default()),
+            sender,
+        }
+    }
+
+    /// Counts one finished pair with `status` ("success", "skipped" or "error") and reports progress
+    fn record(&self, status: &str) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.total += 1;
+        match status {
+            "success" => stats.success += 1,
+            "skipped" => stats.skipped += 1,
+            _ => stats.failed += 1,
+        }
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let elapsed = self.started.elapsed();
+        let seconds = elapsed.as_secs_f64();
+        let remaining = self.total.saturating_sub(stats.total);
+        // A dropped receiver only means nobody is watching
+        let _ = sender.send(BackfillProgress {
+            total: self.total,
+            completed: stats.total,
+            success: stats.success,
+            skipped: stats.skipped,
+            failed: stats.failed,
+            elapsed,
+            throughput: if seconds > 0.0 {
+                stats.total as f64 / seconds
+            } else {
+                0.0
+            },
+            eta: elapsed.mul_f64(remaining as f64 / stats.total as f64),
+        });
+    }
+}
+
 /// Trait for Binance data interfaces (spot, futures, etc.)
 ///
 /// Separates implementation-specific concerns (URLs, paths, processing, downloading) from
@@ -129,12 +273,18 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
     /// Must return DataFrame with "symbol" (String) and "date" (Date) columns
     async fn fetch_new_universe(&self) -> Result<DataFrame>;
 
+    /// Fetch only what is new since the cache: dates strictly after `after[symbol]` for cached symbols,
+    /// and every date of listed symbols not in `after` (newly listed).
+    /// Must return DataFrame with "symbol" (String) and "date" (Date) columns
+    async fn fetch_universe_after(&self, after: &HashMap<String, NaiveDate>) -> Result<DataFrame>;
+
     /// Download raw data file (implementation-specific download logic for flexibility)
     async fn download_raw(&self, symbol: &str, date: NaiveDate) -> Result<()>;
 
     /// Process downloaded zip to parquet (calls implementation-specific helpers)
     /// Returns number of rows processed
     fn process_download_to_parquet(
+        &self,
         zip_path: &Path,
         hive_path: &Path,
         symbol: &str,
@@ -152,18 +302,59 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
         // Fetch new universe if refresh requested or cache missing
         if refresh || !universe_cache_path.exists() {
             let mut universe_df = self.fetch_new_universe().await?;
+            self.write_universe_cache(&mut universe_df)?;
+        }
 
-            // Create parent directory if needed
-            if let Some(parent) = universe_cache_path.parent() {
-                fs::create_dir_all(parent)?;
-            }
+        Ok(())
+    }
 
-            // Write to cache
-            let mut file = fs::File::create(universe_cache_path)?;
-            ParquetWriter::new(&mut file).finish(&mut universe_df)?;
-            println!("Written new universe to {:?}", universe_cache_path)
+    /// Extend the cached universe instead of refetching it: only dates after each symbol's cached maximum
+    /// are queried (plus the full history of newly listed symbols), then merged into `universe.parquet`.
+    /// Symbols no longer listed keep their cached dates. Without a cache, fetches the full universe.
+    /// Returns the number of (symbol, date) pairs added
+    async fn update_universe_incremental(&self) -> Result<usize> {
+        let universe_cache_path = self.universe_cache_path();
+        if !universe_cache_path.exists() {
+            self.initialize_universe(true).await?;
+            return Ok(self.get_universe_df().await?.height());
         }
 
+        let path_str = universe_cache_path
+            .to_str()
+            .context("Invalid universe cache path")?
+            .to_string();
+        // Unfiltered: `date_filters` apply on read, the cache keeps everything listed
+        let cached_df = tokio::task::spawn_blocking(move || {
+            LazyFrame::scan_parquet(PlPath::new(&path_str), Default::default())?
+                .collect()
+                .context("Failed to read universe cache")
+        })
+        .await??;
+        let cached_height = cached_df.height();
+
+        let after = latest_universe_dates(&cached_df)?;
+        let fetched_df = self.fetch_universe_after(&after).await?;
+        let mut universe_df = merge_universe(cached_df, fetched_df)?;
+        // Saturating: duplicate rows in an old cache collapse in the merge
+        let added = universe_df.height().saturating_sub(cached_height);
+        self.write_universe_cache(&mut universe_df)?;
+        println!("Universe update added {} (symbol, date) pairs", added);
+        Ok(added)
+    }
+
+    /// Overwrite `universe.parquet` with `universe_df`
+    fn write_universe_cache(&self, universe_df: &mut DataFrame) -> Result<()> {
+        let universe_cache_path = self.universe_cache_path();
+
+        // Create parent directory if needed
+        if let Some(parent) = universe_cache_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write to cache
+        let mut file = fs::File::create(universe_cache_path)?;
+        ParquetWriter::new(&mut file).finish(universe_df)?;
+        println!("Written new universe to {:?}", universe_cache_path);
         Ok(())
     }
 
@@ -291,11 +482,10 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
             for i in 0..cached.height() {
                 if let (Some(symbol), Some(days_since_epoch)) =
                     (symbol_col.get(i), date_col.phys.get(i))
+                    && let Some(date) = polars_date_to_naive(days_since_epoch)
                 {
-                    if let Some(date) = polars_date_to_naive(days_since_epoch) {
-                        let hive_path = self.build_hive_path(symbol, date);
-                        set.insert(hive_path.to_string_lossy().to_string());
-                    }
+                    let hive_path = self.build_hive_path(symbol, date);
+                    set.insert(hive_path.to_string_lossy().to_string());
                 }
             }
             // Normalize schema: only select symbol and date columns to match new data
@@ -432,8 +622,9 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
         let raw_path = self.build_raw_path(symbol, date);
         let symbol_owned = symbol.to_string();
 
+        let this = Arc::clone(&self);
         tokio::task::spawn_blocking(move || {
-            Self::process_download_to_parquet(&raw_path, &hive_path, &symbol_owned, date)
+            this.process_download_to_parquet(&raw_path, &hive_path, &symbol_owned, date)
         })
         .await??;
 
@@ -484,10 +675,14 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
     /// Uses rayon for thread-level parallelism with per-thread tokio runtimes.
     /// Each thread calls ensure_data_ready to perform async I/O for downloads
     /// and blocking I/O for processing.
+    ///
+    /// `progress` receives a `BackfillProgress` after every finished pair (one event per pair, none if nothing
+    /// is missing), independently of the console output.
     async fn update_universe(
         self: Arc<Self>,
         _num_workers: usize,
         recompute_hive_symbol_date_pairs: bool,
+        progress: Option<mpsc::Sender<BackfillProgress>>,
     ) -> Result<UpdateStats> {
         let missing = self
             .nohive_symbol_date_pairs(recompute_hive_symbol_date_pairs)
@@ -502,10 +697,9 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
         for i in 0..missing.height() {
             if let (Some(symbol), Some(days_since_epoch)) =
                 (symbol_col.get(i), date_col.phys.get(i))
+                && let Some(date) = polars_date_to_naive(days_since_epoch)
             {
-                if let Some(date) = polars_date_to_naive(days_since_epoch) {
-                    pairs.push((symbol.to_string(), date));
-                }
+                pairs.push((symbol.to_string(), date));
             }
         }
 
@@ -518,6 +712,7 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
 
         // Parallel download+process: spawn_blocking avoids nested runtime issues
         let self_clone = Arc::clone(&self);
+        let tracker = ProgressTracker::new(pairs.len(), progress);
         let results: Vec<(String, NaiveDate, String, Option<String>)> =
             tokio::task::spawn_blocking(move || {
                 // Rayon parallelism: each worker thread gets its own tokio runtime
@@ -531,7 +726,7 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
                         let self_clone = Arc::clone(&self_clone);
                         let rt = tokio::runtime::Runtime::new().unwrap(); // Per-thread runtime
 
-                        rt.block_on(async move {
+                        let result = rt.block_on(async move {
                             let hive_path = self_clone.build_hive_path(&symbol, date);
 
                             // Skip if already processed (race condition safety)
@@ -551,7 +746,9 @@ pub trait CryptoDataInterface: Send + Sync + Sized + 'static {
                                     (symbol, date, "error".to_string(), Some(e.to_string()))
                                 }
                             }
-                        })
+                        });
+                        tracker.record(&result.2);
+                        result
                     })
                     .collect()
             })
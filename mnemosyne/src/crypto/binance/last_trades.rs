@@ -19,19 +19,21 @@
 /// ## Timestamp Normalization
 /// Binance changed format at 2025-01-01: pre-2025 milliseconds → post-2025 microseconds.
 /// All data normalized to microseconds for consistent Datetime type.
-use crate::crypto::CryptoDataInterface;
+use crate::crypto::{CryptoDataInterface, polars_compression};
 use anyhow::{Context, Result};
+use argus::ParquetCompressionConfig;
 use chrono::NaiveDate;
 use once_cell;
 use polars::prelude::*;
 use regex::Regex;
+use std::collections::HashMap;
 use std::fs;
 use std::io::Read;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::crypto::binance::s3_helpers::{create_s3_client, get_all_keys, get_all_trade_pairs};
+use crate::crypto::binance::s3_helpers::{create_s3_client, get_all_trade_pairs, get_keys_after};
 use crate::crypto::binance::{BINANCE_S3_BASE_URL, BinanceSchemaPipeline};
 use aws_sdk_s3::Client as S3Client;
 
@@ -148,14 +150,137 @@ fn build_download_url(
     )
 }
 
+/// Alternative header names seen across Binance archive vintages, normalized (lowercase, no `_`)
+const COLUMN_ALIASES: &[(&str, &str)] = &[
+    ("tradeid", "id"),
+    ("quantity", "qty"),
+    ("quotequantity", "quoteqty"),
+];
+
+fn normalize_column_name(name: &str) -> String {
+    let normalized: String = name
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .filter(|c| *c != '_')
+        .collect();
+    COLUMN_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == normalized)
+        .map(|(_, canonical)| canonical.to_string())
+        .unwrap_or(normalized)
+}
+
+/// Whether a CSV line is a header row: Binance data rows always start with a numeric trade id
+fn is_header_line(line: &str) -> bool {
+    line.split(',')
+        .next()
+        .is_some_and(|first| first.trim().parse::<i64>().is_err())
+}
+
+/// Parse a Binance trade CSV into the schema's normalized DataFrame (postprocessing included).
+///
+/// Strict: the first line must agree with `S::has_header()` and every row must match `S::get_schema()`.
+/// A header mismatch is an error rather than a silently dropped (or misparsed) first row.
+///
+/// Lenient: if strict parsing fails, re-read with the header detected from the first line, all columns as strings,
+/// then map columns to the schema by name (headers, see `COLUMN_ALIASES`) or by position (headerless), casting each.
+/// Extra columns are ignored; a missing schema column is still an error. Logs a warning on recovery.
+pub fn parse_trade_csv<S: BinanceSchemaPipeline>(csv_content: String, lenient: bool) -> Result<DataFrame> {
+    let strict = parse_trade_csv_strict::<S>(&csv_content);
+    match strict {
+        Ok(df) => Ok(df),
+        Err(e) if lenient => {
+            let df = parse_trade_csv_lenient::<S>(csv_content).with_context(|| {
+                format!("Lenient CSV parse failed after strict parse failed with: {:#}", e)
+            })?;
+            eprintln!(
+                "Warning: strict CSV parse failed ({:#}); recovered {} rows by header inference",
+                e,
+                df.height()
+            );
+            Ok(df)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn parse_trade_csv_strict<S: BinanceSchemaPipeline>(csv_content: &str) -> Result<DataFrame> {
+    let has_header = S::has_header();
+    let first_line = csv_content.lines().next().unwrap_or_default();
+    anyhow::ensure!(
+        is_header_line(first_line) == has_header,
+        "Expected {} but first line is {:?}",
+        if has_header { "a header row" } else { "no header row" },
+        first_line
+    );
+    let cursor = std::io::Cursor::new(csv_content.as_bytes());
+    let df = CsvReadOptions::default()
+        .with_has_header(has_header) // Spot: no header, Futures: has header
+        .with_schema(Some(Arc::new(S::get_schema()))) // Enforce strict typing
+        .into_reader_with_file_handle(cursor)
+        .finish()?;
+    S::postprocess_df(df)
+}
+
+fn parse_trade_csv_lenient<S: BinanceSchemaPipeline>(csv_content: String) -> Result<DataFrame> {
+    let has_header = is_header_line(csv_content.lines().next().unwrap_or_default());
+    let cursor = std::io::Cursor::new(csv_content);
+    let raw = CsvReadOptions::default()
+        .with_has_header(has_header)
+        .with_infer_schema_length(Some(0)) // Every column as String; typed below
+        .into_reader_with_file_handle(cursor)
+        .finish()?;
+
+    let schema = S::get_schema();
+    let raw_names: Vec<String> = raw
+        .get_column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    anyhow::ensure!(
+        has_header || raw_names.len() >= schema.len(),
+        "Headerless CSV has {} columns, schema needs {}",
+        raw_names.len(),
+        schema.len()
+    );
+
+    let columns = schema
+        .iter()
+        .enumerate()
+        .map(|(i, (name, dtype))| {
+            let source = if has_header {
+                let wanted = normalize_column_name(name);
+                raw_names
+                    .iter()
+                    .find(|raw_name| normalize_column_name(raw_name) == wanted)
+                    .with_context(|| format!("CSV header {:?} has no column for {}", raw_names, name))?
+            } else {
+                &raw_names[i]
+            };
+            let column = col(source.as_str());
+            let typed = if *dtype == DataType::Boolean {
+                column.str().to_lowercase().eq(lit("true"))
+            } else {
+                column.strict_cast(dtype.clone())
+            };
+            Ok(typed.alias(name.clone()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let df = raw.lazy().select(columns).collect()?;
+    S::postprocess_df(df)
+}
+
 /// Synchronous processing: unzip → read CSV → postprocess → write parquet → delete zip.
 /// This is the core processing function called by both sequential and parallel execution paths.
 ///
 /// Processing pipeline:
 /// 1. Extract CSV from zip archive
-/// 2. Parse CSV with schema-specific settings (header presence varies by market type)
+/// 2. Parse CSV with schema-specific settings (header presence varies by market type); `lenient` falls back
+///    to header inference instead of failing (see `parse_trade_csv`)
 /// 3. Postprocess DataFrame (schema-specific, e.g., timestamp normalization)
-/// 4. Write to Hive-partitioned parquet with LZ4 compression
+/// 4. Write to Hive-partitioned parquet with the configured compression
 /// 5. Clean up zip file to save disk space
 ///
 /// Returns: Number of rows processed
@@ -164,15 +289,14 @@ fn process_zip_to_parquet<S: BinanceSchemaPipeline>(
     hive_path: &Path,
     symbol: &str,
     date: NaiveDate,
+    compression: ParquetCompressionConfig,
+    lenient: bool,
 ) -> Result<usize> {
     // Ensure hive directory structure exists: date={date}/symbol={symbol}/
     if let Some(parent) = hive_path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    let schema = S::get_schema();
-    let has_header = S::has_header();
-
     // Phase 1: Extract CSV from zip archive
     let file = fs::File::open(zip_path)?;
     let mut archive = ::zip::ZipArchive::new(file)?;
@@ -195,23 +319,16 @@ fn process_zip_to_parquet<S: BinanceSchemaPipeline>(
     drop(csv_file);
     drop(archive);
 
-    // Phase 2: Parse CSV with schema-specific configuration
-    let cursor = std::io::Cursor::new(csv_content);
-    let mut df = CsvReadOptions::default()
-        .with_has_header(has_header) // Spot: no header, Futures: has header
-        .with_schema(Some(Arc::new(schema))) // Enforce strict typing
-        .into_reader_with_file_handle(cursor)
-        .finish()?;
-
-    // Phase 3: Schema-specific postprocessing (e.g., timestamp normalization)
-    df = S::postprocess_df(df)?;
+    // Phases 2-3: Parse CSV with schema-specific configuration, then postprocess (e.g., timestamp normalization)
+    let mut df = parse_trade_csv::<S>(csv_content, lenient)
+        .with_context(|| format!("Failed to parse {} {} trades", symbol, date))?;
 
     let num_rows = df.height();
 
-    // Phase 4: Write Hive-partitioned parquet with LZ4 compression (fast + reasonable compression)
+    // Phase 4: Write Hive-partitioned parquet
     let mut file = fs::File::create(hive_path)?;
     ParquetWriter::new(&mut file)
-        .with_compression(ParquetCompression::Lz4Raw)
+        .with_compression(polars_compression(compression)?)
         .finish(&mut df)?;
 
     println!("{} {} {}", symbol, date, num_rows);
@@ -222,6 +339,79 @@ fn process_zip_to_parquet<S: BinanceSchemaPipeline>(
     Ok(num_rows)
 }
 
+/// Resolve the per-peg hive root: `{hive_data_path}/peg_symbol={peg}`.
+/// If `hive_data_path` already ends in a `peg_symbol=` partition (e.g. from `DatasetType::hive_path`),
+/// it is used as-is, but must name the same peg. This guarantees USDT and USDC data never share a hive dir.
+pub fn peg_hive_root(hive_data_path: &Path, peg_symbol: &str) -> Result<PathBuf> {
+    anyhow::ensure!(
+        !peg_symbol.is_empty()
+            && peg_symbol
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()),
+        "Invalid peg_symbol '{}'. Must be non-empty uppercase alphanumeric (e.g. USDT, USDC)",
+        peg_symbol
+    );
+    let partition = format!("peg_symbol={}", peg_symbol);
+    let existing = hive_data_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix("peg_symbol="));
+    match existing {
+        Some(existing_peg) if existing_peg == peg_symbol => Ok(hive_data_path.to_path_buf()),
+        Some(existing_peg) => anyhow::bail!(
+            "Hive path {:?} is partitioned for peg {} but peg_symbol is {}",
+            hive_data_path,
+            existing_peg,
+            peg_symbol
+        ),
+        None => Ok(hive_data_path.join(partition)),
+    }
+}
+
+/// List (symbol, date) pairs present under `hive_root` by directory layout alone:
+/// every `date={YYYY-MM-DD}/symbol={SYMBOL}/data.parquet` that exists counts.
+/// Cheap (no parquet validation, no cache): use `hive_symbol_date_pairs` when files must be checked for corruption.
+/// Returns DataFrame: ["symbol": String, "date": Date]
+pub fn scan_hive_pairs(hive_root: &Path) -> Result<DataFrame> {
+    let mut symbols = Vec::new();
+    let mut dates = Vec::new();
+    if hive_root.exists() {
+        for date_entry in fs::read_dir(hive_root)? {
+            let date_dir = date_entry?.path();
+            let Some(date) = date_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("date="))
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            else {
+                continue;
+            };
+            if !date_dir.is_dir() {
+                continue;
+            }
+            for symbol_entry in fs::read_dir(&date_dir)? {
+                let symbol_dir = symbol_entry?.path();
+                let Some(symbol) = symbol_dir
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix("symbol="))
+                    .filter(|symbol| !symbol.is_empty())
+                else {
+                    continue;
+                };
+                if symbol_dir.join("data.parquet").is_file() {
+                    symbols.push(symbol.to_string());
+                    dates.push(date);
+                }
+            }
+        }
+    }
+    Ok(df!(
+        "symbol" => symbols,
+        "date" => dates,
+    )?)
+}
+
 // ============================================
 // Main BinanceTradeBook struct
 // ============================================
@@ -231,7 +421,8 @@ pub struct BinanceTradeBook<S: BinanceSchemaPipeline> {
     /// Files: `BTCUSDT/BTCUSDT-trades-2025-10-05.zip`
     raw_data_path: PathBuf,
 
-    /// Hive parquet directory. Example (Spot USDT): `/data/mnemosyne/binance/lossless/spot/last_trade/peg_symbol=USDT`
+    /// Hive parquet directory, always ending in the `peg_symbol={peg}` partition (see `peg_hive_root`).
+    /// Example (Spot USDT): `/data/mnemosyne/binance/lossless/spot/last_trade/peg_symbol=USDT`
     /// Files: `date=2025-10-05/symbol=BTC/data.parquet`
     hive_data_path: PathBuf,
 
@@ -261,6 +452,12 @@ pub struct BinanceTradeBook<S: BinanceSchemaPipeline> {
     /// Optional latest date (applied to universe after S3 fetch)
     latest_date: Option<NaiveDate>,
 
+    /// Codec for hive parquet files. Defaults to LZ4 (fast + reasonable compression)
+    compression: ParquetCompressionConfig,
+
+    /// Fall back to header inference when strict CSV parsing fails (see `parse_trade_csv`). Off by default
+    lenient_csv: bool,
+
     /// Shared S3 client
     s3_client: S3Client,
 
@@ -268,6 +465,7 @@ pub struct BinanceTradeBook<S: BinanceSchemaPipeline> {
 }
 
 impl<S: BinanceSchemaPipeline> BinanceTradeBook<S> {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         hive_data_path: PathBuf,
         raw_data_path: PathBuf,
@@ -277,14 +475,13 @@ impl<S: BinanceSchemaPipeline> BinanceTradeBook<S> {
         peg_symbol: String,
         earliest_date: Option<NaiveDate>,
         latest_date: Option<NaiveDate>,
+        compression: Option<ParquetCompressionConfig>,
     ) -> Result<Self> {
-        // Validate peg_symbol
-        // if peg_symbol != "USDT" && peg_symbol != "USDC" {
-        //     anyhow::bail!(
-        //         "Invalid peg_symbol '{}'. Must be either 'USDT' or 'USDC'",
-        //         peg_symbol
-        //     );
-        // }
+        // Validate peg_symbol and isolate its data under a peg partition
+        let hive_data_path = peg_hive_root(&hive_data_path, &peg_symbol)?;
+        let compression = compression
+            .unwrap_or(ParquetCompressionConfig::Lz4)
+            .validated()?;
 
         // Create directories if they don't exist
         fs::create_dir_all(&raw_data_path)?;
@@ -306,63 +503,64 @@ impl<S: BinanceSchemaPipeline> BinanceTradeBook<S> {
             peg_symbol,
             earliest_date,
             latest_date,
+            compression,
+            lenient_csv: false,
             s3_client,
             _schema: PhantomData,
         })
     }
 
-    fn build_download_url(&self, symbol: &str, date: NaiveDate) -> String {
-        build_download_url(
-            &self.data_base_url,
-            &self.binance_data_suffix,
-            symbol,
-            date,
-            &self.peg_symbol,
-        )
-    }
-}
-
-// Implement BinanceDataInterface trait
-impl<S: BinanceSchemaPipeline> CryptoDataInterface for BinanceTradeBook<S> {
-    fn build_hive_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
-        self.hive_data_path
-            .join(format!("date={}", date))
-            .join(format!("symbol={}", symbol))
-            .join("data.parquet")
+    pub fn peg_symbol(&self) -> &str {
+        &self.peg_symbol
     }
 
-    fn build_raw_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
-        self.raw_data_path
-            .join(format!("{}{}", symbol, self.peg_symbol))
-            .join(format!(
-                "{}{}-{}-{}.zip",
-                symbol,
-                self.peg_symbol,
-                self.binance_data_suffix,
-                date.format("%Y-%m-%d")
-            ))
+    pub fn compression(&self) -> ParquetCompressionConfig {
+        self.compression
     }
 
-    fn hive_data_path(&self) -> &Path {
-        &self.hive_data_path
+    /// Recover days whose CSV layout drifted (header added/dropped, columns reordered) instead of failing them.
+    pub fn with_lenient_csv(mut self, lenient: bool) -> Self {
+        self.lenient_csv = lenient;
+        self
     }
 
-    fn raw_data_path(&self) -> &Path {
-        &self.raw_data_path
+    pub fn lenient_csv(&self) -> bool {
+        self.lenient_csv
     }
 
-    fn universe_cache_path(&self) -> &Path {
-        &self.universe_cache_path
-    }
+    /// Backfill gaps: universe (with `earliest_date`/`latest_date` applied) minus pairs whose
+    /// `date={date}/symbol={symbol}/data.parquet` already exists under the hive root.
+    /// Unlike `nohive_symbol_date_pairs`, this neither validates nor caches, so it always reflects the
+    /// current directory state and a resumed backfill never re-downloads converted pairs.
+    /// Returns DataFrame: ["symbol": String, "date": Date], sorted by (date, symbol).
+    /// Error: Universe not initialized (see `initialize_universe`)
+    pub async fn missing_pairs(&self) -> Result<DataFrame> {
+        let universe_df = self.get_universe_df().await?;
+        let hive_root = self.hive_data_path.clone();
+
+        // Directory walk + anti-join: blocking I/O and CPU, keep off the async runtime
+        let missing = tokio::task::spawn_blocking(move || {
+            let hive_df = scan_hive_pairs(&hive_root)?;
+            universe_df
+                .lazy()
+                .select([col("symbol"), col("date")])
+                .join(
+                    hive_df.lazy(),
+                    [col("symbol"), col("date")],
+                    [col("symbol"), col("date")],
+                    JoinArgs::new(JoinType::Anti),
+                )
+                .sort(["date", "symbol"], SortMultipleOptions::default())
+                .collect()
+                .context("Failed to diff universe against hive")
+        })
+        .await??;
 
-    fn date_filters(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
-        (self.earliest_date, self.latest_date)
+        Ok(missing)
     }
 
-    /// Fetch complete (symbol, date) universe from S3. Expensive: queries all symbols & dates.
-    /// Cached to universe.parquet to avoid repeated API calls.
-    /// Returns DataFrame: ["symbol": String, "date": Date]
-    async fn fetch_new_universe(&self) -> Result<DataFrame> {
+    /// Base symbols (e.g. BTC, ETH) listed on S3 with a `{symbol}{peg_symbol}` pair for this market
+    async fn fetch_universe_symbols(&self) -> Result<Vec<String>> {
         // Phase 1: Discover trading pairs (e.g., BTCUSDT, ETHUSDT) for this market
         println!("Fetching new universe...waiting for trade pairs");
         let trade_pairs = get_all_trade_pairs(&self.s3_client, &self.base_url, &self.prefix).await?;
@@ -370,29 +568,48 @@ impl<S: BinanceSchemaPipeline> CryptoDataInterface for BinanceTradeBook<S> {
         // Filter to requested peg (e.g., keep BTCUSDT, discard BTCUSDC)
         let symbols: Vec<String> = trade_pairs
             .into_iter()
-            .filter(|x| x.ends_with(peg_suffix))
-            .map(|x| x.strip_suffix(peg_suffix).unwrap().to_string())
+            .filter_map(|x| x.strip_suffix(peg_suffix.as_str()).map(|s| s.to_string()))
+            .filter(|base| !base.is_empty())
             .collect();
+        println!("{} trade pairs quoted in {}", symbols.len(), peg_suffix);
         println!("Fetching new universe...fetched all trade pairs. Waiting");
+        Ok(symbols)
+    }
 
+    /// Available dates of each symbol, strictly after its paired date when given (all dates when `None`).
+    /// A symbol whose listing fails or times out contributes no rows.
+    /// Returns DataFrame: ["symbol": String, "date": Date]
+    async fn fetch_symbol_dates(
+        &self,
+        symbols: Vec<(String, Option<NaiveDate>)>,
+    ) -> Result<DataFrame> {
         // Phase 2: Fetch dates for each symbol with bounded concurrency (max 32 concurrent requests)
         // Unbounded concurrency causes connection pool exhaustion with 500+ symbols
         use futures::stream::{self, StreamExt};
 
         let results: Vec<Result<(String, Vec<String>), anyhow::Error>> = stream::iter(symbols)
-            .map(|symbol| {
+            .map(|(symbol, after)| {
                 let symbol_clone = symbol.clone();
                 let base_url = self.base_url.clone();
                 let peg_symbol = self.peg_symbol.clone();
                 let s3_client = self.s3_client.clone();
                 let prefix = format!("{}/{}{}", self.prefix, symbol, peg_symbol);
+                // Skip keys up to the known date's file, e.g. BTCUSDT-trades-2025-10-05.zip
+                let start_after = after.map(|date| {
+                    format!(
+                        "{}{}-{}-{}.zip",
+                        symbol, peg_symbol, self.binance_data_suffix, date
+                    )
+                });
                 println!("Fetching available dates for {}", symbol);
 
                 async move {
                     // Add per-symbol timeout (30 seconds) to prevent individual symbols from hanging
                     let timeout_duration = std::time::Duration::from_secs(30);
                     let result = tokio::time::timeout(timeout_duration, async {
-                        let paths = get_all_keys(&s3_client, &base_url, &prefix).await?;
+                        let paths =
+                            get_keys_after(&s3_client, &base_url, &prefix, start_after.as_deref())
+                                .await?;
                         let paths: Vec<String> = paths
                             .into_iter()
                             .filter(|x| !x.ends_with(".CHECKSUM")) // Exclude checksum files
@@ -408,6 +625,10 @@ impl<S: BinanceSchemaPipeline> CryptoDataInterface for BinanceTradeBook<S> {
                                     .and_then(|caps| caps.get(1))
                                     .map(|date_match| date_match.as_str().to_string())
                             })
+                            // ISO dates order as strings
+                            .filter(|date_str| {
+                                after.is_none_or(|after| *date_str > after.to_string())
+                            })
                             .collect();
                         Ok::<(String, Vec<String>), anyhow::Error>((symbol, date_strings))
                     })
@@ -466,6 +687,86 @@ impl<S: BinanceSchemaPipeline> CryptoDataInterface for BinanceTradeBook<S> {
         Ok(universe_df)
     }
 
+    fn build_download_url(&self, symbol: &str, date: NaiveDate) -> String {
+        build_download_url(
+            &self.data_base_url,
+            &self.binance_data_suffix,
+            symbol,
+            date,
+            &self.peg_symbol,
+        )
+    }
+}
+
+// Implement BinanceDataInterface trait
+impl<S: BinanceSchemaPipeline> CryptoDataInterface for BinanceTradeBook<S> {
+    fn build_hive_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
+        self.hive_data_path
+            .join(format!("date={}", date))
+            .join(format!("symbol={}", symbol))
+            .join("data.parquet")
+    }
+
+    fn build_raw_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
+        self.raw_data_path
+            .join(format!("{}{}", symbol, self.peg_symbol))
+            .join(format!(
+                "{}{}-{}-{}.zip",
+                symbol,
+                self.peg_symbol,
+                self.binance_data_suffix,
+                date.format("%Y-%m-%d")
+            ))
+    }
+
+    fn hive_data_path(&self) -> &Path {
+        &self.hive_data_path
+    }
+
+    fn raw_data_path(&self) -> &Path {
+        &self.raw_data_path
+    }
+
+    fn universe_cache_path(&self) -> &Path {
+        &self.universe_cache_path
+    }
+
+    fn date_filters(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        (self.earliest_date, self.latest_date)
+    }
+
+    /// Fetch complete (symbol, date) universe from S3. Expensive: queries all symbols & dates.
+    /// Cached to universe.parquet to avoid repeated API calls.
+    /// Returns DataFrame: ["symbol": String, "date": Date]
+    async fn fetch_new_universe(&self) -> Result<DataFrame> {
+        let symbols = self.fetch_universe_symbols().await?;
+        self.fetch_symbol_dates(symbols.into_iter().map(|symbol| (symbol, None)).collect())
+            .await
+    }
+
+    /// Lists each cached symbol's S3 keys only past its cached maximum date (`start_after`), so a daily
+    /// refresh costs one short listing per symbol. Newly listed symbols get a full listing.
+    /// Returns DataFrame: ["symbol": String, "date": Date]
+    async fn fetch_universe_after(&self, after: &HashMap<String, NaiveDate>) -> Result<DataFrame> {
+        let symbols = self.fetch_universe_symbols().await?;
+        let new_symbols = symbols.iter().filter(|s| !after.contains_key(*s)).count();
+        println!(
+            "Updating universe: {} listed symbols, {} not yet cached",
+            symbols.len(),
+            new_symbols
+        );
+        self.fetch_symbol_dates(
+            symbols
+                .into_iter()
+                .map(|symbol| {
+                    let latest = after.get(&symbol).copied();
+                    (symbol, latest)
+                })
+                .collect(),
+        )
+        .await
+    }
+
     async fn download_raw(&self, symbol: &str, date: NaiveDate) -> Result<()> {
         let raw_path = self.build_raw_path(symbol, date);
         if raw_path.exists() {
@@ -489,11 +790,19 @@ impl<S: BinanceSchemaPipeline> CryptoDataInterface for BinanceTradeBook<S> {
     }
 
     fn process_download_to_parquet(
+        &self,
         zip_path: &Path,
         hive_path: &Path,
         symbol: &str,
         date: NaiveDate,
     ) -> Result<usize> {
-        process_zip_to_parquet::<S>(zip_path, hive_path, symbol, date)
+        process_zip_to_parquet::<S>(
+            zip_path,
+            hive_path,
+            symbol,
+            date,
+            self.compression,
+            self.lenient_csv,
+        )
     }
 }
@@ -38,6 +38,18 @@ fn normalize_s3_prefix(prefix: &str) -> String {
 /// List all S3 object keys (files) under a prefix with automatic pagination.
 /// Returns full file paths, e.g.: "data/spot/daily/trades/BTCUSDT/BTCUSDT-trades-2025-10-05.zip"
 pub async fn get_all_keys(client: &Client, _base_url: &str, prefix: &str) -> Result<Vec<String>> {
+    get_keys_after(client, _base_url, prefix, None).await
+}
+
+/// Like `get_all_keys`, but only keys sorting after `{prefix}/{start_after}` (a file name under the prefix).
+/// S3 lists keys in lexicographic order, so for date-stamped names this skips every earlier date server-side.
+/// Example: start_after "BTCUSDT-trades-2025-10-05.zip" → "...-2025-10-05.zip.CHECKSUM", "...-2025-10-06.zip", ...
+pub async fn get_keys_after(
+    client: &Client,
+    _base_url: &str,
+    prefix: &str,
+    start_after: Option<&str>,
+) -> Result<Vec<String>> {
     let fixed_prefix = normalize_s3_prefix(prefix);
     let mut all_keys = Vec::new();
 
@@ -46,6 +58,7 @@ pub async fn get_all_keys(client: &Client, _base_url: &str, prefix: &str) -> Res
         .bucket(BUCKET)
         .prefix(&fixed_prefix)
         .delimiter("/")
+        .set_start_after(start_after.map(|name| format!("{}{}", fixed_prefix, name)))
         .into_paginator()
         .send();
 
@@ -78,14 +91,12 @@ pub async fn get_all_trade_pairs(client: &Client, _base_url: &str, prefix: &str)
     while let Some(result) = paginator.next().await {
         let output = result?;
         for cp in output.common_prefixes() {
-            if let Some(prefix_str) = cp.prefix() {
-                if let Some(pair) = prefix_str.strip_prefix(&fixed_prefix) {
-                    if let Some(pair) = pair.strip_suffix('/') {
-                        if !pair.is_empty() {
-                            trade_pairs.insert(pair.to_string());
-                        }
-                    }
-                }
+            if let Some(prefix_str) = cp.prefix()
+                && let Some(pair) = prefix_str.strip_prefix(&fixed_prefix)
+                && let Some(pair) = pair.strip_suffix('/')
+                && !pair.is_empty()
+            {
+                trade_pairs.insert(pair.to_string());
             }
         }
     }
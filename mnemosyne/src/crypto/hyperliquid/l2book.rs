@@ -1,15 +1,19 @@
+use crate::crypto::{par_map_chunked, polars_compression};
 use anyhow::{Context, Result};
+use argus::ParquetCompressionConfig;
+use argus::types::TradeSize;
 use chrono::NaiveDate;
 use lz4::Decoder;
 use polars::prelude::*;
-use rayon::prelude::*;
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
 /// Raw L2 book data (column vectors before DataFrame conversion)
 struct L2BookData {
+    symbol: Vec<String>,
     time: Vec<String>,
     timestamp: Vec<i64>,
     is_bid: Vec<bool>,
@@ -30,6 +34,7 @@ fn read_hyperliquid_l2book_lz4_raw(symbol_file: &Path, symbol: &str) -> Result<L
     let data_str = String::from_utf8(decompressed)?;
 
     // Column vectors
+    let mut symbol_vec = Vec::new();
     let mut time_vec = Vec::new();
     let mut timestamp_vec = Vec::new();
     let mut is_bid_vec = Vec::new();
@@ -74,20 +79,28 @@ fn read_hyperliquid_l2book_lz4_raw(symbol_file: &Path, symbol: &str) -> Result<L
         for (k, side_levels) in levels.iter().enumerate() {
             let is_bid = k == 0;
             let side_array = side_levels.as_array().context("Invalid level array")?;
-            let mut csize = 0.0;
+            // Exact decimal accumulation: summing f64 sizes drifts (0.1 + 0.2 != 0.3)
+            let mut csize: Option<TradeSize> = None;
 
             for (j, level) in side_array.iter().enumerate() {
                 let px = level["px"].as_str().context("Missing px")?.parse::<f64>()?;
-                let sz = level["sz"].as_str().context("Missing sz")?.parse::<f64>()?;
+                let sz = TradeSize::from_string(
+                    level["sz"].as_str().context("Missing sz")?.to_string(),
+                )?;
                 let n = level["n"].as_i64().context("Missing n")? as i16;
 
-                csize += sz;
+                let total = match csize.take() {
+                    Some(total) => (total + sz)?,
+                    None => sz,
+                };
 
+                symbol_vec.push(symbol.to_string());
                 time_vec.push(time_str.to_string());
                 timestamp_vec.push(timestamp);
                 is_bid_vec.push(is_bid);
                 price_vec.push(px);
-                csize_vec.push(csize);
+                csize_vec.push(total.to_f64());
+                csize = Some(total);
                 depth_vec.push(j as i16);
                 num_orders_vec.push(n);
             }
@@ -95,6 +108,7 @@ fn read_hyperliquid_l2book_lz4_raw(symbol_file: &Path, symbol: &str) -> Result<L
     }
 
     Ok(L2BookData {
+        symbol: symbol_vec,
         time: time_vec,
         timestamp: timestamp_vec,
         is_bid: is_bid_vec,
@@ -105,22 +119,56 @@ fn read_hyperliquid_l2book_lz4_raw(symbol_file: &Path, symbol: &str) -> Result<L
     })
 }
 
-/// Read single Hyperliquid L2 book LZ4 file into DataFrame (public API).
-pub fn read_hyperliquid_l2book_lz4(symbol_file: &Path, symbol: &str) -> Result<DataFrame> {
-    let data = read_hyperliquid_l2book_lz4_raw(symbol_file, symbol)?;
-
-    let df = df!(
-        "time" => data.time,
-        "timestamp" => data.timestamp,
-        "is_bid" => data.is_bid,
-        "price" => data.price,
-        "csize" => data.csize,
-        "depth" => data.depth,
-        "num_orders_at_level" => data.num_orders_at_level,
-    )?;
+impl L2BookData {
+    fn empty() -> Self {
+        Self {
+            symbol: Vec::new(),
+            time: Vec::new(),
+            timestamp: Vec::new(),
+            is_bid: Vec::new(),
+            price: Vec::new(),
+            csize: Vec::new(),
+            depth: Vec::new(),
+            num_orders_at_level: Vec::new(),
+        }
+    }
+
+    fn extend(&mut self, data: L2BookData) {
+        self.symbol.extend(data.symbol);
+        self.time.extend(data.time);
+        self.timestamp.extend(data.timestamp);
+        self.is_bid.extend(data.is_bid);
+        self.price.extend(data.price);
+        self.csize.extend(data.csize);
+        self.depth.extend(data.depth);
+        self.num_orders_at_level.extend(data.num_orders_at_level);
+    }
+
+    /// Raw columns as parsed, `time` still a string
+    fn into_raw_dataframe(self) -> Result<DataFrame> {
+        Ok(df!(
+            "symbol" => self.symbol,
+            "time" => self.time,
+            "timestamp" => self.timestamp,
+            "is_bid" => self.is_bid,
+            "price" => self.price,
+            "csize" => self.csize,
+            "depth" => self.depth,
+            "num_orders_at_level" => self.num_orders_at_level,
+        )?)
+    }
+
+    /// Typed output frame: `time` parsed to microsecond datetimes, exchange `timestamp` as `created_time`
+    fn into_dataframe(self) -> Result<DataFrame> {
+        Self::typed(self.into_raw_dataframe()?.lazy())
+            .collect()
+            .context("Failed to transform L2 book data")
+    }
 
-    df.lazy()
-        .select([
+    /// Typed output columns of `into_dataframe`, as a lazy projection of the raw columns
+    fn typed(raw: LazyFrame) -> LazyFrame {
+        raw.select([
+            col("symbol"),
             col("time").str().to_datetime(
                 Some(TimeUnit::Microseconds),
                 None,
@@ -137,18 +185,51 @@ pub fn read_hyperliquid_l2book_lz4(symbol_file: &Path, symbol: &str) -> Result<D
                 .cast(DataType::Datetime(TimeUnit::Microseconds, None))
                 .alias("created_time"),
         ])
+    }
+}
+
+/// Read single Hyperliquid L2 book LZ4 file into DataFrame (public API).
+pub fn read_hyperliquid_l2book_lz4(symbol_file: &Path, symbol: &str) -> Result<DataFrame> {
+    read_hyperliquid_l2book_lz4_raw(symbol_file, symbol)?.into_dataframe()
+}
+
+/// All `(file, symbol)` L2 book pairs for `date` across its 24 hour directories; empty if there are none.
+/// Layout: `{raw_data_path}/{YYYYMMDD}/{hour}/l2Book/{SYMBOL}.lz4`
+fn l2book_files(raw_data_path: &Path, date: NaiveDate) -> Vec<(PathBuf, String)> {
+    let date_str = date.format("%Y%m%d").to_string();
+    (0..24)
+        .flat_map(|hour| {
+            let hour_path = raw_data_path
+                .join(&date_str)
+                .join(hour.to_string())
+                .join("l2Book");
+            fs::read_dir(&hour_path)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if path.is_file() && path.extension()? == "lz4" {
+                        let symbol = path.file_stem()?.to_str()?.to_string();
+                        Some((path, symbol))
+                    } else {
+                        None
+                    }
+                })
+        })
         .collect()
-        .context("Failed to transform L2 book data")
 }
 
 /// Read all Hyperliquid L2 book files for a full date with parallel processing.
 /// Replicates Python notebook logic from `dev_hyperliquid_market.ipynb`.
 ///
 /// Parallelizes over all (hour, symbol) file pairs using rayon for efficient I/O.
+/// Raw rows (string timestamps included) are held for at most `max_concurrent_files` files at a time:
+/// each batch is converted to a typed DataFrame before the next is read, and the batches are stacked at the end.
 ///
 /// # Arguments
 /// * `raw_data_path` - Base path (e.g., "/bigdata/mnemosyne/hyperliquid/raw/futures/market_data")
 /// * `date` - Date to read
+/// * `max_concurrent_files` - Files read and held raw at once; `None` reads the whole day in one batch (fastest, highest peak memory)
 ///
 /// # Example
 /// ```no_run
@@ -159,33 +240,17 @@ pub fn read_hyperliquid_l2book_lz4(symbol_file: &Path, symbol: &str) -> Result<D
 /// let date = NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
 /// let df = read_hyperliquid_l2book_bydate(
 ///     Path::new("/bigdata/mnemosyne/hyperliquid/raw/futures/market_data"),
-///     date
+///     date,
+///     Some(64),
 /// )?;
+/// # Ok::<(), anyhow::Error>(())
 /// ```
-pub fn read_hyperliquid_l2book_bydate(raw_data_path: &Path, date: NaiveDate) -> Result<DataFrame> {
-    let date_str = date.format("%Y%m%d").to_string();
-
-    // Collect all (path, symbol) pairs across all 24 hours
-    let file_paths: Vec<(PathBuf, String)> = (0..24)
-        .flat_map(|hour| {
-            let hour_path = raw_data_path
-                .join(&date_str)
-                .join(hour.to_string())
-                .join("l2Book");
-            fs::read_dir(&hour_path)
-                .into_iter()
-                .flatten()
-                .filter_map(|entry| {
-                    let path = entry.ok()?.path();
-                    if path.is_file() && path.extension()? == "lz4" {
-                        let symbol = path.file_stem()?.to_str()?.to_string();
-                        Some((path, symbol))
-                    } else {
-                        None
-                    }
-                })
-        })
-        .collect();
+pub fn read_hyperliquid_l2book_bydate(
+    raw_data_path: &Path,
+    date: NaiveDate,
+    max_concurrent_files: Option<usize>,
+) -> Result<DataFrame> {
+    let file_paths = l2book_files(raw_data_path, date);
 
     anyhow::ensure!(
         !file_paths.is_empty(),
@@ -193,73 +258,171 @@ pub fn read_hyperliquid_l2book_bydate(raw_data_path: &Path, date: NaiveDate) ->
         date
     );
 
-    // Parallel read into raw vectors (memory-efficient: no intermediate DataFrames)
-    let all_data: Vec<L2BookData> = file_paths
-        .par_iter()
-        .filter_map(|(path, symbol)| {
-            read_hyperliquid_l2book_lz4_raw(path, symbol).ok()
-        })
-        .collect();
+    // Each batch: parallel read into raw vectors, then one typed DataFrame per batch
+    let mut files_read = 0;
+    let mut batches: Vec<DataFrame> = Vec::new();
+    par_map_chunked(
+        &file_paths,
+        max_concurrent_files,
+        |(path, symbol)| read_hyperliquid_l2book_lz4_raw(path, symbol).ok(),
+        |batch| {
+            let mut combined = L2BookData::empty();
+            for data in batch.into_iter().flatten() {
+                files_read += 1;
+                combined.extend(data);
+            }
+            batches.push(combined.into_dataframe()?);
+            Ok(())
+        },
+    )
+    .context("Failed to transform aggregated L2 book data")?;
 
     anyhow::ensure!(
-        !all_data.is_empty(),
+        files_read > 0,
         "Failed to read any L2 book files for date {}",
         date
     );
 
-    // Aggregate all vectors into single dataset
-    let mut combined = L2BookData {
-        time: Vec::new(),
-        timestamp: Vec::new(),
-        is_bid: Vec::new(),
-        price: Vec::new(),
-        csize: Vec::new(),
-        depth: Vec::new(),
-        num_orders_at_level: Vec::new(),
-    };
+    concat(
+        batches.into_iter().map(|df| df.lazy()).collect::<Vec<_>>(),
+        UnionArgs::default(),
+    )?
+    .collect()
+    .context("Failed to stack L2 book batches")
+}
 
-    for data in all_data {
-        combined.time.extend(data.time);
-        combined.timestamp.extend(data.timestamp);
-        combined.is_bid.extend(data.is_bid);
-        combined.price.extend(data.price);
-        combined.csize.extend(data.csize);
-        combined.depth.extend(data.depth);
-        combined.num_orders_at_level.extend(data.num_orders_at_level);
-    }
+/// Lazy counterpart of `read_hyperliquid_l2book_bydate`: decodes every L2 book file of `date` into raw columns,
+/// but leaves the typed conversion and everything chained onto the result lazy. The source is LZ4 JSON, so the
+/// files are still parsed up front; filters (e.g. one symbol or a time window) are pushed down below the typed
+/// projection, so only the matching rows get their timestamps parsed and materialized on `collect()`.
+///
+/// # Example
+/// ```no_run
+/// use chrono::NaiveDate;
+/// use polars::prelude::*;
+/// use std::path::Path;
+/// use mnemosyne::crypto::hyperliquid::l2book::scan_hyperliquid_l2book_bydate;
+///
+/// let date = NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
+/// let btc = scan_hyperliquid_l2book_bydate(
+///     Path::new("/bigdata/mnemosyne/hyperliquid/raw/futures/market_data"),
+///     date,
+/// )?
+/// .filter(col("symbol").eq(lit("BTC")))
+/// .collect()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn scan_hyperliquid_l2book_bydate(raw_data_path: &Path, date: NaiveDate) -> Result<LazyFrame> {
+    let file_paths = l2book_files(raw_data_path, date);
 
-    // Build single DataFrame from combined data
-    let df = df!(
-        "time" => combined.time,
-        "timestamp" => combined.timestamp,
-        "is_bid" => combined.is_bid,
-        "price" => combined.price,
-        "csize" => combined.csize,
-        "depth" => combined.depth,
-        "num_orders_at_level" => combined.num_orders_at_level,
+    anyhow::ensure!(
+        !file_paths.is_empty(),
+        "No L2 book files found for date {}",
+        date
+    );
+
+    let mut files_read = 0;
+    let mut combined = L2BookData::empty();
+    par_map_chunked(
+        &file_paths,
+        None,
+        |(path, symbol)| read_hyperliquid_l2book_lz4_raw(path, symbol).ok(),
+        |batch| {
+            for data in batch.into_iter().flatten() {
+                files_read += 1;
+                combined.extend(data);
+            }
+            Ok(())
+        },
     )?;
 
-    // Apply transformations once on full dataset
-    df.lazy()
-        .select([
-            col("time").str().to_datetime(
-                Some(TimeUnit::Microseconds),
-                None,
-                StrptimeOptions::default(),
-                lit("raise"),
-            ),
-            col("price"),
-            col("csize"),
-            col("depth").cast(DataType::Int16),
-            col("num_orders_at_level").cast(DataType::Int16),
-            col("is_bid"),
-            col("timestamp")
-                .cast(DataType::Datetime(TimeUnit::Milliseconds, None))
-                .cast(DataType::Datetime(TimeUnit::Microseconds, None))
-                .alias("created_time"),
-        ])
-        .collect()
-        .context("Failed to transform aggregated L2 book data")
+    anyhow::ensure!(
+        files_read > 0,
+        "Failed to read any L2 book files for date {}",
+        date
+    );
+
+    Ok(L2BookData::typed(combined.into_raw_dataframe()?.lazy()))
+}
+
+/// Read one date with `read_hyperliquid_l2book_bydate` and write it to `save_path` (parents created automatically).
+/// Returns the number of rows written.
+fn write_hyperliquid_l2book_bydate(
+    raw_data_path: &Path,
+    date: NaiveDate,
+    save_path: &Path,
+    compression: ParquetCompression,
+    max_concurrent_files: Option<usize>,
+) -> Result<usize> {
+    let mut df = read_hyperliquid_l2book_bydate(raw_data_path, date, max_concurrent_files)?;
+    if let Some(parent) = save_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = fs::File::create(save_path)
+        .with_context(|| format!("Failed to create {}", save_path.display()))?;
+    ParquetWriter::new(&mut file)
+        .with_compression(compression)
+        .finish(&mut df)
+        .with_context(|| format!("Failed to write {}", save_path.display()))?;
+    Ok(df.height())
+}
+
+/// Backfill every date in `[start, end]` (inclusive) into a hive layout: `{out_dir}/date={YYYY-MM-DD}/data.parquet`.
+/// Dates without raw files are skipped; a date that fails to read or write is logged and the range continues.
+/// Returns rows written per successfully written date, in date order.
+/// `max_concurrent_files` bounds each date's read as in `read_hyperliquid_l2book_bydate`.
+/// Error: `start > end`
+pub fn read_hyperliquid_l2book_range_to(
+    raw_data_path: &Path,
+    start: NaiveDate,
+    end: NaiveDate,
+    out_dir: &Path,
+    compression: ParquetCompression,
+    max_concurrent_files: Option<usize>,
+) -> Result<BTreeMap<NaiveDate, usize>> {
+    anyhow::ensure!(
+        start <= end,
+        "Invalid date range: start {} is after end {}",
+        start,
+        end
+    );
+    let mut written = BTreeMap::new();
+    let mut skipped = Vec::new();
+    let mut failed = Vec::new();
+    for date in start.iter_days().take_while(|date| *date <= end) {
+        if l2book_files(raw_data_path, date).is_empty() {
+            skipped.push(date);
+            continue;
+        }
+        let save_path = out_dir.join(format!("date={}", date)).join("data.parquet");
+        match write_hyperliquid_l2book_bydate(
+            raw_data_path,
+            date,
+            &save_path,
+            compression,
+            max_concurrent_files,
+        ) {
+            Ok(rows) => {
+                written.insert(date, rows);
+            }
+            Err(e) => {
+                eprintln!("Error: L2 book backfill failed for {}: {:#}", date, e);
+                failed.push(date);
+            }
+        }
+    }
+    eprintln!(
+        "L2 book backfill [{}, {}]: {} written, {} skipped (no files): {:?}, {} failed: {:?}",
+        start,
+        end,
+        written.len(),
+        skipped.len(),
+        skipped,
+        failed.len(),
+        failed
+    );
+    Ok(written)
 }
 
 // PyO3 bindings
@@ -271,6 +434,8 @@ use pyo3::prelude::*;
 /// * `raw_data_path` - Base path to raw data directory
 /// * `date_str` - Date in YYYY-MM-DD format (e.g., "2025-09-30")
 /// * `save_path` - Output Parquet file path (parents created automatically)
+/// * `compression` - Optional codec, e.g. "zstd:5", "lz4", "brotli:3", "snappy", "uncompressed" (default "brotli:3")
+/// * `max_concurrent_files` - Optional cap on raw files held in memory at once (default: all files of the date)
 ///
 /// # Example (Python)
 /// ```python
@@ -282,11 +447,22 @@ use pyo3::prelude::*;
 /// )
 /// ```
 #[pyfunction]
+#[pyo3(signature = (raw_data_path, date_str, save_path, compression=None, max_concurrent_files=None))]
 pub fn py_read_hyperliquid_l2book_bydate_to(
     raw_data_path: &str,
     date_str: &str,
     save_path: &str,
+    compression: Option<&str>,
+    max_concurrent_files: Option<usize>,
 ) -> PyResult<()> {
+    // Resolve the codec before doing any work so bad levels fail fast
+    let compression = match compression {
+        Some(s) => ParquetCompressionConfig::from_str(s),
+        None => ParquetCompressionConfig::brotli(3),
+    }
+    .and_then(polars_compression)
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
     let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
             "Invalid date format '{}': {}",
@@ -294,8 +470,9 @@ pub fn py_read_hyperliquid_l2book_bydate_to(
         ))
     })?;
 
-    let mut df = read_hyperliquid_l2book_bydate(Path::new(raw_data_path), date)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    let mut df =
+        read_hyperliquid_l2book_bydate(Path::new(raw_data_path), date, max_concurrent_files)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
 
     // Create parent directories
     let save_path_buf = PathBuf::from(save_path);
@@ -304,17 +481,143 @@ pub fn py_read_hyperliquid_l2book_bydate_to(
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
     }
 
-    // Write with Brotli compression (level 3, matching crypto.rs pattern)
     let mut file = fs::File::create(&save_path_buf)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
 
     ParquetWriter::new(&mut file)
-        .with_compression(ParquetCompression::Brotli(Some(
-            BrotliLevel::try_new(3)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
-        )))
+        .with_compression(compression)
         .finish(&mut df)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
 
     Ok(())
 }
+
+/// Lazily scan one date of Hyperliquid L2 books from Python, returning a `polars.LazyFrame` to chain filters onto.
+///
+/// Python polars cannot read the LZ4 JSON source, so the date is parsed once (via
+/// `scan_hyperliquid_l2book_bydate`) into a parquet file at `cache_path`, which is then returned as
+/// `polars.scan_parquet(cache_path)`: filters and projections are pushed down into that scan.
+///
+/// # Arguments
+/// * `raw_data_path` - Base path to raw data directory
+/// * `date_str` - Date in YYYY-MM-DD format (e.g., "2025-09-30")
+/// * `cache_path` - Parquet file to scan from (parents created automatically);
+///   default `{tmp}/mnemosyne_l2book_{YYYYMMDD}.parquet`, overwritten on every call
+///
+/// # Example (Python)
+/// ```python
+/// import polars as pl
+/// import mnemosyne as ms
+/// btc = (
+///     ms.scan_hyperliquid_l2book_bydate("/bigdata/mnemosyne/hyperliquid/raw/futures/market_data", "2025-09-30")
+///     .filter(pl.col("symbol") == "BTC")
+///     .collect()
+/// )
+/// ```
+#[pyfunction]
+#[pyo3(
+    name = "scan_hyperliquid_l2book_bydate",
+    signature = (raw_data_path, date_str, cache_path=None)
+)]
+pub fn py_scan_hyperliquid_l2book_bydate<'py>(
+    py: Python<'py>,
+    raw_data_path: &str,
+    date_str: &str,
+    cache_path: Option<&str>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid date format '{}': {}",
+            date_str, e
+        ))
+    })?;
+    let cache_path = cache_path.map(PathBuf::from).unwrap_or_else(|| {
+        std::env::temp_dir().join(format!(
+            "mnemosyne_l2book_{}.parquet",
+            date.format("%Y%m%d")
+        ))
+    });
+
+    let mut df = scan_hyperliquid_l2book_bydate(Path::new(raw_data_path), date)
+        .and_then(|lf| lf.collect().context("Failed to transform L2 book data"))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+    }
+    let mut file = fs::File::create(&cache_path)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+    ParquetWriter::new(&mut file)
+        .with_compression(ParquetCompression::Lz4Raw)
+        .finish(&mut df)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    // Build the frame with Python polars, so the result is a native polars object
+    py.import("polars")?
+        .call_method1("scan_parquet", (cache_path.to_string_lossy().to_string(),))
+}
+
+/// Backfill all Hyperliquid L2 book dates in `[start_date, end_date]` (inclusive, YYYY-MM-DD) to
+/// `{out_dir}/date={YYYY-MM-DD}/data.parquet` (Python binding).
+/// Dates with no raw files are skipped; a failing date is logged and the rest of the range still runs.
+///
+/// # Arguments
+/// * `raw_data_path` - Base path to raw data directory
+/// * `start_date`, `end_date` - Inclusive range in YYYY-MM-DD format
+/// * `out_dir` - Root of the output hive (created automatically)
+/// * `compression` - Optional codec, as in `read_hyperliquid_l2book_bydate_to` (default "brotli:3")
+/// * `max_concurrent_files` - Optional cap on raw files held in memory at once, per date
+///
+/// Returns `{date: rows_written}` for every date that was written.
+///
+/// # Example (Python)
+/// ```python
+/// import mnemosyne.mnemosyne as ms
+/// rows = ms.py_read_hyperliquid_l2book_range_to(
+///     "/bigdata/mnemosyne/hyperliquid/raw/futures/market_data",
+///     "2025-09-01",
+///     "2025-09-30",
+///     "/bigdata/mnemosyne/hyperliquid/l2book"
+/// )
+/// ```
+#[pyfunction]
+#[pyo3(signature = (raw_data_path, start_date, end_date, out_dir, compression=None, max_concurrent_files=None))]
+pub fn py_read_hyperliquid_l2book_range_to(
+    raw_data_path: &str,
+    start_date: &str,
+    end_date: &str,
+    out_dir: &str,
+    compression: Option<&str>,
+    max_concurrent_files: Option<usize>,
+) -> PyResult<BTreeMap<String, usize>> {
+    let compression = match compression {
+        Some(s) => ParquetCompressionConfig::from_str(s),
+        None => ParquetCompressionConfig::brotli(3),
+    }
+    .and_then(polars_compression)
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+    let parse = |date_str: &str| {
+        NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid date format '{}': {}",
+                date_str, e
+            ))
+        })
+    };
+    let (start, end) = (parse(start_date)?, parse(end_date)?);
+
+    let written = read_hyperliquid_l2book_range_to(
+        Path::new(raw_data_path),
+        start,
+        end,
+        Path::new(out_dir),
+        compression,
+        max_concurrent_files,
+    )
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    Ok(written
+        .into_iter()
+        .map(|(date, rows)| (date.to_string(), rows))
+        .collect())
+}
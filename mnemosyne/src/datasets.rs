@@ -1,12 +1,29 @@
+use crate::{hive, schema};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use polars::prelude::*;
 use pyo3::prelude::*;
+use std::path::PathBuf;
+
+/// Widest range, in days, `py_load` collects into a DataFrame when `lazy` is not given; longer ranges stay lazy
+pub const LOAD_EAGER_MAX_DAYS: i64 = 31;
 
 pub fn lib_cache_path() -> String {
     String::from("/bigdata/mnemosyne/cache")
 }
 
+/// Default root of the lossless and raw trees: `{root}/binance`, `{root}/hyperliquid`
+pub fn data_root() -> String {
+    String::from("/bigdata/mnemosyne")
+}
+
 pub fn binance_data_path() -> String {
     // Where binance grid & lossless data are stored
-    String::from("/bigdata/mnemosyne/binance")
+    binance_data_path_in(&data_root())
+}
+
+fn binance_data_path_in(data_root: &str) -> String {
+    format!("{}/binance", data_root)
 }
 
 pub fn binance_study_data_path() -> String {
@@ -15,7 +32,11 @@ pub fn binance_study_data_path() -> String {
 }
 
 pub fn hyperliquid_data_path() -> String {
-    String::from("/bigdata/mnemosyne/hyperliquid")
+    hyperliquid_data_path_in(&data_root())
+}
+
+fn hyperliquid_data_path_in(data_root: &str) -> String {
+    format!("{}/hyperliquid", data_root)
 }
 
 pub fn hyperliquid_study_data_path() -> String {
@@ -36,23 +57,7 @@ pub enum DatasetType {
 impl DatasetType {
     pub fn hive_path(&self, peg_symbol: &str) -> String {
         // Where "lossless" processed data is stored
-        // assert!((peg_symbol == "USDT") || (peg_symbol == "USDC"));
-        match self {
-            Self::BinanceSpotTrades => format!(
-                "{}/lossless/spot/last_trade/peg_symbol={}",
-                binance_data_path(),
-                peg_symbol
-            ),
-            Self::BinanceUmPerpTrades => format!(
-                "{}/lossless/futures/um/last_trade/peg_symbol={}",
-                binance_data_path(),
-                peg_symbol
-            ),
-            Self::HyperliquidPerpL2 => format!("{}/lossless/l2", hyperliquid_data_path()),
-            Self::HyperliquidPerpTrades => {
-                format!("{}/lossless/last_trade", hyperliquid_data_path())
-            }
-        }
+        self.hive_path_in(&data_root(), peg_symbol)
     }
 
     pub fn raw_data_path(&self, peg_symbol: &str) -> String {
@@ -110,3 +115,209 @@ impl DatasetType {
         format!("{:?}", self)
     }
 }
+
+impl DatasetType {
+    pub const ALL: [DatasetType; 4] = [
+        Self::BinanceSpotTrades,
+        Self::BinanceUmPerpTrades,
+        Self::HyperliquidPerpL2,
+        Self::HyperliquidPerpTrades,
+    ];
+
+    /// Parses a variant name, e.g. `"BinanceSpotTrades"` (what both the Rust and Python enums print as).
+    /// Error: unknown name, listing the valid ones
+    pub fn from_name(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|dataset_type| format!("{:?}", dataset_type) == name)
+            .with_context(|| {
+                format!(
+                    "Unknown dataset type '{}', expected one of {:?}",
+                    name,
+                    Self::ALL
+                )
+            })
+    }
+
+    /// `hive_path` with `data_root` in place of the default `data_root()`
+    pub fn hive_path_in(&self, data_root: &str, peg_symbol: &str) -> String {
+        // assert!((peg_symbol == "USDT") || (peg_symbol == "USDC"));
+        match self {
+            Self::BinanceSpotTrades => format!(
+                "{}/lossless/spot/last_trade/peg_symbol={}",
+                binance_data_path_in(data_root),
+                peg_symbol
+            ),
+            Self::BinanceUmPerpTrades => format!(
+                "{}/lossless/futures/um/last_trade/peg_symbol={}",
+                binance_data_path_in(data_root),
+                peg_symbol
+            ),
+            Self::HyperliquidPerpL2 => {
+                format!("{}/lossless/l2", hyperliquid_data_path_in(data_root))
+            }
+            Self::HyperliquidPerpTrades => {
+                format!(
+                    "{}/lossless/last_trade",
+                    hyperliquid_data_path_in(data_root)
+                )
+            }
+        }
+    }
+
+    /// Whether the lossless hive splits each date into `symbol={SYMBOL}` partitions.
+    /// Hyperliquid L2 books are written one file per date holding every symbol, told apart by a `symbol` column.
+    pub fn symbol_partitioned(&self) -> bool {
+        !matches!(self, Self::HyperliquidPerpL2)
+    }
+
+    /// Files to scan for `symbol` over `[start, end]` (inclusive) in the lossless hive under `data_root`,
+    /// and whether rows of other symbols must still be filtered out on the `symbol` column.
+    /// Every file is checked against `schema::schema_for(self)` first.
+    /// Error: symbol not in the hive (listing the available ones), no data in the range (see `hive::range_paths`),
+    /// or a file whose columns differ from the registered schema
+    pub fn load_paths(
+        &self,
+        data_root: &str,
+        peg_symbol: &str,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<(Vec<PathBuf>, bool)> {
+        let hive_path = PathBuf::from(self.hive_path_in(data_root, peg_symbol));
+        if self.symbol_partitioned() {
+            let paths = hive::range_paths(&hive_path, symbol, start, end)?;
+            self.validate_schemas(&paths)?;
+            return Ok((paths, false));
+        }
+        let paths = hive::date_range_paths(&hive_path, start, end)?;
+        self.validate_schemas(&paths)?;
+        // Only the symbol column is read
+        let symbols = hive::scan_paths(paths.clone())?
+            .select([col("symbol")
+                .cast(DataType::String)
+                .unique()
+                .sort(Default::default())])
+            .collect()
+            .with_context(|| format!("Failed to read symbols under {}", hive_path.display()))?;
+        let symbols: Vec<String> = symbols
+            .column("symbol")?
+            .str()?
+            .into_no_null_iter()
+            .map(str::to_string)
+            .collect();
+        anyhow::ensure!(
+            symbols.iter().any(|s| s == symbol),
+            "Symbol {} not found under {} between {} and {}; {}",
+            symbol,
+            hive_path.display(),
+            start,
+            end,
+            hive::available_symbols_hint(&symbols)
+        );
+        Ok((paths, true))
+    }
+
+    /// Error: the first of `paths` whose columns differ from `schema::schema_for(self)`
+    fn validate_schemas(&self, paths: &[PathBuf]) -> Result<()> {
+        for path in paths {
+            schema::validate_parquet_schema(self, path)?;
+        }
+        Ok(())
+    }
+
+    /// Lazily scans `symbol` over `[start, end]` (inclusive) in this dataset's lossless hive under `data_root`.
+    /// Error: see `load_paths`
+    pub fn load(
+        &self,
+        data_root: &str,
+        peg_symbol: &str,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<LazyFrame> {
+        let (paths, filter_symbol) = self.load_paths(data_root, peg_symbol, symbol, start, end)?;
+        let lf = hive::scan_paths(paths)?;
+        Ok(if filter_symbol {
+            lf.filter(col("symbol").cast(DataType::String).eq(lit(symbol)))
+        } else {
+            lf
+        })
+    }
+}
+
+/// Load `symbol` over `[start_date, end_date]` (inclusive, YYYY-MM-DD) from `dataset_type`'s lossless hive.
+///
+/// # Arguments
+/// * `dataset_type` - `DatasetType` (Python or Rust enum) or its name, e.g. "BinanceSpotTrades"
+/// * `peg_symbol` - Quote currency of Binance hives (default "USDT"); ignored by Hyperliquid datasets
+/// * `data_root` - Root holding `binance/` and `hyperliquid/` (default "/bigdata/mnemosyne")
+/// * `lazy` - Return a `polars.LazyFrame` instead of a `polars.DataFrame`; by default, lazy for ranges
+///   longer than 31 days (`LOAD_EAGER_MAX_DAYS`)
+///
+/// Raises `ValueError` if the symbol is not in the hive (listing available symbols), has no data in the range,
+/// or a file's columns differ from `schema_for(dataset_type)`.
+///
+/// # Example (Python)
+/// ```python
+/// import mnemosyne as ms
+/// df = ms.load(ms.DatasetType.BinanceSpotTrades, "BTC", "2025-10-01", "2025-10-05")
+/// lf = ms.load(ms.DatasetType.HyperliquidPerpL2, "ETH", "2025-01-01", "2025-06-30")  # lazy
+/// ```
+#[pyfunction]
+#[pyo3(
+    name = "load",
+    signature = (dataset_type, symbol, start_date, end_date, peg_symbol="USDT", data_root=None, lazy=None)
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_load<'py>(
+    py: Python<'py>,
+    dataset_type: &Bound<'py, PyAny>,
+    symbol: &str,
+    start_date: &str,
+    end_date: &str,
+    peg_symbol: &str,
+    data_root: Option<&str>,
+    lazy: Option<bool>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let value_error =
+        |e: anyhow::Error| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e));
+    let dataset_type =
+        DatasetType::from_name(&dataset_type.str()?.to_string()).map_err(value_error)?;
+    let parse = |date_str: &str| {
+        NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid date format '{}': {}",
+                date_str, e
+            ))
+        })
+    };
+    let (start, end) = (parse(start_date)?, parse(end_date)?);
+    let data_root = data_root
+        .map(str::to_string)
+        .unwrap_or_else(crate::datasets::data_root);
+    let (paths, filter_symbol) = dataset_type
+        .load_paths(&data_root, peg_symbol, symbol, start, end)
+        .map_err(value_error)?;
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+
+    // Build the frame with Python polars, so the result is a native polars object
+    let pl = py.import("polars")?;
+    let mut frame = pl.call_method1("scan_parquet", (paths,))?;
+    if filter_symbol {
+        let is_symbol = pl
+            .call_method1("col", ("symbol",))?
+            .call_method1("cast", (pl.getattr("String")?,))?
+            .call_method1("__eq__", (symbol,))?;
+        frame = frame.call_method1("filter", (is_symbol,))?;
+    }
+    let lazy = lazy.unwrap_or((end - start).num_days() + 1 > LOAD_EAGER_MAX_DAYS);
+    if lazy {
+        Ok(frame)
+    } else {
+        frame.call_method0("collect")
+    }
+}
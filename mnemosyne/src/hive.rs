@@ -0,0 +1,198 @@
+/// Date-range reads over hive-partitioned parquet trees.
+///
+/// Layout (produced by `Archiver` and `BinanceTradeBook`): `{hive_path}/date={YYYY-MM-DD}/symbol={SYMBOL}/data.parquet`.
+/// Dates outside the range are pruned by path, so their files are never opened.
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Most symbols an error message lists before summarizing the rest
+const SYMBOL_HINT_LIMIT: usize = 20;
+
+/// Distinct symbols with a `date={date}/symbol={symbol}` partition under `hive_path`, sorted
+pub fn hive_symbols(hive_path: &Path) -> Vec<String> {
+    let mut symbols: Vec<String> = std::fs::read_dir(hive_path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("date="))
+        .flat_map(|entry| std::fs::read_dir(entry.path()).into_iter().flatten())
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().to_string_lossy().to_string();
+            name.strip_prefix("symbol=").map(str::to_string)
+        })
+        .collect();
+    symbols.sort();
+    symbols.dedup();
+    symbols
+}
+
+/// `available symbols: A, B, ...` for error messages, listing at most `SYMBOL_HINT_LIMIT`
+pub fn available_symbols_hint(symbols: &[String]) -> String {
+    if symbols.is_empty() {
+        return "no symbols available".to_string();
+    }
+    let listed = symbols[..symbols.len().min(SYMBOL_HINT_LIMIT)].join(", ");
+    if symbols.len() > SYMBOL_HINT_LIMIT {
+        let rest = symbols.len() - SYMBOL_HINT_LIMIT;
+        format!("available symbols: {}, ... ({} more)", listed, rest)
+    } else {
+        format!("available symbols: {}", listed)
+    }
+}
+
+/// Existing `data.parquet` files of `symbol` for each date in `[start, end]` (inclusive), in date order.
+/// Missing dates are skipped with a warning.
+/// Error: `start > end`, or no date in the range has data for `symbol` (listing the hive's symbols if it has none at all)
+pub fn range_paths(hive_path: &Path, symbol: &str, start: NaiveDate, end: NaiveDate) -> Result<Vec<PathBuf>> {
+    anyhow::ensure!(
+        start <= end,
+        "Invalid date range: start {} is after end {}",
+        start,
+        end
+    );
+    let mut paths = Vec::new();
+    let mut missing = Vec::new();
+    for date in start.iter_days().take_while(|date| *date <= end) {
+        let path = hive_path
+            .join(format!("date={}", date))
+            .join(format!("symbol={}", symbol))
+            .join("data.parquet");
+        if path.is_file() {
+            paths.push(path);
+        } else {
+            missing.push(date);
+        }
+    }
+    if paths.is_empty() {
+        let symbols = hive_symbols(hive_path);
+        anyhow::ensure!(
+            symbols.iter().any(|s| s == symbol),
+            "Symbol {} not found under {}; {}",
+            symbol,
+            hive_path.display(),
+            available_symbols_hint(&symbols)
+        );
+        anyhow::bail!(
+            "Symbol {} not found under {} between {} and {}",
+            symbol,
+            hive_path.display(),
+            start,
+            end
+        );
+    }
+    if !missing.is_empty() {
+        eprintln!(
+            "Warning: {} has no data for {} of {} dates in [{}, {}], skipping: {:?}",
+            symbol,
+            missing.len(),
+            missing.len() + paths.len(),
+            start,
+            end,
+            missing
+        );
+    }
+    Ok(paths)
+}
+
+/// Existing `date={date}/data.parquet` files for each date in `[start, end]` (inclusive), for hives not split by symbol.
+/// Error: `start > end`, or no date in the range has data
+pub fn date_range_paths(hive_path: &Path, start: NaiveDate, end: NaiveDate) -> Result<Vec<PathBuf>> {
+    anyhow::ensure!(
+        start <= end,
+        "Invalid date range: start {} is after end {}",
+        start,
+        end
+    );
+    let paths: Vec<PathBuf> = start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| {
+            hive_path
+                .join(format!("date={}", date))
+                .join("data.parquet")
+        })
+        .filter(|path| path.is_file())
+        .collect();
+    anyhow::ensure!(
+        !paths.is_empty(),
+        "No data under {} between {} and {}",
+        hive_path.display(),
+        start,
+        end
+    );
+    Ok(paths)
+}
+
+/// Lazily scan `symbol` over `[start, end]` (inclusive) as a single LazyFrame.
+/// Filters and projections on the result are pushed down into each per-date scan.
+/// Error: see `range_paths`; unreadable parquet metadata
+pub fn load_range(hive_path: &Path, symbol: &str, start: NaiveDate, end: NaiveDate) -> Result<LazyFrame> {
+    scan_paths(range_paths(hive_path, symbol, start, end)?)
+}
+
+/// Lazily scan `paths` as a single LazyFrame, one scan per file.
+/// Error: unreadable parquet metadata
+pub fn scan_paths(paths: Vec<PathBuf>) -> Result<LazyFrame> {
+    let frames = paths
+        .into_iter()
+        .map(|path| {
+            let path_str = path.to_str().context("Invalid hive path")?;
+            LazyFrame::scan_parquet(PlPath::new(path_str), Default::default())
+                .with_context(|| format!("Failed to scan {}", path_str))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    concat(
+        frames,
+        UnionArgs {
+            parallel: true,
+            rechunk: false,
+            to_supertypes: false,
+            ..Default::default()
+        },
+    )
+    .context("Failed to concatenate date scans")
+}
+
+// PyO3 bindings
+use pyo3::prelude::*;
+
+/// Paths of `symbol`'s hive files over `[start_date, end_date]` (inclusive, YYYY-MM-DD), for `pl.scan_parquet`.
+///
+/// # Example (Python)
+/// ```python
+/// import mnemosyne as ms
+/// import polars as pl
+/// paths = ms.hive_range_paths(
+///     "/bigdata/mnemosyne/binance/lossless/spot/last_trade/peg_symbol=USDT",
+///     "BTC",
+///     "2025-10-01",
+///     "2025-10-05",
+/// )
+/// lf = pl.scan_parquet(paths)
+/// ```
+#[pyfunction]
+#[pyo3(name = "hive_range_paths")]
+pub fn py_hive_range_paths(
+    hive_path: &str,
+    symbol: &str,
+    start_date: &str,
+    end_date: &str,
+) -> PyResult<Vec<String>> {
+    let parse = |date_str: &str| {
+        NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid date format '{}': {}",
+                date_str, e
+            ))
+        })
+    };
+    let (start, end) = (parse(start_date)?, parse(end_date)?);
+    let paths = range_paths(Path::new(hive_path), symbol, start, end)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    Ok(paths
+        .into_iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
@@ -1,5 +1,7 @@
 pub mod crypto;
 pub mod datasets;
+pub mod hive;
+pub mod schema;
 
 pub use crypto::binance;
 
@@ -12,5 +14,16 @@ fn mnemosyne(m: &Bound<'_, PyModule>) -> PyResult<()> {
         crypto::hyperliquid::l2book::py_read_hyperliquid_l2book_bydate_to,
         m
     )?)?;
+    m.add_function(wrap_pyfunction!(
+        crypto::hyperliquid::l2book::py_read_hyperliquid_l2book_range_to,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        crypto::hyperliquid::l2book::py_scan_hyperliquid_l2book_bydate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(hive::py_hive_range_paths, m)?)?;
+    m.add_function(wrap_pyfunction!(datasets::py_load, m)?)?;
+    m.add_function(wrap_pyfunction!(schema::py_schema_for, m)?)?;
     Ok(())
 }
@@ -0,0 +1,151 @@
+/// Column contract of each dataset's lossless parquet files.
+///
+/// Readers check every file against `schema_for` before scanning, so an archive written by another version
+/// fails with the expected and actual columns instead of a cryptic error deep inside a query.
+use crate::datasets::DatasetType;
+use anyhow::{Context, Result};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::path::Path;
+
+/// Expected columns of `dataset_type`'s lossless files, in file order; `None` for datasets no lossless writer
+/// produces yet (`HyperliquidPerpTrades`). Fields are nullable, as polars writes them.
+pub fn schema_for(dataset_type: &DatasetType) -> Option<Schema> {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, None);
+    let fields = match dataset_type {
+        // `SpotTradeSchema` after postprocessing: `time` normalized to microseconds
+        DatasetType::BinanceSpotTrades => vec![
+            Field::new("trade_id", DataType::Int64, true),
+            Field::new("price", DataType::Float64, true),
+            Field::new("quantity", DataType::Float64, true),
+            Field::new("quote_quantity", DataType::Float64, true),
+            Field::new("time", timestamp, true),
+            Field::new("is_buyer_maker", DataType::Boolean, true),
+            Field::new("is_best_match", DataType::Boolean, true),
+        ],
+        // `UmFuturesTradeSchema` after postprocessing
+        DatasetType::BinanceUmPerpTrades => vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("price", DataType::Float64, true),
+            Field::new("qty", DataType::Float64, true),
+            Field::new("quote_qty", DataType::Float64, true),
+            Field::new("time", timestamp, true),
+            Field::new("is_buyer_maker", DataType::Boolean, true),
+        ],
+        // `read_hyperliquid_l2book_bydate` output: one row per book level
+        DatasetType::HyperliquidPerpL2 => vec![
+            Field::new("symbol", DataType::Utf8, true),
+            Field::new("time", timestamp.clone(), true),
+            Field::new("price", DataType::Float64, true),
+            Field::new("csize", DataType::Float64, true),
+            Field::new("depth", DataType::Int16, true),
+            Field::new("num_orders_at_level", DataType::Int16, true),
+            Field::new("is_bid", DataType::Boolean, true),
+            Field::new("created_time", timestamp, true),
+        ],
+        DatasetType::HyperliquidPerpTrades => return None,
+    };
+    Some(Schema::new(fields))
+}
+
+/// Schema of the parquet file at `path`, read from its footer only
+pub fn parquet_schema(path: &Path) -> Result<Schema> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .with_context(|| format!("Failed to read parquet metadata of {}", path.display()))?;
+    Ok(builder.schema().as_ref().clone())
+}
+
+/// Checks the file at `path` against `schema_for(dataset_type)`; datasets without a registered schema always pass.
+/// Error: unreadable footer, or "schema mismatch: expected X, got Y" naming the first differing column
+pub fn validate_parquet_schema(dataset_type: &DatasetType, path: &Path) -> Result<()> {
+    let Some(expected) = schema_for(dataset_type) else {
+        return Ok(());
+    };
+    let found = parquet_schema(path)?;
+    if let Some(difference) = schema_difference(&found, &expected) {
+        anyhow::bail!(
+            "{} ({:?}) schema mismatch, {}: expected {}, got {}",
+            path.display(),
+            dataset_type,
+            difference,
+            describe(&expected),
+            describe(&found)
+        );
+    }
+    Ok(())
+}
+
+/// Strings are compared regardless of offset width or view layout, which differ between polars versions
+fn normalized(data_type: &DataType) -> &DataType {
+    match data_type {
+        DataType::LargeUtf8 | DataType::Utf8View => &DataType::Utf8,
+        other => other,
+    }
+}
+
+/// First difference in column names, order or types between `found` and `expected`; nullability is ignored
+fn schema_difference(found: &Schema, expected: &Schema) -> Option<String> {
+    for (index, field) in expected.fields().iter().enumerate() {
+        let Some(actual) = found.fields().get(index) else {
+            return Some(format!("missing column {}", field.name()));
+        };
+        if actual.name() != field.name() {
+            return Some(format!(
+                "column {} is {} where {} is expected",
+                index,
+                actual.name(),
+                field.name()
+            ));
+        }
+        if normalized(actual.data_type()) != normalized(field.data_type()) {
+            return Some(format!(
+                "column {} is {} where {} is expected",
+                field.name(),
+                actual.data_type(),
+                field.data_type()
+            ));
+        }
+    }
+    found
+        .fields()
+        .get(expected.fields().len())
+        .map(|extra| format!("unexpected column {}", extra.name()))
+}
+
+/// `[name: type, ...]`
+fn describe(schema: &Schema) -> String {
+    let columns: Vec<String> = schema
+        .fields()
+        .iter()
+        .map(|field| format!("{}: {}", field.name(), field.data_type()))
+        .collect();
+    format!("[{}]", columns.join(", "))
+}
+
+// PyO3 bindings
+use pyo3::prelude::*;
+
+/// Expected `(column, arrow type)` pairs of `dataset_type`'s lossless files, in file order,
+/// or `None` if the dataset has no registered schema.
+///
+/// # Example (Python)
+/// ```python
+/// import mnemosyne as ms
+/// ms.schema_for(ms.DatasetType.HyperliquidPerpL2)
+/// # [('symbol', 'Utf8'), ('time', ...), ('price', 'Float64'), ...]
+/// ```
+#[pyfunction]
+#[pyo3(name = "schema_for")]
+pub fn py_schema_for(dataset_type: &Bound<'_, PyAny>) -> PyResult<Option<Vec<(String, String)>>> {
+    let dataset_type = DatasetType::from_name(&dataset_type.str()?.to_string())
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))?;
+    Ok(schema_for(&dataset_type).map(|schema| {
+        schema
+            .fields()
+            .iter()
+            .map(|field| (field.name().clone(), field.data_type().to_string()))
+            .collect()
+    }))
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use mnemosyne::crypto::{BackfillProgress, CryptoDataInterface};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 10, day).unwrap()
+}
+
+/// Data source listing `symbols` x `days`; downloads of `failing` fail, every other pair converts
+/// to a one-row parquet
+struct SyntheticSource {
+    root: PathBuf,
+    universe_cache_path: PathBuf,
+    symbols: Vec<&'static str>,
+    days: Vec<u32>,
+    failing: &'static str,
+}
+
+impl SyntheticSource {
+    fn new(
+        root: PathBuf,
+        symbols: Vec<&'static str>,
+        days: Vec<u32>,
+        failing: &'static str,
+    ) -> Self {
+        std::fs::create_dir_all(&root).unwrap();
+        Self {
+            universe_cache_path: root.join("universe.parquet"),
+            root,
+            symbols,
+            days,
+            failing,
+        }
+    }
+}
+
+impl CryptoDataInterface for SyntheticSource {
+    fn build_hive_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
+        self.root
+            .join(format!("date={}", date))
+            .join(format!("symbol={}", symbol))
+            .join("data.parquet")
+    }
+
+    fn build_raw_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
+        self.root.join(format!("{}-{}.zip", symbol, date))
+    }
+
+    fn hive_data_path(&self) -> &Path {
+        &self.root
+    }
+
+    fn raw_data_path(&self) -> &Path {
+        &self.root
+    }
+
+    fn universe_cache_path(&self) -> &Path {
+        &self.universe_cache_path
+    }
+
+    fn date_filters(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        (None, None)
+    }
+
+    async fn fetch_new_universe(&self) -> Result<DataFrame> {
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        for symbol in &self.symbols {
+            for day in &self.days {
+                symbols.push(*symbol);
+                dates.push(date(*day));
+            }
+        }
+        Ok(df!("symbol" => symbols, "date" => dates)?)
+    }
+
+    async fn fetch_universe_after(&self, _after: &HashMap<String, NaiveDate>) -> Result<DataFrame> {
+        unimplemented!("progress tests never extend the universe")
+    }
+
+    async fn download_raw(&self, symbol: &str, _date: NaiveDate) -> Result<()> {
+        if symbol == self.failing {
+            anyhow::bail!("synthetic download failure");
+        }
+        Ok(())
+    }
+
+    fn process_download_to_parquet(
+        &self,
+        _zip_path: &Path,
+        hive_path: &Path,
+        symbol: &str,
+        date: NaiveDate,
+    ) -> Result<usize> {
+        std::fs::create_dir_all(hive_path.parent().unwrap())?;
+        let mut df = df!("symbol" => [symbol], "date" => [date])?;
+        let mut file = std::fs::File::create(hive_path)?;
+        ParquetWriter::new(&mut file).finish(&mut df)?;
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn progress_events_sum_to_update_stats() {
+        let root = scratch_dir("backfill_progress");
+        let source = Arc::new(SyntheticSource::new(
+            root.clone(),
+            vec!["BTC", "ETH", "BAD"],
+            vec![1, 2],
+            "BAD",
+        ));
+        source.initialize_universe(false).await.unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let stats = Arc::clone(&source)
+            .update_universe(4, true, Some(sender))
+            .await
+            .unwrap();
+        // The sender is dropped with the run, which ends the iteration
+        let events: Vec<BackfillProgress> = receiver.iter().collect();
+
+        assert_eq!(
+            (stats.total, stats.success, stats.skipped, stats.failed),
+            (6, 4, 0, 2)
+        );
+        assert_eq!(events.len(), stats.total);
+        for (i, event) in events.iter().enumerate() {
+            assert_eq!(event.total, 6);
+            assert_eq!(event.completed, i + 1);
+            assert_eq!(
+                event.success + event.skipped + event.failed,
+                event.completed
+            );
+        }
+        let last = events.last().unwrap();
+        assert_eq!(
+            (last.success, last.skipped, last.failed),
+            (stats.success, stats.skipped, stats.failed)
+        );
+        assert_eq!(last.eta, Duration::ZERO);
+        assert!(last.throughput > 0.0);
+        assert!(
+            events
+                .windows(2)
+                .all(|pair| pair[0].elapsed <= pair[1].elapsed)
+        );
+
+        // Only the failed pairs are still missing
+        let (sender, receiver) = mpsc::channel();
+        let stats = Arc::clone(&source)
+            .update_universe(4, true, Some(sender))
+            .await
+            .unwrap();
+        assert_eq!((stats.total, stats.failed), (2, 2));
+        assert_eq!(receiver.iter().last().map(|event| event.failed), Some(2));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::NaiveDate;
+use mnemosyne::datasets::DatasetType;
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Scratch data root unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 10, day).unwrap()
+}
+
+fn write_parquet(path: &Path, df: &mut DataFrame) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut file = std::fs::File::create(path).unwrap();
+    ParquetWriter::new(&mut file).finish(df).unwrap();
+}
+
+/// `df` with each of `columns` (microseconds since epoch) cast to the lossless datetime type
+fn with_datetimes(df: DataFrame, columns: &[&str]) -> DataFrame {
+    let casts: Vec<Expr> = columns
+        .iter()
+        .map(|name| col(*name).cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+        .collect();
+    df.lazy().with_columns(casts).collect().unwrap()
+}
+
+/// Binance spot trade partition for (symbol, day) whose `price` column encodes the day
+fn write_spot_partition(data_root: &Path, symbol: &str, day: u32) {
+    let hive_path =
+        DatasetType::BinanceSpotTrades.hive_path_in(data_root.to_str().unwrap(), "USDT");
+    let path = Path::new(&hive_path)
+        .join(format!("date={}", date(day)))
+        .join(format!("symbol={}", symbol))
+        .join("data.parquet");
+    let df = df!(
+        "trade_id" => [day as i64],
+        "price" => [day as f64],
+        "quantity" => [1.0],
+        "quote_quantity" => [day as f64],
+        "time" => [0i64],
+        "is_buyer_maker" => [true],
+        "is_best_match" => [true],
+    )
+    .unwrap();
+    write_parquet(&path, &mut with_datetimes(df, &["time"]));
+}
+
+/// Hyperliquid L2 file for `day` holding one row per symbol, `price` encoding the day
+fn write_l2_date(data_root: &Path, symbols: &[&str], day: u32) {
+    let hive_path =
+        DatasetType::HyperliquidPerpL2.hive_path_in(data_root.to_str().unwrap(), "USDT");
+    let path = Path::new(&hive_path)
+        .join(format!("date={}", date(day)))
+        .join("data.parquet");
+    let rows = symbols.len();
+    let df = df!(
+        "symbol" => symbols.to_vec(),
+        "time" => vec![0i64; rows],
+        "price" => vec![day as f64; rows],
+        "csize" => vec![1.0; rows],
+        "depth" => vec![0i16; rows],
+        "num_orders_at_level" => vec![1i16; rows],
+        "is_bid" => vec![true; rows],
+        "created_time" => vec![0i64; rows],
+    )
+    .unwrap();
+    write_parquet(&path, &mut with_datetimes(df, &["time", "created_time"]));
+}
+
+fn f64_column(df: &DataFrame, name: &str) -> Vec<f64> {
+    df.column(name)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_routes_symbol_partitioned_and_per_date_hives() {
+        let root = scratch_dir("dataset_load_routes");
+        let data_root = root.to_str().unwrap();
+        for day in [1, 2, 3] {
+            write_spot_partition(&root, "BTC", day);
+            write_l2_date(&root, &["BTC", "ETH"], day);
+        }
+        write_spot_partition(&root, "ETH", 2);
+
+        let spot = DatasetType::BinanceSpotTrades
+            .load(data_root, "USDT", "BTC", date(2), date(3))
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(f64_column(&spot, "price"), vec![2.0, 3.0]);
+
+        // Per-date L2 files hold every symbol: only ETH rows come back
+        let l2 = DatasetType::HyperliquidPerpL2
+            .load(data_root, "USDT", "ETH", date(1), date(2))
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(f64_column(&l2, "price"), vec![1.0, 2.0]);
+        assert!(
+            l2.column("symbol")
+                .unwrap()
+                .str()
+                .unwrap()
+                .into_no_null_iter()
+                .all(|symbol| symbol == "ETH")
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn load_lists_available_symbols_for_unknown_symbol() {
+        let root = scratch_dir("dataset_load_unknown");
+        let data_root = root.to_str().unwrap();
+        write_spot_partition(&root, "BTC", 1);
+        write_spot_partition(&root, "ETH", 1);
+        write_l2_date(&root, &["BTC", "SOL"], 1);
+
+        let Err(err) =
+            DatasetType::BinanceSpotTrades.load(data_root, "USDT", "DOGE", date(1), date(1))
+        else {
+            panic!("unknown symbol should fail");
+        };
+        assert!(
+            err.to_string().contains("available symbols: BTC, ETH"),
+            "{}",
+            err
+        );
+
+        let Err(err) =
+            DatasetType::HyperliquidPerpL2.load(data_root, "USDT", "DOGE", date(1), date(1))
+        else {
+            panic!("unknown symbol should fail");
+        };
+        assert!(
+            err.to_string().contains("available symbols: BTC, SOL"),
+            "{}",
+            err
+        );
+
+        // No L2 file in the range at all
+        assert!(
+            DatasetType::HyperliquidPerpL2
+                .load(data_root, "USDT", "BTC", date(5), date(6))
+                .is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn from_name_parses_variant_names() {
+        for dataset_type in DatasetType::ALL {
+            let name = format!("{:?}", dataset_type);
+            assert_eq!(DatasetType::from_name(&name).unwrap(), dataset_type);
+        }
+        let err = DatasetType::from_name("BinanceSpot").unwrap_err();
+        assert!(err.to_string().contains("BinanceSpotTrades"), "{}", err);
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+use mnemosyne::hive::{load_range, range_paths};
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 10, day).unwrap()
+}
+
+/// Writes a two-row partition for (symbol, date) whose `price` column encodes the day
+fn write_partition(root: &Path, symbol: &str, day: u32) {
+    let dir = root
+        .join(format!("date={}", date(day)))
+        .join(format!("symbol={}", symbol));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut df = df!(
+        "date" => [date(day), date(day)],
+        "price" => [day as f64, day as f64 + 0.5],
+    )
+    .unwrap();
+    let mut file = std::fs::File::create(dir.join("data.parquet")).unwrap();
+    ParquetWriter::new(&mut file).finish(&mut df).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_range_concatenates_dates_and_skips_missing() {
+        let root = scratch_dir("hive_range_load");
+        for day in [1, 2, 4, 5] {
+            write_partition(&root, "BTC", day);
+        }
+        write_partition(&root, "ETH", 3);
+
+        // Day 3 has no BTC partition: skipped; days 1 and 5 are outside the range
+        let paths = range_paths(&root, "BTC", date(2), date(4)).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                root.join("date=2025-10-02/symbol=BTC/data.parquet"),
+                root.join("date=2025-10-04/symbol=BTC/data.parquet"),
+            ]
+        );
+
+        let df = load_range(&root, "BTC", date(2), date(4))
+            .unwrap()
+            .collect()
+            .unwrap();
+        let prices: Vec<f64> = df
+            .column("price")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(prices, vec![2.0, 2.5, 4.0, 4.5]);
+
+        // Filters push down into the per-date scans
+        let filtered = load_range(&root, "BTC", date(1), date(5))
+            .unwrap()
+            .filter(col("price").gt(lit(4.2)))
+            .collect()
+            .unwrap();
+        assert_eq!(filtered.height(), 3);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn load_range_rejects_unknown_symbol_and_empty_range() {
+        let root = scratch_dir("hive_range_errors");
+        write_partition(&root, "BTC", 1);
+        write_partition(&root, "ETH", 2);
+
+        let Err(err) = load_range(&root, "SOL", date(1), date(3)) else {
+            panic!("unknown symbol should fail");
+        };
+        assert!(err.to_string().contains("not found"), "{}", err);
+        assert!(
+            err.to_string().contains("available symbols: BTC, ETH"),
+            "{}",
+            err
+        );
+        // Symbol exists, but not in this range
+        assert!(range_paths(&root, "BTC", date(2), date(3)).is_err());
+
+        let err = range_paths(&root, "BTC", date(3), date(1)).unwrap_err();
+        assert!(err.to_string().contains("Invalid date range"), "{}", err);
+        // Single-day range is valid
+        assert_eq!(range_paths(&root, "BTC", date(1), date(1)).unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
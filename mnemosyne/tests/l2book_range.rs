@@ -0,0 +1,211 @@
+use chrono::NaiveDate;
+use mnemosyne::crypto::hyperliquid::l2book::{
+    read_hyperliquid_l2book_bydate, read_hyperliquid_l2book_range_to,
+    scan_hyperliquid_l2book_bydate,
+};
+use mnemosyne::crypto::par_map_chunked;
+use polars::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+}
+
+/// Path of the raw LZ4 file for (date, hour, symbol)
+fn raw_file(raw: &Path, day: u32, hour: u32, symbol: &str) -> PathBuf {
+    let dir = raw
+        .join(date(day).format("%Y%m%d").to_string())
+        .join(hour.to_string())
+        .join("l2Book");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(format!("{}.lz4", symbol))
+}
+
+fn write_lz4(path: &Path, contents: &str) {
+    let file = std::fs::File::create(path).unwrap();
+    let mut encoder = lz4::EncoderBuilder::new().build(file).unwrap();
+    encoder.write_all(contents.as_bytes()).unwrap();
+    let (_, result) = encoder.finish();
+    result.unwrap();
+}
+
+/// Writes `snapshots` L2 book snapshots of `symbol`, each with 2 bid and 1 ask level (3 rows per snapshot)
+fn write_snapshots(raw: &Path, day: u32, hour: u32, symbol: &str, snapshots: usize) {
+    let lines: Vec<String> = (0..snapshots)
+        .map(|i| {
+            serde_json::json!({
+                "time": format!("{}T{:02}:00:{:02}.000000", date(day), hour, i),
+                "ver_num": 1,
+                "raw": {
+                    "channel": "l2Book",
+                    "data": {
+                        "coin": symbol,
+                        "time": 1_759_190_400_000i64 + i as i64 * 1000,
+                        "levels": [
+                            [{"px": "100.0", "sz": "0.1", "n": 1}, {"px": "99.5", "sz": "0.2", "n": 2}],
+                            [{"px": "100.5", "sz": "0.3", "n": 3}],
+                        ],
+                    },
+                },
+            })
+            .to_string()
+        })
+        .collect();
+    write_lz4(&raw_file(raw, day, hour, symbol), &lines.join("\n"));
+}
+
+fn parquet_height(path: &Path) -> usize {
+    let file = std::fs::File::open(path).unwrap();
+    ParquetReader::new(file).finish().unwrap().height()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_writes_each_date_skips_empty_and_continues_past_failures() {
+        let root = scratch_dir("l2book_range");
+        let (raw, out) = (root.join("raw"), root.join("out"));
+        // Day 1: two symbols in different hours; day 2: no files; day 3: unreadable; day 4: one symbol
+        write_snapshots(&raw, 1, 0, "BTC", 2);
+        write_snapshots(&raw, 1, 5, "ETH", 1);
+        write_lz4(&raw_file(&raw, 3, 0, "BTC"), "not json");
+        write_snapshots(&raw, 4, 23, "SOL", 4);
+
+        let written = read_hyperliquid_l2book_range_to(
+            &raw,
+            date(1),
+            date(4),
+            &out,
+            ParquetCompression::Uncompressed,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            written.into_iter().collect::<Vec<_>>(),
+            vec![(date(1), 9), (date(4), 12)]
+        );
+        assert_eq!(parquet_height(&out.join("date=2025-09-01/data.parquet")), 9);
+        assert_eq!(
+            parquet_height(&out.join("date=2025-09-04/data.parquet")),
+            12
+        );
+        assert!(!out.join("date=2025-09-02").exists());
+        assert!(!out.join("date=2025-09-03").exists());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn range_rejects_start_after_end() {
+        let root = scratch_dir("l2book_range_inverted");
+        let result = read_hyperliquid_l2book_range_to(
+            &root.join("raw"),
+            date(4),
+            date(1),
+            &root.join("out"),
+            ParquetCompression::Uncompressed,
+            None,
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid date range")
+        );
+    }
+
+    #[test]
+    fn chunked_map_never_exceeds_max_in_flight() {
+        let items: Vec<usize> = (0..200).collect();
+        let (in_flight, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let mut consumed = Vec::new();
+        par_map_chunked(
+            &items,
+            Some(8),
+            |item| {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(1));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                item * 2
+            },
+            |batch| {
+                assert!(batch.len() <= 8);
+                consumed.extend(batch);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert!(peak.load(Ordering::SeqCst) <= 8);
+        assert_eq!(
+            consumed,
+            items.iter().map(|item| item * 2).collect::<Vec<_>>()
+        );
+        assert!(par_map_chunked(&items, Some(0), |item| *item, |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn bounded_read_matches_unbounded_read_over_many_files() {
+        let root = scratch_dir("l2book_bounded");
+        let raw = root.join("raw");
+        // 24 hours x 3 symbols = 72 files, read 2 at a time
+        for hour in 0..24 {
+            for symbol in ["BTC", "ETH", "SOL"] {
+                write_snapshots(&raw, 1, hour, symbol, 2);
+            }
+        }
+
+        let bounded = read_hyperliquid_l2book_bydate(&raw, date(1), Some(2)).unwrap();
+        let unbounded = read_hyperliquid_l2book_bydate(&raw, date(1), None).unwrap();
+        assert_eq!(bounded.height(), 72 * 6);
+        assert_eq!(bounded.schema(), unbounded.schema());
+        assert!(bounded.equals(&unbounded));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn lazy_symbol_filter_matches_eager_filter() {
+        let root = scratch_dir("l2book_scan");
+        let raw = root.join("raw");
+        for hour in [0, 7, 23] {
+            for symbol in ["BTC", "ETH", "SOL"] {
+                write_snapshots(&raw, 1, hour, symbol, 2);
+            }
+        }
+        let is_eth = || col("symbol").eq(lit("ETH"));
+
+        // Both read the files in the same order, and filtering keeps row order
+
+        let eager = read_hyperliquid_l2book_bydate(&raw, date(1), None)
+            .unwrap()
+            .lazy()
+            .filter(is_eth())
+            .collect()
+            .unwrap();
+        let lazy = scan_hyperliquid_l2book_bydate(&raw, date(1))
+            .unwrap()
+            .filter(is_eth())
+            .collect()
+            .unwrap();
+        // 3 hours x 2 snapshots x 3 rows
+        assert_eq!(lazy.height(), 18);
+        assert_eq!(lazy.schema(), eager.schema());
+        assert!(lazy.equals(&eager));
+
+        assert!(scan_hyperliquid_l2book_bydate(&raw, date(2)).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
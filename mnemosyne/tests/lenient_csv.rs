@@ -0,0 +1,115 @@
+use mnemosyne::crypto::binance::last_trades::{
+    SpotTradeSchema, UmFuturesTradeSchema, parse_trade_csv,
+};
+use polars::prelude::*;
+
+/// 2024 spot rows: millisecond timestamps, Python-style booleans, no header
+const SPOT_ROWS: &str = "\
+1,100.5,0.1,10.05,1704067200000,True,True
+2,100.6,0.2,20.12,1704067201000,False,True
+";
+
+const FUTURES_HEADER: &str = "id,price,qty,quote_qty,time,is_buyer_maker\n";
+const FUTURES_ROWS: &str = "\
+7,200.5,1.0,200.5,1704067200000,true
+8,200.4,2.0,400.8,1704067201500,false
+";
+
+/// Microseconds since epoch of the normalized `time` column
+fn times_us(df: &DataFrame) -> Vec<i64> {
+    let time = df.column("time").unwrap();
+    assert_eq!(
+        time.dtype(),
+        &DataType::Datetime(TimeUnit::Microseconds, None)
+    );
+    time.datetime().unwrap().phys.into_no_null_iter().collect()
+}
+
+fn column_i64(df: &DataFrame, name: &str) -> Vec<i64> {
+    df.column(name)
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headerless_spot_file_parses_in_both_modes() {
+        for lenient in [false, true] {
+            let df = parse_trade_csv::<SpotTradeSchema>(SPOT_ROWS.to_string(), lenient).unwrap();
+            assert_eq!(df.height(), 2);
+            assert_eq!(column_i64(&df, "trade_id"), vec![1, 2]);
+            assert_eq!(
+                times_us(&df),
+                vec![1_704_067_200_000_000, 1_704_067_201_000_000]
+            );
+        }
+    }
+
+    #[test]
+    fn spot_file_with_unexpected_header_recovers_only_when_lenient() {
+        let csv = format!(
+            "trade_id,price,quantity,quote_quantity,time,is_buyer_maker,is_best_match\n{}",
+            SPOT_ROWS
+        );
+        assert!(parse_trade_csv::<SpotTradeSchema>(csv.clone(), false).is_err());
+
+        let df = parse_trade_csv::<SpotTradeSchema>(csv, true).unwrap();
+        assert_eq!(df.height(), 2);
+        assert_eq!(column_i64(&df, "trade_id"), vec![1, 2]);
+        let is_buyer_maker: Vec<bool> = df
+            .column("is_buyer_maker")
+            .unwrap()
+            .bool()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(is_buyer_maker, vec![true, false]);
+    }
+
+    #[test]
+    fn futures_file_missing_header_recovers_only_when_lenient() {
+        let expected = vec![1_704_067_200_000_000, 1_704_067_201_500_000];
+        let df = parse_trade_csv::<UmFuturesTradeSchema>(
+            format!("{}{}", FUTURES_HEADER, FUTURES_ROWS),
+            false,
+        )
+        .unwrap();
+        assert_eq!(times_us(&df), expected);
+
+        // Strict mode refuses rather than swallowing the first trade as a header
+        assert!(parse_trade_csv::<UmFuturesTradeSchema>(FUTURES_ROWS.to_string(), false).is_err());
+
+        let df = parse_trade_csv::<UmFuturesTradeSchema>(FUTURES_ROWS.to_string(), true).unwrap();
+        assert_eq!(column_i64(&df, "id"), vec![7, 8]);
+        assert_eq!(times_us(&df), expected);
+    }
+
+    #[test]
+    fn lenient_maps_renamed_and_reordered_columns_by_name() {
+        // Spot-style names and an extra trailing column on a futures file
+        let csv = "\
+time,trade_id,price,quantity,quote_quantity,is_buyer_maker,extra
+1704067200000,7,200.5,1.0,200.5,true,x
+";
+        let df = parse_trade_csv::<UmFuturesTradeSchema>(csv.to_string(), true).unwrap();
+        assert_eq!(
+            df.get_column_names()
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "price", "qty", "quote_qty", "time", "is_buyer_maker"]
+        );
+        assert_eq!(column_i64(&df, "id"), vec![7]);
+        assert_eq!(times_us(&df), vec![1_704_067_200_000_000]);
+
+        // A schema column with no counterpart is still an error
+        let missing = "id,price,qty,time,is_buyer_maker\n7,200.5,1.0,1704067200000,true\n";
+        assert!(parse_trade_csv::<UmFuturesTradeSchema>(missing.to_string(), true).is_err());
+    }
+}
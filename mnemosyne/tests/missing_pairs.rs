@@ -0,0 +1,137 @@
+use chrono::NaiveDate;
+use mnemosyne::crypto::CryptoDataInterface;
+use mnemosyne::crypto::binance::BinanceSpotTradeBook;
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 10, day).unwrap()
+}
+
+async fn spot_trade_book(
+    root: &Path,
+    earliest_date: Option<NaiveDate>,
+    latest_date: Option<NaiveDate>,
+) -> BinanceSpotTradeBook {
+    BinanceSpotTradeBook::new(
+        root.join("lossless"),
+        root.join("raw"),
+        "https://data.binance.vision/data/spot/daily".to_string(),
+        "trades".to_string(),
+        "spot/daily/trades".to_string(),
+        "USDT".to_string(),
+        earliest_date,
+        latest_date,
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+/// Writes a universe cache of {BTC, ETH} x 2025-10-{01..04}, bypassing the S3 listing
+fn write_universe(book: &BinanceSpotTradeBook) {
+    let mut symbols = Vec::new();
+    let mut dates = Vec::new();
+    for day in 1..=4 {
+        for symbol in ["BTC", "ETH"] {
+            symbols.push(symbol.to_string());
+            dates.push(date(day));
+        }
+    }
+    let mut universe = df!("symbol" => symbols, "date" => dates).unwrap();
+    let mut file = std::fs::File::create(book.universe_cache_path()).unwrap();
+    ParquetWriter::new(&mut file).finish(&mut universe).unwrap();
+}
+
+/// Marks (symbol, date) as converted; contents are irrelevant to gap detection
+fn touch_hive(book: &BinanceSpotTradeBook, symbol: &str, date: NaiveDate) {
+    let path = book.build_hive_path(symbol, date);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, b"").unwrap();
+}
+
+fn pairs(df: &DataFrame) -> Vec<(String, NaiveDate)> {
+    let symbols = df.column("symbol").unwrap().str().unwrap();
+    let dates = df.column("date").unwrap().date().unwrap();
+    (0..df.height())
+        .map(|i| {
+            let days = dates.phys.get(i).unwrap();
+            (
+                symbols.get(i).unwrap().to_string(),
+                NaiveDate::from_num_days_from_ce_opt(days + 719_163).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_pairs_skips_converted_pairs() {
+        let root = scratch_dir("missing_pairs_partial");
+        let book = spot_trade_book(&root, None, None).await;
+        write_universe(&book);
+        touch_hive(&book, "BTC", date(1));
+        touch_hive(&book, "ETH", date(1));
+        touch_hive(&book, "BTC", date(3));
+        // Directory without data.parquet (e.g. interrupted conversion) is still missing
+        std::fs::create_dir_all(book.build_hive_path("ETH", date(2)).parent().unwrap()).unwrap();
+        // Pair outside the universe is ignored
+        touch_hive(&book, "SOL", date(2));
+
+        let missing = book.missing_pairs().await.unwrap();
+        assert_eq!(
+            pairs(&missing),
+            vec![
+                ("BTC".to_string(), date(2)),
+                ("ETH".to_string(), date(2)),
+                ("ETH".to_string(), date(3)),
+                ("BTC".to_string(), date(4)),
+                ("ETH".to_string(), date(4)),
+            ]
+        );
+
+        // Converting the rest closes every gap
+        for (symbol, day) in [("BTC", 2), ("ETH", 2), ("ETH", 3), ("BTC", 4), ("ETH", 4)] {
+            touch_hive(&book, symbol, date(day));
+        }
+        assert_eq!(book.missing_pairs().await.unwrap().height(), 0);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn missing_pairs_respects_date_filters() {
+        let root = scratch_dir("missing_pairs_filtered");
+        let book = spot_trade_book(&root, Some(date(2)), Some(date(3))).await;
+        write_universe(&book);
+        touch_hive(&book, "BTC", date(2));
+
+        let missing = book.missing_pairs().await.unwrap();
+        assert_eq!(
+            pairs(&missing),
+            vec![
+                ("ETH".to_string(), date(2)),
+                ("BTC".to_string(), date(3)),
+                ("ETH".to_string(), date(3)),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn missing_pairs_requires_universe() {
+        let root = scratch_dir("missing_pairs_no_universe");
+        let book = spot_trade_book(&root, None, None).await;
+        assert!(book.missing_pairs().await.is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
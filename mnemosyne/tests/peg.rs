@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+use mnemosyne::crypto::CryptoDataInterface;
+use mnemosyne::crypto::binance::BinanceSpotTradeBook;
+use mnemosyne::crypto::binance::last_trades::peg_hive_root;
+use std::path::{Path, PathBuf};
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+async fn spot_trade_book(root: &Path, peg_symbol: &str) -> BinanceSpotTradeBook {
+    BinanceSpotTradeBook::new(
+        root.join("lossless"),
+        root.join("raw").join(format!("peg_symbol={}", peg_symbol)),
+        "https://data.binance.vision/data/spot/daily".to_string(),
+        "trades".to_string(),
+        "spot/daily/trades".to_string(),
+        peg_symbol.to_string(),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn same_symbol_under_two_pegs_lands_in_separate_partitions() {
+        let root = scratch_dir("two_pegs");
+        let usdt = spot_trade_book(&root, "USDT").await;
+        let usdc = spot_trade_book(&root, "USDC").await;
+        let date = NaiveDate::from_ymd_opt(2025, 10, 5).unwrap();
+
+        let usdt_path = usdt.build_hive_path("BTC", date);
+        let usdc_path = usdc.build_hive_path("BTC", date);
+        assert_ne!(usdt_path, usdc_path);
+        assert_eq!(
+            usdt_path,
+            root.join("lossless/peg_symbol=USDT/date=2025-10-05/symbol=BTC/data.parquet")
+        );
+        assert_eq!(
+            usdc_path,
+            root.join("lossless/peg_symbol=USDC/date=2025-10-05/symbol=BTC/data.parquet")
+        );
+
+        // Universe caches must not be shared either
+        assert_ne!(usdt.universe_cache_path(), usdc.universe_cache_path());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn existing_peg_partition_is_not_duplicated() {
+        let hive = Path::new("/data/lossless/spot/last_trade/peg_symbol=USDC");
+        assert_eq!(peg_hive_root(hive, "USDC").unwrap(), hive.to_path_buf());
+    }
+
+    #[test]
+    fn mismatched_peg_partition_is_rejected() {
+        let hive = Path::new("/data/lossless/spot/last_trade/peg_symbol=USDT");
+        assert!(peg_hive_root(hive, "USDC").is_err());
+    }
+
+    #[test]
+    fn invalid_peg_symbol_is_rejected() {
+        let hive = Path::new("/data/lossless/spot/last_trade");
+        assert!(peg_hive_root(hive, "").is_err());
+        assert!(peg_hive_root(hive, "usdt").is_err());
+        assert!(peg_hive_root(hive, "US/DT").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::NaiveDate;
+use mnemosyne::crypto::binance::last_trades::{
+    SpotTradeSchema, UmFuturesTradeSchema, parse_trade_csv,
+};
+use mnemosyne::crypto::hyperliquid::l2book::read_hyperliquid_l2book_bydate;
+use mnemosyne::datasets::DatasetType;
+use mnemosyne::schema::{parquet_schema, schema_for, validate_parquet_schema};
+use polars::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+fn write_parquet(path: &Path, df: &mut DataFrame) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut file = std::fs::File::create(path).unwrap();
+    ParquetWriter::new(&mut file).finish(df).unwrap();
+}
+
+/// One raw L2 snapshot of BTC on 2025-09-30 at hour 0, in the layout `read_hyperliquid_l2book_bydate` reads
+fn write_l2_snapshot(raw: &Path) {
+    let dir = raw.join("20250930").join("0").join("l2Book");
+    std::fs::create_dir_all(&dir).unwrap();
+    let line = serde_json::json!({
+        "time": "2025-09-30T00:00:00.000000",
+        "ver_num": 1,
+        "raw": {
+            "channel": "l2Book",
+            "data": {
+                "coin": "BTC",
+                "time": 1_759_190_400_000i64,
+                "levels": [
+                    [{"px": "100.0", "sz": "0.1", "n": 1}],
+                    [{"px": "100.5", "sz": "0.3", "n": 3}],
+                ],
+            },
+        },
+    });
+    let file = std::fs::File::create(dir.join("BTC.lz4")).unwrap();
+    let mut encoder = lz4::EncoderBuilder::new().build(file).unwrap();
+    encoder.write_all(line.to_string().as_bytes()).unwrap();
+    let (_, result) = encoder.finish();
+    result.unwrap();
+}
+
+fn column_names(schema: &arrow::datatypes::Schema) -> Vec<String> {
+    schema.fields().iter().map(|f| f.name().clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn written_files_match_registered_schemas() {
+        let root = scratch_dir("schema_registry_written");
+
+        let spot = root.join("spot.parquet");
+        let mut df = parse_trade_csv::<SpotTradeSchema>(
+            "1,100.5,0.1,10.05,1704067200000,True,True\n".to_string(),
+            false,
+        )
+        .unwrap();
+        write_parquet(&spot, &mut df);
+
+        let futures = root.join("futures.parquet");
+        let mut df = parse_trade_csv::<UmFuturesTradeSchema>(
+            "id,price,qty,quote_qty,time,is_buyer_maker\n7,200.5,1.0,200.5,1704067200000,true\n"
+                .to_string(),
+            false,
+        )
+        .unwrap();
+        write_parquet(&futures, &mut df);
+
+        let l2 = root.join("l2.parquet");
+        write_l2_snapshot(&root.join("raw"));
+        let mut df = read_hyperliquid_l2book_bydate(
+            &root.join("raw"),
+            NaiveDate::from_ymd_opt(2025, 9, 30).unwrap(),
+            None,
+        )
+        .unwrap();
+        write_parquet(&l2, &mut df);
+
+        for (dataset_type, path) in [
+            (DatasetType::BinanceSpotTrades, &spot),
+            (DatasetType::BinanceUmPerpTrades, &futures),
+            (DatasetType::HyperliquidPerpL2, &l2),
+        ] {
+            let expected = schema_for(&dataset_type).unwrap();
+            assert_eq!(
+                column_names(&parquet_schema(path).unwrap()),
+                column_names(&expected),
+                "{:?}",
+                dataset_type
+            );
+            validate_parquet_schema(&dataset_type, path).unwrap();
+        }
+        assert!(schema_for(&DatasetType::HyperliquidPerpTrades).is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn older_layouts_fail_with_schema_mismatch() {
+        let root = scratch_dir("schema_registry_mismatch");
+        let data_root = root.to_str().unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+
+        // Spot trades from before the `quantity` rename, with raw millisecond times
+        let path = Path::new(&DatasetType::BinanceSpotTrades.hive_path_in(data_root, "USDT"))
+            .join(format!("date={}", date))
+            .join("symbol=BTC")
+            .join("data.parquet");
+        let mut df = df!(
+            "trade_id" => [1i64],
+            "price" => [100.5],
+            "qty" => [0.1],
+        )
+        .unwrap();
+        write_parquet(&path, &mut df);
+
+        let err = validate_parquet_schema(&DatasetType::BinanceSpotTrades, &path).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("schema mismatch"), "{}", message);
+        assert!(
+            message.contains("column 2 is qty where quantity is expected"),
+            "{}",
+            message
+        );
+        assert!(message.contains("expected [trade_id: Int64"), "{}", message);
+
+        // Readers check before scanning
+        let Err(err) = DatasetType::BinanceSpotTrades.load(data_root, "USDT", "BTC", date, date)
+        else {
+            panic!("mismatched schema should fail");
+        };
+        assert!(err.to_string().contains("schema mismatch"), "{}", err);
+
+        // Same columns with a wrong type
+        let mut df = parse_trade_csv::<SpotTradeSchema>(
+            "1,100.5,0.1,10.05,1704067200000,True,True\n".to_string(),
+            false,
+        )
+        .unwrap()
+        .lazy()
+        .with_column(col("time").cast(DataType::Int64))
+        .collect()
+        .unwrap();
+        write_parquet(&path, &mut df);
+        let err = validate_parquet_schema(&DatasetType::BinanceSpotTrades, &path).unwrap_err();
+        assert!(err.to_string().contains("column time is Int64"), "{}", err);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
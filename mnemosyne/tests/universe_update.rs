@@ -0,0 +1,201 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use mnemosyne::crypto::CryptoDataInterface;
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Scratch directory unique to this test process
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne_tests_{}_{}", name, std::process::id()))
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 10, day).unwrap()
+}
+
+fn universe_df(pairs: &[(&str, NaiveDate)]) -> DataFrame {
+    let symbols: Vec<&str> = pairs.iter().map(|(symbol, _)| *symbol).collect();
+    let dates: Vec<NaiveDate> = pairs.iter().map(|(_, date)| *date).collect();
+    df!("symbol" => symbols, "date" => dates).unwrap()
+}
+
+fn pairs(df: &DataFrame) -> Vec<(String, NaiveDate)> {
+    let symbols = df.column("symbol").unwrap().str().unwrap();
+    let dates = df.column("date").unwrap().date().unwrap();
+    symbols
+        .into_no_null_iter()
+        .zip(dates.as_date_iter())
+        .map(|(symbol, date)| (symbol.to_string(), date.unwrap()))
+        .collect()
+}
+
+/// Data source whose S3 listing is a fixed set of pairs; records what the universe fetches asked for
+struct FakeListing {
+    root: PathBuf,
+    universe_cache_path: PathBuf,
+    listing: Vec<(&'static str, NaiveDate)>,
+    full_fetches: Mutex<usize>,
+    requested_after: Mutex<Option<HashMap<String, NaiveDate>>>,
+}
+
+impl FakeListing {
+    fn new(root: PathBuf, listing: Vec<(&'static str, NaiveDate)>) -> Self {
+        std::fs::create_dir_all(&root).unwrap();
+        Self {
+            universe_cache_path: root.join("universe.parquet"),
+            root,
+            listing,
+            full_fetches: Mutex::new(0),
+            requested_after: Mutex::new(None),
+        }
+    }
+}
+
+impl CryptoDataInterface for FakeListing {
+    fn build_hive_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
+        self.root
+            .join(format!("date={}", date))
+            .join(format!("symbol={}", symbol))
+            .join("data.parquet")
+    }
+
+    fn build_raw_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
+        self.root.join(format!("{}-{}.zip", symbol, date))
+    }
+
+    fn hive_data_path(&self) -> &Path {
+        &self.root
+    }
+
+    fn raw_data_path(&self) -> &Path {
+        &self.root
+    }
+
+    fn universe_cache_path(&self) -> &Path {
+        &self.universe_cache_path
+    }
+
+    fn date_filters(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        (None, None)
+    }
+
+    async fn fetch_new_universe(&self) -> Result<DataFrame> {
+        *self.full_fetches.lock().unwrap() += 1;
+        Ok(universe_df(&self.listing))
+    }
+
+    async fn fetch_universe_after(&self, after: &HashMap<String, NaiveDate>) -> Result<DataFrame> {
+        *self.requested_after.lock().unwrap() = Some(after.clone());
+        let new_pairs: Vec<(&str, NaiveDate)> = self
+            .listing
+            .iter()
+            .copied()
+            .filter(|(symbol, date)| after.get(*symbol).is_none_or(|latest| date > latest))
+            .collect();
+        Ok(universe_df(&new_pairs))
+    }
+
+    async fn download_raw(&self, _symbol: &str, _date: NaiveDate) -> Result<()> {
+        unimplemented!("universe tests never download")
+    }
+
+    fn process_download_to_parquet(
+        &self,
+        _zip_path: &Path,
+        _hive_path: &Path,
+        _symbol: &str,
+        _date: NaiveDate,
+    ) -> Result<usize> {
+        unimplemented!("universe tests never download")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn incremental_update_adds_new_dates_and_symbols_and_keeps_delisted() {
+        let root = scratch_dir("universe_update_incremental");
+        // Listed now: BTC gained 2025-10-04, SOL is newly listed, LUNA is gone
+        let book = FakeListing::new(
+            root.clone(),
+            vec![
+                ("BTC", date(1)),
+                ("BTC", date(2)),
+                ("BTC", date(3)),
+                ("BTC", date(4)),
+                ("ETH", date(2)),
+                ("ETH", date(3)),
+                ("SOL", date(3)),
+                ("SOL", date(4)),
+            ],
+        );
+        let mut cached = universe_df(&[
+            ("BTC", date(1)),
+            ("BTC", date(2)),
+            ("BTC", date(3)),
+            ("ETH", date(2)),
+            ("ETH", date(3)),
+            ("LUNA", date(1)),
+            ("LUNA", date(2)),
+        ]);
+        let mut file = std::fs::File::create(book.universe_cache_path()).unwrap();
+        ParquetWriter::new(&mut file).finish(&mut cached).unwrap();
+
+        let added = book.update_universe_incremental().await.unwrap();
+        assert_eq!(added, 3);
+
+        // Only dates after each cached symbol's latest were requested, never the full universe
+        assert_eq!(*book.full_fetches.lock().unwrap(), 0);
+        let requested = book.requested_after.lock().unwrap().clone().unwrap();
+        let expected: HashMap<String, NaiveDate> =
+            [("BTC", date(3)), ("ETH", date(3)), ("LUNA", date(2))]
+                .into_iter()
+                .map(|(symbol, date)| (symbol.to_string(), date))
+                .collect();
+        assert_eq!(requested, expected);
+
+        let universe = book.get_universe_df().await.unwrap();
+        let expected: Vec<(String, NaiveDate)> = [
+            ("BTC", date(1)),
+            ("BTC", date(2)),
+            ("BTC", date(3)),
+            ("BTC", date(4)),
+            ("ETH", date(2)),
+            ("ETH", date(3)),
+            ("LUNA", date(1)),
+            ("LUNA", date(2)),
+            ("SOL", date(3)),
+            ("SOL", date(4)),
+        ]
+        .into_iter()
+        .map(|(symbol, date)| (symbol.to_string(), date))
+        .collect();
+        assert_eq!(pairs(&universe), expected);
+
+        // Nothing new: a second update adds nothing
+        assert_eq!(book.update_universe_incremental().await.unwrap(), 0);
+        assert_eq!(
+            book.get_universe_df().await.unwrap().height(),
+            expected.len()
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn incremental_update_without_cache_fetches_full_universe() {
+        let root = scratch_dir("universe_update_no_cache");
+        let book = FakeListing::new(root.clone(), vec![("BTC", date(1)), ("ETH", date(1))]);
+
+        assert_eq!(book.update_universe_incremental().await.unwrap(), 2);
+        assert_eq!(*book.full_fetches.lock().unwrap(), 1);
+        assert!(book.requested_after.lock().unwrap().is_none());
+        assert_eq!(book.get_universe_df().await.unwrap().height(), 2);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}